@@ -0,0 +1,14 @@
+use std::env;
+
+fn main() {
+    // The script doesn't depend on our code
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let target = env::var("TARGET").unwrap();
+
+    // Used to reject `arm64e`, same cfg as in `objc-sys`
+    println!("cargo:rustc-check-cfg=cfg(target_arm64e)");
+    if target.starts_with("arm64e-") {
+        println!("cargo:rustc-cfg=target_arm64e");
+    }
+}
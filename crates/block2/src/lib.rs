@@ -212,6 +212,14 @@
 //! You will likely have to use interior mutability instead.
 //!
 //!
+//! ## Panics
+//!
+//! Blocks are invoked from foreign code, and unwinding a Rust panic out of a
+//! block is undefined behaviour. When the `"unwind"` feature is enabled,
+//! panics in the closure of a block are caught, and thrown as an Objective-C
+//! exception instead, see [`objc2::exception`] for details.
+//!
+//!
 //! ## Specifying a runtime
 //!
 //! Different runtime implementations exist and act in slightly different ways
@@ -305,6 +313,7 @@
 #![warn(clippy::missing_panics_doc)]
 // Update in Cargo.toml as well.
 #![doc(html_root_url = "https://docs.rs/block2/0.5.0")]
+#![cfg_attr(feature = "unwind", feature(c_unwind))]
 #![cfg_attr(feature = "unstable-docsrs", feature(doc_auto_cfg, doc_cfg_hide))]
 #![cfg_attr(feature = "unstable-docsrs", doc(cfg_hide(doc)))]
 
@@ -339,6 +348,14 @@ compile_error!("Only one runtime may be selected");
 #[cfg(feature = "unstable-objfw")]
 compile_error!("ObjFW is not yet supported");
 
+// On `arm64e`, the runtime authenticates the block's `isa`, its descriptor,
+// and the invoke, copy and dispose helpers, each with their own signing
+// scheme. Until we sign all of those (including for `global_block!`, where
+// they are stored in a `static`), blocks created in Rust would crash when
+// used by Objective-C.
+#[cfg(target_arm64e)]
+compile_error!("The `arm64e` targets are not yet supported");
+
 // Link to `libclosure` (internally called `libsystem_blocks.dylib`), which is
 // exported by `libSystem.dylib`.
 //
@@ -367,15 +384,60 @@ mod debug;
 pub mod ffi;
 mod global;
 mod rc_block;
+mod selftest;
 mod stack;
 mod traits;
 
 pub use self::block::Block;
 pub use self::global::GlobalBlock;
 pub use self::rc_block::RcBlock;
+pub use self::selftest::{abi_selftest, AbiSelfTestError};
 pub use self::stack::StackBlock;
 pub use self::traits::{BlockFn, IntoBlock};
 
+/// Emit the invoke function of a block, or its function pointer type.
+///
+/// If the `"unwind"` feature is enabled, this is `extern "C-unwind"`, and
+/// panics in the body are turned into Objective-C exceptions, since they
+/// must not unwind into foreign code.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "unwind"))]
+macro_rules! __block_invoke_fn {
+    (type fn($($args:ty),* $(,)?) $(-> $r:ty)?) => {
+        unsafe extern "C" fn($($args),*) $(-> $r)?
+    };
+    (fn ($($signature:tt)*) $body:block) => {
+        unsafe extern "C" fn $($signature)* $body
+    };
+}
+
+/// Emit the invoke function of a block, or its function pointer type.
+///
+/// If the `"unwind"` feature is enabled, this is `extern "C-unwind"`, and
+/// panics in the body are turned into Objective-C exceptions, since they
+/// must not unwind into foreign code.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "unwind")]
+macro_rules! __block_invoke_fn {
+    (type fn($($args:ty),* $(,)?) $(-> $r:ty)?) => {
+        unsafe extern "C-unwind" fn($($args),*) $(-> $r)?
+    };
+    (fn ($($signature:tt)*) $body:block) => {
+        unsafe extern "C-unwind" fn $($signature)* {
+            let closure = move || $body;
+            // SAFETY: This is the block's invoke function, which is declared
+            // `extern "C-unwind"`.
+            unsafe { $crate::__objc2::exception::panic_into_exception(closure) }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[cfg(feature = "unwind")]
+pub use objc2 as __objc2;
+
 /// Deprecated alias for a `'static` `StackBlock`.
 #[deprecated = "renamed to `StackBlock`"]
 pub type ConcreteBlock<A, R, Closure> = StackBlock<'static, A, R, Closure>;
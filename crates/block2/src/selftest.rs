@@ -0,0 +1,243 @@
+//! A self-test of the block ABI used with the blocks runtime.
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use objc2::encode::{Encode, Encoding};
+use objc2::runtime::Bool;
+
+use crate::{global_block, RcBlock, StackBlock};
+
+/// Always returned in memory, i.e. through the `BLOCK_USE_STRET` calling
+/// convention where that is used.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Large {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+}
+
+unsafe impl Encode for Large {
+    const ENCODING: Encoding = Encoding::Struct(
+        "__block2Large",
+        &[f64::ENCODING, f64::ENCODING, f64::ENCODING, f64::ENCODING],
+    );
+}
+
+/// Contains padding between the fields.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Padded {
+    a: u8,
+    b: f64,
+    c: u16,
+}
+
+unsafe impl Encode for Padded {
+    const ENCODING: Encoding = Encoding::Struct(
+        "__block2Padded",
+        &[u8::ENCODING, f64::ENCODING, u16::ENCODING],
+    );
+}
+
+global_block! {
+    static LARGE_GLOBAL_BLOCK = |x: f64| -> Large {
+        Large {
+            a: x,
+            b: x * 2.0,
+            c: x * 3.0,
+            d: x * 4.0,
+        }
+    };
+}
+
+/// An error from [`abi_selftest`], describing the first check that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiSelfTestError {
+    test: &'static str,
+    expected: String,
+    actual: String,
+}
+
+impl AbiSelfTestError {
+    /// The name of the check that failed.
+    pub fn test(&self) -> &'static str {
+        self.test
+    }
+}
+
+impl fmt::Display for AbiSelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "block ABI self-test `{}` failed: expected {}, got {}",
+            self.test, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for AbiSelfTestError {}
+
+fn check<T: PartialEq + fmt::Debug>(
+    test: &'static str,
+    expected: T,
+    actual: T,
+) -> Result<(), AbiSelfTestError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(AbiSelfTestError {
+            test,
+            expected: format!("{expected:?}"),
+            actual: format!("{actual:?}"),
+        })
+    }
+}
+
+/// Check that blocks work correctly with the linked blocks runtime on the
+/// current target.
+///
+/// This calls blocks with a variety of argument and return types, and
+/// checks that the values arrive unchanged. This covers sign-extension of
+/// small integers, `BOOL`, mixed floating point arguments, arguments passed
+/// on the stack, and structs passed and returned in memory (using the
+/// `BLOCK_USE_STRET` calling convention where that is used), for global,
+/// stack and heap blocks. It also checks that captured values survive
+/// copying a block to the heap with `_Block_copy` and releasing it again.
+///
+/// This is the counterpart of `objc2::abi_selftest`, which checks message
+/// sending.
+///
+///
+/// # Errors
+///
+/// Returns an error describing the first check that failed.
+///
+///
+/// # Example
+///
+#[cfg_attr(feature = "apple", doc = "```")]
+#[cfg_attr(not(feature = "apple"), doc = "```no_run")]
+/// if cfg!(debug_assertions) {
+///     block2::abi_selftest().expect("block2 does not support this target");
+/// }
+/// ```
+pub fn abi_selftest() -> Result<(), AbiSelfTestError> {
+    let neg = RcBlock::new(|x: i8| x.wrapping_neg());
+    check("i8 argument and return", -5, neg.call((5,)))?;
+    check("i8 sign extension", 127, neg.call((-127,)))?;
+
+    let not = RcBlock::new(|x: Bool| Bool::new(!x.as_bool()));
+    check("BOOL YES", false, not.call((Bool::YES,)).as_bool())?;
+    check("BOOL NO", true, not.call((Bool::NO,)).as_bool())?;
+
+    let add = RcBlock::new(|a: f32, b: f64| a as f64 + b);
+    check("mixed float arguments", 3.75, add.call((1.5, 2.25)))?;
+
+    // More arguments than there are registers for on any target, so that
+    // some are passed on the stack.
+    let spill = RcBlock::new(
+        |i1: i32,
+         f1: f64,
+         i2: i64,
+         f2: f32,
+         i3: i8,
+         f3: f64,
+         i4: u16,
+         f4: f64,
+         i5: i64,
+         f5: f64,
+         i6: i32,
+         f6: f64| {
+            let ints = i1 as i64 + i2 + i3 as i64 + i4 as i64 + i5 + i6 as i64;
+            let floats = f1 + f2 as f64 + f3 + f4 + f5 + f6;
+            ints as f64 * 1000.0 + floats
+        },
+    );
+    check(
+        "arguments on the stack",
+        3007.875,
+        spill.call((1, 0.5, 2, 0.25, -3, 0.125, 4, 1.0, 5, 2.0, -6, 4.0)),
+    )?;
+
+    let expected = Large {
+        a: 1.5,
+        b: 3.0,
+        c: 4.5,
+        d: 6.0,
+    };
+    check(
+        "large struct return from global block",
+        expected,
+        LARGE_GLOBAL_BLOCK.call((1.5,)),
+    )?;
+    let offset = 1.0;
+    let large = StackBlock::new(move |x: f64| Large {
+        a: x,
+        b: x + offset,
+        c: x + 2.0 * offset,
+        d: x + 3.0 * offset,
+    });
+    let large_expected = Large {
+        a: 1.0,
+        b: 2.0,
+        c: 3.0,
+        d: 4.0,
+    };
+    check(
+        "large struct return from stack block",
+        large_expected,
+        large.call((1.0,)),
+    )?;
+    check(
+        "large struct return from copied block",
+        large_expected,
+        large.copy().call((1.0,)),
+    )?;
+
+    let sum = RcBlock::new(|x: Large| x.a + x.b + x.c + x.d);
+    check("large struct argument", 10.0, sum.call((large_expected,)))?;
+
+    let swap = RcBlock::new(|x: Padded| Padded {
+        a: x.c as u8,
+        b: -x.b,
+        c: x.a as u16,
+    });
+    check(
+        "padded struct argument and return",
+        Padded {
+            a: 42,
+            b: -0.5,
+            c: 7,
+        },
+        swap.call((Padded {
+            a: 7,
+            b: 0.5,
+            c: 42,
+        },)),
+    )?;
+
+    let captured: Vec<i32> = vec![1, 2, 3];
+    let stack = StackBlock::new(move || captured.iter().sum::<i32>());
+    let copied = stack.copy();
+    drop(stack);
+    let copied_again = copied.copy();
+    drop(copied);
+    check("captured values after copy", 6, copied_again.call(()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abi_selftest() {
+        abi_selftest().unwrap();
+    }
+}
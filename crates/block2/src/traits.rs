@@ -82,7 +82,7 @@ macro_rules! impl_traits {
                 ($($a,)*): Self::Args,
             ) -> Self::Output {
                 // Very similar to `MessageArguments::__invoke`
-                let invoke: unsafe extern "C" fn(*mut Block<Self> $(, $t)*) -> R = unsafe {
+                let invoke: crate::__block_invoke_fn!(type fn(*mut Block<Self> $(, $t)*) -> R) = unsafe {
                     mem::transmute(invoke)
                 };
 
@@ -100,20 +100,22 @@ macro_rules! impl_traits {
 
             #[inline]
             fn __get_invoke_stack_block() -> unsafe extern "C" fn() {
-                unsafe extern "C" fn invoke<'f, $($t,)* R, Closure>(
-                    block: *mut StackBlock<'f, ($($t,)*), R, Closure>,
-                    $($a: $t,)*
-                ) -> R
-                where
-                    Closure: Fn($($t),*) -> R + 'f
-                {
-                    let closure = unsafe { &*ptr::addr_of!((*block).closure) };
-                    (closure)($($a),*)
+                crate::__block_invoke_fn! {
+                    fn (invoke<'f, $($t,)* R, Closure>(
+                        block: *mut StackBlock<'f, ($($t,)*), R, Closure>,
+                        $($a: $t,)*
+                    ) -> R
+                    where
+                        Closure: Fn($($t),*) -> R + 'f)
+                    {
+                        let closure = unsafe { &*ptr::addr_of!((*block).closure) };
+                        (closure)($($a),*)
+                    }
                 }
 
                 unsafe {
                     mem::transmute::<
-                        unsafe extern "C" fn(*mut StackBlock<'f, ($($t,)*), R, Closure>, $($t,)*) -> R,
+                        crate::__block_invoke_fn!(type fn(*mut StackBlock<'f, ($($t,)*), R, Closure>, $($t,)*) -> R),
                         unsafe extern "C" fn(),
                     >(invoke)
                 }
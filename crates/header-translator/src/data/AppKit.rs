@@ -153,6 +153,9 @@ data! {
     // actually be `InteriorMutable`?
     class NSTextStorage: Mutable {}
 
+    class NSParagraphStyle: ImmutableWithMutableSubclass<AppKit::NSParagraphStyle::NSMutableParagraphStyle> {}
+    class NSMutableParagraphStyle: MutableWithImmutableSuperclass<AppKit::NSParagraphStyle::NSParagraphStyle> {}
+
     // Documented as "Main Thread Only".
     // > generally thread safe, although operations on views such as creating,
     // > resizing, and moving should happen on the main thread.
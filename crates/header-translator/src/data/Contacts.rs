@@ -1,2 +1,10 @@
 data! {
+    class CNContact: ImmutableWithMutableSubclass<Contacts::CNMutableContact::CNMutableContact> {}
+    class CNMutableContact: MutableWithImmutableSuperclass<Contacts::CNContact::CNContact> {}
+
+    class CNGroup: ImmutableWithMutableSubclass<Contacts::CNMutableGroup::CNMutableGroup> {}
+    class CNMutableGroup: MutableWithImmutableSuperclass<Contacts::CNGroup::CNGroup> {}
+
+    class CNPostalAddress: ImmutableWithMutableSubclass<Contacts::CNMutablePostalAddress::CNMutablePostalAddress> {}
+    class CNMutablePostalAddress: MutableWithImmutableSuperclass<Contacts::CNPostalAddress::CNPostalAddress> {}
 }
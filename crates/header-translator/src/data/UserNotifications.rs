@@ -1,2 +1,4 @@
 data! {
+    class UNNotificationContent: ImmutableWithMutableSubclass<UserNotifications::UNNotificationContent::UNMutableNotificationContent> {}
+    class UNMutableNotificationContent: MutableWithImmutableSuperclass<UserNotifications::UNNotificationContent::UNNotificationContent> {}
 }
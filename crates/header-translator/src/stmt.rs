@@ -2108,14 +2108,11 @@ impl Stmt {
                     //
                     // <https://developer.apple.com/documentation/swift/grouping-related-objective-c-constants#Declare-Simple-Enumerations>
                     | Some(UnexposedAttr::Enum)
-                    // TODO: Use bitflags! here.
-                    | Some(UnexposedAttr::Options)
                     // TODO: Handle this differently.
                     | Some(UnexposedAttr::ErrorEnum) => {
                         match kind {
                             None => {}
                             Some(UnexposedAttr::Enum) => writeln!(f, "// NS_ENUM")?,
-                            Some(UnexposedAttr::Options) => writeln!(f, "// NS_OPTIONS")?,
                             Some(UnexposedAttr::ErrorEnum) => writeln!(f, "// NS_ERROR_ENUM")?,
                             _ => unreachable!(),
                         }
@@ -2155,6 +2152,34 @@ impl Stmt {
                         writeln!(f, "}}")?;
                         writeln!(f)?;
                     }
+                    Some(UnexposedAttr::Options) => {
+                        // `ns_options!` implements bitwise operations, a
+                        // `Debug` impl that prints the names of set flags,
+                        // and `Encode`/`RefEncode` for us.
+                        writeln!(f, "// NS_OPTIONS")?;
+                        write!(f, "{}", self.cfg_gate_ln(config))?;
+                        writeln!(f, "ns_options!(")?;
+                        write!(f, "{availability}")?;
+                        writeln!(f, "pub struct {}: {} {{", id.name, ty.enum_())?;
+
+                        let required_items = self.required_items();
+                        for (name, availability, expr) in variants {
+                            let implied_features = required_items
+                                .iter()
+                                .map(|item| item.location())
+                                .chain(iter::once(self.location()));
+                            write!(f, "    {}", cfg_gate_ln(expr.required_items(), implied_features, config, self.location()))?;
+                            write!(f, "    {availability}")?;
+                            let pretty_name = enum_constant_name(&id.name, name);
+                            if pretty_name != name {
+                                writeln!(f, "    #[doc(alias = \"{name}\")]")?;
+                            }
+                            writeln!(f, "    const {pretty_name} = {expr};")?;
+                        }
+                        writeln!(f, "}}")?;
+                        writeln!(f, ");")?;
+                        writeln!(f)?;
+                    }
                     Some(UnexposedAttr::ClosedEnum) => {
                         // SAFETY: `NS_CLOSED_ENUM` is guaranteed to never
                         // gain additional cases, so we are allowed to use a
@@ -2193,11 +2218,15 @@ impl Stmt {
                     // SAFETY: The enum is either a `#[repr(transparent)]` newtype
                     // over the type, or a `#[repr(REPR)]`, where REPR is a valid
                     // repr with the same size and alignment as the type.
-                    write!(f, "{}", self.cfg_gate_ln(config))?;
-                    let encoding = format!("{}::ENCODING", ty.enum_());
-                    writeln!(f, "{}", unsafe_impl_encode(&id.name, encoding))?;
-                    write!(f, "{}", self.cfg_gate_ln(config))?;
-                    writeln!(f, "{}", unsafe_impl_refencode(&id.name))?;
+                    //
+                    // `ns_options!` already implements these.
+                    if *kind != Some(UnexposedAttr::Options) {
+                        write!(f, "{}", self.cfg_gate_ln(config))?;
+                        let encoding = format!("{}::ENCODING", ty.enum_());
+                        writeln!(f, "{}", unsafe_impl_encode(&id.name, encoding))?;
+                        write!(f, "{}", self.cfg_gate_ln(config))?;
+                        writeln!(f, "{}", unsafe_impl_refencode(&id.name))?;
+                    }
 
                     if let Some(true) = sendable {
                         writeln!(f)?;
@@ -36,6 +36,15 @@ fn main() {
         println!("cargo:rustc-cfg=target_simulator");
     }
 
+    // Used to sign and authenticate function pointers
+    // Matches:
+    // arm64e-apple-darwin
+    // arm64e-apple-ios
+    println!("cargo:rustc-check-cfg=cfg(target_arm64e)");
+    if target.starts_with("arm64e-") {
+        println!("cargo:rustc-cfg=target_arm64e");
+    }
+
     println!("cargo:rustc-check-cfg=cfg(libobjc2_strict_apple_compat)");
     // TODO: Figure out when to enable this
     // println!("cargo:rustc-cfg=libobjc2_strict_apple_compat");
@@ -83,9 +92,8 @@ fn main() {
             }
         }
         (false, false, true) => {
-            // For now
-            unimplemented!("ObjFW is not yet supported")
-            // ObjFW(None)
+            println!("cargo:rerun-if-env-changed=OBJFW_VERSION");
+            Runtime::ObjFW(env::var("OBJFW_VERSION").ok())
         }
         (false, false, false) => panic!("Must specify the desired runtime (using cargo features)."),
         _ => panic!("Invalid feature combination; only one runtime may be selected!"),
@@ -127,7 +135,9 @@ fn main() {
         cc_args.push_str(compat_headers.to_str().unwrap());
     }
 
-    if let Runtime::ObjFW(_) = &runtime {
+    if env::var_os("CARGO_FEATURE_UNSTABLE_MOCK_RUNTIME").is_some() {
+        // The runtime functions are defined in `src/mock`
+    } else if let Runtime::ObjFW(_) = &runtime {
         // Link to libobjfw-rt
         println!("cargo:rustc-link-lib=dylib=objfw-rt");
     } else {
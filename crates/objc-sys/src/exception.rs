@@ -6,6 +6,12 @@
 // A few things here are defined differently depending on the __OBJC2__
 // variable, which is set for all platforms except 32-bit macOS.
 
+#[cfg(any(
+    doc,
+    feature = "apple",
+    feature = "gnustep-1-7",
+    feature = "unstable-exception"
+))]
 use core::ffi::c_void;
 #[cfg(any(
     doc,
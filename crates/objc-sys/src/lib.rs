@@ -67,9 +67,38 @@
 //!
 //! - Feature flag: `unstable-objfw`.
 //!
-//! **Unstable: Doesn't work yet!**
+//! **Unstable: Only partially supported!**
 //!
-//! TODO.
+//! Message sending, reference counting and autorelease pools work, but the
+//! runtime lacks many of the introspection functions available on the other
+//! runtimes, and e.g. does not support adding instance variables or
+//! protocols to classes at runtime.
+//!
+//! The ObjFW version that `clang` should target when compiling the
+//! Objective-C helpers can be specified with the `OBJFW_VERSION` environment
+//! variable, e.g. `OBJFW_VERSION=1.0`. Defaults to `clang`'s own default.
+//!
+//! Note that the autorelease pool functions live in `libobjfw`, not
+//! `libobjfw-rt`, so you will need to link to that as well (`objc2` does
+//! this for you).
+//!
+//!
+//! ### Mock runtime
+//!
+//! - Feature flag: `unstable-mock-runtime`.
+//!
+//! **Unstable: Only intended for testing!**
+//!
+//! Instead of linking to a runtime, this crate provides a (very limited)
+//! runtime written in pure Rust, which uses the GNUStep ABI. This is useful
+//! for running unit tests on platforms where no Objective-C runtime is
+//! installed, such as Linux CI machines without GNUStep.
+//!
+//! Only the root classes `NSObject` and `NSProxy` (along with
+//! `NSAutoreleasePool` and a minimal `NSString`) exist, and many features
+//! like message forwarding and exceptions are not supported. Panics in
+//! methods called by the runtime abort the process, so tests that expect
+//! such panics must be ignored when using the mock.
 //!
 //!
 //! ### Other runtimes
@@ -205,7 +234,8 @@ macro_rules! generate_linking_tests {
         )+}
 
         #[allow(deprecated)]
-        #[cfg(test)]
+        // The mock runtime only implements some of the functions
+        #[cfg(all(test, not(feature = "unstable-mock-runtime")))]
         mod $test_name {
             #[allow(unused)]
             use super::*;
@@ -287,9 +317,12 @@ mod image_info;
 mod libc;
 mod message;
 mod method;
+#[cfg(feature = "unstable-mock-runtime")]
+mod mock;
 mod object;
 mod property;
 mod protocol;
+mod ptrauth;
 mod rc;
 mod selector;
 mod types;
@@ -305,6 +338,7 @@ pub use self::method::*;
 pub use self::object::*;
 pub use self::property::*;
 pub use self::protocol::*;
+pub use self::ptrauth::*;
 pub use self::rc::*;
 pub use self::selector::*;
 pub use self::types::*;
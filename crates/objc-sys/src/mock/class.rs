@@ -0,0 +1,600 @@
+use core::iter;
+use core::mem;
+use core::ptr;
+use std::boxed::Box;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_uint};
+use std::vec::Vec;
+
+use super::method::{Ivar, Method};
+use super::protocol::protocol_conforms;
+use super::{copy_list, registry, to_bool, Locked, Protocol, Registry, SEL};
+use crate::{objc_class, objc_ivar, objc_method, objc_protocol, BOOL, IMP, NO, YES};
+
+/// The memory layout of a class (or metaclass).
+#[repr(C)]
+pub(crate) struct Class {
+    /// The metaclass. This must be the first field, since classes are
+    /// objects too.
+    pub(crate) isa: *const Class,
+    pub(crate) superclass: *const Class,
+    pub(crate) name: *const c_char,
+    pub(crate) is_meta: bool,
+    pub(crate) data: Locked<ClassData>,
+}
+
+// Methods and ivars are boxed, since pointers to them are handed out.
+#[allow(clippy::vec_box)]
+pub(crate) struct ClassData {
+    pub(crate) methods: Vec<Box<Method>>,
+    pub(crate) ivars: Vec<Box<Ivar>>,
+    pub(crate) protocols: Vec<*const Protocol>,
+    pub(crate) instance_size: usize,
+    pub(crate) registered: bool,
+}
+
+// SAFETY: The pointers are never modified after the class has been created,
+// and the data is protected by the registry lock.
+unsafe impl Sync for Class {}
+
+impl Class {
+    pub(crate) const fn new(
+        isa: *const Class,
+        superclass: *const Class,
+        name: *const c_char,
+        is_meta: bool,
+    ) -> Self {
+        Self {
+            isa,
+            superclass,
+            name,
+            is_meta,
+            data: Locked::new(ClassData {
+                methods: Vec::new(),
+                ivars: Vec::new(),
+                protocols: Vec::new(),
+                // Room for the `isa` pointer
+                instance_size: mem::size_of::<*const Class>(),
+                registered: false,
+            }),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The pointer must be NULL or a valid class.
+    pub(crate) unsafe fn from_ptr<'a>(cls: *const objc_class) -> Option<&'a Self> {
+        // SAFETY: Ensured by the caller.
+        unsafe { cls.cast::<Self>().as_ref() }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const objc_class {
+        let ptr: *const Self = self;
+        ptr.cast()
+    }
+
+    pub(crate) fn name(&self) -> &CStr {
+        // SAFETY: The name is a valid, NUL-terminated string.
+        unsafe { CStr::from_ptr(self.name) }
+    }
+
+    /// The class and its superclasses.
+    pub(crate) fn chain(&self) -> impl Iterator<Item = &Self> + '_ {
+        let mut next: *const Self = self;
+        iter::from_fn(move || {
+            // SAFETY: The superclass pointer is NULL or a valid class.
+            let current = unsafe { next.as_ref() }?;
+            next = current.superclass;
+            Some(current)
+        })
+    }
+
+    pub(crate) fn is_subclass_of(&self, other: &Self) -> bool {
+        self.chain().any(|cls| ptr::eq(cls, other))
+    }
+
+    /// Find the method with the given selector in this class, excluding its
+    /// superclasses.
+    pub(crate) fn own_method(&self, registry: &mut Registry, sel: SEL) -> Option<&Method> {
+        let methods = &self.data.get(registry).methods;
+        let method = methods.iter().find(|method| method.sel == sel)?;
+        // SAFETY: Methods are boxed, and never removed from a class while it
+        // is alive, so the reference does not need to borrow the registry.
+        Some(unsafe { &*(&**method as *const Method) })
+    }
+
+    /// Find the method with the given selector in this class or its
+    /// superclasses.
+    pub(crate) fn find_method(&self, registry: &mut Registry, sel: SEL) -> Option<&Method> {
+        self.chain().find_map(|cls| cls.own_method(registry, sel))
+    }
+}
+
+fn name_bytes<'a>(name: *const c_char) -> Option<&'a [u8]> {
+    if name.is_null() {
+        None
+    } else {
+        // SAFETY: Names passed to the runtime are NUL-terminated strings.
+        Some(unsafe { CStr::from_ptr(name) }.to_bytes())
+    }
+}
+
+fn get_class(name: *const c_char) -> Option<&'static Class> {
+    let name = name_bytes(name)?;
+    let mut registry = registry();
+    let cls = *registry.classes.get(name)?;
+    // SAFETY: Classes in the registry are valid.
+    let cls = unsafe { &*cls };
+    if cls.data.get(&mut registry).registered {
+        Some(cls)
+    } else {
+        None
+    }
+}
+
+fn registered_classes() -> Vec<*const objc_class> {
+    let mut registry = registry();
+    let classes: Vec<_> = registry.classes.values().copied().collect();
+    classes
+        .into_iter()
+        // SAFETY: Classes in the registry are valid.
+        .map(|cls| unsafe { &*cls })
+        .filter(|cls| cls.data.get(&mut registry).registered)
+        .map(Class::as_ptr)
+        .collect()
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_getClass(name: *const c_char) -> *const objc_class {
+    get_class(name).map_or(ptr::null(), Class::as_ptr)
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_lookUpClass(name: *const c_char) -> *const objc_class {
+    get_class(name).map_or(ptr::null(), Class::as_ptr)
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_getRequiredClass(name: *const c_char) -> *const objc_class {
+    match get_class(name) {
+        Some(cls) => cls.as_ptr(),
+        None => {
+            let name = name_bytes(name).unwrap_or_default();
+            std::eprintln!(
+                "required class {:?} not found",
+                std::string::String::from_utf8_lossy(name)
+            );
+            std::process::abort()
+        }
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_getMetaClass(name: *const c_char) -> *const objc_class {
+    get_class(name).map_or(ptr::null(), |cls| cls.isa.cast())
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_copyClassList(out_len: *mut c_uint) -> *mut *const objc_class {
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_list(&registered_classes(), out_len) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_getClassList(buffer: *mut *const objc_class, buffer_len: c_int) -> c_int {
+    let classes = registered_classes();
+    if !buffer.is_null() {
+        let len = classes.len().min(buffer_len.max(0) as usize);
+        // SAFETY: The caller ensures that the buffer has room for
+        // `buffer_len` classes.
+        unsafe { ptr::copy_nonoverlapping(classes.as_ptr(), buffer, len) };
+    }
+    classes.len() as c_int
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_allocateClassPair(
+    superclass: *const objc_class,
+    name: *const c_char,
+    _extra_bytes: usize,
+) -> *mut objc_class {
+    let name = match name_bytes(name) {
+        Some(name) => name,
+        None => return ptr::null_mut(),
+    };
+    // SAFETY: Upheld by the caller.
+    let superclass = unsafe { Class::from_ptr(superclass) };
+    let mut registry = registry();
+    if registry.classes.contains_key(name) {
+        return ptr::null_mut();
+    }
+
+    let name_ptr = CString::new(name).unwrap().into_raw();
+    let cls = Box::into_raw(Box::new(Class::new(
+        ptr::null(),
+        superclass.map_or(ptr::null(), |superclass| superclass as *const Class),
+        name_ptr,
+        false,
+    )));
+    let meta = Box::into_raw(Box::new(Class::new(
+        ptr::null(),
+        ptr::null(),
+        name_ptr,
+        true,
+    )));
+
+    // SAFETY: The pointers were just created from `Box`es, and the
+    // superclass is valid.
+    unsafe {
+        (*cls).isa = meta;
+        if let Some(superclass) = superclass {
+            // The metaclass of a metaclass is always the root metaclass
+            (*meta).isa = (*superclass.isa).isa;
+            (*meta).superclass = superclass.isa;
+            let instance_size = superclass.data.get(&mut registry).instance_size;
+            (*cls).data.get(&mut registry).instance_size = instance_size;
+        } else {
+            // The root metaclass is its own metaclass, and its superclass is
+            // the root class itself.
+            (*meta).isa = meta;
+            (*meta).superclass = cls;
+        }
+    }
+
+    registry.classes.insert(name.to_vec(), cls);
+    cls.cast()
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_registerClassPair(cls: *mut objc_class) {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => return,
+    };
+    let mut registry = registry();
+    cls.data.get(&mut registry).registered = true;
+    // SAFETY: The metaclass is valid.
+    unsafe { &*cls.isa }.data.get(&mut registry).registered = true;
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_disposeClassPair(cls: *mut objc_class) {
+    // SAFETY: Upheld by the caller.
+    let class = match unsafe { Class::from_ptr(cls) } {
+        Some(class) => class,
+        None => return,
+    };
+    let mut registry = registry();
+    registry.classes.remove(class.name().to_bytes());
+    // SAFETY: The class pair was allocated by `objc_allocateClassPair`, and
+    // the caller ensures that no instances or subclasses exist.
+    unsafe {
+        let meta = class.isa as *mut Class;
+        let name = class.name as *mut c_char;
+        drop(Box::from_raw(meta));
+        drop(Box::from_raw(cls.cast::<Class>()));
+        drop(CString::from_raw(name));
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_addIvar(
+    cls: *mut objc_class,
+    name: *const c_char,
+    size: usize,
+    alignment: u8,
+    types: *const c_char,
+) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    let (cls, name, types) = match (
+        unsafe { Class::from_ptr(cls) },
+        name_bytes(name),
+        name_bytes(types),
+    ) {
+        (Some(cls), Some(name), Some(types)) => (cls, name, types),
+        _ => return NO,
+    };
+    let mut registry = registry();
+    let exists = cls.chain().any(|cls| {
+        let ivars = &cls.data.get(&mut registry).ivars;
+        ivars.iter().any(|ivar| ivar.name.to_bytes() == name)
+    });
+    let data = cls.data.get(&mut registry);
+    if exists || data.registered || cls.is_meta {
+        return NO;
+    }
+
+    let align = 1usize << alignment;
+    let offset = (data.instance_size + align - 1) & !(align - 1);
+    data.instance_size = offset + size;
+    data.ivars.push(Box::new(Ivar {
+        name: CString::new(name).unwrap(),
+        types: CString::new(types).unwrap(),
+        offset: offset as isize,
+    }));
+    YES
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_addMethod(
+    cls: *mut objc_class,
+    name: SEL,
+    imp: IMP,
+    types: *const c_char,
+) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => return NO,
+    };
+    let mut registry = registry();
+    add_method(
+        &mut registry,
+        cls,
+        name,
+        imp,
+        name_bytes(types).unwrap_or_default(),
+    )
+}
+
+pub(crate) fn add_method(
+    registry: &mut Registry,
+    cls: &Class,
+    sel: SEL,
+    imp: IMP,
+    types: &[u8],
+) -> BOOL {
+    let data = cls.data.get(registry);
+    if sel.is_null() || data.methods.iter().any(|method| method.sel == sel) {
+        return NO;
+    }
+    data.methods.push(Box::new(Method {
+        sel,
+        imp: Locked::new(imp),
+        types: CString::new(types).unwrap(),
+    }));
+    YES
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_replaceMethod(
+    cls: *mut objc_class,
+    name: SEL,
+    imp: IMP,
+    types: *const c_char,
+) -> IMP {
+    // SAFETY: Upheld by the caller.
+    let cls = unsafe { Class::from_ptr(cls) }?;
+    let mut registry = registry();
+    if let Some(method) = cls.own_method(&mut registry, name) {
+        return mem::replace(method.imp.get(&mut registry), imp);
+    }
+    add_method(
+        &mut registry,
+        cls,
+        name,
+        imp,
+        name_bytes(types).unwrap_or_default(),
+    );
+    None
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_addProtocol(
+    cls: *mut objc_class,
+    protocol: *const objc_protocol,
+) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    if protocol.is_null() || unsafe { class_conformsToProtocol(cls, protocol) } == YES {
+        return NO;
+    }
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => return NO,
+    };
+    let mut registry = registry();
+    cls.data.get(&mut registry).protocols.push(protocol.cast());
+    YES
+}
+
+/// Whether the class itself (and not its superclasses) conforms to the
+/// protocol.
+pub(crate) fn class_conforms(registry: &mut Registry, cls: &Class, protocol: &Protocol) -> bool {
+    let protocols = cls.data.get(registry).protocols.clone();
+    protocols.into_iter().any(|proto| {
+        // SAFETY: Protocols are never deallocated.
+        protocol_conforms(registry, unsafe { &*proto }, protocol)
+    })
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn class_conformsToProtocol(
+    cls: *const objc_class,
+    protocol: *const objc_protocol,
+) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    match unsafe { (Class::from_ptr(cls), Protocol::from_ptr(protocol)) } {
+        // Like GNUStep, this also checks the superclasses.
+        (Some(cls), Some(protocol)) => {
+            let mut registry = registry();
+            to_bool(
+                cls.chain()
+                    .any(|cls| class_conforms(&mut registry, cls, protocol)),
+            )
+        }
+        _ => NO,
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_copyIvarList(
+    cls: *const objc_class,
+    out_len: *mut c_uint,
+) -> *mut *const objc_ivar {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => {
+            // SAFETY: Upheld by the caller.
+            return unsafe { copy_list(&[], out_len) };
+        }
+    };
+    let mut registry = registry();
+    let ivars: Vec<_> = cls
+        .data
+        .get(&mut registry)
+        .ivars
+        .iter()
+        .map(|ivar| (&**ivar as *const Ivar).cast::<objc_ivar>())
+        .collect();
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_list(&ivars, out_len) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_copyMethodList(
+    cls: *const objc_class,
+    out_len: *mut c_uint,
+) -> *mut *const objc_method {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => {
+            // SAFETY: Upheld by the caller.
+            return unsafe { copy_list(&[], out_len) };
+        }
+    };
+    let mut registry = registry();
+    let methods: Vec<_> = cls
+        .data
+        .get(&mut registry)
+        .methods
+        .iter()
+        .map(|method| (&**method as *const Method).cast::<objc_method>())
+        .collect();
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_list(&methods, out_len) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_copyProtocolList(
+    cls: *const objc_class,
+    out_len: *mut c_uint,
+) -> *mut *const objc_protocol {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => {
+            // SAFETY: Upheld by the caller.
+            return unsafe { copy_list(&[], out_len) };
+        }
+    };
+    let mut registry = registry();
+    let protocols: Vec<_> = cls
+        .data
+        .get(&mut registry)
+        .protocols
+        .iter()
+        .map(|protocol| protocol.cast::<objc_protocol>())
+        .collect();
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_list(&protocols, out_len) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_getInstanceSize(cls: *const objc_class) -> usize {
+    // SAFETY: Upheld by the caller.
+    match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls.data.get(&mut registry()).instance_size,
+        None => 0,
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_getInstanceVariable(
+    cls: *const objc_class,
+    name: *const c_char,
+) -> *const objc_ivar {
+    // SAFETY: Upheld by the caller.
+    let (cls, name) = match (unsafe { Class::from_ptr(cls) }, name_bytes(name)) {
+        (Some(cls), Some(name)) => (cls, name),
+        _ => return ptr::null(),
+    };
+    let mut registry = registry();
+    for cls in cls.chain() {
+        let ivars = &cls.data.get(&mut registry).ivars;
+        if let Some(ivar) = ivars.iter().find(|ivar| ivar.name.to_bytes() == name) {
+            return (&**ivar as *const Ivar).cast::<objc_ivar>();
+        }
+    }
+    ptr::null()
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_getIvarLayout(_cls: *const objc_class) -> *const u8 {
+    // Ivar layouts are only used by garbage collectors
+    ptr::null()
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_getName(cls: *const objc_class) -> *const c_char {
+    // SAFETY: Upheld by the caller.
+    match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls.name,
+        None => b"nil\0".as_ptr().cast(),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_getSuperclass(cls: *const objc_class) -> *const objc_class {
+    // SAFETY: Upheld by the caller.
+    match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls.superclass.cast(),
+        None => ptr::null(),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_isMetaClass(cls: *const objc_class) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    to_bool(unsafe { Class::from_ptr(cls) }.map_or(false, |cls| cls.is_meta))
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_getInstanceMethod(
+    cls: *const objc_class,
+    name: SEL,
+) -> *const objc_method {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => return ptr::null(),
+    };
+    let mut registry = registry();
+    match cls.find_method(&mut registry, name) {
+        Some(method) => (method as *const Method).cast(),
+        None => ptr::null(),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn class_getClassMethod(cls: *const objc_class, name: SEL) -> *const objc_method {
+    // SAFETY: Upheld by the caller.
+    match unsafe { Class::from_ptr(cls) } {
+        // SAFETY: The metaclass is valid.
+        Some(cls) => unsafe { class_getInstanceMethod(cls.isa.cast(), name) },
+        None => ptr::null(),
+    }
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn class_respondsToSelector(cls: *const objc_class, sel: SEL) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => return NO,
+    };
+    to_bool(cls.find_method(&mut registry(), sel).is_some())
+}
@@ -0,0 +1,110 @@
+use core::mem;
+use core::ptr;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+use super::{imp, nil_method, registry, sel, Class, SEL};
+use crate::{objc_object, objc_super, BOOL, IMP, NO, YES};
+
+/// The class of an object.
+///
+/// # Safety
+///
+/// The object must be valid and non-NULL.
+pub(crate) unsafe fn isa<'a>(obj: *const objc_object) -> &'a Class {
+    // SAFETY: All objects start with their `isa` pointer.
+    unsafe { &**obj.cast::<*const Class>() }
+}
+
+/// An `IMP` for messages that an object does not respond to.
+unsafe extern "C" fn unrecognized_selector(this: *mut objc_object, cmd: SEL) -> usize {
+    // SAFETY: The receiver is valid, and the selector is a valid selector.
+    let (cls, name) = unsafe { (isa(this), CStr::from_ptr(cmd.cast())) };
+    let kind = if cls.is_meta { '+' } else { '-' };
+    std::eprintln!(
+        "{}[{} {}]: unrecognized selector sent to {:p} (message forwarding is not supported by the mock runtime)",
+        kind,
+        cls.name().to_string_lossy(),
+        name.to_string_lossy(),
+        this,
+    );
+    std::process::abort()
+}
+
+fn lookup(cls: &Class, sel: SEL) -> IMP {
+    let mut registry = registry();
+    match cls.find_method(&mut registry, sel) {
+        Some(method) => *method.imp.get(&mut registry),
+        None => imp!(unrecognized_selector as unsafe extern "C" fn(_, _) -> _),
+    }
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn objc_msg_lookup(receiver: *mut objc_object, sel: SEL) -> IMP {
+    if receiver.is_null() {
+        return imp!(nil_method as unsafe extern "C" fn(_, _) -> _);
+    }
+    // SAFETY: Upheld by the caller.
+    lookup(unsafe { isa(receiver) }, sel)
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_msg_lookup_super(sup: *const objc_super, sel: SEL) -> IMP {
+    // SAFETY: Upheld by the caller.
+    let sup = unsafe { &*sup };
+    // SAFETY: Upheld by the caller.
+    match unsafe { Class::from_ptr(sup.super_class) } {
+        Some(cls) if !sup.receiver.is_null() => lookup(cls, sel),
+        _ => imp!(nil_method as unsafe extern "C" fn(_, _) -> _),
+    }
+}
+
+/// Send a message with no arguments.
+///
+/// # Safety
+///
+/// The receiver must be valid or NULL, and the method must have the given
+/// signature.
+pub(crate) unsafe fn send<R>(receiver: *mut objc_object, name: &'static [u8]) -> R {
+    let sel = sel(name);
+    // SAFETY: Upheld by the caller.
+    let imp = unsafe { objc_msg_lookup(receiver, sel) }.unwrap();
+    // SAFETY: Upheld by the caller.
+    let imp: unsafe extern "C" fn(*mut objc_object, SEL) -> R = unsafe { mem::transmute(imp) };
+    // SAFETY: Upheld by the caller.
+    unsafe { imp(receiver, sel) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn sel_registerName(name: *const c_char) -> SEL {
+    if name.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    let name = unsafe { CStr::from_ptr(name) };
+    registry().sel(name.to_bytes())
+}
+
+#[no_mangle]
+unsafe extern "C" fn sel_getUid(name: *const c_char) -> SEL {
+    // SAFETY: Upheld by the caller.
+    unsafe { sel_registerName(name) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn sel_getName(sel: SEL) -> *const c_char {
+    if sel.is_null() {
+        return b"<null selector>\0".as_ptr().cast();
+    }
+    // Selectors are pointers to their name
+    sel.cast()
+}
+
+#[no_mangle]
+unsafe extern "C" fn sel_isEqual(lhs: SEL, rhs: SEL) -> BOOL {
+    if lhs == rhs {
+        YES
+    } else {
+        NO
+    }
+}
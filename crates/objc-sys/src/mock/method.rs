@@ -0,0 +1,304 @@
+use core::mem;
+use core::ptr;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_uint};
+use std::vec::Vec;
+
+use super::{copy_str, registry, Locked, SEL};
+use crate::{objc_ivar, objc_method, IMP};
+
+pub(crate) struct Method {
+    pub(crate) sel: SEL,
+    pub(crate) imp: Locked<IMP>,
+    pub(crate) types: CString,
+}
+
+impl Method {
+    /// # Safety
+    ///
+    /// The pointer must be a valid method.
+    unsafe fn from_ptr<'a>(method: *const objc_method) -> &'a Self {
+        // SAFETY: Ensured by the caller.
+        unsafe { &*method.cast::<Self>() }
+    }
+
+    fn types(&self) -> Vec<&[u8]> {
+        split_types(self.types.as_bytes())
+    }
+}
+
+pub(crate) struct Ivar {
+    pub(crate) name: CString,
+    pub(crate) types: CString,
+    pub(crate) offset: isize,
+}
+
+impl Ivar {
+    /// # Safety
+    ///
+    /// The pointer must be a valid instance variable.
+    unsafe fn from_ptr<'a>(ivar: *const objc_ivar) -> &'a Self {
+        // SAFETY: Ensured by the caller.
+        unsafe { &*ivar.cast::<Self>() }
+    }
+}
+
+/// The length of the first type in a type encoding.
+fn type_len(s: &[u8]) -> usize {
+    let mut i = 0;
+    // Skip qualifiers
+    while i < s.len() && b"rnNoORVA".contains(&s[i]) {
+        i += 1;
+    }
+    match s.get(i) {
+        None => i,
+        Some(b'^') => i + 1 + type_len(&s[i + 1..]),
+        Some(b'{') => i + balanced_len(&s[i..], b'{', b'}'),
+        Some(b'(') => i + balanced_len(&s[i..], b'(', b')'),
+        Some(b'[') => i + balanced_len(&s[i..], b'[', b']'),
+        Some(b'b') => i + 1 + digits_len(&s[i + 1..]),
+        Some(b'@') => match s.get(i + 1) {
+            Some(b'?') => i + 2,
+            // Class name, e.g. `@"NSString"`
+            Some(b'"') => match s[i + 2..].iter().position(|&b| b == b'"') {
+                Some(end) => i + 3 + end,
+                None => s.len(),
+            },
+            _ => i + 1,
+        },
+        Some(_) => i + 1,
+    }
+}
+
+fn balanced_len(s: &[u8], open: u8, close: u8) -> usize {
+    let mut depth = 0;
+    for (i, &b) in s.iter().enumerate() {
+        if b == open {
+            depth += 1;
+        } else if b == close {
+            depth -= 1;
+            if depth == 0 {
+                return i + 1;
+            }
+        }
+    }
+    s.len()
+}
+
+fn digits_len(s: &[u8]) -> usize {
+    s.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+/// Split a method type encoding into the return type and argument types,
+/// skipping the stack offsets.
+pub(crate) fn split_types(mut s: &[u8]) -> Vec<&[u8]> {
+    let mut types = Vec::new();
+    while !s.is_empty() {
+        let len = type_len(s);
+        types.push(&s[..len]);
+        s = &s[len..];
+        // Skip the offset
+        let offset = s
+            .iter()
+            .take_while(|&&b| b == b'-' || b.is_ascii_digit())
+            .count();
+        s = &s[offset..];
+    }
+    types
+}
+
+/// Copy a type into a buffer, like `strncpy`.
+unsafe fn copy_into(ty: Option<&[u8]>, dst: *mut c_char, dst_len: usize) {
+    if dst.is_null() || dst_len == 0 {
+        return;
+    }
+    let ty = ty.unwrap_or_default();
+    let len = ty.len().min(dst_len);
+    // SAFETY: The caller ensures that `dst` has room for `dst_len` bytes.
+    unsafe {
+        ptr::copy_nonoverlapping(ty.as_ptr(), dst.cast(), len);
+        ptr::write_bytes(dst.add(len), 0, dst_len - len);
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_getName(method: *const objc_method) -> SEL {
+    if method.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { Method::from_ptr(method) }.sel
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_getImplementation(method: *const objc_method) -> IMP {
+    if method.is_null() {
+        return None;
+    }
+    // SAFETY: Upheld by the caller.
+    *unsafe { Method::from_ptr(method) }.imp.get(&mut registry())
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_setImplementation(method: *const objc_method, imp: IMP) -> IMP {
+    if method.is_null() {
+        return None;
+    }
+    // SAFETY: Upheld by the caller.
+    let method = unsafe { Method::from_ptr(method) };
+    mem::replace(method.imp.get(&mut registry()), imp)
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_exchangeImplementations(
+    method1: *mut objc_method,
+    method2: *mut objc_method,
+) {
+    if method1.is_null() || method2.is_null() {
+        return;
+    }
+    // SAFETY: Upheld by the caller.
+    let (method1, method2) = unsafe { (Method::from_ptr(method1), Method::from_ptr(method2)) };
+    let mut registry = registry();
+    let imp1 = *method1.imp.get(&mut registry);
+    let imp2 = mem::replace(method2.imp.get(&mut registry), imp1);
+    *method1.imp.get(&mut registry) = imp2;
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_getTypeEncoding(method: *const objc_method) -> *const c_char {
+    if method.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { Method::from_ptr(method) }.types.as_ptr()
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_getNumberOfArguments(method: *const objc_method) -> c_uint {
+    if method.is_null() {
+        return 0;
+    }
+    // SAFETY: Upheld by the caller.
+    let types = unsafe { Method::from_ptr(method) }.types();
+    types.len().saturating_sub(1) as c_uint
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_copyReturnType(method: *const objc_method) -> *mut c_char {
+    if method.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: Upheld by the caller.
+    let types = unsafe { Method::from_ptr(method) }.types();
+    copy_str(types.first().copied().unwrap_or_default())
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_copyArgumentType(
+    method: *const objc_method,
+    index: c_uint,
+) -> *mut c_char {
+    if method.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: Upheld by the caller.
+    let types = unsafe { Method::from_ptr(method) }.types();
+    match types.get(index as usize + 1) {
+        Some(ty) => copy_str(ty),
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_getReturnType(
+    method: *const objc_method,
+    dst: *mut c_char,
+    dst_len: usize,
+) {
+    let ty = if method.is_null() {
+        None
+    } else {
+        // SAFETY: Upheld by the caller.
+        unsafe { Method::from_ptr(method) }.types().first().copied()
+    };
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_into(ty, dst, dst_len) };
+}
+
+#[no_mangle]
+unsafe extern "C" fn method_getArgumentType(
+    method: *const objc_method,
+    index: c_uint,
+    dst: *mut c_char,
+    dst_len: usize,
+) {
+    let ty = if method.is_null() {
+        None
+    } else {
+        // SAFETY: Upheld by the caller.
+        let types = unsafe { Method::from_ptr(method) }.types();
+        types.get(index as usize + 1).copied()
+    };
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_into(ty, dst, dst_len) };
+}
+
+#[no_mangle]
+unsafe extern "C" fn ivar_getName(ivar: *const objc_ivar) -> *const c_char {
+    if ivar.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { Ivar::from_ptr(ivar) }.name.as_ptr()
+}
+
+#[no_mangle]
+unsafe extern "C" fn ivar_getOffset(ivar: *const objc_ivar) -> isize {
+    if ivar.is_null() {
+        return 0;
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { Ivar::from_ptr(ivar) }.offset
+}
+
+#[no_mangle]
+unsafe extern "C" fn ivar_getTypeEncoding(ivar: *const objc_ivar) -> *const c_char {
+    if ivar.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { Ivar::from_ptr(ivar) }.types.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_types() {
+        let cases: &[(&[u8], &[&[u8]])] = &[
+            (b"", &[]),
+            (b"v@:", &[b"v", b"@", b":"]),
+            (b"v16@0:8", &[b"v", b"@", b":"]),
+            (b"Q@:", &[b"Q", b"@", b":"]),
+            (b"@@:@?", &[b"@", b"@", b":", b"@?"]),
+            (b"r*@:^^{a=ib}", &[b"r*", b"@", b":", b"^^{a=ib}"]),
+            (
+                b"{CGRect={CGPoint=dd}{CGSize=dd}}@:",
+                &[b"{CGRect={CGPoint=dd}{CGSize=dd}}", b"@", b":"],
+            ),
+            (
+                b"v@:[4i](u=ci)b3",
+                &[b"v", b"@", b":", b"[4i]", b"(u=ci)", b"b3"],
+            ),
+            (
+                b"@\"NSString\"24@0:8@16",
+                &[b"@\"NSString\"", b"@", b":", b"@"],
+            ),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(split_types(s), *expected, "{:?}", core::str::from_utf8(s));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! A mock Objective-C runtime, written in pure Rust.
+//!
+//! Enabled with the `unstable-mock-runtime` feature, this module defines
+//! (a subset of) the runtime functions declared in this crate, instead of
+//! linking to `libobjc`. This allows running unit tests of code that uses
+//! Objective-C on platforms where no runtime is installed, such as a plain
+//! Linux CI machine.
+//!
+//! The mock uses the GNUStep ABI (message sending goes through
+//! `objc_msg_lookup`), and comes with the root classes `NSObject` and
+//! `NSProxy`, as well as `NSAutoreleasePool`, a minimal `NSString` and the
+//! `NSObject` protocol. New classes and protocols can be created and
+//! registered as usual, and objects are reference-counted, autoreleased and
+//! weakly referenced like on GNUStep.
+//!
+//!
+//! ## Limitations
+//!
+//! This is a stub, suitable for testing the logic of your own code, not for
+//! testing how it interacts with the real runtime. Notably:
+//! - No other classes exist; in particular, nothing from Foundation. The
+//!   `NSString` class only supports `-UTF8String`, `-length` and
+//!   `-lengthOfBytesUsingEncoding:`.
+//! - Sending a message that an object does not respond to aborts the
+//!   process. Message forwarding, `+resolveInstanceMethod:` and
+//!   `-doesNotRecognizeSelector:` are not supported.
+//! - Messages to `nil` only return zero in general-purpose registers, so
+//!   floating-point and struct return values are undefined.
+//! - `+initialize` and `+load` are never sent.
+//! - `-description` returns a string containing the class name and address,
+//!   and `-copy` requires the class to implement `-copyWithZone:`.
+//! - Panics in methods called by the runtime, such as in `-dealloc`, abort
+//!   the process. Tests that expect such panics (with `#[should_panic]`)
+//!   must therefore be ignored when using the mock, e.g. with
+//!   `#[cfg_attr(feature = "unstable-mock-runtime", ignore)]`.
+//! - `objc_retainAutoreleasedReturnValue` only reclaims objects that were
+//!   returned with `objc_autoreleaseReturnValue`, not objects that were
+//!   autoreleased in other ways.
+//! - Exceptions, associated objects, properties, `@synchronized`, blocks and
+//!   the `objc_msgSend` family are not implemented, and using them will fail
+//!   to link.
+//! - Method lookup takes a global lock and isn't cached, so message sending
+//!   is much slower than on a real runtime.
+use core::cell::UnsafeCell;
+use core::ffi::c_void;
+use core::mem;
+use core::ptr;
+use std::boxed::Box;
+use std::collections::HashMap;
+use std::os::raw::{c_char, c_uint};
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+use std::vec::Vec;
+
+use crate::{objc_object, objc_selector, BOOL, NO, YES};
+
+mod class;
+mod message;
+mod method;
+mod object;
+mod protocol;
+mod rc;
+mod root;
+
+pub(crate) use self::class::Class;
+pub(crate) use self::protocol::Protocol;
+
+type SEL = *const objc_selector;
+
+/// The state of the runtime.
+///
+/// The data of classes and protocols may only be accessed while holding the
+/// lock on this, see [`Locked`].
+pub(crate) struct Registry {
+    /// All classes that have been allocated, by name.
+    classes: HashMap<Vec<u8>, *mut Class>,
+    /// Selectors are pointers to interned, NUL-terminated strings.
+    selectors: HashMap<Vec<u8>, *const c_char>,
+    /// All protocols that have been allocated, by name.
+    protocols: HashMap<Vec<u8>, *mut Protocol>,
+    /// The locations of weak references to each object.
+    weak: HashMap<usize, Vec<*mut *mut objc_object>>,
+}
+
+// SAFETY: The pointers in the registry are either to static data, or to
+// leaked allocations, and are only modified while holding the lock.
+unsafe impl Send for Registry {}
+
+impl Registry {
+    fn new() -> Self {
+        Self {
+            classes: HashMap::new(),
+            selectors: HashMap::new(),
+            protocols: HashMap::new(),
+            weak: HashMap::new(),
+        }
+    }
+
+    fn sel(&mut self, name: &[u8]) -> SEL {
+        let name = self.selectors.entry(name.to_vec()).or_insert_with(|| {
+            let mut bytes = name.to_vec();
+            bytes.push(0);
+            Box::leak(bytes.into_boxed_slice()).as_ptr().cast()
+        });
+        name.cast()
+    }
+}
+
+static REGISTRY: AtomicPtr<Mutex<Registry>> = AtomicPtr::new(ptr::null_mut());
+
+/// Lock the runtime.
+///
+/// Must not be called while already holding the lock, and the lock must not
+/// be held while calling method implementations.
+pub(crate) fn registry() -> MutexGuard<'static, Registry> {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let mut registry = Registry::new();
+        root::init(&mut registry);
+        let registry = Box::into_raw(Box::new(Mutex::new(registry)));
+        REGISTRY.store(registry, Ordering::Release);
+    });
+    // SAFETY: Initialized above, and never deallocated.
+    let registry = unsafe { &*REGISTRY.load(Ordering::Acquire) };
+    registry.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Look up a selector by its NUL-terminated name.
+pub(crate) fn sel(name: &'static [u8]) -> SEL {
+    registry().sel(&name[..name.len() - 1])
+}
+
+/// Convert a function pointer to an `IMP`.
+macro_rules! imp {
+    ($f:expr) => {
+        // SAFETY: Function pointers have the same layout, and the resulting
+        // `IMP` is always cast back to the correct type before being called.
+        Some(unsafe { core::mem::transmute::<*const (), crate::InnerImp>($f as *const ()) })
+    };
+}
+pub(crate) use imp;
+
+extern "C" {
+    fn malloc(size: usize) -> *mut c_void;
+}
+
+/// Copy the items into an array allocated with `malloc`, as returned by the
+/// `copy` functions of the runtime.
+///
+/// Like on Apple's runtime, the array is NULL-terminated, and empty lists
+/// are returned as NULL.
+pub(crate) unsafe fn copy_list<T: Copy>(items: &[T], out_len: *mut c_uint) -> *mut T {
+    if !out_len.is_null() {
+        // SAFETY: The caller ensures that `out_len` is valid if non-NULL.
+        unsafe { *out_len = items.len() as c_uint };
+    }
+    if items.is_empty() {
+        return ptr::null_mut();
+    }
+    // SAFETY: Allocating is safe, and we check for NULL below.
+    let ptr: *mut T = unsafe { malloc(mem::size_of::<T>() * (items.len() + 1)) }.cast();
+    assert!(!ptr.is_null(), "failed allocating");
+    // SAFETY: The allocation has space for the items and a terminator.
+    unsafe {
+        ptr::copy_nonoverlapping(items.as_ptr(), ptr, items.len());
+        ptr.add(items.len()).write_bytes(0, 1);
+    }
+    ptr
+}
+
+/// Copy a byte string into a NUL-terminated string allocated with `malloc`.
+pub(crate) fn copy_str(s: &[u8]) -> *mut c_char {
+    // SAFETY: Allocating is safe, and we check for NULL below.
+    let ptr: *mut u8 = unsafe { malloc(s.len() + 1) }.cast();
+    assert!(!ptr.is_null(), "failed allocating");
+    // SAFETY: The allocation has space for the string and the NUL byte.
+    unsafe {
+        ptr::copy_nonoverlapping(s.as_ptr(), ptr, s.len());
+        ptr.add(s.len()).write(0);
+    }
+    ptr.cast()
+}
+
+/// Store a value that is only accessed while holding the registry lock.
+pub(crate) struct Locked<T>(UnsafeCell<T>);
+
+// SAFETY: Only accessed while holding the registry lock.
+unsafe impl<T> Sync for Locked<T> {}
+
+impl<T> Locked<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(UnsafeCell::new(value))
+    }
+
+    /// Access the value, while holding the lock.
+    pub(crate) fn get<'a>(&'a self, _registry: &'a mut Registry) -> &'a mut T {
+        // SAFETY: The registry is locked, and the returned reference borrows
+        // the registry mutably, so no other references exist.
+        unsafe { &mut *self.0.get() }
+    }
+}
+
+pub(crate) fn to_bool(value: bool) -> BOOL {
+    if value {
+        YES
+    } else {
+        NO
+    }
+}
+
+/// An `IMP` that returns zero, used for messages to `nil`.
+unsafe extern "C" fn nil_method(_this: *mut objc_object, _cmd: SEL) -> usize {
+    0
+}
@@ -0,0 +1,169 @@
+use core::ffi::c_void;
+use core::mem;
+use core::ptr;
+use std::alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout};
+use std::os::raw::c_char;
+use std::sync::atomic::AtomicUsize;
+
+use super::message::isa;
+use super::{registry, root, sel, Class, SEL};
+use crate::{objc_class, objc_object};
+
+/// Data stored in front of each object allocated by `class_createInstance`.
+pub(crate) struct Header {
+    /// The retain count, starts at 1.
+    pub(crate) retain_count: AtomicUsize,
+    /// The size of the object, excluding the header.
+    size: usize,
+}
+
+/// The offset from the start of the allocation to the object.
+///
+/// This keeps objects 16-byte aligned, like `malloc` does.
+const HEADER_SIZE: usize = 16;
+const ALIGN: usize = 16;
+
+fn layout(size: usize) -> Layout {
+    Layout::from_size_align(HEADER_SIZE + size, ALIGN).expect("object too large")
+}
+
+/// Get the header of an object.
+///
+/// # Safety
+///
+/// The object must have been created with `class_createInstance`, see
+/// [`has_header`].
+pub(crate) unsafe fn header<'a>(obj: *const objc_object) -> &'a Header {
+    // SAFETY: Ensured by the caller.
+    unsafe { &*obj.cast::<u8>().sub(HEADER_SIZE).cast::<Header>() }
+}
+
+/// Whether the object was created with `class_createInstance`.
+///
+/// Class and protocol objects are statically allocated, or leaked.
+///
+/// # Safety
+///
+/// The object must be valid and non-NULL.
+pub(crate) unsafe fn has_header(obj: *const objc_object) -> bool {
+    // SAFETY: Ensured by the caller.
+    let cls = unsafe { isa(obj) };
+    !cls.is_meta && !ptr::eq(cls, &root::Protocol)
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn class_createInstance(
+    cls: *const objc_class,
+    extra_bytes: usize,
+) -> *mut objc_object {
+    // SAFETY: Upheld by the caller.
+    let cls = match unsafe { Class::from_ptr(cls) } {
+        Some(cls) => cls,
+        None => return ptr::null_mut(),
+    };
+    let size = cls.data.get(&mut registry()).instance_size + extra_bytes;
+    let layout = layout(size);
+    // SAFETY: The layout has a non-zero size.
+    let base = unsafe { alloc_zeroed(layout) };
+    if base.is_null() {
+        handle_alloc_error(layout);
+    }
+    // SAFETY: The allocation has room for the header and the object, and
+    // is properly aligned.
+    unsafe {
+        base.cast::<Header>().write(Header {
+            retain_count: AtomicUsize::new(1),
+            size,
+        });
+        let obj = base.add(HEADER_SIZE);
+        obj.cast::<*const Class>().write(cls);
+        obj.cast()
+    }
+}
+
+/// Call `.cxx_destruct` on each class in the hierarchy, like GNUStep does.
+///
+/// # Safety
+///
+/// The object must be valid and non-NULL.
+unsafe fn call_cxx_destruct(obj: *mut objc_object) {
+    let sel = sel(b".cxx_destruct\0");
+    // SAFETY: Ensured by the caller.
+    let cls = unsafe { isa(obj) };
+    for cls in cls.chain() {
+        let imp = {
+            let mut registry = registry();
+            match cls.own_method(&mut registry, sel) {
+                Some(method) => *method.imp.get(&mut registry),
+                None => continue,
+            }
+        };
+        if let Some(imp) = imp {
+            // SAFETY: `.cxx_destruct` takes no arguments.
+            unsafe {
+                let imp: unsafe extern "C" fn(*mut objc_object, SEL) = mem::transmute(imp);
+                imp(obj, sel);
+            }
+        }
+    }
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn object_dispose(obj: *mut objc_object) -> *mut objc_object {
+    if !obj.is_null() {
+        // SAFETY: The object is valid.
+        unsafe { call_cxx_destruct(obj) };
+        // SAFETY: The caller ensures that the object was created with
+        // `class_createInstance`, and is not used afterwards.
+        unsafe {
+            let size = header(obj).size;
+            dealloc(obj.cast::<u8>().sub(HEADER_SIZE), layout(size));
+        }
+    }
+    ptr::null_mut()
+}
+
+#[no_mangle]
+unsafe extern "C" fn object_getClass(obj: *const objc_object) -> *const objc_class {
+    if obj.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { isa(obj) }.as_ptr()
+}
+
+#[no_mangle]
+unsafe extern "C" fn object_setClass(
+    obj: *mut objc_object,
+    cls: *const objc_class,
+) -> *const objc_class {
+    if obj.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe {
+        let old = isa(obj).as_ptr();
+        obj.cast::<*const objc_class>().write(cls);
+        old
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn object_getClassName(obj: *const objc_object) -> *const c_char {
+    if obj.is_null() {
+        return b"nil\0".as_ptr().cast();
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { isa(obj) }.name
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn object_getIndexedIvars(obj: *const objc_object) -> *const c_void {
+    if obj.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    let size = unsafe { isa(obj) }.data.get(&mut registry()).instance_size;
+    // SAFETY: The extra bytes are located after the instance variables.
+    unsafe { obj.cast::<u8>().add(size).cast() }
+}
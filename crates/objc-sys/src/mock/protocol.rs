@@ -0,0 +1,290 @@
+use core::ptr;
+use std::boxed::Box;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_uint};
+use std::vec::Vec;
+
+use super::{copy_list, registry, root, to_bool, Class, Locked, Registry, SEL};
+use crate::{objc_method_description, objc_protocol, BOOL, NO};
+
+/// The memory layout of a protocol.
+#[repr(C)]
+pub(crate) struct Protocol {
+    /// The `Protocol` class. This must be the first field, since protocols
+    /// are objects too.
+    isa: *const Class,
+    name: *const c_char,
+    data: Locked<ProtocolData>,
+}
+
+struct ProtocolData {
+    protocols: Vec<*const Protocol>,
+    methods: Vec<MethodDescription>,
+    registered: bool,
+}
+
+struct MethodDescription {
+    sel: SEL,
+    types: CString,
+    required: bool,
+    instance: bool,
+}
+
+impl Protocol {
+    /// # Safety
+    ///
+    /// The pointer must be NULL or a valid protocol.
+    pub(crate) unsafe fn from_ptr<'a>(protocol: *const objc_protocol) -> Option<&'a Self> {
+        // SAFETY: Ensured by the caller.
+        unsafe { protocol.cast::<Self>().as_ref() }
+    }
+
+    pub(crate) fn register(&self, registry: &mut Registry) {
+        self.data.get(registry).registered = true;
+    }
+
+    fn name(&self) -> &CStr {
+        // SAFETY: The name is a valid, NUL-terminated string.
+        unsafe { CStr::from_ptr(self.name) }
+    }
+}
+
+/// Whether the protocol is, or incorporates, the other protocol.
+pub(crate) fn protocol_conforms(
+    registry: &mut Registry,
+    protocol: &Protocol,
+    other: &Protocol,
+) -> bool {
+    if ptr::eq(protocol, other) || protocol.name() == other.name() {
+        return true;
+    }
+    let protocols = protocol.data.get(registry).protocols.clone();
+    protocols.into_iter().any(|proto| {
+        // SAFETY: Protocols are never deallocated.
+        protocol_conforms(registry, unsafe { &*proto }, other)
+    })
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_getProtocol(name: *const c_char) -> *const objc_protocol {
+    if name.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: Upheld by the caller.
+    let name = unsafe { CStr::from_ptr(name) }.to_bytes();
+    let mut registry = registry();
+    let protocol = match registry.protocols.get(name) {
+        // SAFETY: Protocols are never deallocated.
+        Some(&protocol) => unsafe { &*protocol },
+        None => return ptr::null(),
+    };
+    if protocol.data.get(&mut registry).registered {
+        (protocol as *const Protocol).cast()
+    } else {
+        ptr::null()
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_copyProtocolList(out_len: *mut c_uint) -> *mut *const objc_protocol {
+    let mut registry = registry();
+    let all: Vec<_> = registry.protocols.values().copied().collect();
+    let protocols: Vec<_> = all
+        .into_iter()
+        // SAFETY: Protocols are never deallocated.
+        .filter(|&protocol| unsafe { &*protocol }.data.get(&mut registry).registered)
+        .map(|protocol| (protocol as *const Protocol).cast::<objc_protocol>())
+        .collect();
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_list(&protocols, out_len) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_allocateProtocol(name: *const c_char) -> *mut objc_protocol {
+    if name.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: Upheld by the caller.
+    let name = unsafe { CStr::from_ptr(name) }.to_bytes();
+    match allocate(&mut registry(), name) {
+        Some(protocol) => (protocol as *const Protocol as *mut Protocol).cast(),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Allocate an unregistered protocol, if one with the name does not exist.
+pub(crate) fn allocate(registry: &mut Registry, name: &[u8]) -> Option<&'static Protocol> {
+    if registry.protocols.contains_key(name) {
+        return None;
+    }
+    let protocol = Box::leak(Box::new(Protocol {
+        isa: &root::Protocol,
+        name: CString::new(name).unwrap().into_raw(),
+        data: Locked::new(ProtocolData {
+            protocols: Vec::new(),
+            methods: Vec::new(),
+            registered: false,
+        }),
+    }));
+    registry.protocols.insert(name.to_vec(), protocol);
+    Some(protocol)
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_registerProtocol(proto: *mut objc_protocol) {
+    // SAFETY: Upheld by the caller.
+    if let Some(proto) = unsafe { Protocol::from_ptr(proto) } {
+        proto.register(&mut registry());
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn protocol_conformsToProtocol(
+    proto: *const objc_protocol,
+    other: *const objc_protocol,
+) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    match unsafe { (Protocol::from_ptr(proto), Protocol::from_ptr(other)) } {
+        (Some(proto), Some(other)) => to_bool(protocol_conforms(&mut registry(), proto, other)),
+        _ => NO,
+    }
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn protocol_isEqual(
+    proto: *const objc_protocol,
+    other: *const objc_protocol,
+) -> BOOL {
+    // SAFETY: Upheld by the caller.
+    match unsafe { (Protocol::from_ptr(proto), Protocol::from_ptr(other)) } {
+        (Some(proto), Some(other)) => {
+            to_bool(ptr::eq(proto, other) || proto.name() == other.name())
+        }
+        _ => NO,
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn protocol_getName(proto: *const objc_protocol) -> *const c_char {
+    // SAFETY: Upheld by the caller.
+    match unsafe { Protocol::from_ptr(proto) } {
+        Some(proto) => proto.name,
+        None => ptr::null(),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn protocol_addMethodDescription(
+    proto: *mut objc_protocol,
+    name: SEL,
+    types: *const c_char,
+    is_required_method: BOOL,
+    is_instance_method: BOOL,
+) {
+    // SAFETY: Upheld by the caller.
+    let proto = match unsafe { Protocol::from_ptr(proto) } {
+        Some(proto) if !name.is_null() && !types.is_null() => proto,
+        _ => return,
+    };
+    // SAFETY: Upheld by the caller.
+    let types = CString::from(unsafe { CStr::from_ptr(types) });
+    let mut registry = registry();
+    let data = proto.data.get(&mut registry);
+    if !data.registered {
+        data.methods.push(MethodDescription {
+            sel: name,
+            types,
+            required: is_required_method != NO,
+            instance: is_instance_method != NO,
+        });
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn protocol_addProtocol(
+    proto: *mut objc_protocol,
+    addition: *const objc_protocol,
+) {
+    // SAFETY: Upheld by the caller.
+    if let (Some(proto), false) = (unsafe { Protocol::from_ptr(proto) }, addition.is_null()) {
+        let mut registry = registry();
+        let data = proto.data.get(&mut registry);
+        if !data.registered {
+            data.protocols.push(addition.cast());
+        }
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn protocol_copyMethodDescriptionList(
+    proto: *const objc_protocol,
+    is_required_method: BOOL,
+    is_instance_method: BOOL,
+    out_len: *mut c_uint,
+) -> *mut objc_method_description {
+    let mut methods = Vec::new();
+    // SAFETY: Upheld by the caller.
+    if let Some(proto) = unsafe { Protocol::from_ptr(proto) } {
+        let mut registry = registry();
+        for method in &proto.data.get(&mut registry).methods {
+            if method.required == (is_required_method != NO)
+                && method.instance == (is_instance_method != NO)
+            {
+                methods.push(objc_method_description {
+                    name: method.sel,
+                    types: method.types.as_ptr(),
+                });
+            }
+        }
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_list(&methods, out_len) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn protocol_getMethodDescription(
+    proto: *const objc_protocol,
+    sel: SEL,
+    is_required_method: BOOL,
+    is_instance_method: BOOL,
+) -> objc_method_description {
+    // SAFETY: Upheld by the caller.
+    if let Some(proto) = unsafe { Protocol::from_ptr(proto) } {
+        let mut registry = registry();
+        for method in &proto.data.get(&mut registry).methods {
+            if method.sel == sel
+                && method.required == (is_required_method != NO)
+                && method.instance == (is_instance_method != NO)
+            {
+                return objc_method_description {
+                    name: method.sel,
+                    types: method.types.as_ptr(),
+                };
+            }
+        }
+    }
+    objc_method_description {
+        name: ptr::null(),
+        types: ptr::null(),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn protocol_copyProtocolList(
+    proto: *const objc_protocol,
+    out_len: *mut c_uint,
+) -> *mut *const objc_protocol {
+    let mut protocols = Vec::new();
+    // SAFETY: Upheld by the caller.
+    if let Some(proto) = unsafe { Protocol::from_ptr(proto) } {
+        let mut registry = registry();
+        let data = proto.data.get(&mut registry);
+        protocols.extend(
+            data.protocols
+                .iter()
+                .map(|proto| proto.cast::<objc_protocol>()),
+        );
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { copy_list(&protocols, out_len) }
+}
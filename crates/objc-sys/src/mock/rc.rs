@@ -0,0 +1,276 @@
+use core::cell::{Cell, RefCell};
+use core::ffi::c_void;
+use core::ptr;
+use std::sync::atomic::Ordering;
+use std::vec::Vec;
+
+use super::message::send;
+use super::object::{has_header, header};
+use super::{registry, Registry};
+use crate::objc_object;
+
+std::thread_local! {
+    /// The objects in the autorelease pools of the current thread.
+    ///
+    /// Pool tokens are the length of this plus one when the pool was pushed,
+    /// such that they are never NULL.
+    static POOL: RefCell<Vec<*mut objc_object>> = const { RefCell::new(Vec::new()) };
+
+    /// An object returned with `objc_autoreleaseReturnValue`, that has not
+    /// yet been autoreleased.
+    ///
+    /// Like in GNUStep, this allows `objc_retainAutoreleasedReturnValue` to
+    /// skip the autorelease / retain pair.
+    static RETURNED: Cell<*mut objc_object> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Autorelease the object that was returned with
+/// `objc_autoreleaseReturnValue`, if it was not claimed.
+fn flush_returned() {
+    let obj = RETURNED.with(|returned| returned.replace(ptr::null_mut()));
+    if !obj.is_null() {
+        // SAFETY: The object has a +1 retain count that is owned by us.
+        unsafe { objc_autorelease(obj) };
+    }
+}
+
+/// Add an object to the innermost autorelease pool.
+pub(crate) fn add_to_pool(obj: *mut objc_object) {
+    POOL.with(|pool| pool.borrow_mut().push(obj));
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_autoreleasePoolPush() -> *mut c_void {
+    flush_returned();
+    let len = POOL.with(|pool| pool.borrow().len());
+    (len + 1) as *mut c_void
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_autoreleasePoolPop(pool: *mut c_void) {
+    flush_returned();
+    let len = (pool as usize).saturating_sub(1);
+    // Release objects one at a time, since `dealloc` may autorelease other
+    // objects into the pool.
+    while let Some(obj) = POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        if pool.len() > len {
+            pool.pop()
+        } else {
+            None
+        }
+    }) {
+        // SAFETY: The pool owns a reference to the object.
+        unsafe { objc_release(obj) };
+    }
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn objc_retain(value: *mut objc_object) -> *mut objc_object {
+    if value.is_null() {
+        return value;
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { send(value, b"retain\0") }
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn objc_release(value: *mut objc_object) {
+    if !value.is_null() {
+        // SAFETY: Upheld by the caller.
+        unsafe { send::<()>(value, b"release\0") };
+    }
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "C" fn objc_autorelease(value: *mut objc_object) -> *mut objc_object {
+    // Like GNUStep, this does not send `autorelease`, even if it has been
+    // overridden.
+    if !value.is_null() {
+        add_to_pool(value);
+    }
+    value
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_autoreleaseReturnValue(value: *mut objc_object) -> *mut objc_object {
+    flush_returned();
+    RETURNED.with(|returned| returned.set(value));
+    value
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_retainAutorelease(value: *mut objc_object) -> *mut objc_object {
+    // SAFETY: Upheld by the caller.
+    unsafe { objc_autorelease(objc_retain(value)) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_retainAutoreleaseReturnValue(
+    value: *mut objc_object,
+) -> *mut objc_object {
+    // SAFETY: Upheld by the caller.
+    unsafe { objc_autoreleaseReturnValue(objc_retain(value)) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_retainAutoreleasedReturnValue(
+    value: *mut objc_object,
+) -> *mut objc_object {
+    let returned = RETURNED.with(|returned| returned.replace(ptr::null_mut()));
+    if !value.is_null() && returned == value {
+        // We take over the +1 retain count from `objc_autoreleaseReturnValue`.
+        return value;
+    }
+    if !returned.is_null() {
+        // SAFETY: The object has a +1 retain count that is owned by us.
+        unsafe { objc_autorelease(returned) };
+    }
+    // SAFETY: Upheld by the caller.
+    unsafe { objc_retain(value) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_storeStrong(addr: *mut *mut objc_object, value: *mut objc_object) {
+    // SAFETY: Upheld by the caller.
+    unsafe {
+        let value = objc_retain(value);
+        let old = addr.replace(value);
+        objc_release(old);
+    }
+}
+
+/// Store a weak reference, while holding the lock.
+unsafe fn store_weak(
+    registry: &mut Registry,
+    addr: *mut *mut objc_object,
+    value: *mut objc_object,
+) -> *mut objc_object {
+    // SAFETY: Upheld by the caller.
+    let old = unsafe { *addr };
+    if let Some(locations) = registry.weak.get_mut(&(old as usize)) {
+        locations.retain(|&location| location != addr);
+        if locations.is_empty() {
+            registry.weak.remove(&(old as usize));
+        }
+    }
+
+    // SAFETY: Upheld by the caller.
+    let value = if value.is_null() || !unsafe { has_header(value) } {
+        // Classes and protocols live forever, so they don't need tracking.
+        value
+    } else if unsafe { header(value) }
+        .retain_count
+        .load(Ordering::Acquire)
+        == 0
+    {
+        // The object is deallocating.
+        ptr::null_mut()
+    } else {
+        registry.weak.entry(value as usize).or_default().push(addr);
+        value
+    };
+    // SAFETY: Upheld by the caller.
+    unsafe { *addr = value };
+    value
+}
+
+/// Clear all weak references to an object that is about to be deallocated.
+pub(crate) fn clear_weak(obj: *mut objc_object) {
+    let mut registry = registry();
+    for location in registry.weak.remove(&(obj as usize)).unwrap_or_default() {
+        // SAFETY: Weak locations are valid until `objc_destroyWeak`.
+        unsafe { *location = ptr::null_mut() };
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_initWeak(
+    addr: *mut *mut objc_object,
+    value: *mut objc_object,
+) -> *mut objc_object {
+    // SAFETY: Upheld by the caller.
+    unsafe {
+        *addr = ptr::null_mut();
+        store_weak(&mut registry(), addr, value)
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_storeWeak(
+    addr: *mut *mut objc_object,
+    value: *mut objc_object,
+) -> *mut objc_object {
+    // SAFETY: Upheld by the caller.
+    unsafe { store_weak(&mut registry(), addr, value) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_destroyWeak(addr: *mut *mut objc_object) {
+    // SAFETY: Upheld by the caller.
+    unsafe { store_weak(&mut registry(), addr, ptr::null_mut()) };
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_loadWeakRetained(addr: *mut *mut objc_object) -> *mut objc_object {
+    let registry = registry();
+    // SAFETY: Upheld by the caller.
+    let value = unsafe { *addr };
+    // SAFETY: The object is valid while it is in the weak table, which we
+    // hold the lock to.
+    if value.is_null() || !unsafe { has_header(value) } {
+        return value;
+    }
+    // Keep the object alive while we send `retain` without holding the
+    // lock, but only if it is not already deallocating.
+    //
+    // SAFETY: The object is valid, see above.
+    let retain_count = &unsafe { header(value) }.retain_count;
+    let mut count = retain_count.load(Ordering::Relaxed);
+    loop {
+        if count == 0 {
+            return ptr::null_mut();
+        }
+        match retain_count.compare_exchange_weak(
+            count,
+            count + 1,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => break,
+            Err(current) => count = current,
+        }
+    }
+    drop(registry);
+    // SAFETY: The object is kept alive by the extra retain count above.
+    let value = unsafe { objc_retain(value) };
+    retain_count.fetch_sub(1, Ordering::Release);
+    value
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_loadWeak(addr: *mut *mut objc_object) -> *mut objc_object {
+    // SAFETY: Upheld by the caller.
+    unsafe { objc_autorelease(objc_loadWeakRetained(addr)) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_copyWeak(to: *mut *mut objc_object, from: *mut *mut objc_object) {
+    let mut registry = registry();
+    // SAFETY: Upheld by the caller.
+    unsafe {
+        *to = ptr::null_mut();
+        store_weak(&mut registry, to, *from);
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn objc_moveWeak(to: *mut *mut objc_object, from: *mut *mut objc_object) {
+    let mut registry = registry();
+    // SAFETY: Upheld by the caller.
+    unsafe {
+        *to = ptr::null_mut();
+        store_weak(&mut registry, to, *from);
+        store_weak(&mut registry, from, ptr::null_mut());
+    }
+}
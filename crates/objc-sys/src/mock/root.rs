@@ -0,0 +1,532 @@
+//! The built-in classes.
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{fence, Ordering};
+use std::ffi::CStr;
+use std::format;
+use std::os::raw::{c_char, c_int};
+
+use super::class::{add_method, class_conforms, class_respondsToSelector};
+use super::message::{isa, objc_msg_lookup, send};
+use super::object::{class_createInstance, header, object_dispose, object_getIndexedIvars};
+use super::protocol::{allocate as allocate_protocol, protocol_isEqual};
+use super::rc::{add_to_pool, clear_weak};
+use super::{imp, registry, sel, to_bool, Class, Protocol as ProtocolObject, Registry, SEL};
+use crate::{objc_class, objc_object, objc_protocol, BOOL, IMP, NO, YES};
+
+const fn root_class(meta: &'static Class, name: &'static [u8]) -> Class {
+    Class::new(meta, ptr::null(), name.as_ptr().cast(), false)
+}
+
+const fn root_metaclass(cls: &'static Class, meta: &'static Class, name: &'static [u8]) -> Class {
+    Class::new(meta, cls, name.as_ptr().cast(), true)
+}
+
+#[cfg_attr(feature = "gnustep-2-0", export_name = "._OBJC_CLASS_NSObject")]
+#[cfg_attr(not(feature = "gnustep-2-0"), export_name = "_OBJC_CLASS_NSObject")]
+pub(crate) static NSObject: Class = root_class(&NSObject_meta, b"NSObject\0");
+static NSObject_meta: Class = root_metaclass(&NSObject, &NSObject_meta, b"NSObject\0");
+
+#[cfg_attr(feature = "gnustep-2-0", export_name = "._OBJC_CLASS_NSProxy")]
+#[cfg_attr(not(feature = "gnustep-2-0"), export_name = "_OBJC_CLASS_NSProxy")]
+static NSProxy: Class = root_class(&NSProxy_meta, b"NSProxy\0");
+static NSProxy_meta: Class = root_metaclass(&NSProxy, &NSProxy_meta, b"NSProxy\0");
+
+static NSAutoreleasePool: Class = Class::new(
+    &NSAutoreleasePool_meta,
+    &NSObject,
+    b"NSAutoreleasePool\0".as_ptr().cast(),
+    false,
+);
+static NSAutoreleasePool_meta: Class = Class::new(
+    &NSObject_meta,
+    &NSObject_meta,
+    b"NSAutoreleasePool\0".as_ptr().cast(),
+    true,
+);
+
+/// A minimal string class, used for `-description`.
+static NSString: Class = Class::new(
+    &NSString_meta,
+    &NSObject,
+    b"NSString\0".as_ptr().cast(),
+    false,
+);
+static NSString_meta: Class = Class::new(
+    &NSObject_meta,
+    &NSObject_meta,
+    b"NSString\0".as_ptr().cast(),
+    true,
+);
+
+/// The class of protocol objects.
+pub(crate) static Protocol: Class = root_class(&Protocol_meta, b"Protocol\0");
+static Protocol_meta: Class = root_metaclass(&Protocol, &Protocol_meta, b"Protocol\0");
+
+type Id = *mut objc_object;
+
+#[cfg(target_pointer_width = "64")]
+const NSUINTEGER: &str = "Q";
+#[cfg(not(target_pointer_width = "64"))]
+const NSUINTEGER: &str = "I";
+
+// Reference counting
+
+unsafe extern "C" fn retain(this: Id, _cmd: SEL) -> Id {
+    // SAFETY: Instances of the root classes have a header.
+    unsafe { header(this) }
+        .retain_count
+        .fetch_add(1, Ordering::Relaxed);
+    this
+}
+
+unsafe extern "C" fn release(this: Id, _cmd: SEL) {
+    // SAFETY: Instances of the root classes have a header.
+    let previous = unsafe { header(this) }
+        .retain_count
+        .fetch_sub(1, Ordering::Release);
+    if previous == 1 {
+        fence(Ordering::Acquire);
+        clear_weak(this);
+        // SAFETY: The object is valid, and no longer has any references.
+        unsafe { send::<()>(this, b"dealloc\0") };
+    }
+}
+
+unsafe extern "C" fn autorelease(this: Id, _cmd: SEL) -> Id {
+    add_to_pool(this);
+    this
+}
+
+unsafe extern "C" fn retain_count(this: Id, _cmd: SEL) -> usize {
+    // SAFETY: Instances of the root classes have a header.
+    unsafe { header(this) }.retain_count.load(Ordering::Relaxed)
+}
+
+unsafe extern "C" fn dealloc(this: Id, _cmd: SEL) {
+    // SAFETY: The object is being deallocated.
+    unsafe { object_dispose(this) };
+}
+
+/// `retain`, `release` and `autorelease` on objects that live forever.
+unsafe extern "C" fn retain_forever(this: Id, _cmd: SEL) -> Id {
+    this
+}
+
+unsafe extern "C" fn release_forever(_this: Id, _cmd: SEL) {}
+
+unsafe extern "C" fn retain_count_forever(_this: Id, _cmd: SEL) -> usize {
+    usize::MAX
+}
+
+// Allocation and initialization
+
+unsafe extern "C" fn alloc(cls: *const objc_class, _cmd: SEL) -> Id {
+    let sel = sel(b"allocWithZone:\0");
+    // SAFETY: Like Foundation, forward to `allocWithZone:` with a NULL
+    // zone, so that subclasses only have to override that.
+    unsafe {
+        let imp = objc_msg_lookup(cls as Id, sel);
+        let imp: unsafe extern "C" fn(Id, SEL, Id) -> Id = mem::transmute(imp);
+        imp(cls as Id, sel, ptr::null_mut())
+    }
+}
+
+unsafe extern "C" fn alloc_with_zone(cls: *const objc_class, _cmd: SEL, _zone: Id) -> Id {
+    // SAFETY: The receiver is a class.
+    unsafe { class_createInstance(cls, 0) }
+}
+
+unsafe extern "C" fn new(cls: *const objc_class, _cmd: SEL) -> Id {
+    // SAFETY: The receiver is a class, and `-init` takes no arguments.
+    unsafe {
+        let obj: Id = send(cls as Id, b"alloc\0");
+        send(obj, b"init\0")
+    }
+}
+
+unsafe extern "C" fn copy(this: Id, _cmd: SEL) -> Id {
+    let sel = sel(b"copyWithZone:\0");
+    // SAFETY: Subclasses that support copying implement `copyWithZone:`,
+    // which takes a zone that may be NULL.
+    unsafe {
+        let imp = objc_msg_lookup(this, sel);
+        let imp: unsafe extern "C" fn(Id, SEL, Id) -> Id = mem::transmute(imp);
+        imp(this, sel, ptr::null_mut())
+    }
+}
+
+unsafe extern "C" fn mutable_copy(this: Id, _cmd: SEL) -> Id {
+    let sel = sel(b"mutableCopyWithZone:\0");
+    // SAFETY: Subclasses that support copying implement
+    // `mutableCopyWithZone:`, which takes a zone that may be NULL.
+    unsafe {
+        let imp = objc_msg_lookup(this, sel);
+        let imp: unsafe extern "C" fn(Id, SEL, Id) -> Id = mem::transmute(imp);
+        imp(this, sel, ptr::null_mut())
+    }
+}
+
+unsafe extern "C" fn noop(_this: Id, _cmd: SEL) {}
+
+// Introspection
+
+unsafe extern "C" fn this(this: Id, _cmd: SEL) -> Id {
+    this
+}
+
+unsafe extern "C" fn description(this: Id, _cmd: SEL) -> Id {
+    // SAFETY: The receiver is valid.
+    let name = unsafe { isa(this) }.name().to_string_lossy();
+    let obj = new_string(&format!("<{name}: {this:p}>"));
+    add_to_pool(obj);
+    obj
+}
+
+unsafe extern "C" fn class(this: Id, _cmd: SEL) -> *const objc_class {
+    // SAFETY: The receiver is valid.
+    unsafe { isa(this) }.as_ptr()
+}
+
+unsafe extern "C" fn superclass(this: Id, _cmd: SEL) -> *const objc_class {
+    // SAFETY: The receiver is valid.
+    unsafe { isa(this) }.superclass.cast()
+}
+
+unsafe extern "C" fn class_superclass(cls: *const Class, _cmd: SEL) -> *const objc_class {
+    // SAFETY: The receiver is a class.
+    unsafe { &*cls }.superclass.cast()
+}
+
+unsafe extern "C" fn is_kind_of_class(this: Id, _cmd: SEL, cls: *const Class) -> BOOL {
+    // SAFETY: The receiver is valid, and the argument is NULL or a class.
+    match unsafe { cls.as_ref() } {
+        Some(cls) => to_bool(unsafe { isa(this) }.is_subclass_of(cls)),
+        None => NO,
+    }
+}
+
+unsafe extern "C" fn is_member_of_class(this: Id, _cmd: SEL, cls: *const Class) -> BOOL {
+    // SAFETY: The receiver is valid.
+    to_bool(ptr::eq(unsafe { isa(this) }, cls))
+}
+
+unsafe extern "C" fn is_subclass_of_class(
+    this: *const Class,
+    _cmd: SEL,
+    cls: *const Class,
+) -> BOOL {
+    // SAFETY: The receiver is a class, and the argument is NULL or a class.
+    match unsafe { cls.as_ref() } {
+        Some(cls) => to_bool(unsafe { &*this }.is_subclass_of(cls)),
+        None => NO,
+    }
+}
+
+unsafe extern "C" fn responds_to_selector(this: Id, _cmd: SEL, sel: SEL) -> BOOL {
+    // SAFETY: The receiver is valid.
+    unsafe { class_respondsToSelector(isa(this).as_ptr(), sel) }
+}
+
+unsafe extern "C" fn instances_respond_to_selector(
+    cls: *const objc_class,
+    _cmd: SEL,
+    sel: SEL,
+) -> BOOL {
+    // SAFETY: The receiver is a class.
+    unsafe { class_respondsToSelector(cls, sel) }
+}
+
+unsafe extern "C" fn conforms_to_protocol(
+    this: Id,
+    _cmd: SEL,
+    protocol: *const objc_protocol,
+) -> BOOL {
+    // SAFETY: The receiver is valid.
+    let cls = unsafe { isa(this) };
+    // SAFETY: The argument is NULL or a protocol.
+    let protocol = match unsafe { ProtocolObject::from_ptr(protocol) } {
+        Some(protocol) => protocol,
+        None => return NO,
+    };
+    let mut registry = registry();
+    to_bool(
+        cls.chain()
+            .any(|cls| class_conforms(&mut registry, cls, protocol)),
+    )
+}
+
+unsafe extern "C" fn class_conforms_to_protocol(
+    cls: *const Class,
+    _cmd: SEL,
+    protocol: *const objc_protocol,
+) -> BOOL {
+    // SAFETY: The receiver is a class.
+    let cls = unsafe { &*cls };
+    // SAFETY: The argument is NULL or a protocol.
+    let protocol = match unsafe { ProtocolObject::from_ptr(protocol) } {
+        Some(protocol) => protocol,
+        None => return NO,
+    };
+    let mut registry = registry();
+    to_bool(
+        cls.chain()
+            .any(|cls| class_conforms(&mut registry, cls, protocol)),
+    )
+}
+
+unsafe extern "C" fn hash(this: Id, _cmd: SEL) -> usize {
+    this as usize
+}
+
+unsafe extern "C" fn is_equal(this: Id, _cmd: SEL, other: Id) -> BOOL {
+    to_bool(this == other)
+}
+
+unsafe extern "C" fn protocol_is_equal(this: Id, _cmd: SEL, other: Id) -> BOOL {
+    // SAFETY: The receiver is a protocol, and the argument is an object.
+    unsafe { protocol_isEqual(this.cast(), other.cast()) }
+}
+
+unsafe extern "C" fn yes(_this: Id, _cmd: SEL) -> BOOL {
+    YES
+}
+
+unsafe extern "C" fn no(_this: Id, _cmd: SEL) -> BOOL {
+    NO
+}
+
+// NSString
+
+/// Create a string, with a +1 retain count.
+fn new_string(s: &str) -> Id {
+    // SAFETY: The class is valid.
+    let obj = unsafe { class_createInstance(NSString.as_ptr(), s.len() + 1) };
+    // SAFETY: The extra bytes have room for the string, and are zeroed, so
+    // the string is NUL-terminated.
+    unsafe {
+        let bytes = object_getIndexedIvars(obj) as *mut u8;
+        ptr::copy_nonoverlapping(s.as_ptr(), bytes, s.len());
+    }
+    obj
+}
+
+fn string_bytes<'a>(this: Id) -> &'a CStr {
+    // SAFETY: The receiver is a string created by `new_string`.
+    unsafe { CStr::from_ptr(object_getIndexedIvars(this).cast()) }
+}
+
+unsafe extern "C" fn utf8_string(this: Id, _cmd: SEL) -> *const c_char {
+    string_bytes(this).as_ptr()
+}
+
+unsafe extern "C" fn length_of_bytes(this: Id, _cmd: SEL, _encoding: c_int) -> usize {
+    string_bytes(this).to_bytes().len()
+}
+
+unsafe extern "C" fn length(this: Id, _cmd: SEL) -> usize {
+    let s = string_bytes(this).to_string_lossy();
+    s.encode_utf16().count()
+}
+
+unsafe extern "C" fn copy_with_zone(this: Id, _cmd: SEL, _zone: Id) -> Id {
+    // SAFETY: The receiver is valid.
+    unsafe { retain(this, _cmd) }
+}
+
+// NSAutoreleasePool
+
+unsafe extern "C" fn add_object(_cls: Id, _cmd: SEL, obj: Id) {
+    add_to_pool(obj);
+}
+
+/// Add the methods of the built-in classes, and register them.
+pub(super) fn init(registry: &mut Registry) {
+    let u = NSUINTEGER;
+    let b = "C"; // BOOL is `unsigned char` on GNUStep
+
+    let mut add = |cls: &Class, name: &str, imp: IMP, types: &str| {
+        let sel = registry.sel(name.as_bytes());
+        add_method(registry, cls, sel, imp, types.as_bytes());
+    };
+
+    macro_rules! f {
+        ($f:ident: $($t:ty),*) => {
+            imp!($f as unsafe extern "C" fn($($t),*) -> _)
+        };
+    }
+
+    // Methods shared by the root classes
+    for (cls, meta) in [(&NSObject, &NSObject_meta), (&NSProxy, &NSProxy_meta)] {
+        add(meta, "alloc", f!(alloc: _, _), "@@:");
+        add(
+            meta,
+            "allocWithZone:",
+            f!(alloc_with_zone: _, _, _),
+            "@@:^{_NSZone=}",
+        );
+        add(meta, "class", f!(this: _, _), "#@:");
+        add(meta, "superclass", f!(class_superclass: _, _), "#@:");
+        add(meta, "retain", f!(retain_forever: _, _), "@@:");
+        add(meta, "release", f!(release_forever: _, _), "v@:");
+        add(meta, "autorelease", f!(retain_forever: _, _), "@@:");
+        add(
+            meta,
+            "retainCount",
+            f!(retain_count_forever: _, _),
+            &format!("{u}@:"),
+        );
+        add(
+            meta,
+            "respondsToSelector:",
+            f!(responds_to_selector: _, _, _),
+            &format!("{b}@::"),
+        );
+        add(meta, "isProxy", f!(no: _, _), &format!("{b}@:"));
+
+        add(cls, "retain", f!(retain: _, _), "@@:");
+        add(cls, "release", f!(release: _, _), "v@:");
+        add(cls, "autorelease", f!(autorelease: _, _), "@@:");
+        add(
+            cls,
+            "retainCount",
+            f!(retain_count: _, _),
+            &format!("{u}@:"),
+        );
+        add(cls, "dealloc", f!(dealloc: _, _), "v@:");
+        add(cls, "class", f!(class: _, _), "#@:");
+        add(cls, "superclass", f!(superclass: _, _), "#@:");
+        add(cls, "hash", f!(hash: _, _), &format!("{u}@:"));
+        add(cls, "isEqual:", f!(is_equal: _, _, _), &format!("{b}@:@"));
+        add(cls, "description", f!(description: _, _), "@@:");
+        add(cls, "debugDescription", f!(description: _, _), "@@:");
+    }
+
+    // NSObject
+    add(&NSObject_meta, "new", f!(new: _, _), "@@:");
+    add(&NSObject_meta, "initialize", f!(noop: _, _), "v@:");
+    add(&NSObject_meta, "load", f!(noop: _, _), "v@:");
+    add(
+        &NSObject_meta,
+        "isSubclassOfClass:",
+        f!(is_subclass_of_class: _, _, _),
+        &format!("{b}@:#"),
+    );
+    add(
+        &NSObject_meta,
+        "instancesRespondToSelector:",
+        f!(instances_respond_to_selector: _, _, _),
+        &format!("{b}@::"),
+    );
+    add(
+        &NSObject_meta,
+        "conformsToProtocol:",
+        f!(class_conforms_to_protocol: _, _, _),
+        &format!("{b}@:@"),
+    );
+    add(&NSObject, "init", f!(this: _, _), "@@:");
+    add(&NSObject, "self", f!(this: _, _), "@@:");
+    add(&NSObject, "copy", f!(copy: _, _), "@@:");
+    add(&NSObject, "mutableCopy", f!(mutable_copy: _, _), "@@:");
+    add(
+        &NSObject,
+        "isKindOfClass:",
+        f!(is_kind_of_class: _, _, _),
+        &format!("{b}@:#"),
+    );
+    add(
+        &NSObject,
+        "isMemberOfClass:",
+        f!(is_member_of_class: _, _, _),
+        &format!("{b}@:#"),
+    );
+    add(
+        &NSObject,
+        "respondsToSelector:",
+        f!(responds_to_selector: _, _, _),
+        &format!("{b}@::"),
+    );
+    add(
+        &NSObject,
+        "conformsToProtocol:",
+        f!(conforms_to_protocol: _, _, _),
+        &format!("{b}@:@"),
+    );
+    add(&NSObject, "isProxy", f!(no: _, _), &format!("{b}@:"));
+
+    // NSProxy
+    add(&NSProxy, "isProxy", f!(yes: _, _), &format!("{b}@:"));
+
+    // NSString
+    add(&NSString, "UTF8String", f!(utf8_string: _, _), "*@:");
+    add(
+        &NSString,
+        "lengthOfBytesUsingEncoding:",
+        f!(length_of_bytes: _, _, _),
+        &format!("{u}@:i"),
+    );
+    add(&NSString, "length", f!(length: _, _), &format!("{u}@:"));
+    add(
+        &NSString,
+        "copyWithZone:",
+        f!(copy_with_zone: _, _, _),
+        "@@:^{_NSZone=}",
+    );
+
+    // NSAutoreleasePool
+    add(
+        &NSAutoreleasePool_meta,
+        "addObject:",
+        f!(add_object: _, _, _),
+        "v@:@",
+    );
+
+    // Protocol
+    add(&Protocol_meta, "class", f!(this: _, _), "#@:");
+    add(&Protocol, "retain", f!(retain_forever: _, _), "@@:");
+    add(&Protocol, "release", f!(release_forever: _, _), "v@:");
+    add(&Protocol, "autorelease", f!(retain_forever: _, _), "@@:");
+    add(
+        &Protocol,
+        "retainCount",
+        f!(retain_count_forever: _, _),
+        &format!("{u}@:"),
+    );
+    add(&Protocol, "class", f!(class: _, _), "#@:");
+    add(&Protocol, "hash", f!(hash: _, _), &format!("{u}@:"));
+    add(
+        &Protocol,
+        "isEqual:",
+        f!(protocol_is_equal: _, _, _),
+        &format!("{b}@:@"),
+    );
+    add(
+        &Protocol,
+        "respondsToSelector:",
+        f!(responds_to_selector: _, _, _),
+        &format!("{b}@::"),
+    );
+
+    // The `NSObject` protocol
+    let protocol = allocate_protocol(registry, b"NSObject").expect("NSObject protocol");
+    protocol.register(registry);
+    for cls in [&NSObject, &NSProxy] {
+        cls.data.get(registry).protocols.push(protocol);
+    }
+
+    for cls in [
+        &NSObject,
+        &NSProxy,
+        &NSAutoreleasePool,
+        &NSString,
+        &Protocol,
+    ] {
+        cls.data.get(registry).registered = true;
+        // SAFETY: The metaclass is valid.
+        unsafe { &*cls.isa }.data.get(registry).registered = true;
+        let name = cls.name().to_bytes().to_vec();
+        registry
+            .classes
+            .insert(name, cls as *const Class as *mut Class);
+    }
+}
@@ -0,0 +1,145 @@
+//! Pointer authentication on `arm64e`.
+//!
+//! On `arm64e`, code compiled by Clang signs function pointers when storing
+//! them, and authenticates them before calling them (see `ptrauth.h`). Rust
+//! does neither, so function pointers created in Rust must be signed before
+//! being given to the runtime, and function pointers read from the runtime
+//! must be authenticated (or stripped) before being called from Rust.
+//!
+//! On all other targets, these functions return the pointer unchanged.
+use core::ffi::c_void;
+
+/// Whether function pointers are signed on the current target.
+pub const PTRAUTH_CALLS: bool = cfg!(target_arm64e);
+
+/// Mix an address and a small integer into a discriminator.
+///
+/// Same as `ptrauth_blend_discriminator`.
+#[inline]
+pub fn ptrauth_blend_discriminator(address: *const c_void, extra: u16) -> usize {
+    #[cfg(target_arm64e)]
+    {
+        (address as usize & 0x0000_FFFF_FFFF_FFFF) | ((extra as usize) << 48)
+    }
+    #[cfg(not(target_arm64e))]
+    {
+        let _ = extra;
+        address as usize
+    }
+}
+
+/// Sign an unsigned function pointer with the key used for function
+/// pointers (`ptrauth_key_function_pointer`, the IA key).
+///
+/// The discriminator is `0` for plain C function pointers (such as `IMP`),
+/// and the result of [`ptrauth_blend_discriminator`] for address-
+/// discriminated pointers (such as the `invoke` pointer in blocks).
+///
+/// Same as `ptrauth_sign_unauthenticated`.
+///
+///
+/// # Safety
+///
+/// The pointer must not already be signed.
+#[inline]
+pub unsafe fn ptrauth_sign_function(ptr: *const c_void, discriminator: usize) -> *const c_void {
+    #[cfg(target_arm64e)]
+    {
+        let mut ptr = ptr;
+        // SAFETY: `pacia1716` only operates on the given registers.
+        unsafe {
+            core::arch::asm!(
+                "pacia1716",
+                inout("x17") ptr,
+                in("x16") discriminator,
+                options(pure, nomem, nostack, preserves_flags),
+            );
+        }
+        ptr
+    }
+    #[cfg(not(target_arm64e))]
+    {
+        let _ = discriminator;
+        ptr
+    }
+}
+
+/// Authenticate a signed function pointer, and return the unsigned pointer.
+///
+/// If the signature does not match, the returned pointer is invalid, and
+/// the process will crash when calling it (or immediately, on CPUs that
+/// implement `FEAT_FPAC`).
+///
+/// Same as `ptrauth_auth_data` with `ptrauth_key_function_pointer`.
+///
+///
+/// # Safety
+///
+/// The pointer must have been signed with the IA key and the given
+/// discriminator.
+#[inline]
+pub unsafe fn ptrauth_auth_function(ptr: *const c_void, discriminator: usize) -> *const c_void {
+    #[cfg(target_arm64e)]
+    {
+        let mut ptr = ptr;
+        // SAFETY: `autia1716` only operates on the given registers.
+        unsafe {
+            core::arch::asm!(
+                "autia1716",
+                inout("x17") ptr,
+                in("x16") discriminator,
+                options(pure, nomem, nostack, preserves_flags),
+            );
+        }
+        ptr
+    }
+    #[cfg(not(target_arm64e))]
+    {
+        let _ = discriminator;
+        ptr
+    }
+}
+
+/// Remove the signature from a function pointer without authenticating it.
+///
+/// Unsigned pointers are returned unchanged.
+///
+/// Same as `ptrauth_strip` with `ptrauth_key_function_pointer`.
+#[inline]
+pub fn ptrauth_strip_function(ptr: *const c_void) -> *const c_void {
+    #[cfg(target_arm64e)]
+    {
+        let mut ptr = ptr;
+        // SAFETY: `xpaclri` only operates on the link register, which is
+        // marked as an operand.
+        unsafe {
+            core::arch::asm!(
+                "xpaclri",
+                inout("x30") ptr,
+                options(pure, nomem, nostack, preserves_flags),
+            );
+        }
+        ptr
+    }
+    #[cfg(not(target_arm64e))]
+    {
+        ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        extern "C" fn func() {}
+        let ptr = func as *const c_void;
+        let address: *const *const c_void = &ptr;
+        let discriminator = ptrauth_blend_discriminator(address.cast(), 42);
+        let signed = unsafe { ptrauth_sign_function(ptr, discriminator) };
+        assert_eq!(ptrauth_strip_function(signed), ptr);
+        assert_eq!(unsafe { ptrauth_auth_function(signed, discriminator) }, ptr);
+        assert_eq!(signed == ptr, !PTRAUTH_CALLS);
+    }
+}
@@ -22,11 +22,10 @@ use crate::objc_object;
 // `retain`, `release`, `autorelease` or `dealloc` do.
 extern_c_unwind! {
     // Autoreleasepool
-    // ObjFW: Defined in `autorelease.h`, not available with libobjfw-rt!
+    // ObjFW: Defined in `autorelease.h`, not available with libobjfw-rt, so
+    // you must link to libobjfw as well to use these.
 
-    #[cfg(any(doc, not(feature = "unstable-objfw")))]
     pub fn objc_autoreleasePoolPop(pool: *mut c_void);
-    #[cfg(any(doc, not(feature = "unstable-objfw")))]
     pub fn objc_autoreleasePoolPush() -> *mut c_void;
 
     // Autorelease
@@ -17,9 +17,9 @@ pub struct objc_ivar {
 }
 
 #[cfg(not(feature = "unstable-c-unwind"))]
-type InnerImp = unsafe extern "C" fn();
+pub(crate) type InnerImp = unsafe extern "C" fn();
 #[cfg(feature = "unstable-c-unwind")]
-type InnerImp = unsafe extern "C-unwind" fn();
+pub(crate) type InnerImp = unsafe extern "C-unwind" fn();
 
 /// A nullable pointer to the start of a method implementation.
 ///
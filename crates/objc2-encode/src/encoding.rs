@@ -1,6 +1,7 @@
 use core::fmt;
 
 use crate::helper::{compare_encodings, Helper, NestingLevel};
+use crate::layout::Layout;
 use crate::parse::Parser;
 use crate::EncodingBox;
 
@@ -242,6 +243,69 @@ impl Encoding {
     pub fn equivalent_to_box(&self, other: &EncodingBox) -> bool {
         compare_encodings(self, other, NestingLevel::new(), false)
     }
+
+    /// The size in bytes of the C type with this encoding on the current
+    /// target, or `None` if it cannot be determined from the encoding.
+    ///
+    /// The size of structs, unions and arrays is computed from their
+    /// fields, using the normal C layout rules (so `#pragma pack` and
+    /// similar attributes are not taken into account).
+    ///
+    /// The size cannot be determined for:
+    /// - [`Void`](Self::Void) and [`Unknown`](Self::Unknown).
+    /// - [`LongDouble`](Self::LongDouble) and
+    ///   [`LongDoubleComplex`](Self::LongDoubleComplex), since their size
+    ///   depends on the compiler.
+    /// - Structs and unions without fields, which are usually opaque.
+    /// - Bitfields outside of a struct or union.
+    ///
+    /// The Apple runtime does not include the type of bitfields in the
+    /// encoding, so these are assumed to be declared as `int`, or as
+    /// `long long` if they are wider than 32 bits.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_encode::Encoding;
+    ///
+    /// const POINT: Encoding = Encoding::Struct("Point", &[Encoding::Char, Encoding::Int]);
+    /// const SIZE: Option<usize> = POINT.size();
+    /// assert_eq!(SIZE, Some(8));
+    /// assert_eq!(Encoding::Array(3, &POINT).size(), Some(24));
+    ///
+    /// assert_eq!(Encoding::Void.size(), None);
+    /// assert_eq!(Encoding::Struct("Opaque", &[]).size(), None);
+    /// ```
+    pub const fn size(&self) -> Option<usize> {
+        match Layout::new(self) {
+            Some(layout) => Some(layout.size),
+            None => None,
+        }
+    }
+
+    /// The alignment in bytes of the C type with this encoding on the
+    /// current target, or `None` if it cannot be determined from the
+    /// encoding.
+    ///
+    /// See [`Encoding::size`] for details.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_encode::Encoding;
+    ///
+    /// let enc = Encoding::Struct("Flags", &[Encoding::Char, Encoding::BitField(3, None)]);
+    /// assert_eq!(enc.size(), Some(4));
+    /// assert_eq!(enc.alignment(), Some(4));
+    /// ```
+    pub const fn alignment(&self) -> Option<usize> {
+        match Layout::new(self) {
+            Some(layout) => Some(layout.align),
+            None => None,
+        }
+    }
 }
 
 /// Formats this [`Encoding`] in a similar way that the `@encode` directive
@@ -465,6 +529,19 @@ mod tests {
             !"[12i";
         }
 
+        fn cpp_class_pointer() {
+            Encoding::Pointer(&Encoding::Struct("ns::Foo", &[]));
+            !Encoding::Pointer(&Encoding::Struct("Foo", &[]));
+            "^{ns::Foo=}";
+            ~"^{ns::Foo=ii}";
+            !"^{ns:Foo=}";
+        }
+
+        fn cpp_template_pointer() {
+            Encoding::Pointer(&Encoding::Struct("vector<int, std::allocator<int> >", &[]));
+            "^{vector<int, std::allocator<int> >=}";
+        }
+
         fn struct_() {
             Encoding::Struct("SomeStruct", &[Encoding::Char, Encoding::Int]);
             ~Encoding::Struct("SomeStruct", &[]);
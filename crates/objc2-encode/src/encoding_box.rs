@@ -25,6 +25,43 @@ use crate::Encoding;
 /// is possible.
 ///
 /// [`Struct`]: Self::Struct
+///
+///
+/// # Examples
+///
+/// Parse the type encoding of a method (such as returned by
+/// `method_getTypeEncoding`), and inspect the arguments.
+///
+/// ```
+/// use objc2_encode::{Encoding, EncodingBox};
+///
+/// let mut types = "{CGRect={CGPoint=dd}{CGSize=dd}}40@0:8^{CGRect}16[4C]24r*32";
+///
+/// let mut encodings = Vec::new();
+/// while !types.is_empty() {
+///     encodings.push(EncodingBox::from_start_of_str(&mut types).unwrap());
+///     // Skip the stack offset.
+///     types = types.trim_start_matches(|c: char| c.is_ascii_digit());
+/// }
+///
+/// assert_eq!(encodings.len(), 6);
+///
+/// match &encodings[0] {
+///     EncodingBox::Struct(name, fields) => {
+///         assert_eq!(name, "CGRect");
+///         assert_eq!(fields.len(), 2);
+///     }
+///     _ => panic!("expected struct"),
+/// }
+/// assert_eq!(encodings[4], Encoding::Array(4, &Encoding::UChar));
+/// assert_eq!(encodings[5], EncodingBox::String);
+///
+/// // Pointers to structs may leave out the fields, and are re-serialized
+/// // with an empty field list.
+/// let rect = &encodings[3];
+/// assert_eq!(rect.to_string(), "^{CGRect=}");
+/// assert!(Encoding::Pointer(&Encoding::Struct("CGRect", &[])).equivalent_to_box(rect));
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive] // Maybe we're missing some encodings?
 pub enum EncodingBox {
@@ -105,6 +142,12 @@ impl EncodingBox {
         _ => unreachable!(),
     };
 
+    /// The default limit on how deeply encodings may be nested when parsing.
+    ///
+    /// This is much deeper than any encoding emitted by a compiler, while
+    /// still leaving plenty of room on the stack for the recursive parser.
+    pub const DEFAULT_MAX_DEPTH: usize = 64;
+
     /// Parse and comsume an encoding from the start of a string.
     ///
     /// This is can be used to parse concatenated encodings, such as those
@@ -115,9 +158,31 @@ impl EncodingBox {
     ///
     /// # Errors
     ///
-    /// Returns an error if the string was an ill-formatted encoding string.
+    /// Returns an error if the string was an ill-formatted encoding string,
+    /// or if it was nested deeper than [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// [`DEFAULT_MAX_DEPTH`]: Self::DEFAULT_MAX_DEPTH
     pub fn from_start_of_str(s: &mut &str) -> Result<Self, ParseError> {
-        let mut parser = Parser::new(s);
+        Self::from_start_of_str_with_max_depth(s, Self::DEFAULT_MAX_DEPTH)
+    }
+
+    /// Parse and comsume an encoding from the start of a string, with a
+    /// custom limit on the nesting depth.
+    ///
+    /// A primitive such as `i` has a depth of 1, and each pointer, array,
+    /// struct or union around it adds one to that (so `{s=^i}` has a depth
+    /// of 3).
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string was an ill-formatted encoding string,
+    /// or if it was nested deeper than `max_depth`.
+    pub fn from_start_of_str_with_max_depth(
+        s: &mut &str,
+        max_depth: usize,
+    ) -> Result<Self, ParseError> {
+        let mut parser = Parser::with_max_depth(s, max_depth);
         parser.strip_leading_qualifiers();
 
         match parser.parse_encoding() {
@@ -130,6 +195,29 @@ impl EncodingBox {
             }
         }
     }
+
+    /// Parse an encoding, with a custom limit on the nesting depth.
+    ///
+    /// The [`FromStr`] implementation uses [`DEFAULT_MAX_DEPTH`]. See
+    /// [`from_start_of_str_with_max_depth`] for how the depth is counted.
+    ///
+    /// [`DEFAULT_MAX_DEPTH`]: Self::DEFAULT_MAX_DEPTH
+    /// [`from_start_of_str_with_max_depth`]: Self::from_start_of_str_with_max_depth
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string was an ill-formatted encoding string,
+    /// or if it was nested deeper than `max_depth`.
+    pub fn from_str_with_max_depth(s: &str, max_depth: usize) -> Result<Self, ParseError> {
+        let mut parser = Parser::with_max_depth(s, max_depth);
+        parser.strip_leading_qualifiers();
+
+        parser
+            .parse_encoding()
+            .and_then(|enc| parser.expect_empty().map(|()| enc))
+            .map_err(|err| ParseError::new(parser, err))
+    }
 }
 
 /// Same formatting as [`Encoding`]'s `Display` implementation.
@@ -155,13 +243,7 @@ impl FromStr for EncodingBox {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parser = Parser::new(s);
-        parser.strip_leading_qualifiers();
-
-        parser
-            .parse_encoding()
-            .and_then(|enc| parser.expect_empty().map(|()| enc))
-            .map_err(|err| ParseError::new(parser, err))
+        Self::from_str_with_max_depth(s, Self::DEFAULT_MAX_DEPTH)
     }
 }
 
@@ -254,4 +336,25 @@ mod tests {
 
         assert_eq!(s, "");
     }
+
+    #[test]
+    fn parse_max_depth() {
+        assert!(EncodingBox::from_str_with_max_depth("^{a=i}", 3).is_ok());
+        let err = EncodingBox::from_str_with_max_depth("^{a=i}", 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed parsing encoding: encoding nested too deeply at byte-index 4 in \"^{a=i}\""
+        );
+
+        let mut s = "^^ii";
+        assert!(EncodingBox::from_start_of_str_with_max_depth(&mut s, 2).is_err());
+        assert_eq!(s, "^^ii");
+        let enc = EncodingBox::from_start_of_str_with_max_depth(&mut s, 3).unwrap();
+        assert_eq!(enc.to_string(), "^^i");
+        assert_eq!(s, "i");
+
+        let s = "^".repeat(1000) + "i";
+        assert!(EncodingBox::from_str(&s).is_err());
+        assert!(!Encoding::Int.equivalent_to_str(&s));
+    }
 }
@@ -0,0 +1,333 @@
+//! Computing the C layout of a type from its encoding.
+use core::ffi::c_void;
+use core::mem;
+
+use crate::Encoding;
+
+/// `?` for use in `const fn`.
+macro_rules! tri {
+    ($e:expr) => {
+        match $e {
+            Some(val) => val,
+            None => return None,
+        }
+    };
+}
+
+/// The size and alignment of a C type, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Layout {
+    pub(crate) size: usize,
+    pub(crate) align: usize,
+}
+
+impl Layout {
+    const fn of<T>() -> Self {
+        Self {
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+        }
+    }
+
+    pub(crate) const fn new(encoding: &Encoding) -> Option<Self> {
+        match encoding {
+            Encoding::Char | Encoding::UChar | Encoding::Bool => Some(Self::of::<u8>()),
+            Encoding::Short | Encoding::UShort => Some(Self::of::<u16>()),
+            // `long` is encoded as `q` on 64-bit platforms, so `l` is always
+            // 32-bit.
+            Encoding::Int | Encoding::UInt | Encoding::Long | Encoding::ULong => {
+                Some(Self::of::<u32>())
+            }
+            Encoding::LongLong | Encoding::ULongLong => Some(Self::of::<u64>()),
+            Encoding::Float => Some(Self::of::<f32>()),
+            Encoding::Double => Some(Self::of::<f64>()),
+            Encoding::FloatComplex => Some(Self::of::<[f32; 2]>()),
+            Encoding::DoubleComplex => Some(Self::of::<[f64; 2]>()),
+            Encoding::String
+            | Encoding::Object
+            | Encoding::Block
+            | Encoding::Class
+            | Encoding::Sel
+            | Encoding::Pointer(_) => Some(Self::of::<*const c_void>()),
+            Encoding::Atomic(t) => Self::new(t),
+            Encoding::Array(len, t) => {
+                let item = tri!(Self::new(t));
+                if *len > usize::MAX as u64 {
+                    return None;
+                }
+                Some(Self {
+                    size: tri!(item.size.checked_mul(*len as usize)),
+                    align: item.align,
+                })
+            }
+            Encoding::Struct(_, fields) => Self::new_struct(fields),
+            Encoding::Union(_, fields) => Self::new_union(fields),
+            // The layout of `long double` depends on the compiler, and the
+            // rest cannot be stored on their own.
+            Encoding::LongDouble
+            | Encoding::LongDoubleComplex
+            | Encoding::Void
+            | Encoding::Unknown
+            | Encoding::BitField(_, _) => None,
+        }
+    }
+
+    /// The storage unit that a bitfield is allocated in.
+    const fn bitfield_unit(bits: u8, ty: Option<&(u64, Encoding)>) -> Option<Self> {
+        let unit = match ty {
+            Some((_, ty)) => tri!(Self::new(ty)),
+            // Apple's runtime does not include the type of bitfields, so we
+            // assume that it is `int`, or `long long` if it doesn't fit.
+            None if bits <= 32 => Self::of::<u32>(),
+            None => Self::of::<u64>(),
+        };
+        if bits as usize > unit.size * 8 {
+            return None;
+        }
+        Some(unit)
+    }
+
+    const fn new_struct(fields: &[Encoding]) -> Option<Self> {
+        // Structs without fields are usually opaque.
+        if fields.is_empty() {
+            return None;
+        }
+
+        // Bitfields are not byte-aligned, so keep track of the offset in
+        // bits.
+        let mut offset_bits: usize = 0;
+        let mut align = 1;
+        let mut i = 0;
+        while i < fields.len() {
+            match &fields[i] {
+                Encoding::BitField(bits, ty) => {
+                    let unit = tri!(Self::bitfield_unit(*bits, *ty));
+                    let bits = *bits as usize;
+                    let unit_bits = unit.size * 8;
+                    if bits == 0 {
+                        // Zero-width bitfields start a new storage unit.
+                        offset_bits = tri!(round_up(offset_bits, unit.align * 8));
+                    } else {
+                        // Bitfields are not allowed to straddle the boundary
+                        // of their storage unit.
+                        if offset_bits / unit_bits
+                            != (tri!(offset_bits.checked_add(bits)) - 1) / unit_bits
+                        {
+                            offset_bits = tri!(round_up(offset_bits, unit_bits));
+                        }
+                        offset_bits = tri!(offset_bits.checked_add(bits));
+                        align = max(align, unit.align);
+                    }
+                }
+                field => {
+                    let field = tri!(Self::new(field));
+                    let offset = tri!(round_up(tri!(round_up(offset_bits, 8)) / 8, field.align));
+                    let end = tri!(offset.checked_add(field.size));
+                    offset_bits = tri!(end.checked_mul(8));
+                    align = max(align, field.align);
+                }
+            }
+            i += 1;
+        }
+
+        let size = tri!(round_up(tri!(round_up(offset_bits, 8)) / 8, align));
+        Some(Self { size, align })
+    }
+
+    const fn new_union(fields: &[Encoding]) -> Option<Self> {
+        if fields.is_empty() {
+            return None;
+        }
+
+        let mut size = 0;
+        let mut align = 1;
+        let mut i = 0;
+        while i < fields.len() {
+            let field = match &fields[i] {
+                Encoding::BitField(bits, ty) => tri!(Self::bitfield_unit(*bits, *ty)),
+                field => tri!(Self::new(field)),
+            };
+            size = max(size, field.size);
+            align = max(align, field.align);
+            i += 1;
+        }
+
+        let size = tri!(round_up(size, align));
+        Some(Self { size, align })
+    }
+}
+
+const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+const fn round_up(n: usize, align: usize) -> Option<usize> {
+    match n.checked_add(align - 1) {
+        Some(n) => Some(n / align * align),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn assert_layout(encoding: &Encoding, size: usize, align: usize) {
+        assert_eq!(Layout::new(encoding), Some(Layout { size, align }));
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert_layout(&Encoding::Char, 1, 1);
+        assert_layout(&Encoding::Short, 2, 2);
+        assert_layout(&Encoding::Int, 4, 4);
+        assert_layout(&Encoding::Long, 4, 4);
+        assert_layout(&Encoding::Double, 8, mem::align_of::<f64>());
+        assert_layout(
+            &Encoding::Object,
+            mem::size_of::<usize>(),
+            mem::align_of::<usize>(),
+        );
+        assert_layout(
+            &Encoding::Pointer(&Encoding::Void),
+            mem::size_of::<usize>(),
+            mem::align_of::<usize>(),
+        );
+        assert_eq!(Layout::new(&Encoding::Void), None);
+        assert_eq!(Layout::new(&Encoding::Unknown), None);
+        assert_eq!(Layout::new(&Encoding::LongDouble), None);
+    }
+
+    #[test]
+    fn test_containers() {
+        #[repr(C)]
+        struct A {
+            a: u8,
+            b: f64,
+            c: u16,
+        }
+        const ENC_A: Encoding =
+            Encoding::Struct("A", &[Encoding::UChar, Encoding::Double, Encoding::UShort]);
+        assert_layout(&ENC_A, mem::size_of::<A>(), mem::align_of::<A>());
+
+        assert_layout(
+            &Encoding::Array(3, &ENC_A),
+            3 * mem::size_of::<A>(),
+            mem::align_of::<A>(),
+        );
+        assert_layout(&Encoding::Array(0, &ENC_A), 0, mem::align_of::<A>());
+
+        #[repr(C)]
+        struct B {
+            a: [u8; 3],
+            b: A,
+        }
+        const ENC_B: Encoding =
+            Encoding::Struct("B", &[Encoding::Array(3, &Encoding::Char), ENC_A]);
+        assert_layout(&ENC_B, mem::size_of::<B>(), mem::align_of::<B>());
+
+        let u = Encoding::Union(
+            "U",
+            &[
+                Encoding::Char,
+                Encoding::Int,
+                Encoding::Array(5, &Encoding::Char),
+            ],
+        );
+        assert_layout(&u, 8, 4);
+
+        assert_eq!(Layout::new(&Encoding::Struct("Opaque", &[])), None);
+        assert_eq!(Layout::new(&Encoding::Union("Opaque", &[])), None);
+        assert_eq!(
+            Layout::new(&Encoding::Struct("S", &[Encoding::Int, Encoding::Void])),
+            None
+        );
+        assert_eq!(
+            Layout::new(&Encoding::Array(u64::MAX, &Encoding::Int)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bitfields() {
+        // struct { unsigned a : 3; }
+        assert_layout(&Encoding::Struct("S", &[Encoding::BitField(3, None)]), 4, 4);
+        // struct { char a; unsigned b : 3; unsigned c : 7; }
+        assert_layout(
+            &Encoding::Struct(
+                "S",
+                &[
+                    Encoding::Char,
+                    Encoding::BitField(3, None),
+                    Encoding::BitField(7, None),
+                ],
+            ),
+            4,
+            4,
+        );
+        // struct { unsigned a : 30; unsigned b : 4; }
+        assert_layout(
+            &Encoding::Struct(
+                "S",
+                &[Encoding::BitField(30, None), Encoding::BitField(4, None)],
+            ),
+            8,
+            4,
+        );
+        // struct { unsigned a : 4; unsigned : 0; unsigned b : 4; }
+        assert_layout(
+            &Encoding::Struct(
+                "S",
+                &[
+                    Encoding::BitField(4, None),
+                    Encoding::BitField(0, None),
+                    Encoding::BitField(4, None),
+                ],
+            ),
+            8,
+            4,
+        );
+        // struct { unsigned long long a : 40; }
+        assert_layout(
+            &Encoding::Struct("S", &[Encoding::BitField(40, None)]),
+            8,
+            mem::align_of::<u64>(),
+        );
+
+        // GNUStep includes the type.
+        // struct { char a; char b : 3; char c : 7; }
+        assert_layout(
+            &Encoding::Struct(
+                "S",
+                &[
+                    Encoding::Char,
+                    Encoding::BitField(3, Some(&(8, Encoding::Char))),
+                    Encoding::BitField(7, Some(&(16, Encoding::Char))),
+                ],
+            ),
+            3,
+            1,
+        );
+        assert_eq!(
+            Layout::new(&Encoding::Struct(
+                "S",
+                &[Encoding::BitField(9, Some(&(0, Encoding::Char)))]
+            )),
+            None
+        );
+
+        // union { unsigned a : 3; char b; }
+        assert_layout(
+            &Encoding::Union("U", &[Encoding::BitField(3, None), Encoding::Char]),
+            4,
+            4,
+        );
+
+        assert_eq!(Layout::new(&Encoding::BitField(3, None)), None);
+    }
+}
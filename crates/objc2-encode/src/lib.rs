@@ -58,12 +58,12 @@ extern crate alloc;
 mod encoding;
 mod encoding_box;
 mod helper;
+mod layout;
 mod parse;
-
-// Will be used at some point when generic constants are available
-#[allow(dead_code)]
 mod static_str;
 
 pub use self::encoding::Encoding;
 pub use self::encoding_box::EncodingBox;
-pub use self::parse::ParseError;
+pub use self::parse::{MethodSignature, ParseError};
+#[doc(hidden)]
+pub use self::static_str::{__bytes_eq, __encoding_str_array, __encoding_str_len};
@@ -4,11 +4,16 @@ use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
+use core::str::FromStr;
 
 use crate::helper::{ContainerKind, EncodingType, Helper, NestingLevel, Primitive};
 use crate::{Encoding, EncodingBox};
 
-/// Check whether a struct or union name is a valid identifier
+/// Check whether a struct or union name is a valid identifier.
+///
+/// Besides C identifiers, this allows the qualified and templated names that
+/// Objective-C++ compilers emit for C++ classes, e.g. `ns::Foo` or
+/// `vector<int, std::allocator<int> >`.
 pub(crate) const fn verify_name(name: &str) -> bool {
     let bytes = name.as_bytes();
 
@@ -23,7 +28,9 @@ pub(crate) const fn verify_name(name: &str) -> bool {
     let mut i = 0;
     while i < bytes.len() {
         let byte = bytes[i];
-        if !(byte.is_ascii_alphanumeric() || byte == b'_') {
+        if !(byte.is_ascii_alphanumeric()
+            || matches!(byte, b'_' | b':' | b'<' | b'>' | b',' | b' '))
+        {
             return false;
         }
         i += 1;
@@ -73,17 +80,25 @@ pub(crate) enum ErrorKind {
     WrongEndContainer(ContainerKind),
     InvalidIdentifier(ContainerKind),
     NotAllConsumed,
+    TooDeep,
+    OffsetTooLarge,
 }
 
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnexpectedEnd => write!(f, "unexpected end"),
+            // The byte may be a control character or part of a multi-byte
+            // character, so escape it.
             Self::Unknown(b) => {
-                write!(f, "unknown encoding character {}", *b as char)
+                write!(f, "unknown encoding character {}", b.escape_ascii())
             }
             Self::UnknownAfterComplex(b) => {
-                write!(f, "unknown encoding character {} after complex", *b as char,)
+                write!(
+                    f,
+                    "unknown encoding character {} after complex",
+                    b.escape_ascii()
+                )
             }
             Self::ExpectedInteger => write!(f, "expected integer"),
             Self::IntegerTooLarge => write!(f, "integer too large"),
@@ -97,7 +112,102 @@ impl fmt::Display for ErrorKind {
             Self::NotAllConsumed => {
                 write!(f, "remaining contents after parsing")
             }
+            Self::TooDeep => write!(f, "encoding nested too deeply"),
+            Self::OffsetTooLarge => write!(f, "stack offset too large"),
+        }
+    }
+}
+
+/// A parsed method type encoding, such as `"v24@0:8@16"`.
+///
+/// This splits the type encoding of a method (as returned by e.g.
+/// `method_getTypeEncoding`) or of a block into the encoding of the return
+/// type and of each argument, along with the stack frame size and the
+/// offset of each argument in the stack frame, similar to
+/// `NSMethodSignature`.
+///
+/// For methods, the first two arguments are the receiver (`@`) and the
+/// selector (`:`), while for blocks, the first argument is the block itself
+/// (`@?`).
+///
+/// The offsets are usually present in encodings emitted by the compiler,
+/// but may be missing in encodings that were written by hand. They are
+/// parsed as signed integers, since some older compilers emitted negative
+/// offsets.
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_encode::{Encoding, EncodingBox, MethodSignature};
+///
+/// let sig: MethodSignature = "v24@0:8@\"NSString\"16".parse().unwrap();
+/// assert_eq!(*sig.return_type(), EncodingBox::Void);
+/// assert_eq!(sig.frame_size(), Some(24));
+///
+/// let args = sig.arguments();
+/// assert_eq!(args.len(), 3);
+/// assert_eq!(args[0], (EncodingBox::Object, Some(0)));
+/// assert_eq!(args[1], (EncodingBox::Sel, Some(8)));
+/// assert_eq!(args[2], (EncodingBox::Object, Some(16)));
+///
+/// // Class names are not stored.
+/// assert_eq!(sig.to_string(), "v24@0:8@16");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MethodSignature {
+    return_type: EncodingBox,
+    frame_size: Option<isize>,
+    arguments: Vec<(EncodingBox, Option<isize>)>,
+}
+
+impl MethodSignature {
+    /// The encoding of the return type.
+    pub fn return_type(&self) -> &EncodingBox {
+        &self.return_type
+    }
+
+    /// The size of the stack frame, i.e. the number after the return type.
+    #[doc(alias = "frameLength")]
+    pub fn frame_size(&self) -> Option<isize> {
+        self.frame_size
+    }
+
+    /// The encoding and stack offset of each argument, including the
+    /// implicit receiver and selector arguments.
+    pub fn arguments(&self) -> &[(EncodingBox, Option<isize>)] {
+        &self.arguments
+    }
+}
+
+impl FromStr for MethodSignature {
+    type Err = ParseError;
+
+    /// Parse a method or block type encoding.
+    ///
+    /// Qualifiers such as `r` (`const`) in front of the return type and the
+    /// arguments are ignored.
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s);
+        parser
+            .parse_method_signature()
+            .map_err(|err| ParseError::new(parser, err))
+    }
+}
+
+/// Same format as the output of `method_getTypeEncoding`, without the
+/// qualifiers.
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = Some((&self.return_type, self.frame_size));
+        let args = self.arguments.iter().map(|(enc, offset)| (enc, *offset));
+        for (enc, offset) in parts.into_iter().chain(args) {
+            write!(f, "{enc}")?;
+            if let Some(offset) = offset {
+                write!(f, "{offset}")?;
+            }
         }
+        Ok(())
     }
 }
 
@@ -108,13 +218,20 @@ pub(crate) struct Parser<'a> {
     data: &'a str,
     // Always "behind"/"at" the current character
     split_point: usize,
+    // How many more levels of nesting we're allowed to parse
+    remaining_depth: usize,
 }
 
 impl<'a> Parser<'a> {
     pub(crate) fn new(data: &'a str) -> Self {
+        Self::with_max_depth(data, EncodingBox::DEFAULT_MAX_DEPTH)
+    }
+
+    pub(crate) fn with_max_depth(data: &'a str, max_depth: usize) -> Self {
         Self {
             split_point: 0,
             data,
+            remaining_depth: max_depth,
         }
     }
 
@@ -211,6 +328,26 @@ impl Parser<'_> {
             .parse()
             .map_err(|_| ErrorKind::IntegerTooLarge)
     }
+
+    /// Parse the stack offset after an encoding in a method signature, if
+    /// present.
+    fn parse_offset(&mut self) -> Result<Option<isize>> {
+        let old_split_point = self.split_point;
+        if let Some(b'+' | b'-') = self.try_peek() {
+            self.advance();
+        }
+        if !self.try_peek().map_or(false, |b| b.is_ascii_digit()) {
+            // Not an offset, so don't consume the sign either.
+            self.split_point = old_split_point;
+            return Ok(None);
+        }
+        self.consume_while(|b| b.is_ascii_digit());
+
+        self.data[old_split_point..self.split_point]
+            .parse()
+            .map(Some)
+            .map_err(|_| ErrorKind::OffsetTooLarge)
+    }
 }
 
 /// Check if the data matches an expected value.
@@ -349,6 +486,26 @@ impl Parser<'_> {
         Ok((s, items))
     }
 
+    fn parse_method_signature(&mut self) -> Result<MethodSignature> {
+        self.strip_leading_qualifiers();
+        let return_type = self.parse_encoding()?;
+        let frame_size = self.parse_offset()?;
+
+        let mut arguments = Vec::new();
+        while !self.is_empty() {
+            self.strip_leading_qualifiers();
+            let encoding = self.parse_encoding()?;
+            let offset = self.parse_offset()?;
+            arguments.push((encoding, offset));
+        }
+
+        Ok(MethodSignature {
+            return_type,
+            frame_size,
+            arguments,
+        })
+    }
+
     pub(crate) fn parse_encoding(&mut self) -> Result<EncodingBox> {
         self.try_parse_encoding()
             .and_then(|res| res.ok_or(ErrorKind::UnexpectedEnd))
@@ -356,8 +513,16 @@ impl Parser<'_> {
 
     fn try_parse_encoding(&mut self) -> Result<Option<EncodingBox>> {
         Ok(if let Some(b) = self.try_peek() {
+            // Parsing is recursive, so limit the nesting to avoid
+            // overflowing the stack on hostile input.
+            self.remaining_depth = self
+                .remaining_depth
+                .checked_sub(1)
+                .ok_or(ErrorKind::TooDeep)?;
             self.advance();
-            Some(self.parse_encoding_inner(b)?)
+            let res = self.parse_encoding_inner(b);
+            self.remaining_depth += 1;
+            Some(res?)
         } else {
             None
         })
@@ -501,6 +666,11 @@ mod tests {
         );
         assert_name("_=}.a'", Ok(("_", vec![])));
         assert_name("abc}def", Ok(("abc", vec![])));
+        assert_name("ns::Foo=}", Ok(("ns::Foo", vec![])));
+        assert_name(
+            "vector<int, std::allocator<int> >=}",
+            Ok(("vector<int, std::allocator<int> >", vec![])),
+        );
         assert_name("=def}", Err(ErrorKind::InvalidIdentifier(KIND)));
         assert_name(".=def}", Err(ErrorKind::InvalidIdentifier(KIND)));
         assert_name("}xyz", Err(ErrorKind::InvalidIdentifier(KIND)));
@@ -550,4 +720,183 @@ mod tests {
         );
         assert_bitfield("b2000C257", Err(ErrorKind::IntegerTooLarge));
     }
+
+    #[test]
+    fn parse_large_integers() {
+        let mut parser = Parser::new("[18446744073709551615i]");
+        assert_eq!(
+            parser.parse_encoding(),
+            Ok(EncodingBox::Array(u64::MAX, Box::new(EncodingBox::Int)))
+        );
+
+        let mut parser = Parser::new("[18446744073709551616i]");
+        assert_eq!(parser.parse_encoding(), Err(ErrorKind::IntegerTooLarge));
+
+        let mut parser = Parser::new("[99999999999999999999999999999999i]");
+        assert_eq!(parser.parse_encoding(), Err(ErrorKind::IntegerTooLarge));
+    }
+
+    #[test]
+    fn parse_max_depth() {
+        #[track_caller]
+        fn assert_depth(enc: &str, max_depth: usize, expected: Result<()>) {
+            let mut parser = Parser::with_max_depth(enc, max_depth);
+            let res = parser.parse_encoding().and_then(|_| parser.expect_empty());
+            assert_eq!(res, expected);
+        }
+
+        assert_depth("i", 1, Ok(()));
+        assert_depth("i", 0, Err(ErrorKind::TooDeep));
+        assert_depth("^i", 1, Err(ErrorKind::TooDeep));
+        assert_depth("^^i", 3, Ok(()));
+        assert_depth("{a=ii}", 2, Ok(()));
+        assert_depth("{a=i{b=i}}", 2, Err(ErrorKind::TooDeep));
+        assert_depth("[4{a=^i}]", 4, Ok(()));
+        assert_depth("[4{a=^i}]", 3, Err(ErrorKind::TooDeep));
+        assert_depth("b8C4", 1, Ok(()));
+
+        let max = EncodingBox::DEFAULT_MAX_DEPTH;
+        let ok = alloc::format!("{}i", "^".repeat(max - 1));
+        assert_eq!(Parser::new(&ok).parse_encoding().map(|_| ()), Ok(()));
+        let too_deep = alloc::format!("{}i", "^".repeat(max));
+        assert_eq!(
+            Parser::new(&too_deep).parse_encoding(),
+            Err(ErrorKind::TooDeep)
+        );
+
+        // Would overflow the stack without the limit.
+        let hostile = alloc::format!("{}i{}", "{a=".repeat(100_000), "}".repeat(100_000));
+        assert_eq!(
+            Parser::new(&hostile).parse_encoding(),
+            Err(ErrorKind::TooDeep)
+        );
+    }
+
+    #[test]
+    fn parse_method_signature() {
+        #[track_caller]
+        fn assert_sig(
+            s: &str,
+            ret: EncodingBox,
+            frame_size: Option<isize>,
+            args: &[(EncodingBox, Option<isize>)],
+        ) {
+            let sig = Parser::new(s).parse_method_signature().unwrap();
+            assert_eq!(sig.return_type, ret);
+            assert_eq!(sig.frame_size, frame_size);
+            assert_eq!(sig.arguments, args);
+        }
+
+        assert_sig("v", EncodingBox::Void, None, &[]);
+        assert_sig(
+            "v24@0:8@16",
+            EncodingBox::Void,
+            Some(24),
+            &[
+                (EncodingBox::Object, Some(0)),
+                (EncodingBox::Sel, Some(8)),
+                (EncodingBox::Object, Some(16)),
+            ],
+        );
+        assert_sig(
+            "{CGPoint=dd}16@0:8",
+            EncodingBox::Struct("CGPoint".into(), vec![EncodingBox::Double; 2]),
+            Some(16),
+            &[(EncodingBox::Object, Some(0)), (EncodingBox::Sel, Some(8))],
+        );
+        // Blocks, and qualifiers.
+        assert_sig(
+            "Vv16@?0r^{__CFString=}8",
+            EncodingBox::Void,
+            Some(16),
+            &[
+                (EncodingBox::Block, Some(0)),
+                (
+                    EncodingBox::Pointer(Box::new(EncodingBox::Struct(
+                        "__CFString".into(),
+                        vec![],
+                    ))),
+                    Some(8),
+                ),
+            ],
+        );
+        // Missing and signed offsets.
+        assert_sig(
+            "v@:i-4",
+            EncodingBox::Void,
+            None,
+            &[
+                (EncodingBox::Object, None),
+                (EncodingBox::Sel, None),
+                (EncodingBox::Int, Some(-4)),
+            ],
+        );
+        assert_sig(
+            "c12@+8:4",
+            EncodingBox::Char,
+            Some(12),
+            &[(EncodingBox::Object, Some(8)), (EncodingBox::Sel, Some(4))],
+        );
+        // Offsets are not mistaken for bitfield sizes or array lengths.
+        assert_sig(
+            "[2i]8b3",
+            EncodingBox::Array(2, Box::new(EncodingBox::Int)),
+            Some(8),
+            &[(EncodingBox::BitField(3, None), None)],
+        );
+    }
+
+    #[test]
+    fn parse_method_signature_errors() {
+        #[track_caller]
+        fn assert_err(s: &str, expected: ErrorKind) {
+            let res = Parser::new(s).parse_method_signature();
+            assert_eq!(res, Err(expected));
+        }
+
+        assert_err("", ErrorKind::UnexpectedEnd);
+        assert_err("v24@0:8+", ErrorKind::Unknown(b'+'));
+        assert_err(
+            "v24@0:8{",
+            ErrorKind::WrongEndContainer(ContainerKind::Struct),
+        );
+        assert_err("v99999999999999999999999", ErrorKind::OffsetTooLarge);
+
+        let err = MethodSignature::from_str("v24@0:8x16").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed parsing encoding: unknown encoding character x at byte-index 8 in \"v24@0:8x16\""
+        );
+    }
+
+    #[test]
+    fn method_signature_roundtrip() {
+        for s in [
+            "v",
+            "v24@0:8@16",
+            "v@:i-4",
+            "{CGRect={CGPoint=dd}{CGSize=dd}}16@0:8",
+            "B32@?0@8^B16q24",
+        ] {
+            let sig = MethodSignature::from_str(s).unwrap();
+            assert_eq!(sig.to_string(), s);
+        }
+        let sig = MethodSignature::from_str("rv12@+8:4").unwrap();
+        assert_eq!(sig.to_string(), "v12@8:4");
+    }
+
+    #[test]
+    fn error_escapes_unknown() {
+        let mut parser = Parser::new("\u{e9}");
+        let err = parser.parse_encoding().unwrap_err();
+        assert_eq!(err, ErrorKind::Unknown(0xc3));
+        assert_eq!(err.to_string(), "unknown encoding character \\xc3");
+
+        let mut parser = Parser::new("j\n");
+        let err = parser.parse_encoding().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown encoding character \\n after complex"
+        );
+    }
 }
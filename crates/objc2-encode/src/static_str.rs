@@ -212,6 +212,39 @@ pub(crate) const fn static_encoding_str_array<const LEN: usize>(
     res
 }
 
+/// The length of the string representation of the encoding.
+///
+/// Used by `objc2::assert_encoding!`.
+#[doc(hidden)]
+pub const fn __encoding_str_len(encoding: &Encoding) -> usize {
+    static_encoding_str_len(encoding, NestingLevel::new())
+}
+
+/// The string representation of the encoding, `LEN` must be the value
+/// returned by [`__encoding_str_len`].
+///
+/// Used by `objc2::assert_encoding!`.
+#[doc(hidden)]
+pub const fn __encoding_str_array<const LEN: usize>(encoding: &Encoding) -> [u8; LEN] {
+    static_encoding_str_array(encoding, NestingLevel::new())
+}
+
+/// `a == b`, usable in `const`.
+#[doc(hidden)]
+pub const fn __bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
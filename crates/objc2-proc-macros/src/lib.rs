@@ -15,6 +15,8 @@ extern "C" {}
 
 use core::hash::{Hash, Hasher};
 
+use proc_macro::Delimiter;
+use proc_macro::Group;
 use proc_macro::Ident;
 use proc_macro::Literal;
 use proc_macro::TokenStream;
@@ -68,3 +70,113 @@ pub fn __hash_idents(input: TokenStream) -> TokenStream {
     let s = format!("{:016x}", hasher.finish());
     TokenTree::Literal(Literal::string(&s)).into()
 }
+
+/// Create a `compile_error!` invocation with the given message.
+fn compile_error(msg: &str) -> TokenStream {
+    format!("::core::compile_error!({msg:?});").parse().unwrap()
+}
+
+/// Parse the optional `run_loop_ms = 123` argument to `main_thread_test`.
+fn parse_run_loop_ms(attr: TokenStream) -> Result<Option<Literal>, &'static str> {
+    let mut iter = attr.into_iter();
+    match iter.next() {
+        None => return Ok(None),
+        Some(TokenTree::Ident(name)) if name.to_string() == "run_loop_ms" => {}
+        Some(_) => return Err("expected `run_loop_ms = ...`"),
+    }
+    match iter.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {}
+        _ => return Err("expected `=` after `run_loop_ms`"),
+    }
+    let lit = match iter.next() {
+        Some(TokenTree::Literal(lit)) => lit,
+        _ => return Err("expected an integer literal"),
+    };
+    if iter.next().is_some() {
+        return Err("unexpected tokens after `run_loop_ms`");
+    }
+    Ok(Some(lit))
+}
+
+/// Run a test on the main thread.
+///
+/// Re-exported and documented in `objc2_foundation::test_utils`.
+#[proc_macro_attribute]
+pub fn main_thread_test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let run_loop_ms = match parse_run_loop_ms(attr) {
+        Ok(run_loop_ms) => run_loop_ms,
+        Err(msg) => return compile_error(msg),
+    };
+
+    // Split `#[attrs] fn name(params) { body }` into its parts.
+    let mut prefix = Vec::new();
+    let mut iter = item.into_iter();
+    for token in iter.by_ref() {
+        match &token {
+            TokenTree::Ident(ident) if ident.to_string() == "fn" => {
+                prefix.push(token);
+                break;
+            }
+            _ => prefix.push(token),
+        }
+    }
+    let name = match iter.next() {
+        Some(TokenTree::Ident(name)) => name,
+        _ => return compile_error("`#[main_thread_test]` must be applied to a function"),
+    };
+    let params = match iter.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => group,
+        _ => return compile_error("`#[main_thread_test]` functions cannot be generic"),
+    };
+    let body = match (iter.next(), iter.next()) {
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Brace => group,
+        _ => return compile_error("`#[main_thread_test]` functions must not return a value"),
+    };
+
+    let run_loop: TokenStream = match run_loop_ms {
+        Some(ms) => {
+            let mut duration: TokenStream = "::std::time::Duration::from_millis".parse().unwrap();
+            duration.extend([TokenTree::Group(Group::new(
+                Delimiter::Parenthesis,
+                TokenTree::Literal(ms).into(),
+            ))]);
+            let mut tokens: TokenStream = "::core::option::Option::Some".parse().unwrap();
+            tokens.extend([TokenTree::Group(Group::new(
+                Delimiter::Parenthesis,
+                duration,
+            ))]);
+            tokens
+        }
+        None => "::core::option::Option::None".parse().unwrap(),
+    };
+
+    // The closure takes a `MainThreadMarker`, which the test may bind to a
+    // name by declaring it as a parameter.
+    let closure_params = if params.stream().is_empty() {
+        "_: ::objc2_foundation::MainThreadMarker".parse().unwrap()
+    } else {
+        params.stream()
+    };
+
+    let mut args: TokenStream = run_loop;
+    args.extend(",move|".parse::<TokenStream>().unwrap());
+    args.extend(closure_params);
+    args.extend("|".parse::<TokenStream>().unwrap());
+    args.extend([TokenTree::Group(body)]);
+
+    let mut call: TokenStream = "::objc2_foundation::test_utils::__main_thread_test"
+        .parse()
+        .unwrap();
+    call.extend([TokenTree::Group(Group::new(Delimiter::Parenthesis, args))]);
+
+    // Only register as a test when using the default test harness, so that
+    // the function can be called manually when using `harness = false`.
+    let mut out: TokenStream = "#[cfg_attr(test, test)]".parse().unwrap();
+    out.extend(prefix);
+    out.extend([
+        TokenTree::Ident(name),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new())),
+        TokenTree::Group(Group::new(Delimiter::Brace, call)),
+    ]);
+    out
+}
@@ -0,0 +1,17 @@
+use std::env;
+
+fn main() {
+    // The script doesn't depend on our code
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let target = env::var("TARGET").unwrap();
+
+    // Used to detect Mac Catalyst in `Platform::TARGET`, same as in `objc-sys`
+    // Matches:
+    // aarch64-apple-ios-macabi
+    // x86_64-apple-ios-macabi
+    println!("cargo:rustc-check-cfg=cfg(target_abi_macabi)");
+    if target.ends_with("macabi") {
+        println!("cargo:rustc-cfg=target_abi_macabi");
+    }
+}
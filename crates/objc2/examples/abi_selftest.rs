@@ -0,0 +1,13 @@
+//! Check that message sending works on the current target.
+//!
+//! Run this when porting to a new target or runtime, with e.g.
+//! `cargo run --example abi_selftest --target <target>`.
+use std::process;
+
+fn main() {
+    if let Err(err) = objc2::abi_selftest() {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+    println!("ABI self-test passed");
+}
@@ -0,0 +1,137 @@
+//! Entry points for fuzzing the parsers in `objc2`, used by `test-fuzz`.
+//!
+//! These take arbitrary bytes, since the strings they parse may come from
+//! untrusted sources such as plugins. They panic if the parser breaks one of
+//! its invariants, but never just because the input was invalid.
+use alloc::string::ToString;
+use core::str::{self, FromStr};
+
+use crate::encode::{Encoding, EncodingBox};
+use crate::runtime::MethodEncodingIter;
+
+fn check_encoding(enc: &EncodingBox) {
+    // Check a "negative" case of `equivalent_to_box`.
+    if *enc != EncodingBox::Char {
+        assert_ne!(EncodingBox::Char, *enc, "not equal to char");
+        assert!(
+            !Encoding::Char.equivalent_to_box(enc),
+            "not equivalent to char"
+        );
+    }
+
+    // The output may differ from the input (e.g. class names of objects are
+    // not stored), but must be stable once it has been through the parser.
+    let s = enc.to_string();
+    let reparsed = EncodingBox::from_str(&s).expect("parsing valid encoding string");
+    assert_eq!(s, reparsed.to_string(), "roundtrip");
+}
+
+/// Parse the data as a single encoding, and as a sequence of encodings.
+pub fn encoding(data: &[u8]) {
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    if let Ok(enc) = EncodingBox::from_str(s) {
+        check_encoding(&enc);
+    }
+
+    let mut rest = s;
+    while let Ok(enc) = EncodingBox::from_start_of_str(&mut rest) {
+        check_encoding(&enc);
+        if rest.is_empty() {
+            break;
+        }
+    }
+}
+
+/// Parse the data as a method type encoding, such as `"v24@0:8@16"`.
+pub fn method_encoding(data: &[u8]) {
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    let mut iter = MethodEncodingIter::new(s);
+    let _ = iter.verify_receiver().map_err(|err| err.to_string());
+    let _ = iter.verify_sel().map_err(|err| err.to_string());
+    check_remaining(iter);
+}
+
+/// Parse the data as the signature of a block, such as `"v16@?0@8"`.
+pub fn block_signature(data: &[u8]) {
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    let mut iter = MethodEncodingIter::new(s);
+    match iter.extract_return() {
+        Ok((enc, _stack_layout)) => check_encoding(&enc),
+        Err(err) => {
+            let _ = err.to_string();
+            return;
+        }
+    }
+    if let Err(err) = iter.verify_block() {
+        let _ = err.to_string();
+        return;
+    }
+    check_remaining(iter);
+}
+
+fn check_remaining(iter: MethodEncodingIter<'_>) {
+    for res in iter {
+        match res {
+            Ok((enc, _stack_layout)) => check_encoding(&enc),
+            // The iterator doesn't advance past errors.
+            Err(err) => {
+                let _ = err.to_string();
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        encoding(b"{CGRect={CGPoint=dd}{CGSize=dd}}");
+        encoding(b"{NSDecimal=b8b4b1b1b18[8S]}");
+        encoding(b"r^{__CFString=}");
+        encoding(b"@\"NSString\"@?#:");
+        encoding(b"[18446744073709551615{?=qiIq}]");
+        encoding(b"[18446744073709551616i]");
+        encoding(b"b18446744073709551615i255");
+        encoding(b"{a=\xff}");
+        encoding(b"\xff");
+        encoding(b"");
+        encoding(&[b'^'; 100_000]);
+    }
+
+    #[test]
+    fn test_method_encoding() {
+        method_encoding(b"v24@0:8@16");
+        method_encoding(b"{CGRect={CGPoint=dd}{CGSize=dd}}16@0:8");
+        method_encoding(b"B32@0:8@16^@24");
+        method_encoding(b"v16@0:99999999999999999999999");
+        method_encoding(b"v16@0:8i");
+        method_encoding(b"v24@0:8{");
+        method_encoding(b"v");
+        method_encoding(&[b'['; 100_000]);
+    }
+
+    #[test]
+    fn test_block_signature() {
+        block_signature(b"v8@?0");
+        block_signature(b"v24@?0@\"NSData\"8@\"NSError\"16");
+        block_signature(b"B24@?0@8^B16");
+        block_signature(b"v16@0:8");
+        block_signature(b"v16@?0@8+");
+        block_signature(b"");
+    }
+}
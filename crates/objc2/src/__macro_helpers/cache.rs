@@ -107,7 +107,7 @@ impl CachedClass {
 mod tests {
     #[test]
     #[should_panic = "class NonExistantClass could not be found"]
-    #[cfg(not(feature = "unstable-static-class"))]
+    #[cfg(any(not(feature = "unstable-static-class"), not(feature = "apple")))]
     fn test_not_found() {
         let _ = crate::class!(NonExistantClass);
     }
@@ -7,6 +7,10 @@ mod argument_private {
     pub trait Sealed {}
 }
 
+/// Used by `ns_enum!` to implement [`ConvertArgument`] for the enum.
+#[doc(hidden)]
+pub use self::argument_private::Sealed as ConvertArgumentSealed;
+
 /// Represents types that can be converted to/from an [`EncodeArgument`] type.
 ///
 /// This is implemented specially for [`bool`] to allow using that as
@@ -75,10 +79,59 @@ impl ConvertArgument for bool {
     }
 }
 
+/// Pass an argument through unchanged.
+///
+/// Used by `msg_send!` so that an argument that cannot be sent produces a
+/// single error pointing at that argument, instead of at the entire tuple of
+/// arguments.
+#[inline]
+pub fn check_argument<T: CheckedArgument<U>, U>(argument: T) -> U {
+    argument.__checked()
+}
+
+/// Helper for [`check_argument`].
+///
+/// The second implementation never applies, it only exists so that if the
+/// argument does not implement [`ConvertArgument`], the return type of
+/// [`check_argument`] is ambiguous instead of being the argument type. The
+/// compiler then does not report the error again for the tuple of arguments.
+pub trait CheckedArgument<U> {
+    #[doc(hidden)]
+    fn __checked(self) -> U;
+}
+
+impl<T: ConvertArgument> CheckedArgument<T> for T {
+    #[inline]
+    fn __checked(self) -> T {
+        self
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct NeverArgument<T>(T);
+
+#[doc(hidden)]
+pub trait NeverImplemented {}
+
+impl<T> CheckedArgument<NeverArgument<T>> for T
+where
+    NeverArgument<T>: NeverImplemented,
+{
+    #[inline]
+    fn __checked(self) -> NeverArgument<T> {
+        NeverArgument(self)
+    }
+}
+
 mod return_private {
     pub trait Sealed {}
 }
 
+/// Used by `ns_enum!` to implement [`ConvertReturn`] for the enum.
+#[doc(hidden)]
+pub use self::return_private::Sealed as ConvertReturnSealed;
+
 /// Same as [`ConvertArgument`], but for return types.
 pub trait ConvertReturn: return_private::Sealed {
     /// The inner type that this can be converted to and from.
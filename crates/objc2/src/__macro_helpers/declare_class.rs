@@ -1,16 +1,21 @@
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+use alloc::string::ToString;
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
 use alloc::vec::Vec;
 use core::marker::PhantomData;
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
 use std::collections::HashSet;
 
 use crate::encode::{Encode, Encoding};
 use crate::rc::{Allocated, Id};
 use crate::runtime::{
-    AnyClass, AnyObject, ClassBuilder, MessageReceiver, MethodImplementation, Sel,
+    AnyClass, AnyObject, Bool, ClassBuilder, ClassRegistrationError, MessageReceiver,
+    MethodImplementation, RegistrationErrorKind, Sel,
 };
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
 use crate::runtime::{AnyProtocol, MethodDescription};
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+use crate::sel;
 use crate::{ClassType, DeclaredClass, Message, ProtocolType};
 
 use super::declared_ivars::{register_with_ivars, setup_dealloc};
@@ -123,7 +128,7 @@ impl<T: Message> MaybeOptionId for Id<T> {
 impl<T: Message> MaybeOptionId for Option<Id<T>> {
     #[inline]
     fn consumed_return(self) -> IdReturnValue {
-        let ptr: *mut T = Id::consume_as_ptr_option(self);
+        let ptr: *mut T = Id::option_into_raw(self);
         IdReturnValue(ptr.cast())
     }
 
@@ -134,6 +139,72 @@ impl<T: Message> MaybeOptionId for Option<Id<T>> {
     }
 }
 
+/// Helper trait for the `Result<T, Id<E>>` return type of `declare_class!`
+/// methods whose selector ends with `error:_`.
+pub trait DeclaredResult {
+    type Ok;
+    type Error: Message;
+
+    /// Convert the result to an `Option`, writing the error (if any) to the
+    /// error out-parameter.
+    ///
+    /// # Safety
+    ///
+    /// The out-parameter must be NULL, or valid for writes.
+    unsafe fn __into_option(self, err: *mut *mut Self::Error) -> Option<Self::Ok>;
+}
+
+impl<T, E: Message> DeclaredResult for Result<T, Id<E>> {
+    type Ok = T;
+    type Error = E;
+
+    #[inline]
+    unsafe fn __into_option(self, err: *mut *mut E) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(error) => {
+                if !err.is_null() {
+                    // Out-parameters are autoreleased, see
+                    // `src/__macro_helpers/writeback.rs`.
+                    //
+                    // SAFETY: Upheld by the caller.
+                    unsafe { *err = Id::autorelease_inner(error) };
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Convert the result of a `declare_class!` `#[method(...)]` with an error
+/// out-parameter to the `BOOL` that Objective-C expects.
+///
+/// # Safety
+///
+/// The out-parameter must be NULL, or valid for writes.
+#[inline]
+pub unsafe fn declared_result_to_bool<E: Message>(
+    res: Result<(), Id<E>>,
+    err: *mut *mut E,
+) -> Bool {
+    // SAFETY: Upheld by the caller.
+    Bool::new(unsafe { res.__into_option(err) }.is_some())
+}
+
+/// Run the body of a `declare_class!` method, turning panics into
+/// Objective-C exceptions, since they must not unwind into Objective-C.
+///
+/// # Safety
+///
+/// Must only be called from a method implementation that is called from
+/// Objective-C, and that is declared `extern "C-unwind"`.
+#[inline]
+#[cfg(feature = "unwind")]
+pub unsafe fn declared_method_body<R>(body: impl FnOnce() -> R) -> R {
+    // SAFETY: Upheld by the caller.
+    unsafe { crate::exception::panic_into_exception(body) }
+}
+
 /// Helper for ensuring that `ClassType::Mutability` is implemented correctly
 /// for subclasses.
 pub trait ValidSubclassMutability<T: mutability::Mutability> {}
@@ -203,48 +274,78 @@ where
 
 #[derive(Debug)]
 pub struct ClassBuilderHelper<T: ?Sized> {
-    builder: ClassBuilder,
+    // Once something fails, the error is stored here, and the rest of the
+    // class is skipped.
+    builder: Result<ClassBuilder, ClassRegistrationError>,
     p: PhantomData<T>,
 }
 
+/// Monomorphized error handling for `ClassType::class`.
 #[track_caller]
-fn failed_declaring_class(name: &str) -> ! {
-    panic!("could not create new class {name}. Perhaps a class with that name already exists?")
+pub fn class_registration_failed(err: ClassRegistrationError) -> ! {
+    panic!("{err}")
 }
 
 impl<T: DeclaredClass> ClassBuilderHelper<T> {
     #[inline]
-    #[track_caller]
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self
     where
         T::Super: ClassType,
     {
-        let mut builder = match ClassBuilder::new(T::NAME, <T::Super as ClassType>::class()) {
-            Some(builder) => builder,
-            None => failed_declaring_class(T::NAME),
+        let builder = match ClassBuilder::new(T::NAME, <T::Super as ClassType>::class()) {
+            Some(mut builder) => {
+                setup_dealloc::<T>(&mut builder);
+                Ok(builder)
+            }
+            None => Err(ClassRegistrationError::new(
+                T::NAME,
+                RegistrationErrorKind::ClassExists,
+            )),
         };
 
-        setup_dealloc::<T>(&mut builder);
-
         Self {
             builder,
             p: PhantomData,
         }
     }
 
+    /// Run an operation on the builder, unless a previous one failed.
+    fn try_with(
+        &mut self,
+        f: impl FnOnce(&mut ClassBuilder) -> Result<(), ClassRegistrationError>,
+    ) {
+        if let Ok(builder) = &mut self.builder {
+            if let Err(err) = f(builder) {
+                // Dropping the builder disposes of the class.
+                self.builder = Err(err);
+            }
+        }
+    }
+
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+    fn fail(&mut self, kind: RegistrationErrorKind) {
+        if self.builder.is_ok() {
+            self.builder = Err(ClassRegistrationError::new(T::NAME, kind));
+        }
+    }
+
     #[inline]
     pub fn add_protocol_methods<P>(&mut self) -> ClassProtocolMethodsBuilder<'_, T>
     where
         P: ?Sized + ProtocolType,
     {
+        #[cfg_attr(feature = "unstable-objfw", allow(unused_variables))]
         let protocol = P::protocol();
 
+        // ObjFW does not support adding protocols at runtime, and does not
+        // expose protocol method descriptions, so skip the verification.
+        #[cfg(not(feature = "unstable-objfw"))]
         if let Some(protocol) = protocol {
-            self.builder.add_protocol(protocol);
+            self.try_with(|builder| builder.try_add_protocol(protocol));
         }
 
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
         {
             ClassProtocolMethodsBuilder {
                 builder: self,
@@ -266,7 +367,7 @@ impl<T: DeclaredClass> ClassBuilderHelper<T> {
             }
         }
 
-        #[cfg(not(debug_assertions))]
+        #[cfg(any(not(debug_assertions), feature = "unstable-objfw"))]
         {
             ClassProtocolMethodsBuilder { builder: self }
         }
@@ -279,7 +380,7 @@ impl<T: DeclaredClass> ClassBuilderHelper<T> {
         F: MethodImplementation<Callee = T>,
     {
         // SAFETY: Checked by caller
-        unsafe { self.builder.add_method(sel, func) }
+        self.try_with(|builder| unsafe { builder.try_add_method(sel, func) });
     }
 
     #[inline]
@@ -288,15 +389,50 @@ impl<T: DeclaredClass> ClassBuilderHelper<T> {
         F: MethodImplementation<Callee = AnyClass>,
     {
         // SAFETY: Checked by caller
-        unsafe { self.builder.add_class_method(sel, func) }
+        self.try_with(|builder| unsafe { builder.try_add_class_method(sel, func) });
     }
 
     #[inline]
-    pub fn register(self) -> (&'static AnyClass, isize, isize) {
-        register_with_ivars::<T>(self.builder)
+    pub fn register(self) -> Result<(&'static AnyClass, isize, isize), ClassRegistrationError> {
+        let res = register_with_ivars::<T>(self.builder?)?;
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+        verify_proxy_forwarding(res.0)?;
+        Ok(res)
     }
 }
 
+/// Proxies must override the forwarding methods, otherwise `NSProxy` will
+/// throw an exception when any message is sent to them.
+///
+/// This can only be checked once the class has been registered, so the
+/// class still exists with the runtime if this fails.
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+fn verify_proxy_forwarding(cls: &AnyClass) -> Result<(), ClassRegistrationError> {
+    if !cls.is_proxy() {
+        return Ok(());
+    }
+    let root = AnyClass::get("NSProxy").unwrap();
+
+    let overrides = |sel: Sel| {
+        let imp = |cls: &AnyClass| {
+            cls.instance_method(sel)
+                .map(|method| method.implementation() as usize)
+        };
+        let (imp, root_imp) = (imp(cls), imp(root));
+        imp.is_some() && imp != root_imp
+    };
+
+    if !overrides(sel!(forwardingTargetForSelector:))
+        && !(overrides(sel!(forwardInvocation:)) && overrides(sel!(methodSignatureForSelector:)))
+    {
+        return Err(ClassRegistrationError::new(
+            cls.name(),
+            RegistrationErrorKind::ProxyForwarding,
+        ));
+    }
+    Ok(())
+}
+
 /// Helper for ensuring that:
 /// - Only methods on the protocol are overriden.
 /// - TODO: The methods have the correct signature.
@@ -304,19 +440,19 @@ impl<T: DeclaredClass> ClassBuilderHelper<T> {
 #[derive(Debug)]
 pub struct ClassProtocolMethodsBuilder<'a, T: ?Sized> {
     builder: &'a mut ClassBuilderHelper<T>,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     protocol: Option<&'static AnyProtocol>,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     required_instance_methods: Vec<MethodDescription>,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     optional_instance_methods: Vec<MethodDescription>,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     registered_instance_methods: HashSet<Sel>,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     required_class_methods: Vec<MethodDescription>,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     optional_class_methods: Vec<MethodDescription>,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     registered_class_methods: HashSet<Sel>,
 }
 
@@ -327,25 +463,28 @@ impl<T: DeclaredClass> ClassProtocolMethodsBuilder<'_, T> {
     where
         F: MethodImplementation<Callee = T>,
     {
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
         if let Some(protocol) = self.protocol {
-            let _types = self
+            let found = self
                 .required_instance_methods
                 .iter()
                 .chain(&self.optional_instance_methods)
-                .find(|desc| desc.sel == sel)
-                .map(|desc| desc.types)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "failed overriding protocol method -[{protocol} {sel}]: method not found"
-                    )
-                });
+                .any(|desc| desc.sel == sel);
+            if !found {
+                self.builder
+                    .fail(RegistrationErrorKind::UnknownProtocolMethod {
+                        protocol: protocol.name().to_string(),
+                        sel,
+                        class_method: false,
+                    });
+                return;
+            }
         }
 
         // SAFETY: Checked by caller
         unsafe { self.builder.add_method(sel, func) };
 
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
         if !self.registered_instance_methods.insert(sel) {
             unreachable!("already added")
         }
@@ -356,78 +495,78 @@ impl<T: DeclaredClass> ClassProtocolMethodsBuilder<'_, T> {
     where
         F: MethodImplementation<Callee = AnyClass>,
     {
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
         if let Some(protocol) = self.protocol {
-            let _types = self
+            let found = self
                 .required_class_methods
                 .iter()
                 .chain(&self.optional_class_methods)
-                .find(|desc| desc.sel == sel)
-                .map(|desc| desc.types)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "failed overriding protocol method +[{protocol} {sel}]: method not found"
-                    )
-                });
+                .any(|desc| desc.sel == sel);
+            if !found {
+                self.builder
+                    .fail(RegistrationErrorKind::UnknownProtocolMethod {
+                        protocol: protocol.name().to_string(),
+                        sel,
+                        class_method: true,
+                    });
+                return;
+            }
         }
 
         // SAFETY: Checked by caller
         unsafe { self.builder.add_class_method(sel, func) };
 
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
         if !self.registered_class_methods.insert(sel) {
             unreachable!("already added")
         }
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
     pub fn finish(self) {
-        let superclass = self.builder.builder.superclass();
-
-        if let Some(protocol) = self.protocol {
-            for desc in &self.required_instance_methods {
-                if self.registered_instance_methods.contains(&desc.sel) {
-                    continue;
-                }
-
-                // TODO: Don't do this when `NS_PROTOCOL_REQUIRES_EXPLICIT_IMPLEMENTATION`
-                if superclass
-                    .and_then(|superclass| superclass.instance_method(desc.sel))
-                    .is_some()
-                {
-                    continue;
-                }
-
-                panic!(
-                    "must implement required protocol method -[{protocol} {}]",
-                    desc.sel
-                )
-            }
-        }
-
-        if let Some(protocol) = self.protocol {
-            for desc in &self.required_class_methods {
-                if self.registered_class_methods.contains(&desc.sel) {
-                    continue;
-                }
-
-                // TODO: Don't do this when `NS_PROTOCOL_REQUIRES_EXPLICIT_IMPLEMENTATION`
-                if superclass
-                    .and_then(|superclass| superclass.class_method(desc.sel))
-                    .is_some()
-                {
-                    continue;
-                }
+        let protocol = match self.protocol {
+            Some(protocol) => protocol,
+            None => return,
+        };
+        let superclass = match &self.builder.builder {
+            Ok(builder) => builder.superclass(),
+            // Already failed.
+            Err(_) => return,
+        };
 
-                panic!(
-                    "must implement required protocol method +[{protocol} {}]",
-                    desc.sel
-                );
-            }
+        // TODO: Don't check the superclass when `NS_PROTOCOL_REQUIRES_EXPLICIT_IMPLEMENTATION`
+        let missing_instance_method = self
+            .required_instance_methods
+            .iter()
+            .find(|desc| {
+                !self.registered_instance_methods.contains(&desc.sel)
+                    && superclass
+                        .and_then(|superclass| superclass.instance_method(desc.sel))
+                        .is_none()
+            })
+            .map(|desc| (desc.sel, false));
+        let missing_class_method = self
+            .required_class_methods
+            .iter()
+            .find(|desc| {
+                !self.registered_class_methods.contains(&desc.sel)
+                    && superclass
+                        .and_then(|superclass| superclass.class_method(desc.sel))
+                        .is_none()
+            })
+            .map(|desc| (desc.sel, true));
+
+        if let Some((sel, class_method)) = missing_instance_method.or(missing_class_method) {
+            self.builder
+                .fail(RegistrationErrorKind::MissingProtocolMethod {
+                    protocol: protocol.name().to_string(),
+                    sel,
+                    class_method,
+                });
         }
     }
 
     #[inline]
-    #[cfg(not(debug_assertions))]
+    #[cfg(any(not(debug_assertions), feature = "unstable-objfw"))]
     pub fn finish(self) {}
 }
@@ -45,13 +45,19 @@
 //! [swift-deinit-unsound]: https://github.com/apple/swift/issues/68734
 //! [unsound-read-padding]: https://play.rust-lang.org/?version=stable&mode=debug&edition=2021&gist=ea068e8d9e55801aa9520ea914eb2822
 
+#[cfg(not(feature = "unstable-objfw"))]
 use alloc::borrow::Cow;
+#[cfg(not(feature = "unstable-objfw"))]
 use alloc::format;
 use core::mem;
 use core::ptr::{self, NonNull};
 
 use crate::encode::{Encode, Encoding};
-use crate::runtime::{AnyClass, AnyObject, ClassBuilder, MessageReceiver, Sel};
+#[cfg(not(feature = "unstable-objfw"))]
+use crate::runtime::RegistrationErrorKind;
+use crate::runtime::{
+    AnyClass, AnyObject, ClassBuilder, ClassRegistrationError, MessageReceiver, Sel,
+};
 use crate::{sel, ClassType, DeclaredClass};
 
 /// A type representing the drop flags that may be set for a type.
@@ -71,7 +77,8 @@ pub(crate) enum DropFlag {
     Allocated = 0x00,
     /// Used when `mem::needs_drop::<T::Ivars>()`, or with debug assertions enabled.
     InitializedIvars = 0x0f,
-    /// Used when `mem::needs_drop::<T>()`, or with debug assertions enabled.
+    /// Used when `mem::needs_drop::<T>()`, when the class has a
+    /// `did_dealloc` hook, or with debug assertions enabled.
     Finalized = 0xff,
 }
 
@@ -100,7 +107,15 @@ impl<T: DeclaredClass> DeclaredIvarsHelper for T {
     /// `needs_drop::<T>` can reliably detect a direct implementation of
     /// `Drop`, since the type only includes `ManuallyDrop` or `PhantomData`
     /// fields.
-    const HAS_DROP_FLAG: bool = mem::needs_drop::<T>() || mem::needs_drop::<T::Ivars>();
+    const HAS_DROP_FLAG: bool =
+        T::__HAS_DID_DEALLOC || mem::needs_drop::<T>() || mem::needs_drop::<T::Ivars>();
+}
+
+/// Whether `dealloc` needs to do anything for fully initialized instances,
+/// i.e. run the `did_dealloc` hook or the `Drop` implementation of the class.
+#[inline]
+fn needs_finalized_dealloc<T: DeclaredClass>() -> bool {
+    T::__HAS_DID_DEALLOC || mem::needs_drop::<T>()
 }
 
 /// Helper function for getting a pointer to the instance variable.
@@ -139,8 +154,9 @@ pub(crate) fn setup_dealloc<T: DeclaredClass>(builder: &mut ClassBuilder)
 where
     T::Super: ClassType,
 {
-    // Add dealloc if the class or the ivars need dropping.
-    if mem::needs_drop::<T>() || mem::needs_drop::<T::Ivars>() {
+    // Add dealloc if the class has a hook, or if it or the ivars need
+    // dropping.
+    if T::HAS_DROP_FLAG {
         let func: unsafe extern "C" fn(_, _) = dealloc::<T>;
         // SAFETY: The function signature is correct, and method contract is
         // upheld inside `dealloc`.
@@ -167,26 +183,42 @@ where
     #[cold]
     fn cold_path() {}
 
+    // Pairs with the release done when dropping each `Id`.
+    crate::sanitize::acquire_for_dealloc(this.as_ptr());
+
     // SAFETY: `dealloc` is only registered when there is a need for dropping,
     // and hence a need for a drop flag.
     let drop_flag = unsafe { *ptr_to_drop_flag(this) };
 
-    if mem::needs_drop::<T>() {
+    if needs_finalized_dealloc::<T>() {
         match drop_flag {
             // Don't deallocate the current instance if it has not been fully
             // initialized.
             //
             // Note that we still run the superclass deinitializer below.
             DropFlag::Allocated | DropFlag::InitializedIvars => cold_path(),
-            // SAFETY: This is the `dealloc` method, so we know that the type
-            // never needs to be deallocated again.
-            //
-            // Additionally, we know that the type was fully initialized, since
-            // that's what the drop flag says.
-            //
-            // TODO: This can unwind, is it correct to just let that
-            // propagate?
-            DropFlag::Finalized => unsafe { ptr::drop_in_place(this.as_ptr()) },
+            DropFlag::Finalized => {
+                // The hook runs first, while the object (including its
+                // instance variables and superclass) is still fully valid.
+                if T::__HAS_DID_DEALLOC {
+                    // SAFETY: We are in `dealloc`, so no other references to
+                    // the object exist, and the object is fully initialized.
+                    //
+                    // TODO: This can unwind, is it correct to just let that
+                    // propagate?
+                    unsafe { (*this.as_ptr()).did_dealloc() };
+                }
+
+                // SAFETY: This is the `dealloc` method, so we know that the
+                // type never needs to be deallocated again.
+                //
+                // Additionally, we know that the type was fully initialized,
+                // since that's what the drop flag says.
+                //
+                // TODO: This can unwind, is it correct to just let that
+                // propagate?
+                unsafe { ptr::drop_in_place(this.as_ptr()) }
+            }
         }
     }
 
@@ -214,6 +246,11 @@ where
                 // TODO: This can unwind, is it correct to just let that
                 // propagate?
                 unsafe { ptr::drop_in_place(ptr_to_ivar(this).as_ptr()) };
+                // Catch accesses to the dropped ivars from e.g. overridden
+                // methods called by the superclass' `dealloc`.
+                //
+                // SAFETY: The ivars are part of the object's allocation.
+                unsafe { crate::sanitize::poison(ptr_to_ivar(this).as_ptr()) };
             }
         }
     }
@@ -237,10 +274,11 @@ where
 }
 
 /// Register the class, and get the ivar offsets.
+#[cfg(not(feature = "unstable-objfw"))]
 #[inline]
 pub(crate) fn register_with_ivars<T: DeclaredClass>(
     mut builder: ClassBuilder,
-) -> (&'static AnyClass, isize, isize) {
+) -> Result<(&'static AnyClass, isize, isize), ClassRegistrationError> {
     let (ivar_name, drop_flag_name): (Cow<'static, str>, Cow<'static, str>) = {
         if cfg!(feature = "gnustep-1-7") {
             // GNUStep does not support a subclass having an ivar with the
@@ -265,16 +303,19 @@ pub(crate) fn register_with_ivars<T: DeclaredClass>(
                 4 => &u32::ENCODING,
                 // The alignment of `u64` may not be 8 on all architectures
                 8 if mem::align_of::<u64>() == 8 => &u64::ENCODING,
-                alignment => panic!("unsupported alignment {alignment} for `{}::Ivars`", T::NAME),
+                alignment => {
+                    return Err(builder.error(RegistrationErrorKind::IvarAlignment(alignment)))
+                }
             },
         );
-        unsafe { builder.add_ivar_inner::<T::Ivars>(&ivar_name, &ivar_encoding) };
+        unsafe { builder.add_ivar_inner::<T::Ivars>(&ivar_name, &ivar_encoding)? };
     }
 
     if T::HAS_DROP_FLAG {
         // TODO: Maybe we can reuse the drop flag when subclassing an already
         // declared class?
-        builder.add_ivar::<DropFlag>(&drop_flag_name);
+        // SAFETY: The encoding is correct.
+        unsafe { builder.add_ivar_inner::<DropFlag>(&drop_flag_name, &DropFlag::ENCODING)? };
     }
 
     let cls = builder.register();
@@ -315,7 +356,25 @@ pub(crate) fn register_with_ivars<T: DeclaredClass>(
         0
     };
 
-    (cls, ivars_offset, drop_flag_offset)
+    Ok((cls, ivars_offset, drop_flag_offset))
+}
+
+/// Register the class.
+///
+/// ObjFW does not support adding instance variables at runtime, so classes
+/// with ivars or a drop flag cannot be declared there.
+#[cfg(feature = "unstable-objfw")]
+#[inline]
+pub(crate) fn register_with_ivars<T: DeclaredClass>(
+    builder: ClassBuilder,
+) -> Result<(&'static AnyClass, isize, isize), ClassRegistrationError> {
+    if T::HAS_IVARS || T::HAS_DROP_FLAG {
+        panic!(
+            "cannot declare class {} with instance variables on ObjFW",
+            T::NAME
+        );
+    }
+    Ok((builder.register(), 0, 0))
 }
 
 /// # Safety
@@ -345,7 +404,10 @@ pub(crate) unsafe fn initialize_ivars<T: DeclaredClass>(ptr: NonNull<T>, val: T:
     // - The location is properly aligned by `ClassBuilder::add_ivar`.
     // - This write is done as part of initialization, so we know that the
     //   pointer is not shared elsewhere.
-    unsafe { ptr_to_ivar(ptr).as_ptr().write(val) };
+    unsafe {
+        crate::sanitize::unpoison(ptr_to_ivar(ptr).as_ptr());
+        ptr_to_ivar(ptr).as_ptr().write(val);
+    }
 
     // Write to drop flag that we've initialized the instance variables.
     //
@@ -381,7 +443,7 @@ pub(crate) unsafe fn set_finalized<T: DeclaredClass>(ptr: NonNull<T>) {
     }
 
     // Write to drop flag that we've fully initialized the class.
-    if T::HAS_DROP_FLAG && (mem::needs_drop::<T>() || cfg!(debug_assertions)) {
+    if T::HAS_DROP_FLAG && (needs_finalized_dealloc::<T>() || cfg!(debug_assertions)) {
         // SAFETY: Just checked that the drop flag is available.
         unsafe { ptr_to_drop_flag(ptr).write(DropFlag::Finalized) }
     }
@@ -419,6 +481,7 @@ pub(crate) unsafe fn get_initialized_ivar_ptr<T: DeclaredClass>(
 }
 
 #[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
 mod tests {
     use std::println;
     use std::sync::Mutex;
@@ -427,7 +490,7 @@ mod tests {
 
     use super::*;
     use crate::mutability::{InteriorMutable, Mutable};
-    use crate::rc::{Allocated, Id, PartialInit, __RcTestObject, __ThreadTestData};
+    use crate::rc::{__RcTestObject, __ThreadTestData, Allocated, Id, PartialInit};
     use crate::runtime::NSObject;
     use crate::{declare_class, msg_send, msg_send_id};
 
@@ -612,6 +675,115 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_did_dealloc() {
+        #[derive(Debug, PartialEq)]
+        enum Operation {
+            DidDealloc(usize),
+            DropClass,
+            DropIvar,
+        }
+
+        static OPERATIONS: Mutex<Vec<Operation>> = Mutex::new(Vec::new());
+
+        struct Ivar(usize);
+
+        impl Drop for Ivar {
+            fn drop(&mut self) {
+                OPERATIONS.lock().unwrap().push(Operation::DropIvar);
+            }
+        }
+
+        declare_class!(
+            struct HasDidDealloc;
+
+            unsafe impl ClassType for HasDidDealloc {
+                type Super = NSObject;
+                type Mutability = InteriorMutable;
+                const NAME: &'static str = "HasDidDealloc";
+            }
+
+            impl DeclaredClass for HasDidDealloc {
+                type Ivars = Ivar;
+
+                fn did_dealloc(&mut self) {
+                    // The ivars are still accessible
+                    let value = self.ivars().0;
+                    OPERATIONS.lock().unwrap().push(Operation::DidDealloc(value));
+                }
+            }
+
+            unsafe impl HasDidDealloc {
+                #[method_id(init)]
+                fn init(this: Allocated<Self>) -> Option<Id<Self>> {
+                    unsafe { msg_send_id![super(this.set_ivars(Ivar(42))), init] }
+                }
+            }
+        );
+
+        impl Drop for HasDidDealloc {
+            fn drop(&mut self) {
+                OPERATIONS.lock().unwrap().push(Operation::DropClass);
+            }
+        }
+
+        // Without `Drop` or ivars that need dropping.
+        declare_class!(
+            struct OnlyDidDealloc;
+
+            unsafe impl ClassType for OnlyDidDealloc {
+                type Super = NSObject;
+                type Mutability = InteriorMutable;
+                const NAME: &'static str = "OnlyDidDealloc";
+            }
+
+            impl DeclaredClass for OnlyDidDealloc {
+                fn did_dealloc(&mut self) {
+                    OPERATIONS.lock().unwrap().push(Operation::DidDealloc(0));
+                }
+            }
+
+            unsafe impl OnlyDidDealloc {
+                #[method_id(init)]
+                fn init(this: Allocated<Self>) -> Option<Id<Self>> {
+                    unsafe { msg_send_id![super(this.set_ivars(())), init] }
+                }
+            }
+        );
+
+        #[track_caller]
+        fn check<const N: usize>(expected: [Operation; N]) {
+            let mut operations = OPERATIONS.lock().unwrap();
+            assert_eq!(&**operations, expected);
+            operations.clear();
+        }
+
+        let _ = HasDidDealloc::alloc();
+        check([]);
+
+        let _ = unsafe { init_only_superclasses(HasDidDealloc::alloc()) };
+        check([]);
+
+        let _ = unsafe { init(HasDidDealloc::alloc()) };
+        check([
+            Operation::DidDealloc(42),
+            Operation::DropClass,
+            Operation::DropIvar,
+        ]);
+
+        assert!(!mem::needs_drop::<OnlyDidDealloc>());
+        assert_ne!(
+            OnlyDidDealloc::class().instance_method(sel!(dealloc)),
+            NSObject::class().instance_method(sel!(dealloc)),
+        );
+
+        let _ = OnlyDidDealloc::alloc();
+        check([]);
+
+        let _ = unsafe { init(OnlyDidDealloc::alloc()) };
+        check([Operation::DidDealloc(0)]);
+    }
+
     #[test]
     fn test_no_generated_dealloc_if_not_needed() {
         #[allow(unused)]
@@ -879,6 +1051,10 @@ mod tests {
         any(feature = "unstable-c-unwind", target_arch = "x86"),
         ignore = "panicking in Drop requires that we actually implement `dealloc` as `C-unwind`"
     )]
+    #[cfg_attr(
+        feature = "unstable-mock-runtime",
+        ignore = "the mock runtime aborts on panics in methods it calls"
+    )]
     fn test_panic_in_drop() {
         declare_class!(
             struct DropPanics;
@@ -909,6 +1085,10 @@ mod tests {
         any(feature = "unstable-c-unwind", target_arch = "x86"),
         ignore = "panicking in Drop requires that we actually implement `dealloc` as `C-unwind`"
     )]
+    #[cfg_attr(
+        feature = "unstable-mock-runtime",
+        ignore = "the mock runtime aborts on panics in methods it calls"
+    )]
     fn test_panic_in_ivar_drop() {
         struct DropPanics;
 
@@ -936,4 +1116,59 @@ mod tests {
         let obj: Id<IvarDropPanics> = unsafe { msg_send_id![super(obj), init] };
         drop(obj);
     }
+
+    #[test]
+    fn test_pinned_ivars() {
+        use core::cell::Cell;
+        use core::marker::PhantomPinned;
+        use core::pin::Pin;
+
+        /// Stores a pointer to itself once pinned.
+        struct SelfRef {
+            this: Cell<*const SelfRef>,
+            _pin: PhantomPinned,
+        }
+
+        impl SelfRef {
+            fn init(self: Pin<&Self>) {
+                self.this.set(&*self);
+            }
+
+            fn check(self: Pin<&Self>) {
+                assert!(ptr::eq(self.this.get(), &*self));
+            }
+        }
+
+        impl Drop for SelfRef {
+            fn drop(&mut self) {
+                // Dropped in place
+                assert!(ptr::eq(self.this.get(), self));
+            }
+        }
+
+        declare_class!(
+            struct PinnedIvars;
+
+            unsafe impl ClassType for PinnedIvars {
+                type Super = NSObject;
+                type Mutability = Mutable;
+                const NAME: &'static str = "PinnedIvars";
+            }
+
+            impl DeclaredClass for PinnedIvars {
+                type Ivars = SelfRef;
+            }
+        );
+
+        let obj = PinnedIvars::alloc().set_ivars(SelfRef {
+            this: Cell::new(ptr::null()),
+            _pin: PhantomPinned,
+        });
+        let mut obj: Id<PinnedIvars> = unsafe { msg_send_id![super(obj), init] };
+        obj.ivars_pinned().init();
+        obj.ivars_pinned().check();
+
+        obj.ivars_pinned_mut().as_ref().check();
+        drop(obj);
+    }
 }
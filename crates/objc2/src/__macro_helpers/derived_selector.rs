@@ -0,0 +1,117 @@
+//! Deriving selectors from Rust method and argument names.
+//!
+//! Used by `#[method]` and `#[method_id]` in `extern_methods!` when no
+//! selector is given. The Rust name is converted from `snake_case` to
+//! `lowerCamelCase`, and each argument after the first contributes its name
+//! as the label of the next part of the selector, similar to how Swift maps
+//! argument labels:
+//!
+//! - `fn description(&self)` -> `description`.
+//! - `fn object_for_key(&self, key: &K)` -> `objectForKey:`.
+//! - `fn set_object(&self, object: &T, for_key: &K)` -> `setObject:forKey:`.
+
+/// Whether the byte at `i` is an underscore that separates two words, and
+/// should therefore be removed.
+const fn is_separator(part: &[u8], i: usize) -> bool {
+    part[i] == b'_' && i != 0 && i + 1 < part.len() && part[i + 1].is_ascii_lowercase()
+}
+
+/// The length of the selector derived from the given parts, including the
+/// trailing NUL byte.
+pub const fn derived_selector_len(parts: &[&str], has_arguments: bool) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let part = parts[i].as_bytes();
+        let mut j = 0;
+        while j < part.len() {
+            if !is_separator(part, j) {
+                len += 1;
+            }
+            j += 1;
+        }
+        if has_arguments {
+            // Colon
+            len += 1;
+        }
+        i += 1;
+    }
+    // NUL byte
+    len + 1
+}
+
+/// Derive a NUL-terminated selector from the given parts.
+///
+/// `N` must be the value returned by [`derived_selector_len`].
+pub const fn derived_selector<const N: usize>(parts: &[&str], has_arguments: bool) -> [u8; N] {
+    let mut res = [0; N];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let part = parts[i].as_bytes();
+        let mut uppercase_next = false;
+        let mut j = 0;
+        while j < part.len() {
+            if is_separator(part, j) {
+                uppercase_next = true;
+            } else {
+                res[pos] = if uppercase_next {
+                    part[j].to_ascii_uppercase()
+                } else {
+                    part[j]
+                };
+                uppercase_next = false;
+                pos += 1;
+            }
+            j += 1;
+        }
+        if has_arguments {
+            res[pos] = b':';
+            pos += 1;
+        }
+        i += 1;
+    }
+    if pos + 1 != N {
+        panic!("invalid selector length");
+    }
+    // The last byte is left as NUL.
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! derive {
+        ($has_arguments:expr, $($part:expr),*) => {{
+            const PARTS: &[&str] = &[$($part),*];
+            const LEN: usize = derived_selector_len(PARTS, $has_arguments);
+            const RES: [u8; LEN] = derived_selector(PARTS, $has_arguments);
+            core::str::from_utf8(&RES).unwrap()
+        }};
+    }
+
+    #[test]
+    fn test_derived_selector() {
+        assert_eq!(derive!(false, "description"), "description\0");
+        assert_eq!(derive!(false, "is_enabled"), "isEnabled\0");
+        assert_eq!(derive!(true, "object_for_key"), "objectForKey:\0");
+        assert_eq!(
+            derive!(true, "set_object", "for_key"),
+            "setObject:forKey:\0"
+        );
+        assert_eq!(
+            derive!(true, "init_with_name", "age", "is_admin"),
+            "initWithName:age:isAdmin:\0"
+        );
+    }
+
+    #[test]
+    fn test_derived_selector_underscores() {
+        assert_eq!(derive!(false, "_private_method"), "_privateMethod\0");
+        assert_eq!(derive!(false, "foo_"), "foo_\0");
+        assert_eq!(derive!(false, "foo_1"), "foo_1\0");
+        assert_eq!(derive!(false, "foo__bar"), "foo_Bar\0");
+        assert_eq!(derive!(false, "fooBar"), "fooBar\0");
+    }
+}
@@ -1,12 +1,22 @@
 pub use core::borrow::{Borrow, BorrowMut};
 pub use core::cell::UnsafeCell;
-pub use core::convert::{AsMut, AsRef};
-pub use core::marker::{PhantomData, Sized};
+pub use core::clone::Clone;
+pub use core::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+pub use core::convert::{AsMut, AsRef, From};
+pub use core::fmt;
+pub use core::hash::Hash;
+pub use core::marker::{Copy, PhantomData, Sized};
 pub use core::mem::{size_of, ManuallyDrop, MaybeUninit};
-pub use core::ops::{Deref, DerefMut};
+pub use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Deref, DerefMut, Not, Sub,
+    SubAssign,
+};
 pub use core::option::Option::{self, None, Some};
-pub use core::primitive::{bool, isize, str, u8};
-pub use core::{compile_error, concat, panic, stringify};
+pub use core::primitive::{bool, isize, str, u8, usize};
+pub use core::ptr::addr_of;
+pub use core::result::Result::{self, Err, Ok};
+pub use core::str::from_utf8_unchecked;
+pub use core::{compile_error, concat, env, module_path, panic, stringify, unreachable};
 // TODO: Use `core::cell::LazyCell`
 pub use std::sync::Once;
 
@@ -15,25 +25,103 @@ mod common_selectors;
 mod convert;
 mod declare_class;
 pub(crate) mod declared_ivars;
+mod derived_selector;
 mod method_family;
 mod msg_send;
 mod msg_send_id;
+pub(crate) mod unique_class_name;
 mod writeback;
 
+use crate::ffi::NSInteger;
+use crate::rc::{autoreleasepool_leaking, Id};
+use crate::runtime::__nsstring::nsstring_to_str;
+use crate::runtime::{AnyObject, NSObject};
+use crate::{msg_send, msg_send_id, Message};
+
 pub use self::cache::{CachedClass, CachedSel};
 pub use self::common_selectors::{alloc_sel, dealloc_sel, init_sel, new_sel};
-pub use self::convert::{ConvertArgument, ConvertArguments, ConvertReturn, TupleExtender};
+pub use self::convert::{
+    check_argument, CheckedArgument, ConvertArgument, ConvertArgumentSealed, ConvertArguments,
+    ConvertReturn, ConvertReturnSealed, TupleExtender,
+};
+#[cfg(feature = "unwind")]
+pub use self::declare_class::declared_method_body;
 pub use self::declare_class::{
-    assert_mutability_matches_superclass_mutability, ClassBuilderHelper,
-    ClassProtocolMethodsBuilder, IdReturnValue, MaybeOptionId, MessageRecieveId,
-    ValidSubclassMutability,
+    assert_mutability_matches_superclass_mutability, class_registration_failed,
+    declared_result_to_bool, ClassBuilderHelper, ClassProtocolMethodsBuilder, DeclaredResult,
+    IdReturnValue, MaybeOptionId, MessageRecieveId, ValidSubclassMutability,
 };
 pub use self::declared_ivars::DeclaredIvarsHelper;
+pub use self::derived_selector::{derived_selector, derived_selector_len};
 pub use self::method_family::{
     retain_semantics, Alloc, CopyOrMutCopy, Init, New, Other, RetainSemantics,
 };
-pub use self::msg_send::MsgSend;
+pub use self::msg_send::{check_receiver, MsgSend};
 pub use self::msg_send_id::{MaybeUnwrap, MsgSendId, MsgSendSuperId};
+pub use self::unique_class_name::{unique_class_name, unique_class_name_len};
+pub use crate::runtime::sel_table::SelTableLayout;
+pub use objc2_encode::{__bytes_eq, __encoding_str_array, __encoding_str_len};
+
+/// Compare two objects with `compare:`, used by `#[compare]` in
+/// `extern_class!`.
+///
+///
+/// # Safety
+///
+/// The class must implement `compare:`, taking another instance of the same
+/// class and returning `NSComparisonResult`.
+#[inline]
+#[track_caller]
+pub unsafe fn compare<T: ?Sized + Message>(obj: &T, other: &T) -> Ordering {
+    // SAFETY: Upheld by caller.
+    let res: NSInteger = unsafe { msg_send![obj, compare: other] };
+    match res {
+        -1 => Ordering::Less,
+        0 => Ordering::Equal,
+        1 => Ordering::Greater,
+        _ => panic!("invalid NSComparisonResult {res} returned from `compare:`"),
+    }
+}
+
+/// Write the result of `description`, used by `#[display]` in
+/// `extern_class!`.
+#[inline]
+pub fn fmt_description<T: ?Sized + Message>(obj: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let obj: &AnyObject = unsafe { &*(obj as *const T).cast() };
+    // SAFETY: `description` is part of the `NSObject` protocol, and returns
+    // an `NSString`.
+    let description: Option<Id<NSObject>> = unsafe { msg_send_id![obj, description] };
+    fmt_nsstring(description.as_deref(), f)
+}
+
+/// Write the result of `debugDescription`, used by `#[debug_description]`
+/// in `extern_class!`.
+#[inline]
+pub fn fmt_debug_description<T: ?Sized + Message>(
+    obj: &T,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let obj: &AnyObject = unsafe { &*(obj as *const T).cast() };
+    // SAFETY: `debugDescription` is part of the `NSObject` protocol, and
+    // returns an `NSString`.
+    let description: Option<Id<NSObject>> = unsafe { msg_send_id![obj, debugDescription] };
+    fmt_nsstring(description.as_deref(), f)
+}
+
+fn fmt_nsstring(s: Option<&NSObject>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some(s) = s {
+        // Like the `Debug` implementation of `ProtocolObject`, we use a
+        // leaking autorelease pool, since the string will usually be UTF-8,
+        // and so that the formatter can be passed into the pool.
+        autoreleasepool_leaking(|pool| {
+            // SAFETY: Checked by caller.
+            let s = unsafe { nsstring_to_str(s, pool) };
+            fmt::Display::fmt(s, f)
+        })
+    } else {
+        f.write_str("(null)")
+    }
+}
 
 /// Helper struct for emitting the module info that macOS 32-bit requires.
 ///
@@ -1,10 +1,8 @@
-use core::mem::ManuallyDrop;
 use core::ptr;
 
 use crate::encode::RefEncode;
-use crate::mutability::IsMutable;
 use crate::rc::Id;
-use crate::runtime::{AnyClass, AnyObject, MessageReceiver, Sel};
+use crate::runtime::{AnyClass, AnyObject, BatchSender, MessageReceiver, Sel};
 use crate::{ClassType, Encode, Message};
 
 use super::{ConvertArguments, ConvertReturn, TupleExtender};
@@ -59,6 +57,24 @@ pub trait MsgSend: Sized {
         R::__from_return(result)
     }
 
+    #[inline]
+    #[track_caller]
+    unsafe fn send_batch_message<A, R>(self, sender: &mut BatchSender, args: A) -> R
+    where
+        A: ConvertArguments,
+        R: ConvertReturn,
+    {
+        let (args, stored) = A::__into_arguments(args);
+
+        // SAFETY: Upheld by caller
+        let result = unsafe { sender.send(self.into_raw_receiver(), args) };
+
+        // SAFETY: Same as in send_message above.
+        unsafe { A::__process_after_message_send(stored) };
+
+        R::__from_return(result)
+    }
+
     #[inline]
     #[track_caller]
     unsafe fn send_super_message_static<A, R>(self, sel: Sel, args: A) -> R
@@ -158,40 +174,24 @@ impl<T: ?Sized + MessageReceiver> MsgSend for T {
     }
 }
 
-impl<'a, T: ?Sized + Message> MsgSend for &'a Id<T> {
-    type Inner = T;
-
-    #[inline]
-    fn into_raw_receiver(self) -> *mut AnyObject {
-        (Id::as_ptr(self) as *mut T).cast()
-    }
-}
-
-impl<'a, T: ?Sized + Message + IsMutable> MsgSend for &'a mut Id<T> {
-    type Inner = T;
-
-    #[inline]
-    fn into_raw_receiver(self) -> *mut AnyObject {
-        Id::as_mut_ptr(self).cast()
-    }
-}
-
-impl<T: ?Sized + Message> MsgSend for ManuallyDrop<Id<T>> {
-    type Inner = T;
-
-    #[inline]
-    fn into_raw_receiver(self) -> *mut AnyObject {
-        Id::into_raw(ManuallyDrop::into_inner(self)).cast()
-    }
+/// Pass a receiver through unchanged.
+///
+/// Used by `msg_send!` so that using something that is not a
+/// [`MessageReceiver`] (such as forgetting the `&` on an object) produces an
+/// error pointing at the receiver.
+#[inline]
+pub fn check_receiver<T: MessageReceiver>(receiver: T) -> T {
+    receiver
 }
 
 #[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
 mod tests {
+    use core::mem::ManuallyDrop;
+
     use crate::msg_send;
     use crate::test_utils;
 
-    use super::*;
-
     #[test]
     fn test_send_message_manuallydrop() {
         let obj = ManuallyDrop::new(test_utils::custom_object());
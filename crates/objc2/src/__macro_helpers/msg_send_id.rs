@@ -776,6 +776,10 @@ mod tests {
         ),
         ignore = "panicking in `init` requires that we emit the function as `C-unwind`"
     )]
+    #[cfg_attr(
+        feature = "unstable-mock-runtime",
+        ignore = "the mock runtime aborts on panics in methods it calls"
+    )]
     fn test_super_init_not_initialized() {
         let obj = __RcTestObject::alloc().set_ivars(());
         let _: Id<__RcTestObject> =
@@ -882,6 +886,90 @@ mod tests {
         expected.assert_current();
     }
 
+    // Explicit method family
+
+    #[test]
+    fn test_method_family_override() {
+        let obj = __RcTestObject::new();
+        let mut expected = __ThreadTestData::current();
+
+        // Same semantics as the `new` family
+        let _: Id<__RcTestObject> = unsafe {
+            msg_send_id![
+                #[unsafe(method_family = copy)]
+                &obj,
+                newMethodOnInstance
+            ]
+        };
+        expected.alloc += 1;
+        expected.init += 1;
+        expected.release += 1;
+        expected.drop += 1;
+        expected.assert_current();
+
+        let _: Id<__RcTestObject> = unsafe {
+            msg_send_id![
+                #[unsafe(method_family = mutableCopy)]
+                super(&obj, __RcTestObject::class()),
+                newMethodOnInstance
+            ]
+        };
+        expected.alloc += 1;
+        expected.init += 1;
+        expected.release += 1;
+        expected.drop += 1;
+        expected.assert_current();
+
+        // Treat the method as if it returned an autoreleased object; the
+        // extra retain is balanced by the manual release below.
+        let res: Id<__RcTestObject> = unsafe {
+            msg_send_id![
+                #[unsafe(method_family = none)]
+                &obj,
+                newMethodOnInstance
+            ]
+        };
+        expected.alloc += 1;
+        expected.init += 1;
+        expected.retain += 1;
+        expected.assert_current();
+
+        let ptr = Id::into_raw(res);
+        unsafe { crate::ffi::objc_release(ptr.cast()) };
+        unsafe { crate::ffi::objc_release(ptr.cast()) };
+        expected.release += 2;
+        expected.drop += 1;
+        expected.assert_current();
+
+        let cls = __RcTestObject::class();
+        let obj: Allocated<__RcTestObject> = unsafe {
+            msg_send_id![
+                #[unsafe(method_family = alloc)]
+                cls,
+                alloc
+            ]
+        };
+        let _: Id<__RcTestObject> = unsafe {
+            msg_send_id![
+                #[unsafe(method_family = init)]
+                obj,
+                init
+            ]
+        };
+        let _: Id<__RcTestObject> = unsafe {
+            msg_send_id![
+                #[unsafe(method_family = new)]
+                cls,
+                new
+            ]
+        };
+        expected.alloc += 2;
+        expected.init += 2;
+        expected.release += 2;
+        expected.drop += 2;
+        expected.assert_current();
+    }
+
     #[test]
     #[should_panic = "unexpected NULL returned from -[__RcTestObject methodReturningNull]"]
     fn test_normal_with_null() {
@@ -0,0 +1,188 @@
+//! Mangling class names, used by `unique_class_name!`.
+//!
+//! The mangled name has the form `{name}__objc2_{crate}_{version}_{hash}`,
+//! where the crate name and version have non-alphanumeric characters
+//! replaced by `_`, and the hash is computed from the class name, crate
+//! name, crate version and module path.
+
+/// Separates the class name from the mangled suffix.
+pub(crate) const SEPARATOR: &str = "__objc2_";
+
+/// The number of hex digits in the hash.
+const HASH_LEN: usize = 16;
+
+/// 64-bit FNV-1a of the parts, each followed by a NUL byte.
+const fn hash(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut i = 0;
+    while i < parts.len() {
+        let part = parts[i].as_bytes();
+        let mut j = 0;
+        while j <= part.len() {
+            let byte = if j < part.len() { part[j] } else { 0 };
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+            j += 1;
+        }
+        i += 1;
+    }
+    hash
+}
+
+const fn write_sanitized(res: &mut [u8], mut pos: usize, s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        res[pos] = if bytes[i].is_ascii_alphanumeric() {
+            bytes[i]
+        } else {
+            b'_'
+        };
+        pos += 1;
+        i += 1;
+    }
+    pos
+}
+
+/// The length of the mangled class name.
+pub const fn unique_class_name_len(name: &str, crate_name: &str, version: &str) -> usize {
+    name.len() + SEPARATOR.len() + crate_name.len() + 1 + version.len() + 1 + HASH_LEN
+}
+
+/// Mangle the class name with the crate name, version and module path.
+///
+/// `N` must be the value returned by [`unique_class_name_len`].
+pub const fn unique_class_name<const N: usize>(
+    name: &str,
+    crate_name: &str,
+    version: &str,
+    module_path: &str,
+) -> [u8; N] {
+    let mut res = [0; N];
+    let mut pos = 0;
+
+    let mut i = 0;
+    while i < name.len() {
+        res[pos] = name.as_bytes()[i];
+        pos += 1;
+        i += 1;
+    }
+    let mut i = 0;
+    while i < SEPARATOR.len() {
+        res[pos] = SEPARATOR.as_bytes()[i];
+        pos += 1;
+        i += 1;
+    }
+    pos = write_sanitized(&mut res, pos, crate_name);
+    res[pos] = b'_';
+    pos += 1;
+    pos = write_sanitized(&mut res, pos, version);
+    res[pos] = b'_';
+    pos += 1;
+
+    let hash = hash(&[name, crate_name, version, module_path]);
+    let mut i = 0;
+    while i < HASH_LEN {
+        let digit = (hash >> ((HASH_LEN - 1 - i) * 4)) & 0xf;
+        res[pos] = b"0123456789abcdef"[digit as usize];
+        pos += 1;
+        i += 1;
+    }
+
+    if pos != N {
+        panic!("invalid class name length");
+    }
+    res
+}
+
+/// Whether `suffix` has the form `{crate}_{version}_{hash}`.
+fn is_mangled_suffix(suffix: &str) -> bool {
+    let bytes = suffix.as_bytes();
+    if bytes.len() < HASH_LEN + 1 {
+        return false;
+    }
+    let (rest, hash) = bytes.split_at(bytes.len() - HASH_LEN);
+    let crate_and_version = match rest.strip_suffix(b"_") {
+        Some(crate_and_version) => crate_and_version,
+        None => return false,
+    };
+    hash.iter().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        && crate_and_version.contains(&b'_')
+        && crate_and_version
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || *b == b'_')
+}
+
+/// Strip the suffix added by [`unique_class_name`], if any.
+///
+/// Class names that merely contain [`SEPARATOR`] are returned unchanged.
+pub(crate) fn unmangle(name: &str) -> &str {
+    match name.rfind(SEPARATOR) {
+        Some(idx) if is_mangled_suffix(&name[idx + SEPARATOR.len()..]) => &name[..idx],
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! mangle {
+        ($name:expr, $crate_name:expr, $version:expr, $module_path:expr) => {{
+            const LEN: usize = unique_class_name_len($name, $crate_name, $version);
+            const RES: [u8; LEN] = unique_class_name($name, $crate_name, $version, $module_path);
+            core::str::from_utf8(&RES).unwrap()
+        }};
+    }
+
+    #[test]
+    fn test_unique_class_name() {
+        let name = mangle!("MyDelegate", "my-crate", "1.2.3-beta.1", "my_crate::app");
+        assert!(
+            name.starts_with("MyDelegate__objc2_my_crate_1_2_3_beta_1_"),
+            "{name}"
+        );
+        assert_eq!(unmangle(name), "MyDelegate");
+
+        // Deterministic.
+        assert_eq!(
+            name,
+            mangle!("MyDelegate", "my-crate", "1.2.3-beta.1", "my_crate::app")
+        );
+
+        // Different versions and modules give different names.
+        let other_version = mangle!("MyDelegate", "my-crate", "1.2.4", "my_crate::app");
+        assert_ne!(name, other_version);
+        assert_eq!(unmangle(other_version), "MyDelegate");
+        let other_module = mangle!("MyDelegate", "my-crate", "1.2.3-beta.1", "my_crate::ui");
+        assert_ne!(name, other_module);
+        assert_eq!(name.len(), other_module.len());
+    }
+
+    #[test]
+    fn test_unmangle() {
+        assert_eq!(unmangle("NSObject"), "NSObject");
+        assert_eq!(unmangle("__objc2AbiSelfTest"), "__objc2AbiSelfTest");
+        assert_eq!(unmangle(""), "");
+
+        // Only a full suffix is stripped.
+        assert_eq!(unmangle("Foo__objc2_Bar"), "Foo__objc2_Bar");
+        assert_eq!(
+            unmangle("Foo__objc2_my_crate_1_0_0_0123456789abcdef"),
+            "Foo"
+        );
+        assert_eq!(
+            unmangle("Foo__objc2_Bar__objc2_my_crate_1_0_0_0123456789abcdef"),
+            "Foo__objc2_Bar"
+        );
+        assert_eq!(
+            unmangle("Foo__objc2_my_crate_1_0_0_0123456789ABCDEF"),
+            "Foo__objc2_my_crate_1_0_0_0123456789ABCDEF"
+        );
+        assert_eq!(unmangle("Foo__objc2_1_0_0123456789abcdef"), "Foo");
+        assert_eq!(
+            unmangle("Foo__objc2_0123456789abcdef"),
+            "Foo__objc2_0123456789abcdef"
+        );
+    }
+}
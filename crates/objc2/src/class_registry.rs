@@ -0,0 +1,263 @@
+//! # A registry of the classes known to Rust.
+//!
+//! When the `"class-registry"` Cargo feature is enabled, every class
+//! declared with [`declare_class!`] or [`extern_class!`] (including the
+//! classes in the framework crates) is recorded in a global registry when
+//! the binary or dynamic library containing it is loaded. This allows
+//! discovering the available bindings at runtime, e.g. for generic
+//! serializers or dependency-injection containers that need to go from a
+//! class name to a Rust type.
+//!
+//! Generic classes such as `NSArray<T>`, and classes that implement
+//! [`ClassType`] manually, are not recorded.
+//!
+//! The registry only records static information about the Rust type, and
+//! does not record type encodings. Use [`ClassInfo::class`] to get the
+//! Objective-C class itself, from which the methods and their type encodings
+//! can be inspected.
+//!
+//! Classes are recorded by a static initializer, in the same way as
+//! [`register_class_at_load!`], so dynamic libraries containing classes
+//! must not be unloaded.
+//!
+//! [`declare_class!`]: crate::declare_class
+//! [`extern_class!`]: crate::extern_class
+//! [`register_class_at_load!`]: crate::register_class_at_load
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use objc2::class_registry::{self, ClassKind};
+//! use objc2::runtime::NSObject;
+//! use objc2::{declare_class, mutability, ClassType, DeclaredClass};
+//!
+//! declare_class!(
+//!     struct MyRegisteredObject;
+//!
+//!     unsafe impl ClassType for MyRegisteredObject {
+//!         type Super = NSObject;
+//!         type Mutability = mutability::InteriorMutable;
+//!         const NAME: &'static str = "MyRegisteredObject";
+//!     }
+//!
+//!     impl DeclaredClass for MyRegisteredObject {}
+//! );
+//!
+//! let info = class_registry::get("MyRegisteredObject").unwrap();
+//! assert_eq!(info.kind(), ClassKind::Declared);
+//! assert!(info.type_name().ends_with("::MyRegisteredObject"));
+//! assert_eq!(info.class(), MyRegisteredObject::class());
+//!
+//! assert!(class_registry::classes().any(|info| info.name() == "MyRegisteredObject"));
+//! ```
+use core::fmt;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::runtime::{AnyClass, ClassRegistrationError};
+
+/// How a class was made available to Rust.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ClassKind {
+    /// The class was declared in Rust with [`declare_class!`].
+    ///
+    /// [`declare_class!`]: crate::declare_class
+    Declared,
+    /// The class is defined elsewhere, and was bound with [`extern_class!`].
+    ///
+    /// [`extern_class!`]: crate::extern_class
+    Extern,
+}
+
+/// Information about a class in the registry.
+pub struct ClassInfo {
+    name: &'static str,
+    type_name: &'static str,
+    kind: ClassKind,
+    class: fn() -> &'static AnyClass,
+    try_register: Option<fn() -> Result<&'static AnyClass, ClassRegistrationError>>,
+    next: AtomicPtr<ClassInfo>,
+}
+
+impl ClassInfo {
+    #[doc(hidden)]
+    pub const fn __new(
+        name: &'static str,
+        type_name: &'static str,
+        kind: ClassKind,
+        class: fn() -> &'static AnyClass,
+        try_register: Option<fn() -> Result<&'static AnyClass, ClassRegistrationError>>,
+    ) -> Self {
+        Self {
+            name,
+            type_name,
+            kind,
+            class,
+            try_register,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// The name of the Objective-C class, i.e. [`ClassType::NAME`].
+    ///
+    /// [`ClassType::NAME`]: crate::ClassType::NAME
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The path of the Rust type that represents the class.
+    ///
+    /// This is only meant for diagnostics, the exact format is not
+    /// guaranteed.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// How the class was made available to Rust.
+    pub fn kind(&self) -> ClassKind {
+        self.kind
+    }
+
+    /// Get the class, by calling [`ClassType::class`] on the Rust type.
+    ///
+    /// This registers the class with the runtime if it was declared in Rust
+    /// and has not been used yet.
+    ///
+    /// [`ClassType::class`]: crate::ClassType::class
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`ClassType::class`] does, e.g. if this is an external class
+    /// that is not available at runtime.
+    pub fn class(&self) -> &'static AnyClass {
+        (self.class)()
+    }
+
+    /// Get the class, registering it with [`DeclaredClass::try_register`] if
+    /// it was declared in Rust.
+    ///
+    /// [`DeclaredClass::try_register`]: crate::DeclaredClass::try_register
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the class was declared in Rust, and registering
+    /// it failed.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is an external class that is not available at
+    /// runtime, see [`ClassInfo::class`].
+    pub fn try_register(&self) -> Result<&'static AnyClass, ClassRegistrationError> {
+        match self.try_register {
+            Some(try_register) => try_register(),
+            None => Ok(self.class()),
+        }
+    }
+}
+
+impl fmt::Debug for ClassInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClassInfo")
+            .field("name", &self.name)
+            .field("type_name", &self.type_name)
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The most recently registered class, which links to the rest.
+static HEAD: AtomicPtr<ClassInfo> = AtomicPtr::new(ptr::null_mut());
+
+#[doc(hidden)]
+pub fn __register(info: &'static ClassInfo) {
+    let info_ptr = info as *const ClassInfo as *mut ClassInfo;
+    let mut head = HEAD.load(Ordering::Acquire);
+    loop {
+        info.next.store(head, Ordering::Relaxed);
+        match HEAD.compare_exchange_weak(head, info_ptr, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => return,
+            Err(new_head) => head = new_head,
+        }
+    }
+}
+
+/// Iterate over all the classes in the registry.
+///
+/// The order of the classes is unspecified.
+pub fn classes() -> impl Iterator<Item = &'static ClassInfo> {
+    let mut current = HEAD.load(Ordering::Acquire);
+    core::iter::from_fn(move || {
+        // SAFETY: The registry only contains `'static` entries.
+        let info: &'static ClassInfo = unsafe { current.as_ref()? };
+        current = info.next.load(Ordering::Acquire);
+        Some(info)
+    })
+}
+
+/// Find the class with the given Objective-C name in the registry.
+///
+/// If multiple Rust types represent the same class, one of them is
+/// returned.
+pub fn get(name: &str) -> Option<&'static ClassInfo> {
+    classes().find(|info| info.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NSObject;
+    use crate::{declare_class, extern_class, mutability, ClassType, DeclaredClass};
+
+    declare_class!(
+        struct RegistryTestObject;
+
+        unsafe impl ClassType for RegistryTestObject {
+            type Super = NSObject;
+            type Mutability = mutability::InteriorMutable;
+            const NAME: &'static str = "__objc2RegistryTestObject";
+        }
+
+        impl DeclaredClass for RegistryTestObject {}
+    );
+
+    extern_class!(
+        struct RegistryTestExtern;
+
+        unsafe impl ClassType for RegistryTestExtern {
+            type Super = NSObject;
+            type Mutability = mutability::InteriorMutable;
+            const NAME: &'static str = "NSObject";
+        }
+    );
+
+    #[test]
+    fn test_declared() {
+        let info = get("__objc2RegistryTestObject").unwrap();
+        assert_eq!(info.kind(), ClassKind::Declared);
+        assert_eq!(
+            info.type_name(),
+            "objc2::class_registry::tests::RegistryTestObject"
+        );
+        assert_eq!(info.class(), RegistryTestObject::class());
+    }
+
+    #[test]
+    fn test_extern() {
+        let info = classes()
+            .find(|info| info.type_name().ends_with("::RegistryTestExtern"))
+            .unwrap();
+        assert_eq!(info.kind(), ClassKind::Extern);
+        assert_eq!(info.name(), "NSObject");
+        assert_eq!(info.class(), NSObject::class());
+    }
+
+    #[test]
+    fn test_unknown() {
+        assert!(get("__objc2NotARegisteredClass").is_none());
+    }
+}
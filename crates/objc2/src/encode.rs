@@ -73,6 +73,8 @@
 
 use core::cell::{Cell, UnsafeCell};
 use core::ffi::c_void;
+use core::fmt;
+use core::marker::{PhantomData, PhantomPinned};
 use core::mem::{self, ManuallyDrop, MaybeUninit};
 use core::num::{
     NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU16, NonZeroU32,
@@ -885,6 +887,184 @@ encode_fn_pointer_impl!(A, B, C, D, E, F, G, H, I, J);
 encode_fn_pointer_impl!(A, B, C, D, E, F, G, H, I, J, K);
 encode_fn_pointer_impl!(A, B, C, D, E, F, G, H, I, J, K, L);
 
+/// A C++ class whose name can be used in type-encodings.
+///
+/// This is used together with [`CppOpaque`] to bind methods that take or
+/// return pointers to C++ objects.
+///
+///
+/// # Safety
+///
+/// [`NAME`][Self::NAME] must be the name that the Objective-C++ compiler uses
+/// when encoding pointers to the class, which includes the namespace and any
+/// template arguments, e.g. `MTL::Device` or
+/// `vector<int, std::allocator<int> >`.
+pub unsafe trait CppType {
+    /// The name of the C++ class.
+    const NAME: &'static str;
+}
+
+/// An opaque C++ object.
+///
+/// Objective-C++ encodes pointers to C++ classes as pointers to structs with
+/// the class' name, e.g. `^{ClassName=}`, and such pointers are usually not
+/// meant to be inspected from Rust, so this type allows you to bind them as
+/// `*mut CppOpaque<T>` (or `&CppOpaque<T>`) with a matching encoding, without
+/// having to know the layout of the class.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::encode::{CppOpaque, CppType, Encoding, RefEncode};
+///
+/// enum Device {}
+///
+/// // SAFETY: The name matches the C++ class `ns::Device`.
+/// unsafe impl CppType for Device {
+///     const NAME: &'static str = "ns::Device";
+/// }
+///
+/// let encoding = <CppOpaque<Device>>::ENCODING_REF;
+/// assert_eq!(encoding.to_string(), "^{ns::Device=}");
+/// // The contents of the class are ignored when comparing.
+/// assert!(encoding.equivalent_to_str("^{ns::Device=^vQ}"));
+/// ```
+#[repr(C)]
+pub struct CppOpaque<T: ?Sized> {
+    // Same workaround as for other opaque types, until extern types are
+    // available.
+    _priv: [u8; 0],
+    // Not `Send`, `Sync` nor `Unpin`, since we don't know anything about the
+    // object.
+    _p: PhantomData<(*mut T, PhantomPinned, UnsafeCell<()>)>,
+}
+
+impl<T: ?Sized> fmt::Debug for CppOpaque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CppOpaque").finish_non_exhaustive()
+    }
+}
+
+// SAFETY: The name is correct, as ensured by `CppType`, and the contents of
+// the class are left out, which is equivalent to any other contents.
+unsafe impl<T: CppType + ?Sized> RefEncode for CppOpaque<T> {
+    const ENCODING_REF: Encoding = Encoding::Pointer(&Encoding::Struct(T::NAME, &[]));
+}
+
+/// Get a pointer to the elements of a slice and its length, for passing to
+/// functions that take a C array, e.g. `CGContextAddLines`.
+///
+/// Such functions usually take the items as `*const U` with the length in a
+/// separate parameter, and `U` is inferred from how the pointer is used, so
+/// you will rarely need to specify the types explicitly. This is commonly the
+/// same type as `T`, but may also be a different type with the same
+/// encoding (e.g. a geometry type from another crate).
+///
+/// Passing the wrong type as a C array is a common source of memory
+/// unsafety, since the callee will index into the array with its own idea of
+/// the element size. This helper centralizes the checks for that.
+///
+/// See [`cast_mut_slice_to_objc`] for functions that write to the array.
+///
+///
+/// # Panics
+///
+/// Panics if the encodings of `T` and `U` are not equivalent, if the two
+/// types differ in size or alignment, or if the size or alignment of `T` do
+/// not match the C layout of its encoding (when that can be determined; it
+/// cannot e.g. for types containing bitfields or `long double`).
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::encode::{cast_slice_to_objc, Encode, Encoding};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// struct CGPoint {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// unsafe impl Encode for CGPoint {
+///     const ENCODING: Encoding = Encoding::Struct("CGPoint", &[f64::ENCODING, f64::ENCODING]);
+/// }
+///
+/// # unsafe extern "C" fn CGContextAddLines(ctx: *mut u8, points: *const CGPoint, count: usize) {}
+/// # let ctx = std::ptr::null_mut();
+/// let points = [CGPoint { x: 0.0, y: 0.0 }, CGPoint { x: 1.0, y: 2.0 }];
+/// let (ptr, len) = cast_slice_to_objc(&points);
+/// unsafe { CGContextAddLines(ctx, ptr, len) };
+/// ```
+///
+/// A type with a different layout is rejected.
+///
+/// ```should_panic
+/// use objc2::encode::cast_slice_to_objc;
+///
+/// let items: [u32; 2] = [1, 2];
+/// let (_ptr, _len) = cast_slice_to_objc::<u32, u64>(&items);
+/// ```
+#[track_caller]
+pub fn cast_slice_to_objc<T: Encode, U: Encode>(slice: &[T]) -> (*const U, usize) {
+    assert_slice_cast::<T, U>();
+    (slice.as_ptr().cast(), slice.len())
+}
+
+/// Get a mutable pointer to the elements of a slice and its length, for
+/// passing to functions that write to a C array.
+///
+/// See [`cast_slice_to_objc`] for details.
+///
+///
+/// # Panics
+///
+/// Panics if the layout checks in [`cast_slice_to_objc`] fail.
+#[track_caller]
+pub fn cast_mut_slice_to_objc<T: Encode, U: Encode>(slice: &mut [T]) -> (*mut U, usize) {
+    assert_slice_cast::<T, U>();
+    (slice.as_mut_ptr().cast(), slice.len())
+}
+
+#[track_caller]
+fn assert_slice_cast<T: Encode, U: Encode>() {
+    assert!(
+        T::ENCODING.equivalent_to(&U::ENCODING),
+        "cannot cast slice with encoding {} to slice with encoding {}",
+        T::ENCODING,
+        U::ENCODING,
+    );
+    assert!(
+        mem::size_of::<T>() == mem::size_of::<U>() && mem::align_of::<T>() == mem::align_of::<U>(),
+        "cannot cast slice of types with different layouts, {} has size {} and alignment {}, while {} has size {} and alignment {}",
+        T::ENCODING,
+        mem::size_of::<T>(),
+        mem::align_of::<T>(),
+        U::ENCODING,
+        mem::size_of::<U>(),
+        mem::align_of::<U>(),
+    );
+    if let Some((size, align)) = c_layout(&T::ENCODING) {
+        assert!(
+            mem::size_of::<T>() == size && mem::align_of::<T>() == align,
+            "the layout of the type with encoding {} does not match the encoding, expected size {} and alignment {}, found size {} and alignment {}",
+            T::ENCODING,
+            size,
+            align,
+            mem::size_of::<T>(),
+            mem::align_of::<T>(),
+        );
+    }
+}
+
+/// The size and alignment of the C type with the given encoding on the
+/// current target, if it can be determined.
+fn c_layout(encoding: &Encoding) -> Option<(usize, usize)> {
+    Some((encoding.size()?, encoding.alignment()?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1031,4 +1211,91 @@ mod tests {
         assert_eq!(<(i8,)>::ENCODINGS, &[i8::ENCODING]);
         assert_eq!(<(i8, u32)>::ENCODINGS, &[i8::ENCODING, u32::ENCODING]);
     }
+
+    #[test]
+    fn test_cpp_opaque() {
+        enum Vector {}
+
+        unsafe impl CppType for Vector {
+            const NAME: &'static str = "vector<int, std::allocator<int> >";
+        }
+
+        let expected = Encoding::Pointer(&Encoding::Struct(Vector::NAME, &[]));
+        assert_eq!(<CppOpaque<Vector>>::ENCODING_REF, expected);
+        assert_eq!(<&CppOpaque<Vector>>::ENCODING, expected);
+        assert_eq!(<Option<&mut CppOpaque<Vector>>>::ENCODING, expected);
+        assert!(expected.equivalent_to_str("^{vector<int, std::allocator<int> >=^i^i^i}"));
+    }
+
+    #[repr(C)]
+    struct Point {
+        x: f64,
+        y: f32,
+    }
+
+    unsafe impl Encode for Point {
+        const ENCODING: Encoding = Encoding::Struct("Point", &[f64::ENCODING, f32::ENCODING]);
+    }
+
+    unsafe impl RefEncode for Point {
+        const ENCODING_REF: Encoding = Encoding::Pointer(&Self::ENCODING);
+    }
+
+    crate::assert_encoding!(i32, "i");
+    crate::assert_encoding!(*const i8, "*");
+    crate::assert_encoding!(Point, "{Point=df}");
+    crate::assert_encoding!(*mut Point, "^{Point=df}");
+    crate::assert_encoding!(*mut *mut Point, "^^{Point}");
+    crate::assert_encoding!([Point; 10], "[10{Point=df}]");
+    crate::assert_encoding!(Option<&crate::runtime::AnyObject>, "@");
+
+    #[test]
+    fn test_c_layout() {
+        assert_eq!(c_layout(&Point::ENCODING), Some((16, 8)));
+        assert_eq!(c_layout(&<[Point; 3]>::ENCODING), Some((48, 8)));
+        assert_eq!(c_layout(&i16::ENCODING), Some((2, 2)));
+        assert_eq!(c_layout(&Encoding::Struct("Opaque", &[])), None);
+        assert_eq!(
+            c_layout(&Encoding::Union("U", &[Encoding::Char, Encoding::Int])),
+            Some((4, 4))
+        );
+        assert_eq!(
+            c_layout(&Encoding::Struct(
+                "S",
+                &[Encoding::Char, Encoding::BitField(3, None)]
+            )),
+            Some((4, 4))
+        );
+        assert_eq!(c_layout(&Encoding::LongDouble), None);
+    }
+
+    #[test]
+    fn test_cast_slice_to_objc() {
+        #[repr(transparent)]
+        struct MyPoint(Point);
+
+        unsafe impl Encode for MyPoint {
+            const ENCODING: Encoding =
+                Encoding::Struct("Point", &[Encoding::Double, Encoding::Float]);
+        }
+
+        let mut points = [
+            MyPoint(Point { x: 1.0, y: 2.0 }),
+            MyPoint(Point { x: 3.0, y: 4.0 }),
+        ];
+        let (ptr, len) = cast_slice_to_objc::<MyPoint, Point>(&points);
+        assert_eq!(len, 2);
+        assert_eq!(unsafe { (*ptr.add(1)).x }, 3.0);
+
+        let (ptr, len) = cast_mut_slice_to_objc::<MyPoint, Point>(&mut points);
+        assert_eq!(len, 2);
+        unsafe { (*ptr).y = 5.0 };
+        assert_eq!(points[0].0.y, 5.0);
+    }
+
+    #[test]
+    #[should_panic = "cannot cast slice with encoding"]
+    fn test_cast_slice_to_objc_different_encoding() {
+        let _ = cast_slice_to_objc::<i32, u32>(&[1, 2, 3]);
+    }
 }
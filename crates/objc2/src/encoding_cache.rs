@@ -0,0 +1,175 @@
+//! # Caching of parsed type encodings.
+//!
+//! Verifying a message (which is done on every `msg_send!` when debug
+//! assertions are enabled, as well as by [`AnyClass::verify_sel`]) requires
+//! parsing the type encoding of the method. Most programs only use a
+//! limited set of distinct signatures, so with this module, the parsed
+//! encodings are stored in a global cache keyed by the raw type encoding
+//! string, such that each signature is only parsed once.
+//!
+//! Entries are never evicted, since the amount of distinct signatures is
+//! bounded by the methods in the process, but the cache can be emptied with
+//! [`clear`]. Use [`stats`] to see how effective the cache is.
+//!
+//! This module is only available when the `"encoding-cache"` Cargo feature
+//! is enabled.
+//!
+//! [`AnyClass::verify_sel`]: crate::runtime::AnyClass::verify_sel
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use objc2::encoding_cache;
+//! use objc2::runtime::{AnyClass, Bool, NSObject};
+//! use objc2::{sel, ClassType};
+//!
+//! let cls = NSObject::class();
+//! for _ in 0..10 {
+//!     cls.verify_sel::<(&AnyClass,), Bool>(sel!(isKindOfClass:)).unwrap();
+//! }
+//!
+//! let stats = encoding_cache::stats();
+//! assert!(stats.hits >= 9);
+//! ```
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::encode::EncodingBox;
+use crate::runtime::MethodEncodingIter;
+
+/// A parsed type encoding, with the encoding and stack offset of the return
+/// type and each argument.
+pub(crate) type ParsedTypes = Arc<[(EncodingBox, Option<isize>)]>;
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<Box<str>, ParsedTypes>,
+    hits: usize,
+    misses: usize,
+}
+
+static CACHE: AtomicPtr<Mutex<Cache>> = AtomicPtr::new(ptr::null_mut());
+
+fn lock() -> MutexGuard<'static, Cache> {
+    let mut cache = CACHE.load(Ordering::Acquire);
+    if cache.is_null() {
+        let new = Box::into_raw(Box::new(Mutex::new(Cache::default())));
+        let res = CACHE.compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire);
+        cache = match res {
+            Ok(_) => new,
+            Err(existing) => {
+                // SAFETY: Another thread initialized the cache first, so our
+                // allocation was never shared.
+                drop(unsafe { Box::from_raw(new) });
+                existing
+            }
+        };
+    }
+    // SAFETY: The cache is never deallocated once initialized.
+    let cache = unsafe { &*cache };
+    // The cache is always left in a consistent state, so we can ignore
+    // poisoning.
+    cache.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Get the parsed form of the given method or block type encoding, parsing
+/// and caching it if it wasn't already in the cache.
+///
+/// Returns `None` if the encoding failed to parse, in which case the caller
+/// should parse it again itself to get the error. Errors are not cached.
+pub(crate) fn method_types(types: &str) -> Option<ParsedTypes> {
+    {
+        let mut cache = lock();
+        if let Some(parsed) = cache.entries.get(types).cloned() {
+            cache.hits += 1;
+            return Some(parsed);
+        }
+        cache.misses += 1;
+    }
+
+    // Parse without holding the lock.
+    let parsed: Vec<_> = MethodEncodingIter::new(types)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let parsed: ParsedTypes = parsed.into();
+
+    let mut cache = lock();
+    // Another thread may have inserted the same encoding in the meantime;
+    // in that case, we just use theirs.
+    let parsed = cache.entries.entry(types.into()).or_insert(parsed).clone();
+    Some(parsed)
+}
+
+/// Statistics about the encoding cache, see [`stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct CacheStats {
+    /// The number of lookups that were already in the cache.
+    pub hits: usize,
+    /// The number of lookups that required parsing the encoding.
+    pub misses: usize,
+    /// The number of distinct encodings currently in the cache.
+    pub entries: usize,
+}
+
+/// Get statistics about the encoding cache since the process started, or
+/// since the last call to [`clear`].
+pub fn stats() -> CacheStats {
+    let cache = lock();
+    CacheStats {
+        hits: cache.hits,
+        misses: cache.misses,
+        entries: cache.entries.len(),
+    }
+}
+
+/// Remove all entries from the encoding cache, and reset its statistics.
+///
+/// Encodings that are currently in use elsewhere are kept alive until they
+/// are no longer used.
+pub fn clear() {
+    let mut cache = lock();
+    cache.entries = HashMap::new();
+    cache.hits = 0;
+    cache.misses = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached() {
+        let types = "{__objc2CacheTest=iQ}24@0:8@16";
+        let first = method_types(types).unwrap();
+        assert_eq!(first.len(), 4);
+        assert_eq!(first[0].1, Some(24));
+        assert_eq!(first[3], (EncodingBox::Object, Some(16)));
+
+        let hits = stats().hits;
+        let second = method_types(types).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(stats().hits > hits);
+        assert!(stats().entries >= 1);
+
+        clear();
+        assert!(!lock().entries.contains_key(types));
+        let third = method_types(types).unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
+        assert_eq!(first, third);
+    }
+
+    #[test]
+    fn test_invalid_not_cached() {
+        let types = "v24@0:8{__objc2CacheTest";
+        assert!(method_types(types).is_none());
+        assert!(method_types(types).is_none());
+        assert!(!lock().entries.contains_key(types));
+    }
+}
@@ -0,0 +1,260 @@
+//! # A unified error type for Objective-C errors.
+//!
+//! Objective-C code reports failure in two different ways: by throwing an
+//! exception (usually an `NSException`), which can be caught with
+//! [`exception::catch`], or by returning an `NSError` through an out
+//! parameter, which [`msg_send!`] and [`msg_send_id!`] convert into a
+//! [`Result`] when the last argument is the special marker `_`.
+//!
+//! Application code often wants to handle both cases uniformly, which is what
+//! the [`ObjcError`] type in this module is for.
+//!
+//! The macros and functions above do not return [`ObjcError`] themselves;
+//! they still return their own error types (`Id<E>` and
+//! `Option<Id<Exception>>` respectively), which can then be converted into an
+//! [`ObjcError`] with `?` or [`From`]. This allows still using a more
+//! specific error type such as `Id<NSError>` where that is desired.
+//!
+#![cfg_attr(
+    feature = "exception",
+    doc = "[`exception::catch`]: crate::exception::catch"
+)]
+#![cfg_attr(
+    not(feature = "exception"),
+    doc = "[`exception::catch`]: crate::exception#feature-not-enabled"
+)]
+//! [`msg_send!`]: crate::msg_send
+//! [`msg_send_id!`]: crate::msg_send_id
+
+use core::fmt;
+use std::error::Error;
+
+use crate::exception::Exception;
+use crate::rc::{autoreleasepool_leaking, Id};
+use crate::runtime::__nsstring::nsstring_to_str;
+use crate::runtime::{AnyClass, AnyObject, NSObject, NSObjectProtocol};
+use crate::{msg_send_id, sel, Message};
+
+/// Either a caught exception, or an `NSError`.
+///
+/// This implements [`From`] for both the error type returned by
+/// [`exception::catch`] and the `Id<AnyObject>` error that [`msg_send!`] and
+/// [`msg_send_id!`] return when using the `_` error marker, so you can use
+/// the `?` operator to bubble up either of them. The macros do not return
+/// this type directly.
+///
+/// If you have a more specific error type, such as `Id<NSError>`, use
+/// [`ObjcError::from_error`].
+///
+#[cfg_attr(
+    feature = "exception",
+    doc = "[`exception::catch`]: crate::exception::catch"
+)]
+#[cfg_attr(
+    not(feature = "exception"),
+    doc = "[`exception::catch`]: crate::exception#feature-not-enabled"
+)]
+/// [`msg_send!`]: crate::msg_send
+/// [`msg_send_id!`]: crate::msg_send_id
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2::error::ObjcError;
+/// use objc2::runtime::AnyObject;
+/// use objc2::msg_send;
+///
+/// fn preflight(obj: &AnyObject) -> Result<(), ObjcError> {
+///     // The error type is inferred to be `Id<AnyObject>`.
+///     unsafe { msg_send![obj, preflightAndReturnError: _] }?;
+///     Ok(())
+/// }
+/// ```
+#[non_exhaustive]
+pub enum ObjcError {
+    /// An exception was thrown.
+    ///
+    /// This is `None` if the thrown exception was `nil`.
+    Exception(Option<Id<Exception>>),
+    /// An error was returned through an `NSError**` out parameter.
+    ///
+    /// This is usually an `NSError`, though this is not checked.
+    Error(Id<AnyObject>),
+}
+
+impl ObjcError {
+    /// Create an error from an object returned through an `NSError**` out
+    /// parameter.
+    #[inline]
+    pub fn from_error<E: Message>(error: Id<E>) -> Self {
+        // SAFETY: All objects can be safely viewed as `AnyObject`.
+        Self::Error(unsafe { Id::cast(error) })
+    }
+
+    /// The exception, if this was created from a caught exception.
+    pub fn exception(&self) -> Option<&Exception> {
+        match self {
+            Self::Exception(exception) => exception.as_deref(),
+            Self::Error(_) => None,
+        }
+    }
+
+    /// The error object, if this was created from an `NSError**` out
+    /// parameter.
+    pub fn error(&self) -> Option<&AnyObject> {
+        match self {
+            Self::Exception(_) => None,
+            Self::Error(error) => Some(error),
+        }
+    }
+
+    fn as_nserror(&self) -> Option<&NSObject> {
+        let error = self.error()?;
+        if !error.class().responds_to(sel!(isKindOfClass:)) {
+            return None;
+        }
+        let error: *const AnyObject = error;
+        // SAFETY: We only use `isKindOfClass:` on NSObject
+        let error = unsafe { error.cast::<NSObject>().as_ref().unwrap() };
+        // Get class dynamically instead of with `class!` macro
+        if error.isKindOfClass(AnyClass::get("NSError")?) {
+            Some(error)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Option<Id<Exception>>> for ObjcError {
+    #[inline]
+    fn from(exception: Option<Id<Exception>>) -> Self {
+        Self::Exception(exception)
+    }
+}
+
+// Note: This must be the only `From<Id<_>>` implementation, since otherwise
+// the error type of `msg_send!` could no longer be inferred when using `?`.
+impl From<Id<AnyObject>> for ObjcError {
+    #[inline]
+    fn from(error: Id<AnyObject>) -> Self {
+        Self::Error(error)
+    }
+}
+
+impl fmt::Debug for ObjcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exception(exception) => f.debug_tuple("Exception").field(exception).finish(),
+            Self::Error(error) => f.debug_tuple("Error").field(error).finish(),
+        }
+    }
+}
+
+impl fmt::Display for ObjcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exception(Some(exception)) => fmt::Display::fmt(exception, f),
+            Self::Exception(None) => write!(f, "nil exception"),
+            Self::Error(error) => autoreleasepool_leaking(|pool| {
+                if let Some(nserror) = self.as_nserror() {
+                    // SAFETY: `localizedDescription` is guaranteed to return
+                    // a non-NULL NSString on `NSError`.
+                    let description: Option<Id<NSObject>> =
+                        unsafe { msg_send_id![nserror, localizedDescription] };
+                    if let Some(description) = &description {
+                        // SAFETY: Just checked that this is an NSString.
+                        let description = unsafe { nsstring_to_str(description, pool) };
+                        return write!(f, "{description}");
+                    }
+                }
+                // Fall back to `AnyObject` Debug
+                write!(f, "error {error:?}")
+            }),
+        }
+    }
+}
+
+impl Error for ObjcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Exception(Some(exception)) => Some(&**exception),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::*;
+    use crate::msg_send;
+    use crate::rc::{__RcTestObject, __ThreadTestData, autoreleasepool};
+    use crate::ClassType;
+
+    fn bool_error(should_error: bool) -> Result<(), ObjcError> {
+        let cls = __RcTestObject::class();
+        unsafe { msg_send![cls, boolAndShouldError: should_error, error: _] }?;
+        Ok(())
+    }
+
+    #[test]
+    fn from_msg_send_error() {
+        let mut expected = __ThreadTestData::current();
+
+        bool_error(false).unwrap();
+        expected.assert_current();
+
+        let err = autoreleasepool(|_pool| {
+            let err = bool_error(true).unwrap_err();
+            expected.alloc += 1;
+            expected.init += 1;
+            expected.autorelease += 1;
+            expected.retain += 1;
+            expected.assert_current();
+            err
+        });
+        expected.release += 1;
+        expected.assert_current();
+
+        assert!(err.exception().is_none());
+        assert!(err.error().is_some());
+        assert!(err.source().is_none());
+        // Not an NSError, so the description falls back to `Debug`
+        assert!(format!("{err}").starts_with("error "));
+
+        drop(err);
+        expected.release += 1;
+        expected.drop += 1;
+        expected.assert_current();
+    }
+
+    #[test]
+    fn from_nil_exception() {
+        let err = ObjcError::from(None::<Id<Exception>>);
+        assert!(err.exception().is_none());
+        assert!(err.error().is_none());
+        assert_eq!(format!("{err}"), "nil exception");
+        assert_eq!(format!("{err:?}"), "Exception(None)");
+    }
+
+    #[test]
+    #[cfg(feature = "exception")]
+    fn from_caught_exception() {
+        use crate::exception::{catch, throw};
+        use crate::runtime::NSObject;
+
+        let obj = NSObject::new();
+        // TODO: Investigate why this is required on GNUStep!
+        let _obj2 = obj.clone();
+        // SAFETY: The object is never used as an `Exception` other than being
+        // thrown and caught.
+        let exception: Id<Exception> = unsafe { Id::cast(obj) };
+        let res = unsafe { catch(|| throw(exception)) };
+        let err: ObjcError = res.unwrap_err().into();
+        assert!(err.exception().is_some());
+        assert!(err.source().is_some());
+        assert_eq!(format!("{err}"), "unknown exception");
+    }
+}
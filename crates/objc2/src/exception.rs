@@ -6,6 +6,23 @@
 //! [`msg_send!`] in a `@catch` and panics if an exception is caught,
 //! preventing Objective-C from unwinding into Rust.
 //!
+//! Additionally, the `"unwind"` feature (which implies `"catch-all"`) makes
+//! this translation work in both directions, at every boundary between
+//! Objective-C and Rust that this crate controls:
+//! - Exceptions thrown in [`msg_send!`] become Rust panics whose payload is
+//!   an [`ExceptionPanic`] containing the exception.
+//! - Rust panics that escape a method implemented with [`declare_class!`]
+//!   (or a block from `block2` with its `"unwind"` feature) are caught and
+//!   thrown as Objective-C exceptions instead, see [`panic_into_exception`].
+//!   A panic that was caused by an exception re-throws the original
+//!   exception.
+//!
+//! Since the exceptions then unwind through the functions that implement
+//! those methods and blocks, they are declared `extern "C-unwind"`. The
+//! `"unwind"` feature thus implies `"unstable-c-unwind"`, and requires a
+//! nightly compiler; crates using [`declare_class!`] must also be able to
+//! use the `"C-unwind"` ABI (stable since Rust 1.81).
+//!
 //! Most of the functionality in this module is only available when the
 //! `"exception"` feature is enabled.
 //!
@@ -15,6 +32,7 @@
 //! - [Exception Handling in LLVM](https://llvm.org/docs/ExceptionHandling.html)
 //!
 //! [`msg_send!`]: crate::msg_send
+//! [`declare_class!`]: crate::declare_class
 
 // TODO: Test this with panic=abort, and ensure that the code-size is
 // reasonable in that case.
@@ -29,10 +47,19 @@ use core::panic::RefUnwindSafe;
 use core::panic::UnwindSafe;
 #[cfg(feature = "exception")]
 use core::ptr;
+#[cfg(feature = "unwind")]
+use std::any::Any;
+#[cfg(feature = "unwind")]
+use std::boxed::Box;
 use std::error::Error;
+use std::string::String;
+use std::vec::Vec;
 
 use crate::encode::{Encoding, RefEncode};
-#[cfg(feature = "exception")]
+#[cfg(any(
+    feature = "exception",
+    all(feature = "apple", not(all(target_os = "macos", target_arch = "x86")))
+))]
 use crate::ffi;
 use crate::rc::{autoreleasepool_leaking, Id};
 use crate::runtime::__nsstring::nsstring_to_str;
@@ -96,6 +123,11 @@ extern_methods!(
         // Returns NSString
         #[method_id(reason)]
         unsafe fn reason(&self) -> Option<Id<NSObject>>;
+
+        // Only safe on NSException
+        // Returns NSArray<NSString>
+        #[method_id(callStackSymbols)]
+        unsafe fn call_stack_symbols(&self) -> Option<Id<NSObject>>;
     }
 );
 
@@ -228,6 +260,13 @@ unsafe fn try_no_ret<F: FnOnce()>(closure: F) -> Result<(), Option<Id<Exception>
     let context: *mut Option<F> = &mut closure;
     let context = context.cast();
 
+    // The function is called from Objective-C, so it must be signed on
+    // `arm64e`.
+    //
+    // SAFETY: Rust does not sign function pointers itself, and signing
+    // preserves that the pointer is non-null.
+    let f = unsafe { mem::transmute(ffi::ptrauth_sign_function(f as *const c_void, 0)) };
+
     let mut exception = ptr::null_mut();
     let success = unsafe { ffi::try_catch(f, context, &mut exception) };
 
@@ -293,6 +332,363 @@ pub unsafe fn catch<R>(
     result.map(|()| value.unwrap_or_else(|| unreachable!()))
 }
 
+/// The payload of a Rust panic that was caused by an Objective-C exception.
+///
+/// When the `"unwind"` feature is enabled, exceptions thrown in
+/// [`msg_send!`] and similar are caught, and turned into a Rust panic with
+/// this as the payload (using [`panic_any`]). This allows retrieving the
+/// original exception with [`catch_unwind`] and [`downcast`].
+///
+/// If the panic later propagates out of a method declared with
+/// [`declare_class!`] or a block from `block2`, the original exception is
+/// re-thrown, so Objective-C callers see the exception unchanged.
+///
+/// Note that the default panic hook cannot print this payload, so it will
+/// display as `Box<dyn Any>`; use [`std::panic::set_hook`] if you want the
+/// exception in your panic messages.
+///
+/// [`msg_send!`]: crate::msg_send
+/// [`panic_any`]: std::panic::panic_any
+/// [`catch_unwind`]: std::panic::catch_unwind
+/// [`downcast`]: std::boxed::Box::downcast
+/// [`declare_class!`]: crate::declare_class
+///
+///
+/// # Example
+///
+/// ```
+/// use std::panic::{catch_unwind, AssertUnwindSafe};
+///
+/// use objc2::exception::ExceptionPanic;
+/// use objc2::rc::Id;
+/// use objc2::runtime::NSObject;
+/// use objc2::msg_send_id;
+///
+/// let obj = NSObject::new();
+/// let payload = catch_unwind(AssertUnwindSafe(|| {
+///     // `NSObject` does not implement `NSCopying`.
+///     let _: Id<NSObject> = unsafe { msg_send_id![&obj, copy] };
+/// }))
+/// .unwrap_err();
+///
+/// let payload = payload.downcast::<ExceptionPanic>().unwrap();
+/// assert!(payload.exception().is_some());
+/// ```
+#[cfg(feature = "unwind")]
+pub struct ExceptionPanic(Option<Id<Exception>>);
+
+// SAFETY: Exceptions are thrown to be handled by whoever catches them, and
+// may already be caught on a different thread than the one they were created
+// on; in practice, they are always instances of the (thread-safe)
+// `NSException`.
+#[cfg(feature = "unwind")]
+unsafe impl Send for ExceptionPanic {}
+
+#[cfg(feature = "unwind")]
+impl ExceptionPanic {
+    /// The exception that caused the panic.
+    ///
+    /// This is `None` if the exception object was `nil`, see [`catch`].
+    pub fn exception(&self) -> Option<&Exception> {
+        self.0.as_deref()
+    }
+
+    /// Take the exception that caused the panic.
+    pub fn into_exception(self) -> Option<Id<Exception>> {
+        self.0
+    }
+}
+
+#[cfg(feature = "unwind")]
+impl fmt::Debug for ExceptionPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(exception) => write!(f, "uncaught {exception:?}"),
+            None => write!(f, "uncaught exception nil"),
+        }
+    }
+}
+
+#[cfg(feature = "unwind")]
+impl fmt::Display for ExceptionPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(exception) => write!(f, "uncaught exception: {exception}"),
+            None => write!(f, "uncaught exception nil"),
+        }
+    }
+}
+
+/// Panic with the exception as the payload.
+#[cfg(feature = "unwind")]
+#[track_caller]
+pub(crate) fn panic_with_exception(exception: Option<Id<Exception>>) -> ! {
+    std::panic::panic_any(ExceptionPanic(exception))
+}
+
+/// The name of exceptions created from Rust panics.
+#[cfg(feature = "unwind")]
+const RUST_PANIC_NAME: &str = "RustPanic";
+
+/// Create an `NSString` from a Rust string.
+#[cfg(feature = "unwind")]
+fn nsstring_from_str(string: &str) -> Option<Id<NSObject>> {
+    use crate::msg_send_id;
+    use crate::runtime::__nsstring::UTF8_ENCODING;
+
+    let cls = AnyClass::get("NSString")?;
+    let bytes: *const c_void = string.as_ptr().cast();
+    // SAFETY: The bytes are valid UTF-8 for the given length.
+    unsafe {
+        msg_send_id![
+            msg_send_id![cls, alloc],
+            initWithBytes: bytes,
+            length: string.len(),
+            encoding: UTF8_ENCODING,
+        ]
+    }
+}
+
+/// Convert the payload of a Rust panic to an Objective-C exception.
+///
+/// If the panic was originally caused by an exception (that is, the payload
+/// is an [`ExceptionPanic`]), that exception is returned. Otherwise, a new
+/// `NSException` named `RustPanic` is created, with the panic message as the
+/// reason.
+///
+/// Returns `None` if the original exception was `nil`, or if `NSException`
+/// is not available (because Foundation is not linked).
+#[cfg(feature = "unwind")]
+pub fn exception_from_panic(payload: Box<dyn Any + Send>) -> Option<Id<Exception>> {
+    use crate::msg_send_id;
+
+    let payload = match payload.downcast::<ExceptionPanic>() {
+        Ok(payload) => return payload.into_exception(),
+        Err(payload) => payload,
+    };
+
+    let message: &str = if let Some(message) = payload.downcast_ref::<&'static str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    };
+
+    let cls = AnyClass::get("NSException")?;
+    let name = nsstring_from_str(RUST_PANIC_NAME)?;
+    let reason = nsstring_from_str(message)?;
+    let user_info: Option<&AnyObject> = None;
+    // SAFETY: The name and reason are `NSString`s, and the user info may be
+    // `nil`.
+    unsafe {
+        msg_send_id![
+            cls,
+            exceptionWithName: &*name,
+            reason: &*reason,
+            userInfo: user_info,
+        ]
+    }
+}
+
+/// Run the given closure, and turn any Rust panic that occurs into an
+/// Objective-C exception.
+///
+/// This is the inverse of the behaviour of [`msg_send!`] when the
+/// `"unwind"` feature is enabled, and is what [`declare_class!`] uses for
+/// methods implemented in Rust, since unwinding a Rust panic into
+/// Objective-C is undefined behaviour. The exception is created with
+/// [`exception_from_panic`].
+///
+/// If no exception could be created, the process is aborted.
+///
+/// [`msg_send!`]: crate::msg_send
+/// [`declare_class!`]: crate::declare_class
+///
+///
+/// # Safety
+///
+/// This may throw an Objective-C exception, see [`throw`] for the
+/// requirements for that to be sound; most notably, the caller must be
+/// Objective-C code prepared to handle exceptions, and this must be called
+/// from a function declared `extern "C-unwind"`, since unwinding out of an
+/// `extern "C"` function is undefined behaviour.
+#[cfg(feature = "unwind")]
+#[inline]
+pub unsafe fn panic_into_exception<R>(closure: impl FnOnce() -> R) -> R {
+    match std::panic::catch_unwind(core::panic::AssertUnwindSafe(closure)) {
+        Ok(value) => value,
+        Err(payload) => unsafe { throw_panic(payload) },
+    }
+}
+
+#[cfg(feature = "unwind")]
+#[cold]
+#[inline(never)]
+unsafe fn throw_panic(payload: Box<dyn Any + Send>) -> ! {
+    match exception_from_panic(payload) {
+        // SAFETY: Upheld by the caller.
+        Some(exception) => unsafe { throw(exception) },
+        None => {
+            std::eprintln!("failed creating exception from Rust panic, aborting");
+            std::process::abort()
+        }
+    }
+}
+
+/// A report of an Objective-C exception that was not caught, see
+/// [`install_uncaught_exception_handler`].
+///
+/// This can also be created from any exception with
+/// [`UncaughtExceptionReport::new`], e.g. for logging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UncaughtExceptionReport {
+    name: Option<String>,
+    reason: Option<String>,
+    call_stack_symbols: Vec<String>,
+}
+
+impl UncaughtExceptionReport {
+    /// Gather the name, reason and call stack of the exception.
+    ///
+    /// These are only available if the exception is an `NSException`.
+    pub fn new(exception: &Exception) -> Self {
+        use crate::{msg_send, msg_send_id};
+
+        let mut report = Self {
+            name: None,
+            reason: None,
+            call_stack_symbols: Vec::new(),
+        };
+        if let Some(true) = exception.is_nsexception() {
+            autoreleasepool_leaking(|pool| {
+                // SAFETY: Just checked that the object is an NSException.
+                let (name, reason) = unsafe { (exception.name(), exception.reason()) };
+                // SAFETY: `name` and `reason` are guaranteed to be NSString.
+                report.name = name.map(|name| unsafe { nsstring_to_str(&name, pool) }.into());
+                report.reason =
+                    reason.map(|reason| unsafe { nsstring_to_str(&reason, pool) }.into());
+
+                // SAFETY: `callStackSymbols` returns an array of NSString.
+                if let Some(symbols) = unsafe { exception.call_stack_symbols() } {
+                    let count: usize = unsafe { msg_send![&symbols, count] };
+                    for i in 0..count {
+                        let symbol: Id<NSObject> =
+                            unsafe { msg_send_id![&symbols, objectAtIndex: i] };
+                        let symbol = unsafe { nsstring_to_str(&symbol, pool) };
+                        report.call_stack_symbols.push(symbol.into());
+                    }
+                }
+            });
+        }
+        report
+    }
+
+    /// The name of the exception, e.g. `NSInvalidArgumentException`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The reason that the exception was thrown, e.g. `-[NSObject foo]:
+    /// unrecognized selector sent to instance 0x...`.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// The symbolicated call stack from where the exception was thrown,
+    /// one frame per line.
+    pub fn call_stack_symbols(&self) -> &[String] {
+        &self.call_stack_symbols
+    }
+}
+
+impl fmt::Display for UncaughtExceptionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "uncaught Objective-C exception")?;
+        if let Some(name) = &self.name {
+            write!(f, " `{name}`")?;
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, ": {reason}")?;
+        }
+        if !self.call_stack_symbols.is_empty() {
+            write!(f, "\ncall stack:")?;
+            for symbol in &self.call_stack_symbols {
+                write!(f, "\n{symbol}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The handler that was installed before ours.
+#[cfg(all(feature = "apple", not(all(target_os = "macos", target_arch = "x86"))))]
+static PREVIOUS_HANDLER: std::sync::atomic::AtomicPtr<core::ffi::c_void> =
+    std::sync::atomic::AtomicPtr::new(core::ptr::null_mut());
+
+/// Install a handler for Objective-C exceptions that are not caught, which
+/// turns them into a readable Rust panic before the process is terminated.
+///
+/// Such exceptions are usually caused by programmer errors, e.g. sending a
+/// message to an object that does not respond to it ("unrecognized selector
+/// sent to instance"). By default, the runtime logs the exception and
+/// aborts, which may be hard to relate to the Rust code that caused it.
+///
+/// With this installed, an [`UncaughtExceptionReport`] of the exception is
+/// used as the message of a panic, so that it is reported by the panic hook
+/// (which may be customized with [`std::panic::set_hook`], e.g. to send it
+/// to a crash reporter). The panic is then caught, and the previously
+/// installed handler (which includes any handler set with
+/// `NSSetUncaughtExceptionHandler`) is run, before the process is aborted.
+///
+/// Calling this multiple times has no effect.
+///
+/// Only available on Apple platforms (except 32-bit macOS).
+#[cfg(all(feature = "apple", not(all(target_os = "macos", target_arch = "x86"))))]
+#[doc(alias = "objc_setUncaughtExceptionHandler")]
+#[doc(alias = "NSSetUncaughtExceptionHandler")]
+pub fn install_uncaught_exception_handler() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+
+    extern "C" fn handler(exception: *mut ffi::objc_object) {
+        // SAFETY: The runtime gives us a valid exception object (or `nil`).
+        let report =
+            unsafe { exception.cast::<Exception>().as_ref() }.map(UncaughtExceptionReport::new);
+        let _ = std::panic::catch_unwind(move || match report {
+            Some(report) => panic!("{report}"),
+            None => panic!("uncaught Objective-C exception nil"),
+        });
+
+        let previous = PREVIOUS_HANDLER.load(std::sync::atomic::Ordering::Acquire);
+        if !previous.is_null() {
+            // SAFETY: The pointer was returned by the runtime, and is thus
+            // signed on `arm64e`.
+            let previous = unsafe { ffi::ptrauth_auth_function(previous, 0) };
+            let previous: ffi::objc_uncaught_exception_handler =
+                unsafe { core::mem::transmute(previous) };
+            // SAFETY: The previous handler expects the same exception.
+            unsafe { previous(exception) };
+        }
+
+        std::process::abort()
+    }
+
+    INSTALL.call_once(|| {
+        // SAFETY: Rust does not sign function pointers itself, and signing
+        // preserves that the pointer is non-null.
+        let handler: ffi::objc_uncaught_exception_handler = unsafe {
+            core::mem::transmute(ffi::ptrauth_sign_function(
+                handler as *const core::ffi::c_void,
+                0,
+            ))
+        };
+        // SAFETY: The handler has the correct signature, and may be called
+        // from any thread.
+        let previous = unsafe { ffi::objc_setUncaughtExceptionHandler(handler) };
+        PREVIOUS_HANDLER.store(previous as *mut _, std::sync::atomic::Ordering::Release);
+    });
+}
+
 #[cfg(test)]
 #[cfg(feature = "exception")]
 mod tests {
@@ -354,6 +750,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_uncaught_exception_report() {
+        let obj = AssertUnwindSafe(NSObject::new());
+        let result = unsafe {
+            catch(|| {
+                let _: Id<NSObject> = msg_send_id![&*obj, copy];
+            })
+        };
+        let err = result.unwrap_err().unwrap();
+        let report = UncaughtExceptionReport::new(&err);
+
+        assert_eq!(report.name(), Some("NSInvalidArgumentException"));
+        assert!(report
+            .reason()
+            .unwrap()
+            .contains("unrecognized selector sent to instance"));
+        assert!(!report.call_stack_symbols().is_empty());
+        assert!(report
+            .to_string()
+            .starts_with("uncaught Objective-C exception `NSInvalidArgumentException`: "));
+    }
+
+    #[test]
+    fn test_uncaught_exception_report_object() {
+        let obj = NSObject::new();
+        // SAFETY: Any object can be used as an exception.
+        let exception: Id<Exception> = unsafe { Id::cast(obj) };
+        let report = UncaughtExceptionReport::new(&exception);
+
+        assert_eq!(report.name(), None);
+        assert_eq!(report.reason(), None);
+        assert!(report.call_stack_symbols().is_empty());
+        assert_eq!(report.to_string(), "uncaught Objective-C exception");
+    }
+
     #[test]
     fn test_throw_catch_object() {
         let obj = NSObject::new();
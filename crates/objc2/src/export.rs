@@ -0,0 +1,621 @@
+//! # Exporting class metadata for use from Swift and Objective-C.
+//!
+//! Classes declared with [`declare_class!`] only exist at runtime, so the
+//! Swift and Objective-C compilers cannot see them, and a mixed project
+//! cannot call them in a type-checked manner, or subclass them.
+//!
+//! This module provides [`ClassMetadata`], which collects the information
+//! that a header needs from a registered class (the class name, superclass,
+//! adopted protocols, selectors and properties), [`Header`], which writes
+//! the `@interface` declarations for such classes, with the parameter and
+//! return types derived from the method encodings, and [`ModuleMap`], which
+//! writes the `module.modulemap` that Swift needs to import such a header.
+//!
+//! The intended usage is to run a small binary or test as part of your build
+//! that registers the classes, collects their metadata, and writes the
+//! files to a directory that the Swift or Xcode project includes.
+//!
+//! This module is only available when the `"unstable-export"` Cargo feature
+//! is enabled.
+//!
+//! [`declare_class!`]: crate::declare_class
+//!
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "apple", doc = "```")]
+#![cfg_attr(not(feature = "apple"), doc = "```no_run")]
+//! use objc2::export::{ClassMetadata, Header, ModuleMap};
+//! use objc2::runtime::NSObject;
+//! use objc2::ClassType;
+//!
+//! let metadata = ClassMetadata::new(NSObject::class());
+//! assert_eq!(metadata.name(), "NSObject");
+//!
+//! let header = Header::new()
+//!     .import("Foundation/Foundation.h")
+//!     .class(metadata);
+//! // Write `header.to_string()` to `MyRustClasses.h`
+//!
+//! let module_map = ModuleMap::new("MyRustClasses").header("MyRustClasses.h");
+//! assert_eq!(
+//!     module_map.to_string(),
+//!     "module MyRustClasses {\n    header \"MyRustClasses.h\"\n    export *\n}\n",
+//! );
+//! ```
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::encode::EncodingBox;
+use crate::runtime::{AnyClass, Method, MethodEncodingIter, Sel};
+
+/// Metadata about a method on a class.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MethodMetadata {
+    sel: Sel,
+    type_encoding: String,
+}
+
+impl MethodMetadata {
+    fn new(method: &Method) -> Self {
+        Self {
+            sel: method.name(),
+            type_encoding: method.type_encoding().to_string(),
+        }
+    }
+
+    /// The selector of the method.
+    #[inline]
+    pub fn sel(&self) -> Sel {
+        self.sel
+    }
+
+    /// The type encoding of the method, as reported by the runtime.
+    ///
+    /// This includes the return type, and the types of the receiver, the
+    /// selector and each argument, and may include stack offsets.
+    #[inline]
+    pub fn type_encoding(&self) -> &str {
+        &self.type_encoding
+    }
+}
+
+/// Metadata about a property on a class.
+///
+/// `declare_class!` does not register properties with the runtime, so these
+/// are inferred from pairs of methods that follow the naming convention for
+/// getters and setters, e.g. `foo` and `setFoo:`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PropertyMetadata {
+    name: String,
+    type_encoding: String,
+}
+
+impl PropertyMetadata {
+    /// The name of the property, which is also the selector of its getter.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The type encoding of the property's value.
+    #[inline]
+    pub fn type_encoding(&self) -> &str {
+        &self.type_encoding
+    }
+}
+
+/// Metadata about a class, as needed for declaring it in a header.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClassMetadata {
+    name: String,
+    superclass: Option<String>,
+    protocols: Vec<String>,
+    instance_methods: Vec<MethodMetadata>,
+    class_methods: Vec<MethodMetadata>,
+    properties: Vec<PropertyMetadata>,
+}
+
+impl ClassMetadata {
+    /// Collect the metadata of the given class.
+    ///
+    /// Only the methods and protocols that the class itself defines or
+    /// adopts are included, not those from its superclasses. Methods that
+    /// are internal to the runtime, like `.cxx_destruct`, are skipped.
+    pub fn new(cls: &AnyClass) -> Self {
+        let instance_methods = methods(cls);
+        let class_methods = methods(cls.metaclass());
+        let properties = properties(&instance_methods);
+        Self {
+            name: cls.name().to_string(),
+            superclass: cls
+                .superclass()
+                .map(|superclass| superclass.name().to_string()),
+            protocols: cls
+                .adopted_protocols()
+                .iter()
+                .map(|protocol| protocol.name().to_string())
+                .collect(),
+            instance_methods,
+            class_methods,
+            properties,
+        }
+    }
+
+    /// The name of the class.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name of the superclass, or [`None`] if this is a root class.
+    #[inline]
+    pub fn superclass(&self) -> Option<&str> {
+        self.superclass.as_deref()
+    }
+
+    /// The names of the protocols that the class adopts.
+    #[inline]
+    pub fn protocols(&self) -> &[String] {
+        &self.protocols
+    }
+
+    /// The instance methods defined on the class, sorted by selector.
+    #[inline]
+    pub fn instance_methods(&self) -> &[MethodMetadata] {
+        &self.instance_methods
+    }
+
+    /// The class methods defined on the class, sorted by selector.
+    #[inline]
+    pub fn class_methods(&self) -> &[MethodMetadata] {
+        &self.class_methods
+    }
+
+    /// The properties inferred from the instance methods, sorted by name.
+    ///
+    /// See [`PropertyMetadata`] for details.
+    #[inline]
+    pub fn properties(&self) -> &[PropertyMetadata] {
+        &self.properties
+    }
+}
+
+fn methods(cls: &AnyClass) -> Vec<MethodMetadata> {
+    let mut methods: Vec<_> = cls
+        .instance_methods()
+        .iter()
+        .filter(|method| !method.name().name().starts_with('.'))
+        .map(|method| MethodMetadata::new(method))
+        .collect();
+    // Sort to get a deterministic output, since the order that the runtime
+    // returns methods in is unspecified.
+    methods.sort_by(|a, b| a.sel.name().cmp(b.sel.name()));
+    methods
+}
+
+fn setter_name(getter: &str) -> Option<String> {
+    let mut chars = getter.chars();
+    let first = chars.next()?;
+    Some(format!(
+        "set{}{}:",
+        first.to_ascii_uppercase(),
+        chars.as_str()
+    ))
+}
+
+fn properties(methods: &[MethodMetadata]) -> Vec<PropertyMetadata> {
+    let mut properties = Vec::new();
+    for getter in methods {
+        let name = getter.sel.name();
+        if name.contains(':') {
+            continue;
+        }
+        let setter_name = match setter_name(name) {
+            Some(setter_name) => setter_name,
+            None => continue,
+        };
+        let setter = match methods.iter().find(|m| m.sel.name() == setter_name) {
+            Some(setter) => setter,
+            None => continue,
+        };
+        // The getter returns the value, and the setter returns `void` and
+        // takes the value as its only argument.
+        let getter_types = parse_types(getter.type_encoding());
+        let setter_types = parse_types(setter.type_encoding());
+        let value = match (getter_types.len(), getter_types.first()) {
+            (3, Some(value)) => value,
+            _ => continue,
+        };
+        if setter_types.len() == 4
+            && setter_types[0] == EncodingBox::Void
+            && setter_types[3] == *value
+        {
+            properties.push(PropertyMetadata {
+                name: name.to_string(),
+                type_encoding: value.to_string(),
+            });
+        }
+    }
+    properties
+}
+
+/// Parse a method type encoding into its parts, ignoring stack offsets.
+///
+/// Unparseable encodings result in an empty list.
+fn parse_types(types: &str) -> Vec<EncodingBox> {
+    MethodEncodingIter::new(types)
+        .map(|res| res.map(|(encoding, _stack_layout)| encoding))
+        .collect::<Result<_, _>>()
+        .unwrap_or_default()
+}
+
+/// A Clang module map, for importing headers into Swift.
+///
+/// The [`Display`](fmt::Display) implementation writes the contents of a
+/// `module.modulemap` file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModuleMap {
+    name: String,
+    headers: Vec<String>,
+}
+
+impl ModuleMap {
+    /// Create a module map for a module with the given name.
+    ///
+    /// This is the name that Swift code uses to `import` the module.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Add a header to the module, relative to the module map.
+    pub fn header(mut self, path: &str) -> Self {
+        self.headers.push(path.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ModuleMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "module {} {{", self.name)?;
+        for header in &self.headers {
+            writeln!(f, "    header {header:?}")?;
+        }
+        writeln!(f, "    export *")?;
+        writeln!(f, "}}")
+    }
+}
+
+/// An Objective-C header declaring classes.
+///
+/// The [`Display`](fmt::Display) implementation writes the contents of the
+/// header, which contains an `@interface` block for each class, with the
+/// methods' parameter and return types derived from their type encodings.
+///
+/// Since the header is plain text, this can be used from a build script or
+/// a code generation step, similar to `cbindgen`, to let Objective-C code in
+/// the same project call the classes with compile-time type checking.
+///
+/// Note that encodings lose some type information: All objects are declared
+/// as `id`, and blocks as `id` as well. Methods whose types cannot be
+/// represented in a header (e.g. anonymous structs passed by value) are
+/// skipped, with a comment noting that.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Header {
+    imports: Vec<String>,
+    classes: Vec<ClassMetadata>,
+}
+
+impl Header {
+    /// Create an empty header.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an `#import` for the given header, e.g. `"Foundation/Foundation.h"`.
+    ///
+    /// The header must declare the superclasses and the protocols of the
+    /// classes that are added.
+    pub fn import(mut self, header: &str) -> Self {
+        self.imports.push(header.to_string());
+        self
+    }
+
+    /// Add a class to the header.
+    pub fn class(mut self, metadata: ClassMetadata) -> Self {
+        self.classes.push(metadata);
+        self
+    }
+}
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "// This file was generated by objc2, do not edit.")?;
+        writeln!(f, "#pragma once")?;
+        writeln!(f)?;
+        for import in &self.imports {
+            writeln!(f, "#import <{import}>")?;
+        }
+        if !self.imports.is_empty() {
+            writeln!(f)?;
+        }
+        for cls in &self.classes {
+            writeln!(f, "@class {};", cls.name)?;
+        }
+        for cls in &self.classes {
+            writeln!(f)?;
+            write_interface(f, cls)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_interface(f: &mut fmt::Formatter<'_>, cls: &ClassMetadata) -> fmt::Result {
+    match &cls.superclass {
+        Some(superclass) => write!(f, "@interface {} : {superclass}", cls.name)?,
+        None => {
+            writeln!(f, "__attribute__((objc_root_class))")?;
+            write!(f, "@interface {}", cls.name)?;
+        }
+    }
+    if !cls.protocols.is_empty() {
+        write!(f, " <{}>", cls.protocols.join(", "))?;
+    }
+    writeln!(f)?;
+
+    let mut accessors = Vec::new();
+    for property in &cls.properties {
+        match parse_types(&property.type_encoding)
+            .first()
+            .and_then(c_type)
+        {
+            Some(ty) => {
+                writeln!(f, "@property (nonatomic) {ty}{};", property.name)?;
+                accessors.push(property.name.clone());
+                accessors.extend(setter_name(&property.name));
+            }
+            None => writeln!(
+                f,
+                "// Skipped property `{}`: unsupported type",
+                property.name,
+            )?,
+        }
+    }
+
+    for method in &cls.class_methods {
+        write_method(f, '+', method)?;
+    }
+    for method in &cls.instance_methods {
+        if !accessors.iter().any(|name| name == method.sel.name()) {
+            write_method(f, '-', method)?;
+        }
+    }
+    writeln!(f, "@end")
+}
+
+fn write_method(f: &mut fmt::Formatter<'_>, kind: char, method: &MethodMetadata) -> fmt::Result {
+    let types = parse_types(&method.type_encoding);
+    let sel = method.sel.name();
+    // The receiver and the selector are implicit.
+    let (ret, args) = match (types.first(), types.get(3..)) {
+        (Some(ret), Some(args)) if types[1..3] == [EncodingBox::Object, EncodingBox::Sel] => {
+            (ret, args)
+        }
+        // The class methods have the metaclass as the receiver.
+        (Some(ret), Some(args)) if types[1..3] == [EncodingBox::Class, EncodingBox::Sel] => {
+            (ret, args)
+        }
+        _ => return writeln!(f, "// Skipped {kind}{sel}: invalid type encoding"),
+    };
+    let c_types: Option<Vec<String>> = core::iter::once(ret).chain(args).map(c_type).collect();
+    let c_types = match c_types {
+        Some(c_types) => c_types,
+        None => return writeln!(f, "// Skipped {kind}{sel}: unsupported type"),
+    };
+
+    write!(f, "{kind} ({})", c_types[0].trim_end())?;
+    if args.is_empty() {
+        write!(f, "{sel}")?;
+    } else {
+        // Each part of the selector before a `:` takes an argument.
+        for (i, (part, ty)) in sel.split_terminator(':').zip(&c_types[1..]).enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{part}:({})arg{i}", ty.trim_end())?;
+        }
+    }
+    writeln!(f, ";")
+}
+
+/// The C type corresponding to an encoding, followed by a space if it is
+/// not a pointer type, such that the name can be appended directly.
+///
+/// Returns [`None`] if the type cannot be represented in a header.
+fn c_type(encoding: &EncodingBox) -> Option<String> {
+    let ty = match encoding {
+        EncodingBox::Char => "char",
+        EncodingBox::Short => "short",
+        EncodingBox::Int => "int",
+        EncodingBox::Long => "long",
+        EncodingBox::LongLong => "long long",
+        EncodingBox::UChar => "unsigned char",
+        EncodingBox::UShort => "unsigned short",
+        EncodingBox::UInt => "unsigned int",
+        EncodingBox::ULong => "unsigned long",
+        EncodingBox::ULongLong => "unsigned long long",
+        EncodingBox::Float => "float",
+        EncodingBox::Double => "double",
+        EncodingBox::LongDouble => "long double",
+        EncodingBox::FloatComplex => "float _Complex",
+        EncodingBox::DoubleComplex => "double _Complex",
+        EncodingBox::LongDoubleComplex => "long double _Complex",
+        EncodingBox::Bool => "bool",
+        EncodingBox::Void => "void",
+        EncodingBox::String => return Some("char *".into()),
+        EncodingBox::Object | EncodingBox::Block => "id",
+        EncodingBox::Class => "Class",
+        EncodingBox::Sel => "SEL",
+        EncodingBox::Pointer(pointee) => {
+            let pointee = c_type(pointee).unwrap_or_else(|| "void ".into());
+            return Some(format!("{pointee}*"));
+        }
+        EncodingBox::Atomic(inner) => {
+            return Some(format!("_Atomic({}) ", c_type(inner)?.trim_end()))
+        }
+        EncodingBox::Struct(name, _) if is_named(name) => return Some(format!("struct {name} ")),
+        EncodingBox::Union(name, _) if is_named(name) => return Some(format!("union {name} ")),
+        _ => return None,
+    };
+    Some(format!("{ty} "))
+}
+
+fn is_named(name: &str) -> bool {
+    !name.is_empty() && name != "?"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::str::FromStr;
+
+    use crate::test_utils;
+
+    #[test]
+    fn test_class_metadata() {
+        let cls = test_utils::custom_class();
+        let metadata = ClassMetadata::new(cls);
+        assert_eq!(metadata.name(), "CustomObject");
+        assert_eq!(metadata.superclass(), None);
+        assert_eq!(metadata.protocols(), ["CustomProtocol"]);
+
+        let sels: Vec<_> = metadata
+            .instance_methods()
+            .iter()
+            .map(|method| method.sel())
+            .collect();
+        assert!(sels.contains(&crate::sel!(foo)));
+        assert!(sels.contains(&crate::sel!(setFoo:)));
+        assert!(metadata
+            .class_methods()
+            .iter()
+            .any(|method| method.sel() == crate::sel!(classFoo)));
+
+        let foo = metadata
+            .properties()
+            .iter()
+            .find(|property| property.name() == "foo")
+            .unwrap();
+        assert_eq!(foo.type_encoding(), "I");
+    }
+
+    #[test]
+    fn test_parse_types() {
+        let expected = [
+            EncodingBox::Void,
+            EncodingBox::Object,
+            EncodingBox::Sel,
+            EncodingBox::UInt,
+        ];
+        assert_eq!(parse_types("v24@0:8I16"), expected);
+        assert_eq!(parse_types("v@:I"), expected);
+        assert!(parse_types("v@:!").is_empty());
+    }
+
+    #[test]
+    fn test_c_type() {
+        let c_type = |s: &str| c_type(&EncodingBox::from_str(s).unwrap());
+        assert_eq!(c_type("I").as_deref(), Some("unsigned int "));
+        assert_eq!(c_type("@").as_deref(), Some("id "));
+        assert_eq!(c_type("^@").as_deref(), Some("id *"));
+        assert_eq!(c_type("^^v").as_deref(), Some("void **"));
+        assert_eq!(c_type("^?").as_deref(), Some("void *"));
+        assert_eq!(c_type("{CGPoint=dd}").as_deref(), Some("struct CGPoint "));
+        assert_eq!(c_type("^{?=dd}").as_deref(), Some("void *"));
+        assert_eq!(c_type("{?=dd}"), None);
+        assert_eq!(c_type("[4i]"), None);
+    }
+
+    #[test]
+    fn test_header() {
+        let metadata = ClassMetadata {
+            name: "MyObject".into(),
+            superclass: Some("NSObject".into()),
+            protocols: vec!["NSCopying".into()],
+            instance_methods: vec![
+                MethodMetadata {
+                    sel: crate::sel!(addNumber:toNumber:),
+                    type_encoding: "q32@0:8q16q24".into(),
+                },
+                MethodMetadata {
+                    sel: crate::sel!(foo),
+                    type_encoding: "I16@0:8".into(),
+                },
+                MethodMetadata {
+                    sel: crate::sel!(setFoo:),
+                    type_encoding: "v20@0:8I16".into(),
+                },
+                MethodMetadata {
+                    sel: crate::sel!(takesAnonymous:),
+                    type_encoding: "v@:{?=ii}".into(),
+                },
+            ],
+            class_methods: vec![MethodMetadata {
+                sel: crate::sel!(new),
+                type_encoding: "@16#0:8".into(),
+            }],
+            properties: vec![PropertyMetadata {
+                name: "foo".into(),
+                type_encoding: "I".into(),
+            }],
+        };
+        let header = Header::new()
+            .import("Foundation/Foundation.h")
+            .class(metadata);
+        let expected = "\
+// This file was generated by objc2, do not edit.
+#pragma once
+
+#import <Foundation/Foundation.h>
+
+@class MyObject;
+
+@interface MyObject : NSObject <NSCopying>
+@property (nonatomic) unsigned int foo;
++ (id)new;
+- (long long)addNumber:(long long)arg0 toNumber:(long long)arg1;
+// Skipped -takesAnonymous:: unsupported type
+@end
+";
+        assert_eq!(header.to_string(), expected);
+    }
+
+    #[test]
+    fn test_root_class_header() {
+        let metadata = ClassMetadata::new(test_utils::custom_class());
+        let header = Header::new().class(metadata).to_string();
+        assert!(header.contains(
+            "__attribute__((objc_root_class))\n@interface CustomObject <CustomProtocol>\n"
+        ));
+        assert!(header.contains("@property (nonatomic) unsigned int foo;\n"));
+        assert!(!header.contains(")foo;"));
+        assert!(header.contains("+ (unsigned int)classFoo;\n"));
+    }
+
+    #[test]
+    fn test_module_map() {
+        let module_map = ModuleMap::new("Foo").header("Foo.h").header("Bar.h");
+        let expected =
+            "module Foo {\n    header \"Foo.h\"\n    header \"Bar.h\"\n    export *\n}\n";
+        assert_eq!(module_map.to_string(), expected);
+    }
+}
@@ -154,6 +154,7 @@
     feature(negative_impls, auto_traits)
 )]
 #![cfg_attr(feature = "unstable-c-unwind", feature(c_unwind))]
+#![cfg_attr(feature = "unstable-sanitize", feature(cfg_sanitize))]
 #![cfg_attr(feature = "unstable-docsrs", feature(doc_cfg, doc_auto_cfg))]
 #![warn(missing_docs)]
 #![warn(clippy::missing_errors_doc)]
@@ -179,7 +180,9 @@ pub use objc_sys as ffi;
 
 #[doc(no_inline)]
 pub use self::encode::{Encode, Encoding, RefEncode};
-pub use self::top_level_traits::{ClassType, DeclaredClass, Message, ProtocolType};
+pub use self::platform::Platform;
+pub use self::selftest::{abi_selftest, AbiSelfTestError};
+pub use self::top_level_traits::{ClassType, DeclaredClass, Inherits, Message, ProtocolType};
 
 #[cfg(feature = "objc2-proc-macros")]
 #[doc(hidden)]
@@ -200,15 +203,31 @@ macro_rules! __hash_idents {
 #[doc(hidden)]
 pub mod __framework_prelude;
 #[doc(hidden)]
+pub mod __fuzz;
+#[doc(hidden)]
 pub mod __macro_helpers;
+#[cfg(feature = "class-registry")]
+pub mod class_registry;
 pub mod encode;
+#[cfg(feature = "encoding-cache")]
+pub mod encoding_cache;
+pub mod error;
 pub mod exception;
+#[cfg(all(feature = "unstable-export", not(feature = "unstable-objfw")))]
+pub mod export;
 mod macros;
 pub mod mutability;
+mod platform;
+#[cfg(feature = "plugin-loader")]
+pub mod plugin;
 pub mod rc;
+#[cfg(feature = "realtime-audit")]
+pub mod realtime;
 pub mod runtime;
-#[cfg(test)]
-mod test_utils;
+mod sanitize;
+mod selftest;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 mod top_level_traits;
 #[cfg(any(doc, doctest, test))]
 pub mod topics;
@@ -218,7 +237,9 @@ mod verify;
 /// module.
 #[deprecated = "Moved to the `runtime` module"]
 pub mod declare {
-    pub use super::runtime::{ClassBuilder, ProtocolBuilder};
+    pub use super::runtime::ClassBuilder;
+    #[cfg(not(feature = "unstable-objfw"))]
+    pub use super::runtime::ProtocolBuilder;
     use super::*;
 
     /// Use [`runtime::ClassBuilder`] instead.
@@ -226,6 +247,7 @@ pub mod declare {
     pub type ClassDecl = runtime::ClassBuilder;
 
     /// Use [`runtime::ProtocolBuilder`] instead.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[deprecated = "Use `runtime::ProtocolBuilder` instead."]
     pub type ProtocolDecl = runtime::ProtocolBuilder;
 }
@@ -235,5 +257,10 @@ pub mod declare {
     all(feature = "apple", not(feature = "unstable-compiler-rt")),
     link(name = "Foundation", kind = "framework")
 )]
-#[cfg_attr(feature = "gnustep-1-7", link(name = "gnustep-base", kind = "dylib"))]
+#[cfg_attr(
+    all(feature = "gnustep-1-7", not(feature = "unstable-mock-runtime")),
+    link(name = "gnustep-base", kind = "dylib")
+)]
+// Link to ObjFW to make autorelease pools work
+#[cfg_attr(feature = "unstable-objfw", link(name = "objfw", kind = "dylib"))]
 extern "C" {}
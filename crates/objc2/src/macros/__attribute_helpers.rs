@@ -62,7 +62,8 @@ macro_rules! __extract_and_apply_cfg_attributes {
 ///
 /// Further arguments are passed on to the output macro, with the following
 /// arguments appended to it:
-/// 1. The `method` or `method_id` attribute.
+/// 1. The `method` or `method_id` attribute. `#[method]` and `#[method_id]`
+///    without a selector are passed on with the selector `@__derived`.
 ///    (#[$method_or_method_id:ident($($sel:tt)*)])
 ///
 /// 2. The retain semantics, if any was present in the selector for
@@ -142,6 +143,33 @@ macro_rules! __extract_custom_attributes_inner {
         }
     };
 
+    // `method` or `method_id` attribute without a selector. This is marked
+    // with `@__derived`, and then handled by the output macro.
+    {
+        (
+            #[method]
+            $($rest:tt)*
+        )
+        $($state:tt)*
+    } => {
+        $crate::__extract_custom_attributes_inner! {
+            (#[method(@__derived)] $($rest)*)
+            $($state)*
+        }
+    };
+    {
+        (
+            #[method_id]
+            $($rest:tt)*
+        )
+        $($state:tt)*
+    } => {
+        $crate::__extract_custom_attributes_inner! {
+            (#[method_id(@__derived)] $($rest)*)
+            $($state)*
+        }
+    };
+
     // `method` attribute
     {
         (
@@ -0,0 +1,66 @@
+/// Record a class in [`objc2::class_registry`] when the binary is loaded.
+///
+/// Takes the generic parameters of the type first; generic classes are not
+/// recorded, since there is no single type to record.
+///
+/// [`objc2::class_registry`]: crate::class_registry
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "class-registry")]
+macro_rules! __class_registry_entry {
+    ($(#[$m:meta])* ($($generics:tt)+) $for:ty, $kind:ident) => {};
+    ($(#[$m:meta])* () $for:ty, $kind:ident) => {
+        $(#[$m])*
+        const _: () = {
+            static __OBJC2_CLASS_INFO: $crate::class_registry::ClassInfo =
+                $crate::class_registry::ClassInfo::__new(
+                    <$for as $crate::ClassType>::NAME,
+                    $crate::__macro_helpers::concat!(
+                        $crate::__macro_helpers::module_path!(),
+                        "::",
+                        $crate::__macro_helpers::stringify!($for),
+                    ),
+                    $crate::class_registry::ClassKind::$kind,
+                    <$for as $crate::ClassType>::class,
+                    $crate::__class_registry_try_register!($kind, $for),
+                );
+
+            extern "C" fn __objc2_register_class_info() {
+                $crate::class_registry::__register(&__OBJC2_CLASS_INFO);
+            }
+
+            // Same as in `register_class_at_load!`.
+            #[cfg_attr(
+                target_vendor = "apple",
+                link_section = "__DATA,__mod_init_func,mod_init_funcs"
+            )]
+            #[cfg_attr(
+                all(not(target_vendor = "apple"), not(windows)),
+                link_section = ".init_array"
+            )]
+            #[cfg_attr(windows, link_section = ".CRT$XCU")]
+            #[used] // Make sure this reaches the linker
+            static __OBJC2_REGISTER_CLASS_INFO: extern "C" fn() = __objc2_register_class_info;
+        };
+    };
+}
+
+/// Only classes declared in Rust can be registered fallibly.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "class-registry")]
+macro_rules! __class_registry_try_register {
+    (Declared, $for:ty) => {
+        $crate::__macro_helpers::Some(<$for as $crate::DeclaredClass>::try_register)
+    };
+    (Extern, $for:ty) => {
+        $crate::__macro_helpers::None
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "class-registry"))]
+macro_rules! __class_registry_entry {
+    ($(#[$m:meta])* ($($generics:tt)*) $for:ty, $kind:ident) => {};
+}
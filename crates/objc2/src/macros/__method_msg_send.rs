@@ -315,3 +315,172 @@ macro_rules! __method_msg_send_id {
         )
     });
 }
+
+/// Same as `__method_msg_send` and `__method_msg_send_id`, but for methods
+/// where the selector is derived from the method and argument names.
+///
+/// The first argument is either `(send_message)` or `(send_message_id)`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_msg_send_derived {
+    // Skip using `MainThreadMarker` in the message send.
+    (
+        ($kind:ident)
+        ($receiver:expr)
+        ($name:ident)
+        ($arg:ident: MainThreadMarker $(, $($params_rest:tt)*)?)
+
+        ($($arg_parsed:tt)*)
+    ) => ({
+        let _ = $arg;
+        $crate::__method_msg_send_derived! {
+            ($kind)
+            ($receiver)
+            ($name)
+            ($($($params_rest)*)?)
+
+            ($($arg_parsed)*)
+        }
+    });
+
+    // Parse each argument
+    (
+        ($kind:ident)
+        ($receiver:expr)
+        ($name:ident)
+        ($arg:ident : $_arg_ty:ty $(, $($params_rest:tt)*)?)
+
+        ($($arg_parsed:tt)*)
+    ) => {
+        $crate::__method_msg_send_derived! {
+            ($kind)
+            ($receiver)
+            ($name)
+            ($($($params_rest)*)?)
+
+            ($($arg_parsed)* $arg)
+        }
+    };
+
+    // Variadic method
+    (
+        ($kind:ident)
+        ($receiver:expr)
+        ($name:ident)
+        ($($arg:ident :)? ...)
+
+        ($($arg_parsed:tt)*)
+    ) => ({
+        $crate::__macro_helpers::compile_error!(
+            "variadic methods are not yet supported"
+        )
+    });
+
+    // No arguments
+    (
+        ($kind:ident)
+        ($receiver:expr)
+        ($name:ident)
+        ()
+
+        ()
+    ) => {
+        $crate::__method_msg_send_derived_out! {
+            ($kind)
+            ($receiver)
+            ($crate::__macro_helpers::stringify!($name))
+            (false)
+            ()
+        }
+    };
+
+    // The first argument is labelled by the method name, the rest by the
+    // argument names.
+    (
+        ($kind:ident)
+        ($receiver:expr)
+        ($name:ident)
+        ()
+
+        ($first:ident $($rest:ident)*)
+    ) => {
+        $crate::__method_msg_send_derived_out! {
+            ($kind)
+            ($receiver)
+            (
+                $crate::__macro_helpers::stringify!($name),
+                $($crate::__macro_helpers::stringify!($rest),)*
+            )
+            (true)
+            ($first $($rest)*)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_msg_send_derived_out {
+    (
+        ($kind:ident)
+        ($receiver:expr)
+        ($($parts:expr),* $(,)?)
+        ($has_arguments:expr)
+        ($($arg:ident)*)
+    ) => ({
+        const __OBJC2_SELECTOR_PARTS: &[&$crate::__macro_helpers::str] = &[$($parts),*];
+        const __OBJC2_SELECTOR_LEN: usize =
+            $crate::__macro_helpers::derived_selector_len(__OBJC2_SELECTOR_PARTS, $has_arguments);
+        const __OBJC2_SELECTOR_BYTES: [$crate::__macro_helpers::u8; __OBJC2_SELECTOR_LEN] =
+            $crate::__macro_helpers::derived_selector(__OBJC2_SELECTOR_PARTS, $has_arguments);
+        // SAFETY: The selector is derived from Rust identifiers, which are
+        // valid UTF-8, and only ASCII bytes are changed or inserted.
+        const __OBJC2_SELECTOR_DATA: &$crate::__macro_helpers::str =
+            unsafe { $crate::__macro_helpers::from_utf8_unchecked(&__OBJC2_SELECTOR_BYTES) };
+
+        static CACHED_SEL: $crate::__macro_helpers::CachedSel =
+            $crate::__macro_helpers::CachedSel::new();
+        // SAFETY: The selector data is NUL-terminated.
+        let sel = unsafe { CACHED_SEL.get(__OBJC2_SELECTOR_DATA) };
+
+        $crate::__method_msg_send_derived_call! {
+            ($kind)
+            ($receiver)
+            (sel)
+            (__OBJC2_SELECTOR_DATA)
+            ($($arg)*)
+        }
+    });
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_msg_send_derived_call {
+    (
+        (send_message)
+        ($receiver:expr)
+        ($sel:ident)
+        ($_data:ident)
+        ($($arg:ident)*)
+    ) => {
+        $crate::__macro_helpers::MsgSend::send_message::<_, _>(
+            $crate::__macro_helpers::check_receiver($receiver),
+            $sel,
+            ($($crate::__macro_helpers::check_argument($arg),)*),
+        )
+    };
+    (
+        (send_message_id)
+        ($receiver:expr)
+        ($sel:ident)
+        ($data:ident)
+        ($($arg:ident)*)
+    ) => {
+        <$crate::__macro_helpers::RetainSemantics<{
+            $crate::__macro_helpers::retain_semantics($data)
+        }> as $crate::__macro_helpers::MsgSendId<_, _>>::send_message_id(
+            $receiver,
+            $sel,
+            ($($crate::__macro_helpers::check_argument($arg),)*),
+        )
+    };
+}
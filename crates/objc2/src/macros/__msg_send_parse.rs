@@ -126,6 +126,93 @@ macro_rules! __msg_send_parse {
             $($macro_args)*
         }
     }};
+
+    // Invalid syntax.
+    //
+    // These are tried last, and are only here to give better error messages
+    // than the generic "no rules expected the token" error.
+    {
+        ($error_fn:ident)
+        ($($selector_output:tt)*)
+        ($($argument_output:tt)*)
+        ($selector:ident : $(,)?)
+        $($_rest:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "missing argument after `",
+            $crate::__macro_helpers::stringify!($selector),
+            ":`, each part of the selector that ends with a colon must be ",
+            "followed by an argument",
+        ))
+    };
+    {
+        ($error_fn:ident)
+        ($($selector_output:tt)*)
+        ($($argument_output:tt)*)
+        ($selector:ident : _ , $($rest:tt)+)
+        $($_rest:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "the error parameter `",
+            $crate::__macro_helpers::stringify!($selector),
+            ": _` must be the last argument",
+        ))
+    };
+    {
+        ($error_fn:ident)
+        ($($selector_output:tt)*)
+        ($($argument_output:tt)*)
+        ($selector:ident $(, $($rest:tt)*)?)
+        $($_rest:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "missing `:` and argument after `",
+            $crate::__macro_helpers::stringify!($selector),
+            "`, the number of arguments must match the number of colons in ",
+            "the selector",
+        ))
+    };
+    {
+        ($error_fn:ident)
+        ($($selector_output:tt)*)
+        ($($argument_output:tt)*)
+        ($selector:ident : $($rest:tt)+)
+        $($_rest:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "expected `,` after the argument to `",
+            $crate::__macro_helpers::stringify!($selector),
+            ":`",
+        ))
+    };
+    {
+        ($error_fn:ident)
+        ($($selector_output:tt)*)
+        ($($argument_output:tt)*)
+        ($selector:ident $next:tt $($rest:tt)*)
+        $($_rest:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "expected `:` after `",
+            $crate::__macro_helpers::stringify!($selector),
+            "`, found `",
+            $crate::__macro_helpers::stringify!($next),
+            "`",
+        ))
+    };
+    {
+        ($error_fn:ident)
+        ($($selector_output:tt)*)
+        ($($argument_output:tt)*)
+        ($($rest:tt)*)
+        $($_rest:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "expected a selector, found `",
+            $crate::__macro_helpers::stringify!($($rest)*),
+            "`",
+        ))
+    };
 }
 
 #[doc(hidden)]
@@ -170,6 +257,17 @@ macro_rules! __comma_between_args {
             ($crate::__macro_helpers::stringify!($obj), $($args)*)
         }
     };
+    // send_batch!
+    (
+        (send_batch_message)
+        ($($args:tt)*)
+        ($objects:expr)
+    ) => {
+        $crate::__comma_between_args_inner! {
+            ("send_batch")
+            ($crate::__macro_helpers::stringify!($objects), $($args)*)
+        }
+    };
     // msg_send_id!
     (
         (send_super_message_id_static)
@@ -0,0 +1,76 @@
+/// Assert at compile-time that the encoding of a type is the given string.
+///
+/// This allows crates that implement [`Encode`] for their types to make sure
+/// that the encoding doesn't change by accident, e.g. when refactoring the
+/// implementation or when updating a dependency that the encoding is built
+/// from. The check is done entirely at compile-time, so it does not need a
+/// test to run, and has no runtime cost.
+///
+/// The string must match the encoding exactly, as it would be written by
+/// the [`Display`] implementation of [`Encoding`]. Since encodings often
+/// differ between platforms (e.g. `CGFloat` is `f` on 32-bit platforms),
+/// you may need to put the assertion behind a `cfg`.
+///
+/// [`Encode`]: crate::Encode
+/// [`Encoding`]: crate::Encoding
+/// [`Display`]: core::fmt::Display
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2::assert_encoding;
+/// use objc2::encode::{Encode, Encoding, RefEncode};
+///
+/// #[repr(C)]
+/// struct CGPoint {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// unsafe impl Encode for CGPoint {
+///     const ENCODING: Encoding = Encoding::Struct("CGPoint", &[f64::ENCODING, f64::ENCODING]);
+/// }
+///
+/// unsafe impl RefEncode for CGPoint {
+///     const ENCODING_REF: Encoding = Encoding::Pointer(&Self::ENCODING);
+/// }
+///
+/// assert_encoding!(CGPoint, "{CGPoint=dd}");
+/// assert_encoding!(*const CGPoint, "^{CGPoint=dd}");
+/// ```
+///
+/// A mismatch is a compile error.
+///
+/// ```compile_fail
+/// use objc2::assert_encoding;
+///
+/// assert_encoding!(u32, "i");
+/// ```
+#[macro_export]
+macro_rules! assert_encoding {
+    ($t:ty, $s:expr $(,)?) => {
+        const _: () = {
+            const ENCODING: $crate::Encoding = <$t as $crate::Encode>::ENCODING;
+            const LEN: $crate::__macro_helpers::usize =
+                $crate::__macro_helpers::__encoding_str_len(&ENCODING);
+            const STR: [$crate::__macro_helpers::u8; LEN] =
+                $crate::__macro_helpers::__encoding_str_array::<LEN>(&ENCODING);
+
+            if !$crate::__macro_helpers::__bytes_eq(
+                &STR,
+                $crate::__macro_helpers::str::as_bytes($s),
+            ) {
+                $crate::__macro_helpers::panic!(
+                    "{}",
+                    $crate::__macro_helpers::concat!(
+                        "the encoding of `",
+                        $crate::__macro_helpers::stringify!($t),
+                        "` is not ",
+                        $crate::__macro_helpers::stringify!($s),
+                    )
+                );
+            }
+        };
+    };
+}
@@ -62,10 +62,13 @@
 ///
 /// The class is guaranteed to have been created and registered with the
 /// Objective-C runtime after the [`ClassType::class`] function has been
-/// called.
+/// called. If you need the class to be available by name before that (e.g.
+/// for use in nibs or with `NSClassFromString`), use
+/// [`register_class_at_load!`].
 ///
 /// [`ClassType::NAME`]: crate::ClassType::NAME
 /// [`ClassType::class`]: crate::ClassType::class
+/// [`register_class_at_load!`]: crate::register_class_at_load
 ///
 ///
 /// ## `DeclaredClass` implementation
@@ -80,7 +83,12 @@
 /// `init`), you must override the subclass' designated initializers, and
 /// initialize your ivars properly in there.
 ///
+/// You may also implement [`did_dealloc`], which is called in `dealloc`
+/// before the type's [`Drop`] implementation and the drop glue of the ivars,
+/// while the object is still fully valid.
+///
 /// [`Ivars`]: crate::DeclaredClass::Ivars
+/// [`did_dealloc`]: crate::DeclaredClass::did_dealloc
 ///
 ///
 /// ## Inherent method definitions
@@ -106,6 +114,16 @@
 /// If the `#[method_id(...)]` attribute is used, the return type must be
 /// `Option<Id<T>>` or `Id<T>`. Additionally, if the selector is in the
 /// "init"-family, the `self`/`this` parameter must be `Allocated<Self>`.
+/// Returning `None` from an initializer releases the allocated object, same
+/// as returning `nil` from `init` does in Objective-C.
+///
+/// Methods can also report failure with an `NSError**` out-parameter, by
+/// ending the selector with `_` (e.g. `#[method_id(initWithURL:error:_)]`),
+/// similar to [`extern_methods!`]. The method should then not take the
+/// error parameter itself, but instead return `Result<Id<T>, Id<E>>` (or
+/// `Result<(), Id<E>>` when using `#[method(...)]`), which allows using `?`
+/// in the body. On failure, the error is autoreleased and written to the
+/// out-parameter if it is non-NULL, and `nil` (or `NO`) is returned.
 ///
 /// Putting other attributes on the method such as `cfg`, `allow`, `doc`,
 /// `deprecated` and so on is supported. However, note that `cfg_attr` may not
@@ -149,6 +167,25 @@
 /// [`extern_protocol!`]: crate::extern_protocol
 ///
 ///
+/// ## Subclassing `NSProxy`
+///
+/// Classes may also inherit from `NSProxy` instead of `NSObject`. Since
+/// `NSProxy` does not have an `init` method, initializers of such classes
+/// should call [`PartialInit::init_proxy`] instead of sending `init` to
+/// `super`.
+///
+/// `NSProxy` throws an exception for every message it does not itself
+/// implement, so proxies must also override either
+/// `forwardingTargetForSelector:`, or both `forwardInvocation:` and
+/// `methodSignatureForSelector:`.
+///
+/// See [`runtime::Proxy`] for a ready-made proxy that forwards to a target
+/// object.
+///
+/// [`PartialInit::init_proxy`]: crate::rc::PartialInit::init_proxy
+/// [`runtime::Proxy`]: crate::runtime::Proxy
+///
+///
 /// # Panics
 ///
 /// The implemented `ClassType::class` method may panic in a few cases, such
@@ -158,6 +195,8 @@
 ///   equal to the one on the superclass.
 /// - Debug assertions are enabled, and the protocol's required methods are not
 ///   implemented.
+/// - Debug assertions are enabled, the class is a proxy, and the forwarding
+///   methods are not implemented.
 ///
 /// And possibly more similar cases in the future.
 ///
@@ -390,6 +429,11 @@ macro_rules! declare_class {
 
         impl DeclaredClass for $for_declared:ty {
             $(type Ivars = $ivars:ty;)?
+
+            $(
+                $(#[$did_dealloc_m:meta])*
+                fn did_dealloc(&mut $did_dealloc_self:ident) $did_dealloc_body:block
+            )?
         }
 
         $($impls:tt)*
@@ -431,10 +475,44 @@ macro_rules! declare_class {
                 const NAME: &'static $crate::__macro_helpers::str = $name_const;
 
                 fn class() -> &'static $crate::runtime::AnyClass {
+                    match <Self as DeclaredClass>::try_register() {
+                        $crate::__macro_helpers::Ok(cls) => cls,
+                        $crate::__macro_helpers::Err(err) => {
+                            $crate::__macro_helpers::class_registration_failed(err)
+                        }
+                    }
+                }
+
+                #[inline]
+                fn as_super(&self) -> &Self::Super {
+                    &*self.__superclass
+                }
+
+                #[inline]
+                fn as_super_mut(&mut self) -> &mut Self::Super {
+                    &mut *self.__superclass
+                }
+            }
+
+            impl DeclaredClass for $for_declared {
+                type Ivars = $crate::__select_ivars!($($ivars)?);
+
+                $(
+                    $(#[$did_dealloc_m])*
+                    fn did_dealloc(&mut $did_dealloc_self) $did_dealloc_body
+
+                    const __HAS_DID_DEALLOC: $crate::__macro_helpers::bool = true;
+                )?
+
+                fn try_register() -> $crate::__macro_helpers::Result<
+                    &'static $crate::runtime::AnyClass,
+                    $crate::runtime::ClassRegistrationError,
+                > {
                     $crate::__macro_helpers::assert_mutability_matches_superclass_mutability::<Self>();
 
                     // TODO: Use `std::sync::OnceLock`
                     static REGISTER_CLASS: $crate::__macro_helpers::Once = $crate::__macro_helpers::Once::new();
+                    static mut __OBJC2_REGISTER_ERROR: $crate::__macro_helpers::Option<$crate::runtime::ClassRegistrationError> = $crate::__macro_helpers::None;
 
                     REGISTER_CLASS.call_once(|| {
                         let mut __objc2_builder = $crate::__macro_helpers::ClassBuilderHelper::<Self>::new();
@@ -445,38 +523,36 @@ macro_rules! declare_class {
                             $($impls)*
                         }
 
-                        let (__objc2_cls, __objc2_ivar_offset, __objc2_drop_flag_offset) = __objc2_builder.register();
-
-                        // SAFETY: Modification is ensured by `Once` to happen
-                        // before any access to the variables.
-                        unsafe {
-                            __OBJC2_CLASS.write(__objc2_cls);
-                            if <Self as $crate::__macro_helpers::DeclaredIvarsHelper>::HAS_IVARS {
-                                __OBJC2_IVAR_OFFSET.write(__objc2_ivar_offset);
+                        match __objc2_builder.register() {
+                            $crate::__macro_helpers::Ok((__objc2_cls, __objc2_ivar_offset, __objc2_drop_flag_offset)) => {
+                                // SAFETY: Modification is ensured by `Once` to happen
+                                // before any access to the variables.
+                                unsafe {
+                                    __OBJC2_CLASS.write(__objc2_cls);
+                                    if <Self as $crate::__macro_helpers::DeclaredIvarsHelper>::HAS_IVARS {
+                                        __OBJC2_IVAR_OFFSET.write(__objc2_ivar_offset);
+                                    }
+                                    if <Self as $crate::__macro_helpers::DeclaredIvarsHelper>::HAS_DROP_FLAG {
+                                        __OBJC2_DROP_FLAG_OFFSET.write(__objc2_drop_flag_offset);
+                                    }
+                                }
                             }
-                            if <Self as $crate::__macro_helpers::DeclaredIvarsHelper>::HAS_DROP_FLAG {
-                                __OBJC2_DROP_FLAG_OFFSET.write(__objc2_drop_flag_offset);
+                            $crate::__macro_helpers::Err(err) => {
+                                // SAFETY: Same as above.
+                                unsafe { __OBJC2_REGISTER_ERROR = $crate::__macro_helpers::Some(err) };
                             }
                         }
                     });
 
-                    // SAFETY: We just registered the class, so is now available
-                    unsafe { __OBJC2_CLASS.assume_init() }
-                }
-
-                #[inline]
-                fn as_super(&self) -> &Self::Super {
-                    &*self.__superclass
-                }
+                    // SAFETY: The error is only written inside the `Once`,
+                    // which has completed at this point.
+                    if let $crate::__macro_helpers::Some(err) = unsafe { &*$crate::__macro_helpers::addr_of!(__OBJC2_REGISTER_ERROR) } {
+                        return $crate::__macro_helpers::Err($crate::__macro_helpers::Clone::clone(err));
+                    }
 
-                #[inline]
-                fn as_super_mut(&mut self) -> &mut Self::Super {
-                    &mut *self.__superclass
+                    // SAFETY: We just registered the class, so is now available
+                    $crate::__macro_helpers::Ok(unsafe { __OBJC2_CLASS.assume_init() })
                 }
-            }
-
-            impl DeclaredClass for $for_declared {
-                type Ivars = $crate::__select_ivars!($($ivars)?);
 
                 #[inline]
                 fn __ivars_offset() -> $crate::__macro_helpers::isize {
@@ -516,6 +592,10 @@ macro_rules! declare_class {
             }
         };
 
+        $crate::__class_registry_entry! {
+            () $for_class, Declared
+        }
+
         // Methods
         $crate::__declare_class_output_impls! {
             $($impls)*
@@ -841,10 +921,58 @@ macro_rules! __declare_class_method_out {
         ($($params_prefix:tt)*)
         ($($params_rest:tt)*)
 
-        ($($m_method:tt)*)
+        (#[$method_or_method_id:ident($($sel:tt)*)])
+        ($($retain_semantics:tt)*)
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+    } => {
+        $crate::__declare_class_split_error_sel! {
+            ()
+            ($($sel)*)
+
+            ($crate::__declare_class_method_out_split)
+
+            ($($qualifiers)*)
+            ($name)
+            ($($ret)?)
+            ($body)
+
+            ($builder_method)
+            ($receiver)
+            ($receiver_ty)
+            ($($params_prefix)*)
+            ($($params_rest)*)
+
+            ($method_or_method_id)
+            ($($retain_semantics)*)
+            ($($m_optional)*)
+            ($($m_checked)*)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __declare_class_method_out_split {
+    {
+        ($($qualifiers:tt)*)
+        ($name:ident)
+        ($($ret:ty)?)
+        ($body:block)
+
+        ($builder_method:ident)
+        ($receiver:expr)
+        ($receiver_ty:ty)
+        ($($params_prefix:tt)*)
+        ($($params_rest:tt)*)
+
+        ($method_or_method_id:ident)
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+
+        ($($sel:tt)*)
+        ($($error:tt)*)
     } => {
         $crate::__declare_class_rewrite_params! {
             ($($params_rest)*)
@@ -863,10 +991,66 @@ macro_rules! __declare_class_method_out {
             ($receiver_ty)
             ($($params_prefix)*)
 
-            ($($m_method)*)
+            (#[$method_or_method_id($($sel)*)])
             ($($retain_semantics)*)
             ($($m_optional)*)
             ($($m_checked)*)
+            ($($error)*)
+        }
+    };
+}
+
+/// Strip a trailing `_` from the selector, which signifies that the last
+/// argument is an error out-parameter.
+///
+/// Outputs the selector and either `(error)` or `()`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __declare_class_split_error_sel {
+    // Error selector
+    {
+        ($($sel_parsed:tt)*)
+        ($sel:ident : _)
+
+        ($out_macro:path)
+        $($macro_args:tt)*
+    } => {
+        $out_macro! {
+            $($macro_args)*
+
+            ($($sel_parsed)* $sel :)
+            (error)
+        }
+    };
+    // Done
+    {
+        ($($sel_parsed:tt)*)
+        ()
+
+        ($out_macro:path)
+        $($macro_args:tt)*
+    } => {
+        $out_macro! {
+            $($macro_args)*
+
+            ($($sel_parsed)*)
+            ()
+        }
+    };
+    // Parse each token
+    {
+        ($($sel_parsed:tt)*)
+        ($next:tt $($sel_rest:tt)*)
+
+        ($out_macro:path)
+        $($macro_args:tt)*
+    } => {
+        $crate::__declare_class_split_error_sel! {
+            ($($sel_parsed)* $next)
+            ($($sel_rest)*)
+
+            ($out_macro)
+            $($macro_args)*
         }
     };
 }
@@ -971,19 +1155,71 @@ macro_rules! __declare_class_method_out_inner {
         ()
         ($($__m_optional:tt)*)
         ($($m_checked:tt)*)
+        ()
 
         ($($params_converted:tt)*)
         ($($body_prefix:tt)*)
     } => {
-        $($m_checked)*
-        #[allow(clippy::diverging_sub_expression)]
-        $($qualifiers)* extern "C" fn $name(
-            $($params_prefix)*
-            $($params_converted)*
-        ) $(-> <$ret as $crate::__macro_helpers::ConvertReturn>::__Inner)? {
-            $($body_prefix)*
-            $crate::__convert_result! {
-                $body $(; $ret)?
+        $crate::__declare_class_method_fn! {
+            $($m_checked)*
+            #[allow(clippy::diverging_sub_expression)]
+            ($($qualifiers)*)
+            ($name(
+                $($params_prefix)*
+                $($params_converted)*
+            ) $(-> <$ret as $crate::__macro_helpers::ConvertReturn>::__Inner)?)
+            {
+                $($body_prefix)*
+                $crate::__convert_result! {
+                    $body $(; $ret)?
+                }
+            }
+        }
+    };
+
+    // #[method(...)] with error
+    {
+        ($($qualifiers:tt)*)
+        ($name:ident)
+        ($ret:ty)
+        ($body:block)
+
+        ($__builder_method:ident)
+        ($__receiver:expr)
+        ($__receiver_ty:ty)
+        ($($params_prefix:tt)*)
+
+        (#[method($($__sel:tt)*)])
+        ()
+        ($($__m_optional:tt)*)
+        ($($m_checked:tt)*)
+        (error)
+
+        ($($params_converted:tt)*)
+        ($($body_prefix:tt)*)
+    } => {
+        $crate::__declare_class_method_fn! {
+            $($m_checked)*
+            #[allow(clippy::diverging_sub_expression)]
+            ($($qualifiers)*)
+            ($name(
+                $($params_prefix)*
+                $($params_converted)*
+                __objc2_error: *mut *mut <$ret as $crate::__macro_helpers::DeclaredResult>::Error,
+            ) -> $crate::runtime::Bool)
+            {
+                $($body_prefix)*
+
+                // Run the body in a closure, to allow using `?` in it.
+                #[allow(clippy::redundant_closure_call)]
+                let __objc2_result = (move || -> $ret { $body })();
+
+                // SAFETY: The error out-parameter is NULL or valid for writes,
+                // as per Cocoa's error handling conventions.
+                #[allow(unreachable_code)]
+                unsafe {
+                    $crate::__macro_helpers::declared_result_to_bool(__objc2_result, __objc2_error)
+                }
             }
         }
     };
@@ -1004,21 +1240,88 @@ macro_rules! __declare_class_method_out_inner {
         () // Specifying retain semantics is unsupported in declare_class! for now
         ($($__m_optional:tt)*)
         ($($m_checked:tt)*)
+        ()
 
         ($($params_converted:tt)*)
         ($($body_prefix:tt)*)
     } => {
-        $($m_checked)*
-        #[allow(clippy::diverging_sub_expression)]
-        $($qualifiers)* extern "C" fn $name(
-            $($params_prefix)*
-            $($params_converted)*
-        ) -> $crate::__macro_helpers::IdReturnValue {
-            // TODO: Somehow tell the compiler that `this: Allocated<Self>` is non-null.
+        $crate::__declare_class_method_fn! {
+            $($m_checked)*
+            #[allow(clippy::diverging_sub_expression)]
+            ($($qualifiers)*)
+            ($name(
+                $($params_prefix)*
+                $($params_converted)*
+            ) -> $crate::__macro_helpers::IdReturnValue)
+            {
+                // TODO: Somehow tell the compiler that `this: Allocated<Self>` is non-null.
+
+                $($body_prefix)*
+
+                let __objc2_result = $body;
+
+                #[allow(unreachable_code)]
+                <$crate::__macro_helpers::RetainSemantics<{
+                    $crate::__macro_helpers::retain_semantics(
+                        $crate::__sel_helper! {
+                            ()
+                            $($sel)*
+                        }
+                    )
+                }> as $crate::__macro_helpers::MessageRecieveId<
+                    $receiver_ty,
+                    $ret,
+                >>::into_return(__objc2_result)
+            }
+        }
+    };
+
+    // #[method_id(...)] with error
+    {
+        ($($qualifiers:tt)*)
+        ($name:ident)
+        ($ret:ty)
+        ($body:block)
 
+        ($__builder_method:ident)
+        ($__receiver:expr)
+        ($receiver_ty:ty)
+        ($($params_prefix:tt)*)
+
+        (#[method_id($($sel:tt)*)])
+        ()
+        ($($__m_optional:tt)*)
+        ($($m_checked:tt)*)
+        (error)
+
+        ($($params_converted:tt)*)
+        ($($body_prefix:tt)*)
+    } => {
+        $crate::__declare_class_method_fn! {
+            $($m_checked)*
+            #[allow(clippy::diverging_sub_expression)]
+            ($($qualifiers)*)
+            ($name(
+                $($params_prefix)*
+                $($params_converted)*
+                __objc2_error: *mut *mut <$ret as $crate::__macro_helpers::DeclaredResult>::Error,
+            ) -> $crate::__macro_helpers::IdReturnValue)
+            {
             $($body_prefix)*
 
-            let __objc2_result = $body;
+            // Run the body in a closure, to allow using `?` in it.
+            #[allow(clippy::redundant_closure_call)]
+            let __objc2_result = (move || -> $ret { $body })();
+
+            // SAFETY: The error out-parameter is NULL or valid for writes,
+            // as per Cocoa's error handling conventions.
+            #[allow(unreachable_code)]
+            let __objc2_result = unsafe {
+                $crate::__macro_helpers::DeclaredResult::__into_option(
+                    __objc2_result,
+                    __objc2_error,
+                )
+            };
 
             #[allow(unreachable_code)]
             <$crate::__macro_helpers::RetainSemantics<{
@@ -1030,11 +1333,15 @@ macro_rules! __declare_class_method_out_inner {
                 )
             }> as $crate::__macro_helpers::MessageRecieveId<
                 $receiver_ty,
-                $ret,
+                $crate::__macro_helpers::Option<
+                    <$ret as $crate::__macro_helpers::DeclaredResult>::Ok,
+                >,
             >>::into_return(__objc2_result)
+            }
         }
     };
 
+    // Missing return type
     {
         ($($qualifiers:tt)*)
         ($name:ident)
@@ -1046,21 +1353,88 @@ macro_rules! __declare_class_method_out_inner {
         ($__receiver_ty:ty)
         ($($params_prefix:tt)*)
 
-        (#[method_id($($sel:tt)*)])
+        (#[$method_or_method_id:ident($($sel:tt)*)])
         ($($retain_semantics:tt)*)
         ($($__m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($error:tt)*)
 
         ($($params_converted:tt)*)
         ($($body_prefix:tt)*)
     } => {
-        $($m_checked)*
-        $($qualifiers)* extern "C" fn $name() {
-            $crate::__macro_helpers::compile_error!("`#[method_id(...)]` must have a return type")
+        $crate::__declare_class_method_fn! {
+            $($m_checked)*
+            ($($qualifiers)*)
+            ($name())
+            {
+                $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+                    "`#[",
+                    $crate::__macro_helpers::stringify!($method_or_method_id),
+                    "(...)]` must have a return type",
+                    $crate::__declare_class_missing_return_hint!($($error)*),
+                ))
+            }
         }
     };
 }
 
+/// Emit the function that implements a method in `declare_class!`.
+///
+/// If the `"unwind"` feature is enabled, this is `extern "C-unwind"`, and
+/// panics in the body are turned into Objective-C exceptions, since they
+/// must not unwind into Objective-C.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "unwind"))]
+macro_rules! __declare_class_method_fn {
+    {
+        $(#[$m:meta])*
+        ($($qualifiers:tt)*)
+        ($($signature:tt)*)
+        $body:block
+    } => {
+        $(#[$m])*
+        $($qualifiers)* extern "C" fn $($signature)* $body
+    };
+}
+
+/// Emit the function that implements a method in `declare_class!`.
+///
+/// If the `"unwind"` feature is enabled, this is `extern "C-unwind"`, and
+/// panics in the body are turned into Objective-C exceptions, since they
+/// must not unwind into Objective-C.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "unwind")]
+macro_rules! __declare_class_method_fn {
+    {
+        $(#[$m:meta])*
+        ($($qualifiers:tt)*)
+        ($($signature:tt)*)
+        $body:block
+    } => {
+        $(#[$m])*
+        $($qualifiers)* extern "C-unwind" fn $($signature)* {
+            let __objc2_body = move || $body;
+
+            // SAFETY: Method implementations are only called from
+            // Objective-C.
+            unsafe { $crate::__macro_helpers::declared_method_body(__objc2_body) }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __declare_class_missing_return_hint {
+    () => {
+        ""
+    };
+    (error) => {
+        " of `Result<_, Id<E>>` when the selector ends with `_`"
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __convert_result {
@@ -1094,6 +1468,40 @@ macro_rules! __declare_class_register_out {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+    } => {
+        $crate::__declare_class_split_error_sel! {
+            ()
+            ($($sel)*)
+
+            ($crate::__declare_class_register_out_split)
+
+            ($builder)
+            ($($qualifiers)*)
+            ($name)
+            ($builder_method)
+            ($($params_rest)*)
+            ($method_or_method_id)
+            ($($m_optional)*)
+            ($($m_checked)*)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __declare_class_register_out_split {
+    {
+        ($builder:ident)
+        ($($qualifiers:tt)*)
+        ($name:ident)
+        ($builder_method:ident)
+        ($($params_rest:tt)*)
+        ($method_or_method_id:ident)
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+
+        ($($sel:tt)*)
+        ($($error:ident)?)
     } => {
         $crate::__extract_and_apply_cfg_attributes! {
             ($($m_checked)*)
@@ -1105,7 +1513,11 @@ macro_rules! __declare_class_register_out {
                 $crate::sel!($($sel)*),
                 Self::$name as $crate::__fn_ptr! {
                     ($($qualifiers)*)
+                    // Receiver and selector
                     (_, _,)
+                    // The error out-parameter, if any. The parameter types
+                    // are all inferred, so the order does not matter.
+                    $($error: _,)?
                     $($params_rest)*
                 },
             );
@@ -1150,6 +1562,14 @@ macro_rules! __declare_class_invalid_selectors {
             "Use `#[method(autorelease)]` and do the memory management yourself",
         ))
     };
+    (#[$method_or_method_id:ident(@__derived)]) => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "must specify the selector in `#[",
+            $crate::__macro_helpers::stringify!($method_or_method_id),
+            "(...)]`, deriving it from the method name is only supported in ",
+            "`extern_methods!`",
+        ))
+    };
     (#[$method_or_method_id:ident($($sel:tt)*)]) => {};
 }
 
@@ -1164,6 +1584,26 @@ macro_rules! __declare_class_no_optional {
     };
 }
 
+/// The function pointer type of `__declare_class_method_fn!`.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "unwind"))]
+macro_rules! __declare_class_fn_ptr_abi {
+    (($($qualifiers:tt)*) ($($output:tt)*)) => {
+        $($qualifiers)* extern "C" fn($($output)*) -> _
+    };
+}
+
+/// The function pointer type of `__declare_class_method_fn!`.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "unwind")]
+macro_rules! __declare_class_fn_ptr_abi {
+    (($($qualifiers:tt)*) ($($output:tt)*)) => {
+        $($qualifiers)* extern "C-unwind" fn($($output)*) -> _
+    };
+}
+
 /// Create function pointer type with inferred parameters.
 #[doc(hidden)]
 #[macro_export]
@@ -1173,7 +1613,10 @@ macro_rules! __fn_ptr {
         ($($output:tt)*)
         $(,)?
     ) => {
-        $($qualifiers)* extern "C" fn($($output)*) -> _
+        $crate::__declare_class_fn_ptr_abi! {
+            ($($qualifiers)*)
+            ($($output)*)
+        }
     };
     (
         ($($qualifiers:tt)*)
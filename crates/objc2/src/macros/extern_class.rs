@@ -39,6 +39,81 @@
 /// [`PhantomData`]: core::marker::PhantomData
 ///
 ///
+/// ## Comparisons
+///
+/// `#[derive(PartialEq, Eq, Hash)]` uses the superclass' implementation,
+/// which eventually ends up calling `isEqual:` and `hash` on [`NSObject`].
+///
+/// Additionally, you may add the `#[compare]` attribute to the struct, which
+/// implements [`PartialOrd`] and [`Ord`] using the Objective-C `compare:`
+/// method. This requires that `PartialEq` and `Eq` are implemented as well,
+/// and you must ensure that the class actually implements `compare:`
+/// (taking another instance of the class, and returning
+/// `NSComparisonResult`), and that it is consistent with `isEqual:`. If
+/// `compare:` returns an invalid `NSComparisonResult`, `cmp` panics.
+///
+/// Beware that `compare:` is not always consistent with `isEqual:`, even in
+/// Foundation. For example, `NSString`'s `compare:` considers canonically
+/// equivalent strings to be equal, while `isEqual:` compares them literally.
+///
+/// [`NSObject`]: crate::runtime::NSObject
+///
+/// ```
+/// use objc2::runtime::NSObject;
+/// use objc2::{extern_class, mutability, ClassType};
+///
+/// extern_class!(
+///     #[derive(PartialEq, Eq, Hash)]
+///     #[compare]
+///     pub struct NSDate;
+///
+///     unsafe impl ClassType for NSDate {
+///         type Super = NSObject;
+///         type Mutability = mutability::Immutable;
+///     }
+/// );
+///
+/// fn assert_ord<T: Ord>() {}
+/// assert_ord::<NSDate>();
+/// ```
+///
+///
+/// ## Formatting
+///
+/// `#[derive(Debug)]` uses the superclass' implementation, which for
+/// [`NSObject`] prints the object's `description`.
+///
+/// If you prefer using Objective-C's formatting over a pure-Rust
+/// implementation, you can add the `#[display]` attribute to implement
+/// [`Display`] using `description`, and the `#[debug_description]`
+/// attribute to implement [`Debug`] using `debugDescription` (in which case
+/// you should not also derive `Debug`). Both run inside an autorelease pool,
+/// and print `(null)` if the method returned `nil`. The class must respond
+/// to these methods, which all subclasses of `NSObject` do.
+///
+/// [`Display`]: core::fmt::Display
+/// [`Debug`]: core::fmt::Debug
+///
+/// ```
+/// use objc2::runtime::NSObject;
+/// use objc2::{extern_class, mutability, ClassType};
+///
+/// extern_class!(
+///     #[display]
+///     #[debug_description]
+///     pub struct NSValue;
+///
+///     unsafe impl ClassType for NSValue {
+///         type Super = NSObject;
+///         type Mutability = mutability::Immutable;
+///     }
+/// );
+///
+/// fn assert_fmt<T: std::fmt::Display + std::fmt::Debug>() {}
+/// assert_fmt::<NSValue>();
+/// ```
+///
+///
 /// ## `ClassType` implementation
 ///
 /// The syntax of this macro neatly documents that it implements the
@@ -63,6 +138,64 @@
 /// [`NAME`]: crate::ClassType::NAME
 ///
 ///
+/// ## Generics
+///
+/// Classes that use Objective-C's lightweight generics, such as
+/// `NSMeasurement<UnitType>` or `NSDictionary<KeyType, ObjectType>`, can be
+/// declared with generic parameters on the struct, which can then be used in
+/// method signatures in [`extern_methods!`].
+///
+/// The generic parameters may have a single bound (usually `?Sized`), and a
+/// default (usually [`AnyObject`]). The `ClassType` impl must repeat the
+/// parameters, and may add a [`Message`] bound. Fields are not supported on
+/// generic classes.
+///
+/// Since lightweight generics are erased at runtime, the class is the same
+/// regardless of the generic parameters; so [`ClassType::class`] and
+/// [`ClassType::NAME`] do not depend on them, and it is up to you to ensure
+/// that the parameters are correct (in the same way that the
+/// [`ClassType::Super`] must be correct).
+///
+/// The generated struct behaves as-if it contained an [`Id`] of each of the
+/// generic parameters; that is, it is covariant in them, and is only
+/// [`Send`] or [`Sync`] if the superclass and all the parameters are. This
+/// is the correct choice for collection-like classes, but may be overly
+/// restrictive for others; if you need different variance or auto traits,
+/// use `extern_class!` with explicit [`PhantomData`] fields instead.
+///
+/// [`extern_methods!`]: crate::extern_methods
+/// [`AnyObject`]: crate::runtime::AnyObject
+/// [`Message`]: crate::Message
+/// [`ClassType::class`]: crate::ClassType::class
+/// [`Id`]: crate::rc::Id
+///
+/// ```
+/// use objc2::rc::Id;
+/// use objc2::runtime::{AnyObject, NSObject};
+/// use objc2::{extern_class, extern_methods, mutability, ClassType, Message};
+///
+/// extern_class!(
+///     #[derive(PartialEq, Eq, Hash)]
+///     pub struct NSMeasurement<UnitType: ?Sized = AnyObject>;
+///
+///     unsafe impl<UnitType: ?Sized + Message> ClassType for NSMeasurement<UnitType> {
+///         type Super = NSObject;
+///         type Mutability = mutability::Immutable;
+///     }
+/// );
+///
+/// extern_methods!(
+///     unsafe impl<UnitType: ?Sized + Message> NSMeasurement<UnitType> {
+///         #[method_id(unit)]
+///         pub fn unit(&self) -> Id<UnitType>;
+///
+///         #[method(doubleValue)]
+///         pub fn doubleValue(&self) -> f64;
+///     }
+/// );
+/// ```
+///
+///
 /// # Safety
 ///
 /// This macro implements the three unsafe traits [`RefEncode`], [`Message`]
@@ -176,7 +309,7 @@
 macro_rules! extern_class {
     // No fields
     (
-        $(#[$m:meta])*
+        $(#[$($m:tt)*])*
         $v:vis struct $name:ident;
 
         $(#[$impl_m:meta])*
@@ -190,7 +323,7 @@ macro_rules! extern_class {
     ) => {
         // Shorthand syntax for the following
         $crate::extern_class!(
-            $(#[$m])*
+            $(#[$($m)*])*
             $v struct $name {}
 
             $(#[$impl_m])*
@@ -204,7 +337,7 @@ macro_rules! extern_class {
         );
     };
     (
-        $(#[$m:meta])*
+        $(#[$($m:tt)*])*
         $v:vis struct $name:ident {
             $($field_vis:vis $field:ident: $field_ty:ty,)*
         }
@@ -219,7 +352,7 @@ macro_rules! extern_class {
         }
     ) => {
         $crate::__inner_extern_class!(
-            $(#[$m])*
+            $(#[$($m)*])*
             $v struct $name<> {
                 __superclass: $superclass,
                 $($field_vis $field: $field_ty,)*
@@ -254,6 +387,48 @@ macro_rules! extern_class {
             }
         };
     };
+    // Generic classes
+    (
+        $(#[$($m:tt)*])*
+        $v:vis struct $name:ident<$($t_struct:ident $(: $(?$b_sized_struct:ident)? $($b_struct:ident)? $(= $default:ty)?)?),* $(,)?>;
+
+        $(#[$impl_m:meta])*
+        unsafe impl<$($t_for:ident $(: $(?$b_sized_for:ident +)? $b_for:ident)?),* $(,)?> ClassType for $for:ty {
+            $(#[inherits($($inheritance_rest:ty),+)])?
+            type Super = $superclass:ty;
+            type Mutability = $mutability:ty;
+
+            $(const NAME: &'static str = $name_const:expr;)?
+        }
+    ) => {
+        $crate::__inner_extern_class!(
+            $(#[$($m)*])*
+            $v struct $name<$($t_struct $(: $(?$b_sized_struct)? $($b_struct)? $(= $default)?)?),*> {
+                __superclass: $superclass,
+                // Act as-if the class stores the generic types. This makes
+                // the class covariant in them, and makes the auto traits
+                // depend on them, like they would for a collection.
+                __generics: $crate::__macro_helpers::PhantomData<($($crate::rc::Id<$t_struct>,)*)>,
+            }
+
+            $(#[$impl_m])*
+            unsafe impl<$($t_for $(: $(?$b_sized_for +)? $b_for)?),*> ClassType for $for {
+                $(#[inherits($($inheritance_rest),+)])?
+                type Super = $superclass;
+                type Mutability = $mutability;
+
+                fn as_super(&self) -> &Self::Super {
+                    &self.__superclass
+                }
+
+                fn as_super_mut(&mut self) -> &mut Self::Super {
+                    &mut self.__superclass
+                }
+
+                $(const NAME: &'static str = $name_const;)?
+            }
+        );
+    };
 }
 
 #[doc(hidden)]
@@ -319,7 +494,7 @@ macro_rules! __impl_as_ref_borrow {
 #[macro_export]
 macro_rules! __inner_extern_class {
     (
-        $(#[$m:meta])*
+        $(#[$($m:tt)*])*
         $v:vis struct $name:ident<$($t_struct:ident $(: $(?$b_sized_struct:ident)? $($b_struct:ident)? $(= $default:ty)?)?),* $(,)?> {
             $superclass_field:ident: $superclass_field_ty:ty,
             $($fields:tt)*
@@ -337,11 +512,18 @@ macro_rules! __inner_extern_class {
             $(const NAME: &'static str = $name_const:expr;)?
         }
     ) => {
-        $(#[$m])*
-        #[repr(C)]
-        $v struct $name<$($t_struct $(: $(?$b_sized_struct)? $($b_struct)? $(= $default)?)?),*> {
-            $superclass_field: $superclass_field_ty,
-            $($fields)*
+        $crate::__extern_class_extract_attrs! {
+            ($(#[$($m)*])*)
+            ()
+            ()
+
+            ($v struct $name<$($t_struct $(: $(?$b_sized_struct)? $($b_struct)? $(= $default)?)?),*> {
+                $superclass_field: $superclass_field_ty,
+                $($fields)*
+            })
+            ($(#[$impl_m])*)
+            ($($t_for $(: $(?$b_sized_for +)? $b_for)?),*)
+            ($for)
         }
 
         $crate::__extern_class_impl_traits! {
@@ -376,6 +558,245 @@ macro_rules! __inner_extern_class {
             #[inline]
             fn as_super_mut(&mut $as_super_mut_self) -> &mut Self::Super $as_super_mut
         }
+
+        $crate::__class_registry_entry! {
+            $(#[$impl_m])*
+            ($($t_for)*) $for, Extern
+        }
+    };
+}
+
+/// Extract the `#[compare]`, `#[display]` and `#[debug_description]`
+/// attributes from the struct's attributes, and emit the struct along with
+/// the trait implementations they request.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __extern_class_extract_attrs {
+    // Base case
+    {
+        ()
+        ($($m_kept:tt)*)
+        ($($flags:ident)*)
+
+        ($($struct:tt)*)
+        ($($impl_m:tt)*)
+        ($($t:tt)*)
+        ($for:ty)
+    } => {
+        $($m_kept)*
+        #[repr(C)]
+        $($struct)*
+
+        $crate::__extern_class_impl_attrs! {
+            ($($flags)*)
+            ($($impl_m)*)
+            ($($t)*)
+            ($for)
+        }
+    };
+    // `#[compare]`, `#[display]` or `#[debug_description]` attribute
+    {
+        (
+            #[$flag:ident]
+            $($m_rest:tt)*
+        )
+        ($($m_kept:tt)*)
+        ($($flags:ident)*)
+
+        $($rest:tt)*
+    } => {
+        $crate::__extern_class_extract_attrs! {
+            @($flag)
+            ($($m_rest)*)
+            ($($m_kept)*)
+            ($($flags)*)
+
+            $($rest)*
+        }
+    };
+    // Other attributes
+    {
+        (
+            #[$($m:tt)*]
+            $($m_rest:tt)*
+        )
+        ($($m_kept:tt)*)
+        ($($flags:ident)*)
+
+        $($rest:tt)*
+    } => {
+        $crate::__extern_class_extract_attrs! {
+            ($($m_rest)*)
+            ($($m_kept)* #[$($m)*])
+            ($($flags)*)
+
+            $($rest)*
+        }
+    };
+
+    // Helper for checking whether a single-identifier attribute is one of
+    // ours (identifiers can't be matched against literal tokens directly
+    // after having been captured).
+    {
+        @(compare)
+        ($($m_rest:tt)*)
+        ($($m_kept:tt)*)
+        ($($flags:ident)*)
+
+        $($rest:tt)*
+    } => {
+        $crate::__extern_class_extract_attrs! {
+            ($($m_rest)*)
+            ($($m_kept)*)
+            ($($flags)* compare)
+
+            $($rest)*
+        }
+    };
+    {
+        @(display)
+        ($($m_rest:tt)*)
+        ($($m_kept:tt)*)
+        ($($flags:ident)*)
+
+        $($rest:tt)*
+    } => {
+        $crate::__extern_class_extract_attrs! {
+            ($($m_rest)*)
+            ($($m_kept)*)
+            ($($flags)* display)
+
+            $($rest)*
+        }
+    };
+    {
+        @(debug_description)
+        ($($m_rest:tt)*)
+        ($($m_kept:tt)*)
+        ($($flags:ident)*)
+
+        $($rest:tt)*
+    } => {
+        $crate::__extern_class_extract_attrs! {
+            ($($m_rest)*)
+            ($($m_kept)*)
+            ($($flags)* debug_description)
+
+            $($rest)*
+        }
+    };
+    {
+        @($other:ident)
+        ($($m_rest:tt)*)
+        ($($m_kept:tt)*)
+        ($($flags:ident)*)
+
+        $($rest:tt)*
+    } => {
+        $crate::__extern_class_extract_attrs! {
+            ($($m_rest)*)
+            ($($m_kept)* #[$other])
+            ($($flags)*)
+
+            $($rest)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __extern_class_impl_attrs {
+    {
+        ()
+        ($($impl_m:tt)*)
+        ($($t:tt)*)
+        ($for:ty)
+    } => {};
+    {
+        (compare $($flags:ident)*)
+        ($($impl_m:tt)*)
+        ($($t:tt)*)
+        ($for:ty)
+    } => {
+        $($impl_m)*
+        impl<$($t)*> $crate::__macro_helpers::PartialOrd for $for {
+            #[inline]
+            #[doc(alias = "compare:")]
+            fn partial_cmp(
+                &self,
+                other: &Self,
+            ) -> $crate::__macro_helpers::Option<$crate::__macro_helpers::Ordering> {
+                $crate::__macro_helpers::Some($crate::__macro_helpers::Ord::cmp(self, other))
+            }
+        }
+
+        $($impl_m)*
+        impl<$($t)*> $crate::__macro_helpers::Ord for $for {
+            #[inline]
+            #[doc(alias = "compare:")]
+            fn cmp(&self, other: &Self) -> $crate::__macro_helpers::Ordering {
+                // SAFETY: The user ensured, by writing `#[compare]`, that the
+                // class implements `compare:`.
+                unsafe { $crate::__macro_helpers::compare(self, other) }
+            }
+        }
+
+        $crate::__extern_class_impl_attrs! {
+            ($($flags)*)
+            ($($impl_m)*)
+            ($($t)*)
+            ($for)
+        }
+    };
+    {
+        (display $($flags:ident)*)
+        ($($impl_m:tt)*)
+        ($($t:tt)*)
+        ($for:ty)
+    } => {
+        $($impl_m)*
+        impl<$($t)*> $crate::__macro_helpers::fmt::Display for $for {
+            #[inline]
+            #[doc(alias = "description")]
+            fn fmt(
+                &self,
+                f: &mut $crate::__macro_helpers::fmt::Formatter<'_>,
+            ) -> $crate::__macro_helpers::fmt::Result {
+                $crate::__macro_helpers::fmt_description(self, f)
+            }
+        }
+
+        $crate::__extern_class_impl_attrs! {
+            ($($flags)*)
+            ($($impl_m)*)
+            ($($t)*)
+            ($for)
+        }
+    };
+    {
+        (debug_description $($flags:ident)*)
+        ($($impl_m:tt)*)
+        ($($t:tt)*)
+        ($for:ty)
+    } => {
+        $($impl_m)*
+        impl<$($t)*> $crate::__macro_helpers::fmt::Debug for $for {
+            #[inline]
+            #[doc(alias = "debugDescription")]
+            fn fmt(
+                &self,
+                f: &mut $crate::__macro_helpers::fmt::Formatter<'_>,
+            ) -> $crate::__macro_helpers::fmt::Result {
+                $crate::__macro_helpers::fmt_debug_description(self, f)
+            }
+        }
+
+        $crate::__extern_class_impl_attrs! {
+            ($($flags)*)
+            ($($impl_m)*)
+            ($($t)*)
+            ($for)
+        }
     };
 }
 
@@ -502,5 +923,38 @@ macro_rules! __extern_class_impl_traits {
 
             ($superclass, $($inheritance_rest,)*)
         }
+
+        $(#[$impl_m])*
+        $crate::__impl_inherits! {
+            impl ($($t)*) for $for;
+
+            ($superclass, $($inheritance_rest,)*)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_inherits {
+    {
+        impl ($($t:tt)*) for $for:ty;
+
+        ()
+    } => {};
+    {
+        impl ($($t:tt)*) for $for:ty;
+
+        ($item:ty, $($tail:ty,)*)
+    } => {
+        // SAFETY: The caller of `extern_class!` / `declare_class!` verifies
+        // that the class actually inherits the classes in the chain (and
+        // `AnyObject` is always last in the chain).
+        unsafe impl<$($t)*> $crate::Inherits<$item> for $for {}
+
+        $crate::__impl_inherits! {
+            impl ($($t)*) for $for;
+
+            ($($tail,)*)
+        }
     };
 }
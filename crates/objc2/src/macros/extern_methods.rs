@@ -20,12 +20,32 @@
 /// or `#[method_id(my:selector:)]` attribute. The `method` attribute maps to
 /// a call to [`msg_send!`], while the `method_id` maps to [`msg_send_id!`].
 ///
+/// The selector may also be left out, as in `#[method]` or `#[method_id]`,
+/// in which case it is derived from the name of the function and the names
+/// of its arguments, similar to how Swift maps argument labels to selectors.
+/// The function name is converted from `snake_case` to `lowerCamelCase` and
+/// forms the first part of the selector, and each argument after the first
+/// forms a subsequent part in the same way. So `fn description(&self)` maps
+/// to `description`, `fn object_for_key(&self, key: &K)` maps to
+/// `objectForKey:`, and `fn set_object(&self, obj: &T, for_key: &K)` maps to
+/// `setObject:forKey:`. The number of arguments therefore always matches the
+/// number of colons in the selector; when the selector is given explicitly,
+/// this is instead checked at compile-time. Error parameters are not
+/// supported with derived selectors.
+///
 /// If the attribute ends with "_", as in `#[method(my:error:_)]` or
 /// `#[method_id(my:error:_)]`, the method is assumed to take an
 /// implicit `NSError**` parameter, which is automatically converted to a
 /// [`Result`]. See the error section in [`msg_send!`] and [`msg_send_id!`]
 /// for details.
 ///
+/// Initializers that may fail, such as `initWithContentsOfFile:`, can be
+/// declared by taking `this: Allocated<Self>` and returning
+/// `Option<Id<Self>>`, or `Result<Id<Self>, Id<E>>` when using an error
+/// parameter. The allocated object is consumed by the call, and it is the
+/// initializer's responsibility to release it if it fails, so you do not
+/// have to handle that yourself.
+///
 /// If you use `objc2_foundation::MainThreadMarker` as a parameter type, the
 /// macro will ignore it, allowing you to neatly specify "this method must be
 /// run on the main thread". Note that due to type-system limitations, this is
@@ -114,6 +134,10 @@
 ///         #[method_id(fooObject)]
 ///         pub fn foo_object(&self) -> Id<NSObject>;
 ///
+///         // The selector is derived from the names, `setFoo:andBar:`.
+///         #[method]
+///         pub fn set_foo(&self, foo: NSUInteger, and_bar: bool);
+///
 ///         #[method(withError:_)]
 ///         // Since the selector specifies "_", the return type is assumed to
 ///         // be `Result`.
@@ -173,6 +197,10 @@
 ///         unsafe { msg_send_id![self, fooObject] }
 ///     }
 ///
+///     pub fn set_foo(&self, foo: NSUInteger, and_bar: bool) {
+///         unsafe { msg_send![self, setFoo: foo, andBar: and_bar] }
+///     }
+///
 ///     // Since the selector specifies one more argument than we
 ///     // have, the return type is assumed to be `Result`.
 ///     pub fn with_error(&self) -> Result<(), Id<NSError>> {
@@ -188,14 +216,14 @@ macro_rules! extern_methods {
     (
         $(
             $(#[$impl_m:meta])*
-            unsafe impl<$($t:ident $(: $b:ident $(+ $rest:ident)*)?),* $(,)?> $type:ty {
+            unsafe impl<$($t:ident $(: $(?$b_sized:ident)? $($b:ident)? $(+ $rest:ident)*)?),* $(,)?> $type:ty {
                 $($methods:tt)*
             }
         )+
     ) => {
         $(
             $(#[$impl_m])*
-            impl<$($t $(: $b $(+ $rest)*)?),*> $type {
+            impl<$($t $(: $(?$b_sized)? $($b)? $(+ $rest)*)?),*> $type {
                 $crate::__extern_methods_rewrite_methods! {
                     $($methods)*
                 }
@@ -247,6 +275,7 @@ macro_rules! __extern_methods_rewrite_methods {
             ($crate::__extern_methods_method_out)
             ($v unsafe fn $name($($params)*) $(-> $ret)?)
             ($($($where : $bound ,)+)?)
+            ($name)
         }
 
         $crate::__extern_methods_rewrite_methods! {
@@ -272,6 +301,7 @@ macro_rules! __extern_methods_rewrite_methods {
             ($crate::__extern_methods_method_out)
             ($v fn $name($($params)*) $(-> $ret)?)
             ($($($where : $bound ,)+)?)
+            ($name)
         }
 
         $crate::__extern_methods_rewrite_methods! {
@@ -297,10 +327,87 @@ macro_rules! __extern_methods_rewrite_methods {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __extern_methods_method_out {
+    // #[method], selector derived from the name
+    {
+        ($($function_start:tt)*)
+        ($($where:ty : $bound:path ,)*)
+        ($name:ident)
+
+        ($__builder_method:ident)
+        ($receiver:expr)
+        ($__receiver_ty:ty)
+        ($($__params_prefix:tt)*)
+        ($($params_rest:tt)*)
+
+        (#[method(@__derived)])
+        ()
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+    } => {
+        $($m_checked)*
+        $($function_start)*
+        where
+            $($where : $bound,)*
+        {
+            $crate::__extern_methods_no_optional!($($m_optional)*);
+
+            #[allow(unused_unsafe)]
+            unsafe {
+                $crate::__method_msg_send_derived! {
+                    (send_message)
+                    ($receiver)
+                    ($name)
+                    ($($params_rest)*)
+
+                    ()
+                }
+            }
+        }
+    };
+
+    // #[method_id], selector derived from the name
+    {
+        ($($function_start:tt)*)
+        ($($where:ty : $bound:path ,)*)
+        ($name:ident)
+
+        ($__builder_method:ident)
+        ($receiver:expr)
+        ($__receiver_ty:ty)
+        ($($__params_prefix:tt)*)
+        ($($params_rest:tt)*)
+
+        (#[method_id(@__derived)])
+        ()
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+    } => {
+        $($m_checked)*
+        $($function_start)*
+        where
+            $($where : $bound,)*
+        {
+            $crate::__extern_methods_no_optional!($($m_optional)*);
+
+            #[allow(unused_unsafe)]
+            unsafe {
+                $crate::__method_msg_send_derived! {
+                    (send_message_id)
+                    ($receiver)
+                    ($name)
+                    ($($params_rest)*)
+
+                    ()
+                }
+            }
+        }
+    };
+
     // #[method(...)]
     {
         ($($function_start:tt)*)
         ($($where:ty : $bound:path ,)*)
+        ($__name:ident)
 
         ($__builder_method:ident)
         ($receiver:expr)
@@ -338,6 +445,7 @@ macro_rules! __extern_methods_method_out {
     {
         ($($function_start:tt)*)
         ($($where:ty : $bound:path ,)*)
+        ($__name:ident)
 
         ($__builder_method:ident)
         ($receiver:expr)
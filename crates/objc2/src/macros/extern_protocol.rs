@@ -41,6 +41,14 @@
 /// [`ProtocolObject<dyn T>`], which also means that [`ProtocolType`] is
 /// implemented for `dyn T`.
 ///
+/// Methods with a body (and without a `#[method(...)]` attribute) are output
+/// as provided trait methods, and can be used to ship helpers implemented in
+/// Rust alongside the protocol's Objective-C methods, for example
+/// convenience wrappers over required methods. These helpers get an implicit
+/// `where Self: Sized + Message` bound (so that the trait stays usable as
+/// `dyn T`), and are available on [`ProtocolObject<dyn T>`] as well as on
+/// every type implementing the protocol.
+///
 /// Finally, you can use the `#[optional]` attribute to mark optional methods.
 /// This currently doesn't have any effect, but probably will have one in the
 /// future when implementing protocols in [`declare_class!`].
@@ -125,6 +133,12 @@
 ///             &self,
 ///             type_identifier: &NSString,
 ///         ) -> NSItemProviderRepresentationVisibility;
+///
+///         // A helper implemented in Rust, which is not part of the
+///         // Objective-C protocol.
+///         fn is_visible_for_representation(&self, type_identifier: &NSString) -> bool {
+///             self.itemProviderVisibilityForRepresentation(type_identifier) != 0
+///         }
 ///     }
 ///
 ///     // SAFETY:
@@ -270,6 +284,50 @@ macro_rules! __extern_protocol_rewrite_methods {
             $($rest)*
         }
     };
+
+    // Provided unsafe method, implemented in Rust
+    {
+        $(#[$($m:tt)*])*
+        $v:vis unsafe fn $name:ident($($params:tt)*) $(-> $ret:ty)?
+        // TODO: Handle where bounds better
+        $(where $($where:ty : $bound:path),+ $(,)?)?
+        $body:block
+
+        $($rest:tt)*
+    } => {
+        $(#[$($m)*])*
+        $v unsafe fn $name($($params)*) $(-> $ret)?
+        where
+            Self: $crate::__macro_helpers::Sized + $crate::Message
+            $($(, $where : $bound)+)?
+        $body
+
+        $crate::__extern_protocol_rewrite_methods! {
+            $($rest)*
+        }
+    };
+
+    // Provided safe method, implemented in Rust
+    {
+        $(#[$($m:tt)*])*
+        $v:vis fn $name:ident($($params:tt)*) $(-> $ret:ty)?
+        // TODO: Handle where bounds better
+        $(where $($where:ty : $bound:path),+ $(,)?)?
+        $body:block
+
+        $($rest:tt)*
+    } => {
+        $(#[$($m)*])*
+        $v fn $name($($params)*) $(-> $ret)?
+        where
+            Self: $crate::__macro_helpers::Sized + $crate::Message
+            $($(, $where : $bound)+)?
+        $body
+
+        $crate::__extern_protocol_rewrite_methods! {
+            $($rest)*
+        }
+    };
 }
 
 #[doc(hidden)]
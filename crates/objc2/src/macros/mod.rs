@@ -1,12 +1,19 @@
 mod __attribute_helpers;
+mod __class_registry;
 mod __method_msg_send;
 mod __msg_send_parse;
 mod __rewrite_self_param;
+mod assert_encoding;
 mod declare_class;
 mod extern_category;
 mod extern_class;
 mod extern_methods;
 mod extern_protocol;
+mod ns_enum;
+mod ns_options;
+mod register_class_at_load;
+mod sel_table;
+mod unique_class_name;
 
 /// Gets a reference to an [`AnyClass`] from the given name.
 ///
@@ -31,7 +38,14 @@ mod extern_protocol;
 ///
 /// If the experimental `"unstable-static-class"` feature is enabled, this
 /// will emit special statics that will be replaced by dyld when the program
-/// starts up.
+/// starts up. That is, the class reference is placed in the
+/// `__objc_classrefs` section (or `__cls_refs` on the old 32-bit macOS
+/// runtime), same as what Clang emits, which means that getting the class
+/// compiles down to a single load instead of a call to `objc_getClass`.
+///
+/// The GNUstep runtime does not use these sections, so there this feature
+/// falls back to the default behaviour of looking up the class once, and
+/// caching the result.
 ///
 /// Errors that were previously runtime panics may now turn into linker errors
 /// if you try to use a class which is not available. Additionally, you may
@@ -62,8 +76,14 @@ mod extern_protocol;
 ///
 /// Try to get a non-existing class.
 ///
-#[cfg_attr(not(feature = "unstable-static-class"), doc = "```should_panic")]
-#[cfg_attr(feature = "unstable-static-class", doc = "```ignore")]
+#[cfg_attr(
+    any(not(feature = "unstable-static-class"), not(feature = "apple")),
+    doc = "```should_panic"
+)]
+#[cfg_attr(
+    all(feature = "unstable-static-class", feature = "apple"),
+    doc = "```ignore"
+)]
 /// use objc2::class;
 ///
 /// let _ = class!(NonExistantClass);
@@ -80,7 +100,7 @@ macro_rules! class {
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(not(feature = "unstable-static-class"))]
+#[cfg(any(not(feature = "unstable-static-class"), not(feature = "apple")))]
 macro_rules! __class_inner {
     ($name:expr, $_hash:expr) => {{
         static CACHED_CLASS: $crate::__macro_helpers::CachedClass =
@@ -549,18 +569,6 @@ macro_rules! __statics_class {
     }
 }
 
-#[doc(hidden)]
-#[macro_export]
-#[cfg(not(feature = "apple"))]
-macro_rules! __statics_class {
-    ($($args:tt)*) => {
-        // TODO
-        $crate::__macro_helpers::compile_error!(
-            "The `\"unstable-static-class\"` feature is not yet supported on GNUStep!"
-        )
-    };
-}
-
 #[doc(hidden)]
 #[macro_export]
 #[cfg(all(
@@ -617,7 +625,8 @@ macro_rules! __sel_inner {
 #[macro_export]
 #[cfg(all(
     feature = "unstable-static-class",
-    not(feature = "unstable-static-class-inlined")
+    not(feature = "unstable-static-class-inlined"),
+    feature = "apple",
 ))]
 macro_rules! __class_inner {
     ($name:expr, $hash:expr) => {{
@@ -638,7 +647,7 @@ macro_rules! __class_inner {
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(feature = "unstable-static-class-inlined")]
+#[cfg(all(feature = "unstable-static-class-inlined", feature = "apple"))]
 macro_rules! __class_inner {
     ($name:expr, $hash:expr) => {{
         $crate::__statics_class! {
@@ -787,6 +796,13 @@ macro_rules! __class_inner {
 /// Do beware that this is only valid on methods that return `BOOL`, see
 /// [`msg_send_id!`] for methods that return instance types.
 ///
+/// If `E` is [`AnyObject`], the error can be converted into an
+/// [`ObjcError`] using the `?` operator, which makes it easy to handle it
+/// together with caught exceptions.
+///
+/// [`AnyObject`]: crate::runtime::AnyObject
+/// [`ObjcError`]: crate::error::ObjcError
+///
 /// [cocoa-error]: https://developer.apple.com/library/archive/documentation/Cocoa/Conceptual/ErrorHandlingCocoa/ErrorHandling/ErrorHandling.html
 /// [swift-error]: https://developer.apple.com/documentation/swift/about-imported-cocoa-error-parameters
 ///
@@ -989,13 +1005,22 @@ macro_rules! msg_send {
             ($obj)
         }
     };
+    [$obj:expr $(,)?] => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "expected a selector after the receiver, as in `msg_send![",
+            $crate::__macro_helpers::stringify!($obj),
+            ", selector]` or `msg_send![",
+            $crate::__macro_helpers::stringify!($obj),
+            ", selector: argument]`",
+        ))
+    };
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __msg_send_helper {
     {
-        ($($fn_args:tt)+)
+        ($receiver:expr $(, $fn_args:expr)*)
         ($fn:ident)
         ($($selector:tt)*)
         ($($argument:expr,)*)
@@ -1009,12 +1034,36 @@ macro_rules! __msg_send_helper {
         // Always add trailing comma after each argument, so that we get a
         // 1-tuple if there is only one.
         //
+        // The receiver and each argument is checked separately, so that
+        // errors about invalid types point at the offending expression.
+        //
         // And use `::<_, _>` for better UI
-        result = $crate::__macro_helpers::MsgSend::$fn::<_, _>($($fn_args)+, $crate::sel!($($selector)*), ($($argument,)*));
+        result = $crate::__macro_helpers::MsgSend::$fn::<_, _>(
+            $crate::__msg_send_check_receiver!($fn, $receiver),
+            $($fn_args,)*
+            $crate::sel!($($selector)*),
+            ($($crate::__macro_helpers::check_argument($argument),)*),
+        );
         result
     });
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __msg_send_check_receiver {
+    // `super(obj)` has more specific requirements on the receiver, which
+    // give better errors on their own.
+    (send_super_message_static, $receiver:expr) => {
+        $receiver
+    };
+    (send_super_message_static_error, $receiver:expr) => {
+        $receiver
+    };
+    ($fn:ident, $receiver:expr) => {
+        $crate::__macro_helpers::check_receiver($receiver)
+    };
+}
+
 /// Deprecated. Use [`msg_send!`] instead.
 #[macro_export]
 #[deprecated = "use a normal msg_send! instead, it will perform the conversion for you"]
@@ -1026,6 +1075,88 @@ macro_rules! msg_send_bool {
     });
 }
 
+/// Send the same message to each object in an iterator.
+///
+/// This uses the same syntax as [`msg_send!`], except that the receiver is
+/// an iterator of receivers, and that the method must return `()`. The
+/// arguments are evaluated once before the first message is sent, and must
+/// therefore be [`Copy`].
+///
+/// Internally, this uses a [`BatchSender`], which caches the method
+/// implementation between each message send, and can thereby be noticeably
+/// faster than calling [`msg_send!`] in a loop when the objects are mostly of
+/// the same class.
+///
+/// [`BatchSender`]: crate::runtime::BatchSender
+///
+///
+/// # Safety
+///
+/// This shares the same safety requirements as [`msg_send!`], for each of
+/// the objects in the iterator.
+///
+///
+/// # Examples
+///
+/// ```no_run
+/// use objc2::rc::Id;
+/// use objc2::runtime::NSObject;
+/// use objc2::send_batch;
+///
+/// let layers: Vec<Id<NSObject>> = Vec::new(); // Some `CALayer`s
+/// unsafe { send_batch![&layers, setHidden: true] };
+/// unsafe { send_batch![layers.iter().skip(1), setOpacity: 0.5f32] };
+/// ```
+#[macro_export]
+macro_rules! send_batch {
+    [$objects:expr, $($selector_and_arguments:tt)+] => {
+        $crate::__msg_send_parse! {
+            (send_batch_message_error)
+            ()
+            ()
+            ($($selector_and_arguments)+)
+            (send_batch_message)
+
+            ($crate::__send_batch_helper)
+            ($objects)
+        }
+    };
+}
+
+/// Helper macro to avoid exposing these in the docs for [`send_batch!`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __send_batch_helper {
+    {
+        ($objects:expr)
+        (send_batch_message_error)
+        ($($selector:tt)*)
+        ($($argument:expr,)*)
+    } => {{
+        $crate::__macro_helpers::compile_error!(
+            "send_batch! does not support error parameters, use msg_send! in a loop instead"
+        )
+    }};
+    {
+        ($objects:expr)
+        (send_batch_message)
+        ($($selector:tt)*)
+        ($($argument:expr,)*)
+    } => ({
+        let mut sender = $crate::runtime::BatchSender::new($crate::sel!($($selector)*));
+        // Always add trailing comma after each argument, so that we get a
+        // 1-tuple if there is only one.
+        let arguments = ($($crate::__macro_helpers::check_argument($argument),)*);
+        for obj in $objects {
+            let _: () = $crate::__macro_helpers::MsgSend::send_batch_message::<_, _>(
+                obj,
+                &mut sender,
+                arguments,
+            );
+        }
+    });
+}
+
 /// [`msg_send!`] for methods returning `id`, `NSObject*`, or similar object
 /// pointers.
 ///
@@ -1120,6 +1251,33 @@ macro_rules! msg_send_bool {
 /// The `retain`, `release` and `autorelease` selectors are not supported, use
 /// [`Id::retain`], [`Id::drop`] and [`Id::autorelease`] for that.
 ///
+/// ## Overriding the method family
+///
+/// Some methods don't follow the naming conventions, and e.g. return a
+/// retained object even though their name does not start with `new`, or are
+/// named `copyFoo` but return an autoreleased object. In Objective-C, such
+/// methods are marked with `__attribute__((objc_method_family(...)))`; when
+/// calling these from Rust, you can instead put the
+/// `#[unsafe(method_family = ...)]` attribute in front of the receiver to
+/// override the family that the macro infers from the selector.
+///
+/// The family must be one of `new`, `alloc`, `init`, `copy`, `mutableCopy`
+/// or `none`, and the receiver and return type must then match what is
+/// described above for that family.
+///
+/// ```no_run
+/// use objc2::msg_send_id;
+/// use objc2::rc::Id;
+/// use objc2::runtime::NSObject;
+/// # let obj = NSObject::new();
+///
+/// // Assume that `-copyCurrentItem` is a method from a third-party library
+/// // which, contrary to convention, returns an autoreleased object.
+/// let _: Id<NSObject> = unsafe {
+///     msg_send_id![#[unsafe(method_family = none)] &obj, copyCurrentItem]
+/// };
+/// ```
+///
 /// [sel-families]: https://clang.llvm.org/docs/AutomaticReferenceCounting.html#arc-method-families
 /// [`MessageReceiver`]: crate::runtime::MessageReceiver
 /// [`Id::retain_autoreleased`]: crate::rc::Id::retain_autoreleased
@@ -1190,6 +1348,12 @@ macro_rules! msg_send_bool {
 /// ```
 #[macro_export]
 macro_rules! msg_send_id {
+    [#[unsafe(method_family = $family:ident)] $($obj_selector_and_arguments:tt)+] => {
+        $crate::__msg_send_id_method_family! {
+            ($family)
+            ($($obj_selector_and_arguments)+)
+        }
+    };
     [super($obj:expr), $($selector_and_arguments:tt)+] => {
         $crate::__msg_send_parse! {
             (send_super_message_id_static_error)
@@ -1257,6 +1421,86 @@ macro_rules! msg_send_id {
     };
 }
 
+/// Map a method family given in `#[unsafe(method_family = ...)]` to the
+/// retain semantics used by [`msg_send_id!`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __msg_send_id_method_family {
+    ((new) ($($rest:tt)+)) => {
+        $crate::__msg_send_id_explicit! { (New) ($($rest)+) }
+    };
+    ((alloc) ($($rest:tt)+)) => {
+        $crate::__msg_send_id_explicit! { (Alloc) ($($rest)+) }
+    };
+    ((init) ($($rest:tt)+)) => {
+        $crate::__msg_send_id_explicit! { (Init) ($($rest)+) }
+    };
+    ((copy) ($($rest:tt)+)) => {
+        $crate::__msg_send_id_explicit! { (CopyOrMutCopy) ($($rest)+) }
+    };
+    ((mutableCopy) ($($rest:tt)+)) => {
+        $crate::__msg_send_id_explicit! { (CopyOrMutCopy) ($($rest)+) }
+    };
+    ((none) ($($rest:tt)+)) => {
+        $crate::__msg_send_id_explicit! { (Other) ($($rest)+) }
+    };
+    (($family:ident) ($($rest:tt)+)) => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "unknown method family `",
+            $crate::__macro_helpers::stringify!($family),
+            "`, expected one of `new`, `alloc`, `init`, `copy`, `mutableCopy` or `none`",
+        ))
+    };
+}
+
+/// [`msg_send_id!`] with explicitly specified retain semantics.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __msg_send_id_explicit {
+    (($retain_semantics:ident) (super($obj:expr), $($selector_and_arguments:tt)+)) => {
+        $crate::__msg_send_parse! {
+            (send_super_message_id_static_error)
+            ()
+            ()
+            ($($selector_and_arguments)+)
+            (send_super_message_id_static)
+
+            ($crate::__msg_send_id_helper)
+            ($obj)
+            ($retain_semantics)
+            (MsgSendSuperId)
+        }
+    };
+    (($retain_semantics:ident) (super($obj:expr, $superclass:expr), $($selector_and_arguments:tt)+)) => {
+        $crate::__msg_send_parse! {
+            (send_super_message_id_error)
+            ()
+            ()
+            ($($selector_and_arguments)+)
+            (send_super_message_id)
+
+            ($crate::__msg_send_id_helper)
+            ($obj, $superclass)
+            ($retain_semantics)
+            (MsgSendSuperId)
+        }
+    };
+    (($retain_semantics:ident) ($obj:expr, $($selector_and_arguments:tt)+)) => {
+        $crate::__msg_send_parse! {
+            (send_message_id_error)
+            ()
+            ()
+            ($($selector_and_arguments)+)
+            (send_message_id)
+
+            ($crate::__msg_send_id_helper)
+            ($obj)
+            ($retain_semantics)
+            (MsgSendId)
+        }
+    };
+}
+
 /// Helper macro to avoid exposing these in the docs for [`msg_send_id!`].
 #[doc(hidden)]
 #[macro_export]
@@ -1320,7 +1564,7 @@ macro_rules! __msg_send_id_helper {
         <$crate::__macro_helpers::$retain_semantics as $crate::__macro_helpers::$trait<_, _>>::$fn(
             $($fn_args)+,
             $crate::sel!($($selector)*),
-            ($($argument,)*),
+            ($($crate::__macro_helpers::check_argument($argument),)*),
         )
     });
     {
@@ -1344,7 +1588,7 @@ macro_rules! __msg_send_id_helper {
                 __SELECTOR_DATA,
                 $crate::__hash_idents!($($selector)*)
             ),
-            ($($argument,)*),
+            ($($crate::__macro_helpers::check_argument($argument),)*),
         );
         result
     });
@@ -0,0 +1,355 @@
+/// Create a new Rust enum to represent an Objective-C enumeration.
+///
+/// This is similar to an `NS_ENUM` declaration in Objective-C, but instead
+/// of a newtype over the integer, a native Rust enum is created that you can
+/// use with `match`.
+///
+/// Objective-C enumerations are open; a newer version of the OS may add new
+/// cases at any time, and such values may be handed to you by the framework,
+/// or round-trip through collections, user defaults, archives and so on.
+/// It would be undefined behaviour to transmute such a value to a Rust enum
+/// that doesn't know about it, so the enum must have a variant that carries
+/// the raw value, written as `Name(_)`, which is used for every value that
+/// doesn't correspond to a known case. The enum is marked
+/// `#[non_exhaustive]` to allow adding new cases to it as well.
+///
+/// Since the layout of such an enum is not the same as the layout of the
+/// underlying integer, it does not implement [`Encode`]. Instead, it is
+/// converted to and from the integer when used as an argument or return
+/// type in [`msg_send!`], [`extern_methods!`], [`extern_protocol!`] and
+/// [`declare_class!`] (similar to how [`bool`] is converted to and from
+/// [`Bool`]), and hence uses the same encoding as the integer type. When
+/// the enum has to be stored somewhere that requires [`Encode`], such as in
+/// a struct field, use the underlying integer and convert it with
+/// `from_raw` / `to_raw` (or `From`).
+///
+/// [`Encode`]: crate::Encode
+/// [`msg_send!`]: crate::msg_send
+/// [`extern_methods!`]: crate::extern_methods
+/// [`extern_protocol!`]: crate::extern_protocol
+/// [`declare_class!`]: crate::declare_class
+/// [`Bool`]: crate::runtime::Bool
+///
+///
+/// # Specification
+///
+/// The enum name and the underlying integer type is specified as
+/// `enum Name: Type`, followed by each known case as `Case = value,`, and
+/// exactly one variant written as `Case(_),` which holds unknown values.
+///
+/// Attributes on the enum and on each case (including `#[cfg(...)]`) are
+/// applied to the enum and to the enum variants, respectively.
+///
+/// The following is implemented for the enum:
+/// - `Clone`, `Copy`, `Debug`, `PartialEq`, `Eq` and `Hash`.
+/// - `const fn from_raw(raw: Type) -> Self`, which never returns the unknown
+///   variant for values that correspond to a known case.
+/// - `const fn to_raw(self) -> Type`.
+/// - Conversions to and from the underlying integer type with `From`.
+///
+/// Note that the unknown variant can be constructed manually with a value
+/// that corresponds to a known case, in which case it will compare unequal
+/// to that case; prefer `from_raw` when converting from the integer.
+///
+///
+/// # Examples
+///
+/// Declare `NSTextAlignment` as a Rust enum, and use it in a method.
+///
+/// ```
+/// use objc2::ffi::NSInteger;
+/// use objc2::{extern_class, extern_methods, mutability, ns_enum, ClassType};
+/// use objc2::runtime::NSObject;
+///
+/// ns_enum!(
+///     #[doc(alias = "NSTextAlignment")]
+///     pub enum TextAlignment: NSInteger {
+///         #[doc(alias = "NSTextAlignmentLeft")]
+///         Left = 0,
+///         #[doc(alias = "NSTextAlignmentRight")]
+///         Right = 2,
+///         #[doc(alias = "NSTextAlignmentCenter")]
+///         Center = 1,
+///         /// A case unknown to these bindings.
+///         Unknown(_),
+///     }
+/// );
+///
+/// assert_eq!(TextAlignment::from_raw(2), TextAlignment::Right);
+/// assert_eq!(TextAlignment::from_raw(42), TextAlignment::Unknown(42));
+/// assert_eq!(TextAlignment::Unknown(42).to_raw(), 42);
+///
+/// extern_class!(
+///     pub struct NSText;
+///
+///     unsafe impl ClassType for NSText {
+///         type Super = NSObject;
+///         type Mutability = mutability::MainThreadOnly;
+///     }
+/// );
+///
+/// extern_methods!(
+///     unsafe impl NSText {
+///         #[method(alignment)]
+///         pub fn alignment(&self) -> TextAlignment;
+///
+///         #[method(setAlignment:)]
+///         pub fn setAlignment(&self, alignment: TextAlignment);
+///     }
+/// );
+/// ```
+#[doc(alias = "NS_ENUM")]
+#[doc(alias = "CF_ENUM")]
+#[macro_export]
+macro_rules! ns_enum {
+    (
+        $(#[$m:meta])*
+        $v:vis enum $name:ident: $ty:ty {
+            $($variants:tt)*
+        }
+    ) => {
+        $crate::__ns_enum_parse! {
+            ($(#[$m])*)
+            ($v)
+            ($name)
+            ($ty)
+            ()
+            ()
+
+            $($variants)*
+        }
+    };
+}
+
+/// tt-munch each variant.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ns_enum_parse {
+    // Base case
+    {
+        ($($m:tt)*)
+        ($v:vis)
+        ($name:ident)
+        ($ty:ty)
+        ($($known:tt)*)
+        ($($unknown:tt)+)
+    } => {
+        $crate::__ns_enum_out! {
+            ($($m)*)
+            ($v)
+            ($name)
+            ($ty)
+            ($($known)*)
+            ($($unknown)+)
+        }
+    };
+
+    // Missing unknown variant
+    {
+        ($($m:tt)*)
+        ($v:vis)
+        ($name:ident)
+        ($ty:ty)
+        ($($known:tt)*)
+        ()
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "`",
+            $crate::__macro_helpers::stringify!($name),
+            "` must have a variant for unknown values, e.g. `Unknown(_)`",
+        ));
+    };
+
+    // Known variant
+    {
+        ($($m:tt)*)
+        ($v:vis)
+        ($name:ident)
+        ($ty:ty)
+        ($($known:tt)*)
+        ($($unknown:tt)*)
+
+        $(#[$($variant_m:tt)*])*
+        $variant:ident = $value:expr
+        $(, $($rest:tt)*)?
+    } => {
+        $crate::__ns_enum_parse! {
+            ($($m)*)
+            ($v)
+            ($name)
+            ($ty)
+            ($($known)* {
+                ($(#[$($variant_m)*])*)
+                ($variant)
+                ($value)
+            })
+            ($($unknown)*)
+
+            $($($rest)*)?
+        }
+    };
+
+    // Unknown variant
+    {
+        ($($m:tt)*)
+        ($v:vis)
+        ($name:ident)
+        ($ty:ty)
+        ($($known:tt)*)
+        ()
+
+        $(#[$($unknown_m:tt)*])*
+        $unknown:ident(_)
+        $(, $($rest:tt)*)?
+    } => {
+        $crate::__ns_enum_parse! {
+            ($($m)*)
+            ($v)
+            ($name)
+            ($ty)
+            ($($known)*)
+            ($(#[$($unknown_m)*])* $unknown)
+
+            $($($rest)*)?
+        }
+    };
+
+    // Duplicate unknown variant
+    {
+        ($($m:tt)*)
+        ($v:vis)
+        ($name:ident)
+        ($ty:ty)
+        ($($known:tt)*)
+        ($($first_unknown:tt)+)
+
+        $(#[$($unknown_m:tt)*])*
+        $unknown:ident(_)
+        $(, $($rest:tt)*)?
+    } => {
+        $crate::__macro_helpers::compile_error!($crate::__macro_helpers::concat!(
+            "`",
+            $crate::__macro_helpers::stringify!($name),
+            "` can only have one variant for unknown values, found `",
+            $crate::__macro_helpers::stringify!($unknown),
+            "(_)`",
+        ));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ns_enum_out {
+    {
+        ($($m:tt)*)
+        ($v:vis)
+        ($name:ident)
+        ($ty:ty)
+        ($({
+            ($($variant_m:tt)*)
+            ($variant:ident)
+            ($value:expr)
+        })*)
+        ($(#[$($unknown_m:tt)*])* $unknown:ident)
+    } => {
+        $($m)*
+        #[non_exhaustive]
+        #[derive(
+            $crate::__macro_helpers::Clone,
+            $crate::__macro_helpers::Copy,
+            $crate::__macro_helpers::fmt::Debug,
+            $crate::__macro_helpers::PartialEq,
+            $crate::__macro_helpers::Eq,
+            $crate::__macro_helpers::Hash,
+        )]
+        $v enum $name {
+            $(
+                $($variant_m)*
+                $variant,
+            )*
+            $(#[$($unknown_m)*])*
+            $unknown($ty),
+        }
+
+        impl $name {
+            /// Convert from the underlying integer value.
+            #[inline]
+            #[allow(deprecated)]
+            pub const fn from_raw(raw: $ty) -> Self {
+                $(
+                    $crate::__extract_and_apply_cfg_attributes! {
+                        ($($variant_m)*)
+                        if raw == $value {
+                            return Self::$variant;
+                        }
+                    }
+                )*
+                Self::$unknown(raw)
+            }
+
+            /// Convert to the underlying integer value.
+            #[inline]
+            #[allow(deprecated)]
+            pub const fn to_raw(self) -> $ty {
+                if let Self::$unknown(raw) = self {
+                    return raw;
+                }
+                $(
+                    $crate::__extract_and_apply_cfg_attributes! {
+                        ($($variant_m)*)
+                        if let Self::$variant = self {
+                            return $value;
+                        }
+                    }
+                )*
+                $crate::__macro_helpers::unreachable!()
+            }
+        }
+
+        impl $crate::__macro_helpers::From<$ty> for $name {
+            #[inline]
+            fn from(raw: $ty) -> Self {
+                Self::from_raw(raw)
+            }
+        }
+
+        impl $crate::__macro_helpers::From<$name> for $ty {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.to_raw()
+            }
+        }
+
+        impl $crate::__macro_helpers::ConvertArgumentSealed for $name {}
+        impl $crate::__macro_helpers::ConvertArgument for $name {
+            type __Inner = $ty;
+
+            type __StoredBeforeMessage = ();
+
+            #[inline]
+            fn __from_declared_param(inner: Self::__Inner) -> Self {
+                Self::from_raw(inner)
+            }
+
+            #[inline]
+            fn __into_argument(self) -> (Self::__Inner, Self::__StoredBeforeMessage) {
+                (self.to_raw(), ())
+            }
+        }
+
+        impl $crate::__macro_helpers::ConvertReturnSealed for $name {}
+        impl $crate::__macro_helpers::ConvertReturn for $name {
+            type __Inner = $ty;
+
+            #[inline]
+            fn __into_declared_return(self) -> Self::__Inner {
+                self.to_raw()
+            }
+
+            #[inline]
+            fn __from_return(inner: Self::__Inner) -> Self {
+                Self::from_raw(inner)
+            }
+        }
+    };
+}
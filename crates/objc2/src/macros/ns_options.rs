@@ -0,0 +1,326 @@
+/// Create a new type to represent an Objective-C option set.
+///
+/// This is similar to an `NS_OPTIONS` or `CF_OPTIONS` declaration in
+/// Objective-C, and is what the framework crates use for types like
+/// `NSStringCompareOptions` and `NSWindowStyleMask`.
+///
+/// The type is created as a `#[repr(transparent)]` newtype over the given
+/// integer type, with the specified flags as associated constants. The
+/// integer is exposed as a public field; external code may construct
+/// arbitrary values from the raw bits, and the type itself makes no attempt
+/// at truncating bits that don't correspond to any known flag (new flags are
+/// added to Apple's frameworks regularly, and they must round-trip through
+/// Rust unchanged).
+///
+/// The following is implemented for the new type:
+/// - [`Encode`] and [`RefEncode`], with the same encoding as the underlying
+///   integer type (usually `NSUInteger`).
+/// - `Clone`, `Copy`, `PartialEq`, `Eq`, `Hash`, `PartialOrd` and `Ord`.
+/// - The bitwise operators `|`, `&`, `^`, `!` and `-` (difference), along
+///   with their assigning counterparts.
+/// - Conversions to and from the underlying integer type with `From`.
+/// - `Debug`, which prints the names of the flags that are set, followed by
+///   any remaining unknown bits in hexadecimal, e.g.
+///   `NSStringCompareOptions(CaseInsensitiveSearch | NumericSearch | 0x1000)`.
+///
+/// Additionally, the type has a set of inherent methods similar to those
+/// generated by the [`bitflags`] crate, such as `empty`, `bits`, `contains`,
+/// `insert` and `remove`.
+///
+/// [`Encode`]: crate::Encode
+/// [`RefEncode`]: crate::RefEncode
+/// [`bitflags`]: https://docs.rs/bitflags/2/bitflags/
+///
+///
+/// # Specification
+///
+/// The syntax mirrors the `bitflags!` macro, that is, the struct name and
+/// the underlying integer type is specified as `struct Name: Type`, followed
+/// by each flag as `const Flag = value;`.
+///
+/// Attributes on the struct are applied to the struct, and attributes on each
+/// flag (including `#[cfg(...)]` and `#[deprecated]`) are applied to the
+/// associated constant.
+///
+/// Flags with a value of zero are never printed in the `Debug`
+/// implementation, and flags whose bits are covered by a previously printed
+/// flag are skipped.
+///
+///
+/// # Examples
+///
+/// Declare `NSStringCompareOptions` and use it.
+///
+/// ```
+/// use objc2::ffi::NSUInteger;
+/// use objc2::{ns_options, Encode, Encoding};
+///
+/// ns_options!(
+///     /// Options for comparing strings.
+///     #[doc(alias = "NSStringCompareOptions")]
+///     pub struct StringCompareOptions: NSUInteger {
+///         #[doc(alias = "NSCaseInsensitiveSearch")]
+///         const CaseInsensitiveSearch = 1;
+///         #[doc(alias = "NSLiteralSearch")]
+///         const LiteralSearch = 2;
+///         #[doc(alias = "NSBackwardsSearch")]
+///         const BackwardsSearch = 4;
+///         #[doc(alias = "NSNumericSearch")]
+///         const NumericSearch = 64;
+///     }
+/// );
+///
+/// let options = StringCompareOptions::CaseInsensitiveSearch | StringCompareOptions::NumericSearch;
+/// assert!(options.contains(StringCompareOptions::NumericSearch));
+/// assert!(!options.contains(StringCompareOptions::LiteralSearch));
+/// assert_eq!(options.bits(), 65);
+///
+/// assert_eq!(
+///     format!("{options:?}"),
+///     "StringCompareOptions(CaseInsensitiveSearch | NumericSearch)",
+/// );
+/// assert_eq!(
+///     format!("{:?}", StringCompareOptions::from(0x1001)),
+///     "StringCompareOptions(CaseInsensitiveSearch | 0x1000)",
+/// );
+/// assert_eq!(
+///     format!("{:?}", StringCompareOptions::empty()),
+///     "StringCompareOptions(0x0)",
+/// );
+///
+/// // The encoding is the same as the underlying integer
+/// assert_eq!(StringCompareOptions::ENCODING, NSUInteger::ENCODING);
+/// ```
+#[doc(alias = "NS_OPTIONS")]
+#[doc(alias = "CF_OPTIONS")]
+#[doc(alias = "bitflags")]
+#[macro_export]
+macro_rules! ns_options {
+    (
+        $(#[$m:meta])*
+        $v:vis struct $name:ident: $ty:ty {
+            $(
+                $(#[$($flag_m:tt)*])*
+                const $flag:ident = $value:expr;
+            )*
+        }
+    ) => {
+        $(#[$m])*
+        #[repr(transparent)]
+        #[derive(
+            $crate::__macro_helpers::Clone,
+            $crate::__macro_helpers::Copy,
+            $crate::__macro_helpers::PartialEq,
+            $crate::__macro_helpers::Eq,
+            $crate::__macro_helpers::Hash,
+            $crate::__macro_helpers::PartialOrd,
+            $crate::__macro_helpers::Ord,
+        )]
+        $v struct $name(pub $ty);
+
+        impl $name {
+            $(
+                $(#[$($flag_m)*])*
+                pub const $flag: Self = Self($value);
+            )*
+
+            /// Get a value with no flags set.
+            #[inline]
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            /// Get the underlying bits of the value.
+            #[inline]
+            pub const fn bits(self) -> $ty {
+                self.0
+            }
+
+            /// Convert from the underlying bits, retaining any bits that
+            /// don't correspond to a known flag.
+            #[inline]
+            pub const fn from_bits_retain(bits: $ty) -> Self {
+                Self(bits)
+            }
+
+            /// Whether no bits are set.
+            #[inline]
+            pub const fn is_empty(self) -> $crate::__macro_helpers::bool {
+                self.0 == 0
+            }
+
+            /// Whether all the bits in `other` are also set in `self`.
+            #[inline]
+            pub const fn contains(self, other: Self) -> $crate::__macro_helpers::bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Whether any of the bits in `other` are also set in `self`.
+            #[inline]
+            pub const fn intersects(self, other: Self) -> $crate::__macro_helpers::bool {
+                self.0 & other.0 != 0
+            }
+
+            /// The bitwise or (`|`) of the bits in `self` and `other`.
+            #[inline]
+            #[must_use]
+            pub const fn union(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+
+            /// The bitwise and (`&`) of the bits in `self` and `other`.
+            #[inline]
+            #[must_use]
+            pub const fn intersection(self, other: Self) -> Self {
+                Self(self.0 & other.0)
+            }
+
+            /// The bits in `self` that are not set in `other`.
+            #[inline]
+            #[must_use]
+            pub const fn difference(self, other: Self) -> Self {
+                Self(self.0 & !other.0)
+            }
+
+            /// Set the bits in `other`.
+            #[inline]
+            pub fn insert(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+
+            /// Unset the bits in `other`.
+            #[inline]
+            pub fn remove(&mut self, other: Self) {
+                self.0 &= !other.0;
+            }
+
+            /// Toggle the bits in `other`.
+            #[inline]
+            pub fn toggle(&mut self, other: Self) {
+                self.0 ^= other.0;
+            }
+
+            /// Set or unset the bits in `other` depending on `value`.
+            #[inline]
+            pub fn set(&mut self, other: Self, value: $crate::__macro_helpers::bool) {
+                if value {
+                    self.insert(other);
+                } else {
+                    self.remove(other);
+                }
+            }
+        }
+
+        // SAFETY: The type is a `#[repr(transparent)]` newtype over the
+        // integer type.
+        unsafe impl $crate::Encode for $name {
+            const ENCODING: $crate::Encoding = <$ty as $crate::Encode>::ENCODING;
+        }
+
+        // SAFETY: Same as above.
+        unsafe impl $crate::RefEncode for $name {
+            const ENCODING_REF: $crate::Encoding =
+                $crate::Encoding::Pointer(&<Self as $crate::Encode>::ENCODING);
+        }
+
+        impl $crate::__macro_helpers::From<$ty> for $name {
+            #[inline]
+            fn from(bits: $ty) -> Self {
+                Self(bits)
+            }
+        }
+
+        impl $crate::__macro_helpers::From<$name> for $ty {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        $crate::__ns_options_binary_op!($name, BitOr, bitor, BitOrAssign, bitor_assign, |);
+        $crate::__ns_options_binary_op!($name, BitAnd, bitand, BitAndAssign, bitand_assign, &);
+        $crate::__ns_options_binary_op!($name, BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+        impl $crate::__macro_helpers::Sub for $name {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, other: Self) -> Self {
+                self.difference(other)
+            }
+        }
+
+        impl $crate::__macro_helpers::SubAssign for $name {
+            #[inline]
+            fn sub_assign(&mut self, other: Self) {
+                self.remove(other);
+            }
+        }
+
+        impl $crate::__macro_helpers::Not for $name {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self {
+                Self(!self.0)
+            }
+        }
+
+        impl $crate::__macro_helpers::fmt::Debug for $name {
+            #[allow(deprecated)]
+            fn fmt(
+                &self,
+                f: &mut $crate::__macro_helpers::fmt::Formatter<'_>,
+            ) -> $crate::__macro_helpers::fmt::Result {
+                f.write_str($crate::__macro_helpers::stringify!($name))?;
+                f.write_str("(")?;
+                let mut remaining = self.0;
+                let mut first = true;
+                $(
+                    $crate::__extract_and_apply_cfg_attributes! {
+                        ($(#[$($flag_m)*])*)
+                        let flag = Self::$flag.0;
+                        if flag != 0 && remaining & flag != 0 && self.0 & flag == flag {
+                            if !first {
+                                f.write_str(" | ")?;
+                            }
+                            first = false;
+                            f.write_str($crate::__macro_helpers::stringify!($flag))?;
+                            remaining &= !flag;
+                        }
+                    }
+                )*
+                if first || remaining != 0 {
+                    if !first {
+                        f.write_str(" | ")?;
+                    }
+                    f.write_str("0x")?;
+                    $crate::__macro_helpers::fmt::LowerHex::fmt(&remaining, f)?;
+                }
+                f.write_str(")")
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ns_options_binary_op {
+    ($name:ident, $trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:tt) => {
+        impl $crate::__macro_helpers::$trait for $name {
+            type Output = Self;
+
+            #[inline]
+            fn $fn(self, other: Self) -> Self {
+                Self(self.0 $op other.0)
+            }
+        }
+
+        impl $crate::__macro_helpers::$assign_trait for $name {
+            #[inline]
+            fn $assign_fn(&mut self, other: Self) {
+                *self = Self(self.0 $op other.0);
+            }
+        }
+    };
+}
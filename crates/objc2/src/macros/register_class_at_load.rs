@@ -0,0 +1,84 @@
+/// Register classes with the Objective-C runtime when the binary is loaded.
+///
+/// Classes created with [`declare_class!`] are normally registered lazily,
+/// the first time that [`ClassType::class`] is called. This is usually what
+/// you want, but it means that the class is invisible to code that looks it
+/// up by name, such as `NSClassFromString`, nibs, storyboards or
+/// `NSKeyedUnarchiver`, until some Rust code has touched it.
+///
+/// This macro emits a static initializer (placed in `__mod_init_func` on
+/// Apple platforms, and in `.init_array` / `.CRT$XCU` elsewhere) that calls
+/// [`ClassType::class`] for each of the given types before `main` runs, or
+/// when the dynamic library containing it is loaded. This matches how
+/// classes written in Objective-C behave.
+///
+/// Registration still goes through [`ClassType::class`], so calling that
+/// afterwards is cheap and returns the same class.
+///
+/// [`declare_class!`]: crate::declare_class
+/// [`ClassType::class`]: crate::ClassType::class
+///
+///
+/// # Panics
+///
+/// The initializer runs before `main`, where unwinding is not possible. If
+/// registering a class panics (e.g. because a class with the same name
+/// already exists), the process will abort.
+///
+/// In general, you should keep the work done in the class declaration
+/// minimal when using this, since it runs before the rest of the program has
+/// been set up.
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2::runtime::{AnyClass, NSObject};
+/// use objc2::{declare_class, mutability, register_class_at_load, ClassType, DeclaredClass};
+///
+/// declare_class!(
+///     struct MyLoadTimeObject;
+///
+///     unsafe impl ClassType for MyLoadTimeObject {
+///         type Super = NSObject;
+///         type Mutability = mutability::InteriorMutable;
+///         const NAME: &'static str = "MyLoadTimeObject";
+///     }
+///
+///     impl DeclaredClass for MyLoadTimeObject {}
+/// );
+///
+/// register_class_at_load!(MyLoadTimeObject);
+///
+/// // The class is available by name without first calling
+/// // `MyLoadTimeObject::class()`.
+/// let cls = AnyClass::get("MyLoadTimeObject").unwrap();
+/// assert_eq!(cls, MyLoadTimeObject::class());
+/// ```
+#[doc(alias = "__mod_init_func")]
+#[doc(alias = "+load")]
+#[macro_export]
+macro_rules! register_class_at_load {
+    ($($cls:ty),+ $(,)?) => {
+        const _: () = {
+            extern "C" fn __objc2_register_classes_at_load() {
+                $(
+                    let _ = <$cls as $crate::ClassType>::class();
+                )+
+            }
+
+            #[cfg_attr(
+                target_vendor = "apple",
+                link_section = "__DATA,__mod_init_func,mod_init_funcs"
+            )]
+            #[cfg_attr(
+                all(not(target_vendor = "apple"), not(windows)),
+                link_section = ".init_array"
+            )]
+            #[cfg_attr(windows, link_section = ".CRT$XCU")]
+            #[used] // Make sure this reaches the linker
+            static __OBJC2_REGISTER_CLASSES_AT_LOAD: extern "C" fn() =
+                __objc2_register_classes_at_load;
+        };
+    };
+}
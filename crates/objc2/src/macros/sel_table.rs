@@ -0,0 +1,111 @@
+/// Create a static [`SelTable`], a lookup table keyed by selector.
+///
+/// The syntax is a `static` item whose initializer is a list of selectors
+/// (in the same syntax as [`sel!`]) mapped to values with `=>`. The values
+/// must be constant expressions, e.g. function pointers.
+///
+/// The perfect hash function for the table is computed at compile time, so
+/// the table should be kept reasonably small (on the order of a few hundred
+/// entries at most).
+///
+/// [`SelTable`]: crate::runtime::SelTable
+/// [`sel!`]: crate::sel
+///
+///
+/// # Panics
+///
+/// Fails to compile if the same selector is given twice.
+///
+///
+/// # Example
+///
+/// See [`SelTable`].
+#[macro_export]
+macro_rules! sel_table {
+    // Fast path for selectors without `::`, which avoids recursion.
+    {
+        $(#[$m:meta])*
+        $v:vis static $name:ident: $table:ident<$ty:ty> = {
+            $($first:ident $(: $($rest:ident)?)* => $value:expr),* $(,)?
+        };
+    } => {
+        $crate::__sel_table_parse! {
+            ($(#[$m])* $v $name $ty)
+            ($(($first $(: $($rest)?)*) => $value,)*)
+            ()
+        }
+    };
+    {
+        $(#[$m:meta])*
+        $v:vis static $name:ident: $table:ident<$ty:ty> = {
+            $($entries:tt)*
+        };
+    } => {
+        $crate::__sel_table_parse! {
+            ($(#[$m])* $v $name $ty)
+            ()
+            ()
+            $($entries)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sel_table_parse {
+    // Base case
+    {
+        ($(#[$m:meta])* $v:vis $name:ident $ty:ty)
+        ($(($($sel:tt)*) => $value:expr,)*)
+        ()
+    } => {
+        $(#[$m])*
+        $v static $name: $crate::runtime::SelTable<$ty> = {
+            const KEYS: &[$crate::runtime::SelKey] = &[$(
+                $crate::runtime::SelKey::new($crate::__sel_helper! {
+                    ()
+                    $($sel)*
+                }),
+            )*];
+            const N: usize = KEYS.len();
+            const SLOTS: usize = $crate::__macro_helpers::SelTableLayout::slots(N);
+            const BUCKETS: usize = $crate::__macro_helpers::SelTableLayout::buckets(N);
+            const LAYOUT: $crate::__macro_helpers::SelTableLayout<SLOTS, BUCKETS> =
+                $crate::__macro_helpers::SelTableLayout::build::<N>(KEYS);
+
+            static VALUES: [$ty; N] = [$($value,)*];
+            static SLOTS_DATA: [u32; SLOTS] = LAYOUT.slots;
+            static DISPLACEMENTS: [(u32, u32); BUCKETS] = LAYOUT.displacements;
+
+            $crate::runtime::SelTable::__new(KEYS, &VALUES, &SLOTS_DATA, &DISPLACEMENTS, LAYOUT.seed)
+        };
+    };
+    // Found the value of an entry
+    {
+        ($($args:tt)*)
+        ($($done:tt)*)
+        ($($sel:tt)+)
+        => $value:expr $(, $($rest:tt)*)?
+    } => {
+        $crate::__sel_table_parse! {
+            ($($args)*)
+            ($($done)* ($($sel)+) => $value,)
+            ()
+            $($($rest)*)?
+        }
+    };
+    // Munch a token of the selector
+    {
+        ($($args:tt)*)
+        ($($done:tt)*)
+        ($($sel:tt)*)
+        $next:tt $($rest:tt)*
+    } => {
+        $crate::__sel_table_parse! {
+            ($($args)*)
+            ($($done)*)
+            ($($sel)* $next)
+            $($rest)*
+        }
+    };
+}
@@ -0,0 +1,78 @@
+/// Create a class name that is unique to the current crate version.
+///
+/// If two versions of a crate that both declare a class with
+/// [`declare_class!`] end up in the same binary, registering the second
+/// class will fail, since class names are global in Objective-C. This macro
+/// can be used as the value of [`ClassType::NAME`] to avoid that, by adding
+/// a suffix with the name and version of the crate, and a hash of those and
+/// the module path.
+///
+/// The class can be looked up by the name without the suffix with
+/// [`AnyClass::get_unmangled`], and [`AnyClass::unmangled_name`] strips the
+/// suffix again.
+///
+/// Note that the mangled name changes with every version of the crate, so
+/// this should not be used for classes whose name is stored or referenced
+/// elsewhere, such as classes that are archived with `NSKeyedArchiver`, or
+/// referenced by name in nibs or storyboards.
+///
+/// This reads the `CARGO_PKG_NAME` and `CARGO_PKG_VERSION` environment
+/// variables, and so only works when compiling with Cargo.
+///
+/// [`declare_class!`]: crate::declare_class
+/// [`ClassType::NAME`]: crate::ClassType::NAME
+/// [`AnyClass::get_unmangled`]: crate::runtime::AnyClass::get_unmangled
+/// [`AnyClass::unmangled_name`]: crate::runtime::AnyClass::unmangled_name
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2::runtime::{AnyClass, NSObject};
+/// use objc2::{declare_class, mutability, unique_class_name, ClassType, DeclaredClass};
+///
+/// declare_class!(
+///     struct MyDelegate;
+///
+///     unsafe impl ClassType for MyDelegate {
+///         type Super = NSObject;
+///         type Mutability = mutability::InteriorMutable;
+///         const NAME: &'static str = unique_class_name!("MyDelegate");
+///     }
+///
+///     impl DeclaredClass for MyDelegate {}
+/// );
+///
+/// let cls = MyDelegate::class();
+/// assert_ne!(cls.name(), "MyDelegate");
+/// assert_eq!(cls.unmangled_name(), "MyDelegate");
+/// assert_eq!(AnyClass::get_unmangled("MyDelegate"), Some(cls));
+/// ```
+#[macro_export]
+macro_rules! unique_class_name {
+    ($name:expr) => {{
+        const __OBJC2_NAME: &$crate::__macro_helpers::str = $name;
+        const __OBJC2_CRATE_NAME: &$crate::__macro_helpers::str =
+            $crate::__macro_helpers::env!("CARGO_PKG_NAME");
+        const __OBJC2_CRATE_VERSION: &$crate::__macro_helpers::str =
+            $crate::__macro_helpers::env!("CARGO_PKG_VERSION");
+        const __OBJC2_LEN: $crate::__macro_helpers::usize =
+            $crate::__macro_helpers::unique_class_name_len(
+                __OBJC2_NAME,
+                __OBJC2_CRATE_NAME,
+                __OBJC2_CRATE_VERSION,
+            );
+        const __OBJC2_BYTES: [$crate::__macro_helpers::u8; __OBJC2_LEN] =
+            $crate::__macro_helpers::unique_class_name(
+                __OBJC2_NAME,
+                __OBJC2_CRATE_NAME,
+                __OBJC2_CRATE_VERSION,
+                $crate::__macro_helpers::module_path!(),
+            );
+        // SAFETY: The name is valid UTF-8, and only ASCII bytes are
+        // appended to it.
+        const __OBJC2_MANGLED: &$crate::__macro_helpers::str =
+            unsafe { $crate::__macro_helpers::from_utf8_unchecked(&__OBJC2_BYTES) };
+        __OBJC2_MANGLED
+    }};
+}
@@ -0,0 +1,148 @@
+//! Detecting the Apple platform at compile-time and at runtime.
+#[cfg(all(feature = "apple", target_os = "ios", not(target_abi_macabi)))]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// An operating system that Objective-C code can run on.
+///
+/// Use [`Platform::TARGET`] for the platform that the code was compiled
+/// for, and [`Platform::current`] for the platform that the process is
+/// running on. These differ when an unmodified iOS app runs on a Mac with
+/// Apple silicon.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::Platform;
+///
+/// if matches!(Platform::current(), Platform::MacCatalyst | Platform::IOSAppOnMac) {
+///     // Use the menu bar
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Platform {
+    /// macOS.
+    MacOS,
+    /// iOS and iPadOS.
+    IOS,
+    /// An iOS app built for the Mac with Mac Catalyst (the `*-apple-ios-macabi`
+    /// targets).
+    ///
+    /// Such apps have access to both UIKit and (parts of) AppKit, and use
+    /// the values of enumerations that differ between the two from macOS.
+    MacCatalyst,
+    /// An iOS app running unmodified on a Mac with Apple silicon ("Designed
+    /// for iPad").
+    ///
+    /// This can only be detected at runtime, so it is never
+    /// [`Platform::TARGET`].
+    IOSAppOnMac,
+    /// tvOS.
+    TvOS,
+    /// watchOS.
+    WatchOS,
+    /// visionOS.
+    VisionOS,
+    /// A non-Apple platform, e.g. Linux or Windows with GNUStep.
+    Other,
+}
+
+impl Platform {
+    /// The platform that the code was compiled for.
+    pub const TARGET: Self = if cfg!(target_os = "macos") {
+        Self::MacOS
+    } else if cfg!(all(target_os = "ios", target_abi_macabi)) {
+        Self::MacCatalyst
+    } else if cfg!(target_os = "ios") {
+        Self::IOS
+    } else if cfg!(target_os = "tvos") {
+        Self::TvOS
+    } else if cfg!(target_os = "watchos") {
+        Self::WatchOS
+    } else if cfg!(target_os = "visionos") {
+        Self::VisionOS
+    } else {
+        Self::Other
+    };
+
+    /// The platform that the process is running on.
+    ///
+    /// This is the same as [`Platform::TARGET`], except for iOS apps running
+    /// on a Mac, which is detected with `-[NSProcessInfo isiOSAppOnMac]`
+    /// (and thus requires Foundation to be loaded).
+    #[doc(alias = "isiOSAppOnMac")]
+    #[doc(alias = "isMacCatalystApp")]
+    pub fn current() -> Self {
+        #[cfg(all(feature = "apple", target_os = "ios", not(target_abi_macabi)))]
+        if is_ios_app_on_mac() {
+            return Self::IOSAppOnMac;
+        }
+        Self::TARGET
+    }
+
+    /// Whether this is a platform where apps run on a Mac, i.e. macOS, Mac
+    /// Catalyst or iOS apps on a Mac.
+    #[inline]
+    pub const fn is_mac(self) -> bool {
+        matches!(self, Self::MacOS | Self::MacCatalyst | Self::IOSAppOnMac)
+    }
+}
+
+/// 0 = unknown, 1 = no, 2 = yes.
+#[cfg(all(feature = "apple", target_os = "ios", not(target_abi_macabi)))]
+static IS_IOS_APP_ON_MAC: AtomicU8 = AtomicU8::new(0);
+
+#[cfg(all(feature = "apple", target_os = "ios", not(target_abi_macabi)))]
+fn is_ios_app_on_mac() -> bool {
+    use crate::runtime::{AnyClass, AnyObject};
+    use crate::{msg_send, sel};
+
+    match IS_IOS_APP_ON_MAC.load(Ordering::Relaxed) {
+        1 => return false,
+        2 => return true,
+        _ => {}
+    }
+
+    let res = AnyClass::get("NSProcessInfo").map_or(false, |cls| {
+        // SAFETY: `processInfo` returns the shared process info, and
+        // `isiOSAppOnMac` is only sent if it is available (iOS 14.0).
+        unsafe {
+            let info: *mut AnyObject = msg_send![cls, processInfo];
+            let responds: bool = msg_send![info, respondsToSelector: sel!(isiOSAppOnMac)];
+            responds && msg_send![info, isiOSAppOnMac]
+        }
+    });
+
+    // Racing here is fine, the result is the same.
+    IS_IOS_APP_ON_MAC.store(if res { 2 } else { 1 }, Ordering::Relaxed);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target() {
+        if cfg!(target_os = "macos") {
+            assert_eq!(Platform::TARGET, Platform::MacOS);
+            assert!(Platform::TARGET.is_mac());
+        }
+        if cfg!(not(target_vendor = "apple")) {
+            assert_eq!(Platform::TARGET, Platform::Other);
+            assert!(!Platform::TARGET.is_mac());
+        }
+    }
+
+    #[test]
+    fn test_current() {
+        let current = Platform::current();
+        if current != Platform::TARGET {
+            assert_eq!(Platform::TARGET, Platform::IOS);
+            assert_eq!(current, Platform::IOSAppOnMac);
+        }
+        // Cached.
+        assert_eq!(Platform::current(), current);
+    }
+}
@@ -0,0 +1,330 @@
+//! # Loading dynamic libraries that declare classes.
+//!
+//! A plugin is a dynamic library (e.g. a Rust `cdylib`, possibly wrapped in
+//! an `NSBundle`) that declares classes with [`declare_class!`], and which
+//! is loaded by the host application at runtime. Classes in Rust are
+//! registered with the Objective-C runtime lazily, so after loading such a
+//! library, its classes would normally not be visible to the runtime until
+//! the plugin itself calls [`ClassType::class`].
+//!
+//! This module uses the [class registry] to find the classes that the
+//! plugin declares, checks that none of them clash with a class that
+//! already exists in the process, and then registers them, so that they can
+//! be looked up by name (e.g. with [`AnyClass::get`] or
+//! `NSClassFromString`).
+//!
+//! Use [`load`] to load a library with `dlopen`. If you load the library
+//! some other way, such as with `-[NSBundle loadAndReturnError:]`, take a
+//! [`Snapshot`] beforehand, and call [`Snapshot::register_new_classes`]
+//! afterwards.
+//!
+//! This module is only available when the `"plugin-loader"` Cargo feature
+//! is enabled, and the plugin must have been built with the
+//! `"class-registry"` feature of `objc2` enabled.
+//!
+//! [`declare_class!`]: crate::declare_class
+//! [`ClassType::class`]: crate::ClassType::class
+//! [class registry]: crate::class_registry
+//!
+//!
+//! # Unloading
+//!
+//! Plugins are never unloaded, since the runtime and the class registry
+//! keep referring to the data in the library. This also applies when
+//! loading fails because of a conflict.
+//!
+//!
+//! # Conflicts
+//!
+//! A class in the plugin conflicts with the process if a class with the same
+//! name is registered with the runtime, or has been declared in Rust by
+//! previously loaded code. Classes in the plugin also conflict with each
+//! other if they have the same name. The classes in the plugin are only
+//! registered if there are no conflicts.
+//!
+//! Note that classes that the plugin registers while it is being loaded
+//! (e.g. with [`register_class_at_load!`]) cannot be checked beforehand; if
+//! such a class conflicts, the process is aborted.
+//!
+//! [`register_class_at_load!`]: crate::register_class_at_load
+//!
+//!
+//! # Example
+//!
+#![cfg_attr(unix, doc = "```no_run")]
+#![cfg_attr(not(unix), doc = "```ignore")]
+//! use objc2::plugin;
+//! use objc2::runtime::AnyClass;
+//!
+//! // SAFETY: The plugin is trusted.
+//! let plugin = unsafe { plugin::load("target/debug/libmy_plugin.dylib") }.unwrap();
+//! for info in plugin.classes() {
+//!     println!("loaded {}", info.name());
+//! }
+//! let cls = AnyClass::get("MyPluginObject").unwrap();
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ptr;
+use std::collections::HashSet;
+
+use crate::class_registry::{self, ClassInfo, ClassKind};
+use crate::runtime::{AnyClass, ClassRegistrationError};
+
+/// The state of the process before loading a plugin.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Debug)]
+pub struct Snapshot {
+    last: Option<&'static ClassInfo>,
+    names: HashSet<&'static str>,
+}
+
+impl Snapshot {
+    /// Record the classes that currently exist in the process.
+    pub fn take() -> Self {
+        let mut names: HashSet<&'static str> =
+            AnyClass::classes().iter().map(|cls| cls.name()).collect();
+        names.extend(
+            class_registry::classes()
+                .filter(|info| info.kind() == ClassKind::Declared)
+                .map(|info| info.name()),
+        );
+        Self {
+            last: class_registry::classes().next(),
+            names,
+        }
+    }
+
+    /// Check and register the classes declared by code that was loaded
+    /// since the snapshot was taken.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PluginError::Conflict`] if any of the new classes have the
+    /// same name as a class that already existed when the snapshot was
+    /// taken, or as another of the new classes. In that case, none of the new
+    /// classes are registered.
+    ///
+    /// Returns [`PluginError::Registration`] if registering one of the
+    /// classes fails. The classes before it remain registered.
+    pub fn register_new_classes(self) -> Result<Plugin, PluginError> {
+        let classes: Vec<&'static ClassInfo> = class_registry::classes()
+            .take_while(|info| !self.last.map_or(false, |last| ptr::eq(*info, last)))
+            .filter(|info| info.kind() == ClassKind::Declared)
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut conflicts: Vec<&'static str> = Vec::new();
+        for info in &classes {
+            let name = info.name();
+            let conflicting = self.names.contains(name) || !seen.insert(name);
+            if conflicting && !conflicts.contains(&name) {
+                conflicts.push(name);
+            }
+        }
+        if !conflicts.is_empty() {
+            return Err(PluginError::Conflict(conflicts));
+        }
+
+        for info in &classes {
+            info.try_register().map_err(PluginError::Registration)?;
+        }
+
+        Ok(Plugin { classes })
+    }
+}
+
+/// A loaded plugin.
+///
+/// Dropping this does not unload the library.
+#[derive(Debug)]
+pub struct Plugin {
+    classes: Vec<&'static ClassInfo>,
+}
+
+impl Plugin {
+    /// The classes declared by the plugin, which have been registered with
+    /// the runtime.
+    pub fn classes(&self) -> &[&'static ClassInfo] {
+        &self.classes
+    }
+}
+
+/// An error that occurred while loading a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PluginError {
+    /// The library could not be loaded, with the message from the dynamic
+    /// loader.
+    Load(String),
+    /// The plugin declares classes with the same names as existing classes,
+    /// or multiple classes with the same name.
+    Conflict(Vec<&'static str>),
+    /// One of the classes in the plugin could not be registered.
+    Registration(ClassRegistrationError),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load(msg) => write!(f, "failed loading plugin: {msg}"),
+            Self::Conflict(names) => {
+                write!(f, "plugin declares conflicting classes: ")?;
+                for (i, name) in names.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{name}")?;
+                }
+                Ok(())
+            }
+            Self::Registration(err) => write!(f, "failed registering plugin class: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+#[cfg(unix)]
+mod dl {
+    use std::os::raw::{c_char, c_int, c_void};
+
+    pub(super) const RTLD_NOW: c_int = 2;
+
+    #[cfg_attr(target_os = "linux", link(name = "dl"))]
+    extern "C" {
+        pub(super) fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        pub(super) fn dlerror() -> *mut c_char;
+    }
+}
+
+/// Load the dynamic library at the given path with `dlopen`, and register
+/// the classes that it declares.
+///
+/// See the [module-level documentation](self) for details.
+///
+///
+/// # Errors
+///
+/// Returns [`PluginError::Load`] if the library could not be loaded,
+/// [`PluginError::Conflict`] if it declares classes that already exist or
+/// that have the same name as each other, and
+/// [`PluginError::Registration`] if one of its classes could not be
+/// registered.
+///
+///
+/// # Safety
+///
+/// Loading a library runs its static initializers, so the library must be
+/// trusted to be sound. Loading the same library multiple times is fine.
+#[cfg(unix)]
+#[doc(alias = "dlopen")]
+pub unsafe fn load(path: impl AsRef<std::path::Path>) -> Result<Plugin, PluginError> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_ref().as_os_str().as_bytes())
+        .map_err(|_| PluginError::Load("path contains a NUL byte".into()))?;
+
+    let snapshot = Snapshot::take();
+    // SAFETY: The path is a valid C string, and the caller upholds that
+    // running the library's initializers is sound.
+    let handle = unsafe { dl::dlopen(path.as_ptr(), dl::RTLD_NOW) };
+    if handle.is_null() {
+        // SAFETY: `dlerror` returns NULL or a valid C string.
+        let msg = unsafe { dl::dlerror() };
+        let msg = if msg.is_null() {
+            String::from("unknown error")
+        } else {
+            unsafe { CStr::from_ptr(msg) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        return Err(PluginError::Load(msg));
+    }
+    snapshot.register_new_classes()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use super::*;
+    use crate::runtime::NSObject;
+    use crate::{declare_class, mutability, ClassType, DeclaredClass};
+
+    declare_class!(
+        struct PluginTestObject;
+
+        unsafe impl ClassType for PluginTestObject {
+            type Super = NSObject;
+            type Mutability = mutability::InteriorMutable;
+            const NAME: &'static str = "__objc2PluginTestObject";
+        }
+
+        impl DeclaredClass for PluginTestObject {}
+    );
+
+    // The registration functions of these are never called, since they
+    // conflict.
+    static EXISTING: ClassInfo = ClassInfo::__new(
+        "__objc2PluginTestObject",
+        "plugin::PluginTestObject",
+        ClassKind::Declared,
+        <PluginTestObject as ClassType>::class,
+        None,
+    );
+
+    static DUPLICATE_A: ClassInfo = ClassInfo::__new(
+        "__objc2PluginTestDuplicate",
+        "plugin::DuplicateA",
+        ClassKind::Declared,
+        <PluginTestObject as ClassType>::class,
+        None,
+    );
+
+    static DUPLICATE_B: ClassInfo = ClassInfo::__new(
+        "__objc2PluginTestDuplicate",
+        "plugin::DuplicateB",
+        ClassKind::Declared,
+        <PluginTestObject as ClassType>::class,
+        None,
+    );
+
+    // Done in a single test, since registering the conflicting classes would
+    // otherwise interfere with the other checks.
+    #[test]
+    fn test_register_new_classes() {
+        let plugin = Snapshot::take().register_new_classes().unwrap();
+        assert!(plugin.classes().is_empty());
+
+        let snapshot = Snapshot::take();
+        class_registry::__register(&EXISTING);
+        let err = snapshot.register_new_classes().unwrap_err();
+        assert_eq!(err, PluginError::Conflict(vec!["__objc2PluginTestObject"]));
+        assert_eq!(
+            err.to_string(),
+            "plugin declares conflicting classes: __objc2PluginTestObject"
+        );
+
+        let snapshot = Snapshot::take();
+        class_registry::__register(&DUPLICATE_A);
+        class_registry::__register(&DUPLICATE_B);
+        let err = snapshot.register_new_classes().unwrap_err();
+        assert_eq!(
+            err,
+            PluginError::Conflict(vec!["__objc2PluginTestDuplicate"])
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_load_missing() {
+        let err = unsafe { load("/nonexistent/libobjc2_plugin.so") }.unwrap_err();
+        assert!(matches!(err, PluginError::Load(_)), "{err:?}");
+    }
+}
@@ -3,9 +3,11 @@ use core::mem::ManuallyDrop;
 use core::ptr::NonNull;
 use core::{fmt, ptr};
 
-use crate::__macro_helpers::declared_ivars::initialize_ivars;
+use crate::__macro_helpers::declared_ivars::{initialize_ivars, set_finalized};
+use crate::rc::Id;
+use crate::runtime::__NSProxy as NSProxy;
 use crate::runtime::{objc_release_fast, AnyObject};
-use crate::{DeclaredClass, Message};
+use crate::{ClassType, DeclaredClass, Message};
 
 /// An Objective-C object that has been allocated, but not initialized.
 ///
@@ -283,6 +285,37 @@ impl<T: ?Sized + Message> PartialInit<T> {
     }
 }
 
+impl<T: DeclaredClass + ClassType<Super = NSProxy>> PartialInit<T> {
+    /// Finish initializing a direct subclass of `NSProxy`.
+    ///
+    /// `NSProxy` does not have an `init` method, so unlike other classes,
+    /// proxies should not call `msg_send_id![super(this), init]` in their
+    /// initializers. Use this method instead once the instance variables have
+    /// been set with [`Allocated::set_ivars`].
+    ///
+    /// Returns `None` if the allocation failed.
+    ///
+    /// See the documentation for [`declare_class!`] for an example.
+    ///
+    /// [`declare_class!`]: crate::declare_class
+    //
+    // Note: Not an associated method, to match `Allocated::set_ivars`.
+    #[inline]
+    #[track_caller]
+    pub fn init_proxy(self) -> Option<Id<T>> {
+        let ptr = Self::into_ptr(self);
+        if let Some(ptr) = NonNull::new(ptr) {
+            // SAFETY: `NSProxy` has no initializer, so there are no
+            // superclass initializers left to run, and the instance variables
+            // have been initialized by `set_ivars`.
+            unsafe { set_finalized(ptr) };
+        }
+        // SAFETY: The object has +1 retain count (or is NULL), which we
+        // transfer to `Id`.
+        unsafe { Id::from_raw(ptr) }
+    }
+}
+
 impl<T: ?Sized> Drop for PartialInit<T> {
     #[inline]
     fn drop(&mut self) {
@@ -1,8 +1,13 @@
 use core::ffi::c_void;
 #[cfg(not(all(debug_assertions, not(feature = "unstable-autoreleasesafe"))))]
 use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
 #[cfg(all(debug_assertions, not(feature = "unstable-autoreleasesafe")))]
-use std::{cell::RefCell, thread_local, vec::Vec};
+use std::{
+    cell::{Cell, RefCell},
+    thread_local,
+    vec::Vec,
+};
 
 use crate::ffi;
 
@@ -34,6 +39,8 @@ impl Pool {
     /// created.
     #[inline]
     unsafe fn new() -> Self {
+        #[cfg(feature = "realtime-audit")]
+        crate::realtime::check(crate::realtime::Operation::AutoreleasePoolPush);
         let context = unsafe { ffi::objc_autoreleasePoolPush() };
         #[cfg(all(debug_assertions, not(feature = "unstable-autoreleasesafe")))]
         POOLS.with(|c| c.borrow_mut().push(context));
@@ -63,6 +70,8 @@ impl Pool {
     /// [revision `551.1`]: https://github.com/apple-oss-distributions/objc4/blob/objc4-551.1/runtime/objc-exception.mm#L516
     #[inline]
     unsafe fn drain(self) {
+        #[cfg(feature = "realtime-audit")]
+        crate::realtime::check(crate::realtime::Operation::AutoreleasePoolPop);
         unsafe { ffi::objc_autoreleasePoolPop(self.context) }
     }
 }
@@ -516,6 +525,131 @@ where
     f(AutoreleasePool::new(None))
 }
 
+/// A top-level autorelease pool, created with [`ensure_pool`].
+///
+/// The pool is drained when this is dropped.
+///
+/// This is not [`Send`] nor [`Sync`], since the pool must be drained on the
+/// thread it was created on.
+#[derive(Debug)]
+#[must_use = "the pool is drained immediately if the guard is not kept alive"]
+pub struct TopLevelPool {
+    pool: ManuallyDrop<Pool>,
+}
+
+impl Drop for TopLevelPool {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: The pool is not used again after this.
+        let pool = unsafe { ManuallyDrop::take(&mut self.pool) };
+        // SAFETY: No references with a lifetime bound to this pool are handed
+        // out, and the pools are dropped in the reverse order they were
+        // created (checked with debug assertions, see `ensure_pool`).
+        unsafe { pool.drain() };
+    }
+}
+
+#[cfg(all(debug_assertions, not(feature = "unstable-autoreleasesafe")))]
+thread_local! {
+    /// Whether we have warned about a missing pool on this thread, so that
+    /// the warning is only printed once.
+    static WARNED_MISSING_POOL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Warn if an object is autoreleased on a thread where no pool created from
+/// Rust is active, since the object may then be leaked.
+#[inline]
+pub(crate) fn warn_if_missing_pool() {
+    #[cfg(all(debug_assertions, not(feature = "unstable-autoreleasesafe")))]
+    let _ = WARNED_MISSING_POOL.try_with(|warned| {
+        if !warned.get() && POOLS.with(|c| c.borrow().is_empty()) {
+            warned.set(true);
+            std::eprintln!(
+                "objc2: autoreleased an object on a thread without an active autorelease pool, \
+                 so it may be leaked; wrap the work in `autoreleasepool`, or keep the guard \
+                 returned by `ensure_pool` alive until the thread is done"
+            );
+        }
+    });
+}
+
+/// Install an autorelease pool at the top level of the current thread,
+/// which is drained when the returned guard is dropped.
+///
+/// Threads created by `std::thread`, by libdispatch's worker pools, or by
+/// Swift concurrency's cooperative executor do not necessarily have an
+/// autorelease pool installed. Objects that are autoreleased on such threads
+/// without an enclosing [`autoreleasepool`] are (depending on the runtime)
+/// either leaked outright, or only released once the thread exits.
+///
+/// Calling this function at the start of such a thread, and keeping the
+/// guard alive until the thread is done, ensures that such objects are
+/// released. Unlike an outer pool that the runtime may push, the pool is
+/// owned by the guard, so it is never drained out from under it.
+///
+/// This is a fallback, and does not replace [`autoreleasepool`]; objects in
+/// the top-level pool are only released when the guard is dropped, which for
+/// long-lived worker threads may be never. You should still wrap units of
+/// work in an [`autoreleasepool`].
+///
+/// If debug assertions are enabled, objects that are autoreleased with
+/// [`Id::autorelease_return`] on a thread where no pool created from Rust
+/// (with this or [`autoreleasepool`]) is active print a warning, once per
+/// thread, since they may be leaked. Pools pushed by Objective-C code are
+/// not detected, so the warning is not printed for objects returned from
+/// [`declare_class!`] methods, which are usually called from inside such a
+/// pool.
+///
+/// To find all places where objects are autoreleased without any pool,
+/// including from Objective-C, run your program with the environment
+/// variable `OBJC_DEBUG_MISSING_POOLS=YES`; Apple's runtime will then log a
+/// warning for each such object (GNUStep logs these warnings by default).
+///
+/// [`Id::autorelease_return`]: crate::rc::Id::autorelease_return
+/// [`declare_class!`]: crate::declare_class
+///
+///
+/// # Restrictions
+///
+/// The guard must be dropped in the reverse order of the pools that were
+/// created after it, so it must not be dropped inside an [`autoreleasepool`]
+/// that was entered after it was created, nor be returned out of the
+/// [`autoreleasepool`] it was created in.
+///
+/// Like with [`autoreleasepool`], this will panic with debug assertions
+/// enabled, and is prevented at compile time when the
+/// `"unstable-autoreleasesafe"` feature is enabled.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::rc::{ensure_pool, Id};
+/// use objc2::runtime::NSObject;
+///
+/// std::thread::spawn(|| {
+///     let _pool = ensure_pool();
+///
+///     // Objects autoreleased on this thread are now released when `_pool`
+///     // is dropped, instead of being leaked.
+///     let obj = NSObject::new();
+///     let _ = Id::autorelease_return(obj);
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[doc(alias = "objc_autoreleasePoolPush")]
+#[inline]
+pub fn ensure_pool() -> TopLevelPool {
+    // SAFETY: No `AutoreleasePool` is handed out, and the drop order is
+    // checked with debug assertions (and the `AutoreleaseSafe` bound).
+    let pool = unsafe { Pool::new() };
+
+    TopLevelPool {
+        pool: ManuallyDrop::new(pool),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::mem;
@@ -523,19 +657,23 @@ mod tests {
 
     use static_assertions::{assert_impl_all, assert_not_impl_any};
 
-    use super::{AutoreleasePool, AutoreleaseSafe};
-    use crate::runtime::AnyObject;
+    use super::{autoreleasepool, ensure_pool, AutoreleasePool, AutoreleaseSafe, TopLevelPool};
+    use crate::rc::{__RcTestObject, __ThreadTestData, Id};
+    use crate::runtime::{AnyObject, NSObject};
 
     #[test]
     fn auto_traits() {
         assert_impl_all!(AutoreleasePool<'static>: Unpin, UnwindSafe, RefUnwindSafe);
         assert_not_impl_any!(AutoreleasePool<'static>: Send, Sync);
+        assert_not_impl_any!(TopLevelPool: Send, Sync);
 
         assert_impl_all!(usize: AutoreleaseSafe);
         assert_impl_all!(*mut AnyObject: AutoreleaseSafe);
         assert_impl_all!(&mut AnyObject: AutoreleaseSafe);
         #[cfg(feature = "unstable-autoreleasesafe")]
         assert_not_impl_any!(AutoreleasePool<'static>: AutoreleaseSafe);
+        #[cfg(feature = "unstable-autoreleasesafe")]
+        assert_not_impl_any!(TopLevelPool: AutoreleaseSafe);
     }
 
     #[allow(unused)]
@@ -553,6 +691,39 @@ mod tests {
     #[allow(unused)]
     fn assert_object_safe(_: &dyn AutoreleaseSafe) {}
 
+    #[test]
+    fn ensure_pool_on_new_thread() {
+        std::thread::spawn(|| {
+            let _outer = ensure_pool();
+            let _inner = ensure_pool();
+            let obj = Id::autorelease_return(NSObject::new());
+            assert!(!obj.is_null());
+            autoreleasepool(|_| {});
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn ensure_pool_drained() {
+        std::thread::spawn(|| {
+            let obj = __RcTestObject::new();
+            let mut expected = __ThreadTestData::current();
+
+            let pool = ensure_pool();
+            let _ = Id::autorelease_inner(obj);
+            expected.autorelease += 1;
+            expected.assert_current();
+
+            drop(pool);
+            expected.release += 1;
+            expected.drop += 1;
+            expected.assert_current();
+        })
+        .join()
+        .unwrap();
+    }
+
     #[cfg_attr(
         not(feature = "unstable-autoreleasesafe"),
         ignore = "only stably ZST when `unstable-autoreleasesafe` is enabled"
@@ -0,0 +1,274 @@
+use core::fmt;
+use core::hash;
+use core::ops::Deref;
+
+use super::Id;
+use crate::ffi::NSUInteger;
+use crate::mutability::{HasStableHash, IsIdCloneable};
+use crate::runtime::AnyObject;
+use crate::{msg_send, Message};
+
+/// An [`Id`] whose hash is computed once with `-hash`, and then cached.
+///
+/// `Id<T>` implements [`Hash`] by calling `-hash` every time it is hashed,
+/// which for some classes (e.g. long `NSString`s) means traversing the
+/// entire object. When using objects as keys in a `HashMap` or `HashSet`,
+/// where each key is hashed many times while the map grows, it can instead
+/// be beneficial to cache the hash.
+///
+/// Equality is still determined by the type's [`PartialEq`] implementation
+/// (usually `isEqual:`), though unequal hashes are used as a fast path.
+///
+/// [`Hash`]: core::hash::Hash
+///
+///
+/// # Hash stability
+///
+/// Caching the hash, like using an object as a key in general, is only
+/// correct if the hash does not change while the object is in use. This is
+/// guaranteed for types that implement [`HasStableHash`], which is required
+/// by [`HashedId::new`]; for other types, e.g. those with interior
+/// mutability, use [`HashedId::new_unchecked`].
+///
+/// When debug assertions are enabled, the hash is re-computed each time it
+/// is used, and a panic is raised if it has changed, to catch objects that
+/// were mutated while used as keys.
+///
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use objc2::rc::HashedId;
+/// use objc2::runtime::NSObject;
+///
+/// let obj = NSObject::new();
+/// // `NSObject`'s hash is based on its address, and thus stable.
+/// let key = HashedId::new_unchecked(obj.clone());
+///
+/// let mut map = HashMap::new();
+/// map.insert(key.clone(), 42);
+/// assert_eq!(map[&key], 42);
+/// ```
+pub struct HashedId<T: ?Sized> {
+    inner: Id<T>,
+    hash: NSUInteger,
+}
+
+impl<T: ?Sized + Message> HashedId<T> {
+    /// Compute the hash of the object, and store it alongside the object.
+    #[inline]
+    #[doc(alias = "hash")]
+    pub fn new(obj: Id<T>) -> Self
+    where
+        T: HasStableHash,
+    {
+        Self::new_unchecked(obj)
+    }
+
+    /// Compute the hash of the object, and store it alongside the object,
+    /// without checking that the hash is stable.
+    ///
+    /// The object's hash and equality should not change while this exists,
+    /// e.g. it should not be mutated through interior mutability. Violating
+    /// this is a logic error, as with [`HashMap`]; the behaviour is then
+    /// unspecified (e.g. the object may not be found in a map), but it is
+    /// not undefined behaviour. When debug assertions are enabled, a panic
+    /// is raised if the hash changed.
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    #[doc(alias = "hash")]
+    pub fn new_unchecked(obj: Id<T>) -> Self {
+        let hash = object_hash(&*obj);
+        Self { inner: obj, hash }
+    }
+}
+
+impl<T: ?Sized> HashedId<T> {
+    /// Unwrap the inner [`Id`].
+    #[inline]
+    pub fn into_inner(this: Self) -> Id<T> {
+        this.inner
+    }
+
+    /// Get a reference to the inner [`Id`].
+    #[inline]
+    pub fn as_id(this: &Self) -> &Id<T> {
+        &this.inner
+    }
+
+    /// The cached hash of the object.
+    #[inline]
+    pub fn cached_hash(this: &Self) -> NSUInteger {
+        this.hash
+    }
+}
+
+fn object_hash<T: ?Sized + Message>(obj: &T) -> NSUInteger {
+    let obj: &AnyObject = unsafe { &*(obj as *const T).cast() };
+    // SAFETY: All objects respond to `hash`, and it returns `NSUInteger`.
+    unsafe { msg_send![obj, hash] }
+}
+
+#[cfg(debug_assertions)]
+#[track_caller]
+fn check_hash<T: ?Sized + Message>(obj: &T, cached: NSUInteger) {
+    let hash = object_hash(obj);
+    if hash != cached {
+        let obj: &AnyObject = unsafe { &*(obj as *const T).cast() };
+        panic!(
+            "the hash of an instance of {} changed from {cached} to {hash} while it was used as a key; was it mutated?",
+            obj.class(),
+        );
+    }
+}
+
+impl<T: ?Sized> Deref for HashedId<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for HashedId<T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Message + IsIdCloneable> Clone for HashedId<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            hash: self.hash,
+        }
+    }
+}
+
+impl<T: ?Sized + Message + PartialEq> PartialEq for HashedId<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && *self.inner == *other.inner
+    }
+}
+
+impl<T: ?Sized + Message + Eq> Eq for HashedId<T> {}
+
+impl<T: ?Sized + Message> hash::Hash for HashedId<T> {
+    #[inline]
+    #[track_caller]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        #[cfg(debug_assertions)]
+        check_hash(&*self.inner, self.hash);
+        self.hash.hash(state);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for HashedId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.inner, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::mutability::{Immutable, InteriorMutable};
+    use crate::rc::Allocated;
+    use crate::runtime::NSObject;
+    use crate::{declare_class, msg_send_id, ClassType, DeclaredClass};
+
+    declare_class!(
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct StableHash;
+
+        unsafe impl ClassType for StableHash {
+            type Super = NSObject;
+            type Mutability = Immutable;
+            const NAME: &'static str = "TestStableHash";
+        }
+
+        impl DeclaredClass for StableHash {}
+    );
+
+    declare_class!(
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct MutableHash;
+
+        unsafe impl ClassType for MutableHash {
+            type Super = NSObject;
+            type Mutability = InteriorMutable;
+            const NAME: &'static str = "TestMutableHash";
+        }
+
+        impl DeclaredClass for MutableHash {
+            type Ivars = Cell<usize>;
+        }
+
+        unsafe impl MutableHash {
+            #[method(hash)]
+            fn hash(&self) -> NSUInteger {
+                self.ivars().get()
+            }
+
+            #[method(isEqual:)]
+            fn is_equal(&self, other: &AnyObject) -> bool {
+                let other: &Self = unsafe { &*(other as *const AnyObject).cast() };
+                self.ivars().get() == other.ivars().get()
+            }
+        }
+    );
+
+    impl MutableHash {
+        fn new(hash: usize) -> Id<Self> {
+            let this: Allocated<Self> = Self::alloc();
+            let this = this.set_ivars(Cell::new(hash));
+            unsafe { msg_send_id![super(this), init] }
+        }
+    }
+
+    #[test]
+    fn test_cached() {
+        let obj: Id<StableHash> = unsafe { msg_send_id![StableHash::class(), new] };
+        let hashed = HashedId::new(obj.clone());
+        assert_eq!(HashedId::cached_hash(&hashed), object_hash(&*obj));
+
+        let mut set = HashSet::new();
+        assert!(set.insert(hashed.clone()));
+        assert!(!set.insert(hashed));
+        let other: Id<StableHash> = unsafe { msg_send_id![StableHash::class(), new] };
+        assert!(set.insert(HashedId::new(other)));
+        assert!(set.contains(&HashedId::new(obj)));
+    }
+
+    #[test]
+    fn test_equal_objects() {
+        let a = HashedId::new_unchecked(MutableHash::new(3));
+        let b = HashedId::new_unchecked(MutableHash::new(3));
+        let c = HashedId::new_unchecked(MutableHash::new(4));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        should_panic = "the hash of an instance of TestMutableHash changed from 1 to 2 while it was used as a key"
+    )]
+    fn test_mutated_key() {
+        let obj = MutableHash::new(1);
+        let hashed = HashedId::new_unchecked(obj.clone());
+        obj.ivars().set(2);
+        hash::Hash::hash(&hashed, &mut DefaultHasher::new());
+    }
+}
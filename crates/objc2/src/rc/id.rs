@@ -8,7 +8,7 @@ use core::ptr::{self, NonNull};
 use super::AutoreleasePool;
 use crate::mutability::{IsIdCloneable, IsMutable};
 use crate::runtime::{objc_release_fast, objc_retain_fast};
-use crate::{ffi, ClassType, Message};
+use crate::{ffi, ClassType, Inherits, Message};
 
 /// A reference counted pointer type for Objective-C objects.
 ///
@@ -76,6 +76,22 @@ use crate::{ffi, ClassType, Message};
 /// Additionally, it participates in the null-pointer optimization, that is,
 /// `Option<Id<T>>` is guaranteed to have the same size as `Id<T>`.
 ///
+/// These guarantees extend to the ABI as well: `Id<T>` is passed and
+/// returned exactly like `NonNull<T>`, and `Option<Id<T>>` exactly like
+/// `*mut T`. This means that both can be used directly in the signature of
+/// an `extern "C" fn`, where they represent an object pointer whose +1
+/// retain count is transferred along with it (like `ns_returns_retained` for
+/// return values, and `ns_consumed` for arguments). Other crates may rely on
+/// this.
+///
+/// For the common case of a nullable pointer that is _not_ retained, such as
+/// the parameters of a delegate callback, use `Option<&T>` in the signature
+/// instead, and [`Message::retain`] it if you need to keep it around (or use
+/// [`Id::retain`] if all you have is a raw pointer). To go the other way,
+/// see [`Id::option_into_raw`] and [`Id::option_as_ptr`].
+///
+/// [`Message::retain`]: crate::Message::retain
+///
 ///
 ///
 /// # Example
@@ -284,14 +300,55 @@ impl<T: ?Sized + Message> Id<T> {
         this.ptr.as_ptr()
     }
 
+    /// Consumes an optional `Id`, returning a possibly NULL raw pointer with
+    /// +1 retain count.
+    ///
+    /// This is the nullable counterpart to [`Id::into_raw`], and the
+    /// opposite of [`Id::from_raw`].
+    ///
+    /// This is an associated method, and must be called as
+    /// `Id::option_into_raw(obj)`.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2::rc::Id;
+    /// use objc2::runtime::NSObject;
+    ///
+    /// let obj: Option<Id<NSObject>> = None;
+    /// assert!(Id::option_into_raw(obj).is_null());
+    ///
+    /// let obj = Some(NSObject::new());
+    /// let ptr = Id::option_into_raw(obj);
+    /// // SAFETY: The pointer has +1 retain count from above.
+    /// let obj = unsafe { Id::from_raw(ptr) };
+    /// assert!(obj.is_some());
+    /// ```
     #[inline]
-    pub(crate) fn consume_as_ptr_option(this: Option<Self>) -> *mut T
+    pub fn option_into_raw(this: Option<Self>) -> *mut T
     where
         T: Sized,
     {
         this.map(|this| Id::into_raw(this))
             .unwrap_or_else(ptr::null_mut)
     }
+
+    /// Returns a possibly NULL raw pointer to the object.
+    ///
+    /// This is the nullable counterpart to [`Id::as_ptr`], useful when
+    /// passing an optional object to a function that takes a nullable
+    /// pointer without transferring ownership.
+    ///
+    /// This is an associated method, and must be called as
+    /// `Id::option_as_ptr(obj)`.
+    #[inline]
+    pub fn option_as_ptr(this: Option<&Self>) -> *const T
+    where
+        T: Sized,
+    {
+        this.map(Id::as_ptr).unwrap_or_else(ptr::null)
+    }
 }
 
 // TODO: Add ?Sized bound
@@ -515,8 +572,12 @@ impl<T: Message> Id<T> {
     }
 
     #[inline]
-    pub(super) fn autorelease_inner(this: Self) -> *mut T {
+    pub(crate) fn autorelease_inner(this: Self) -> *mut T {
+        #[cfg(feature = "realtime-audit")]
+        crate::realtime::check(crate::realtime::Operation::Autorelease);
         let ptr = ManuallyDrop::new(this).ptr.as_ptr();
+        // The object is released when the pool is drained.
+        crate::sanitize::release(ptr);
         // SAFETY:
         // - The `ptr` is guaranteed to be valid and have at least one
         //   retain count.
@@ -646,6 +707,7 @@ impl<T: Message> Id<T> {
     #[must_use = "if you don't intend to use the object any more, drop it as usual"]
     #[inline]
     pub fn autorelease_return(this: Self) -> *mut T {
+        super::autorelease::warn_if_missing_pool();
         Self::autorelease_return_option(Some(this))
     }
 }
@@ -666,6 +728,42 @@ where
     }
 }
 
+impl<T: Message + 'static> Id<T> {
+    /// Convert the object into any of its superclasses.
+    ///
+    /// Unlike [`Id::into_super`], this can convert through several levels of
+    /// the inheritance chain at once, e.g. from `Id<NSMutableString>`
+    /// directly to `Id<NSObject>`. The conversion is checked at compile-time
+    /// and reuses the existing pointer, so no retain or release is performed.
+    ///
+    /// To convert into a protocol object, use [`ProtocolObject::from_id`].
+    ///
+    /// [`ProtocolObject::from_id`]: crate::runtime::ProtocolObject::from_id
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2::rc::Id;
+    /// use objc2::runtime::{AnyObject, NSObject};
+    ///
+    /// let obj = NSObject::new();
+    /// let obj: Id<AnyObject> = Id::into_ancestor(obj);
+    /// ```
+    #[inline]
+    pub fn into_ancestor<U>(this: Self) -> Id<U>
+    where
+        T: Inherits<U>,
+        U: Message + 'static,
+    {
+        // SAFETY:
+        // - The `Inherits` bound ensures that the casted-to type is a
+        //   superclass of the type (or the type itself).
+        // - Both types are `'static`, so no lifetime information is lost.
+        unsafe { Self::cast::<U>(this) }
+    }
+}
+
 // TODO: Add ?Sized bound
 impl<T: Message + IsIdCloneable> Clone for Id<T> {
     /// Makes a clone of the shared object.
@@ -712,6 +810,7 @@ impl<T: ?Sized> Drop for Id<T> {
         // but that would be confusing and inconsistent since we cannot really
         // guarantee that it is run if the `Id<T>` is passed to Objective-C.
 
+        crate::sanitize::release(self.ptr.as_ptr());
         // SAFETY: The `ptr` is guaranteed to be valid and have at least one
         // retain count.
         unsafe { objc_release_fast(self.ptr.as_ptr().cast()) };
@@ -875,7 +974,7 @@ struct TestDocIdWithNonClassType {
 
 #[cfg(test)]
 mod tests {
-    use core::mem::size_of;
+    use core::mem::{align_of, size_of};
 
     use static_assertions::{assert_impl_all, assert_not_impl_any};
 
@@ -1043,6 +1142,17 @@ mod tests {
         expected.assert_current();
     }
 
+    #[test]
+    fn test_into_ancestor() {
+        let obj: Id<__RcTestObject> = __RcTestObject::new();
+        let expected = __ThreadTestData::current();
+
+        let obj: Id<__RcTestObject> = Id::into_ancestor(obj);
+        let obj: Id<NSObject> = Id::into_ancestor(obj);
+        let _obj: Id<AnyObject> = Id::into_ancestor(obj);
+        expected.assert_current();
+    }
+
     #[repr(C)]
     struct MyObject<'a> {
         inner: NSObject,
@@ -1061,5 +1171,51 @@ mod tests {
 
         assert_eq!(size_of::<Id<NSObject>>(), ptr_size);
         assert_eq!(size_of::<Option<Id<NSObject>>>(), ptr_size);
+        assert_eq!(align_of::<Id<NSObject>>(), align_of::<*const NSObject>());
+        assert_eq!(
+            align_of::<Option<Id<NSObject>>>(),
+            align_of::<*const NSObject>()
+        );
+    }
+
+    #[test]
+    fn test_ffi_safe() {
+        // Will fail to compile with `improper_ctypes_definitions` if not.
+        #[deny(improper_ctypes_definitions)]
+        extern "C" fn passthrough(obj: Option<Id<NSObject>>) -> Option<Id<NSObject>> {
+            obj
+        }
+
+        #[deny(improper_ctypes_definitions)]
+        extern "C" fn passthrough_nonnull(obj: Id<NSObject>) -> Id<NSObject> {
+            obj
+        }
+
+        // Call through a function pointer that uses raw pointers, to verify
+        // that the ABI matches.
+        let f: extern "C" fn(*mut NSObject) -> *mut NSObject =
+            unsafe { core::mem::transmute(passthrough as extern "C" fn(_) -> _) };
+        assert!(f(ptr::null_mut()).is_null());
+
+        let obj = NSObject::new();
+        let expected = Id::as_ptr(&obj);
+        let ptr = f(Id::into_raw(obj));
+        assert_eq!(ptr as *const NSObject, expected);
+        let obj = passthrough_nonnull(unsafe { Id::from_raw(ptr) }.unwrap());
+        assert_eq!(Id::as_ptr(&obj), expected);
+    }
+
+    #[test]
+    fn test_option_ptr_conversions() {
+        let obj = NSObject::new();
+        assert_eq!(Id::option_as_ptr(Some(&obj)), Id::as_ptr(&obj));
+        assert!(Id::<NSObject>::option_as_ptr(None).is_null());
+
+        let expected = Id::as_ptr(&obj);
+        let ptr = Id::option_into_raw(Some(obj));
+        assert_eq!(ptr as *const NSObject, expected);
+        let obj = unsafe { Id::from_raw(ptr) };
+        assert!(obj.is_some());
+        assert!(Id::<NSObject>::option_into_raw(None).is_null());
     }
 }
@@ -0,0 +1,362 @@
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash;
+use core::ops::Deref;
+use core::panic::{RefUnwindSafe, UnwindSafe};
+use core::ptr::NonNull;
+
+use super::Id;
+use crate::mutability::IsIdCloneable;
+use crate::Message;
+
+/// A reference into an object, that keeps the object alive.
+///
+/// Methods on Objective-C objects often return references that borrow from
+/// the object, such as the keys of an `NSDictionary`, or the string in an
+/// instance variable of your own class. Returning such references from a
+/// function usually requires keeping the `Id` that owns the object around
+/// separately, or retaining each of the returned objects.
+///
+/// This type instead bundles the owning [`Id`] together with a reference
+/// derived from it, such that the reference can be passed around (and
+/// returned from functions) without any lifetime tied to a local variable.
+/// It is created with [`Id::map`] or [`Id::filter_map`], and dereferences to
+/// the projected value.
+///
+/// The owner is never given out mutably, so the projected reference stays
+/// valid for as long as this exists.
+///
+///
+/// # Example
+///
+/// Return a reference to an object stored in an instance variable.
+///
+/// ```
+/// use objc2::rc::{Id, MappedId};
+/// use objc2::runtime::NSObject;
+/// use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
+///
+/// declare_class!(
+///     struct Container;
+///
+///     unsafe impl ClassType for Container {
+///         type Super = NSObject;
+///         type Mutability = mutability::Immutable;
+///         const NAME: &'static str = "MappedIdExampleContainer";
+///     }
+///
+///     impl DeclaredClass for Container {
+///         type Ivars = Id<NSObject>;
+///     }
+/// );
+///
+/// impl Container {
+///     fn new(item: Id<NSObject>) -> Id<Self> {
+///         let this = Self::alloc().set_ivars(item);
+///         unsafe { msg_send_id![super(this), init] }
+///     }
+///
+///     fn item(&self) -> &NSObject {
+///         self.ivars()
+///     }
+/// }
+///
+/// fn create_item() -> MappedId<Container, NSObject> {
+///     let container = Container::new(NSObject::new());
+///     // `container.item()` would borrow from the local variable, so that
+///     // couldn't be returned; project the `Id` instead.
+///     Id::map(container, |container| container.item())
+/// }
+///
+/// let item = create_item();
+/// let _: &NSObject = &item;
+/// # assert!(core::ptr::eq(&*item, MappedId::owner(&item).item()));
+/// ```
+///
+/// Similarly, the keys of a dictionary can be borrowed without retaining each
+/// of them.
+///
+/// ```ignore
+/// use objc2_foundation::{NSDictionary, NSString};
+///
+/// fn first_key(dict: Id<NSDictionary<NSString, NSObject>>) -> Option<MappedId<_, NSString>> {
+///     Id::filter_map(dict, |dict| dict.keys().next()).ok()
+/// }
+/// ```
+pub struct MappedId<T: ?Sized, U: ?Sized> {
+    owner: Id<T>,
+    /// A pointer to the projected value, which borrows from `owner`.
+    ptr: NonNull<U>,
+}
+
+impl<T: ?Sized> Id<T> {
+    /// Project the object to a value borrowed from it, while keeping the
+    /// object alive.
+    ///
+    /// This is an associated function, and must be called as
+    /// `Id::map(this, f)`, to avoid conflicting with methods on `T`.
+    ///
+    /// See [`MappedId`] for details.
+    #[inline]
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> MappedId<T, U>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        let ptr = NonNull::from(f(&this));
+        MappedId { owner: this, ptr }
+    }
+
+    /// Try to project the object to a value borrowed from it, while keeping
+    /// the object alive.
+    ///
+    /// This is an associated function, and must be called as
+    /// `Id::filter_map(this, f)`, to avoid conflicting with methods on `T`.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// If the closure returns [`None`], the original `Id` is returned in the
+    /// `Err` variant.
+    #[inline]
+    pub fn filter_map<U: ?Sized, F>(this: Self, f: F) -> Result<MappedId<T, U>, Self>
+    where
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        match f(&this).map(NonNull::from) {
+            Some(ptr) => Ok(MappedId { owner: this, ptr }),
+            None => Err(this),
+        }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> MappedId<T, U> {
+    /// Project the value further.
+    ///
+    /// This is an associated function, and must be called as
+    /// `MappedId::map(this, f)`, to avoid conflicting with methods on `U`.
+    #[inline]
+    pub fn map<V: ?Sized, F>(this: Self, f: F) -> MappedId<T, V>
+    where
+        F: FnOnce(&U) -> &V,
+    {
+        let ptr = NonNull::from(f(&this));
+        MappedId {
+            owner: this.owner,
+            ptr,
+        }
+    }
+
+    /// Try to project the value further.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// If the closure returns [`None`], `this` is returned unchanged in the
+    /// `Err` variant.
+    #[inline]
+    pub fn filter_map<V: ?Sized, F>(this: Self, f: F) -> Result<MappedId<T, V>, Self>
+    where
+        F: FnOnce(&U) -> Option<&V>,
+    {
+        match f(&this).map(NonNull::from) {
+            Some(ptr) => Ok(MappedId {
+                owner: this.owner,
+                ptr,
+            }),
+            None => Err(this),
+        }
+    }
+
+    /// Get a reference to the object that owns the projected value.
+    #[inline]
+    pub fn owner(this: &Self) -> &Id<T> {
+        &this.owner
+    }
+
+    /// Discard the projection, and return the object that owned it.
+    #[inline]
+    pub fn into_owner(this: Self) -> Id<T> {
+        this.owner
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Deref for MappedId<T, U> {
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &U {
+        // SAFETY: The pointer was created from a reference that borrowed from
+        // `owner` (or was `'static`). The object is kept alive by `owner`,
+        // which is never mutably dereferenced, so the reference is still
+        // valid.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> AsRef<U> for MappedId<T, U> {
+    #[inline]
+    fn as_ref(&self) -> &U {
+        self
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Borrow<U> for MappedId<T, U> {
+    #[inline]
+    fn borrow(&self) -> &U {
+        self
+    }
+}
+
+impl<T: Message + IsIdCloneable, U: ?Sized> Clone for MappedId<T, U> {
+    /// Retain the owner, and make a new reference to the same value.
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            // The clone points to the same object, so the projected pointer
+            // is still valid.
+            owner: self.owner.clone(),
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + PartialEq> PartialEq for MappedId<T, U> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + Eq> Eq for MappedId<T, U> {}
+
+impl<T: ?Sized, U: ?Sized + hash::Hash> hash::Hash for MappedId<T, U> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + fmt::Debug> fmt::Debug for MappedId<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + fmt::Display> fmt::Display for MappedId<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+// SAFETY: Sending the `MappedId` sends the owner, and gives the other thread
+// access to `&U`.
+unsafe impl<T: ?Sized, U: ?Sized + Sync> Send for MappedId<T, U> where Id<T>: Send {}
+
+// SAFETY: Sharing the `MappedId` shares the owner, and gives the other
+// thread access to `&U`.
+unsafe impl<T: ?Sized, U: ?Sized + Sync> Sync for MappedId<T, U> where Id<T>: Sync {}
+
+impl<T: ?Sized, U: ?Sized> Unpin for MappedId<T, U> {}
+
+impl<T: ?Sized, U: ?Sized + RefUnwindSafe> RefUnwindSafe for MappedId<T, U> where
+    Id<T>: RefUnwindSafe
+{
+}
+
+impl<T: ?Sized, U: ?Sized + RefUnwindSafe> UnwindSafe for MappedId<T, U> where Id<T>: UnwindSafe {}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+    use crate::mutability::Immutable;
+    use crate::rc::{__RcTestObject, __ThreadTestData};
+    use crate::runtime::NSObject;
+    use crate::{declare_class, msg_send_id, ClassType, DeclaredClass};
+
+    declare_class!(
+        struct Container;
+
+        unsafe impl ClassType for Container {
+            type Super = NSObject;
+            type Mutability = Immutable;
+            const NAME: &'static str = "TestMappedIdContainer";
+        }
+
+        impl DeclaredClass for Container {
+            type Ivars = Option<Id<__RcTestObject>>;
+        }
+    );
+
+    declare_class!(
+        struct SendSync;
+
+        unsafe impl ClassType for SendSync {
+            type Super = NSObject;
+            type Mutability = Immutable;
+            const NAME: &'static str = "TestMappedIdSendSync";
+        }
+
+        impl DeclaredClass for SendSync {}
+    );
+
+    unsafe impl Send for SendSync {}
+    unsafe impl Sync for SendSync {}
+
+    impl Container {
+        fn new(item: Option<Id<__RcTestObject>>) -> Id<Self> {
+            let this = Self::alloc().set_ivars(item);
+            unsafe { msg_send_id![super(this), init] }
+        }
+    }
+
+    #[test]
+    fn test_map_does_not_retain() {
+        let mut expected = __ThreadTestData::current();
+
+        let container = Container::new(Some(__RcTestObject::new()));
+        expected.alloc += 1;
+        expected.init += 1;
+        expected.assert_current();
+
+        let item = Id::map(container, |container| container.ivars().as_deref().unwrap());
+        expected.assert_current();
+
+        let cls = MappedId::map(item.clone(), |item| item.class());
+        assert_eq!(&*cls, __RcTestObject::class());
+        drop(cls);
+        expected.assert_current();
+
+        drop(item);
+        expected.release += 1;
+        expected.drop += 1;
+        expected.assert_current();
+    }
+
+    #[test]
+    fn test_filter_map() {
+        let container = Container::new(None);
+        let container = match Id::filter_map(container, |container| container.ivars().as_deref()) {
+            Ok(_) => panic!("should not have an item"),
+            Err(container) => container,
+        };
+        assert!(container.ivars().is_none());
+
+        let container = Container::new(Some(__RcTestObject::new()));
+        let item = Id::filter_map(container, |container| container.ivars().as_deref())
+            .ok()
+            .expect("should have an item");
+        let item = MappedId::filter_map(item, |_| None::<&NSObject>).unwrap_err();
+        assert!(MappedId::into_owner(item).ivars().is_some());
+    }
+
+    #[test]
+    fn auto_traits() {
+        assert_impl_all!(MappedId<SendSync, SendSync>: Send, Sync, Unpin);
+        assert_not_impl_any!(MappedId<SendSync, Cell<u8>>: Send, Sync);
+        assert_not_impl_any!(MappedId<NSObject, SendSync>: Send, Sync);
+    }
+}
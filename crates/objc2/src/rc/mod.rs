@@ -50,18 +50,29 @@
 
 mod allocated_partial_init;
 mod autorelease;
+mod hashed_id;
 mod id;
 mod id_forwarding_impls;
 mod id_traits;
+mod mapped_id;
+mod object_id;
+mod retain_release_hooks;
 mod test_object;
+mod weak_callback;
 mod weak_id;
 
 pub use self::allocated_partial_init::{Allocated, PartialInit};
 pub use self::autorelease::{
-    autoreleasepool, autoreleasepool_leaking, AutoreleasePool, AutoreleaseSafe,
+    autoreleasepool, autoreleasepool_leaking, ensure_pool, AutoreleasePool, AutoreleaseSafe,
+    TopLevelPool,
 };
+pub use self::hashed_id::HashedId;
 pub use self::id::Id;
 pub use self::id_traits::{DefaultId, IdFromIterator, IdIntoIterator};
+pub use self::mapped_id::MappedId;
+pub use self::object_id::ObjectId;
+pub use self::retain_release_hooks::RetainReleaseHooks;
 #[doc(hidden)]
 pub use self::test_object::{__RcTestObject, __ThreadTestData};
+pub use self::weak_callback::WeakCallback;
 pub use self::weak_id::WeakId;
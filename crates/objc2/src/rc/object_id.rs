@@ -0,0 +1,195 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash;
+use core::ops::Deref;
+
+use super::Id;
+use crate::mutability::IsIdCloneable;
+use crate::Message;
+
+/// An [`Id`] that compares and hashes by the identity of the object, instead
+/// of by its contents.
+///
+/// The `PartialEq` and `Hash` implementations on `Id<T>` forward to those of
+/// `T`, which for most Objective-C objects are implemented with `isEqual:`
+/// and `hash`. That is usually what you want, but sometimes you want to know
+/// whether two references point to the _same_ object, for example when
+/// keeping a registry of observers or delegates in a `HashMap`, where two
+/// distinct but equal objects must be treated as different keys.
+///
+/// This wrapper implements [`PartialEq`], [`Eq`], [`Hash`], [`PartialOrd`]
+/// and [`Ord`] using only the address of the object, and dereferences to
+/// `T` like `Id<T>` does.
+///
+/// [`Hash`]: core::hash::Hash
+///
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use objc2::rc::ObjectId;
+/// use objc2::runtime::NSObject;
+///
+/// let obj = NSObject::new();
+/// let other = NSObject::new();
+///
+/// let mut set = HashSet::new();
+/// assert!(set.insert(ObjectId::new(obj.clone())));
+/// assert!(!set.insert(ObjectId::new(obj.clone())));
+/// assert!(set.insert(ObjectId::new(other)));
+/// assert_eq!(set.len(), 2);
+/// ```
+#[repr(transparent)]
+pub struct ObjectId<T: ?Sized> {
+    inner: Id<T>,
+}
+
+impl<T: ?Sized> ObjectId<T> {
+    /// Wrap the object, such that it is compared by identity.
+    #[inline]
+    pub fn new(obj: Id<T>) -> Self {
+        Self { inner: obj }
+    }
+
+    /// Unwrap the inner [`Id`].
+    #[inline]
+    pub fn into_inner(this: Self) -> Id<T> {
+        this.inner
+    }
+
+    /// Get a reference to the inner [`Id`].
+    #[inline]
+    pub fn as_id(this: &Self) -> &Id<T> {
+        &this.inner
+    }
+
+    /// The address of the object, which is what is used for comparisons.
+    #[inline]
+    pub fn as_ptr(this: &Self) -> *const T {
+        let obj: &T = &this.inner;
+        obj
+    }
+
+    #[inline]
+    fn addr(&self) -> *const () {
+        Self::as_ptr(self).cast()
+    }
+}
+
+impl<T: ?Sized> From<Id<T>> for ObjectId<T> {
+    #[inline]
+    fn from(obj: Id<T>) -> Self {
+        Self::new(obj)
+    }
+}
+
+impl<T: ?Sized> Deref for ObjectId<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for ObjectId<T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Message + IsIdCloneable> Clone for ObjectId<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<T: ?Sized> PartialEq for ObjectId<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl<T: ?Sized> Eq for ObjectId<T> {}
+
+impl<T: ?Sized> PartialOrd for ObjectId<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: ?Sized> Ord for ObjectId<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl<T: ?Sized> hash::Hash for ObjectId<T> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.addr().hash(state);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for ObjectId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ObjectId").field(&&*self.inner).finish()
+    }
+}
+
+impl<T: ?Sized> fmt::Pointer for ObjectId<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.inner, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::rc::{__RcTestObject, __ThreadTestData};
+    use crate::runtime::NSObject;
+
+    #[test]
+    fn test_identity() {
+        let obj = NSObject::new();
+        let other = NSObject::new();
+
+        assert_eq!(ObjectId::new(obj.clone()), ObjectId::new(obj.clone()));
+        assert_ne!(ObjectId::new(obj.clone()), ObjectId::new(other.clone()));
+        assert_eq!(
+            ObjectId::new(obj.clone()).cmp(&ObjectId::new(other.clone())),
+            Id::as_ptr(&obj).cmp(&Id::as_ptr(&other)),
+        );
+
+        let mut map = HashMap::new();
+        map.insert(ObjectId::new(obj.clone()), 1);
+        map.insert(ObjectId::new(other.clone()), 2);
+        map.insert(ObjectId::new(obj.clone()), 3);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&ObjectId::new(obj)], 3);
+        assert_eq!(map[&ObjectId::new(other)], 2);
+    }
+
+    #[test]
+    fn test_no_extra_retains() {
+        let obj = __RcTestObject::new();
+        let expected = __ThreadTestData::current();
+
+        let id = ObjectId::from(obj);
+        assert!(core::ptr::eq(ObjectId::as_ptr(&id), &*id));
+        let _ = std::format!("{id:?} {id:p}");
+        let obj = ObjectId::into_inner(id);
+        expected.assert_current();
+        drop(obj);
+    }
+}
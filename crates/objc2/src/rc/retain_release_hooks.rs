@@ -0,0 +1,122 @@
+use core::ptr::NonNull;
+
+use crate::runtime::AnyObject;
+use crate::{class, msg_send, Message};
+
+/// Custom reference-counting entry points for an object.
+///
+/// [`Id`] manages memory via. the runtime functions `objc_retain`,
+/// `objc_release` and `objc_autorelease`. For subclasses of `NSObject`, these
+/// operate on the runtime's built-in reference count, but for classes that
+/// override `-retain`, `-release` and `-autorelease` (notably root classes
+/// that do not inherit from `NSObject`, or objects whose lifetime is managed
+/// by some other system like C++), the runtime instead forwards to those
+/// methods.
+///
+/// This trait describes such custom entry points in Rust. Use
+/// [`ClassBuilder::add_retain_release_hooks`] to install them as methods on
+/// a class, after which [`Id`] (and any Objective-C code that retains or
+/// releases the object) will go through them.
+///
+/// [`Id`]: crate::rc::Id
+/// [`ClassBuilder::add_retain_release_hooks`]: crate::runtime::ClassBuilder::add_retain_release_hooks
+///
+///
+/// # Safety
+///
+/// The implementation must behave like a reference count:
+/// - [`retain`] must keep the object alive until a matching call to
+///   [`release`] has been made.
+/// - [`release`] must not deallocate the object while there are outstanding
+///   retains.
+/// - [`autorelease`] must arrange for [`release`] to be called at some later
+///   point, and must keep the object alive until then.
+///
+/// The methods may be called from any thread that the object is allowed to
+/// be used on, and must not unwind.
+///
+/// [`retain`]: Self::retain
+/// [`release`]: Self::release
+/// [`autorelease`]: Self::autorelease
+///
+///
+/// # Example
+///
+/// Create a root class whose objects are kept alive forever.
+///
+/// ```
+/// use core::ptr::NonNull;
+///
+/// use objc2::encode::{Encoding, RefEncode};
+/// use objc2::rc::RetainReleaseHooks;
+/// use objc2::runtime::{AnyClass, AnyObject, ClassBuilder, Sel};
+/// use objc2::Message;
+///
+/// #[repr(C)]
+/// struct Immortal(AnyObject);
+///
+/// unsafe impl RefEncode for Immortal {
+///     const ENCODING_REF: Encoding = Encoding::Object;
+/// }
+///
+/// unsafe impl Message for Immortal {}
+///
+/// unsafe impl RetainReleaseHooks for Immortal {
+///     unsafe fn retain(this: NonNull<Self>) -> NonNull<Self> {
+///         this
+///     }
+///
+///     unsafe fn release(_this: NonNull<Self>) {}
+/// }
+///
+/// extern "C" fn initialize(_cls: &AnyClass, _cmd: Sel) {}
+///
+/// let mut builder = ClassBuilder::root("Immortal", initialize as extern "C" fn(_, _)).unwrap();
+/// // SAFETY: The class represents `Immortal`.
+/// unsafe { builder.add_retain_release_hooks::<Immortal>() };
+/// let _cls = builder.register();
+/// ```
+pub unsafe trait RetainReleaseHooks: Message {
+    /// Increment the reference count of the object.
+    ///
+    /// Called instead of `-retain`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be a valid pointer to an object of this type.
+    unsafe fn retain(this: NonNull<Self>) -> NonNull<Self>;
+
+    /// Decrement the reference count of the object, and deallocate it if it
+    /// reaches zero.
+    ///
+    /// Called instead of `-release`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be a valid pointer to an object of this type, whose
+    /// reference count is owned by the caller.
+    unsafe fn release(this: NonNull<Self>);
+
+    /// Put the object in the innermost autorelease pool, to be released when
+    /// the pool is drained.
+    ///
+    /// Called instead of `-autorelease`.
+    ///
+    /// The default implementation adds the object to the current pool with
+    /// `+[NSAutoreleasePool addObject:]`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// Same as [`release`][Self::release].
+    unsafe fn autorelease(this: NonNull<Self>) -> NonNull<Self> {
+        let obj: *mut AnyObject = this.as_ptr().cast();
+        // SAFETY: The object is valid, and the pool takes over the reference
+        // count from the caller. Note that we can't use `objc_autorelease`
+        // here, since that would call back into `-autorelease`.
+        let _: () = unsafe { msg_send![class!(NSAutoreleasePool), addObject: obj] };
+        this
+    }
+}
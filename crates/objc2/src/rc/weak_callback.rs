@@ -0,0 +1,203 @@
+use core::fmt;
+
+use super::{Id, WeakId};
+use crate::mutability::{IsIdCloneable, IsRetainable};
+use crate::Message;
+
+/// A closure that is only called while a weakly referenced object is alive.
+///
+/// Callbacks registered with Objective-C APIs (such as notification
+/// observers, timers or delegates) often need to access some object, but
+/// must not keep it alive, since that would easily create a reference cycle.
+/// Capturing a [`WeakId`] in the closure and loading it manually every time
+/// works, but is easy to get wrong; this type instead ensures that the
+/// closure is only ever invoked with a strong reference to the object, and
+/// that the object is kept alive for the duration of the call.
+///
+/// When the object has been deallocated, calling the callback does nothing,
+/// and returns `None`.
+///
+///
+/// # Example
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use objc2::rc::WeakCallback;
+/// use objc2::runtime::NSObject;
+///
+/// let calls = Cell::new(0);
+/// let obj = NSObject::new();
+/// let callback = WeakCallback::from_id(&obj, |_obj: &NSObject, n: i32| {
+///     calls.set(calls.get() + n);
+/// });
+///
+/// assert_eq!(callback.call_with(2), Some(()));
+/// assert_eq!(calls.get(), 2);
+///
+/// drop(obj);
+/// assert_eq!(callback.call_with(3), None);
+/// assert_eq!(calls.get(), 2);
+/// ```
+pub struct WeakCallback<T: ?Sized, F> {
+    obj: WeakId<T>,
+    f: F,
+}
+
+impl<T: Message, F> WeakCallback<T, F> {
+    /// Create a new callback that is invoked with the given object.
+    #[inline]
+    pub fn new(obj: &T, f: F) -> Self
+    where
+        T: IsRetainable,
+    {
+        Self {
+            obj: WeakId::new(obj),
+            f,
+        }
+    }
+
+    /// Create a new callback that is invoked with the given [`Id`].
+    ///
+    /// You should prefer [`WeakCallback::new`] whenever the object is
+    /// retainable.
+    #[inline]
+    pub fn from_id(obj: &Id<T>, f: F) -> Self
+    where
+        T: IsIdCloneable,
+    {
+        Self {
+            obj: WeakId::from_id(obj),
+            f,
+        }
+    }
+
+    /// Create a new callback from an existing weak reference.
+    #[inline]
+    pub fn from_weak(obj: WeakId<T>, f: F) -> Self {
+        Self { obj, f }
+    }
+
+    /// Load the object, if it is still alive.
+    #[inline]
+    pub fn load(&self) -> Option<Id<T>> {
+        self.obj.load()
+    }
+
+    /// Whether the object is still alive.
+    ///
+    /// Note that the object may be deallocated right after this returns, so
+    /// prefer [`call`](Self::call) or [`load`](Self::load) over checking
+    /// this first.
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.load().is_some()
+    }
+
+    /// Invoke the closure with the object, if it is still alive.
+    #[inline]
+    pub fn call<R>(&self) -> Option<R>
+    where
+        F: Fn(&T) -> R,
+    {
+        let obj = self.load()?;
+        Some((self.f)(&obj))
+    }
+
+    /// Invoke the closure with the object and an extra argument, if the
+    /// object is still alive.
+    ///
+    /// This is useful for callbacks that receive a value, such as an
+    /// `NSNotification` or an `NSTimer`.
+    #[inline]
+    pub fn call_with<A, R>(&self, arg: A) -> Option<R>
+    where
+        F: Fn(&T, A) -> R,
+    {
+        let obj = self.load()?;
+        Some((self.f)(&obj, arg))
+    }
+
+    /// Invoke the mutable closure with the object, if it is still alive.
+    #[inline]
+    pub fn call_mut<R>(&mut self) -> Option<R>
+    where
+        F: FnMut(&T) -> R,
+    {
+        let obj = self.load()?;
+        Some((self.f)(&obj))
+    }
+
+    /// Get a reference to the closure.
+    #[inline]
+    pub fn get_fn(&self) -> &F {
+        &self.f
+    }
+
+    /// Unwrap the weak reference and the closure.
+    #[inline]
+    pub fn into_parts(self) -> (WeakId<T>, F) {
+        (self.obj, self.f)
+    }
+}
+
+impl<T: ?Sized, F> fmt::Debug for WeakCallback<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakCallback")
+            .field("obj", &self.obj)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::rc::{__RcTestObject, __ThreadTestData};
+
+    #[test]
+    fn test_no_retain_cycle() {
+        let obj = __RcTestObject::new();
+        let mut expected = __ThreadTestData::current();
+
+        let calls = Cell::new(0);
+        let callback = WeakCallback::new(&*obj, |_: &__RcTestObject| {
+            calls.set(calls.get() + 1);
+        });
+        expected.assert_current();
+
+        assert_eq!(callback.call(), Some(()));
+        assert_eq!(calls.get(), 1);
+        expected.try_retain += 1;
+        expected.release += 1;
+        expected.assert_current();
+
+        drop(obj);
+        expected.release += 1;
+        expected.drop += 1;
+        expected.assert_current();
+
+        if cfg!(not(feature = "gnustep-1-7")) {
+            // See `WeakId`'s `test_weak`.
+            assert!(!callback.is_alive());
+            assert_eq!(callback.call(), None);
+            assert_eq!(calls.get(), 1);
+        }
+    }
+
+    #[test]
+    fn test_call_mut() {
+        let obj = __RcTestObject::new();
+        let mut count = 0;
+        let mut callback = WeakCallback::new(&*obj, |_: &__RcTestObject| {
+            count += 1;
+            count
+        });
+        assert_eq!(callback.call_mut(), Some(1));
+        assert_eq!(callback.call_mut(), Some(2));
+        let _ = std::format!("{callback:?}");
+        drop(callback);
+        assert_eq!(count, 2);
+    }
+}
@@ -7,7 +7,7 @@ use std::panic::{RefUnwindSafe, UnwindSafe};
 
 use super::Id;
 use crate::mutability::{IsIdCloneable, IsRetainable};
-use crate::{ffi, Message};
+use crate::{ffi, runtime, Message};
 
 /// A weak pointer to an Objective-C reference counted object.
 ///
@@ -98,7 +98,12 @@ impl<T: Message> WeakId<T> {
     #[inline]
     pub fn load(&self) -> Option<Id<T>> {
         let ptr = self.inner.get();
-        let obj = unsafe { ffi::objc_loadWeakRetained(ptr) }.cast();
+        let obj = if runtime::features().load_weak_retained() {
+            unsafe { ffi::objc_loadWeakRetained(ptr) }
+        } else {
+            unsafe { ffi::objc_retain(ffi::objc_loadWeak(ptr)) }
+        };
+        let obj = obj.cast();
         // SAFETY: The object has +1 retain count
         unsafe { Id::from_raw(obj) }
     }
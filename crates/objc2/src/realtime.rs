@@ -0,0 +1,280 @@
+//! # Auditing real-time threads and signal handlers.
+//!
+//! Sending a message may take locks inside the runtime (e.g. when the method
+//! cache is filled), and autorelease pools may allocate, so neither should
+//! be done on threads with real-time constraints (such as audio I/O
+//! threads), or inside signal handlers. Such mistakes are easy to make, and
+//! usually only show up as sporadic glitches.
+//!
+//! This module allows marking the current thread as being in such a context
+//! with [`enter`], after which every message send, autorelease and
+//! autorelease pool push or pop that `objc2` performs on the thread is
+//! reported to a handler, which by default panics. Use
+//! [`set_violation_handler`] to log the violation instead.
+//!
+//! Note that this only catches operations performed through `objc2`; message
+//! sends from Objective-C code, or from other Rust crates that call the
+//! runtime directly, are not detected.
+//!
+//! This module is only available when the `"realtime-audit"` Cargo feature
+//! is enabled, and the checks are compiled out otherwise.
+//!
+//!
+//! # Example
+//!
+//! ```should_panic
+//! use objc2::realtime::{self, RealtimeContext};
+//! use objc2::runtime::{NSObject, NSObjectProtocol};
+//!
+//! let obj = NSObject::new();
+//!
+//! // E.g. inside an audio render callback
+//! let _guard = realtime::enter(RealtimeContext::Thread);
+//! // Panics with "message send of `hash` on a real-time thread"
+//! let _ = obj.hash();
+//! ```
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::thread_local;
+
+use crate::runtime::Sel;
+
+/// The kind of context that the current thread is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RealtimeContext {
+    /// A thread with real-time constraints, such as an audio I/O thread.
+    Thread,
+    /// A signal handler.
+    SignalHandler,
+}
+
+impl fmt::Display for RealtimeContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Thread => write!(f, "on a real-time thread"),
+            Self::SignalHandler => write!(f, "inside a signal handler"),
+        }
+    }
+}
+
+/// An operation that is not allowed in a [`RealtimeContext`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// A message send with the given selector.
+    MessageSend(Sel),
+    /// An object was autoreleased.
+    Autorelease,
+    /// An autorelease pool was pushed.
+    AutoreleasePoolPush,
+    /// An autorelease pool was popped.
+    AutoreleasePoolPop,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MessageSend(sel) => write!(f, "message send of `{sel}`"),
+            Self::Autorelease => write!(f, "autorelease"),
+            Self::AutoreleasePoolPush => write!(f, "autorelease pool push"),
+            Self::AutoreleasePoolPop => write!(f, "autorelease pool pop"),
+        }
+    }
+}
+
+/// An operation that was performed in a [`RealtimeContext`].
+///
+/// This is given to the handler set with [`set_violation_handler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Violation {
+    context: RealtimeContext,
+    operation: Operation,
+}
+
+impl Violation {
+    /// The context that the thread was in.
+    #[inline]
+    pub fn context(&self) -> RealtimeContext {
+        self.context
+    }
+
+    /// The operation that was performed.
+    #[inline]
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.operation, self.context)
+    }
+}
+
+thread_local! {
+    // Const-initialized and without a destructor, so that it can be accessed
+    // from signal handlers.
+    static CONTEXT: Cell<Option<RealtimeContext>> = const { Cell::new(None) };
+}
+
+/// Null means that the default handler is used.
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Mark the current thread as being in the given context, until the
+/// returned guard is dropped.
+///
+/// Guards may be nested, in which case the innermost context applies.
+#[inline]
+pub fn enter(context: RealtimeContext) -> RealtimeGuard {
+    RealtimeGuard {
+        previous: replace(Some(context)),
+        _not_send: PhantomData,
+    }
+}
+
+/// The context that the current thread is in, if any.
+#[inline]
+pub fn current() -> Option<RealtimeContext> {
+    CONTEXT.try_with(Cell::get).ok().flatten()
+}
+
+/// Set the function that is called when an operation is performed in a
+/// [`RealtimeContext`].
+///
+/// The default handler panics. The handler is called with the context
+/// temporarily cleared, so it may itself send messages (though if it is
+/// called from a signal handler, it should still restrict itself to
+/// async-signal-safe operations).
+pub fn set_violation_handler(handler: fn(&Violation)) {
+    HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+/// Marks the current thread as being in a [`RealtimeContext`].
+///
+/// Created with [`enter`]; restores the previous context when dropped.
+#[derive(Debug)]
+#[must_use = "the context is exited when the guard is dropped"]
+pub struct RealtimeGuard {
+    previous: Option<RealtimeContext>,
+    // The guard modifies thread-local state
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for RealtimeGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = replace(self.previous);
+    }
+}
+
+fn replace(context: Option<RealtimeContext>) -> Option<RealtimeContext> {
+    CONTEXT.try_with(|c| c.replace(context)).ok().flatten()
+}
+
+/// Report the operation if the current thread is in a real-time context.
+#[inline]
+#[track_caller]
+pub(crate) fn check(operation: Operation) {
+    if let Some(context) = current() {
+        violation(Violation { context, operation });
+    }
+}
+
+#[cold]
+#[track_caller]
+fn violation(violation: Violation) {
+    // Don't report operations done while unwinding from a previous
+    // violation, e.g. when autorelease pools are popped, since panicking
+    // again would abort.
+    if std::thread::panicking() {
+        return;
+    }
+    // Clear the context while the handler runs, to avoid recursion.
+    let _guard = RealtimeGuard {
+        previous: replace(None),
+        _not_send: PhantomData,
+    };
+    let handler = HANDLER.load(Ordering::Acquire);
+    if handler.is_null() {
+        panic!("{violation}");
+    } else {
+        // SAFETY: The pointer was created from a `fn(&Violation)` in
+        // `set_violation_handler`.
+        let handler: fn(&Violation) = unsafe { mem::transmute(handler) };
+        handler(&violation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rc::autoreleasepool;
+    use crate::runtime::{BatchSender, NSObject, NSObjectProtocol};
+    use crate::sel;
+
+    #[test]
+    fn test_nested() {
+        assert_eq!(current(), None);
+        let outer = enter(RealtimeContext::Thread);
+        assert_eq!(current(), Some(RealtimeContext::Thread));
+        {
+            let _inner = enter(RealtimeContext::SignalHandler);
+            assert_eq!(current(), Some(RealtimeContext::SignalHandler));
+        }
+        assert_eq!(current(), Some(RealtimeContext::Thread));
+        drop(outer);
+        assert_eq!(current(), None);
+    }
+
+    #[test]
+    #[should_panic = "message send of `hash` on a real-time thread"]
+    fn test_message_send() {
+        let obj = NSObject::new();
+        let _guard = enter(RealtimeContext::Thread);
+        let _ = obj.hash();
+    }
+
+    #[test]
+    #[should_panic = "message send of `hash` on a real-time thread"]
+    fn test_batch_sender() {
+        let obj = NSObject::new();
+        let mut sender = BatchSender::new(sel!(hash));
+        // Fill the cache, so that the method implementation is called
+        // directly.
+        let _: usize = unsafe { sender.send(&*obj, ()) };
+        let _guard = enter(RealtimeContext::Thread);
+        let _: usize = unsafe { sender.send(&*obj, ()) };
+    }
+
+    #[test]
+    #[should_panic = "autorelease pool push inside a signal handler"]
+    fn test_autoreleasepool() {
+        let _guard = enter(RealtimeContext::SignalHandler);
+        autoreleasepool(|_| {});
+    }
+
+    #[test]
+    fn test_outside_context() {
+        let obj = NSObject::new();
+        autoreleasepool(|_| {
+            let _ = obj.hash();
+        });
+    }
+
+    #[test]
+    fn test_display() {
+        let violation = Violation {
+            context: RealtimeContext::Thread,
+            operation: Operation::MessageSend(sel!(init)),
+        };
+        assert_eq!(
+            std::format!("{violation}"),
+            "message send of `init` on a real-time thread"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 //! # Dynamically creating classes and protocols.
 use alloc::format;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
+use core::ffi::c_void;
+use core::fmt;
 use core::mem;
 use core::mem::ManuallyDrop;
 use core::ptr;
@@ -9,7 +11,10 @@ use std::ffi::CString;
 
 use crate::encode::{Encode, EncodeArguments, EncodeReturn, Encoding};
 use crate::ffi;
-use crate::runtime::{AnyClass, AnyObject, AnyProtocol, Bool, Imp, MethodImplementation, Sel};
+use crate::rc::RetainReleaseHooks;
+#[cfg(not(feature = "unstable-objfw"))]
+use crate::runtime::AnyProtocol;
+use crate::runtime::{AnyClass, AnyObject, Bool, Imp, MethodImplementation, Sel};
 use crate::sel;
 use crate::Message;
 
@@ -23,10 +28,22 @@ fn method_type_encoding(ret: &Encoding, args: &[Encoding]) -> CString {
     CString::new(types).unwrap()
 }
 
+/// Sign a function pointer created in Rust, as the runtime expects on
+/// `arm64e`. Does nothing on other targets.
+fn sign_imp(func: Imp) -> Imp {
+    // SAFETY: Rust does not sign function pointers itself, so `func` is
+    // unsigned.
+    let ptr = unsafe { ffi::ptrauth_sign_function(func as *const c_void, 0) };
+    // SAFETY: Signing preserves that the pointer is non-null.
+    unsafe { mem::transmute::<*const c_void, Imp>(ptr) }
+}
+
+#[cfg(not(feature = "unstable-objfw"))]
 trait Log2Alignment {
     const LOG2_ALIGNMENT: u8;
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl<T> Log2Alignment for T {
     const LOG2_ALIGNMENT: u8 = {
         let align = mem::align_of::<T>();
@@ -221,7 +238,8 @@ impl ClassBuilder {
     /// For example, your class probably cannot be passed to Cocoa code unless
     /// the entire `NSObject` protocol is implemented.
     /// Functionality it expects, like implementations of `-retain` and
-    /// `-release` used by ARC, will not be present otherwise.
+    /// `-release` used by ARC, will not be present otherwise (see
+    /// [`ClassBuilder::add_retain_release_hooks`] for adding those).
     pub fn root<F>(name: &str, intitialize_fn: F) -> Option<Self>
     where
         F: MethodImplementation<Callee = AnyClass, Arguments = (), Return = ()>,
@@ -250,6 +268,23 @@ impl ClassBuilder {
     /// The caller must ensure that the types match those that are expected
     /// when the method is invoked from Objective-C.
     pub unsafe fn add_method<T, F>(&mut self, sel: Sel, func: F)
+    where
+        T: Message + ?Sized,
+        F: MethodImplementation<Callee = T>,
+    {
+        // SAFETY: Checked by caller
+        if let Err(err) = unsafe { self.try_add_method(sel, func) } {
+            panic!("{err}")
+        }
+    }
+
+    /// Same as [`add_method`](Self::add_method), but returns an error
+    /// instead of panicking if the method could not be added.
+    pub(crate) unsafe fn try_add_method<T, F>(
+        &mut self,
+        sel: Sel,
+        func: F,
+    ) -> Result<(), ClassRegistrationError>
     where
         T: Message + ?Sized,
         F: MethodImplementation<Callee = T>,
@@ -260,6 +295,58 @@ impl ClassBuilder {
                 F::Arguments::ENCODINGS,
                 &F::Return::ENCODING_RETURN,
                 func.__imp(),
+            )
+        }
+    }
+
+    /// Adds `-retain`, `-release` and `-autorelease` methods that forward to
+    /// the given [`RetainReleaseHooks`] implementation.
+    ///
+    /// This is mostly useful on root classes created with
+    /// [`ClassBuilder::root`], which otherwise have no reference counting
+    /// methods.
+    ///
+    /// [`RetainReleaseHooks`]: crate::rc::RetainReleaseHooks
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the methods were already added to this class.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The class being built must represent `T`, that is, all instances of
+    /// the class must be valid instances of `T`.
+    #[doc(alias = "retain")]
+    #[doc(alias = "release")]
+    pub unsafe fn add_retain_release_hooks<T: RetainReleaseHooks>(&mut self) {
+        extern "C" fn retain<T: RetainReleaseHooks>(this: NonNull<T>, _cmd: Sel) -> *mut T {
+            // SAFETY: Upheld by the caller of `add_retain_release_hooks`.
+            unsafe { T::retain(this) }.as_ptr()
+        }
+
+        extern "C" fn release<T: RetainReleaseHooks>(this: NonNull<T>, _cmd: Sel) {
+            // SAFETY: Upheld by the caller of `add_retain_release_hooks`.
+            unsafe { T::release(this) }
+        }
+
+        extern "C" fn autorelease<T: RetainReleaseHooks>(this: NonNull<T>, _cmd: Sel) -> *mut T {
+            // SAFETY: Upheld by the caller of `add_retain_release_hooks`.
+            unsafe { T::autorelease(this) }.as_ptr()
+        }
+
+        // SAFETY: The signatures match what is expected of these selectors,
+        // and the caller upholds that the receiver is `T`.
+        unsafe {
+            self.add_method(
+                sel!(retain),
+                retain::<T> as extern "C" fn(NonNull<T>, _) -> _,
+            );
+            self.add_method(sel!(release), release::<T> as extern "C" fn(NonNull<T>, _));
+            self.add_method(
+                sel!(autorelease),
+                autorelease::<T> as extern "C" fn(NonNull<T>, _) -> _,
             );
         }
     }
@@ -270,32 +357,43 @@ impl ClassBuilder {
         enc_args: &[Encoding],
         enc_ret: &Encoding,
         func: Imp,
-    ) {
-        let sel_args = sel.number_of_arguments();
-        assert_eq!(
-            sel_args,
-            enc_args.len(),
-            "selector {sel} accepts {sel_args} arguments, but function accepts {}",
-            enc_args.len(),
-        );
+    ) -> Result<(), ClassRegistrationError> {
+        if sel.number_of_arguments() != enc_args.len() {
+            return Err(self.error(RegistrationErrorKind::ArgumentCount {
+                sel,
+                class_method: false,
+                function_args: enc_args.len(),
+            }));
+        }
 
         // Verify that, if the method is present on the superclass, that the
         // encoding is correct.
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
         if let Some(superclass) = self.superclass() {
             if let Some(method) = superclass.instance_method(sel) {
                 if let Err(err) = crate::verify::verify_method_signature(method, enc_args, enc_ret)
                 {
-                    panic!("declared invalid method -[{} {sel}]: {err}", self.name())
+                    return Err(self.error(RegistrationErrorKind::InvalidMethod {
+                        sel,
+                        class_method: false,
+                        reason: err.to_string(),
+                    }));
                 }
             }
         }
 
         let types = method_type_encoding(enc_ret, enc_args);
+        let func = sign_imp(func);
         let success = Bool::from_raw(unsafe {
             ffi::class_addMethod(self.as_mut_ptr(), sel.as_ptr(), Some(func), types.as_ptr())
         });
-        assert!(success.as_bool(), "failed to add method {sel}");
+        if !success.as_bool() {
+            return Err(self.error(RegistrationErrorKind::AddMethod {
+                sel,
+                class_method: false,
+            }));
+        }
+        Ok(())
     }
 
     fn metaclass_mut(&mut self) -> *mut ffi::objc_class {
@@ -315,6 +413,22 @@ impl ClassBuilder {
     /// The caller must ensure that the types match those that are expected
     /// when the method is invoked from Objective-C.
     pub unsafe fn add_class_method<F>(&mut self, sel: Sel, func: F)
+    where
+        F: MethodImplementation<Callee = AnyClass>,
+    {
+        // SAFETY: Checked by caller
+        if let Err(err) = unsafe { self.try_add_class_method(sel, func) } {
+            panic!("{err}")
+        }
+    }
+
+    /// Same as [`add_class_method`](Self::add_class_method), but returns an
+    /// error instead of panicking if the method could not be added.
+    pub(crate) unsafe fn try_add_class_method<F>(
+        &mut self,
+        sel: Sel,
+        func: F,
+    ) -> Result<(), ClassRegistrationError>
     where
         F: MethodImplementation<Callee = AnyClass>,
     {
@@ -324,7 +438,7 @@ impl ClassBuilder {
                 F::Arguments::ENCODINGS,
                 &F::Return::ENCODING_RETURN,
                 func.__imp(),
-            );
+            )
         }
     }
 
@@ -334,28 +448,33 @@ impl ClassBuilder {
         enc_args: &[Encoding],
         enc_ret: &Encoding,
         func: Imp,
-    ) {
-        let sel_args = sel.number_of_arguments();
-        assert_eq!(
-            sel_args,
-            enc_args.len(),
-            "selector {sel} accepts {sel_args} arguments, but function accepts {}",
-            enc_args.len(),
-        );
+    ) -> Result<(), ClassRegistrationError> {
+        if sel.number_of_arguments() != enc_args.len() {
+            return Err(self.error(RegistrationErrorKind::ArgumentCount {
+                sel,
+                class_method: true,
+                function_args: enc_args.len(),
+            }));
+        }
 
         // Verify that, if the method is present on the superclass, that the
         // encoding is correct.
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
         if let Some(superclass) = self.superclass() {
             if let Some(method) = superclass.class_method(sel) {
                 if let Err(err) = crate::verify::verify_method_signature(method, enc_args, enc_ret)
                 {
-                    panic!("declared invalid method +[{} {sel}]: {err}", self.name())
+                    return Err(self.error(RegistrationErrorKind::InvalidMethod {
+                        sel,
+                        class_method: true,
+                        reason: err.to_string(),
+                    }));
                 }
             }
         }
 
         let types = method_type_encoding(enc_ret, enc_args);
+        let func = sign_imp(func);
         let success = Bool::from_raw(unsafe {
             ffi::class_addMethod(
                 self.metaclass_mut(),
@@ -364,7 +483,13 @@ impl ClassBuilder {
                 types.as_ptr(),
             )
         });
-        assert!(success.as_bool(), "failed to add class method {sel}");
+        if !success.as_bool() {
+            return Err(self.error(RegistrationErrorKind::AddMethod {
+                sel,
+                class_method: true,
+            }));
+        }
+        Ok(())
     }
 
     /// Adds an ivar with type `T` and the provided name.
@@ -374,15 +499,24 @@ impl ClassBuilder {
     ///
     /// If the ivar wasn't successfully added for some reason - this usually
     /// happens if there already was an ivar with that name.
+    #[cfg(not(feature = "unstable-objfw"))]
     pub fn add_ivar<T: Encode>(&mut self, name: &str) {
         // SAFETY: The encoding is correct
-        unsafe { self.add_ivar_inner::<T>(name, &T::ENCODING) }
+        if let Err(err) = unsafe { self.add_ivar_inner::<T>(name, &T::ENCODING) } {
+            panic!("{err}")
+        }
     }
 
-    pub(crate) unsafe fn add_ivar_inner<T>(&mut self, name: &str, encoding: &Encoding) {
+    #[cfg(not(feature = "unstable-objfw"))]
+    pub(crate) unsafe fn add_ivar_inner<T>(
+        &mut self,
+        name: &str,
+        encoding: &Encoding,
+    ) -> Result<(), ClassRegistrationError> {
         unsafe { self.add_ivar_inner_mono(name, mem::size_of::<T>(), T::LOG2_ALIGNMENT, encoding) }
     }
 
+    #[cfg(not(feature = "unstable-objfw"))]
     // Monomorphized version
     unsafe fn add_ivar_inner_mono(
         &mut self,
@@ -390,7 +524,7 @@ impl ClassBuilder {
         size: usize,
         align: u8,
         encoding: &Encoding,
-    ) {
+    ) -> Result<(), ClassRegistrationError> {
         let c_name = CString::new(name).unwrap();
         let encoding = CString::new(encoding.to_string()).unwrap();
 
@@ -411,7 +545,10 @@ impl ClassBuilder {
                 encoding.as_ptr(),
             )
         });
-        assert!(success.as_bool(), "failed to add ivar {name}");
+        if !success.as_bool() {
+            return Err(self.error(RegistrationErrorKind::AddIvar(name.to_string())));
+        }
+        Ok(())
     }
 
     /// Adds the given protocol to self.
@@ -419,11 +556,32 @@ impl ClassBuilder {
     /// # Panics
     ///
     /// If the protocol wasn't successfully added.
+    #[cfg(not(feature = "unstable-objfw"))]
     pub fn add_protocol(&mut self, proto: &AnyProtocol) {
+        if let Err(err) = self.try_add_protocol(proto) {
+            panic!("{err}")
+        }
+    }
+
+    /// Same as [`add_protocol`](Self::add_protocol), but returns an error
+    /// instead of panicking if the protocol could not be added.
+    #[cfg(not(feature = "unstable-objfw"))]
+    pub(crate) fn try_add_protocol(
+        &mut self,
+        proto: &AnyProtocol,
+    ) -> Result<(), ClassRegistrationError> {
         let success = unsafe { ffi::class_addProtocol(self.as_mut_ptr(), proto.as_ptr()) };
         let success = Bool::from_raw(success).as_bool();
-        if cfg!(not(feature = "gnustep-1-7")) {
-            assert!(success, "failed to add protocol {proto}");
+        if cfg!(not(feature = "gnustep-1-7")) && !success {
+            return Err(self.error(RegistrationErrorKind::AddProtocol(proto.name().to_string())));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn error(&self, kind: RegistrationErrorKind) -> ClassRegistrationError {
+        ClassRegistrationError {
+            class: self.name().to_string(),
+            kind,
         }
     }
 
@@ -452,21 +610,232 @@ impl Drop for ClassBuilder {
             ffi::objc_registerClassPair(self.as_mut_ptr());
         }
 
-        unsafe { ffi::objc_disposeClassPair(self.as_mut_ptr()) }
+        // ObjFW cannot dispose classes, so the class is leaked there.
+        #[cfg(not(feature = "unstable-objfw"))]
+        unsafe {
+            ffi::objc_disposeClassPair(self.as_mut_ptr())
+        }
+    }
+}
+
+/// An error that occurred while registering a class with the runtime.
+///
+/// This is returned by [`DeclaredClass::try_register`] for classes declared
+/// with [`declare_class!`], and describes which part of the class caused the
+/// failure.
+///
+/// [`DeclaredClass::try_register`]: crate::DeclaredClass::try_register
+/// [`declare_class!`]: crate::declare_class
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClassRegistrationError {
+    class: String,
+    kind: RegistrationErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum RegistrationErrorKind {
+    ClassExists,
+    // Only checked when debug assertions are enabled.
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+    InvalidMethod {
+        sel: Sel,
+        class_method: bool,
+        reason: String,
+    },
+    ArgumentCount {
+        sel: Sel,
+        class_method: bool,
+        function_args: usize,
+    },
+    AddMethod {
+        sel: Sel,
+        class_method: bool,
+    },
+    #[cfg(not(feature = "unstable-objfw"))]
+    AddIvar(String),
+    #[cfg(not(feature = "unstable-objfw"))]
+    IvarAlignment(usize),
+    #[cfg(not(feature = "unstable-objfw"))]
+    AddProtocol(String),
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+    UnknownProtocolMethod {
+        protocol: String,
+        sel: Sel,
+        class_method: bool,
+    },
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+    MissingProtocolMethod {
+        protocol: String,
+        sel: Sel,
+        class_method: bool,
+    },
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+    ProxyForwarding,
+}
+
+impl ClassRegistrationError {
+    pub(crate) fn new(class: &str, kind: RegistrationErrorKind) -> Self {
+        Self {
+            class: class.to_string(),
+            kind,
+        }
+    }
+
+    /// The name of the class that failed to register.
+    pub fn class_name(&self) -> &str {
+        &self.class
+    }
+
+    /// The selector of the method that caused the failure, if any.
+    pub fn selector(&self) -> Option<Sel> {
+        match self.kind {
+            RegistrationErrorKind::ArgumentCount { sel, .. }
+            | RegistrationErrorKind::AddMethod { sel, .. } => Some(sel),
+            #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+            RegistrationErrorKind::InvalidMethod { sel, .. }
+            | RegistrationErrorKind::UnknownProtocolMethod { sel, .. }
+            | RegistrationErrorKind::MissingProtocolMethod { sel, .. } => Some(sel),
+            _ => None,
+        }
+    }
+
+    /// Whether the method that caused the failure is a class method.
+    ///
+    /// Returns `false` if the failure was not caused by a method.
+    pub fn is_class_method(&self) -> bool {
+        match self.kind {
+            RegistrationErrorKind::ArgumentCount { class_method, .. }
+            | RegistrationErrorKind::AddMethod { class_method, .. } => class_method,
+            #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+            RegistrationErrorKind::InvalidMethod { class_method, .. }
+            | RegistrationErrorKind::UnknownProtocolMethod { class_method, .. }
+            | RegistrationErrorKind::MissingProtocolMethod { class_method, .. } => class_method,
+            _ => false,
+        }
+    }
+
+    /// The name of the instance variable that caused the failure, if any.
+    pub fn ivar(&self) -> Option<&str> {
+        match &self.kind {
+            #[cfg(not(feature = "unstable-objfw"))]
+            RegistrationErrorKind::AddIvar(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The name of the protocol that caused the failure, if any.
+    pub fn protocol(&self) -> Option<&str> {
+        match &self.kind {
+            #[cfg(not(feature = "unstable-objfw"))]
+            RegistrationErrorKind::AddProtocol(protocol) => Some(protocol),
+            #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+            RegistrationErrorKind::UnknownProtocolMethod { protocol, .. }
+            | RegistrationErrorKind::MissingProtocolMethod { protocol, .. } => Some(protocol),
+            _ => None,
+        }
     }
 }
 
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+fn method_prefix(class_method: bool) -> char {
+    if class_method {
+        '+'
+    } else {
+        '-'
+    }
+}
+
+impl fmt::Display for ClassRegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let class = &self.class;
+        match &self.kind {
+            RegistrationErrorKind::ClassExists => write!(
+                f,
+                "could not create new class {class}. Perhaps a class with that name already exists?"
+            ),
+            #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+            RegistrationErrorKind::InvalidMethod {
+                sel,
+                class_method,
+                reason,
+            } => write!(
+                f,
+                "declared invalid method {}[{class} {sel}]: {reason}",
+                method_prefix(*class_method),
+            ),
+            RegistrationErrorKind::ArgumentCount {
+                sel,
+                function_args,
+                ..
+            } => write!(
+                f,
+                "selector {sel} accepts {} arguments, but function accepts {function_args}",
+                sel.number_of_arguments(),
+            ),
+            RegistrationErrorKind::AddMethod {
+                sel,
+                class_method: false,
+            } => write!(f, "failed to add method {sel}"),
+            RegistrationErrorKind::AddMethod {
+                sel,
+                class_method: true,
+            } => write!(f, "failed to add class method {sel}"),
+            #[cfg(not(feature = "unstable-objfw"))]
+            RegistrationErrorKind::AddIvar(name) => write!(f, "failed to add ivar {name}"),
+            #[cfg(not(feature = "unstable-objfw"))]
+            RegistrationErrorKind::IvarAlignment(alignment) => {
+                write!(f, "unsupported alignment {alignment} for `{class}::Ivars`")
+            }
+            #[cfg(not(feature = "unstable-objfw"))]
+            RegistrationErrorKind::AddProtocol(protocol) => {
+                write!(f, "failed to add protocol {protocol}")
+            }
+            #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+            RegistrationErrorKind::UnknownProtocolMethod {
+                protocol,
+                sel,
+                class_method,
+            } => write!(
+                f,
+                "failed overriding protocol method {}[{protocol} {sel}]: method not found",
+                method_prefix(*class_method),
+            ),
+            #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+            RegistrationErrorKind::MissingProtocolMethod {
+                protocol,
+                sel,
+                class_method,
+            } => write!(
+                f,
+                "must implement required protocol method {}[{protocol} {sel}]",
+                method_prefix(*class_method),
+            ),
+            #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+            RegistrationErrorKind::ProxyForwarding => write!(
+                f,
+                "proxy class {class} must override either `forwardingTargetForSelector:`, or both `forwardInvocation:` and `methodSignatureForSelector:`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ClassRegistrationError {}
+
 /// A type for declaring a new protocol and adding new methods to it
 /// before registering it.
+#[cfg(not(feature = "unstable-objfw"))]
 #[derive(Debug)]
 pub struct ProtocolBuilder {
     proto: NonNull<AnyProtocol>,
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 // SAFETY: Similar to ClassBuilder
 unsafe impl Send for ProtocolBuilder {}
+#[cfg(not(feature = "unstable-objfw"))]
 unsafe impl Sync for ProtocolBuilder {}
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl ProtocolBuilder {
     fn as_mut_ptr(&mut self) -> *mut ffi::objc_protocol {
         self.proto.as_ptr().cast()
@@ -560,6 +929,7 @@ impl ProtocolBuilder {
     }
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl Drop for ProtocolBuilder {
     fn drop(&mut self) {
         // We implement Drop to communicate to the type-system that this type
@@ -569,6 +939,7 @@ impl Drop for ProtocolBuilder {
 }
 
 #[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
 mod tests {
     use core::hash::Hasher;
     use std::collections::hash_map::DefaultHasher;
@@ -804,6 +1175,60 @@ mod tests {
         assert!(is_present(cls));
     }
 
+    #[test]
+    fn test_retain_release_hooks() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static RETAINS: AtomicUsize = AtomicUsize::new(0);
+        static RELEASES: AtomicUsize = AtomicUsize::new(0);
+
+        #[repr(C)]
+        struct HookedObject(AnyObject);
+
+        unsafe impl RefEncode for HookedObject {
+            const ENCODING_REF: Encoding = Encoding::Object;
+        }
+
+        unsafe impl Message for HookedObject {}
+
+        unsafe impl RetainReleaseHooks for HookedObject {
+            unsafe fn retain(this: NonNull<Self>) -> NonNull<Self> {
+                RETAINS.fetch_add(1, Ordering::Relaxed);
+                this
+            }
+
+            unsafe fn release(_this: NonNull<Self>) {
+                RELEASES.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        extern "C" fn initialize(_cls: &AnyClass, _cmd: Sel) {}
+
+        let mut builder =
+            ClassBuilder::root("TestRetainReleaseHooks", initialize as extern "C" fn(_, _))
+                .unwrap();
+        unsafe { builder.add_retain_release_hooks::<HookedObject>() };
+        let cls = builder.register();
+
+        let ptr: *mut HookedObject = unsafe { ffi::class_createInstance(cls.as_ptr(), 0) }.cast();
+        let obj = unsafe { Id::retain(ptr) }.unwrap();
+        assert_eq!(RETAINS.load(Ordering::Relaxed), 1);
+        assert_eq!(RELEASES.load(Ordering::Relaxed), 0);
+
+        let obj2 = unsafe { Id::retain(ptr) }.unwrap();
+        assert_eq!(RETAINS.load(Ordering::Relaxed), 2);
+
+        drop(obj);
+        drop(obj2);
+        assert_eq!(RETAINS.load(Ordering::Relaxed), 2);
+        assert_eq!(RELEASES.load(Ordering::Relaxed), 2);
+
+        #[allow(deprecated)]
+        unsafe {
+            ffi::object_dispose(ptr.cast())
+        };
+    }
+
     #[test]
     fn test_class_method() {
         let cls = test_utils::custom_class();
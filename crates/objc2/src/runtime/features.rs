@@ -0,0 +1,167 @@
+/// The capabilities of the Objective-C runtime that is being linked to.
+///
+/// The different runtimes that `objc2` supports do not all implement the
+/// same set of functionality. Instead of failing at link time (or worse, at
+/// runtime), cross-platform code can query this with [`features`] and
+/// choose a different code path if something is not supported.
+///
+/// The capabilities are determined by the runtime that was selected with
+/// Cargo features (see `objc-sys` for details), and are therefore known at
+/// compile time; all methods on this are `const`.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::runtime;
+///
+/// if runtime::features().weak_references() {
+///     // Use `WeakId`
+/// } else {
+///     // Fall back to something else
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RuntimeFeatures {
+    native_exceptions: bool,
+    weak_references: bool,
+    load_weak_retained: bool,
+    arc_entry_points: bool,
+    autorelease_return_optimization: bool,
+}
+
+/// Query the capabilities of the Objective-C runtime.
+///
+/// See [`RuntimeFeatures`] for details.
+#[inline]
+pub const fn features() -> RuntimeFeatures {
+    RuntimeFeatures::CURRENT
+}
+
+impl RuntimeFeatures {
+    #[cfg(all(feature = "apple", not(all(target_os = "macos", target_arch = "x86"))))]
+    const CURRENT: Self = Self {
+        native_exceptions: true,
+        weak_references: true,
+        load_weak_retained: true,
+        arc_entry_points: true,
+        autorelease_return_optimization: true,
+    };
+
+    // The legacy runtime on 32-bit macOS does not support weak references,
+    // and only uses setjmp/longjmp-based exceptions.
+    #[cfg(all(feature = "apple", target_os = "macos", target_arch = "x86"))]
+    const CURRENT: Self = Self {
+        native_exceptions: false,
+        weak_references: false,
+        load_weak_retained: false,
+        arc_entry_points: true,
+        autorelease_return_optimization: false,
+    };
+
+    // libobjc2 has supported all of this since version 1.7.
+    #[cfg(all(feature = "gnustep-1-7", not(feature = "unstable-mock-runtime")))]
+    const CURRENT: Self = Self {
+        native_exceptions: true,
+        weak_references: true,
+        load_weak_retained: true,
+        arc_entry_points: true,
+        autorelease_return_optimization: true,
+    };
+
+    // The mock runtime does not implement exceptions.
+    #[cfg(feature = "unstable-mock-runtime")]
+    const CURRENT: Self = Self {
+        native_exceptions: false,
+        weak_references: true,
+        load_weak_retained: true,
+        arc_entry_points: true,
+        autorelease_return_optimization: true,
+    };
+
+    // ObjFW's `objc_autoreleaseReturnValue` always autoreleases.
+    #[cfg(feature = "unstable-objfw")]
+    const CURRENT: Self = Self {
+        native_exceptions: true,
+        weak_references: true,
+        load_weak_retained: true,
+        arc_entry_points: true,
+        autorelease_return_optimization: false,
+    };
+
+    /// Whether the runtime supports throwing and catching Objective-C
+    /// exceptions using the platform's native unwinder.
+    ///
+    /// If this is `false`, [`exception::catch`] and [`exception::throw`] may
+    /// not be used, even if the `"exception"` Cargo feature is enabled.
+    ///
+    /// [`exception::catch`]: crate::exception::catch
+    /// [`exception::throw`]: crate::exception::throw
+    #[inline]
+    pub const fn native_exceptions(self) -> bool {
+        self.native_exceptions
+    }
+
+    /// Whether the runtime supports zeroing weak references, and with that
+    /// [`WeakId`].
+    ///
+    /// [`WeakId`]: crate::rc::WeakId
+    #[inline]
+    #[doc(alias = "objc_initWeak")]
+    pub const fn weak_references(self) -> bool {
+        self.weak_references
+    }
+
+    /// Whether the runtime provides `objc_loadWeakRetained`, which loads a
+    /// weak reference without putting it in the autorelease pool.
+    ///
+    /// If this is `false`, [`WeakId::load`] falls back to `objc_loadWeak`
+    /// followed by a retain.
+    ///
+    /// [`WeakId::load`]: crate::rc::WeakId::load
+    #[inline]
+    #[doc(alias = "objc_loadWeakRetained")]
+    pub const fn load_weak_retained(self) -> bool {
+        self.load_weak_retained
+    }
+
+    /// Whether the runtime provides the entry points used by ARC, such as
+    /// `objc_retain`, `objc_release` and `objc_autoreleaseReturnValue`.
+    ///
+    /// These are required by `objc2`, so this is always `true`; it is
+    /// provided for completeness.
+    #[inline]
+    pub const fn arc_entry_points(self) -> bool {
+        self.arc_entry_points
+    }
+
+    /// Whether returning autoreleased objects with
+    /// `objc_autoreleaseReturnValue` and
+    /// `objc_retainAutoreleasedReturnValue` can skip the autorelease pool.
+    ///
+    /// If this is `false`, the objects are still correctly retained, but
+    /// stay alive until the innermost autorelease pool is drained.
+    #[inline]
+    pub const fn autorelease_return_optimization(self) -> bool {
+        self.autorelease_return_optimization
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features() {
+        let features = features();
+        assert!(features.arc_entry_points());
+        if cfg!(any(feature = "gnustep-1-7", target_arch = "aarch64")) {
+            assert!(features.weak_references());
+            assert!(features.load_weak_retained());
+        }
+        if cfg!(feature = "unstable-mock-runtime") {
+            assert!(!features.native_exceptions());
+        }
+        let _ = std::format!("{features:?}");
+    }
+}
@@ -3,9 +3,13 @@ use core::fmt;
 use core::marker::PhantomData;
 use core::ops::Deref;
 use core::ptr::{self, NonNull};
+#[cfg(not(feature = "unstable-objfw"))]
 use core::str;
+#[cfg(not(feature = "unstable-objfw"))]
 use core::str::Utf8Error;
+#[cfg(not(feature = "unstable-objfw"))]
 use std::ffi::CStr;
+#[cfg(not(feature = "unstable-objfw"))]
 use std::os::raw::c_char;
 
 use crate::ffi;
@@ -85,11 +89,13 @@ impl<T> AsRef<[T]> for MallocSlice<T> {
 }
 
 // TODO: Change this to `MallocCStr` once we get rid of `malloc_buf` support.
+#[cfg(not(feature = "unstable-objfw"))]
 #[repr(transparent)]
 pub(crate) struct MallocStr {
     ptr: NonNull<str>,
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl MallocStr {
     // Currently has to have the same API as `malloc_buf::Malloc`
     pub(crate) unsafe fn from_c_str(ptr: *mut c_char) -> Result<Self, Utf8Error> {
@@ -111,6 +117,7 @@ impl MallocStr {
     }
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl Drop for MallocStr {
     #[inline]
     fn drop(&mut self) {
@@ -119,6 +126,7 @@ impl Drop for MallocStr {
     }
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl Deref for MallocStr {
     type Target = str;
 
@@ -129,6 +137,7 @@ impl Deref for MallocStr {
     }
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl fmt::Debug for MallocStr {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -136,6 +145,7 @@ impl fmt::Debug for MallocStr {
     }
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl fmt::Display for MallocStr {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -143,6 +153,7 @@ impl fmt::Display for MallocStr {
     }
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl AsRef<str> for MallocStr {
     #[inline]
     fn as_ref(&self) -> &str {
@@ -1,12 +1,15 @@
-use core::ptr::NonNull;
+use core::mem::ManuallyDrop;
+use core::pin::Pin;
+use core::ptr::{self, NonNull};
 
 use crate::encode::{EncodeArguments, EncodeReturn, RefEncode};
-use crate::mutability::IsAllowedMutable;
-use crate::runtime::{AnyClass, AnyObject, Sel};
+use crate::mutability::{IsAllowedMutable, IsMutable};
+use crate::rc::Id;
+use crate::runtime::{AnyClass, AnyObject, Imp, Sel};
 use crate::Message;
 
 /// Wrap the given closure in `exception::catch` if the `catch-all` feature is
-/// enabled.
+/// enabled, and turn the exception into a panic.
 ///
 /// This is a macro to help with monomorphization when the feature is
 /// disabled, as well as improving the final stack trace (`#[track_caller]`
@@ -18,7 +21,7 @@ macro_rules! conditional_try {
     };
 }
 
-#[cfg(feature = "catch-all")]
+#[cfg(all(feature = "catch-all", not(feature = "unwind")))]
 macro_rules! conditional_try {
     (|| $expr:expr) => {{
         let f = core::panic::AssertUnwindSafe(|| $expr);
@@ -35,6 +38,17 @@ macro_rules! conditional_try {
     }};
 }
 
+#[cfg(feature = "unwind")]
+macro_rules! conditional_try {
+    (|| $expr:expr) => {{
+        let f = core::panic::AssertUnwindSafe(|| $expr);
+        match crate::exception::catch(f) {
+            Ok(r) => r,
+            Err(exception) => crate::exception::panic_with_exception(exception),
+        }
+    }};
+}
+
 // More information on how objc_msgSend works:
 // <https://web.archive.org/web/20200118080513/http://www.friday.com/bbum/2009/12/18/objc_msgsend-part-1-the-road-map/>
 // <https://www.mikeash.com/pyblog/objc_msgsends-new-prototype.html>
@@ -62,6 +76,9 @@ mod msg_send_primitive {
     /// `objc_msgSend_stret` is not even available in arm64.
     ///
     /// <https://twitter.com/gparker/status/378079715824660480>
+    ///
+    /// This includes `arm64e`; the pointers here are only called from Rust,
+    /// so they are left unsigned.
     unsafe impl<T: EncodeReturn> MsgSendFn for T {
         const MSG_SEND: Imp = ffi::objc_msgSend;
         const MSG_SEND_SUPER: Imp = ffi::objc_msgSendSuper;
@@ -268,6 +285,115 @@ mod msg_send_primitive {
     }
 }
 
+#[cfg(feature = "unstable-objfw")]
+mod msg_send_primitive {
+    use core::mem;
+
+    use crate::encode::{EncodeArguments, EncodeReturn, Encoding};
+    use crate::ffi;
+    use crate::runtime::{AnyClass, AnyObject, Imp, Sel};
+
+    /// Whether the return value is passed through a hidden pointer argument.
+    ///
+    /// ObjFW has separate `_stret` lookup functions for these, which return
+    /// a forwarding handler with the matching calling convention if the
+    /// method is not found.
+    ///
+    /// The rules are the same as the ones used for Apple's
+    /// `objc_msgSend_stret`, see the `apple` implementation above.
+    #[inline]
+    fn uses_stret<R: EncodeReturn>() -> bool {
+        let size = mem::size_of::<R>();
+        if cfg!(target_arch = "x86_64") {
+            size > 16
+        } else if cfg!(target_arch = "x86") {
+            !matches!(size, 0 | 1 | 2 | 4 | 8)
+        } else if cfg!(target_arch = "arm") {
+            !matches!(
+                R::ENCODING_RETURN,
+                Encoding::LongLong | Encoding::ULongLong | Encoding::Double
+            ) && size > 4
+        } else if cfg!(target_arch = "aarch64") {
+            // Homogeneous floating-point aggregates of up to four members
+            // are returned in registers.
+            size > 16 && !is_hfa(&R::ENCODING_RETURN)
+        } else {
+            size > 2 * mem::size_of::<usize>()
+        }
+    }
+
+    fn is_hfa(encoding: &Encoding) -> bool {
+        match encoding {
+            Encoding::Struct(_, [first @ (Encoding::Float | Encoding::Double), rest @ ..])
+                if rest.len() < 4 =>
+            {
+                rest.iter().all(|field| field == first)
+            }
+            _ => false,
+        }
+    }
+
+    #[inline]
+    fn unwrap_msg_send_fn(msg_send_fn: Option<Imp>) -> Imp {
+        match msg_send_fn {
+            Some(msg_send_fn) => msg_send_fn,
+            None => {
+                // SAFETY: Same as with GNUStep, the lookup functions never
+                // return NULL.
+                unsafe { core::hint::unreachable_unchecked() }
+            }
+        }
+    }
+
+    #[track_caller]
+    pub(crate) unsafe fn send<A: EncodeArguments, R: EncodeReturn>(
+        receiver: *mut AnyObject,
+        sel: Sel,
+        args: A,
+    ) -> R {
+        // See the GNUStep implementation for why this is needed.
+        if receiver.is_null() {
+            // SAFETY: Caller guarantees that messages to NULL-receivers only
+            // return pointers or primitive values.
+            return unsafe { mem::zeroed() };
+        }
+
+        let msg_send_fn = if uses_stret::<R>() {
+            unsafe { ffi::objc_msg_lookup_stret(receiver.cast(), sel.as_ptr()) }
+        } else {
+            unsafe { ffi::objc_msg_lookup(receiver.cast(), sel.as_ptr()) }
+        };
+        let msg_send_fn = unwrap_msg_send_fn(msg_send_fn);
+        unsafe { A::__invoke(msg_send_fn, receiver, sel, args) }
+    }
+
+    #[track_caller]
+    pub(crate) unsafe fn send_super<A: EncodeArguments, R: EncodeReturn>(
+        receiver: *mut AnyObject,
+        superclass: &AnyClass,
+        sel: Sel,
+        args: A,
+    ) -> R {
+        if receiver.is_null() {
+            // SAFETY: Same as in `send`.
+            return unsafe { mem::zeroed() };
+        }
+
+        let superclass: *const AnyClass = superclass;
+        let sup = ffi::objc_super {
+            receiver: receiver.cast(),
+            super_class: superclass.cast(),
+        };
+        let msg_send_fn = if uses_stret::<R>() {
+            unsafe { ffi::objc_msg_lookup_super_stret(&sup, sel.as_ptr()) }
+        } else {
+            unsafe { ffi::objc_msg_lookup_super(&sup, sel.as_ptr()) }
+        };
+        let msg_send_fn = unwrap_msg_send_fn(msg_send_fn);
+        unsafe { A::__invoke(msg_send_fn, receiver, sel, args) }
+    }
+}
+
 /// Help with monomorphizing in framework crates
 #[cfg(debug_assertions)]
 #[track_caller]
@@ -286,7 +412,7 @@ fn msg_send_check(
     msg_send_check_class(cls, sel, args, ret);
 }
 
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
 #[track_caller]
 fn msg_send_check_class(
     cls: &AnyClass,
@@ -302,6 +428,10 @@ fn msg_send_check_class(
         } else {
             return;
         }
+    } else if cls.is_proxy() {
+        // Proxies forward messages that they don't implement themselves to
+        // some other object, so we can't verify those here.
+        return;
     } else {
         VerificationError::from(Inner::MethodNotFound)
     };
@@ -309,13 +439,25 @@ fn msg_send_check_class(
     panic_verify(cls, sel, &err);
 }
 
+/// ObjFW does not expose the type encodings of methods, so we can't verify
+/// the message there.
+#[cfg(all(debug_assertions, feature = "unstable-objfw"))]
+#[inline]
+fn msg_send_check_class(
+    _cls: &AnyClass,
+    _sel: Sel,
+    _args: &[crate::encode::Encoding],
+    _ret: &crate::encode::Encoding,
+) {
+}
+
 #[cfg(debug_assertions)]
 #[track_caller]
 fn panic_null(sel: Sel) -> ! {
     panic!("messsaging {sel} to nil")
 }
 
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
 #[track_caller]
 fn panic_verify(cls: &AnyClass, sel: Sel, err: &crate::runtime::VerificationError) -> ! {
     panic!(
@@ -332,6 +474,13 @@ mod private {
 ///
 /// Examples include objects pointers, class pointers, and block pointers.
 ///
+/// This is also implemented for common wrappers around objects, so that they
+/// can be used directly without reborrowing:
+/// - `&Id<T>` and `&mut Id<T>`.
+/// - `ManuallyDrop<Id<T>>`, which consumes the `Id` without releasing it.
+///   This is useful for messages that themselves release the receiver.
+/// - `Pin<&T>` and `Pin<&mut T>`; sending a message never moves the object.
+///
 ///
 /// # Safety
 ///
@@ -394,6 +543,8 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
     #[doc(alias = "performSelector:withObject:withObject:")]
     unsafe fn send_message<A: EncodeArguments, R: EncodeReturn>(self, sel: Sel, args: A) -> R {
         let receiver = self.__as_raw_receiver();
+        #[cfg(feature = "realtime-audit")]
+        crate::realtime::check(crate::realtime::Operation::MessageSend(sel));
         #[cfg(debug_assertions)]
         {
             // SAFETY: Caller ensures only valid or NULL pointers.
@@ -439,6 +590,8 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
         args: A,
     ) -> R {
         let receiver = self.__as_raw_receiver();
+        #[cfg(feature = "realtime-audit")]
+        crate::realtime::check(crate::realtime::Operation::MessageSend(sel));
         #[cfg(debug_assertions)]
         {
             if receiver.is_null() {
@@ -454,6 +607,131 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
     }
 }
 
+/// Send the same message to many objects, caching the method implementation
+/// between each send.
+///
+/// Each message send has to look up the method implementation in the
+/// receiver's class. While that is usually fast, it adds up when sending the
+/// same selector to thousands of objects, e.g. when configuring a large
+/// number of layers. This instead looks up the implementation once for each
+/// run of objects with the same class, and then calls it directly.
+///
+/// Note that this means that methods that are replaced (swizzled) while the
+/// sender is in use may not be picked up, and that receivers that don't
+/// implement the method themselves (such as proxies) fall back to a normal
+/// message send.
+///
+/// See also the [`send_batch!`] macro, which is usually more convenient.
+///
+/// [`send_batch!`]: crate::send_batch
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::runtime::{BatchSender, NSObject};
+/// use objc2::sel;
+///
+/// let objects = [NSObject::new(), NSObject::new()];
+///
+/// let mut sender = BatchSender::new(sel!(hash));
+/// for obj in &objects {
+///     let hash: usize = unsafe { sender.send(&**obj, ()) };
+///     # let _ = hash;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+// Not `Copy`, since the sender carries a cache that is updated when sending.
+#[allow(missing_copy_implementations)]
+pub struct BatchSender {
+    sel: Sel,
+    cache: Option<(*const AnyClass, Imp)>,
+}
+
+impl BatchSender {
+    /// Create a new sender for the given selector.
+    #[inline]
+    pub const fn new(sel: Sel) -> Self {
+        Self { sel, cache: None }
+    }
+
+    /// The selector that is sent.
+    #[inline]
+    pub const fn sel(&self) -> Sel {
+        self.sel
+    }
+
+    /// Send the message to the given receiver with the given arguments.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// This shares the same safety requirements as
+    /// [`MessageReceiver::send_message`].
+    #[inline]
+    #[track_caller]
+    pub unsafe fn send<T: MessageReceiver, A: EncodeArguments, R: EncodeReturn>(
+        &mut self,
+        receiver: T,
+        args: A,
+    ) -> R {
+        let receiver = receiver.__as_raw_receiver();
+        // SAFETY: Caller ensures only valid or NULL pointers.
+        let cls = if let Some(obj) = unsafe { receiver.as_ref() } {
+            obj.class()
+        } else {
+            // Let the normal path handle messages to `nil`.
+            return unsafe { receiver.send_message(self.sel, args) };
+        };
+
+        let imp = match self.cache {
+            Some((cached, imp)) if ptr::eq(cached, cls) => imp,
+            _ => {
+                if let Some(imp) = self.lookup::<A, R>(cls) {
+                    self.cache = Some((cls, imp));
+                    imp
+                } else {
+                    // SAFETY: Upheld by caller.
+                    return unsafe { receiver.send_message(self.sel, args) };
+                }
+            }
+        };
+
+        // The fallbacks above are checked in `send_message`.
+        #[cfg(feature = "realtime-audit")]
+        crate::realtime::check(crate::realtime::Operation::MessageSend(self.sel));
+
+        // SAFETY: The implementation was looked up on the receiver's class,
+        // and the caller upholds that the arguments and return type are
+        // correct.
+        //
+        // The @catch is safe since the method implementation is an
+        // Objective-C method, which does Objective-C compatible unwinding.
+        unsafe { conditional_try!(|| A::__invoke(imp, receiver, self.sel, args)) }
+    }
+
+    #[track_caller]
+    // The types are only used for verifying the method.
+    #[cfg_attr(
+        any(not(debug_assertions), feature = "unstable-objfw"),
+        allow(clippy::extra_unused_type_parameters)
+    )]
+    fn lookup<A: EncodeArguments, R: EncodeReturn>(&self, cls: &AnyClass) -> Option<Imp> {
+        #[cfg(not(feature = "unstable-objfw"))]
+        {
+            let method = cls.instance_method(self.sel)?;
+            #[cfg(debug_assertions)]
+            msg_send_check_class(cls, self.sel, A::ENCODINGS, &R::ENCODING_RETURN);
+            Some(method.implementation())
+        }
+        #[cfg(feature = "unstable-objfw")]
+        {
+            let _ = cls;
+            None
+        }
+    }
+}
+
 // Note that we implement MessageReceiver for unsized types as well, this is
 // to support `extern type`s in the future, not because we want to allow DSTs.
 
@@ -509,6 +787,59 @@ unsafe impl<'a, T: ?Sized + Message + IsAllowedMutable> MessageReceiver for &'a
     }
 }
 
+impl<'a, T: ?Sized + Message> private::Sealed for &'a Id<T> {}
+unsafe impl<'a, T: ?Sized + Message> MessageReceiver for &'a Id<T> {
+    type __Inner = T;
+
+    #[inline]
+    fn __as_raw_receiver(self) -> *mut AnyObject {
+        (Id::as_ptr(self) as *mut T).cast()
+    }
+}
+
+impl<'a, T: ?Sized + Message + IsMutable> private::Sealed for &'a mut Id<T> {}
+unsafe impl<'a, T: ?Sized + Message + IsMutable> MessageReceiver for &'a mut Id<T> {
+    type __Inner = T;
+
+    #[inline]
+    fn __as_raw_receiver(self) -> *mut AnyObject {
+        Id::as_mut_ptr(self).cast()
+    }
+}
+
+impl<T: ?Sized + Message> private::Sealed for ManuallyDrop<Id<T>> {}
+unsafe impl<T: ?Sized + Message> MessageReceiver for ManuallyDrop<Id<T>> {
+    type __Inner = T;
+
+    #[inline]
+    fn __as_raw_receiver(self) -> *mut AnyObject {
+        Id::into_raw(ManuallyDrop::into_inner(self)).cast()
+    }
+}
+
+impl<'a, T: ?Sized + Message> private::Sealed for Pin<&'a T> {}
+unsafe impl<'a, T: ?Sized + Message> MessageReceiver for Pin<&'a T> {
+    type __Inner = T;
+
+    #[inline]
+    fn __as_raw_receiver(self) -> *mut AnyObject {
+        Pin::get_ref(self).__as_raw_receiver()
+    }
+}
+
+impl<'a, T: ?Sized + Message + IsAllowedMutable> private::Sealed for Pin<&'a mut T> {}
+unsafe impl<'a, T: ?Sized + Message + IsAllowedMutable> MessageReceiver for Pin<&'a mut T> {
+    type __Inner = T;
+
+    #[inline]
+    fn __as_raw_receiver(self) -> *mut AnyObject {
+        // SAFETY: The pointer is only passed to the runtime, which never
+        // moves objects.
+        let ptr: *mut T = unsafe { Pin::get_unchecked_mut(self) };
+        ptr.cast()
+    }
+}
+
 impl private::Sealed for *const AnyClass {}
 unsafe impl MessageReceiver for *const AnyClass {
     type __Inner = AnyClass;
@@ -531,6 +862,7 @@ unsafe impl<'a> MessageReceiver for &'a AnyClass {
 }
 
 #[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
 mod tests {
     use core::ptr;
 
@@ -539,7 +871,7 @@ mod tests {
     use crate::rc::{Allocated, Id};
     use crate::runtime::NSObject;
     use crate::test_utils;
-    use crate::{declare_class, msg_send, msg_send_id, ClassType, DeclaredClass};
+    use crate::{declare_class, msg_send, msg_send_id, sel, send_batch, ClassType, DeclaredClass};
 
     declare_class!(
         struct MutableObject;
@@ -581,6 +913,44 @@ mod tests {
         assert_eq!(result, 4);
     }
 
+    #[test]
+    fn test_wrapper_receivers() {
+        let mut obj = test_utils::custom_object();
+        unsafe {
+            let _: () = (&mut obj).send_message(sel!(setFoo:), (4u32,));
+            let foo: u32 = (&obj).send_message(sel!(foo), ());
+            assert_eq!(foo, 4);
+
+            // Objects are `!Unpin`, but they are never moved anyhow.
+            let _: () = msg_send![Pin::new_unchecked(&mut *obj), setFoo: 5u32];
+            let foo: u32 = msg_send![Pin::new_unchecked(&*obj), foo];
+            assert_eq!(foo, 5);
+            let foo: u32 = Pin::new_unchecked(&*obj).send_message(sel!(foo), ());
+            assert_eq!(foo, 5);
+        }
+
+        let obj = ManuallyDrop::new(obj);
+        let _: () = unsafe { obj.send_message(sel!(release), ()) };
+    }
+
+    #[test]
+    fn test_batch_sender() {
+        let mut objects = [
+            test_utils::custom_object(),
+            test_utils::custom_object(),
+            test_utils::custom_subclass_object(),
+            test_utils::custom_object(),
+        ];
+        unsafe { send_batch![&mut objects, setFoo: 4u32] };
+
+        let mut sender = BatchSender::new(sel!(foo));
+        assert_eq!(sender.sel(), sel!(foo));
+        for (obj, expected) in objects.iter().zip([4u32, 4, 6, 4]) {
+            let foo: u32 = unsafe { sender.send(&**obj, ()) };
+            assert_eq!(foo, expected);
+        }
+    }
+
     #[test]
     fn test_send_message_stret() {
         let obj = test_utils::custom_object();
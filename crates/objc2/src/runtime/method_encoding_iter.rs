@@ -28,18 +28,22 @@ impl<'a> MethodEncodingIter<'a> {
     pub(crate) fn verify_receiver(&mut self) -> Result<(), EncodingParseError> {
         // TODO: Verify stack layout
         let (enc, _stack_layout) = self.next().ok_or(EncodingParseError::MissingReceiver)??;
-        if !Encoding::Object.equivalent_to_box(&enc) {
-            return Err(EncodingParseError::InvalidReceiver(enc));
+        verify_receiver(&enc)
+    }
+
+    /// Verify that the first argument is the block itself, as in the
+    /// signature of a block.
+    pub(crate) fn verify_block(&mut self) -> Result<(), EncodingParseError> {
+        let (enc, _stack_layout) = self.next().ok_or(EncodingParseError::MissingBlock)??;
+        if !Encoding::Block.equivalent_to_box(&enc) {
+            return Err(EncodingParseError::InvalidBlock(enc));
         }
         Ok(())
     }
 
     pub(crate) fn verify_sel(&mut self) -> Result<(), EncodingParseError> {
         let (enc, _stack_layout) = self.next().ok_or(EncodingParseError::MissingSel)??;
-        if !Encoding::Sel.equivalent_to_box(&enc) {
-            return Err(EncodingParseError::InvalidSel(enc));
-        }
-        Ok(())
+        verify_sel(&enc)
     }
 
     fn extract_encoding(&mut self) -> Result<(EncodingBox, Option<isize>), EncodingParseError> {
@@ -66,6 +70,20 @@ impl<'a> Iterator for MethodEncodingIter<'a> {
     }
 }
 
+pub(crate) fn verify_receiver(enc: &EncodingBox) -> Result<(), EncodingParseError> {
+    if !Encoding::Object.equivalent_to_box(enc) {
+        return Err(EncodingParseError::InvalidReceiver(enc.clone()));
+    }
+    Ok(())
+}
+
+pub(crate) fn verify_sel(enc: &EncodingBox) -> Result<(), EncodingParseError> {
+    if !Encoding::Sel.equivalent_to_box(enc) {
+        return Err(EncodingParseError::InvalidSel(enc.clone()));
+    }
+    Ok(())
+}
+
 // TODO: Is `isize` correct here?
 fn parse_stack_layout(s: &mut &str) -> Result<Option<isize>, ParseIntError> {
     let rest = s.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+');
@@ -85,8 +103,10 @@ pub(crate) enum EncodingParseError {
     MissingReturn,
     MissingReceiver,
     MissingSel,
+    MissingBlock,
     InvalidReceiver(EncodingBox),
     InvalidSel(EncodingBox),
+    InvalidBlock(EncodingBox),
 }
 
 impl From<ParseError> for EncodingParseError {
@@ -113,12 +133,16 @@ impl fmt::Display for EncodingParseError {
             Self::MissingReturn => write!(f, "return type must be present")?,
             Self::MissingReceiver => write!(f, "receiver type must be present")?,
             Self::MissingSel => write!(f, "selector type must be present")?,
+            Self::MissingBlock => write!(f, "block type must be present")?,
             Self::InvalidReceiver(enc) => {
                 write!(f, "receiver encoding must be '@', but it was '{enc}'")?;
             }
             Self::InvalidSel(enc) => {
                 write!(f, "selector encoding must be '@', but it was '{enc}'")?;
             }
+            Self::InvalidBlock(enc) => {
+                write!(f, "block encoding must be '@?', but it was '{enc}'")?;
+            }
         }
         write!(f, ". This is likely a bug, please report it!")
     }
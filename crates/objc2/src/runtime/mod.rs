@@ -14,6 +14,7 @@
 //! ```
 #![allow(clippy::missing_panics_doc)]
 
+#[cfg(not(feature = "unstable-objfw"))]
 use alloc::vec::Vec;
 use core::fmt;
 use core::hash;
@@ -30,6 +31,7 @@ use std::os::raw::c_uint;
 pub mod __nsstring;
 mod bool;
 mod declare;
+mod features;
 #[cfg(not(feature = "malloc"))]
 mod malloc;
 mod message_receiver;
@@ -39,13 +41,22 @@ mod nsobject;
 mod nsproxy;
 mod nszone;
 mod protocol_object;
+mod proxy;
 mod retain_release_fast;
+pub(crate) mod sel_table;
+mod subclass_of;
 
+pub(crate) use self::declare::RegistrationErrorKind;
+#[cfg(not(feature = "unstable-objfw"))]
+pub(crate) use self::method_encoding_iter::{verify_receiver, verify_sel};
 pub(crate) use self::method_encoding_iter::{EncodingParseError, MethodEncodingIter};
 pub(crate) use self::retain_release_fast::{objc_release_fast, objc_retain_fast};
-use crate::encode::{Encode, EncodeArguments, EncodeReturn, Encoding, OptionEncode, RefEncode};
+use crate::encode::{Encode, Encoding, OptionEncode, RefEncode};
+#[cfg(not(feature = "unstable-objfw"))]
+use crate::encode::{EncodeArguments, EncodeReturn};
+#[cfg(not(feature = "unstable-objfw"))]
 use crate::verify::{verify_method_signature, Inner};
-use crate::{ffi, Message};
+use crate::{ffi, ClassType, Message};
 
 // Note: While this is not public, it is still a breaking change to remove,
 // since `objc2-foundation` relies on it.
@@ -53,16 +64,24 @@ use crate::{ffi, Message};
 pub use self::nsproxy::NSProxy as __NSProxy;
 
 pub use self::bool::Bool;
-pub use self::declare::{ClassBuilder, ProtocolBuilder};
-pub use self::message_receiver::MessageReceiver;
+#[cfg(not(feature = "unstable-objfw"))]
+pub use self::declare::ProtocolBuilder;
+pub use self::declare::{ClassBuilder, ClassRegistrationError};
+pub use self::features::{features, RuntimeFeatures};
+pub use self::message_receiver::{BatchSender, MessageReceiver};
 pub use self::method_implementation::MethodImplementation;
 pub use self::nsobject::{NSObject, NSObjectProtocol};
 pub use self::nszone::NSZone;
 pub use self::protocol_object::{ImplementedBy, ProtocolObject};
+pub use self::proxy::Proxy;
+pub use self::sel_table::{SelKey, SelTable};
+pub use self::subclass_of::SubclassOf;
 pub use crate::verify::VerificationError;
 
 #[cfg(not(feature = "malloc"))]
-use self::malloc::{MallocSlice, MallocStr};
+use self::malloc::MallocSlice;
+#[cfg(all(not(feature = "malloc"), not(feature = "unstable-objfw")))]
+use self::malloc::MallocStr;
 #[cfg(feature = "malloc")]
 use malloc_buf::{Malloc as MallocSlice, Malloc as MallocStr};
 
@@ -86,6 +105,7 @@ macro_rules! MallocSlice {
 
 /// Same as `MallocSlice!`.
 #[cfg(not(feature = "malloc"))]
+#[cfg_attr(feature = "unstable-objfw", allow(unused_macros))]
 macro_rules! MallocStr {
     () => {
         impl std::ops::Deref<Target = str> + AsRef<str> + std::fmt::Debug + std::fmt::Display
@@ -150,6 +170,10 @@ type InnerImp = unsafe extern "C-unwind" fn();
 ///
 /// Also note that this is non-null! If you require an Imp that can be null,
 /// use `Option<Imp>`.
+///
+/// On `arm64e`, implementations given to and returned from the runtime are
+/// signed, see [`ffi::ptrauth_sign_function`] and
+/// [`ffi::ptrauth_auth_function`].
 pub type Imp = InnerImp;
 
 /// A method selector.
@@ -348,6 +372,7 @@ impl Ivar {
     /// Returns the instance variable's name.
     ///
     /// See [Apple's documentation](https://developer.apple.com/documentation/objectivec/1418922-ivar_getname?language=objc).
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "ivar_getName")]
     pub fn name(&self) -> &str {
         let name = unsafe { CStr::from_ptr(ffi::ivar_getName(self.as_ptr())) };
@@ -357,6 +382,7 @@ impl Ivar {
     /// Returns the instance variable's offset from the object base.
     ///
     /// See [Apple's documentation](https://developer.apple.com/documentation/objectivec/1418976-ivar_getoffset?language=objc).
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     #[doc(alias = "ivar_getOffset")]
     pub fn offset(&self) -> isize {
@@ -366,12 +392,14 @@ impl Ivar {
     /// Returns the instance variable's `@encode(type)` string.
     ///
     /// See [Apple's documentation](https://developer.apple.com/documentation/objectivec/1418569-ivar_gettypeencoding?language=objc).
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "ivar_getTypeEncoding")]
     pub fn type_encoding(&self) -> &str {
         let encoding = unsafe { CStr::from_ptr(ffi::ivar_getTypeEncoding(self.as_ptr())) };
         str::from_utf8(encoding.to_bytes()).unwrap()
     }
 
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     pub(crate) fn debug_assert_encoding(&self, _expected: &Encoding) {
         #[cfg(debug_assertions)]
@@ -421,6 +449,7 @@ impl Ivar {
     /// No thread syncronization is done on accesses to the variable, so you
     /// must ensure that any access to the returned pointer do not cause data
     /// races, and that Rust's mutability rules are not otherwise violated.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     pub unsafe fn load_ptr<T: Encode>(&self, obj: &AnyObject) -> *mut T {
         self.debug_assert_encoding(&T::ENCODING);
@@ -453,6 +482,7 @@ impl Ivar {
     /// thread is concurrently mutating the variable. This requirement can be
     /// considered upheld if all mutation happens through [`Ivar::load_mut`]
     /// (since that takes the object mutably).
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     pub unsafe fn load<'obj, T: Encode>(&self, obj: &'obj AnyObject) -> &'obj T {
         // SAFETY: That the ivar is valid as `&T` is ensured by the caller,
@@ -479,6 +509,7 @@ impl Ivar {
     /// This access happens through `&mut`, which means we know it to be the
     /// only reference, hence you do not need to do any work to ensure that
     /// data races do not happen.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     pub unsafe fn load_mut<'obj, T: Encode>(&self, obj: &'obj mut AnyObject) -> &'obj mut T {
         self.debug_assert_encoding(&T::ENCODING);
@@ -495,6 +526,7 @@ impl Ivar {
 
 standard_pointer_impls!(Ivar);
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl fmt::Debug for Ivar {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Ivar")
@@ -505,12 +537,22 @@ impl fmt::Debug for Ivar {
     }
 }
 
+// ObjFW does not provide introspection of ivars.
+#[cfg(feature = "unstable-objfw")]
+impl fmt::Debug for Ivar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ivar").finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(feature = "unstable-objfw"))]
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub(crate) struct MethodDescription {
     pub(crate) sel: Sel,
     pub(crate) types: &'static str,
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl MethodDescription {
     pub(crate) unsafe fn from_raw(raw: ffi::objc_method_description) -> Option<Self> {
         // SAFETY: Sel::from_ptr checks for NULL, rest is checked by caller.
@@ -547,12 +589,14 @@ impl Method {
 
     // Note: We don't take `&mut` here, since the operations on methods work
     // atomically.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     pub(crate) fn as_mut_ptr(&self) -> *mut ffi::objc_method {
         self.as_ptr() as _
     }
 
     /// Returns the name of self.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     #[doc(alias = "method_getName")]
     pub fn name(&self) -> Sel {
@@ -560,6 +604,7 @@ impl Method {
     }
 
     /// Returns the `Encoding` of self's return type.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "method_copyReturnType")]
     pub fn return_type(&self) -> MallocStr!() {
         unsafe {
@@ -570,6 +615,7 @@ impl Method {
 
     /// Returns the `Encoding` of a single parameter type of self, or
     /// [`None`] if self has no parameter at the given index.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "method_copyArgumentType")]
     pub fn argument_type(&self, index: usize) -> Option<MallocStr!()> {
         unsafe {
@@ -590,8 +636,15 @@ impl Method {
     /// }
     /// assert!(types.next().is_none());
     /// ```
-    #[doc(alias = "method_getTypeEncoding")]
+    #[cfg(not(feature = "unstable-objfw"))]
     pub(crate) fn types(&self) -> MethodEncodingIter<'_> {
+        MethodEncodingIter::new(self.type_encoding())
+    }
+
+    /// The full type encoding of the method, including stack offsets.
+    #[cfg(not(feature = "unstable-objfw"))]
+    #[doc(alias = "method_getTypeEncoding")]
+    pub(crate) fn type_encoding(&self) -> &str {
         // SAFETY: The method pointer is valid and non-null
         let cstr = unsafe { ffi::method_getTypeEncoding(self.as_ptr()) };
         if cstr.is_null() {
@@ -600,11 +653,11 @@ impl Method {
         // SAFETY: `method_getTypeEncoding` returns a C-string, and we just
         // checked that it is non-null.
         let encoding = unsafe { CStr::from_ptr(cstr) };
-        let s = str::from_utf8(encoding.to_bytes()).expect("method type encoding to be UTF-8");
-        MethodEncodingIter::new(s)
+        str::from_utf8(encoding.to_bytes()).expect("method type encoding to be UTF-8")
     }
 
     /// Returns the number of arguments accepted by self.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     #[doc(alias = "method_getNumberOfArguments")]
     pub fn arguments_count(&self) -> usize {
@@ -612,6 +665,11 @@ impl Method {
     }
 
     /// Returns the implementation of this method.
+    ///
+    /// On `arm64e`, the returned pointer is signed, and must be
+    /// authenticated with [`ffi::ptrauth_auth_function`] before being
+    /// called from Rust.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "method_getImplementation")]
     pub fn implementation(&self) -> Imp {
         unsafe { ffi::method_getImplementation(self.as_ptr()).expect("null IMP") }
@@ -643,6 +701,12 @@ impl Method {
     ///
     ///    A common mistake would be expecting e.g. a pointer to not be null,
     ///    where the null case was handled before.
+    ///
+    /// 3. Be signed on `arm64e`. Function pointers created in Rust must be
+    ///    signed with [`ffi::ptrauth_sign_function`] with a discriminator of
+    ///    `0`, whereas pointers returned from [`Self::implementation`] are
+    ///    already signed.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "method_setImplementation")]
     pub unsafe fn set_implementation(&self, imp: Imp) -> Imp {
         // SAFETY: The new impl is not NULL, and the rest is upheld by the
@@ -684,6 +748,7 @@ impl Method {
     ///     m1.set_implementation(imp);
     /// }
     /// ```
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     #[doc(alias = "method_exchangeImplementations")]
     pub unsafe fn exchange_implementation(&self, other: &Self) {
@@ -697,6 +762,7 @@ impl Method {
 
 standard_pointer_impls!(Method);
 
+#[cfg(not(feature = "unstable-objfw"))]
 impl fmt::Debug for Method {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Method")
@@ -707,6 +773,14 @@ impl fmt::Debug for Method {
     }
 }
 
+// ObjFW does not provide introspection of methods.
+#[cfg(feature = "unstable-objfw")]
+impl fmt::Debug for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Method").finish_non_exhaustive()
+    }
+}
+
 /// A type that represents an Objective-C class.
 ///
 /// This is an opaque type meant to be used behind a shared reference
@@ -748,6 +822,52 @@ impl AnyClass {
         unsafe { cls.cast::<Self>().as_ref() }
     }
 
+    /// Returns the class with the specified name, if it is registered with
+    /// the Objective-C runtime and is either `T`'s class or a subclass of it.
+    ///
+    /// This is the checked counterpart to `NSClassFromString`, and is useful
+    /// when the class name comes from configuration (e.g. a plist), and you
+    /// need the class to be usable as `T` afterwards. The returned
+    /// [`SubclassOf`] allocates instances typed as `T`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2::rc::Id;
+    /// use objc2::runtime::{AnyClass, NSObject};
+    /// use objc2::msg_send_id;
+    ///
+    /// let cls = AnyClass::get_checked::<NSObject>("NSObject").unwrap();
+    /// let obj: Id<NSObject> = unsafe { msg_send_id![cls.alloc(), init] };
+    ///
+    /// // Classes that do not exist, or are not subclasses, are rejected.
+    /// assert!(AnyClass::get_checked::<NSObject>("NonExistantClass").is_none());
+    /// ```
+    #[doc(alias = "NSClassFromString")]
+    pub fn get_checked<T: ClassType + ?Sized>(name: &str) -> Option<SubclassOf<T>> {
+        SubclassOf::new(Self::get(name)?)
+    }
+
+    /// Returns the class with the specified name, ignoring the suffix added
+    /// by [`unique_class_name!`], or [`None`] if no such class is registered
+    /// with the Objective-C runtime.
+    ///
+    /// A class named exactly `name` is preferred. Otherwise, if multiple
+    /// versions of a crate have registered a class with the name, one of
+    /// them is returned; use [`ClassType::class`] on the specific type if you
+    /// need a specific one.
+    ///
+    /// [`unique_class_name!`]: crate::unique_class_name
+    pub fn get_unmangled(name: &str) -> Option<&'static Self> {
+        Self::get(name).or_else(|| {
+            Self::classes()
+                .iter()
+                .copied()
+                .find(|cls| cls.unmangled_name() == name)
+        })
+    }
+
     // Same as `get`, but ...
     // fn lookup(name: &str) -> Option<&'static Self>;
 
@@ -793,6 +913,16 @@ impl AnyClass {
         unsafe { Self::name_raw(self.as_ptr()) }
     }
 
+    /// Returns the name of the class, without the suffix added by
+    /// [`unique_class_name!`].
+    ///
+    /// For other classes, this is the same as [`name`](Self::name).
+    ///
+    /// [`unique_class_name!`]: crate::unique_class_name
+    pub fn unmangled_name(&self) -> &str {
+        crate::__macro_helpers::unique_class_name::unmangle(self.name())
+    }
+
     /// # Safety
     ///
     /// 1. The class pointer must be valid.
@@ -814,6 +944,34 @@ impl AnyClass {
         unsafe { Self::superclass_raw(self.as_ptr()) }
     }
 
+    /// Whether self is the given class, or a subclass of it.
+    ///
+    /// This walks the superclass chain in the runtime, and does not send the
+    /// `isSubclassOfClass:` message.
+    #[doc(alias = "isSubclassOfClass:")]
+    pub fn is_subclass_of(&self, cls: &AnyClass) -> bool {
+        let mut current = Some(self);
+        while let Some(c) = current {
+            if c == cls {
+                return true;
+            }
+            current = c.superclass();
+        }
+        false
+    }
+
+    /// Whether the class' root class is `NSProxy`.
+    ///
+    /// Done by name, to avoid having to look up the `NSProxy` class.
+    #[cfg(all(debug_assertions, not(feature = "unstable-objfw")))]
+    pub(crate) fn is_proxy(&self) -> bool {
+        let mut root = self;
+        while let Some(superclass) = root.superclass() {
+            root = superclass;
+        }
+        root.name() == "NSProxy"
+    }
+
     /// Returns the metaclass of self.
     #[inline]
     #[doc(alias = "object_getClass")]
@@ -839,6 +997,7 @@ impl AnyClass {
     /// Returns a specified instance method for self, or [`None`] if self and
     /// its superclasses do not contain an instance method with the specified
     /// selector.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     #[doc(alias = "class_getInstanceMethod")]
     pub fn instance_method(&self, sel: Sel) -> Option<&Method> {
@@ -853,6 +1012,7 @@ impl AnyClass {
     /// selector.
     ///
     /// Same as `cls.metaclass().class_method()`.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[inline]
     #[doc(alias = "class_getClassMethod")]
     pub fn class_method(&self, sel: Sel) -> Option<&Method> {
@@ -870,6 +1030,7 @@ impl AnyClass {
     ///
     /// Attempting to access or modify instance variables of a class that you
     /// do no control may invoke undefined behaviour.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "class_getInstanceVariable")]
     pub fn instance_variable(&self, name: &str) -> Option<&Ivar> {
         let name = CString::new(name).unwrap();
@@ -879,6 +1040,7 @@ impl AnyClass {
         }
     }
 
+    #[cfg(not(feature = "unstable-objfw"))]
     #[allow(unused)]
     #[doc(alias = "class_getClassVariable")]
     fn class_variable(&self, name: &str) -> Option<&Ivar> {
@@ -889,6 +1051,7 @@ impl AnyClass {
     }
 
     /// Describes the instance methods implemented by self.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "class_copyMethodList")]
     pub fn instance_methods(&self) -> MallocSlice!(&Method) {
         unsafe {
@@ -908,6 +1071,7 @@ impl AnyClass {
     }
 
     /// Get a list of the protocols to which this class conforms.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "class_copyProtocolList")]
     pub fn adopted_protocols(&self) -> MallocSlice!(&AnyProtocol) {
         unsafe {
@@ -919,6 +1083,7 @@ impl AnyClass {
     }
 
     /// Describes the instance variables declared by self.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "class_copyIvarList")]
     pub fn instance_variables(&self) -> MallocSlice!(&Ivar) {
         unsafe {
@@ -978,6 +1143,7 @@ impl AnyClass {
     /// let result = cls.verify_sel::<(&AnyClass,), Bool>(sel);
     /// assert!(result.is_ok());
     /// ```
+    #[cfg(not(feature = "unstable-objfw"))]
     #[allow(clippy::missing_errors_doc)] // Written differently in the docs
     pub fn verify_sel<A, R>(&self, sel: Sel) -> Result<(), VerificationError>
     where
@@ -1034,6 +1200,7 @@ impl AnyProtocol {
 
     /// Returns the protocol definition of a specified protocol, or [`None`]
     /// if the protocol is not registered with the Objective-C runtime.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "objc_getProtocol")]
     pub fn get(name: &str) -> Option<&'static Self> {
         let name = CString::new(name).unwrap();
@@ -1044,6 +1211,7 @@ impl AnyProtocol {
     }
 
     /// Obtains the list of registered protocol definitions.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "objc_copyProtocolList")]
     pub fn protocols() -> MallocSlice!(&'static Self) {
         unsafe {
@@ -1054,6 +1222,7 @@ impl AnyProtocol {
     }
 
     /// Get a list of the protocols to which this protocol conforms.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[doc(alias = "protocol_copyProtocolList")]
     pub fn adopted_protocols(&self) -> MallocSlice!(&AnyProtocol) {
         unsafe {
@@ -1084,6 +1253,7 @@ impl AnyProtocol {
         str::from_utf8(name.to_bytes()).unwrap()
     }
 
+    #[cfg(not(feature = "unstable-objfw"))]
     fn method_descriptions_inner(&self, required: bool, instance: bool) -> Vec<MethodDescription> {
         let mut count: c_uint = 0;
         let descriptions = unsafe {
@@ -1106,12 +1276,14 @@ impl AnyProtocol {
             .collect()
     }
 
+    #[cfg(not(feature = "unstable-objfw"))]
     #[allow(dead_code)]
     #[doc(alias = "protocol_copyMethodDescriptionList")]
     pub(crate) fn method_descriptions(&self, required: bool) -> Vec<MethodDescription> {
         self.method_descriptions_inner(required, true)
     }
 
+    #[cfg(not(feature = "unstable-objfw"))]
     #[allow(dead_code)]
     #[doc(alias = "protocol_copyMethodDescriptionList")]
     pub(crate) fn class_method_descriptions(&self, required: bool) -> Vec<MethodDescription> {
@@ -1285,6 +1457,7 @@ impl AnyObject {
         ptr
     }
 
+    #[cfg(not(feature = "unstable-objfw"))]
     pub(crate) fn lookup_instance_variable_dynamically(&self, name: &str) -> &'static Ivar {
         let cls = self.class();
         cls.instance_variable(name)
@@ -1300,6 +1473,7 @@ impl AnyObject {
     /// must be of type `T`.
     ///
     /// See [`Ivar::load_ptr`] for details surrounding this.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[deprecated = "this is difficult to use correctly, use `Ivar::load` instead."]
     pub unsafe fn get_ivar<T: Encode>(&self, name: &str) -> &T {
         let ivar = self.lookup_instance_variable_dynamically(name);
@@ -1316,6 +1490,7 @@ impl AnyObject {
     /// must be of type `T`.
     ///
     /// See [`Ivar::load_ptr`] for details surrounding this.
+    #[cfg(not(feature = "unstable-objfw"))]
     #[deprecated = "this is difficult to use correctly, use `Ivar::load_mut` instead."]
     pub unsafe fn get_mut_ivar<T: Encode>(&mut self, name: &str) -> &mut T {
         let ivar = self.lookup_instance_variable_dynamically(name);
@@ -1335,6 +1510,7 @@ impl fmt::Debug for AnyObject {
 }
 
 #[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
 mod tests {
     use alloc::format;
     use alloc::string::ToString;
@@ -1342,7 +1518,7 @@ mod tests {
 
     use super::*;
     use crate::test_utils;
-    use crate::{class, msg_send, sel, ClassType};
+    use crate::{class, msg_send, sel};
 
     #[test]
     fn test_selector() {
@@ -1450,6 +1626,65 @@ mod tests {
 
         let subclass = test_utils::custom_subclass();
         assert_eq!(subclass.superclass().unwrap(), cls);
+        assert!(subclass.is_subclass_of(cls));
+        assert!(cls.is_subclass_of(cls));
+        assert!(!cls.is_subclass_of(subclass));
+    }
+
+    #[test]
+    fn test_get_checked() {
+        use crate::msg_send_id;
+        use crate::rc::Id;
+
+        // Classes are registered lazily, so make sure it is available.
+        let _ = crate::rc::__RcTestObject::class();
+
+        let cls = AnyClass::get_checked::<NSObject>("NSObject").unwrap();
+        assert_eq!(cls.as_class(), NSObject::class());
+        let cls = AnyClass::get_checked::<NSObject>("__RcTestObject").unwrap();
+        assert_eq!(cls.as_class(), crate::rc::__RcTestObject::class());
+        assert_eq!(cls.name(), "__RcTestObject");
+        assert!(AnyClass::get_checked::<crate::rc::__RcTestObject>("NSObject").is_none());
+        assert!(AnyClass::get_checked::<NSObject>("NonExistantClass").is_none());
+
+        // Allocating with a subclass gives an instance of it, typed as the
+        // superclass.
+        let obj: Id<NSObject> = unsafe { msg_send_id![cls.alloc(), init] };
+        assert_eq!(obj.class(), crate::rc::__RcTestObject::class());
+    }
+
+    #[test]
+    fn test_get_unmangled() {
+        use crate::{declare_class, mutability, unique_class_name, DeclaredClass};
+
+        declare_class!(
+            struct UniqueNameTest;
+
+            unsafe impl ClassType for UniqueNameTest {
+                type Super = NSObject;
+                type Mutability = mutability::InteriorMutable;
+                const NAME: &'static str = unique_class_name!("__objc2UniqueNameTest");
+            }
+
+            impl DeclaredClass for UniqueNameTest {}
+        );
+
+        let name = UniqueNameTest::NAME;
+        assert!(
+            name.starts_with("__objc2UniqueNameTest__objc2_objc2_"),
+            "{name}"
+        );
+
+        let cls = UniqueNameTest::class();
+        assert_eq!(cls.name(), name);
+        assert_eq!(cls.unmangled_name(), "__objc2UniqueNameTest");
+        assert_eq!(AnyClass::get("__objc2UniqueNameTest"), None);
+        assert_eq!(AnyClass::get_unmangled("__objc2UniqueNameTest"), Some(cls));
+        assert_eq!(AnyClass::get_unmangled(name), Some(cls));
+
+        assert_eq!(NSObject::class().unmangled_name(), "NSObject");
+        assert_eq!(AnyClass::get_unmangled("NSObject"), Some(NSObject::class()));
+        assert_eq!(AnyClass::get_unmangled("NonExistantClass"), None);
     }
 
     #[test]
@@ -46,7 +46,9 @@ unsafe impl ClassType for NSObject {
 
     #[inline]
     fn class() -> &'static AnyClass {
-        #[cfg(feature = "apple")]
+        // ObjFW does not provide these classes itself, so they are looked up
+        // dynamically.
+        #[cfg(any(feature = "apple", feature = "unstable-objfw"))]
         {
             crate::__class_inner!("NSObject", "NSObject")
         }
@@ -451,6 +453,16 @@ impl Eq for NSObject {}
 /// > they must have the same hash value.
 ///
 /// See <https://developer.apple.com/documentation/objectivec/1418956-nsobject/1418859-hash>
+///
+/// Note that the hash of an object may change if it is mutated, which means
+/// that objects that can be mutated through a shared reference (that is,
+/// classes whose mutability is `InteriorMutable`) should be used with care
+/// as keys in `HashMap` and `HashSet`. The hash is also not guaranteed to be
+/// stable across processes or OS versions.
+///
+/// See also [`HashedId`] for caching the hash.
+///
+/// [`HashedId`]: crate::rc::HashedId
 impl hash::Hash for NSObject {
     #[inline]
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
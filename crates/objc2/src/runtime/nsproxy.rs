@@ -37,7 +37,9 @@ unsafe impl ClassType for NSProxy {
 
     #[inline]
     fn class() -> &'static AnyClass {
-        #[cfg(feature = "apple")]
+        // ObjFW does not provide these classes itself, so they are looked up
+        // dynamically.
+        #[cfg(any(feature = "apple", feature = "unstable-objfw"))]
         {
             crate::__class_inner!("NSProxy", "NSProxy")
         }
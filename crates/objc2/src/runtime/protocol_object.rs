@@ -219,6 +219,10 @@ mod tests {
 
             #[method(bar)]
             fn bar_instance(&self);
+
+            fn has_same_hash(&self, other: &Self) -> bool {
+                self.hash() == other.hash()
+            }
         }
 
         unsafe impl ProtocolType for dyn Bar {}
@@ -384,6 +388,19 @@ mod tests {
         assert_eq!(hashstate_a.finish(), hashstate_b.finish());
     }
 
+    #[test]
+    fn provided_methods() {
+        let obj = DummyClass::new();
+        let obj2 = DummyClass::new();
+        assert!(obj.has_same_hash(&obj));
+        assert!(!obj.has_same_hash(&obj2));
+
+        let bar: &ProtocolObject<dyn Bar> = ProtocolObject::from_ref(&*obj);
+        let bar2: &ProtocolObject<dyn Bar> = ProtocolObject::from_ref(&*obj2);
+        assert!(bar.has_same_hash(bar));
+        assert!(!bar.has_same_hash(bar2));
+    }
+
     // We use `debug_assertions` here just because it's something that we know
     // our CI already tests.
     extern_protocol!(
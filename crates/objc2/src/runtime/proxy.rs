@@ -0,0 +1,170 @@
+use alloc::boxed::Box;
+
+use crate::mutability::InteriorMutable;
+use crate::rc::Id;
+use crate::runtime::__NSProxy as NSProxy;
+use crate::runtime::{AnyObject, Sel};
+use crate::{declare_class, msg_send, ClassType, DeclaredClass, Message};
+
+type Interceptor = Box<dyn Fn(&AnyObject, Sel)>;
+
+declare_class!(
+    /// A proxy that forwards all messages to a target object, after first
+    /// handing them to an interceptor closure.
+    ///
+    /// This is a simple `NSProxy` subclass, useful for e.g. logging or
+    /// counting the messages sent to an object, for lazily loading the real
+    /// object, or for testing.
+    ///
+    /// Messages that `NSProxy` itself implements (such as `-retain`,
+    /// `-class`, `-description` and `-isEqual:`) are not forwarded. Messages
+    /// that are forwarded are delivered using the runtime's fast forwarding
+    /// path (`-forwardingTargetForSelector:`), so the interceptor can observe
+    /// which selector is sent, but not its arguments or return value.
+    ///
+    /// If you need more control than this, you can subclass `NSProxy`
+    /// yourself using [`declare_class!`].
+    ///
+    /// [`declare_class!`]: crate::declare_class
+    ///
+    ///
+    /// # Example
+    ///
+    /// Count the number of messages sent to an object.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// use objc2::rc::Id;
+    /// use objc2::runtime::{NSObject, Proxy};
+    /// use objc2::{declare_class, msg_send, msg_send_id, mutability, ClassType, DeclaredClass};
+    ///
+    /// declare_class!(
+    ///     struct Target;
+    ///
+    ///     unsafe impl ClassType for Target {
+    ///         type Super = NSObject;
+    ///         type Mutability = mutability::InteriorMutable;
+    ///         const NAME: &'static str = "ProxyExampleTarget";
+    ///     }
+    ///
+    ///     impl DeclaredClass for Target {}
+    ///
+    ///     unsafe impl Target {
+    ///         #[method(value)]
+    ///         fn value(&self) -> u32 {
+    ///             42
+    ///         }
+    ///     }
+    /// );
+    ///
+    /// let target: Id<Target> = unsafe { msg_send_id![Target::class(), new] };
+    ///
+    /// let count = Rc::new(Cell::new(0));
+    /// let proxy = Proxy::wrap(target, {
+    ///     let count = Rc::clone(&count);
+    ///     move |_target, _sel| count.set(count.get() + 1)
+    /// });
+    ///
+    /// // `NSProxy` does not implement `-value`, so this is forwarded
+    /// let value: u32 = unsafe { msg_send![&proxy, value] };
+    /// assert_eq!(value, 42);
+    /// assert_eq!(count.get(), 1);
+    /// ```
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    pub struct Proxy;
+
+    unsafe impl ClassType for Proxy {
+        type Super = NSProxy;
+        type Mutability = InteriorMutable;
+        const NAME: &'static str = "__Objc2Proxy";
+    }
+
+    impl DeclaredClass for Proxy {
+        type Ivars = (Id<AnyObject>, Interceptor);
+    }
+
+    unsafe impl Proxy {
+        #[method(forwardingTargetForSelector:)]
+        fn __forwarding_target(&self, sel: Sel) -> *mut AnyObject {
+            let (target, interceptor) = self.ivars();
+            interceptor(target, sel);
+            Id::as_ptr(target) as *mut AnyObject
+        }
+
+        #[method(respondsToSelector:)]
+        fn __responds_to_selector(&self, sel: Sel) -> bool {
+            let (target, _) = self.ivars();
+            unsafe { msg_send![&**target, respondsToSelector: sel] }
+        }
+    }
+);
+
+impl Proxy {
+    /// Create a new proxy that forwards messages to `target`, calling
+    /// `interceptor` with the target and the selector before each forwarded
+    /// message.
+    ///
+    /// To send messages to the proxy as-if it were the target, you may use
+    /// [`Id::cast`].
+    pub fn wrap<T: Message>(
+        target: Id<T>,
+        interceptor: impl Fn(&AnyObject, Sel) + 'static,
+    ) -> Id<Self> {
+        // SAFETY: All objects can be safely viewed as `AnyObject`.
+        let target = unsafe { Id::cast::<AnyObject>(target) };
+        let this = Self::alloc().set_ivars((target, Box::new(interceptor)));
+        this.init_proxy().expect("failed allocating proxy")
+    }
+
+    /// The object that messages are forwarded to.
+    pub fn target(&self) -> &AnyObject {
+        &self.ivars().0
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
+mod tests {
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::runtime::NSObject;
+    use crate::{sel, test_utils};
+
+    #[test]
+    #[cfg_attr(
+        feature = "unstable-mock-runtime",
+        ignore = "the mock runtime does not support message forwarding"
+    )]
+    fn forwards_to_target() {
+        let mut obj = test_utils::custom_object();
+        let _: () = unsafe { msg_send![&mut obj, setFoo: 13u32] };
+
+        let sels = Rc::new(RefCell::new(Vec::new()));
+        let proxy = Proxy::wrap(obj, {
+            let sels = Rc::clone(&sels);
+            move |_target, sel| sels.borrow_mut().push(sel)
+        });
+
+        let result: u32 = unsafe { msg_send![&proxy, foo] };
+        assert_eq!(result, 13);
+        assert_eq!(*sels.borrow(), [sel!(foo)]);
+    }
+
+    #[test]
+    fn target() {
+        let obj = NSObject::new();
+        let ptr: *const NSObject = &*obj;
+        let proxy = Proxy::wrap(obj, |_, _| {});
+        assert_eq!(proxy.target() as *const AnyObject, ptr.cast());
+
+        let responds: bool = unsafe { msg_send![&proxy, respondsToSelector: sel!(hash)] };
+        assert!(responds);
+        let responds: bool = unsafe { msg_send![&proxy, respondsToSelector: sel!(foo)] };
+        assert!(!responds);
+    }
+}
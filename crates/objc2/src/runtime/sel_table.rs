@@ -0,0 +1,577 @@
+use core::fmt;
+use core::hash;
+use std::ffi::CStr;
+
+use super::Sel;
+use crate::ffi;
+
+/// Hash the bytes of a selector name with 64-bit FNV-1a.
+///
+/// Ignores a trailing NUL byte, so that it can be used on the data from
+/// `sel!`-style macros.
+const fn fnv1a(bytes: &[u8], len: usize) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < len {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// A final mixing step, to spread the bits of the seeded hash.
+const fn mix(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    x ^= x >> 33;
+    x
+}
+
+const fn name_len(bytes: &[u8]) -> usize {
+    if !bytes.is_empty() && bytes[bytes.len() - 1] == 0 {
+        bytes.len() - 1
+    } else {
+        bytes.len()
+    }
+}
+
+/// A selector name that can be constructed in `const` contexts, for use as
+/// a key in static lookup tables.
+///
+/// [`Sel`] itself is the result of registering a name with the runtime, so
+/// it can only be created at runtime, and its pointer value (and thus its
+/// [`Hash`] implementation on Apple platforms) is only stable within the
+/// current process. `SelKey` instead stores the name along with a hash of
+/// it that is computed at compile time, and can be compared against a `Sel`
+/// without allocating.
+///
+/// See [`SelTable`] for building a table of these.
+///
+/// [`Hash`]: core::hash::Hash
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::runtime::SelKey;
+/// use objc2::sel;
+///
+/// const KEY: SelKey = SelKey::new("setValue:forKey:");
+///
+/// assert_eq!(KEY.name(), "setValue:forKey:");
+/// assert!(KEY.matches(sel!(setValue:forKey:)));
+/// assert!(!KEY.matches(sel!(setValue:)));
+/// ```
+#[derive(Clone, Copy)]
+pub struct SelKey {
+    // May contain a trailing NUL byte.
+    data: &'static str,
+    len: usize,
+    hash: u64,
+}
+
+impl SelKey {
+    /// Create a key from the name of a selector.
+    ///
+    /// The name is not validated, but keys with names that are not valid
+    /// selectors will never match any selector.
+    #[inline]
+    pub const fn new(name: &'static str) -> Self {
+        let len = name_len(name.as_bytes());
+        Self {
+            data: name,
+            len,
+            hash: fnv1a(name.as_bytes(), len),
+        }
+    }
+
+    /// The name of the selector.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        &self.data[..self.len]
+    }
+
+    /// The hash of the selector name.
+    ///
+    /// This is stable across processes and compilations.
+    #[inline]
+    pub const fn name_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Register the selector with the runtime.
+    ///
+    /// Prefer [`sel!`] when the selector is known statically.
+    ///
+    /// [`sel!`]: crate::sel
+    pub fn sel(&self) -> Sel {
+        let data = self.data.as_bytes();
+        if data.len() != self.len {
+            // SAFETY: The data is NUL-terminated.
+            unsafe { Sel::register_unchecked(data.as_ptr().cast()) }
+        } else {
+            Sel::register(self.name())
+        }
+    }
+
+    /// Whether the key has the same name as the given selector.
+    #[inline]
+    pub fn matches(&self, sel: Sel) -> bool {
+        self.name().as_bytes() == sel_bytes(sel)
+    }
+
+    const fn bytes_eq(&self, other: &Self) -> bool {
+        if self.len != other.len || self.hash != other.hash {
+            return false;
+        }
+        let a = self.data.as_bytes();
+        let b = other.data.as_bytes();
+        let mut i = 0;
+        while i < self.len {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+}
+
+/// The name of the selector, without the UTF-8 check that `Sel::name` does.
+#[inline]
+fn sel_bytes(sel: Sel) -> &'static [u8] {
+    // SAFETY: Input is non-null selector. The returned string is a valid
+    // C-string that lives as long as the selector, which is forever.
+    unsafe { CStr::from_ptr(ffi::sel_getName(sel.as_ptr())) }.to_bytes()
+}
+
+impl PartialEq for SelKey {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes_eq(other)
+    }
+}
+
+impl Eq for SelKey {}
+
+impl hash::Hash for SelKey {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl PartialEq<Sel> for SelKey {
+    #[inline]
+    fn eq(&self, other: &Sel) -> bool {
+        self.matches(*other)
+    }
+}
+
+impl fmt::Debug for SelKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SelKey").field(&self.name()).finish()
+    }
+}
+
+impl fmt::Display for SelKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.name(), f)
+    }
+}
+
+const EMPTY: u32 = u32::MAX;
+
+/// The bucket and the two slot hashes of a key, for a given seed.
+#[inline]
+const fn hashes(hash: u64, seed: u64) -> (u32, u32, u32) {
+    let h = mix(hash ^ seed);
+    let h2 = mix(h);
+    ((h >> 32) as u32, h as u32, (h2 as u32) | 1)
+}
+
+#[inline]
+const fn slot(f1: u32, f2: u32, d1: u32, d2: u32, slots: usize) -> usize {
+    (f1.wrapping_add(d1.wrapping_mul(f2)).wrapping_add(d2) as usize) & (slots - 1)
+}
+
+/// The perfect hash function of a [`SelTable`], computed at compile time.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct SelTableLayout<const SLOTS: usize, const BUCKETS: usize> {
+    pub slots: [u32; SLOTS],
+    pub displacements: [(u32, u32); BUCKETS],
+    pub seed: u64,
+}
+
+impl SelTableLayout<0, 0> {
+    /// The number of slots to use for `len` keys.
+    ///
+    /// A power of two, with a load factor of at most 80%.
+    pub const fn slots(len: usize) -> usize {
+        (len + len / 4 + 1).next_power_of_two()
+    }
+
+    /// The number of buckets to use for `len` keys.
+    pub const fn buckets(len: usize) -> usize {
+        if len == 0 {
+            1
+        } else {
+            (len + 3) / 4
+        }
+    }
+}
+
+impl<const SLOTS: usize, const BUCKETS: usize> SelTableLayout<SLOTS, BUCKETS> {
+    /// Build a perfect hash function for the keys, using the "hash and
+    /// displace" algorithm.
+    ///
+    /// Each key is first assigned to a bucket. Then, starting with the
+    /// largest bucket, a pair of displacements is searched for that moves
+    /// all the keys in the bucket to unoccupied slots.
+    pub const fn build<const N: usize>(keys: &[SelKey]) -> Self {
+        if keys.len() != N {
+            panic!("wrong number of keys");
+        }
+        if keys.len() >= EMPTY as usize {
+            panic!("too many keys in selector table");
+        }
+
+        let mut i = 0;
+        while i < N {
+            let mut j = i + 1;
+            while j < N {
+                if keys[i].bytes_eq(&keys[j]) {
+                    panic!("duplicate selector in selector table");
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+
+        let mut seed = 0;
+        while seed < 64 {
+            if let Some(layout) = Self::try_build::<N>(keys, seed) {
+                return layout;
+            }
+            seed += 1;
+        }
+        panic!("failed building selector table")
+    }
+
+    const fn try_build<const N: usize>(keys: &[SelKey], seed: u64) -> Option<Self> {
+        let mut layout = Self {
+            slots: [EMPTY; SLOTS],
+            displacements: [(0, 0); BUCKETS],
+            seed,
+        };
+
+        let mut key_hashes = [(0, 0, 0); N];
+        let mut bucket_sizes = [0usize; BUCKETS];
+        let mut max_size = 0;
+        let mut i = 0;
+        while i < N {
+            let (bucket, f1, f2) = hashes(keys[i].hash, seed);
+            let bucket = bucket as usize % BUCKETS;
+            key_hashes[i] = (bucket, f1, f2);
+            bucket_sizes[bucket] += 1;
+            if bucket_sizes[bucket] > max_size {
+                max_size = bucket_sizes[bucket];
+            }
+            i += 1;
+        }
+
+        // Used to detect collisions within a bucket while trying a
+        // displacement, without having to clear it each time.
+        let mut generation = [0u32; SLOTS];
+        let mut current_generation = 0;
+
+        // Place the largest buckets first, while the table is mostly empty.
+        let mut size = max_size;
+        while size > 0 {
+            let mut bucket = 0;
+            while bucket < BUCKETS {
+                if bucket_sizes[bucket] != size {
+                    bucket += 1;
+                    continue;
+                }
+
+                let mut placed = false;
+                let mut d1 = 0;
+                'd1: while d1 < SLOTS as u32 {
+                    let mut d2 = 0;
+                    while d2 < SLOTS as u32 {
+                        current_generation += 1;
+                        let mut fits = true;
+                        let mut i = 0;
+                        while i < N {
+                            let (b, f1, f2) = key_hashes[i];
+                            if b == bucket {
+                                let s = slot(f1, f2, d1, d2, SLOTS);
+                                if layout.slots[s] != EMPTY || generation[s] == current_generation {
+                                    fits = false;
+                                    break;
+                                }
+                                generation[s] = current_generation;
+                            }
+                            i += 1;
+                        }
+
+                        if fits {
+                            let mut i = 0;
+                            while i < N {
+                                let (b, f1, f2) = key_hashes[i];
+                                if b == bucket {
+                                    layout.slots[slot(f1, f2, d1, d2, SLOTS)] = i as u32;
+                                }
+                                i += 1;
+                            }
+                            layout.displacements[bucket] = (d1, d2);
+                            placed = true;
+                            break 'd1;
+                        }
+                        d2 += 1;
+                    }
+                    d1 += 1;
+                }
+
+                if !placed {
+                    return None;
+                }
+                bucket += 1;
+            }
+            size -= 1;
+        }
+
+        Some(layout)
+    }
+}
+
+/// A static lookup table keyed by selector.
+///
+/// The table uses a perfect hash function that is computed at compile time,
+/// so lookups never allocate, and only hash the selector name once and do a
+/// single string comparison. This is useful for e.g. routing selectors in
+/// `forwardInvocation:` or `resolveInstanceMethod:` to Rust handlers,
+/// without building a `HashMap` at runtime.
+///
+/// Create it with the [`sel_table!`] macro.
+///
+/// [`sel_table!`]: crate::sel_table
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::runtime::SelTable;
+/// use objc2::{sel, sel_table};
+///
+/// fn increment(x: i32) -> i32 {
+///     x + 1
+/// }
+///
+/// fn double(x: i32) -> i32 {
+///     x * 2
+/// }
+///
+/// sel_table! {
+///     static HANDLERS: SelTable<fn(i32) -> i32> = {
+///         increment => increment,
+///         doubleValue:withOptions: => double,
+///     };
+/// }
+///
+/// let handler = HANDLERS.get(sel!(doubleValue:withOptions:)).unwrap();
+/// assert_eq!(handler(21), 42);
+/// assert!(HANDLERS.get(sel!(decrement)).is_none());
+/// ```
+pub struct SelTable<V: 'static> {
+    keys: &'static [SelKey],
+    values: &'static [V],
+    slots: &'static [u32],
+    displacements: &'static [(u32, u32)],
+    seed: u64,
+}
+
+impl<V: 'static> SelTable<V> {
+    #[doc(hidden)]
+    pub const fn __new<const N: usize, const SLOTS: usize, const BUCKETS: usize>(
+        keys: &'static [SelKey],
+        values: &'static [V; N],
+        slots: &'static [u32; SLOTS],
+        displacements: &'static [(u32, u32); BUCKETS],
+        seed: u64,
+    ) -> Self {
+        if keys.len() != N {
+            panic!("wrong number of keys");
+        }
+        Self {
+            keys,
+            values,
+            slots,
+            displacements,
+            seed,
+        }
+    }
+
+    /// The number of entries in the table.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether the table has no entries.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    fn index_of(&self, name: &[u8]) -> Option<usize> {
+        let (bucket, f1, f2) = hashes(fnv1a(name, name.len()), self.seed);
+        let (d1, d2) = self.displacements[bucket as usize % self.displacements.len()];
+        let index = self.slots[slot(f1, f2, d1, d2, self.slots.len())];
+        let key = self.keys.get(index as usize)?;
+        (key.name().as_bytes() == name).then(|| index as usize)
+    }
+
+    /// Look up the value for the given selector.
+    #[inline]
+    pub fn get(&self, sel: Sel) -> Option<&'static V> {
+        self.index_of(sel_bytes(sel)).map(|i| &self.values[i])
+    }
+
+    /// Look up the value for the selector with the given name.
+    #[inline]
+    pub fn get_by_name(&self, name: &str) -> Option<&'static V> {
+        self.index_of(name.as_bytes()).map(|i| &self.values[i])
+    }
+
+    /// Whether the table contains the given selector.
+    #[inline]
+    pub fn contains(&self, sel: Sel) -> bool {
+        self.index_of(sel_bytes(sel)).is_some()
+    }
+
+    /// An iterator over the keys and values in the table, in the order they
+    /// were declared.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static SelKey, &'static V)> + '_ {
+        self.keys.iter().zip(self.values.iter())
+    }
+}
+
+impl<V: 'static> Clone for SelTable<V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V: 'static> Copy for SelTable<V> {}
+
+impl<V: fmt::Debug + 'static> fmt::Debug for SelTable<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(k, v)| (k.name(), v)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::format;
+
+    use super::*;
+    use crate::{sel, sel_table};
+
+    #[test]
+    fn test_key() {
+        const KEY: SelKey = SelKey::new("foo:bar:");
+        const KEY_NUL: SelKey = SelKey::new("foo:bar:\0");
+        assert_eq!(KEY, KEY_NUL);
+        assert_eq!(KEY.name(), "foo:bar:");
+        assert_eq!(KEY_NUL.name(), "foo:bar:");
+        assert_eq!(KEY.name_hash(), KEY_NUL.name_hash());
+        assert_eq!(KEY.sel(), sel!(foo:bar:));
+        assert_eq!(KEY_NUL.sel(), sel!(foo:bar:));
+        assert_eq!(KEY, sel!(foo:bar:));
+        assert_ne!(KEY, SelKey::new("foo:"));
+        assert_eq!(format!("{KEY} {KEY:?}"), "foo:bar: SelKey(\"foo:bar:\")");
+
+        let mut set = HashSet::new();
+        assert!(set.insert(KEY));
+        assert!(!set.insert(KEY_NUL));
+    }
+
+    fn one() -> u32 {
+        1
+    }
+
+    fn two() -> u32 {
+        2
+    }
+
+    sel_table! {
+        static SMALL: SelTable<fn() -> u32> = {
+            one => one,
+            two:: => two,
+            with:internal::colons: => two,
+        };
+    }
+
+    sel_table! {
+        /// Doc comments are allowed.
+        static EMPTY_TABLE: SelTable<u8> = {};
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(SMALL.len(), 3);
+        assert_eq!(SMALL.get(sel!(one)).unwrap()(), 1);
+        assert_eq!(SMALL.get(sel!(two::)).unwrap()(), 2);
+        assert_eq!(SMALL.get_by_name("with:internal::colons:").unwrap()(), 2);
+        assert!(SMALL.get(sel!(two)).is_none());
+        assert!(SMALL.get(sel!(two:)).is_none());
+        assert!(SMALL.contains(sel!(one)));
+        assert!(!SMALL.contains(sel!(three)));
+
+        let names: std::vec::Vec<_> = SMALL.iter().map(|(k, _)| k.name()).collect();
+        assert_eq!(names, ["one", "two::", "with:internal::colons:"]);
+
+        assert!(EMPTY_TABLE.is_empty());
+        assert!(EMPTY_TABLE.get(sel!(one)).is_none());
+        assert_eq!(format!("{EMPTY_TABLE:?}"), "{}");
+    }
+
+    #[test]
+    fn test_large_table() {
+        macro_rules! large {
+            ($($name:ident)*) => {
+                sel_table! {
+                    static LARGE: SelTable<&str> = {
+                        $($name: => stringify!($name),)*
+                    };
+                }
+            };
+        }
+
+        large!(
+            a b c d e f g h i j k l m n o p q r s t u v w x y z
+            aa bb cc dd ee ff gg hh ii jj kk ll mm nn oo pp qq rr ss tt
+            init new alloc dealloc copy mutableCopy description hash
+            isEqual retain release autorelease count objectAtIndex
+        );
+
+        assert_eq!(LARGE.len(), 60);
+        for (key, value) in LARGE.iter() {
+            assert_eq!(key.name(), format!("{value}:"));
+            assert_eq!(LARGE.get(key.sel()), Some(value));
+            assert_eq!(LARGE.get_by_name(key.name()), Some(value));
+        }
+        assert_eq!(LARGE.get(sel!(a)), None);
+        assert_eq!(LARGE.get_by_name("zz:"), None);
+    }
+}
@@ -0,0 +1,92 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::mutability::IsAllocableAnyThread;
+use crate::rc::Allocated;
+use crate::runtime::AnyClass;
+use crate::{msg_send_id, ClassType};
+
+/// A class that is known to be `T`'s class, or a subclass of it.
+///
+/// Instances of the class can be treated as instances of `T`, so this can
+/// be used to allocate objects of type `T`.
+///
+/// Created with [`AnyClass::get_checked`]. This dereferences to
+/// [`AnyClass`].
+pub struct SubclassOf<T: ?Sized> {
+    cls: &'static AnyClass,
+    p: PhantomData<fn() -> *const T>,
+}
+
+impl<T: ?Sized + ClassType> SubclassOf<T> {
+    /// Wrap the class if it is `T`'s class, or a subclass of it.
+    pub(crate) fn new(cls: &'static AnyClass) -> Option<Self> {
+        if cls.is_subclass_of(T::class()) {
+            Some(Self {
+                cls,
+                p: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The class itself.
+    #[inline]
+    pub fn as_class(self) -> &'static AnyClass {
+        self.cls
+    }
+
+    /// Allocate a new instance of the class, typed as `T`.
+    ///
+    /// This is the same as [`ClassType::alloc`], but with the class that
+    /// was looked up.
+    #[inline]
+    pub fn alloc(self) -> Allocated<T>
+    where
+        T: IsAllocableAnyThread + Sized,
+    {
+        // SAFETY:
+        // - It is always safe to (attempt to) allocate an object.
+        // - The object is of the correct type, since the class is `T`'s
+        //   class or a subclass of it.
+        // - The object is safe to `dealloc` on the current thread (due to the
+        //   `IsAllocableAnyThread` bound which guarantees it is not
+        //   `MainThreadOnly`).
+        unsafe { msg_send_id![self.cls, alloc] }
+    }
+}
+
+impl<T: ?Sized> Clone for SubclassOf<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for SubclassOf<T> {}
+
+impl<T: ?Sized> PartialEq for SubclassOf<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cls == other.cls
+    }
+}
+
+impl<T: ?Sized> Eq for SubclassOf<T> {}
+
+impl<T: ?Sized> Deref for SubclassOf<T> {
+    type Target = AnyClass;
+
+    #[inline]
+    fn deref(&self) -> &AnyClass {
+        self.cls
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for SubclassOf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SubclassOf").field(&self.cls).finish()
+    }
+}
@@ -0,0 +1,100 @@
+//! Annotations for ThreadSanitizer and AddressSanitizer.
+//!
+//! The Objective-C runtime is usually not instrumented, so the sanitizers
+//! cannot see the synchronization done by its reference counting, nor that
+//! instance variables of declared classes are only valid between being
+//! initialized and being dropped in `dealloc`. We tell them about these with
+//! the functions here.
+//!
+//! These are only enabled when the `"unstable-sanitize"` feature is enabled
+//! and the code is compiled with `-Zsanitizer=thread` or
+//! `-Zsanitizer=address`, and are no-ops otherwise.
+#![cfg_attr(not(feature = "unstable-sanitize"), allow(unused_variables))]
+
+/// The sanitizer interface, or no-ops for the sanitizers that are not
+/// enabled.
+///
+/// This is a separate module, since `cfg(sanitize)` must not be evaluated
+/// at all when the `cfg_sanitize` feature is not enabled.
+#[cfg(feature = "unstable-sanitize")]
+mod hooks {
+    use core::ffi::c_void;
+
+    #[cfg(sanitize = "thread")]
+    extern "C" {
+        pub(super) fn __tsan_acquire(addr: *mut c_void);
+        pub(super) fn __tsan_release(addr: *mut c_void);
+    }
+
+    #[cfg(not(sanitize = "thread"))]
+    pub(super) unsafe fn __tsan_acquire(_addr: *mut c_void) {}
+    #[cfg(not(sanitize = "thread"))]
+    pub(super) unsafe fn __tsan_release(_addr: *mut c_void) {}
+
+    #[cfg(sanitize = "address")]
+    extern "C" {
+        pub(super) fn __asan_poison_memory_region(addr: *const c_void, size: usize);
+        pub(super) fn __asan_unpoison_memory_region(addr: *const c_void, size: usize);
+    }
+
+    #[cfg(not(sanitize = "address"))]
+    pub(super) unsafe fn __asan_poison_memory_region(_addr: *const c_void, _size: usize) {}
+    #[cfg(not(sanitize = "address"))]
+    pub(super) unsafe fn __asan_unpoison_memory_region(_addr: *const c_void, _size: usize) {}
+}
+
+/// Mark that the current thread is about to give up a reference to the
+/// object, which may then be deallocated on another thread.
+///
+/// Must be paired with [`acquire_for_dealloc`].
+#[inline]
+pub(crate) fn release<T: ?Sized>(obj: *const T) {
+    // SAFETY: TSan only uses the address to key its synchronization state.
+    #[cfg(feature = "unstable-sanitize")]
+    unsafe {
+        hooks::__tsan_release(obj as *mut core::ffi::c_void)
+    };
+}
+
+/// Mark that the object is about to be deallocated, after every thread has
+/// given up its reference with [`release`].
+#[inline]
+pub(crate) fn acquire_for_dealloc<T: ?Sized>(obj: *const T) {
+    // SAFETY: TSan only uses the address to key its synchronization state.
+    #[cfg(feature = "unstable-sanitize")]
+    unsafe {
+        hooks::__tsan_acquire(obj as *mut core::ffi::c_void)
+    };
+}
+
+/// Mark the memory as invalid to access, e.g. after dropping the value
+/// stored there.
+///
+///
+/// # Safety
+///
+/// The memory must be part of a live allocation.
+#[inline]
+pub(crate) unsafe fn poison<T>(ptr: *const T) {
+    // SAFETY: Upheld by the caller.
+    #[cfg(feature = "unstable-sanitize")]
+    unsafe {
+        hooks::__asan_poison_memory_region(ptr.cast(), core::mem::size_of::<T>())
+    };
+}
+
+/// Mark the memory as valid to access again, e.g. before writing a value to
+/// it.
+///
+///
+/// # Safety
+///
+/// The memory must be part of a live allocation.
+#[inline]
+pub(crate) unsafe fn unpoison<T>(ptr: *const T) {
+    // SAFETY: Upheld by the caller.
+    #[cfg(feature = "unstable-sanitize")]
+    unsafe {
+        hooks::__asan_unpoison_memory_region(ptr.cast(), core::mem::size_of::<T>())
+    };
+}
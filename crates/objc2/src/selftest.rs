@@ -0,0 +1,424 @@
+//! A self-test of the calling convention used by the runtime.
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::encode::{Encode, Encoding};
+use crate::mutability::InteriorMutable;
+use crate::rc::Id;
+use crate::runtime::{Bool, NSObject};
+use crate::{declare_class, msg_send, msg_send_id, ClassType, DeclaredClass};
+
+/// Small enough to be returned in floating point registers on most targets
+/// (a homogeneous floating-point aggregate on ARM).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SmallFloats {
+    a: f32,
+    b: f32,
+}
+
+unsafe impl Encode for SmallFloats {
+    const ENCODING: Encoding =
+        Encoding::Struct("__objc2SmallFloats", &[f32::ENCODING, f32::ENCODING]);
+}
+
+/// Returned in two integer registers on 64-bit targets, and in memory on
+/// 32-bit targets.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Pair {
+    a: i64,
+    b: i64,
+}
+
+unsafe impl Encode for Pair {
+    const ENCODING: Encoding = Encoding::Struct("__objc2Pair", &[i64::ENCODING, i64::ENCODING]);
+}
+
+/// Always returned in memory, i.e. with `objc_msgSend_stret` where that is
+/// used.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Large {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+}
+
+unsafe impl Encode for Large {
+    const ENCODING: Encoding = Encoding::Struct(
+        "__objc2Large",
+        &[f64::ENCODING, f64::ENCODING, f64::ENCODING, f64::ENCODING],
+    );
+}
+
+/// Contains padding between the fields.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Padded {
+    a: u8,
+    b: f64,
+    c: u16,
+}
+
+unsafe impl Encode for Padded {
+    const ENCODING: Encoding = Encoding::Struct(
+        "__objc2Padded",
+        &[u8::ENCODING, f64::ENCODING, u16::ENCODING],
+    );
+}
+
+declare_class!(
+    struct AbiSelfTest;
+
+    unsafe impl ClassType for AbiSelfTest {
+        type Super = NSObject;
+        type Mutability = InteriorMutable;
+        const NAME: &'static str = "__objc2AbiSelfTest";
+    }
+
+    impl DeclaredClass for AbiSelfTest {}
+
+    unsafe impl AbiSelfTest {
+        #[method(negI8:)]
+        fn neg_i8(&self, x: i8) -> i8 {
+            x.wrapping_neg()
+        }
+
+        #[method(incU16:)]
+        fn inc_u16(&self, x: u16) -> u16 {
+            x.wrapping_add(1)
+        }
+
+        #[method(not:)]
+        fn not(&self, x: Bool) -> Bool {
+            Bool::new(!x.as_bool())
+        }
+
+        #[method(addF32:toF64:)]
+        fn add_f32_to_f64(&self, a: f32, b: f64) -> f64 {
+            a as f64 + b
+        }
+
+        #[method(halfF32:)]
+        fn half_f32(&self, x: f32) -> f32 {
+            x / 2.0
+        }
+
+        // More arguments than there are registers for on any target, so
+        // that some are passed on the stack.
+        #[method(spillI1:f1:i2:f2:i3:f3:i4:f4:i5:f5:i6:f6:i7:f7:i8:f8:)]
+        #[allow(clippy::too_many_arguments)]
+        fn spill(
+            &self,
+            i1: i32,
+            f1: f64,
+            i2: i64,
+            f2: f32,
+            i3: i8,
+            f3: f64,
+            i4: u16,
+            f4: f64,
+            i5: i64,
+            f5: f64,
+            i6: i32,
+            f6: f64,
+            i7: i64,
+            f7: f64,
+            i8: u8,
+            f8: f64,
+        ) -> f64 {
+            let ints = i1 as i64 + i2 + i3 as i64 + i4 as i64 + i5 + i6 as i64 + i7 + i8 as i64;
+            let floats = f1 + f2 as f64 + f3 + f4 + f5 + f6 + f7 + f8;
+            ints as f64 * 1000.0 + floats
+        }
+
+        #[method(smallFloats:)]
+        fn small_floats(&self, x: f32) -> SmallFloats {
+            SmallFloats { a: x, b: x * 2.0 }
+        }
+
+        #[method(pair:)]
+        fn pair(&self, x: i64) -> Pair {
+            Pair { a: x, b: -x }
+        }
+
+        #[method(large:)]
+        fn large(&self, x: f64) -> Large {
+            Large {
+                a: x,
+                b: x + 1.0,
+                c: x + 2.0,
+                d: x + 3.0,
+            }
+        }
+
+        #[method(sumLarge:)]
+        fn sum_large(&self, x: Large) -> f64 {
+            x.a + x.b + x.c + x.d
+        }
+
+        #[method(swapPadded:)]
+        fn swap_padded(&self, x: Padded) -> Padded {
+            Padded {
+                a: x.c as u8,
+                b: -x.b,
+                c: x.a as u16,
+            }
+        }
+    }
+);
+
+declare_class!(
+    struct AbiSelfTestSubclass;
+
+    unsafe impl ClassType for AbiSelfTestSubclass {
+        type Super = AbiSelfTest;
+        type Mutability = InteriorMutable;
+        const NAME: &'static str = "__objc2AbiSelfTestSubclass";
+    }
+
+    impl DeclaredClass for AbiSelfTestSubclass {}
+
+    unsafe impl AbiSelfTestSubclass {
+        // Overridden, so that messages to `super` can be told apart.
+        #[method(halfF32:)]
+        fn half_f32(&self, _x: f32) -> f32 {
+            -1.0
+        }
+
+        #[method(large:)]
+        fn large(&self, _x: f64) -> Large {
+            Large {
+                a: -1.0,
+                b: -1.0,
+                c: -1.0,
+                d: -1.0,
+            }
+        }
+    }
+);
+
+/// An error from [`abi_selftest`], describing the first check that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiSelfTestError {
+    test: &'static str,
+    expected: String,
+    actual: String,
+}
+
+impl AbiSelfTestError {
+    /// The name of the check that failed.
+    pub fn test(&self) -> &'static str {
+        self.test
+    }
+}
+
+impl fmt::Display for AbiSelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ABI self-test `{}` failed: expected {}, got {}",
+            self.test, self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AbiSelfTestError {}
+
+fn check<T: PartialEq + fmt::Debug>(
+    test: &'static str,
+    expected: T,
+    actual: T,
+) -> Result<(), AbiSelfTestError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(AbiSelfTestError {
+            test,
+            expected: format!("{expected:?}"),
+            actual: format!("{actual:?}"),
+        })
+    }
+}
+
+/// Check that message sending works correctly with the linked runtime on
+/// the current target.
+///
+/// This sends messages with a variety of argument and return types to a
+/// class declared in Rust, and checks that the values arrive unchanged.
+/// This covers sign- and zero-extension of small integers, `BOOL`, mixed
+/// floating point arguments, arguments passed on the stack, and structs
+/// passed and returned in registers and in memory (including through
+/// `objc_msgSend_stret` and `objc_msgSend_fpret` where those are used), as
+/// well as messages to `super` (including through `objc_msgSendSuper_stret`).
+/// When the `"exception"` feature is enabled, throwing and catching an
+/// exception is checked as well. Blocks are checked separately by
+/// `block2::abi_selftest`, since they are provided by the `block2` crate.
+///
+/// The calling convention is selected at compile-time based on the target,
+/// so this is mostly useful when bringing up support for a new target (or
+/// a new runtime), where a mistake would otherwise lead to memory
+/// corruption far from its source. A good place to call it is at the start
+/// of `main` in debug builds, see the example below.
+///
+///
+/// # Errors
+///
+/// Returns an error describing the first check that failed.
+///
+///
+/// # Example
+///
+#[cfg_attr(feature = "apple", doc = "```")]
+#[cfg_attr(not(feature = "apple"), doc = "```no_run")]
+/// if cfg!(debug_assertions) {
+///     objc2::abi_selftest().expect("objc2 does not support this target");
+/// }
+/// ```
+pub fn abi_selftest() -> Result<(), AbiSelfTestError> {
+    let obj = AbiSelfTest::alloc().set_ivars(());
+    let obj: Id<AbiSelfTest> = unsafe { msg_send_id![super(obj), init] };
+    let obj = &*obj;
+
+    unsafe {
+        check("i8 argument and return", -5i8, msg_send![obj, negI8: 5i8])?;
+        check("i8 sign extension", 127i8, msg_send![obj, negI8: -127i8])?;
+        check("u16 zero extension", 0u16, msg_send![obj, incU16: u16::MAX])?;
+        let res: Bool = msg_send![obj, not: Bool::YES];
+        check("BOOL YES", false, res.as_bool())?;
+        let res: Bool = msg_send![obj, not: Bool::NO];
+        check("BOOL NO", true, res.as_bool())?;
+        check(
+            "mixed float arguments",
+            3.75f64,
+            msg_send![obj, addF32: 1.5f32, toF64: 2.25f64],
+        )?;
+        check("float return", 0.75f32, msg_send![obj, halfF32: 1.5f32])?;
+
+        let res: f64 = msg_send![
+            obj,
+            spillI1: 1i32,
+            f1: 0.5f64,
+            i2: 2i64,
+            f2: 0.25f32,
+            i3: -3i8,
+            f3: 0.125f64,
+            i4: 4u16,
+            f4: 1.0f64,
+            i5: 5i64,
+            f5: 2.0f64,
+            i6: -6i32,
+            f6: 4.0f64,
+            i7: 7i64,
+            f7: 8.0f64,
+            i8: 8u8,
+            f8: 16.0f64,
+        ];
+        check("arguments on the stack", 18031.875f64, res)?;
+
+        check(
+            "small float struct return",
+            SmallFloats { a: 1.5, b: 3.0 },
+            msg_send![obj, smallFloats: 1.5f32],
+        )?;
+        check(
+            "two-word struct return",
+            Pair {
+                a: 1 << 40,
+                b: -(1 << 40),
+            },
+            msg_send![obj, pair: 1i64 << 40],
+        )?;
+        let large = Large {
+            a: 1.0,
+            b: 2.0,
+            c: 3.0,
+            d: 4.0,
+        };
+        check("large struct return", large, msg_send![obj, large: 1.0f64])?;
+        check(
+            "large struct argument",
+            10.0f64,
+            msg_send![obj, sumLarge: large],
+        )?;
+        check(
+            "padded struct argument and return",
+            Padded {
+                a: 42,
+                b: -0.5,
+                c: 7,
+            },
+            msg_send![
+                obj,
+                swapPadded: Padded {
+                    a: 7,
+                    b: 0.5,
+                    c: 42,
+                }
+            ],
+        )?;
+
+        let hash: usize = msg_send![obj, hash];
+        check(
+            "message to super",
+            hash,
+            msg_send![super(obj, NSObject::class()), hash],
+        )?;
+    }
+
+    let sub = AbiSelfTestSubclass::alloc().set_ivars(());
+    let sub: Id<AbiSelfTestSubclass> = unsafe { msg_send_id![super(sub), init] };
+    let sub = &*sub;
+
+    unsafe {
+        check(
+            "float return from super",
+            0.75f32,
+            msg_send![super(sub, AbiSelfTest::class()), halfF32: 1.5f32],
+        )?;
+        check(
+            "large struct return from super",
+            Large {
+                a: 1.0,
+                b: 2.0,
+                c: 3.0,
+                d: 4.0,
+            },
+            msg_send![super(sub, AbiSelfTest::class()), large: 1.0f64],
+        )?;
+    }
+
+    #[cfg(feature = "exception")]
+    {
+        use crate::exception::{catch, throw, Exception};
+
+        let exception = NSObject::new();
+        let ptr: *const NSObject = &*exception;
+        // SAFETY: Any object can be thrown as an exception.
+        let exception: Id<Exception> = unsafe { Id::cast(exception) };
+        let res: Result<(), _> = unsafe { catch(|| throw(exception)) };
+        let caught = res
+            .err()
+            .flatten()
+            .map(|exception| Id::as_ptr(&exception).cast::<NSObject>());
+        check("exception", Some(ptr), caught)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abi_selftest() {
+        abi_selftest().unwrap();
+        // Running it again reuses the class.
+        abi_selftest().unwrap();
+    }
+}
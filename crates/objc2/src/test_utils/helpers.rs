@@ -0,0 +1,478 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::collections::HashMap;
+use std::sync::Once;
+use std::thread_local;
+
+use crate::rc::{autoreleasepool, Id, WeakId};
+use crate::runtime::__nsstring::nsstring_to_str;
+use crate::runtime::{AnyClass, AnyObject, Imp, NSObject, NSZone};
+use crate::{msg_send, msg_send_id, sel, ClassType, Message};
+
+fn as_object<T: ?Sized + Message>(obj: &T) -> &AnyObject {
+    // SAFETY: All `Message` types are Objective-C objects.
+    unsafe { &*(obj as *const T).cast::<AnyObject>() }
+}
+
+/// Assert that an object is an instance of the given class, or one of its
+/// subclasses.
+///
+/// The object can be anything that dereferences to a type that implements
+/// [`Message`], such as `&T` or `Id<T>`, and the class must implement
+/// [`ClassType`].
+///
+/// This is only available when the `"test-utils"` Cargo feature is enabled.
+///
+/// [`Message`]: crate::Message
+/// [`ClassType`]: crate::ClassType
+///
+///
+/// # Panics
+///
+/// Panics if the object is not an instance of the class.
+///
+///
+/// # Example
+///
+/// See the [module-level documentation](crate::test_utils).
+#[macro_export]
+macro_rules! assert_class {
+    ($obj:expr, $cls:ty $(,)?) => {
+        $crate::test_utils::__assert_class::<$cls, _>(&*$obj)
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_class<C: ClassType, T: ?Sized + Message>(obj: &T) {
+    let actual = as_object(obj).class();
+    let expected = C::class();
+    if !actual.is_subclass_of(expected) {
+        panic!("expected an instance of {expected}, but found an instance of {actual}");
+    }
+}
+
+/// Assert that the `description` of an object matches an expected string.
+///
+/// Since the description of most objects include their address, which
+/// differs between each run, all hexadecimal numbers starting with `0x` are
+/// replaced with `0x0` before comparing, see [`normalize_description`].
+///
+/// This is only available when the `"test-utils"` Cargo feature is enabled.
+///
+/// [`normalize_description`]: crate::test_utils::normalize_description
+///
+///
+/// # Panics
+///
+/// Panics if the normalized description is not equal to the expected
+/// string, and prints both.
+///
+///
+/// # Example
+///
+/// See the [module-level documentation](crate::test_utils).
+#[macro_export]
+macro_rules! assert_description_snapshot {
+    ($obj:expr, $expected:expr $(,)?) => {
+        $crate::test_utils::__assert_description_snapshot(&*$obj, $expected)
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_description_snapshot<T: ?Sized + Message>(obj: &T, expected: &str) {
+    let actual = normalize_description(&description(obj));
+    assert_eq!(actual, expected, "description did not match snapshot");
+}
+
+/// The `description` of an object, as a Rust string.
+pub fn description<T: ?Sized + Message>(obj: &T) -> String {
+    let description: Id<NSObject> = unsafe { msg_send_id![as_object(obj), description] };
+    // SAFETY: `description` returns an `NSString`.
+    autoreleasepool(|pool| unsafe { nsstring_to_str(&description, pool) }.to_string())
+}
+
+/// Replace all hexadecimal numbers starting with `0x` with `0x0`.
+///
+/// This makes descriptions containing object addresses stable across runs.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::test_utils::normalize_description;
+///
+/// assert_eq!(
+///     normalize_description("<NSObject: 0x600003a04010>"),
+///     "<NSObject: 0x0>",
+/// );
+/// ```
+pub fn normalize_description(description: &str) -> String {
+    let mut res = String::with_capacity(description.len());
+    let mut rest = description;
+    while let Some(i) = rest.find("0x") {
+        res.push_str(&rest[..i]);
+        rest = &rest[i + 2..];
+        let digits = rest.trim_start_matches(|c: char| c.is_ascii_hexdigit());
+        if digits.len() == rest.len() {
+            // Not followed by a number, leave as-is.
+            res.push_str("0x");
+        } else {
+            res.push_str("0x0");
+            rest = digits;
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+/// The current retain count of an object.
+///
+/// Note that the retain count is generally not meaningful, since the
+/// runtime and frameworks are free to retain and autorelease objects
+/// internally, and some objects (like constant strings or tagged pointers)
+/// are not reference-counted at all. It is only useful in controlled test
+/// scenarios, where you know exactly which references to the object exist,
+/// e.g. for objects of classes that you declared yourself.
+#[doc(alias = "retainCount")]
+pub fn retain_count<T: ?Sized + Message>(obj: &T) -> usize {
+    unsafe { msg_send![as_object(obj), retainCount] }
+}
+
+/// Assert that an object is deallocated once all strong references to it
+/// are gone, and the autorelease pool it was created in has been drained.
+///
+/// The closure is run inside a new autorelease pool, and a weak reference
+/// is taken to the object that it returns, before the object is released
+/// and the pool is drained.
+///
+/// This requires the runtime to support weak references, see
+/// [`RuntimeFeatures::weak_references`].
+///
+/// [`RuntimeFeatures::weak_references`]: crate::runtime::RuntimeFeatures::weak_references
+///
+///
+/// # Panics
+///
+/// Panics if the object is still alive afterwards, i.e. if it was leaked or
+/// retained somewhere else.
+#[track_caller]
+pub fn assert_no_leak<T: Message, F: FnOnce() -> Id<T>>(f: F) {
+    let (weak, cls) = autoreleasepool(|_| {
+        let obj = f();
+        let cls: &'static AnyClass = as_object(&*obj).class();
+        // SAFETY: The weak reference is only used to check whether the
+        // object is alive, so the exact class does not matter.
+        let obj: Id<NSObject> = unsafe { Id::cast(obj) };
+        (WeakId::from_id(&obj), cls)
+    });
+    if weak.load().is_some() {
+        panic!("leaked instance of {cls}: still alive after draining the autorelease pool");
+    }
+}
+
+type AllocWithZone =
+    unsafe extern "C" fn(&AnyClass, crate::runtime::Sel, *mut NSZone) -> *mut AnyObject;
+
+/// The original implementation of `+[NSObject allocWithZone:]`.
+static ORIGINAL_ALLOC: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+thread_local! {
+    /// The number of live `AllocationCounter`s on this thread.
+    static ACTIVE: Cell<usize> = const { Cell::new(0) };
+    /// The number of objects of each class allocated on this thread while a
+    /// counter was active.
+    static COUNTS: RefCell<HashMap<*const AnyClass, usize>> = RefCell::new(HashMap::new());
+}
+
+unsafe extern "C" fn counting_alloc_with_zone(
+    cls: &AnyClass,
+    cmd: crate::runtime::Sel,
+    zone: *mut NSZone,
+) -> *mut AnyObject {
+    // SAFETY: Stored before this implementation was installed.
+    let original: AllocWithZone = unsafe { mem::transmute(ORIGINAL_ALLOC.load(Ordering::Acquire)) };
+    // SAFETY: Forwarding the arguments we got.
+    let obj = unsafe { original(cls, cmd, zone) };
+
+    if !obj.is_null() && ACTIVE.try_with(Cell::get).unwrap_or(0) > 0 {
+        let cls: *const AnyClass = cls;
+        let _ = COUNTS.try_with(|counts| {
+            if let Ok(mut counts) = counts.try_borrow_mut() {
+                *counts.entry(cls).or_insert(0) += 1;
+            }
+        });
+    }
+    obj
+}
+
+fn install_allocation_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let method = NSObject::class()
+            .class_method(sel!(allocWithZone:))
+            .expect("NSObject should have +allocWithZone:");
+        // Store the original before installing the hook, since the hook may
+        // be called from other threads immediately.
+        ORIGINAL_ALLOC.store(method.implementation() as *mut (), Ordering::Release);
+        let hook: AllocWithZone = counting_alloc_with_zone;
+        // SAFETY: The hook has the same signature as `allocWithZone:`, and
+        // forwards to the original implementation.
+        let hook: Imp = unsafe { mem::transmute(hook) };
+        let _ = unsafe { method.set_implementation(hook) };
+    });
+}
+
+fn current_counts() -> HashMap<*const AnyClass, usize> {
+    COUNTS.with(|counts| counts.borrow().clone())
+}
+
+/// Counts the Objective-C objects that are allocated on the current thread.
+///
+/// Creating the first counter installs a hook on `+[NSObject allocWithZone:]`
+/// (which `+alloc` forwards to), that records the class of each object
+/// allocated on a thread while a counter is alive on that thread. Counters
+/// on different threads are independent, so tests may run in parallel.
+///
+/// Note that only allocations that reach `NSObject`'s `+allocWithZone:` are
+/// counted. In particular, this does not count tagged pointers, objects
+/// created directly with `class_createInstance` or by CoreFoundation, or
+/// objects of classes that override `+allocWithZone:` without calling
+/// `super` (such as some class clusters, which instead allocate a
+/// placeholder).
+///
+/// This is only available when the `"test-utils"` Cargo feature is enabled.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::runtime::NSObject;
+/// use objc2::test_utils::AllocationCounter;
+///
+/// let counter = AllocationCounter::start();
+/// let _a = NSObject::new();
+/// let _b = NSObject::new();
+/// assert_eq!(counter.count::<NSObject>(), 2);
+/// assert_eq!(counter.total(), 2);
+/// ```
+pub struct AllocationCounter {
+    snapshot: HashMap<*const AnyClass, usize>,
+    // Tied to the current thread's counts
+    _not_send: PhantomData<*const ()>,
+}
+
+impl AllocationCounter {
+    /// Start counting allocations on the current thread.
+    pub fn start() -> Self {
+        install_allocation_hook();
+        ACTIVE.with(|active| active.set(active.get() + 1));
+        Self {
+            snapshot: current_counts(),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Reset the counts, such that only allocations after this point are
+    /// reported.
+    pub fn checkpoint(&mut self) {
+        self.snapshot = current_counts();
+    }
+
+    /// The number of objects of each class allocated since the last
+    /// checkpoint, sorted by class name.
+    pub fn counts(&self) -> Vec<(&'static AnyClass, usize)> {
+        let mut counts: Vec<_> = current_counts()
+            .into_iter()
+            .filter_map(|(cls, count)| {
+                let count = count - self.snapshot.get(&cls).copied().unwrap_or(0);
+                // SAFETY: Classes are never deallocated.
+                let cls: &'static AnyClass = unsafe { &*cls };
+                (count > 0).then(|| (cls, count))
+            })
+            .collect();
+        counts.sort_by(|(a, _), (b, _)| a.name().cmp(b.name()));
+        counts
+    }
+
+    /// The number of objects of exactly the given class allocated since the
+    /// last checkpoint.
+    pub fn count_of(&self, cls: &AnyClass) -> usize {
+        let cls: *const AnyClass = cls;
+        let current = COUNTS.with(|counts| counts.borrow().get(&cls).copied().unwrap_or(0));
+        current - self.snapshot.get(&cls).copied().unwrap_or(0)
+    }
+
+    /// The number of objects of exactly the class `C` allocated since the
+    /// last checkpoint.
+    pub fn count<C: ClassType>(&self) -> usize {
+        self.count_of(C::class())
+    }
+
+    /// The total number of objects allocated since the last checkpoint.
+    pub fn total(&self) -> usize {
+        self.counts().iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl Drop for AllocationCounter {
+    fn drop(&mut self) {
+        let _ = ACTIVE.try_with(|active| active.set(active.get() - 1));
+    }
+}
+
+impl fmt::Debug for AllocationCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.counts()
+                    .into_iter()
+                    .map(|(cls, count)| (cls.name(), count)),
+            )
+            .finish()
+    }
+}
+
+/// Assert that the closure allocates at most `max` Objective-C objects on
+/// the current thread, and return its result.
+///
+/// See [`AllocationCounter`] for which allocations are counted.
+///
+/// This is only available when the `"test-utils"` Cargo feature is enabled.
+///
+///
+/// # Panics
+///
+/// Panics if more than `max` objects were allocated, and prints the number
+/// of objects allocated of each class.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::runtime::NSObject;
+/// use objc2::test_utils::assert_max_allocations;
+///
+/// let obj = assert_max_allocations(1, NSObject::new);
+/// ```
+#[track_caller]
+pub fn assert_max_allocations<R, F: FnOnce() -> R>(max: usize, f: F) -> R {
+    let counter = AllocationCounter::start();
+    let res = f();
+    let total = counter.total();
+    if total > max {
+        panic!("expected at most {max} allocations, but found {total}: {counter:?}");
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assert_class() {
+        let obj = NSObject::new();
+        assert_class!(obj, NSObject);
+        assert_class!(&*obj, NSObject);
+    }
+
+    #[test]
+    #[should_panic = "expected an instance of NSProxy, but found an instance of NSObject"]
+    fn test_assert_class_wrong() {
+        let obj = NSObject::new();
+        assert_class!(obj, crate::runtime::__NSProxy);
+    }
+
+    #[test]
+    fn test_description_snapshot() {
+        let obj = NSObject::new();
+        assert!(description(&*obj).starts_with("<NSObject: 0x"));
+        assert_description_snapshot!(obj, "<NSObject: 0x0>");
+    }
+
+    #[test]
+    fn test_normalize_description() {
+        assert_eq!(normalize_description(""), "");
+        assert_eq!(normalize_description("0x"), "0x");
+        assert_eq!(normalize_description("0xabc 0x1F, 0xg"), "0x0 0x0, 0xg");
+        assert_eq!(normalize_description("a0x12b"), "a0x0");
+    }
+
+    #[test]
+    fn test_retain_count() {
+        let obj = NSObject::new();
+        assert_eq!(retain_count(&*obj), 1);
+        let obj2 = obj.clone();
+        assert_eq!(retain_count(&*obj), 2);
+        drop(obj2);
+        assert_eq!(retain_count(&*obj), 1);
+    }
+
+    #[test]
+    fn test_no_leak() {
+        assert_no_leak(NSObject::new);
+        assert_no_leak(|| {
+            let obj = NSObject::new();
+            autoreleasepool(|pool| {
+                let _ = Id::autorelease(obj.clone(), pool);
+            });
+            obj
+        });
+    }
+
+    #[test]
+    fn test_allocation_counter() {
+        let mut counter = AllocationCounter::start();
+        assert_eq!(counter.total(), 0);
+
+        let _a = NSObject::new();
+        let _b = NSObject::new();
+        assert_eq!(counter.count::<NSObject>(), 2);
+        assert_eq!(counter.counts(), [(NSObject::class(), 2)]);
+        assert_eq!(std::format!("{counter:?}"), "{\"NSObject\": 2}");
+
+        {
+            let nested = AllocationCounter::start();
+            let _c = NSObject::new();
+            assert_eq!(nested.total(), 1);
+        }
+        assert_eq!(counter.total(), 3);
+
+        counter.checkpoint();
+        assert_eq!(counter.total(), 0);
+        let _ = crate::rc::__RcTestObject::new();
+        assert_eq!(counter.count::<crate::rc::__RcTestObject>(), 1);
+        assert_eq!(counter.count::<NSObject>(), 0);
+    }
+
+    #[test]
+    fn test_max_allocations() {
+        let obj = assert_max_allocations(1, NSObject::new);
+        assert_max_allocations(0, || drop(obj));
+    }
+
+    #[test]
+    #[should_panic = "expected at most 1 allocations, but found 2: {\"NSObject\": 2}"]
+    fn test_max_allocations_exceeded() {
+        assert_max_allocations(1, || (NSObject::new(), NSObject::new()));
+    }
+
+    #[test]
+    #[should_panic = "leaked instance of NSObject"]
+    fn test_leak() {
+        let mut leaked = None;
+        assert_no_leak(|| {
+            let obj = NSObject::new();
+            leaked = Some(obj.clone());
+            obj
+        });
+    }
+}
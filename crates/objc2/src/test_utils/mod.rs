@@ -0,0 +1,48 @@
+//! # Helpers for testing code that uses Objective-C objects.
+//!
+//! These are meant for use in the test suites of crates that bind to, or
+//! declare, Objective-C classes, and are only available when the
+//! `"test-utils"` Cargo feature is enabled.
+//!
+//! - [`assert_class!`] checks that an object is an instance of a class.
+//! - [`assert_description_snapshot!`] compares the `description` of an
+//!   object against an expected string, with pointer addresses normalized.
+//! - [`retain_count`] reads the retain count of an object.
+//! - [`assert_no_leak`] checks that an object is deallocated once it and the
+//!   autorelease pool it was created in are gone.
+//! - [`AllocationCounter`] and [`assert_max_allocations`] count the objects
+//!   allocated on the current thread, for asserting that an operation does
+//!   not allocate more than expected.
+//!
+//! [`assert_class!`]: crate::assert_class
+//! [`assert_description_snapshot!`]: crate::assert_description_snapshot
+//!
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "apple", doc = "```")]
+#![cfg_attr(not(feature = "apple"), doc = "```no_run")]
+//! use objc2::runtime::NSObject;
+//! use objc2::test_utils::{assert_no_leak, retain_count};
+//! use objc2::{assert_class, assert_description_snapshot};
+//!
+//! let obj = NSObject::new();
+//! assert_class!(obj, NSObject);
+//! assert_description_snapshot!(obj, "<NSObject: 0x0>");
+//! assert_eq!(retain_count(&*obj), 1);
+//!
+//! assert_no_leak(NSObject::new);
+//! ```
+
+#[cfg(feature = "test-utils")]
+mod helpers;
+// The test classes are built with APIs that are not available on ObjFW.
+#[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
+mod classes;
+
+#[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
+pub(crate) use self::classes::*;
+#[cfg(feature = "test-utils")]
+pub use self::helpers::*;
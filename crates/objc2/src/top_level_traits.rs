@@ -1,3 +1,4 @@
+use core::pin::Pin;
 use core::ptr::NonNull;
 
 use crate::__macro_helpers::declared_ivars::get_initialized_ivar_ptr;
@@ -5,7 +6,7 @@ use crate::encode::RefEncode;
 use crate::msg_send_id;
 use crate::mutability::{IsAllocableAnyThread, IsRetainable, Mutability};
 use crate::rc::{Allocated, Id};
-use crate::runtime::{AnyClass, AnyProtocol};
+use crate::runtime::{AnyClass, AnyProtocol, ClassRegistrationError};
 
 /// Types that can be sent Objective-C messages.
 ///
@@ -310,6 +311,27 @@ pub unsafe trait ClassType: Message {
     // TODO: `fn mtm(&self) -> MainThreadMarker where T::Mutability: MainThreadOnly`
 }
 
+/// Marks that a type is the same class as, or a subclass of, `T`.
+///
+/// This is implemented by [`extern_class!`] and [`declare_class!`] for the
+/// superclass and all the classes listed in `#[inherits(...)]` (and for
+/// [`AnyObject`]), and allows converting between [`Id`]s in the whole
+/// inheritance chain at once with [`Id::into_ancestor`].
+///
+/// [`extern_class!`]: crate::extern_class
+/// [`declare_class!`]: crate::declare_class
+/// [`AnyObject`]: crate::runtime::AnyObject
+///
+///
+/// # Safety
+///
+/// Instances of the type must be valid instances of `T`, such that it is
+/// safe to reinterpret a pointer to the type as a pointer to `T`.
+pub unsafe trait Inherits<T: ?Sized + Message>: Message {}
+
+// SAFETY: Any type can be reinterpreted as itself.
+unsafe impl<T: ?Sized + Message> Inherits<T> for T {}
+
 /// Marks types whose implementation is defined in Rust.
 ///
 /// This is used in [`declare_class!`], and allows access to the instance
@@ -344,11 +366,16 @@ pub trait DeclaredClass: ClassType {
 
     /// Get a mutable reference to the instance variable data that this object
     /// carries.
+    ///
+    /// This requires the instance variables to be [`Unpin`], since they are
+    /// pinned, see [`ivars_pinned`](Self::ivars_pinned). Use
+    /// [`ivars_pinned_mut`](Self::ivars_pinned_mut) otherwise.
     #[inline]
     #[track_caller]
     fn ivars_mut(&mut self) -> &mut Self::Ivars
     where
         Self: Sized, // Required because of MSRV
+        Self::Ivars: Unpin,
     {
         let ptr: NonNull<Self> = NonNull::from(self);
         // SAFETY: The pointer is valid and initialized.
@@ -361,6 +388,175 @@ pub trait DeclaredClass: ClassType {
         unsafe { ivars.as_mut() }
     }
 
+    /// Get a pinned reference to the instance variable data that this object
+    /// carries.
+    ///
+    /// Objective-C objects are allocated by the runtime, and are never moved
+    /// once allocated, so the instance variables are always pinned; they are
+    /// dropped in place when the object is deallocated (see
+    /// [`did_dealloc`](Self::did_dealloc) for the exact order), and
+    /// [`ivars_mut`](Self::ivars_mut) is only available for [`Unpin`] types.
+    ///
+    /// This allows storing self-referential types, such as intrusive lists or
+    /// futures, in the instance variables.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::marker::PhantomPinned;
+    /// use std::pin::Pin;
+    ///
+    /// use objc2::rc::{Allocated, Id};
+    /// use objc2::runtime::NSObject;
+    /// use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
+    ///
+    /// struct Node {
+    ///     value: u32,
+    ///     _pin: PhantomPinned,
+    /// }
+    ///
+    /// impl Node {
+    ///     fn value(self: Pin<&Self>) -> u32 {
+    ///         self.value
+    ///     }
+    /// }
+    ///
+    /// declare_class!(
+    ///     struct HasNode;
+    ///
+    ///     unsafe impl ClassType for HasNode {
+    ///         type Super = NSObject;
+    ///         type Mutability = mutability::Mutable;
+    ///         const NAME: &'static str = "HasNode";
+    ///     }
+    ///
+    ///     impl DeclaredClass for HasNode {
+    ///         type Ivars = Node;
+    ///     }
+    /// );
+    ///
+    /// let obj: Allocated<HasNode> = HasNode::alloc();
+    /// let obj = obj.set_ivars(Node { value: 42, _pin: PhantomPinned });
+    /// let obj: Id<HasNode> = unsafe { msg_send_id![super(obj), init] };
+    ///
+    /// assert_eq!(obj.ivars_pinned().value(), 42);
+    /// ```
+    #[inline]
+    #[track_caller]
+    fn ivars_pinned(&self) -> Pin<&Self::Ivars>
+    where
+        Self: Sized, // Required because of MSRV
+    {
+        // SAFETY: The instance variables are never moved, see above.
+        unsafe { Pin::new_unchecked(self.ivars()) }
+    }
+
+    /// Get a pinned mutable reference to the instance variable data that
+    /// this object carries.
+    ///
+    /// See [`ivars_pinned`](Self::ivars_pinned) for details.
+    #[inline]
+    #[track_caller]
+    fn ivars_pinned_mut(&mut self) -> Pin<&mut Self::Ivars>
+    where
+        Self: Sized, // Required because of MSRV
+    {
+        let ptr: NonNull<Self> = NonNull::from(self);
+        // SAFETY: The pointer is valid and initialized.
+        let mut ivars = unsafe { get_initialized_ivar_ptr(ptr) };
+        // SAFETY: Same as in `ivars_mut`, and the instance variables are
+        // never moved, see `ivars_pinned`.
+        unsafe { Pin::new_unchecked(ivars.as_mut()) }
+    }
+
+    /// A hook that is called when the object is deallocated.
+    ///
+    /// This is called from `dealloc` on fully initialized objects, before
+    /// anything has been torn down, so the object, its instance variables
+    /// and its superclass are all still valid. This makes it the place to do
+    /// cleanup that requires the object, such as invalidating timers or
+    /// removing the object as an observer.
+    ///
+    /// The order of operations in `dealloc` is:
+    /// 1. This hook.
+    /// 2. The [`Drop`] implementation of the class, if any.
+    /// 3. The drop glue of [`Ivars`](Self::Ivars).
+    /// 4. The superclass' `dealloc`.
+    ///
+    /// Note that neither this nor [`Drop`] is run if the object was never
+    /// fully initialized, e.g. if an initializer returned `nil`; the
+    /// instance variables are still dropped in that case, if they were set.
+    ///
+    /// Implement this inside the `impl DeclaredClass` block in
+    /// [`declare_class!`]; the default does nothing.
+    ///
+    /// [`declare_class!`]: crate::declare_class
+    #[inline]
+    fn did_dealloc(&mut self)
+    where
+        Self: Sized, // Required because of MSRV
+    {
+    }
+
+    /// Register the class with the Objective-C runtime, or get it if it has
+    /// already been registered.
+    ///
+    /// This is the fallible version of [`ClassType::class`], which panics if
+    /// registering the class fails. The first call does the registration,
+    /// and later calls return the same class or error.
+    ///
+    /// This is implemented by [`declare_class!`].
+    ///
+    /// [`declare_class!`]: crate::declare_class
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Fails if a class with the same name already exists, if one of the
+    /// methods, instance variables or protocols could not be added, if a
+    /// method's selector does not have the same number of arguments as the
+    /// function, or if the instance variables have an alignment that the
+    /// runtime does not support. When debug assertions are enabled, this
+    /// also fails if a method does not match the method it overrides, if a
+    /// required protocol method is missing, or if a proxy class does not
+    /// override the forwarding methods (in which case the class has already
+    /// been registered). The error describes which selector, instance
+    /// variable or protocol caused the failure.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// On ObjFW, panics if the class has instance variables, since those
+    /// cannot be added at runtime there.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2::runtime::NSObject;
+    /// use objc2::{declare_class, mutability, ClassType, DeclaredClass};
+    ///
+    /// declare_class!(
+    ///     struct AlreadyExists;
+    ///
+    ///     unsafe impl ClassType for AlreadyExists {
+    ///         type Super = NSObject;
+    ///         type Mutability = mutability::InteriorMutable;
+    ///         const NAME: &'static str = "NSObject";
+    ///     }
+    ///
+    ///     impl DeclaredClass for AlreadyExists {}
+    /// );
+    ///
+    /// let err = AlreadyExists::try_register().unwrap_err();
+    /// assert_eq!(err.class_name(), "NSObject");
+    /// ```
+    fn try_register() -> Result<&'static AnyClass, ClassRegistrationError>;
+
+    #[doc(hidden)]
+    const __HAS_DID_DEALLOC: bool = false;
+
     #[doc(hidden)]
     fn __ivars_offset() -> isize;
 
@@ -437,7 +633,15 @@ pub unsafe trait ProtocolType {
     /// protocol, e.g. if the program is not properly linked to the framework
     /// that defines the protocol.
     fn protocol() -> Option<&'static AnyProtocol> {
-        AnyProtocol::get(Self::NAME)
+        // ObjFW does not support looking up protocols at runtime.
+        #[cfg(feature = "unstable-objfw")]
+        {
+            None
+        }
+        #[cfg(not(feature = "unstable-objfw"))]
+        {
+            AnyProtocol::get(Self::NAME)
+        }
     }
 
     #[doc(hidden)]
@@ -1,33 +1,46 @@
+#[cfg(not(feature = "unstable-objfw"))]
+use core::borrow::Borrow;
 use core::fmt;
 use core::hash::Hash;
 use std::error::Error;
 
+#[cfg(not(feature = "unstable-objfw"))]
 use crate::encode::{Encoding, EncodingBox};
-use crate::runtime::{EncodingParseError, Method};
+use crate::runtime::EncodingParseError;
+#[cfg(not(feature = "unstable-objfw"))]
+use crate::runtime::{verify_receiver, verify_sel, Method};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub(crate) enum Inner {
+    #[cfg(not(feature = "unstable-objfw"))]
     MethodNotFound,
     EncodingParseError(EncodingParseError),
+    #[cfg(not(feature = "unstable-objfw"))]
     MismatchedReturn(EncodingBox, Encoding),
+    #[cfg(not(feature = "unstable-objfw"))]
     MismatchedArgumentsCount(usize, usize),
+    #[cfg(not(feature = "unstable-objfw"))]
     MismatchedArgument(usize, EncodingBox, Encoding),
 }
 
 impl fmt::Display for Inner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            #[cfg(not(feature = "unstable-objfw"))]
             Self::MethodNotFound => write!(f, "method not found"),
             Self::EncodingParseError(e) => write!(f, "{e}"),
+            #[cfg(not(feature = "unstable-objfw"))]
             Self::MismatchedReturn(expected, actual) => {
                 write!(
                     f,
                     "expected return to have type code '{expected}', but found '{actual}'",
                 )
             }
+            #[cfg(not(feature = "unstable-objfw"))]
             Self::MismatchedArgumentsCount(expected, actual) => {
                 write!(f, "expected {expected} arguments, but {actual} were given",)
             }
+            #[cfg(not(feature = "unstable-objfw"))]
             Self::MismatchedArgument(i, expected, actual) => {
                 write!(
                     f,
@@ -76,6 +89,7 @@ impl Error for VerificationError {}
 ///
 /// Note: This is a top-level comparison; `*mut *mut c_void` or structures
 /// containing `*mut c_void` are not allowed differently than usual.
+#[cfg(not(feature = "unstable-objfw"))]
 fn relaxed_equivalent_to_box(encoding: &Encoding, expected: &EncodingBox) -> bool {
     if cfg!(feature = "relax-void-encoding")
         && matches!(encoding, Encoding::Pointer(&Encoding::Void))
@@ -87,21 +101,46 @@ fn relaxed_equivalent_to_box(encoding: &Encoding, expected: &EncodingBox) -> boo
     }
 }
 
+#[cfg(not(feature = "unstable-objfw"))]
 pub(crate) fn verify_method_signature(
     method: &Method,
     args: &[Encoding],
     ret: &Encoding,
 ) -> Result<(), VerificationError> {
-    let mut iter = method.types();
+    let expected_count = method.name().number_of_arguments();
+
+    #[cfg(feature = "encoding-cache")]
+    if let Some(types) = crate::encoding_cache::method_types(method.type_encoding()) {
+        let iter = types
+            .iter()
+            .map(|(enc, stack_layout)| Ok((enc, *stack_layout)));
+        return verify_types(iter, expected_count, args, ret);
+    }
 
+    verify_types(method.types(), expected_count, args, ret)
+}
+
+/// Verify the parsed types of a method against the expected encodings.
+///
+/// This is generic over the encoding, so that it can use both freshly
+/// parsed encodings, and encodings borrowed from the encoding cache.
+#[cfg(not(feature = "unstable-objfw"))]
+fn verify_types<E: Borrow<EncodingBox>>(
+    mut iter: impl Iterator<Item = Result<(E, Option<isize>), EncodingParseError>>,
+    expected_count: usize,
+    args: &[Encoding],
+    ret: &Encoding,
+) -> Result<(), VerificationError> {
     // TODO: Verify stack layout
-    let (expected, _stack_layout) = iter.extract_return()?;
-    if !relaxed_equivalent_to_box(ret, &expected) {
-        return Err(Inner::MismatchedReturn(expected, ret.clone()).into());
+    let (expected, _stack_layout) = iter.next().ok_or(EncodingParseError::MissingReturn)??;
+    if !relaxed_equivalent_to_box(ret, expected.borrow()) {
+        return Err(Inner::MismatchedReturn(expected.borrow().clone(), ret.clone()).into());
     }
 
-    iter.verify_receiver()?;
-    iter.verify_sel()?;
+    let (receiver, _stack_layout) = iter.next().ok_or(EncodingParseError::MissingReceiver)??;
+    verify_receiver(receiver.borrow())?;
+    let (sel, _stack_layout) = iter.next().ok_or(EncodingParseError::MissingSel)??;
+    verify_sel(sel.borrow())?;
 
     let actual_count = args.len();
 
@@ -109,8 +148,13 @@ pub(crate) fn verify_method_signature(
         if let Some(res) = iter.next() {
             // TODO: Verify stack layout
             let (expected, _stack_layout) = res?;
-            if !relaxed_equivalent_to_box(actual, &expected) {
-                return Err(Inner::MismatchedArgument(i, expected, actual.clone()).into());
+            if !relaxed_equivalent_to_box(actual, expected.borrow()) {
+                return Err(Inner::MismatchedArgument(
+                    i,
+                    expected.borrow().clone(),
+                    actual.clone(),
+                )
+                .into());
             }
         } else {
             return Err(Inner::MismatchedArgumentsCount(i, actual_count).into());
@@ -122,7 +166,6 @@ pub(crate) fn verify_method_signature(
         return Err(Inner::MismatchedArgumentsCount(actual_count + remaining, actual_count).into());
     }
 
-    let expected_count = method.name().number_of_arguments();
     if expected_count != actual_count {
         return Err(Inner::MismatchedArgumentsCount(expected_count, actual_count).into());
     }
@@ -131,6 +174,7 @@ pub(crate) fn verify_method_signature(
 }
 
 #[cfg(test)]
+#[cfg(not(feature = "unstable-objfw"))]
 mod tests {
     use super::*;
     use crate::runtime::Sel;
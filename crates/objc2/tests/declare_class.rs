@@ -2,9 +2,9 @@
 use core::ptr::{self, NonNull};
 
 use objc2::mutability::Immutable;
-use objc2::rc::Id;
+use objc2::rc::{Allocated, Id};
 use objc2::runtime::NSObject;
-use objc2::{declare_class, extern_methods, sel, ClassType, DeclaredClass};
+use objc2::{declare_class, extern_methods, msg_send, msg_send_id, sel, ClassType, DeclaredClass};
 
 // Test that adding the `deprecated` attribute does not mean that warnings
 // when using the method internally are output.
@@ -260,6 +260,69 @@ fn test_multiple_colon_selector() {
     assert!(obj.test_object(1, 2, 3, ptr::null()).is_none());
 }
 
+declare_class!(
+    struct TestDerivedSelector;
+
+    unsafe impl ClassType for TestDerivedSelector {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "TestDerivedSelector";
+    }
+
+    impl DeclaredClass for TestDerivedSelector {}
+
+    unsafe impl TestDerivedSelector {
+        #[method(isEnabled)]
+        fn _is_enabled(&self) -> bool {
+            true
+        }
+
+        #[method(valueForKey:)]
+        fn _value_for_key(&self, key: i32) -> i32 {
+            key * 2
+        }
+
+        #[method(addValue:toValue:)]
+        fn _add_value(arg1: i32, arg2: i32) -> i32 {
+            arg1 + arg2
+        }
+
+        #[method_id(objectWithValue:)]
+        fn _object_with_value(&self, _value: i32) -> Option<Id<Self>> {
+            None
+        }
+    }
+);
+
+extern_methods!(
+    unsafe impl TestDerivedSelector {
+        #[method_id]
+        fn new() -> Id<Self>;
+
+        #[method]
+        fn is_enabled(&self) -> bool;
+
+        #[method]
+        fn value_for_key(&self, key: i32) -> i32;
+
+        #[method]
+        fn add_value(value: i32, to_value: i32) -> i32;
+
+        #[method_id]
+        fn object_with_value(&self, value: i32) -> Option<Id<Self>>;
+    }
+);
+
+#[test]
+fn test_derived_selector() {
+    assert_eq!(TestDerivedSelector::add_value(2, 3), 5);
+
+    let obj = TestDerivedSelector::new();
+    assert!(obj.is_enabled());
+    assert_eq!(obj.value_for_key(4), 8);
+    assert!(obj.object_with_value(1).is_none());
+}
+
 declare_class!(
     struct DeclareClassAllTheBool;
 
@@ -427,6 +490,10 @@ extern_methods!(
     not(all(target_pointer_width = "64", not(feature = "catch-all"))),
     ignore = "unwinds through FFI boundary"
 )]
+#[cfg_attr(
+    feature = "unstable-mock-runtime",
+    ignore = "the mock runtime aborts on panics in methods it calls"
+)]
 fn out_param1() {
     let mut param = OutParam::new();
     OutParam::unsupported1(&mut param);
@@ -438,6 +505,10 @@ fn out_param1() {
     not(all(target_pointer_width = "64", not(feature = "catch-all"))),
     ignore = "unwinds through FFI boundary"
 )]
+#[cfg_attr(
+    feature = "unstable-mock-runtime",
+    ignore = "the mock runtime aborts on panics in methods it calls"
+)]
 fn out_param2() {
     OutParam::unsupported2(None);
 }
@@ -448,6 +519,10 @@ fn out_param2() {
     not(all(target_pointer_width = "64", not(feature = "catch-all"))),
     ignore = "unwinds through FFI boundary"
 )]
+#[cfg_attr(
+    feature = "unstable-mock-runtime",
+    ignore = "the mock runtime aborts on panics in methods it calls"
+)]
 fn out_param3() {
     let mut param = Some(OutParam::new());
     OutParam::unsupported3(&mut param);
@@ -459,6 +534,10 @@ fn out_param3() {
     not(all(target_pointer_width = "64", not(feature = "catch-all"))),
     ignore = "unwinds through FFI boundary"
 )]
+#[cfg_attr(
+    feature = "unstable-mock-runtime",
+    ignore = "the mock runtime aborts on panics in methods it calls"
+)]
 fn out_param4() {
     OutParam::unsupported4(None);
 }
@@ -491,3 +570,162 @@ fn test_pointer_receiver_allowed() {
 
     let _ = PointerReceiver::class();
 }
+
+declare_class!(
+    struct FallibleInit;
+
+    unsafe impl ClassType for FallibleInit {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "FallibleInit";
+    }
+
+    impl DeclaredClass for FallibleInit {
+        type Ivars = u32;
+    }
+
+    unsafe impl FallibleInit {
+        #[method_id(initWithValue:)]
+        fn _init_with_value(this: Allocated<Self>, value: i32) -> Option<Id<Self>> {
+            match u32::try_from(value) {
+                Ok(value) => {
+                    let this = this.set_ivars(value);
+                    unsafe { msg_send_id![super(this), init] }
+                }
+                Err(_) => None,
+            }
+        }
+
+        #[method_id(initWithValue:error:_)]
+        fn _init_with_value_error(
+            this: Allocated<Self>,
+            value: i32,
+        ) -> Result<Id<Self>, Id<NSObject>> {
+            let value = u32::try_from(value).map_err(|_| NSObject::new())?;
+            let this = this.set_ivars(value);
+            Ok(unsafe { msg_send_id![super(this), init] })
+        }
+
+        #[method_id(fallibleWithError:_)]
+        fn _new_error() -> Result<Id<Self>, Id<NSObject>> {
+            Err(NSObject::new())
+        }
+
+        #[method(checkValue:error:_)]
+        fn _check_value(&self, value: u32) -> Result<(), Id<NSObject>> {
+            if *self.ivars() == value {
+                Ok(())
+            } else {
+                Err(NSObject::new())
+            }
+        }
+    }
+);
+
+extern_methods!(
+    unsafe impl FallibleInit {
+        #[method_id(initWithValue:)]
+        fn init_with_value(this: Allocated<Self>, value: i32) -> Option<Id<Self>>;
+
+        #[method_id(initWithValue:error:_)]
+        fn init_with_value_error(
+            this: Allocated<Self>,
+            value: i32,
+        ) -> Result<Id<Self>, Id<NSObject>>;
+
+        #[method_id(fallibleWithError:_)]
+        fn new_error() -> Result<Id<Self>, Id<NSObject>>;
+
+        #[method(checkValue:error:_)]
+        fn check_value(&self, value: u32) -> Result<(), Id<NSObject>>;
+    }
+);
+
+#[test]
+fn test_fallible_init() {
+    let obj = FallibleInit::init_with_value(FallibleInit::alloc(), 3).unwrap();
+    assert_eq!(*obj.ivars(), 3);
+    assert!(FallibleInit::init_with_value(FallibleInit::alloc(), -1).is_none());
+}
+
+#[test]
+fn test_fallible_error() {
+    let obj = FallibleInit::init_with_value_error(FallibleInit::alloc(), 5).unwrap();
+    assert_eq!(*obj.ivars(), 5);
+    let res = FallibleInit::init_with_value_error(FallibleInit::alloc(), -1);
+    assert_eq!(res.err().unwrap().class(), NSObject::class());
+
+    assert!(FallibleInit::new_error().is_err());
+
+    assert!(obj.check_value(5).is_ok());
+    assert!(obj.check_value(4).is_err());
+
+    // The error parameter may be NULL
+    let res: bool =
+        unsafe { msg_send![&obj, checkValue: 4u32, error: ptr::null_mut::<*mut NSObject>()] };
+    assert!(!res);
+}
+
+declare_class!(
+    struct DeclareClassExisting;
+
+    unsafe impl ClassType for DeclareClassExisting {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "NSObject";
+    }
+
+    impl DeclaredClass for DeclareClassExisting {}
+);
+
+#[test]
+fn test_try_register_existing() {
+    let err = DeclareClassExisting::try_register().unwrap_err();
+    assert_eq!(err.class_name(), "NSObject");
+    assert_eq!(err.selector(), None);
+    assert_eq!(
+        err.to_string(),
+        "could not create new class NSObject. Perhaps a class with that name already exists?"
+    );
+    // Returns the same error again.
+    assert_eq!(DeclareClassExisting::try_register().unwrap_err(), err);
+}
+
+#[test]
+#[should_panic = "could not create new class NSObject. Perhaps a class with that name already exists?"]
+fn test_class_existing() {
+    let _ = DeclareClassExisting::class();
+}
+
+declare_class!(
+    struct DeclareClassInvalidOverride;
+
+    unsafe impl ClassType for DeclareClassInvalidOverride {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "DeclareClassInvalidOverride";
+    }
+
+    impl DeclaredClass for DeclareClassInvalidOverride {}
+
+    unsafe impl DeclareClassInvalidOverride {
+        #[method(hash)]
+        fn hash(&self) -> i8 {
+            0
+        }
+    }
+);
+
+#[test]
+#[cfg(debug_assertions)]
+fn test_try_register_invalid_override() {
+    let err = DeclareClassInvalidOverride::try_register().unwrap_err();
+    assert_eq!(err.class_name(), "DeclareClassInvalidOverride");
+    assert_eq!(err.selector(), Some(sel!(hash)));
+    assert!(!err.is_class_method());
+    assert!(
+        err.to_string()
+            .starts_with("declared invalid method -[DeclareClassInvalidOverride hash]: "),
+        "{err}"
+    );
+}
@@ -0,0 +1,192 @@
+use objc2::mutability::Immutable;
+use objc2::rc::{__RcTestObject, __ThreadTestData, autoreleasepool, Allocated, Id};
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{declare_class, extern_class, extern_methods, msg_send_id, ClassType, DeclaredClass};
+
+declare_class!(
+    struct ComparableValue;
+
+    unsafe impl ClassType for ComparableValue {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "ComparableValue";
+    }
+
+    impl DeclaredClass for ComparableValue {
+        type Ivars = i32;
+    }
+
+    unsafe impl ComparableValue {
+        #[method(isEqual:)]
+        fn is_equal(&self, other: &AnyObject) -> bool {
+            let other: &Self = unsafe { &*(other as *const AnyObject).cast() };
+            self.ivars() == other.ivars()
+        }
+
+        #[method(compare:)]
+        fn compare(&self, other: &Self) -> isize {
+            self.ivars().cmp(other.ivars()) as isize
+        }
+    }
+);
+
+impl ComparableValue {
+    fn new(value: i32) -> Id<Self> {
+        let this = Self::alloc().set_ivars(value);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+// Test `#[compare]` on an externally declared class.
+extern_class!(
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    #[compare]
+    struct ExternComparable;
+
+    unsafe impl ClassType for ExternComparable {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "ComparableValue";
+    }
+);
+
+fn value(value: i32) -> Id<ExternComparable> {
+    // Ensure the class is registered.
+    let _ = ComparableValue::class();
+    unsafe { Id::cast(ComparableValue::new(value)) }
+}
+
+#[test]
+fn test_compare() {
+    let one = value(1);
+    let two = value(2);
+
+    assert_eq!(one, value(1));
+    assert_ne!(one, two);
+    assert!(one < two);
+    assert!(two > one);
+    assert_eq!(one.cmp(&value(1)), core::cmp::Ordering::Equal);
+
+    let mut values = vec![value(3), value(1), value(2)];
+    values.sort();
+    assert_eq!(values, [value(1), value(2), value(3)]);
+}
+
+declare_class!(
+    struct Described;
+
+    unsafe impl ClassType for Described {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "Described";
+    }
+
+    impl DeclaredClass for Described {}
+
+    unsafe impl Described {
+        #[method_id(description)]
+        fn description(&self) -> Id<NSObject> {
+            unsafe { msg_send_id![&*NSObject::new(), description] }
+        }
+
+        #[method_id(debugDescription)]
+        fn debug_description(&self) -> Option<Id<NSObject>> {
+            None
+        }
+    }
+);
+
+// Test `#[display]` and `#[debug_description]` on an externally declared
+// class.
+extern_class!(
+    #[display]
+    #[debug_description]
+    struct ExternDescribed;
+
+    unsafe impl ClassType for ExternDescribed {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "Described";
+    }
+);
+
+#[test]
+fn test_description() {
+    let _ = Described::class();
+    let obj: Id<ExternDescribed> = unsafe { msg_send_id![ExternDescribed::class(), new] };
+
+    let display = format!("{obj}");
+    assert!(display.starts_with("<NSObject: 0x"), "{display}");
+    assert_eq!(format!("{obj:?}"), "(null)");
+}
+
+// Test fallible initializers on an externally declared class.
+extern_class!(
+    #[derive(Debug)]
+    struct ExternRcTestObject;
+
+    unsafe impl ClassType for ExternRcTestObject {
+        type Super = NSObject;
+        type Mutability = Immutable;
+        const NAME: &'static str = "__RcTestObject";
+    }
+);
+
+extern_methods!(
+    unsafe impl ExternRcTestObject {
+        #[method_id(initReturningNull)]
+        fn init_returning_null(this: Allocated<Self>) -> Option<Id<Self>>;
+
+        #[method_id(initAndShouldError:error:_)]
+        fn init_and_should_error(
+            this: Allocated<Self>,
+            should_error: bool,
+        ) -> Result<Id<Self>, Id<__RcTestObject>>;
+    }
+);
+
+#[test]
+fn test_fallible_init() {
+    let _ = __RcTestObject::class();
+    let mut expected = __ThreadTestData::current();
+
+    // The allocated object is released when the initializer fails
+    let obj = ExternRcTestObject::init_returning_null(ExternRcTestObject::alloc());
+    assert!(obj.is_none());
+    expected.alloc += 1;
+    expected.release += 1;
+    expected.assert_current();
+
+    let obj = autoreleasepool(|_pool| {
+        let res = ExternRcTestObject::init_and_should_error(ExternRcTestObject::alloc(), false);
+        expected.alloc += 1;
+        expected.init += 1;
+        expected.assert_current();
+        res.unwrap()
+    });
+    expected.assert_current();
+
+    drop(obj);
+    expected.release += 1;
+    expected.drop += 1;
+    expected.assert_current();
+
+    let err = autoreleasepool(|_pool| {
+        let res = ExternRcTestObject::init_and_should_error(ExternRcTestObject::alloc(), true);
+        // The allocated object, and the newly created error
+        expected.alloc += 2;
+        expected.release += 1;
+        expected.init += 1;
+        expected.autorelease += 1;
+        expected.retain += 1;
+        expected.assert_current();
+        res.unwrap_err()
+    });
+    expected.release += 1;
+    expected.assert_current();
+
+    drop(err);
+    expected.release += 1;
+    expected.drop += 1;
+    expected.assert_current();
+}
@@ -15,6 +15,10 @@ fn create_obj() -> Id<NSObject> {
 }
 
 #[test]
+#[cfg_attr(
+    feature = "unstable-mock-runtime",
+    ignore = "the mock runtime only reclaims objects from `objc_autoreleaseReturnValue`"
+)]
 fn test_retain_autoreleased() {
     autoreleasepool(|_| {
         // Run once to allow DYLD to resolve the symbol stubs.
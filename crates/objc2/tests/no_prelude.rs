@@ -9,7 +9,7 @@
 
 extern crate objc2 as new_objc2;
 
-use new_objc2::{ClassType, DeclaredClass, ProtocolType};
+use new_objc2::{ClassType, DeclaredClass, Message, ProtocolType};
 
 mod core {}
 mod std {}
@@ -139,6 +139,37 @@ new_objc2::extern_class!(
     }
 );
 
+new_objc2::extern_class!(
+    #[derive(PartialEq, Eq)]
+    #[compare]
+    #[display]
+    #[debug_description]
+    struct NSObject4;
+
+    unsafe impl ClassType for NSObject4 {
+        type Super = new_objc2::runtime::NSObject;
+        type Mutability = new_objc2::mutability::Immutable;
+        const NAME: &'static str = "NSObject";
+    }
+);
+
+new_objc2::extern_class!(
+    struct NSObject3<T>;
+
+    unsafe impl<T: Message> ClassType for NSObject3<T> {
+        type Super = new_objc2::runtime::NSObject;
+        type Mutability = new_objc2::mutability::Immutable;
+        const NAME: &'static str = "NSObject";
+    }
+);
+
+new_objc2::extern_methods!(
+    unsafe impl<T: Message> NSObject3<T> {
+        #[method(a)]
+        fn a(&self);
+    }
+);
+
 new_objc2::extern_protocol!(
     #[allow(clippy::missing_safety_doc)]
     unsafe trait CustomProtocol {
@@ -149,6 +180,21 @@ new_objc2::extern_protocol!(
     unsafe impl ProtocolType for dyn CustomProtocol {}
 );
 
+new_objc2::ns_enum!(
+    enum CustomEnum: new_objc2::ffi::NSInteger {
+        A = 1,
+        B = 2,
+        Unknown(_),
+    }
+);
+
+new_objc2::ns_options!(
+    struct CustomOptions: new_objc2::ffi::NSUInteger {
+        const A = 1;
+        const B = 2;
+    }
+);
+
 #[test]
 fn test_selector() {
     let _sel = new_objc2::sel!(abc);
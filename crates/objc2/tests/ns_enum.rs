@@ -0,0 +1,92 @@
+use objc2::ffi::NSInteger;
+use objc2::mutability::InteriorMutable;
+use objc2::rc::Id;
+use objc2::runtime::NSObject;
+use objc2::{declare_class, msg_send, msg_send_id, ns_enum, ClassType, DeclaredClass};
+
+ns_enum!(
+    #[allow(dead_code)]
+    enum TestEnum: NSInteger {
+        A = 0,
+        B = 1,
+        C = TestEnum::B.to_raw() + 2,
+        #[deprecated = "use A instead"]
+        OldA = -1,
+        #[cfg(any())]
+        Disabled = 4,
+        Other(_),
+    }
+);
+
+ns_enum!(
+    pub enum UnknownFirst: u8 {
+        Unknown(_),
+        Known = 1,
+    }
+);
+
+#[test]
+fn test_raw() {
+    assert_eq!(TestEnum::from_raw(0), TestEnum::A);
+    assert_eq!(TestEnum::from_raw(1), TestEnum::B);
+    assert_eq!(TestEnum::from_raw(3), TestEnum::C);
+    assert_eq!(TestEnum::from_raw(4), TestEnum::Other(4));
+    assert_eq!(TestEnum::from_raw(-2), TestEnum::Other(-2));
+
+    assert_eq!(TestEnum::A.to_raw(), 0);
+    assert_eq!(TestEnum::C.to_raw(), 3);
+    assert_eq!(TestEnum::Other(42).to_raw(), 42);
+
+    #[allow(deprecated)]
+    {
+        assert_eq!(TestEnum::from_raw(-1), TestEnum::OldA);
+        assert_eq!(TestEnum::OldA.to_raw(), -1);
+    }
+
+    assert_eq!(TestEnum::from(1), TestEnum::B);
+    assert_eq!(NSInteger::from(TestEnum::Other(7)), 7);
+
+    assert_eq!(UnknownFirst::from_raw(1), UnknownFirst::Known);
+    assert_eq!(UnknownFirst::from_raw(0), UnknownFirst::Unknown(0));
+    assert_eq!(format!("{:?}", UnknownFirst::Unknown(5)), "Unknown(5)");
+}
+
+declare_class!(
+    struct EnumEcho;
+
+    unsafe impl ClassType for EnumEcho {
+        type Super = NSObject;
+        type Mutability = InteriorMutable;
+        const NAME: &'static str = "NSEnumTestEcho";
+    }
+
+    impl DeclaredClass for EnumEcho {}
+
+    unsafe impl EnumEcho {
+        #[method(echo:)]
+        fn echo(&self, value: TestEnum) -> TestEnum {
+            value
+        }
+
+        #[method(echoRaw:)]
+        fn echo_raw(&self, value: NSInteger) -> NSInteger {
+            value
+        }
+    }
+);
+
+#[test]
+fn test_message_send() {
+    let obj: Id<EnumEcho> = unsafe { msg_send_id![EnumEcho::class(), new] };
+
+    let res: TestEnum = unsafe { msg_send![&obj, echo: TestEnum::B] };
+    assert_eq!(res, TestEnum::B);
+
+    // Values unknown to Rust round-trip unchanged.
+    let res: TestEnum = unsafe { msg_send![&obj, echo: TestEnum::Other(1234)] };
+    assert_eq!(res, TestEnum::Other(1234));
+    let res: TestEnum = unsafe { msg_send![&obj, echoRaw: 1234 as NSInteger] };
+    assert_eq!(res, TestEnum::Other(1234));
+    let res: NSInteger = unsafe { msg_send![&obj, echo: TestEnum::C] };
+    assert_eq!(res, 3);
+}
@@ -0,0 +1,92 @@
+#![allow(non_upper_case_globals)]
+use objc2::encode::{Encode, Encoding, RefEncode};
+use objc2::ffi::NSUInteger;
+use objc2::ns_options;
+
+ns_options!(
+    struct TestOptions: NSUInteger {
+        const None = 0;
+        const A = 1 << 0;
+        const B = 1 << 1;
+        const C = 1 << 2;
+        const AB = TestOptions::A.0 | TestOptions::B.0;
+        #[deprecated = "use C instead"]
+        const OldC = 1 << 2;
+        #[cfg(any())]
+        const Disabled = 1 << 3;
+    }
+);
+
+ns_options!(
+    pub struct SmallOptions: u8 {
+        const X = 0x80;
+    }
+);
+
+#[test]
+fn test_ops() {
+    let mut options = TestOptions::A | TestOptions::C;
+    assert_eq!(options.bits(), 0b101);
+    assert!(options.contains(TestOptions::A));
+    assert!(!options.contains(TestOptions::AB));
+    assert!(options.intersects(TestOptions::AB));
+    assert!(options.contains(TestOptions::None));
+
+    options.insert(TestOptions::B);
+    assert!(options.contains(TestOptions::AB));
+    options.remove(TestOptions::A);
+    assert_eq!(options, TestOptions::B | TestOptions::C);
+    options.toggle(TestOptions::AB);
+    assert_eq!(options, TestOptions::A | TestOptions::C);
+    options.set(TestOptions::C, false);
+    assert_eq!(options, TestOptions::A);
+
+    assert_eq!(TestOptions::AB - TestOptions::A, TestOptions::B);
+    assert_eq!(TestOptions::AB & TestOptions::B, TestOptions::B);
+    assert_eq!(TestOptions::AB ^ TestOptions::B, TestOptions::A);
+    assert_eq!((!TestOptions::A).bits(), !1);
+
+    let mut options = TestOptions::empty();
+    assert!(options.is_empty());
+    options |= TestOptions::A;
+    options ^= TestOptions::C;
+    options &= TestOptions::C;
+    options -= TestOptions::B;
+    assert_eq!(options, TestOptions::C);
+}
+
+#[test]
+fn test_conversions() {
+    let options = TestOptions::from(0b11);
+    assert_eq!(options, TestOptions::AB);
+    assert_eq!(NSUInteger::from(options), 0b11);
+    assert_eq!(TestOptions::from_bits_retain(0x100).bits(), 0x100);
+    assert_eq!(u8::from(SmallOptions::X), 0x80);
+}
+
+#[test]
+fn test_debug() {
+    assert_eq!(format!("{:?}", TestOptions::empty()), "TestOptions(0x0)");
+    assert_eq!(format!("{:?}", TestOptions::A), "TestOptions(A)");
+    assert_eq!(format!("{:?}", TestOptions::AB), "TestOptions(A | B)");
+    assert_eq!(
+        format!("{:?}", TestOptions::A | TestOptions::C),
+        "TestOptions(A | C)"
+    );
+    assert_eq!(
+        format!("{:?}", TestOptions::from(0b1001)),
+        "TestOptions(A | 0x8)"
+    );
+    assert_eq!(
+        format!("{:?}", TestOptions::from(0x10)),
+        "TestOptions(0x10)"
+    );
+    assert_eq!(format!("{:?}", SmallOptions::X), "SmallOptions(X)");
+}
+
+#[test]
+fn test_encoding() {
+    assert_eq!(TestOptions::ENCODING, NSUInteger::ENCODING);
+    assert_eq!(TestOptions::ENCODING_REF, NSUInteger::ENCODING_REF);
+    assert_eq!(SmallOptions::ENCODING, Encoding::UChar);
+}
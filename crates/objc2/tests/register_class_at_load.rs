@@ -0,0 +1,42 @@
+use objc2::mutability::InteriorMutable;
+use objc2::runtime::{AnyClass, NSObject};
+use objc2::{declare_class, register_class_at_load, ClassType, DeclaredClass};
+
+declare_class!(
+    struct RegisteredAtLoad;
+
+    unsafe impl ClassType for RegisteredAtLoad {
+        type Super = NSObject;
+        type Mutability = InteriorMutable;
+        const NAME: &'static str = "RegisterClassAtLoadTestObject";
+    }
+
+    impl DeclaredClass for RegisteredAtLoad {}
+);
+
+declare_class!(
+    struct RegisteredAtLoadSubclass;
+
+    unsafe impl ClassType for RegisteredAtLoadSubclass {
+        #[inherits(NSObject)]
+        type Super = RegisteredAtLoad;
+        type Mutability = InteriorMutable;
+        const NAME: &'static str = "RegisterClassAtLoadTestSubclass";
+    }
+
+    impl DeclaredClass for RegisteredAtLoadSubclass {}
+);
+
+register_class_at_load!(RegisteredAtLoad, RegisteredAtLoadSubclass,);
+
+#[test]
+fn available_by_name() {
+    // Look up by name first, to ensure that we're not the ones registering
+    // the class.
+    let cls = AnyClass::get("RegisterClassAtLoadTestObject").unwrap();
+    assert_eq!(cls, RegisteredAtLoad::class());
+
+    let subclass = AnyClass::get("RegisterClassAtLoadTestSubclass").unwrap();
+    assert_eq!(subclass, RegisteredAtLoadSubclass::class());
+    assert_eq!(subclass.superclass(), Some(cls));
+}
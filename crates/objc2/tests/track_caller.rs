@@ -70,6 +70,10 @@ impl Drop for PanicChecker {
 }
 
 #[test]
+#[cfg_attr(
+    feature = "unstable-mock-runtime",
+    ignore = "the mock runtime aborts on panics in methods it calls"
+)]
 fn test_track_caller() {
     let checker = PanicChecker::new();
 
@@ -87,7 +91,7 @@ fn test_track_caller() {
 
     test_unwind(&checker);
 
-    #[cfg(not(feature = "unstable-static-class"))]
+    #[cfg(any(not(feature = "unstable-static-class"), not(feature = "apple")))]
     test_unknown_class(&checker);
 }
 
@@ -224,7 +228,7 @@ fn test_unwind(checker: &PanicChecker) {
     });
 }
 
-#[cfg(not(feature = "unstable-static-class"))]
+#[cfg(any(not(feature = "unstable-static-class"), not(feature = "apple")))]
 fn test_unknown_class(checker: &PanicChecker) {
     let msg = "class NonExistantClass could not be found";
     checker.assert_panics(msg, line!() + 1, || {
@@ -0,0 +1,78 @@
+//! Test translating between Objective-C exceptions and Rust panics.
+#![cfg(feature = "unwind")]
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use objc2::exception::{Exception, ExceptionPanic};
+use objc2::rc::Id;
+use objc2::runtime::NSObject;
+use objc2::{declare_class, msg_send, msg_send_id, mutability, ClassType, DeclaredClass};
+
+declare_class!(
+    struct Thrower;
+
+    unsafe impl ClassType for Thrower {
+        type Super = NSObject;
+        type Mutability = mutability::Immutable;
+        const NAME: &'static str = "UnwindTestThrower";
+    }
+
+    impl DeclaredClass for Thrower {}
+
+    unsafe impl Thrower {
+        #[method(panicWithMessage)]
+        fn panic_with_message(&self) {
+            panic!("panic in method");
+        }
+
+        #[method_id(copyUncopyable:)]
+        fn copy_uncopyable(&self, obj: &NSObject) -> Id<NSObject> {
+            unsafe { msg_send_id![obj, copy] }
+        }
+    }
+);
+
+fn catch_exception(f: impl FnOnce()) -> Id<Exception> {
+    let payload = catch_unwind(AssertUnwindSafe(f)).expect_err("should have panicked");
+    let payload = payload
+        .downcast::<ExceptionPanic>()
+        .expect("payload should be ExceptionPanic");
+    payload
+        .into_exception()
+        .expect("exception should not be nil")
+}
+
+#[test]
+fn exception_becomes_panic() {
+    let obj = NSObject::new();
+    let exception = catch_exception(|| {
+        let _: Id<NSObject> = unsafe { msg_send_id![&obj, copy] };
+    });
+    assert!(exception.to_string().contains("copyWithZone:"));
+}
+
+#[test]
+fn panic_becomes_exception() {
+    let obj: Id<Thrower> = unsafe { msg_send_id![Thrower::class(), new] };
+    let exception = catch_exception(|| {
+        let _: () = unsafe { msg_send![&obj, panicWithMessage] };
+    });
+    assert_eq!(exception.to_string(), "panic in method");
+    assert!(format!("{exception:?}").contains("'RustPanic'"));
+}
+
+#[test]
+fn exception_roundtrips_through_method() {
+    let obj: Id<Thrower> = unsafe { msg_send_id![Thrower::class(), new] };
+    let uncopyable = NSObject::new();
+
+    let expected = catch_exception(|| {
+        let _: Id<NSObject> = unsafe { msg_send_id![&uncopyable, copy] };
+    });
+    let exception = catch_exception(|| {
+        let _: Id<NSObject> = unsafe { msg_send_id![&obj, copyUncopyable: &*uncopyable] };
+    });
+    // The original exception is re-thrown, instead of being wrapped in a
+    // `RustPanic` exception.
+    assert_eq!(expected.to_string(), exception.to_string());
+    assert!(!format!("{exception:?}").contains("'RustPanic'"));
+}
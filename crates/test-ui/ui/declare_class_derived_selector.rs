@@ -0,0 +1,22 @@
+//! Test that selectors must be specified explicitly in `declare_class!`.
+use objc2::runtime::NSObject;
+use objc2::{declare_class, mutability, ClassType, DeclaredClass};
+
+declare_class!(
+    struct CustomObject;
+
+    unsafe impl ClassType for CustomObject {
+        type Super = NSObject;
+        type Mutability = mutability::InteriorMutable;
+        const NAME: &'static str = "CustomObject";
+    }
+
+    impl DeclaredClass for CustomObject {}
+
+    unsafe impl CustomObject {
+        #[method]
+        fn foo(&self) {}
+    }
+);
+
+fn main() {}
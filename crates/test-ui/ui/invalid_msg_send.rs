@@ -15,6 +15,8 @@ fn main() {
     let _: () = unsafe { msg_send![obj, a: b, c d] };
     let _: () = unsafe { msg_send![obj, a: b: c] };
     let _: () = unsafe { msg_send![obj, a: b c: d,] };
+    let _: () = unsafe { msg_send![obj, a: b, c] };
+    let _: () = unsafe { msg_send![obj, 1] };
 
     let _: Result<(), Id<NSObject>> = unsafe { msg_send![obj, a: _, b: _] };
 }
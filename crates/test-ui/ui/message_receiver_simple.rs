@@ -6,9 +6,6 @@ use objc2::sel;
 fn main() {
     let obj = NSObject::new();
 
-    // Receiver `&Id` not allowed
-    let _: usize = unsafe { MessageReceiver::send_message(&obj, sel!(hash), ()) };
-
     // No `bool` argument conversion
     let _: () = unsafe { MessageReceiver::send_message(&*obj, sel!(hash:), (true,)) };
 
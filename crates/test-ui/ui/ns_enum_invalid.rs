@@ -0,0 +1,19 @@
+//! Test invalid `ns_enum!` declarations.
+use objc2::ns_enum;
+
+ns_enum!(
+    enum MissingUnknown: u32 {
+        A = 0,
+        B = 1,
+    }
+);
+
+ns_enum!(
+    enum DuplicateUnknown: u32 {
+        A = 0,
+        Unknown(_),
+        Other(_),
+    }
+);
+
+fn main() {}
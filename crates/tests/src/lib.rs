@@ -19,6 +19,8 @@ mod test_declare_class_protocol;
 mod test_encode_utils;
 #[cfg(test)]
 mod test_foundation_retain_semantics;
+#[cfg(all(test, feature = "all"))]
+mod test_main_thread;
 #[cfg(test)]
 mod test_object;
 
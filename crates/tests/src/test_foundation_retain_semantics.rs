@@ -3,7 +3,8 @@ use std::ptr;
 use std::vec::Vec;
 
 use objc2::mutability::Immutable;
-use objc2::rc::{Id, __RcTestObject, __ThreadTestData};
+use objc2::rc::Id;
+use objc2::rc::{__RcTestObject, __ThreadTestData};
 use objc2::{declare_class, extern_methods, ClassType, DeclaredClass};
 use objc2_foundation::{
     NSArray, NSCopying, NSMutableArray, NSMutableCopying, NSMutableDictionary, NSMutableSet,
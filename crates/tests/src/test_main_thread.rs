@@ -0,0 +1,7 @@
+use objc2_foundation::test_utils::main_thread_test;
+
+#[main_thread_test]
+#[should_panic = "`#[main_thread_test]` requires the main thread"]
+fn requires_harness() {
+    unreachable!("must not run outside the main thread");
+}
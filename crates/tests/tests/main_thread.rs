@@ -0,0 +1,30 @@
+//! Test `#[main_thread_test]` together with its test harness.
+use std::panic;
+
+use objc2_foundation::test_utils::{main_thread_test, main_thread_test_harness};
+use objc2_foundation::{is_main_thread, MainThreadMarker};
+
+#[main_thread_test]
+fn on_main_thread() {
+    assert!(is_main_thread());
+}
+
+#[main_thread_test(run_loop_ms = 10)]
+fn with_marker_and_run_loop(mtm: MainThreadMarker) {
+    assert!(is_main_thread());
+    let _ = mtm;
+}
+
+#[main_thread_test]
+fn panics() {
+    panic!("expected panic");
+}
+
+fn main() {
+    main_thread_test_harness(|| {
+        assert!(!is_main_thread());
+        on_main_thread();
+        with_marker_and_run_loop();
+        assert!(panic::catch_unwind(panics).is_err());
+    })
+}
@@ -0,0 +1,70 @@
+//! Test the run loop executor, which has to run on the main thread.
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use objc2_foundation::executor::{block_on, sleep, spawn_local, RunLoopSpawner};
+use objc2_foundation::test_utils::{main_thread_test, main_thread_test_harness};
+use objc2_foundation::{MainThreadMarker, Spawner};
+
+#[main_thread_test]
+fn block_on_ready(mtm: MainThreadMarker) {
+    assert_eq!(block_on(mtm, async { 42 }), 42);
+}
+
+#[main_thread_test]
+fn timer_wakeup(mtm: MainThreadMarker) {
+    let start = Instant::now();
+    block_on(mtm, sleep(mtm, Duration::from_millis(20)));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
+
+#[main_thread_test]
+fn local_tasks(mtm: MainThreadMarker) {
+    let counter = Rc::new(Cell::new(0));
+    for _ in 0..3 {
+        let counter = counter.clone();
+        mtm.spawn_local(async move {
+            sleep(mtm, Duration::from_millis(1)).await;
+            counter.set(counter.get() + 1);
+        });
+    }
+    // Not polled until the run loop runs.
+    assert_eq!(counter.get(), 0);
+
+    block_on(mtm, sleep(mtm, Duration::from_millis(50)));
+    assert_eq!(counter.get(), 3);
+}
+
+#[main_thread_test]
+fn spawn_from_other_thread(mtm: MainThreadMarker) {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        RunLoopSpawner.spawn(async move {
+            sender.send(MainThreadMarker::new().is_some()).unwrap();
+        });
+    })
+    .join()
+    .unwrap();
+
+    let ran_on_main = Rc::new(Cell::new(None));
+    let result = ran_on_main.clone();
+    spawn_local(mtm, async move {
+        // Give the other task a chance to run.
+        sleep(mtm, Duration::from_millis(10)).await;
+        result.set(receiver.try_recv().ok());
+    });
+    block_on(mtm, sleep(mtm, Duration::from_millis(50)));
+    assert_eq!(ran_on_main.get(), Some(true));
+}
+
+fn main() {
+    main_thread_test_harness(|| {
+        block_on_ready();
+        timer_wakeup();
+        local_tasks();
+        spawn_from_other_thread();
+    })
+}
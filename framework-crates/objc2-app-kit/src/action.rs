@@ -0,0 +1,158 @@
+use alloc::boxed::Box;
+use core::ffi::c_void;
+
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol, Sel};
+use objc2::{declare_class, ffi, msg_send_id, mutability, sel, ClassType, DeclaredClass};
+use objc2_foundation::MainThreadMarker;
+
+use crate::*;
+
+/// The key under which a handler is associated with its sender.
+static HANDLER_KEY: u8 = 0;
+
+declare_class!(
+    /// An object that calls a Rust closure when sent an action.
+    ///
+    /// This is the target of a target-action pair, which lets controls and
+    /// menu items call closures without declaring a class for each of them.
+    /// The closure receives the sender of the action.
+    ///
+    /// Targets are not retained by their senders, so the handler must be
+    /// kept alive separately; [`ActionHandler::attach`] does this by
+    /// associating it with the sender. Usually, the helpers
+    /// [`NSControl::set_action_handler`] and
+    /// [`NSMenuItem::set_action_handler`] are used instead of using this
+    /// directly.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_app_kit::{NSButton, NSControl};
+    /// use objc2_foundation::{ns_string, MainThreadMarker};
+    ///
+    /// let mtm = MainThreadMarker::new().unwrap();
+    /// let button = unsafe {
+    ///     NSButton::buttonWithTitle_target_action(ns_string!("Click"), None, None, mtm)
+    /// };
+    /// button.set_action_handler(|_control: &NSControl| println!("clicked"));
+    /// ```
+    pub struct ActionHandler;
+
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - Actions are sent on the main thread.
+    // - `ActionHandler` does not implement `Drop`.
+    unsafe impl ClassType for ActionHandler {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustActionHandler";
+    }
+
+    impl DeclaredClass for ActionHandler {
+        type Ivars = Box<dyn Fn(&AnyObject)>;
+    }
+
+    unsafe impl NSObjectProtocol for ActionHandler {}
+
+    unsafe impl ActionHandler {
+        #[method(performAction:)]
+        fn perform_action(&self, sender: Option<&AnyObject>) {
+            if let Some(sender) = sender {
+                (self.ivars())(sender);
+            }
+        }
+    }
+);
+
+impl ActionHandler {
+    /// Create a handler that calls the closure with the sender of each
+    /// action.
+    pub fn new(mtm: MainThreadMarker, f: impl Fn(&AnyObject) + 'static) -> Id<Self> {
+        let this = mtm
+            .alloc::<Self>()
+            .set_ivars(Box::new(f) as Box<dyn Fn(&AnyObject)>);
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        unsafe { msg_send_id![super(this), init] }
+    }
+
+    /// The action selector, to pass to `setAction:` along with the handler
+    /// as the target.
+    pub fn action() -> Sel {
+        sel!(performAction:)
+    }
+
+    /// Keep the handler alive for as long as the given object is, by
+    /// associating it with the object.
+    ///
+    /// Each object holds at most one handler; attaching another releases
+    /// the previous one.
+    #[doc(alias = "objc_setAssociatedObject")]
+    pub fn attach(&self, object: &AnyObject) {
+        let object: *const AnyObject = object;
+        let this: *const Self = self;
+        // SAFETY: Both pointers are valid objects, the key is a static with
+        // a unique address, and the policy retains the handler.
+        unsafe {
+            ffi::objc_setAssociatedObject(
+                object as *mut ffi::objc_object,
+                &HANDLER_KEY as *const u8 as *const c_void,
+                this as *mut ffi::objc_object,
+                ffi::OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+            )
+        };
+    }
+}
+
+#[cfg(all(feature = "NSControl", feature = "NSResponder", feature = "NSView"))]
+impl NSControl {
+    /// Call the closure whenever the control sends its action.
+    ///
+    /// This replaces the target and action of the control. The handler is
+    /// kept alive for as long as the control is.
+    #[doc(alias = "setTarget:")]
+    #[doc(alias = "setAction:")]
+    pub fn set_action_handler(&self, f: impl Fn(&NSControl) + 'static) {
+        let handler = ActionHandler::new(MainThreadMarker::from(self), move |sender| {
+            // SAFETY: Controls send themselves as the sender of their
+            // action, and the handler is only the target of this control.
+            let sender = unsafe { &*(sender as *const AnyObject as *const NSControl) };
+            f(sender)
+        });
+        handler.attach(self);
+        let target: &AnyObject = &handler;
+        // SAFETY: The handler responds to the action, and is kept alive by
+        // the control.
+        unsafe {
+            self.setTarget(Some(target));
+            self.setAction(Some(ActionHandler::action()));
+        }
+    }
+}
+
+#[cfg(feature = "NSMenuItem")]
+impl NSMenuItem {
+    /// Call the closure whenever the menu item is chosen.
+    ///
+    /// This replaces the target and action of the menu item. The handler is
+    /// kept alive for as long as the menu item is.
+    #[doc(alias = "setTarget:")]
+    #[doc(alias = "setAction:")]
+    pub fn set_action_handler(&self, f: impl Fn(&NSMenuItem) + 'static) {
+        let handler = ActionHandler::new(MainThreadMarker::from(self), move |sender| {
+            // SAFETY: Menu items send themselves as the sender of their
+            // action, and the handler is only the target of this item.
+            let sender = unsafe { &*(sender as *const AnyObject as *const NSMenuItem) };
+            f(sender)
+        });
+        handler.attach(self);
+        let target: &AnyObject = &handler;
+        // SAFETY: The handler responds to the action, and is kept alive by
+        // the menu item.
+        unsafe {
+            self.setTarget(Some(target));
+            self.setAction(Some(ActionHandler::action()));
+        }
+    }
+}
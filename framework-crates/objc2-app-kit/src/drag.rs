@@ -0,0 +1,279 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+
+use objc2::rc::Id;
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{declare_class, ffi, msg_send_id, mutability, ClassType, DeclaredClass};
+use objc2_foundation::{MainThreadMarker, NSArray, NSPoint, NSRect};
+
+use crate::*;
+
+/// Information about an ongoing drag, passed to the closures of a
+/// [`DropTargetView`].
+#[derive(Clone, Copy)]
+pub struct DragInfo<'a>(&'a ProtocolObject<dyn NSDraggingInfo>);
+
+impl<'a> DragInfo<'a> {
+    /// The underlying dragging info.
+    pub fn as_protocol(&self) -> &'a ProtocolObject<dyn NSDraggingInfo> {
+        self.0
+    }
+
+    /// The pasteboard holding the dragged data.
+    ///
+    /// The contents can be decoded with the typed pasteboard helpers, such
+    /// as [`NSPasteboard::string`] and [`NSPasteboard::file_urls`].
+    #[doc(alias = "draggingPasteboard")]
+    pub fn pasteboard(&self) -> Id<NSPasteboard> {
+        // SAFETY: Getting the pasteboard is safe.
+        unsafe { self.0.draggingPasteboard() }
+    }
+
+    /// The operations allowed by the source of the drag.
+    #[doc(alias = "draggingSourceOperationMask")]
+    pub fn source_operations(&self) -> NSDragOperation {
+        // SAFETY: Getting the operation mask is safe.
+        unsafe { self.0.draggingSourceOperationMask() }
+    }
+
+    /// The location of the drag, in the base coordinate system of the
+    /// destination window.
+    #[doc(alias = "draggingLocation")]
+    pub fn location(&self) -> NSPoint {
+        // SAFETY: Getting the location is safe.
+        unsafe { self.0.draggingLocation() }
+    }
+}
+
+impl fmt::Debug for DragInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DragInfo").field(&self.0).finish()
+    }
+}
+
+/// The key under which a drag source is associated with its session.
+static SOURCE_KEY: u8 = 0;
+
+type ValidateFn = Box<dyn Fn(DragInfo<'_>) -> NSDragOperation>;
+type PerformFn = Box<dyn Fn(DragInfo<'_>) -> bool>;
+
+#[doc(hidden)]
+pub struct DropTargetIvars {
+    validate: ValidateFn,
+    perform: PerformFn,
+}
+
+declare_class!(
+    /// A view that accepts drops, with the behaviour implemented by Rust
+    /// closures.
+    ///
+    /// Implementing `NSDraggingDestination` requires subclassing a view,
+    /// which this does for you. Add the view on top of the area that should
+    /// accept drops, for example as a subview with the same frame.
+    ///
+    /// Created with [`DropTargetView::new`].
+    pub struct DropTargetView;
+
+    // SAFETY:
+    // - The superclass NSView does not have any subclassing requirements.
+    // - Views must only be used on the main thread.
+    // - `DropTargetView` does not implement `Drop`.
+    unsafe impl ClassType for DropTargetView {
+        type Super = NSView;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustDropTargetView";
+    }
+
+    impl DeclaredClass for DropTargetView {
+        type Ivars = DropTargetIvars;
+    }
+
+    unsafe impl DropTargetView {
+        #[method(draggingEntered:)]
+        fn dragging_entered(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> NSDragOperation {
+            (self.ivars().validate)(DragInfo(sender))
+        }
+
+        #[method(draggingUpdated:)]
+        fn dragging_updated(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> NSDragOperation {
+            (self.ivars().validate)(DragInfo(sender))
+        }
+
+        #[method(prepareForDragOperation:)]
+        fn prepare_for_drag_operation(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> bool {
+            (self.ivars().validate)(DragInfo(sender)) != NSDragOperation::None
+        }
+
+        #[method(performDragOperation:)]
+        fn perform_drag_operation(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> bool {
+            (self.ivars().perform)(DragInfo(sender))
+        }
+    }
+);
+
+impl DropTargetView {
+    /// Create a view that accepts drops of the given pasteboard types.
+    ///
+    /// `validate` is called while a drag is over the view, and returns the
+    /// operation that would be performed, or [`NSDragOperation::None`] to
+    /// reject the drop. It is usually one of the
+    /// [source's operations](DragInfo::source_operations).
+    ///
+    /// `perform` is called when the user drops onto the view, and returns
+    /// whether the drop was successful.
+    #[doc(alias = "registerForDraggedTypes:")]
+    pub fn new<V, P>(
+        mtm: MainThreadMarker,
+        frame: NSRect,
+        types: &[&NSPasteboardType],
+        validate: V,
+        perform: P,
+    ) -> Id<Self>
+    where
+        V: Fn(DragInfo<'_>) -> NSDragOperation + 'static,
+        P: Fn(DragInfo<'_>) -> bool + 'static,
+    {
+        let this = mtm.alloc::<Self>().set_ivars(DropTargetIvars {
+            validate: Box::new(validate),
+            perform: Box::new(perform),
+        });
+        // SAFETY: `initWithFrame:` is the designated initializer of NSView.
+        let this: Id<Self> = unsafe { msg_send_id![super(this), initWithFrame: frame] };
+        let types: Vec<_> = types.iter().map(|ty| ty.retain()).collect();
+        // SAFETY: Registering the types is safe.
+        unsafe { this.registerForDraggedTypes(&NSArray::from_vec(types)) };
+        this
+    }
+}
+
+type OperationsFn = Box<dyn Fn(NSDraggingContext) -> NSDragOperation>;
+type EndedFn = Box<dyn Fn(NSDragOperation)>;
+
+#[doc(hidden)]
+pub struct DragSourceIvars {
+    operations: OperationsFn,
+    ended: Option<EndedFn>,
+}
+
+declare_class!(
+    /// A source of drags, with the behaviour implemented by Rust closures.
+    ///
+    /// Created with [`DragSource::new`], and used to start a drag with
+    /// [`DragSource::begin`].
+    pub struct DragSource;
+
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - Dragging sessions are only used on the main thread.
+    // - `DragSource` does not implement `Drop`.
+    unsafe impl ClassType for DragSource {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustDragSource";
+    }
+
+    impl DeclaredClass for DragSource {
+        type Ivars = DragSourceIvars;
+    }
+
+    unsafe impl NSObjectProtocol for DragSource {}
+
+    unsafe impl NSDraggingSource for DragSource {
+        #[method(draggingSession:sourceOperationMaskForDraggingContext:)]
+        fn source_operation_mask(
+            &self,
+            _session: &NSDraggingSession,
+            context: NSDraggingContext,
+        ) -> NSDragOperation {
+            (self.ivars().operations)(context)
+        }
+
+        #[method(draggingSession:endedAtPoint:operation:)]
+        fn ended(&self, _session: &NSDraggingSession, _point: NSPoint, operation: NSDragOperation) {
+            if let Some(ended) = &self.ivars().ended {
+                ended(operation);
+            }
+        }
+    }
+);
+
+impl DragSource {
+    /// Create a drag source.
+    ///
+    /// `operations` returns the operations allowed in the given context
+    /// (within the application or outside of it). `ended` is called with
+    /// the operation that was performed once the drag ends, which is
+    /// [`NSDragOperation::None`] if the drop was rejected or cancelled.
+    pub fn new<O>(
+        mtm: MainThreadMarker,
+        operations: O,
+        ended: Option<Box<dyn Fn(NSDragOperation)>>,
+    ) -> Id<Self>
+    where
+        O: Fn(NSDraggingContext) -> NSDragOperation + 'static,
+    {
+        let this = mtm.alloc::<Self>().set_ivars(DragSourceIvars {
+            operations: Box::new(operations),
+            ended,
+        });
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        unsafe { msg_send_id![super(this), init] }
+    }
+
+    /// Start a drag of the given pasteboard writers from the view.
+    ///
+    /// This must be called while handling a mouse down or mouse dragged
+    /// `event` in `view`. Each item is shown at `frame` (in the view's
+    /// coordinate system) while being dragged.
+    ///
+    /// The source is kept alive for as long as the returned session is,
+    /// which is at least until the drag has ended.
+    #[doc(alias = "beginDraggingSessionWithItems:event:source:")]
+    pub fn begin(
+        &self,
+        view: &NSView,
+        event: &NSEvent,
+        writers: &[&ProtocolObject<dyn NSPasteboardWriting>],
+        frame: NSRect,
+    ) -> Id<NSDraggingSession> {
+        let mtm = MainThreadMarker::from(self);
+        let items: Vec<_> = writers
+            .iter()
+            .map(|writer| {
+                // SAFETY: Creating and configuring a dragging item is safe.
+                unsafe {
+                    let item = NSDraggingItem::initWithPasteboardWriter(mtm.alloc(), writer);
+                    item.setDraggingFrame_contents(frame, None);
+                    item
+                }
+            })
+            .collect();
+        let items = NSArray::from_vec(items);
+        // SAFETY: The source implements the protocol, and is kept alive by
+        // the session below.
+        let session = unsafe {
+            view.beginDraggingSessionWithItems_event_source(
+                &items,
+                event,
+                ProtocolObject::from_ref(self),
+            )
+        };
+        // AppKit does not document that the source is retained, so retain
+        // it for as long as the session is alive.
+        let object: *const NSDraggingSession = &*session;
+        let this: *const Self = self;
+        // SAFETY: Both pointers are valid objects, the key is a static with
+        // a unique address, and the policy retains the source.
+        unsafe {
+            ffi::objc_setAssociatedObject(
+                object as *mut ffi::objc_object,
+                &SOURCE_KEY as *const u8 as *const c_void,
+                this as *mut ffi::objc_object,
+                ffi::OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+            )
+        };
+        session
+    }
+}
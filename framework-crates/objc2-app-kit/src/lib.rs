@@ -54,26 +54,119 @@ extern "C" {}
 /// (!TARGET_CPU_X86_64 || (TARGET_OS_IPHONE && !TARGET_OS_MACCATALYST))
 ///
 /// <https://github.com/xamarin/xamarin-macios/issues/12111>
-// TODO: Make this work with mac catalyst
 #[allow(dead_code)]
 pub(crate) const TARGET_ABI_USES_IOS_VALUES: bool =
-    !cfg!(any(target_arch = "x86", target_arch = "x86_64")) || cfg!(not(target_os = "macos"));
+    !cfg!(any(target_arch = "x86", target_arch = "x86_64"))
+        || !matches!(
+            objc2::Platform::TARGET,
+            objc2::Platform::MacOS | objc2::Platform::MacCatalyst
+        );
 
+#[cfg(feature = "alloc")]
+mod action;
 #[cfg(feature = "NSApplication")]
 mod application;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSDragging",
+    feature = "NSDraggingItem",
+    feature = "NSDraggingSession",
+    feature = "NSEvent",
+    feature = "NSPasteboard",
+    feature = "NSResponder",
+    feature = "NSView"
+))]
+mod drag;
 mod generated;
 #[cfg(feature = "NSImage")]
 mod image;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSApplication",
+    feature = "NSOpenPanel",
+    feature = "NSPanel",
+    feature = "NSResponder",
+    feature = "NSSavePanel",
+    feature = "NSWindow"
+))]
+mod panel;
+#[cfg(all(feature = "alloc", feature = "NSPasteboard"))]
+mod pasteboard;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSControl",
+    feature = "NSResponder",
+    feature = "NSTableColumn",
+    feature = "NSTableView",
+    feature = "NSView"
+))]
+mod table;
 #[cfg(feature = "NSText")]
 mod text;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSGraphics",
+    feature = "NSResponder",
+    feature = "NSView",
+    feature = "NSWindow"
+))]
+mod window;
+#[cfg(all(feature = "futures-core", feature = "block2", feature = "NSWorkspace"))]
+mod workspace;
 
+#[cfg(feature = "alloc")]
+pub use self::action::ActionHandler;
 #[cfg(feature = "NSApplication")]
 pub use self::application::*;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSDragging",
+    feature = "NSDraggingItem",
+    feature = "NSDraggingSession",
+    feature = "NSEvent",
+    feature = "NSPasteboard",
+    feature = "NSResponder",
+    feature = "NSView"
+))]
+pub use self::drag::{DragInfo, DragSource, DropTargetView};
 pub use self::generated::*;
 #[cfg(feature = "NSImage")]
 pub use self::image::*;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSApplication",
+    feature = "NSOpenPanel",
+    feature = "NSPanel",
+    feature = "NSResponder",
+    feature = "NSSavePanel",
+    feature = "NSWindow"
+))]
+pub use self::panel::{OpenPanel, SavePanel};
+#[cfg(all(feature = "alloc", feature = "NSPasteboard"))]
+pub use self::pasteboard::PasteboardWatcher;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSControl",
+    feature = "NSResponder",
+    feature = "NSTableColumn",
+    feature = "NSTableView",
+    feature = "NSView"
+))]
+pub use self::table::{TableDiff, TableSource};
 #[cfg(feature = "NSText")]
 pub use self::text::*;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSGraphics",
+    feature = "NSResponder",
+    feature = "NSView",
+    feature = "NSWindow"
+))]
+pub use self::window::{WindowDelegate, WindowHandlers};
+#[cfg(all(feature = "futures-core", feature = "block2", feature = "NSWorkspace"))]
+pub use self::workspace::{PowerEvent, PowerEvents};
 
 // MacTypes.h
 #[allow(unused)]
@@ -0,0 +1,278 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::path::{Path, PathBuf};
+
+use block2::{Block, RcBlock};
+use objc2::rc::Id;
+use objc2_foundation::{oneshot, MainThreadMarker, NSArray, NSString, Oneshot, NSURL};
+
+use crate::*;
+
+/// Show the panel, returning a future that resolves once it has been
+/// dismissed.
+fn begin(panel: &NSSavePanel) -> Oneshot<NSModalResponse> {
+    let (sender, receiver) = oneshot();
+    let block = RcBlock::new(move |response: NSModalResponse| sender.send(response));
+    let block: &Block<dyn Fn(NSModalResponse) + 'static> = &block;
+    // SAFETY: The block has the correct signature, and is copied by the
+    // panel.
+    unsafe { panel.beginWithCompletionHandler(block) };
+    receiver
+}
+
+/// Options shared between open and save panels.
+#[derive(Clone, Debug, Default)]
+struct Options {
+    title: Option<String>,
+    message: Option<String>,
+    prompt: Option<String>,
+    directory: Option<PathBuf>,
+    extensions: Option<Vec<String>>,
+    can_create_directories: bool,
+    shows_hidden_files: bool,
+}
+
+impl Options {
+    fn apply(&self, panel: &NSSavePanel) {
+        // SAFETY: The panel has not been shown yet, so configuring it is
+        // safe.
+        unsafe {
+            if let Some(title) = &self.title {
+                panel.setTitle(&NSString::from_str(title));
+            }
+            if let Some(message) = &self.message {
+                panel.setMessage(Some(&NSString::from_str(message)));
+            }
+            if let Some(prompt) = &self.prompt {
+                panel.setPrompt(Some(&NSString::from_str(prompt)));
+            }
+            if let Some(directory) = &self.directory {
+                panel.setDirectoryURL(NSURL::from_path(directory, true).as_deref());
+            }
+            if let Some(extensions) = &self.extensions {
+                let extensions: Vec<_> = extensions
+                    .iter()
+                    .map(|extension| NSString::from_str(extension))
+                    .collect();
+                // `allowedContentTypes` is skipped in the translation, since
+                // it takes `UTType` from `objc2-uniform-type-identifiers`.
+                #[allow(deprecated)]
+                panel.setAllowedFileTypes(Some(&NSArray::from_vec(extensions)));
+            }
+            panel.setCanCreateDirectories(self.can_create_directories);
+            panel.setShowsHiddenFiles(self.shows_hidden_files);
+        }
+    }
+}
+
+macro_rules! common_options {
+    () => {
+        /// Set the title of the panel.
+        pub fn title(mut self, title: &str) -> Self {
+            self.options.title = Some(title.into());
+            self
+        }
+
+        /// Set the message shown at the top of the panel.
+        #[doc(alias = "setMessage:")]
+        pub fn message(mut self, message: &str) -> Self {
+            self.options.message = Some(message.into());
+            self
+        }
+
+        /// Set the text of the default button.
+        #[doc(alias = "setPrompt:")]
+        pub fn prompt(mut self, prompt: &str) -> Self {
+            self.options.prompt = Some(prompt.into());
+            self
+        }
+
+        /// Set the directory that the panel initially shows.
+        #[doc(alias = "setDirectoryURL:")]
+        pub fn directory(mut self, directory: impl AsRef<Path>) -> Self {
+            self.options.directory = Some(directory.as_ref().into());
+            self
+        }
+
+        /// Only allow files with the given extensions, such as `"txt"`.
+        #[doc(alias = "setAllowedFileTypes:")]
+        pub fn extensions(mut self, extensions: &[&str]) -> Self {
+            let extensions = extensions.iter().map(|&extension| extension.into());
+            self.options.extensions = Some(extensions.collect());
+            self
+        }
+
+        /// Allow the user to create directories from the panel.
+        #[doc(alias = "setCanCreateDirectories:")]
+        pub fn can_create_directories(mut self, can_create_directories: bool) -> Self {
+            self.options.can_create_directories = can_create_directories;
+            self
+        }
+
+        /// Show hidden files in the panel.
+        #[doc(alias = "setShowsHiddenFiles:")]
+        pub fn shows_hidden_files(mut self, shows_hidden_files: bool) -> Self {
+            self.options.shows_hidden_files = shows_hidden_files;
+            self
+        }
+    };
+}
+
+/// A builder for showing an open panel, which lets the user choose files
+/// or directories.
+///
+/// In a sandboxed application, the chosen URLs are accessible until the
+/// application quits. Use [`NSURL::security_scoped_bookmark`] to persist
+/// access across launches.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_app_kit::OpenPanel;
+/// use objc2_foundation::MainThreadMarker;
+///
+/// # async fn example() {
+/// let mtm = MainThreadMarker::new().unwrap();
+/// let paths = OpenPanel::new()
+///     .multiple(true)
+///     .extensions(&["png", "jpg"])
+///     .run(mtm)
+///     .await;
+/// if let Some(paths) = paths {
+///     println!("chose {paths:?}");
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[doc(alias = "NSOpenPanel")]
+pub struct OpenPanel {
+    options: Options,
+    files: bool,
+    directories: bool,
+    multiple: bool,
+}
+
+impl OpenPanel {
+    /// An open panel that allows choosing a single file.
+    pub fn new() -> Self {
+        Self {
+            options: Options::default(),
+            files: true,
+            directories: false,
+            multiple: false,
+        }
+    }
+
+    common_options!();
+
+    /// Allow the user to choose files.
+    #[doc(alias = "setCanChooseFiles:")]
+    pub fn files(mut self, files: bool) -> Self {
+        self.files = files;
+        self
+    }
+
+    /// Allow the user to choose directories.
+    #[doc(alias = "setCanChooseDirectories:")]
+    pub fn directories(mut self, directories: bool) -> Self {
+        self.directories = directories;
+        self
+    }
+
+    /// Allow the user to choose more than one item.
+    #[doc(alias = "setAllowsMultipleSelection:")]
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Show the panel, and wait for the user to choose.
+    ///
+    /// Returns the chosen URLs, or `None` if the user cancelled.
+    #[doc(alias = "beginWithCompletionHandler:")]
+    pub async fn run_urls(self, mtm: MainThreadMarker) -> Option<Vec<Id<NSURL>>> {
+        // SAFETY: Creating and configuring an open panel on the main thread
+        // is safe.
+        let panel = unsafe { NSOpenPanel::openPanel(mtm) };
+        self.options.apply(&panel);
+        // SAFETY: The panel has not been shown yet.
+        unsafe {
+            panel.setCanChooseFiles(self.files);
+            panel.setCanChooseDirectories(self.directories);
+            panel.setAllowsMultipleSelection(self.multiple);
+        }
+        if begin(&panel).await != NSModalResponseOK {
+            return None;
+        }
+        // SAFETY: Getting the chosen URLs is safe.
+        let urls = unsafe { panel.URLs() };
+        Some(urls.iter().map(|url| url.retain()).collect())
+    }
+
+    /// Show the panel, and wait for the user to choose.
+    ///
+    /// Returns the chosen paths, or `None` if the user cancelled.
+    pub async fn run(self, mtm: MainThreadMarker) -> Option<Vec<PathBuf>> {
+        let urls = self.run_urls(mtm).await?;
+        Some(urls.iter().filter_map(|url| url.to_file_path()).collect())
+    }
+}
+
+impl Default for OpenPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A builder for showing a save panel, which lets the user choose where to
+/// save a file.
+#[derive(Clone, Debug, Default)]
+#[doc(alias = "NSSavePanel")]
+pub struct SavePanel {
+    options: Options,
+    name: Option<String>,
+}
+
+impl SavePanel {
+    /// A save panel with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    common_options!();
+
+    /// Set the initial file name.
+    #[doc(alias = "setNameFieldStringValue:")]
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Show the panel, and wait for the user to choose.
+    ///
+    /// Returns the chosen URL, or `None` if the user cancelled.
+    #[doc(alias = "beginWithCompletionHandler:")]
+    pub async fn run_url(self, mtm: MainThreadMarker) -> Option<Id<NSURL>> {
+        // SAFETY: Creating and configuring a save panel on the main thread
+        // is safe.
+        let panel = unsafe { NSSavePanel::savePanel(mtm) };
+        self.options.apply(&panel);
+        if let Some(name) = &self.name {
+            // SAFETY: The panel has not been shown yet.
+            unsafe { panel.setNameFieldStringValue(&NSString::from_str(name)) };
+        }
+        if begin(&panel).await != NSModalResponseOK {
+            return None;
+        }
+        // SAFETY: Getting the chosen URL is safe.
+        unsafe { panel.URL() }
+    }
+
+    /// Show the panel, and wait for the user to choose.
+    ///
+    /// Returns the chosen path, or `None` if the user cancelled.
+    pub async fn run(self, mtm: MainThreadMarker) -> Option<PathBuf> {
+        self.run_url(mtm).await?.to_file_path()
+    }
+}
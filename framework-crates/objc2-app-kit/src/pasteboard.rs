@@ -0,0 +1,222 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use objc2::rc::Id;
+use objc2::runtime::{AnyClass, AnyObject, ProtocolObject};
+use objc2::ClassType;
+use objc2_foundation::{NSArray, NSData, NSInteger, NSString, NSURL};
+
+use crate::*;
+
+/// Convert a class to an object, for use with
+/// `readObjectsForClasses:options:`.
+fn class_object(cls: &AnyClass) -> Id<AnyObject> {
+    let cls: *const AnyClass = cls;
+    // SAFETY: Classes are objects, and retaining them is a no-op.
+    unsafe { Id::retain(cls as *mut AnyObject) }.unwrap()
+}
+
+impl NSPasteboard {
+    /// Replace the contents of the pasteboard with the given string.
+    ///
+    /// Returns `false` if the pasteboard could not be written to.
+    #[doc(alias = "setString:forType:")]
+    pub fn set_string(&self, string: &str) -> bool {
+        // SAFETY: Clearing and writing to the pasteboard is safe.
+        unsafe {
+            let _ = self.clearContents();
+            self.setString_forType(&NSString::from_str(string), NSPasteboardTypeString)
+        }
+    }
+
+    /// The string on the pasteboard, if any.
+    #[doc(alias = "stringForType:")]
+    pub fn string(&self) -> Option<String> {
+        // SAFETY: Reading from the pasteboard is safe.
+        let string = unsafe { self.stringForType(NSPasteboardTypeString) };
+        string.map(|string| string.to_string())
+    }
+
+    /// Replace the contents of the pasteboard with the given data, stored
+    /// under the given type.
+    ///
+    /// The type is usually a custom uniform type identifier, such as
+    /// `"com.example.my-app.item"`.
+    ///
+    /// Returns `false` if the pasteboard could not be written to.
+    #[doc(alias = "setData:forType:")]
+    pub fn set_data(&self, ty: &NSPasteboardType, data: &[u8]) -> bool {
+        // SAFETY: Clearing and writing to the pasteboard is safe.
+        unsafe {
+            let _ = self.clearContents();
+            self.setData_forType(Some(&NSData::with_bytes(data)), ty)
+        }
+    }
+
+    /// The data stored under the given type on the pasteboard, if any.
+    #[doc(alias = "dataForType:")]
+    pub fn data(&self, ty: &NSPasteboardType) -> Option<Vec<u8>> {
+        // SAFETY: Reading from the pasteboard is safe.
+        let data = unsafe { self.dataForType(ty) };
+        data.map(|data| data.bytes().to_vec())
+    }
+
+    /// Replace the contents of the pasteboard with the given file URLs.
+    ///
+    /// Returns `false` if the pasteboard could not be written to.
+    #[doc(alias = "writeObjects:")]
+    pub fn set_file_urls(&self, urls: &[&NSURL]) -> bool {
+        let objects: Vec<_> = urls
+            .iter()
+            .map(|url| ProtocolObject::from_id(url.retain()))
+            .collect();
+        let objects = NSArray::from_vec(objects);
+        // SAFETY: Clearing and writing to the pasteboard is safe, and
+        // `NSURL` implements `NSPasteboardWriting`.
+        unsafe {
+            let _ = self.clearContents();
+            self.writeObjects(&objects)
+        }
+    }
+
+    /// The file URLs on the pasteboard.
+    #[doc(alias = "readObjectsForClasses:options:")]
+    pub fn file_urls(&self) -> Vec<Id<NSURL>> {
+        let classes = NSArray::from_vec(alloc::vec![class_object(NSURL::class())]);
+        // SAFETY: `NSURL` implements `NSPasteboardReading`.
+        let objects = unsafe { self.readObjectsForClasses_options(&classes, None) };
+        let objects = match objects {
+            Some(objects) => objects,
+            None => return Vec::new(),
+        };
+        objects
+            .iter()
+            .map(|object| {
+                let object: *const AnyObject = object;
+                // SAFETY: Only `NSURL`s were requested, and they are
+                // immutable, so retaining them is safe.
+                unsafe { Id::retain(object as *mut NSURL) }.unwrap()
+            })
+            // SAFETY: Checking whether the URL is a file URL is safe.
+            .filter(|url| unsafe { url.isFileURL() })
+            .collect()
+    }
+
+    /// Replace the contents of the pasteboard with the given image.
+    ///
+    /// Returns `false` if the pasteboard could not be written to.
+    #[cfg(feature = "NSImage")]
+    #[doc(alias = "writeObjects:")]
+    pub fn set_image(&self, image: &NSImage) -> bool {
+        let objects = NSArray::from_vec(alloc::vec![ProtocolObject::from_id(image.retain())]);
+        // SAFETY: Clearing and writing to the pasteboard is safe, and
+        // `NSImage` implements `NSPasteboardWriting`.
+        unsafe {
+            let _ = self.clearContents();
+            self.writeObjects(&objects)
+        }
+    }
+
+    /// The image on the pasteboard, if any.
+    #[cfg(feature = "NSImage")]
+    #[doc(alias = "initWithPasteboard:")]
+    pub fn image(&self) -> Option<Id<NSImage>> {
+        // SAFETY: Creating an image from a pasteboard is safe.
+        unsafe { NSImage::initWithPasteboard(NSImage::alloc(), self) }
+    }
+
+    /// Replace the contents of the pasteboard with the value, serialized as
+    /// JSON under the given type.
+    ///
+    /// The type should be a custom uniform type identifier, such as
+    /// `"com.example.my-app.item"`.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be serialized. The
+    /// pasteboard is not modified in that case.
+    #[cfg(feature = "serde")]
+    pub fn set_value<T: serde::Serialize + ?Sized>(
+        &self,
+        ty: &NSPasteboardType,
+        value: &T,
+    ) -> Result<bool, serde_json::Error> {
+        let data = serde_json::to_vec(value)?;
+        Ok(self.set_data(ty, &data))
+    }
+
+    /// The value stored under the given type, deserialized from JSON.
+    ///
+    /// Returns `None` if there is no data of the given type on the
+    /// pasteboard.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data could not be deserialized as `T`.
+    #[cfg(feature = "serde")]
+    pub fn value<T: serde::de::DeserializeOwned>(
+        &self,
+        ty: &NSPasteboardType,
+    ) -> Option<Result<T, serde_json::Error>> {
+        self.data(ty).map(|data| serde_json::from_slice(&data))
+    }
+}
+
+/// Detect changes to a pasteboard by polling its change count.
+///
+/// Pasteboards do not notify observers of changes, so polling is the only
+/// way to detect that another application wrote to the pasteboard.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_app_kit::{NSPasteboard, PasteboardWatcher};
+///
+/// let pasteboard = unsafe { NSPasteboard::generalPasteboard() };
+/// let mut watcher = PasteboardWatcher::new(&pasteboard);
+/// loop {
+///     if watcher.has_changed() {
+///         println!("pasteboard changed: {:?}", pasteboard.string());
+///     }
+///     std::thread::sleep(std::time::Duration::from_millis(500));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PasteboardWatcher {
+    pasteboard: Id<NSPasteboard>,
+    change_count: NSInteger,
+}
+
+impl PasteboardWatcher {
+    /// Start watching the pasteboard for changes, starting from its current
+    /// contents.
+    pub fn new(pasteboard: &NSPasteboard) -> Self {
+        Self {
+            pasteboard: pasteboard.retain(),
+            // SAFETY: Getting the change count is safe.
+            change_count: unsafe { pasteboard.changeCount() },
+        }
+    }
+
+    /// Whether the contents of the pasteboard have changed since the
+    /// watcher was created or last returned `true`.
+    #[doc(alias = "changeCount")]
+    pub fn has_changed(&mut self) -> bool {
+        // SAFETY: Getting the change count is safe.
+        let change_count = unsafe { self.pasteboard.changeCount() };
+        if change_count == self.change_count {
+            false
+        } else {
+            self.change_count = change_count;
+            true
+        }
+    }
+
+    /// The watched pasteboard.
+    pub fn pasteboard(&self) -> &NSPasteboard {
+        &self.pasteboard
+    }
+}
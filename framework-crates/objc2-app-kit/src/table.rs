@@ -0,0 +1,319 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::{Ref, RefCell};
+use core::fmt;
+
+use objc2::rc::Id;
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
+use objc2_foundation::{MainThreadMarker, NSIndexSet, NSInteger};
+
+use crate::*;
+
+/// The rows that changed between two versions of a list.
+///
+/// The indices in [`removed`](Self::removed) refer to the old list, and the
+/// indices in [`inserted`](Self::inserted) to the new list. Both are sorted
+/// in ascending order. Applying the removals to the old list, followed by
+/// the insertions, produces the new list; this is the order in which table
+/// and collection views expect batch updates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TableDiff {
+    /// The indices of the rows that were removed from the old list.
+    pub removed: Vec<usize>,
+    /// The indices of the rows that were inserted into the new list.
+    pub inserted: Vec<usize>,
+}
+
+impl TableDiff {
+    /// Compute the smallest set of removals and insertions that turns `old`
+    /// into `new`.
+    ///
+    /// Moved rows are represented as a removal and an insertion.
+    ///
+    /// Unchanged rows at the start and end of the lists are skipped in
+    /// linear time. The remaining rows are diffed in `O(n * m)` time and
+    /// space, so this is fast for the usual case of a few rows changing.
+    pub fn new<T: PartialEq>(old: &[T], new: &[T]) -> Self {
+        let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+        let (old_rest, new_rest) = (&old[prefix..], &new[prefix..]);
+        let suffix = old_rest
+            .iter()
+            .rev()
+            .zip(new_rest.iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let old_mid = &old_rest[..old_rest.len() - suffix];
+        let new_mid = &new_rest[..new_rest.len() - suffix];
+
+        // Longest common subsequence table, where `lcs[i][j]` is the length
+        // of the LCS of `old_mid[i..]` and `new_mid[j..]`.
+        let (n, m) = (old_mid.len(), new_mid.len());
+        let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = Self::default();
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                diff.removed.push(prefix + i);
+                i += 1;
+            } else {
+                diff.inserted.push(prefix + j);
+                j += 1;
+            }
+        }
+        diff
+    }
+
+    /// Whether the lists are equal.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.inserted.is_empty()
+    }
+}
+
+/// The type-erased model of a [`TableSource`].
+trait Model {
+    fn len(&self) -> usize;
+
+    fn view(
+        &self,
+        table_view: &NSTableView,
+        column: Option<&NSTableColumn>,
+        row: usize,
+    ) -> Option<Id<NSView>>;
+}
+
+struct Items<T, F> {
+    items: Rc<RefCell<Vec<T>>>,
+    configure: F,
+}
+
+impl<T, F> Model for Items<T, F>
+where
+    F: Fn(&NSTableView, Option<&NSTableColumn>, &T) -> Id<NSView>,
+{
+    fn len(&self) -> usize {
+        self.items.borrow().len()
+    }
+
+    fn view(
+        &self,
+        table_view: &NSTableView,
+        column: Option<&NSTableColumn>,
+        row: usize,
+    ) -> Option<Id<NSView>> {
+        let items = self.items.borrow();
+        let item = items.get(row)?;
+        Some((self.configure)(table_view, column, item))
+    }
+}
+
+declare_class!(
+    /// The data source and delegate installed by a [`TableSource`].
+    struct TableAdapter;
+
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - Table views are only used on the main thread.
+    // - `TableAdapter` does not implement `Drop`.
+    unsafe impl ClassType for TableAdapter {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustTableAdapter";
+    }
+
+    impl DeclaredClass for TableAdapter {
+        type Ivars = Box<dyn Model>;
+    }
+
+    unsafe impl NSObjectProtocol for TableAdapter {}
+
+    unsafe impl NSTableViewDataSource for TableAdapter {
+        #[method(numberOfRowsInTableView:)]
+        fn number_of_rows(&self, _table_view: &NSTableView) -> NSInteger {
+            self.ivars().len() as NSInteger
+        }
+    }
+
+    unsafe impl NSTableViewDelegate for TableAdapter {
+        #[method_id(tableView:viewForTableColumn:row:)]
+        fn view_for_row(
+            &self,
+            table_view: &NSTableView,
+            column: Option<&NSTableColumn>,
+            row: NSInteger,
+        ) -> Option<Id<NSView>> {
+            self.ivars().view(table_view, column, row as usize)
+        }
+    }
+);
+
+/// A view-based table view data source backed by a list of Rust values.
+///
+/// The view for each row is created by a closure, which usually reuses
+/// views with `makeViewWithIdentifier:owner:`. The list can be replaced
+/// with [`set_items`](Self::set_items), which animates the rows that
+/// changed.
+///
+/// The table view only holds weak references to its data source and
+/// delegate, so the `TableSource` must be kept alive for as long as it is
+/// attached.
+///
+///
+/// # Panics
+///
+/// The closure must not replace the items of the table it is configuring
+/// a row of.
+pub struct TableSource<T> {
+    adapter: Id<TableAdapter>,
+    items: Rc<RefCell<Vec<T>>>,
+}
+
+impl<T: 'static> TableSource<T> {
+    /// Create a data source for the given items.
+    pub fn new<F>(mtm: MainThreadMarker, items: Vec<T>, configure: F) -> Self
+    where
+        F: Fn(&NSTableView, Option<&NSTableColumn>, &T) -> Id<NSView> + 'static,
+    {
+        let items = Rc::new(RefCell::new(items));
+        let model: Box<dyn Model> = Box::new(Items {
+            items: Rc::clone(&items),
+            configure,
+        });
+        let adapter = mtm.alloc::<TableAdapter>().set_ivars(model);
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        let adapter = unsafe { msg_send_id![super(adapter), init] };
+        Self { adapter, items }
+    }
+
+    /// Install this as the data source and delegate of the table view, and
+    /// load its rows.
+    #[doc(alias = "setDataSource:")]
+    #[doc(alias = "setDelegate:")]
+    pub fn attach(&self, table_view: &NSTableView) {
+        // SAFETY: The adapter implements both protocols, and is kept alive
+        // by `self`.
+        unsafe {
+            table_view.setDataSource(Some(ProtocolObject::from_ref(&*self.adapter)));
+            table_view.setDelegate(Some(ProtocolObject::from_ref(&*self.adapter)));
+            table_view.reloadData();
+        }
+    }
+
+    /// The current items.
+    pub fn items(&self) -> Ref<'_, [T]> {
+        Ref::map(self.items.borrow(), |items| &**items)
+    }
+
+    /// Replace the items, and reload every row of the table view.
+    #[doc(alias = "reloadData")]
+    pub fn replace_items(&self, table_view: &NSTableView, items: Vec<T>) {
+        *self.items.borrow_mut() = items;
+        // SAFETY: Reloading the table view is safe.
+        unsafe { table_view.reloadData() };
+    }
+
+    /// Replace the items, and animate the removal and insertion of the rows
+    /// that changed.
+    ///
+    /// Returns the computed difference. Rows whose items compare equal are
+    /// left alone; to refresh the contents of such rows, use
+    /// `reloadDataForRowIndexes:columnIndexes:`.
+    #[doc(alias = "removeRowsAtIndexes:withAnimation:")]
+    #[doc(alias = "insertRowsAtIndexes:withAnimation:")]
+    pub fn set_items(
+        &self,
+        table_view: &NSTableView,
+        items: Vec<T>,
+        animation: NSTableViewAnimationOptions,
+    ) -> TableDiff
+    where
+        T: PartialEq,
+    {
+        let diff = TableDiff::new(&self.items.borrow(), &items);
+        *self.items.borrow_mut() = items;
+        if diff.is_empty() {
+            return diff;
+        }
+        let removed = NSIndexSet::from_indexes(&diff.removed);
+        let inserted = NSIndexSet::from_indexes(&diff.inserted);
+        // SAFETY: The updates are balanced, and the indices are in bounds of
+        // the table at the time they are applied.
+        unsafe {
+            table_view.beginUpdates();
+            table_view.removeRowsAtIndexes_withAnimation(&removed, animation);
+            table_view.insertRowsAtIndexes_withAnimation(&inserted, animation);
+            table_view.endUpdates();
+        }
+        diff
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TableSource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TableSource")
+            .field("items", &self.items.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(old: &[char], new: &[char]) {
+        let diff = TableDiff::new(old, new);
+        let mut list = old.to_vec();
+        for &index in diff.removed.iter().rev() {
+            list.remove(index);
+        }
+        for &index in &diff.inserted {
+            list.insert(index, new[index]);
+        }
+        assert_eq!(list, new);
+    }
+
+    #[test]
+    fn diff() {
+        let diff = TableDiff::new(&['a', 'b', 'c', 'd'], &['a', 'x', 'c', 'd', 'e']);
+        assert_eq!(diff.removed, [1]);
+        assert_eq!(diff.inserted, [1, 4]);
+
+        assert!(TableDiff::new(&['a', 'b'], &['a', 'b']).is_empty());
+        assert_eq!(TableDiff::new::<char>(&[], &['a']).inserted, [0]);
+        assert_eq!(TableDiff::new::<char>(&['a'], &[]).removed, [0]);
+    }
+
+    #[test]
+    fn diff_applies() {
+        let cases: &[(&str, &str)] = &[
+            ("", ""),
+            ("abc", ""),
+            ("", "abc"),
+            ("abcdef", "abdf"),
+            ("abc", "cab"),
+            ("aaaa", "aa"),
+            ("kitten", "sitting"),
+            ("abcabba", "cbabac"),
+        ];
+        for (old, new) in cases {
+            let old: Vec<char> = old.chars().collect();
+            let new: Vec<char> = new.chars().collect();
+            apply(&old, &new);
+        }
+    }
+}
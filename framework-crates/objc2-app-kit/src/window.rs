@@ -0,0 +1,296 @@
+use alloc::boxed::Box;
+use core::fmt;
+
+use objc2::rc::Id;
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
+use objc2_foundation::{MainThreadMarker, NSNotification, NSPoint, NSRect, NSSize, NSString};
+
+use crate::*;
+
+impl NSWindow {
+    /// Create a window with the given content rectangle (in screen
+    /// coordinates) and style.
+    ///
+    /// The window is buffered, and is not released when closed, so that it
+    /// can be owned by the returned [`Id`] like any other object. It is not
+    /// shown until ordered front, for example with `makeKeyAndOrderFront:`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_app_kit::{NSWindow, NSWindowStyleMask};
+    /// use objc2_foundation::{MainThreadMarker, NSPoint, NSRect, NSSize};
+    ///
+    /// let mtm = MainThreadMarker::new().unwrap();
+    /// let window = NSWindow::with_content_rect(
+    ///     mtm,
+    ///     NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(800.0, 600.0)),
+    ///     NSWindowStyleMask::Titled | NSWindowStyleMask::Closable | NSWindowStyleMask::Resizable,
+    /// );
+    /// window.set_title("Hello");
+    /// ```
+    #[doc(alias = "initWithContentRect:styleMask:backing:defer:")]
+    pub fn with_content_rect(
+        mtm: MainThreadMarker,
+        content_rect: NSRect,
+        style: NSWindowStyleMask,
+    ) -> Id<Self> {
+        // SAFETY: The backing type is valid, and windows are created on the
+        // main thread. Disabling `releasedWhenClosed` is required for the
+        // window to be owned by `Id`, since it would otherwise release
+        // itself when closed.
+        unsafe {
+            let window = Self::initWithContentRect_styleMask_backing_defer(
+                mtm.alloc(),
+                content_rect,
+                style,
+                NSBackingStoreType::NSBackingStoreBuffered,
+                false,
+            );
+            window.setReleasedWhenClosed(false);
+            window
+        }
+    }
+
+    /// Set the title of the window.
+    #[doc(alias = "setTitle:")]
+    pub fn set_title(&self, title: &str) {
+        // SAFETY: Setting the title is safe.
+        unsafe { self.setTitle(&NSString::from_str(title)) };
+    }
+
+    /// Replace the content view of the window.
+    ///
+    /// The view is resized to fill the content area of the window.
+    #[doc(alias = "setContentView:")]
+    pub fn set_content_view(&self, view: &NSView) {
+        // SAFETY: Setting the content view is safe.
+        unsafe { self.setContentView(Some(view)) };
+    }
+
+    /// Set closures to handle events on the window.
+    ///
+    /// This replaces the window's delegate. The window only holds a weak
+    /// reference to its delegate, so the returned object must be kept alive
+    /// for as long as the handlers should be called.
+    #[doc(alias = "setDelegate:")]
+    #[must_use = "the handlers are only called while the delegate is alive"]
+    pub fn set_handlers(&self, handlers: WindowHandlers) -> Id<WindowDelegate> {
+        let delegate = WindowDelegate::new(MainThreadMarker::from(self), handlers);
+        // SAFETY: The delegate implements the protocol, and is kept alive by
+        // the caller.
+        unsafe { self.setDelegate(Some(ProtocolObject::from_ref(&*delegate))) };
+        delegate
+    }
+
+    /// Convert a point from the window's base coordinate system to screen
+    /// coordinates.
+    #[doc(alias = "convertPointToScreen:")]
+    pub fn point_to_screen(&self, point: NSPoint) -> NSPoint {
+        // SAFETY: Converting coordinates is safe.
+        unsafe { self.convertPointToScreen(point) }
+    }
+
+    /// Convert a point from screen coordinates to the window's base
+    /// coordinate system.
+    #[doc(alias = "convertPointFromScreen:")]
+    pub fn point_from_screen(&self, point: NSPoint) -> NSPoint {
+        // SAFETY: Converting coordinates is safe.
+        unsafe { self.convertPointFromScreen(point) }
+    }
+
+    /// Convert a rectangle from the window's base coordinate system to
+    /// screen coordinates.
+    #[doc(alias = "convertRectToScreen:")]
+    pub fn rect_to_screen(&self, rect: NSRect) -> NSRect {
+        // SAFETY: Converting coordinates is safe.
+        unsafe { self.convertRectToScreen(rect) }
+    }
+
+    /// Convert a rectangle from screen coordinates to the window's base
+    /// coordinate system.
+    #[doc(alias = "convertRectFromScreen:")]
+    pub fn rect_from_screen(&self, rect: NSRect) -> NSRect {
+        // SAFETY: Converting coordinates is safe.
+        unsafe { self.convertRectFromScreen(rect) }
+    }
+}
+
+impl NSView {
+    /// Convert a point from the view's coordinate system to the base
+    /// coordinate system of its window.
+    #[doc(alias = "convertPoint:toView:")]
+    pub fn point_to_window(&self, point: NSPoint) -> NSPoint {
+        // SAFETY: Converting coordinates is safe. Passing no view converts
+        // to window coordinates.
+        unsafe { self.convertPoint_toView(point, None) }
+    }
+
+    /// Convert a point from the base coordinate system of the view's window
+    /// to the view's coordinate system.
+    #[doc(alias = "convertPoint:fromView:")]
+    pub fn point_from_window(&self, point: NSPoint) -> NSPoint {
+        // SAFETY: Converting coordinates is safe. Passing no view converts
+        // from window coordinates.
+        unsafe { self.convertPoint_fromView(point, None) }
+    }
+
+    /// Convert a rectangle from the view's coordinate system to the base
+    /// coordinate system of its window.
+    #[doc(alias = "convertRect:toView:")]
+    pub fn rect_to_window(&self, rect: NSRect) -> NSRect {
+        // SAFETY: Converting coordinates is safe. Passing no view converts
+        // to window coordinates.
+        unsafe { self.convertRect_toView(rect, None) }
+    }
+
+    /// Convert a rectangle from the base coordinate system of the view's
+    /// window to the view's coordinate system.
+    #[doc(alias = "convertRect:fromView:")]
+    pub fn rect_from_window(&self, rect: NSRect) -> NSRect {
+        // SAFETY: Converting coordinates is safe. Passing no view converts
+        // from window coordinates.
+        unsafe { self.convertRect_fromView(rect, None) }
+    }
+
+    /// Convert a point from the view's coordinate system to screen
+    /// coordinates.
+    ///
+    /// Returns `None` if the view is not in a window.
+    pub fn point_to_screen(&self, point: NSPoint) -> Option<NSPoint> {
+        // SAFETY: Getting the window is safe.
+        let window = unsafe { self.window() }?;
+        Some(window.point_to_screen(self.point_to_window(point)))
+    }
+
+    /// Convert a rectangle from the view's coordinate system to screen
+    /// coordinates.
+    ///
+    /// Returns `None` if the view is not in a window.
+    pub fn rect_to_screen(&self, rect: NSRect) -> Option<NSRect> {
+        // SAFETY: Getting the window is safe.
+        let window = unsafe { self.window() }?;
+        Some(window.rect_to_screen(self.rect_to_window(rect)))
+    }
+}
+
+/// Closures called in response to events on a window.
+///
+/// Used with [`NSWindow::set_handlers`].
+#[derive(Default)]
+pub struct WindowHandlers {
+    should_close: Option<Box<dyn Fn(&NSWindow) -> bool>>,
+    will_close: Option<Box<dyn Fn(&NSWindow)>>,
+    did_resize: Option<Box<dyn Fn(&NSWindow, NSSize)>>,
+}
+
+impl WindowHandlers {
+    /// No handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decide whether the window may be closed when the user clicks the
+    /// close button.
+    #[doc(alias = "windowShouldClose:")]
+    pub fn should_close(mut self, f: impl Fn(&NSWindow) -> bool + 'static) -> Self {
+        self.should_close = Some(Box::new(f));
+        self
+    }
+
+    /// Called when the window is about to close.
+    #[doc(alias = "windowWillClose:")]
+    pub fn on_close(mut self, f: impl Fn(&NSWindow) + 'static) -> Self {
+        self.will_close = Some(Box::new(f));
+        self
+    }
+
+    /// Called with the new size of the content area when the window has
+    /// been resized.
+    #[doc(alias = "windowDidResize:")]
+    pub fn on_resize(mut self, f: impl Fn(&NSWindow, NSSize) + 'static) -> Self {
+        self.did_resize = Some(Box::new(f));
+        self
+    }
+}
+
+impl fmt::Debug for WindowHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WindowHandlers")
+            .field("should_close", &self.should_close.is_some())
+            .field("will_close", &self.will_close.is_some())
+            .field("did_resize", &self.did_resize.is_some())
+            .finish()
+    }
+}
+
+/// The window that posted a window notification.
+fn notification_window(notification: &NSNotification) -> Option<Id<NSWindow>> {
+    // SAFETY: Getting the object is safe.
+    let object = unsafe { notification.object() }?;
+    // SAFETY: The object of window notifications is the window.
+    Some(unsafe { Id::cast(object) })
+}
+
+declare_class!(
+    /// A window delegate that calls Rust closures.
+    ///
+    /// Created with [`NSWindow::set_handlers`].
+    pub struct WindowDelegate;
+
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - Window delegates are only used on the main thread.
+    // - `WindowDelegate` does not implement `Drop`.
+    unsafe impl ClassType for WindowDelegate {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustWindowDelegate";
+    }
+
+    impl DeclaredClass for WindowDelegate {
+        type Ivars = WindowHandlers;
+    }
+
+    unsafe impl NSObjectProtocol for WindowDelegate {}
+
+    unsafe impl NSWindowDelegate for WindowDelegate {
+        #[method(windowShouldClose:)]
+        fn window_should_close(&self, sender: &NSWindow) -> bool {
+            match &self.ivars().should_close {
+                Some(should_close) => should_close(sender),
+                None => true,
+            }
+        }
+
+        #[method(windowWillClose:)]
+        fn window_will_close(&self, notification: &NSNotification) {
+            if let Some(will_close) = &self.ivars().will_close {
+                if let Some(window) = notification_window(notification) {
+                    will_close(&window);
+                }
+            }
+        }
+
+        #[method(windowDidResize:)]
+        fn window_did_resize(&self, notification: &NSNotification) {
+            if let Some(did_resize) = &self.ivars().did_resize {
+                if let Some(window) = notification_window(notification) {
+                    // SAFETY: Getting the frame of the window is safe.
+                    let size = unsafe { window.contentRectForFrameRect(window.frame()) }.size;
+                    did_resize(&window, size);
+                }
+            }
+        }
+    }
+);
+
+impl WindowDelegate {
+    fn new(mtm: MainThreadMarker, handlers: WindowHandlers) -> Id<Self> {
+        let this = mtm.alloc::<Self>().set_ivars(handlers);
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
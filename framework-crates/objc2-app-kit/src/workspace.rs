@@ -0,0 +1,150 @@
+use core::fmt;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::task::{Context, Poll};
+
+use block2::{Block, RcBlock};
+use futures_core::Stream;
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+use objc2_foundation::{
+    event_channel, BufferPolicy, EventSender, EventStream, NSNotification, NSNotificationCenter,
+    NSNotificationName,
+};
+
+use crate::*;
+
+/// A change to the power state of the system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PowerEvent {
+    /// The system is about to sleep.
+    ///
+    /// This is the time to checkpoint state. Note that the system does not
+    /// wait for the stream to be polled, so this should be done promptly.
+    #[doc(alias = "NSWorkspaceWillSleepNotification")]
+    WillSleep,
+    /// The system woke from sleep.
+    #[doc(alias = "NSWorkspaceDidWakeNotification")]
+    DidWake,
+    /// The displays went to sleep.
+    #[doc(alias = "NSWorkspaceScreensDidSleepNotification")]
+    ScreensDidSleep,
+    /// The displays woke from sleep.
+    #[doc(alias = "NSWorkspaceScreensDidWakeNotification")]
+    ScreensDidWake,
+    /// The user requested a logout or power off.
+    #[doc(alias = "NSWorkspaceWillPowerOffNotification")]
+    WillPowerOff,
+}
+
+/// A stream of changes to the power state of the system.
+///
+/// Created with [`NSWorkspace::power_events`]. The observers are removed
+/// from the workspace's notification center when the stream is dropped.
+#[must_use = "streams do nothing unless polled"]
+pub struct PowerEvents {
+    center: Id<NSNotificationCenter>,
+    tokens: [Id<AnyObject>; 5],
+    stream: EventStream<PowerEvent>,
+}
+
+impl NSWorkspace {
+    /// Observe the system going to sleep and waking up as an asynchronous
+    /// [`Stream`].
+    ///
+    /// Events are buffered according to the given policy until the stream
+    /// is polled. The notifications are posted on the main thread, so the
+    /// main run loop must be running for events to be delivered.
+    #[doc(alias = "notificationCenter")]
+    pub fn power_events(&self, policy: BufferPolicy) -> PowerEvents {
+        let (sender, stream) = event_channel(policy);
+        // SAFETY: Getting the notification center is safe.
+        let center = unsafe { self.notificationCenter() };
+        // SAFETY: The notification names are valid strings.
+        let tokens = unsafe {
+            [
+                observe(
+                    &center,
+                    NSWorkspaceWillSleepNotification,
+                    &sender,
+                    PowerEvent::WillSleep,
+                ),
+                observe(
+                    &center,
+                    NSWorkspaceDidWakeNotification,
+                    &sender,
+                    PowerEvent::DidWake,
+                ),
+                observe(
+                    &center,
+                    NSWorkspaceScreensDidSleepNotification,
+                    &sender,
+                    PowerEvent::ScreensDidSleep,
+                ),
+                observe(
+                    &center,
+                    NSWorkspaceScreensDidWakeNotification,
+                    &sender,
+                    PowerEvent::ScreensDidWake,
+                ),
+                observe(
+                    &center,
+                    NSWorkspaceWillPowerOffNotification,
+                    &sender,
+                    PowerEvent::WillPowerOff,
+                ),
+            ]
+        };
+        PowerEvents {
+            center,
+            tokens,
+            stream,
+        }
+    }
+}
+
+fn observe(
+    center: &NSNotificationCenter,
+    name: &NSNotificationName,
+    sender: &EventSender<PowerEvent>,
+    event: PowerEvent,
+) -> Id<AnyObject> {
+    let block = RcBlock::new({
+        let sender = sender.clone();
+        move |_: NonNull<NSNotification>| sender.send(event)
+    });
+    let block: &Block<dyn Fn(NonNull<NSNotification>) + 'static> = &block;
+    // SAFETY: The block has the correct signature, and the center retains
+    // (copies) it.
+    let token =
+        unsafe { center.addObserverForName_object_queue_usingBlock(Some(name), None, None, block) };
+    // SAFETY: The observer token is an object.
+    unsafe { Id::cast(token) }
+}
+
+impl Stream for PowerEvents {
+    type Item = PowerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<PowerEvent>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+impl Drop for PowerEvents {
+    fn drop(&mut self) {
+        for token in &self.tokens {
+            // SAFETY: The tokens were returned by
+            // `addObserverForName:object:queue:usingBlock:` on this center.
+            unsafe { self.center.removeObserver(token) };
+        }
+    }
+}
+
+impl fmt::Debug for PowerEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PowerEvents")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
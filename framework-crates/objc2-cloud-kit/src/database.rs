@@ -0,0 +1,375 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::future::Future;
+
+use block2::{Block, RcBlock};
+use objc2::mutability::IsIdCloneable;
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+use objc2::{ClassType, Message};
+use objc2_foundation::{oneshot, NSArray, NSDictionary, NSError, NSPredicate, NSString};
+
+use crate::*;
+
+/// Retain the object or error passed to a completion handler.
+///
+/// # Safety
+///
+/// The pointers must be NULL or valid objects, and one of them must be
+/// non-NULL.
+unsafe fn result<T: ClassType>(object: *mut T, error: *mut NSError) -> Result<Id<T>, Id<NSError>> {
+    // SAFETY: Upheld by the caller.
+    match unsafe { Id::retain(error) } {
+        Some(error) => Err(error),
+        None => Ok(unsafe { Id::retain(object) }.expect("completion handler got no result")),
+    }
+}
+
+/// The errors for individual items of a batch operation that partially
+/// failed.
+///
+/// CloudKit reports such failures as a single error with the code
+/// `CKErrorPartialFailure`, with the error for each item in its user info.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PartialFailure {
+    errors: Vec<(Id<CKRecordID>, Id<NSError>)>,
+}
+
+impl PartialFailure {
+    /// Split a partial failure into the errors for each record.
+    ///
+    /// Returns `None` if the error is not a partial failure.
+    #[doc(alias = "CKPartialErrorsByItemIDKey")]
+    pub fn from_error(error: &NSError) -> Option<Self> {
+        // SAFETY: The statics are valid strings.
+        let (domain, key) = unsafe { (CKErrorDomain, CKPartialErrorsByItemIDKey) };
+        if *error.domain() != *domain || error.code() != CKErrorCode::CKErrorPartialFailure.0 {
+            return None;
+        }
+        let user_info = error.userInfo();
+        let errors: *const AnyObject = user_info.get(key)?;
+        // SAFETY: The partial errors of record operations map record IDs to
+        // errors.
+        let errors = unsafe { &*errors.cast::<NSDictionary<CKRecordID, NSError>>() };
+        let (ids, errors) = errors.to_vecs();
+        Some(Self {
+            errors: ids
+                .into_iter()
+                .zip(errors)
+                .map(|(id, error)| (id.retain(), error.retain()))
+                .collect(),
+        })
+    }
+
+    /// The identifier and error of each record that failed.
+    pub fn errors(&self) -> &[(Id<CKRecordID>, Id<NSError>)] {
+        &self.errors
+    }
+
+    /// The identifiers of the records that failed because of another
+    /// record, and that can be retried as they are.
+    ///
+    /// In atomic operations, every record fails if one of them does; these
+    /// have the error code `CKErrorBatchRequestFailed`.
+    pub fn retryable(&self) -> impl Iterator<Item = &CKRecordID> {
+        self.errors
+            .iter()
+            .filter(|(_, error)| error.code() == CKErrorCode::CKErrorBatchRequestFailed.0)
+            .map(|(id, _)| &**id)
+    }
+}
+
+/// An error from a batch operation on a [`CKDatabase`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BatchError {
+    /// The whole operation failed.
+    Failed(Id<NSError>),
+    /// Some of the records failed.
+    Partial {
+        /// The records that were processed successfully.
+        succeeded: Vec<Id<CKRecord>>,
+        /// The errors for the records that failed.
+        failure: PartialFailure,
+    },
+}
+
+impl BatchError {
+    fn new(error: Id<NSError>, succeeded: Vec<Id<CKRecord>>) -> Self {
+        match PartialFailure::from_error(&error) {
+            Some(failure) => Self::Partial { succeeded, failure },
+            None => Self::Failed(error),
+        }
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(error) => write!(f, "operation failed: {error}"),
+            Self::Partial { succeeded, failure } => write!(
+                f,
+                "operation partially failed: {} records failed, {} succeeded",
+                failure.errors.len(),
+                succeeded.len(),
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Failed(error) => Some(&**error),
+            Self::Partial { .. } => None,
+        }
+    }
+}
+
+/// Retain the objects in an array passed to a completion handler.
+fn array_to_vec<T: ClassType + IsIdCloneable>(array: *mut NSArray<T>) -> Vec<Id<T>> {
+    // SAFETY: The pointer is NULL or a valid array.
+    match unsafe { Id::retain(array) } {
+        Some(array) => (0..array.len())
+            .filter_map(|index| array.get_retained(index))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+impl CKDatabase {
+    /// Save the record to the database.
+    ///
+    /// Resolves to the record as saved by the server, which includes the
+    /// server's change tag and metadata.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by CloudKit, for example
+    /// `CKErrorServerRecordChanged` if the record was changed on the server
+    /// since it was fetched.
+    #[doc(alias = "saveRecord:completionHandler:")]
+    pub fn save(
+        &self,
+        record: &CKRecord,
+    ) -> impl Future<Output = Result<Id<CKRecord>, Id<NSError>>> {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new({
+            // SAFETY: The record is set if and only if the error is not.
+            move |record: *mut CKRecord, error: *mut NSError| {
+                sender.send(unsafe { result(record, error) })
+            }
+        });
+        let block: &Block<dyn Fn(*mut CKRecord, *mut NSError) + 'static> = &block;
+        // SAFETY: The block has the correct signature, and is copied by the
+        // database. It may be called on any thread, which is fine since the
+        // sender is thread-safe.
+        unsafe { self.saveRecord_completionHandler(record, block) };
+        receiver
+    }
+
+    /// Fetch the record with the given identifier.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by CloudKit, for example
+    /// `CKErrorUnknownItem` if no such record exists.
+    #[doc(alias = "fetchRecordWithID:completionHandler:")]
+    pub fn fetch(
+        &self,
+        id: &CKRecordID,
+    ) -> impl Future<Output = Result<Id<CKRecord>, Id<NSError>>> {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new({
+            // SAFETY: The record is set if and only if the error is not.
+            move |record: *mut CKRecord, error: *mut NSError| {
+                sender.send(unsafe { result(record, error) })
+            }
+        });
+        let block: &Block<dyn Fn(*mut CKRecord, *mut NSError) + 'static> = &block;
+        // SAFETY: See `save`.
+        unsafe { self.fetchRecordWithID_completionHandler(id, block) };
+        receiver
+    }
+
+    /// Delete the record with the given identifier.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by CloudKit.
+    #[doc(alias = "deleteRecordWithID:completionHandler:")]
+    pub fn delete(&self, id: &CKRecordID) -> impl Future<Output = Result<(), Id<NSError>>> {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new({
+            // SAFETY: The ID is set if and only if the error is not.
+            move |id: *mut CKRecordID, error: *mut NSError| {
+                sender.send(unsafe { result(id, error) }.map(drop))
+            }
+        });
+        let block: &Block<dyn Fn(*mut CKRecordID, *mut NSError) + 'static> = &block;
+        // SAFETY: See `save`.
+        unsafe { self.deleteRecordWithID_completionHandler(id, block) };
+        receiver
+    }
+
+    /// Fetch the records matching the query, in the given zone or the
+    /// default zone.
+    ///
+    /// This fetches as many records as CloudKit returns in one batch; use
+    /// `CKQueryOperation` to page through larger result sets.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by CloudKit, for example if a field in the
+    /// predicate is not marked as queryable in the schema.
+    #[doc(alias = "performQuery:inZoneWithID:completionHandler:")]
+    pub fn query(
+        &self,
+        query: &CKQuery,
+        zone: Option<&CKRecordZoneID>,
+    ) -> impl Future<Output = Result<Vec<Id<CKRecord>>, Id<NSError>>> {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new({
+            move |records: *mut NSArray<CKRecord>, error: *mut NSError| {
+                // SAFETY: The error is NULL or a valid error.
+                let result = match unsafe { Id::retain(error) } {
+                    Some(error) => Err(error),
+                    None => Ok(array_to_vec(records)),
+                };
+                sender.send(result)
+            }
+        });
+        let block: &Block<dyn Fn(*mut NSArray<CKRecord>, *mut NSError) + 'static> = &block;
+        // SAFETY: See `save`.
+        #[allow(deprecated)]
+        unsafe {
+            self.performQuery_inZoneWithID_completionHandler(query, zone, block)
+        };
+        receiver
+    }
+
+    /// Save the records in a single operation.
+    ///
+    /// Records are saved individually, such that some of them may be saved
+    /// even if others fail.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BatchError::Partial`] with the records that were saved and
+    /// the errors for the others if only some of them failed, and
+    /// [`BatchError::Failed`] if the whole operation failed.
+    #[doc(alias = "CKModifyRecordsOperation")]
+    pub fn save_all(
+        &self,
+        records: &[Id<CKRecord>],
+    ) -> impl Future<Output = Result<Vec<Id<CKRecord>>, BatchError>> {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new({
+            move |saved: *mut NSArray<CKRecord>,
+                  _deleted: *mut NSArray<CKRecordID>,
+                  error: *mut NSError| {
+                let saved = array_to_vec(saved);
+                // SAFETY: The error is NULL or a valid error.
+                let result = match unsafe { Id::retain(error) } {
+                    Some(error) => Err(BatchError::new(error, saved)),
+                    None => Ok(saved),
+                };
+                sender.send(result)
+            }
+        });
+        let block: &Block<
+            dyn Fn(*mut NSArray<CKRecord>, *mut NSArray<CKRecordID>, *mut NSError) + 'static,
+        > = &block;
+        // SAFETY: The block has the correct signature, and is copied by the
+        // operation. The operation is retained by the database until it has
+        // finished.
+        unsafe {
+            let operation = CKModifyRecordsOperation::initWithRecordsToSave_recordIDsToDelete(
+                CKModifyRecordsOperation::alloc(),
+                Some(&NSArray::from_id_slice(records)),
+                None,
+            );
+            operation.setAtomic(false);
+            operation.setModifyRecordsCompletionBlock(Some(block));
+            self.addOperation(&operation);
+        }
+        receiver
+    }
+
+    /// Fetch the record with the given identifier, and convert it to `T`.
+    ///
+    /// Errors from converting the record are reported as
+    /// [`FieldError`]s wrapped in [`FetchError::Field`].
+    pub fn fetch_as<T: Record>(
+        &self,
+        id: &CKRecordID,
+    ) -> impl Future<Output = Result<T, FetchError>> {
+        let fetch = self.fetch(id);
+        async move {
+            let record = fetch.await.map_err(FetchError::CloudKit)?;
+            T::from_record(&record).map_err(FetchError::Field)
+        }
+    }
+
+    /// Fetch the records of type `T` matching the predicate, and convert
+    /// them to `T`.
+    ///
+    /// See [`query`](Self::query) for details.
+    #[doc(alias = "initWithRecordType:predicate:")]
+    pub fn query_as<T: Record>(
+        &self,
+        predicate: &NSPredicate,
+        zone: Option<&CKRecordZoneID>,
+    ) -> impl Future<Output = Result<Vec<T>, FetchError>> {
+        // SAFETY: Creating a query is safe; the predicate is validated when
+        // the query is performed.
+        let query = unsafe {
+            CKQuery::initWithRecordType_predicate(
+                CKQuery::alloc(),
+                &NSString::from_str(T::RECORD_TYPE),
+                predicate,
+            )
+        };
+        let records = self.query(&query, zone);
+        async move {
+            let records = records.await.map_err(FetchError::CloudKit)?;
+            records
+                .iter()
+                .map(|record| T::from_record(record).map_err(FetchError::Field))
+                .collect()
+        }
+    }
+}
+
+/// An error from fetching typed [`Record`]s.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FetchError {
+    /// CloudKit failed to fetch the record.
+    CloudKit(Id<NSError>),
+    /// The record did not match the Rust type.
+    Field(FieldError),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CloudKit(error) => write!(f, "failed fetching record: {error}"),
+            Self::Field(error) => write!(f, "fetched record did not match: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CloudKit(error) => Some(&**error),
+            Self::Field(error) => Some(error),
+        }
+    }
+}
@@ -15,6 +15,49 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "CKDatabase",
+    feature = "CKDatabaseOperation",
+    feature = "CKError",
+    // Required for `objc2-foundation/NSDictionary`.
+    feature = "CKFetchRecordsOperation",
+    feature = "CKModifyRecordsOperation",
+    feature = "CKOperation",
+    feature = "CKQuery",
+    feature = "CKRecord",
+    feature = "CKRecordID",
+    feature = "CKRecordZoneID"
+))]
+mod database;
 mod generated;
+#[cfg(all(feature = "alloc", feature = "CKRecord", feature = "CKRecordID"))]
+mod record;
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "CKDatabase",
+    feature = "CKDatabaseOperation",
+    feature = "CKError",
+    feature = "CKFetchRecordsOperation",
+    feature = "CKModifyRecordsOperation",
+    feature = "CKOperation",
+    feature = "CKQuery",
+    feature = "CKRecord",
+    feature = "CKRecordID",
+    feature = "CKRecordZoneID"
+))]
+pub use self::database::{BatchError, FetchError, PartialFailure};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(feature = "alloc", feature = "CKRecord", feature = "CKRecordID"))]
+pub use self::record::{Field, FieldError, Record};
+
+// Used by `record!`.
+#[doc(hidden)]
+#[cfg(all(feature = "alloc", feature = "CKRecord", feature = "CKRecordID"))]
+pub mod __macro_helpers {
+    pub use crate::record::check_record_type;
+}
@@ -0,0 +1,299 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::ClassType;
+use objc2_foundation::{NSData, NSNumber, NSString};
+
+use crate::*;
+
+/// Cast the object to the given class, if it is an instance of it.
+fn downcast<T: ClassType>(object: Option<&AnyObject>) -> Option<&T> {
+    let object: *const AnyObject = object?;
+    // SAFETY: Record values are `NSObject`s.
+    let checked = unsafe { &*object.cast::<NSObject>() };
+    if checked.is_kind_of::<T>() {
+        // SAFETY: Just checked that the object is an instance of `T`.
+        Some(unsafe { &*object.cast::<T>() })
+    } else {
+        None
+    }
+}
+
+/// A Rust type that can be stored in a field of a [`CKRecord`].
+///
+/// This is implemented for strings, 64-bit integers and floats, booleans,
+/// binary data, and [`Option`] of those, which maps a missing field to
+/// `None`.
+pub trait Field: Sized {
+    /// Convert the value to the object stored in the record.
+    fn to_value(&self) -> Option<Id<ProtocolObject<dyn CKRecordValue>>>;
+
+    /// Convert an object stored in the record to the value.
+    ///
+    /// Returns `None` if the object is of an unexpected class, or if the
+    /// field is missing and `Self` is not an `Option`.
+    fn from_value(value: Option<&AnyObject>) -> Option<Self>;
+}
+
+macro_rules! number_field {
+    ($($t:ty => $new:ident, $as:ident;)*) => {$(
+        impl Field for $t {
+            fn to_value(&self) -> Option<Id<ProtocolObject<dyn CKRecordValue>>> {
+                Some(ProtocolObject::from_id(NSNumber::$new(*self)))
+            }
+
+            fn from_value(value: Option<&AnyObject>) -> Option<Self> {
+                downcast::<NSNumber>(value).map(|number| number.$as())
+            }
+        }
+    )*};
+}
+
+number_field! {
+    i64 => new_i64, as_i64;
+    f64 => new_f64, as_f64;
+    bool => new_bool, as_bool;
+}
+
+impl Field for String {
+    fn to_value(&self) -> Option<Id<ProtocolObject<dyn CKRecordValue>>> {
+        Some(ProtocolObject::from_id(NSString::from_str(self)))
+    }
+
+    fn from_value(value: Option<&AnyObject>) -> Option<Self> {
+        downcast::<NSString>(value).map(|string| string.to_string())
+    }
+}
+
+impl Field for Vec<u8> {
+    fn to_value(&self) -> Option<Id<ProtocolObject<dyn CKRecordValue>>> {
+        Some(ProtocolObject::from_id(NSData::with_bytes(self)))
+    }
+
+    fn from_value(value: Option<&AnyObject>) -> Option<Self> {
+        downcast::<NSData>(value).map(|data| data.bytes().to_vec())
+    }
+}
+
+impl<T: Field> Field for Option<T> {
+    fn to_value(&self) -> Option<Id<ProtocolObject<dyn CKRecordValue>>> {
+        self.as_ref().and_then(T::to_value)
+    }
+
+    fn from_value(value: Option<&AnyObject>) -> Option<Self> {
+        match value {
+            None => Some(None),
+            Some(value) => T::from_value(Some(value)).map(Some),
+        }
+    }
+}
+
+/// An error from converting a [`CKRecord`] to a Rust type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FieldError {
+    /// The record has a different record type.
+    WrongRecordType {
+        /// The expected record type.
+        expected: &'static str,
+        /// The type of the record.
+        actual: String,
+    },
+    /// The field is missing, but the Rust type is not an `Option`.
+    Missing {
+        /// The name of the field.
+        key: String,
+    },
+    /// The value of the field has a type that does not match the Rust type.
+    TypeMismatch {
+        /// The name of the field.
+        key: String,
+    },
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongRecordType { expected, actual } => write!(
+                f,
+                "expected a record of type {expected:?}, found {actual:?}"
+            ),
+            Self::Missing { key } => write!(f, "record has no field {key:?}"),
+            Self::TypeMismatch { key } => {
+                write!(f, "field {key:?} does not match the Rust type")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FieldError {}
+
+impl CKRecord {
+    /// Get the value of a field.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the field is missing and `T` is not an `Option`,
+    /// or if the value does not match `T`.
+    #[doc(alias = "objectForKey:")]
+    pub fn get<T: Field>(&self, key: &str) -> Result<T, FieldError> {
+        // SAFETY: Getting a field is safe.
+        let value = unsafe { self.objectForKey(&NSString::from_str(key)) };
+        let object = value.as_deref().map(|value| {
+            let value: *const ProtocolObject<dyn CKRecordValue> = value;
+            // SAFETY: Protocol objects are objects.
+            unsafe { &*value.cast::<AnyObject>() }
+        });
+        match T::from_value(object) {
+            Some(value) => Ok(value),
+            None if object.is_none() => Err(FieldError::Missing { key: key.into() }),
+            None => Err(FieldError::TypeMismatch { key: key.into() }),
+        }
+    }
+
+    /// Set the value of a field, or remove it if the value is `None`.
+    #[doc(alias = "setObject:forKey:")]
+    pub fn set<T: Field>(&self, key: &str, value: &T) {
+        let value = value.to_value();
+        // SAFETY: The value is of a class that records can store.
+        unsafe { self.setObject_forKey(value.as_deref(), &NSString::from_str(key)) };
+    }
+}
+
+/// A Rust type that can be converted to and from a [`CKRecord`].
+///
+/// This is usually implemented with the [`record!`] macro.
+///
+/// [`record!`]: crate::record
+pub trait Record: Sized {
+    /// The record type in the container's schema.
+    const RECORD_TYPE: &'static str;
+
+    /// Read the fields of the record.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record has a different record type, or if a
+    /// field does not match.
+    fn from_record(record: &CKRecord) -> Result<Self, FieldError>;
+
+    /// Write the fields to the record, replacing their previous values.
+    fn write_to(&self, record: &CKRecord);
+
+    /// Create a new record with a generated identifier in the default zone,
+    /// and write the fields to it.
+    #[doc(alias = "initWithRecordType:")]
+    fn to_record(&self) -> Id<CKRecord> {
+        // SAFETY: Creating a record is safe.
+        let record = unsafe {
+            CKRecord::initWithRecordType(CKRecord::alloc(), &NSString::from_str(Self::RECORD_TYPE))
+        };
+        self.write_to(&record);
+        record
+    }
+
+    /// Create a new record with the given identifier, and write the fields
+    /// to it.
+    #[doc(alias = "initWithRecordType:recordID:")]
+    fn to_record_with_id(&self, id: &CKRecordID) -> Id<CKRecord> {
+        // SAFETY: Creating a record is safe.
+        let record = unsafe {
+            CKRecord::initWithRecordType_recordID(
+                CKRecord::alloc(),
+                &NSString::from_str(Self::RECORD_TYPE),
+                id,
+            )
+        };
+        self.write_to(&record);
+        record
+    }
+}
+
+/// Check that the record has the given record type.
+pub fn check_record_type(record: &CKRecord, expected: &'static str) -> Result<(), FieldError> {
+    // SAFETY: Getting the record type is safe.
+    let actual = unsafe { record.recordType() }.to_string();
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(FieldError::WrongRecordType { expected, actual })
+    }
+}
+
+/// Declare a Rust struct that can be converted to and from a [`CKRecord`]
+/// of the given record type.
+///
+/// Each field of the struct maps to the record field of the same name, and
+/// must implement [`Field`]. Fields that may be missing from the record
+/// should be `Option`s.
+///
+/// To update an existing record, for example one fetched from the
+/// database, use [`Record::write_to`] instead of creating a new record, so
+/// that the server can detect conflicting changes.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_cloud_kit::{record, CKRecord, Record};
+///
+/// record! {
+///     /// A note synced with iCloud.
+///     #[derive(Clone, Debug, PartialEq)]
+///     pub struct Note("Note") {
+///         pub title: String,
+///         pub body: Option<String>,
+///         pub pinned: bool,
+///     }
+/// }
+///
+/// # fn example(fetched: &CKRecord) -> Result<(), objc2_cloud_kit::FieldError> {
+/// let mut note = Note::from_record(fetched)?;
+/// note.pinned = true;
+/// note.write_to(fetched);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! record {
+    (
+        $(#[$m:meta])*
+        $v:vis struct $name:ident($record_type:literal) {
+            $(
+                $(#[$field_m:meta])*
+                $field_v:vis $field:ident: $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$m])*
+        $v struct $name {
+            $(
+                $(#[$field_m])*
+                $field_v $field: $ty,
+            )*
+        }
+
+        impl $crate::Record for $name {
+            const RECORD_TYPE: &'static str = $record_type;
+
+            fn from_record(
+                record: &$crate::CKRecord,
+            ) -> ::core::result::Result<Self, $crate::FieldError> {
+                $crate::__macro_helpers::check_record_type(record, $record_type)?;
+                ::core::result::Result::Ok(Self {
+                    $($field: record.get::<$ty>(stringify!($field))?,)*
+                })
+            }
+
+            fn write_to(&self, record: &$crate::CKRecord) {
+                $(record.set::<$ty>(stringify!($field), &self.$field);)*
+            }
+        }
+    };
+}
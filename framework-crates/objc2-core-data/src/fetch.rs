@@ -0,0 +1,277 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+use objc2::Message;
+use objc2_foundation::{NSArray, NSError, NSPredicate, NSSortDescriptor, NSString};
+
+use crate::*;
+
+/// An error from executing a [`FetchRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FetchError {
+    /// The context failed to execute the request.
+    Fetch(Id<NSError>),
+    /// A fetched object did not match the Rust type.
+    Attribute(AttributeError),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch(error) => write!(f, "failed fetching objects: {error}"),
+            Self::Attribute(error) => write!(f, "fetched object did not match: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Fetch(error) => Some(&**error),
+            Self::Attribute(error) => Some(error),
+        }
+    }
+}
+
+impl From<AttributeError> for FetchError {
+    fn from(error: AttributeError) -> Self {
+        Self::Attribute(error)
+    }
+}
+
+/// A builder for fetching instances of a [`ManagedObject`] type.
+///
+/// Filters are combined with `AND`, and sort descriptors are applied in the
+/// order they were added.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_core_data::{managed_object, FetchRequest, NSManagedObjectContext};
+///
+/// managed_object! {
+///     pub struct Person("Person") {
+///         name: String => set_name,
+///         age: i64 => set_age,
+///     }
+/// }
+///
+/// # fn example(context: &NSManagedObjectContext) -> Result<(), Box<dyn std::error::Error>> {
+/// let request = FetchRequest::new().filter_eq("name", &"Ferris".to_string());
+/// // SAFETY: The format is valid, and has one specifier per argument.
+/// let request = unsafe { request.filter("age >= %@", &[&18i64]) };
+/// let adults: Vec<Person> = request.sort("age", false).limit(10).execute(context)?;
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "NSFetchRequest")]
+pub struct FetchRequest<T> {
+    format: String,
+    arguments: Vec<Id<AnyObject>>,
+    sort: Vec<Id<NSSortDescriptor>>,
+    limit: usize,
+    offset: usize,
+    p: PhantomData<fn() -> T>,
+}
+
+impl<T: ManagedObject> FetchRequest<T> {
+    /// A request for every instance of the entity.
+    pub fn new() -> Self {
+        Self {
+            format: String::new(),
+            arguments: Vec::new(),
+            sort: Vec::new(),
+            limit: 0,
+            offset: 0,
+            p: PhantomData,
+        }
+    }
+
+    fn push_filter(&mut self, format: &str) {
+        if self.format.is_empty() {
+            self.format.push('(');
+        } else {
+            self.format.push_str(" AND (");
+        }
+        self.format.push_str(format);
+        self.format.push(')');
+    }
+
+    /// Only fetch objects matching the predicate format string.
+    ///
+    /// Each `%@` in the format is replaced by the next argument. Use `%K` for
+    /// key paths given as [`String`] arguments.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if an argument is `None`; use `== nil` in the format instead.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The format must be a valid predicate format string, with exactly one
+    /// format specifier per argument.
+    ///
+    /// The predicate is parsed in [`to_request`](Self::to_request) (and
+    /// therefore in [`execute`](Self::execute)), and `NSPredicate` raises an
+    /// Objective-C exception there if the format is invalid, which is
+    /// undefined behaviour to unwind into Rust.
+    #[doc(alias = "predicateWithFormat:argumentArray:")]
+    pub unsafe fn filter(mut self, format: &str, arguments: &[&dyn PredicateArgument]) -> Self {
+        self.push_filter(format);
+        self.arguments
+            .extend(arguments.iter().map(|argument| argument.__to_argument()));
+        self
+    }
+
+    /// Only fetch objects whose attribute is equal to the value.
+    ///
+    /// The attribute is type checked against the model when the objects
+    /// are fetched.
+    pub fn filter_eq<A: Attribute>(mut self, key: &str, value: &A) -> Self {
+        self.arguments
+            .push(Id::into_super(Id::into_super(NSString::from_str(key))));
+        match value.to_object() {
+            Some(object) => {
+                self.push_filter("%K == %@");
+                self.arguments.push(object);
+            }
+            None => self.push_filter("%K == nil"),
+        }
+        self
+    }
+
+    /// Sort the fetched objects by the key, after any previously added sort
+    /// keys.
+    #[doc(alias = "sortDescriptorWithKey:ascending:")]
+    pub fn sort(mut self, key: &str, ascending: bool) -> Self {
+        // SAFETY: Creating a sort descriptor is safe; the key is only used
+        // when the request is executed.
+        let descriptor = unsafe {
+            NSSortDescriptor::sortDescriptorWithKey_ascending(
+                Some(&NSString::from_str(key)),
+                ascending,
+            )
+        };
+        self.sort.push(descriptor);
+        self
+    }
+
+    /// Fetch at most this many objects.
+    #[doc(alias = "setFetchLimit:")]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Skip this many objects at the start of the results.
+    #[doc(alias = "setFetchOffset:")]
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Create the underlying fetch request.
+    pub fn to_request(&self) -> Id<NSFetchRequest> {
+        // SAFETY: The format strings are either our own, or were checked by
+        // the caller of `filter`, so creating the predicate won't throw.
+        //
+        // The entity name, predicate and sort descriptors are only validated
+        // against the model when the request is executed.
+        unsafe {
+            let request =
+                NSFetchRequest::fetchRequestWithEntityName(&NSString::from_str(T::ENTITY_NAME));
+            if !self.format.is_empty() {
+                let arguments = NSArray::from_id_slice(&self.arguments);
+                let predicate = NSPredicate::predicateWithFormat_argumentArray(
+                    &NSString::from_str(&self.format),
+                    Some(&arguments),
+                );
+                request.setPredicate(Some(&predicate));
+            }
+            if !self.sort.is_empty() {
+                request.setSortDescriptors(Some(&NSArray::from_id_slice(&self.sort)));
+            }
+            request.setFetchLimit(self.limit);
+            request.setFetchOffset(self.offset);
+            request
+        }
+    }
+
+    /// Fetch the matching objects from the context.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context fails to execute the request, or if
+    /// the entity does not match `T`.
+    #[doc(alias = "executeFetchRequest:error:")]
+    pub fn execute(&self, context: &NSManagedObjectContext) -> Result<Vec<T>, FetchError> {
+        let request = self.to_request();
+        // SAFETY: The request fetches managed objects, since its result type
+        // is not changed from the default.
+        let results =
+            unsafe { context.executeFetchRequest_error(&request) }.map_err(FetchError::Fetch)?;
+        // SAFETY: The results are managed objects, see above.
+        let results: Id<NSArray<NSManagedObject>> = unsafe { Id::cast(results) };
+        results
+            .iter()
+            .map(|object| T::from_object(object.retain()).map_err(FetchError::from))
+            .collect()
+    }
+}
+
+impl<T: ManagedObject> Default for FetchRequest<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for FetchRequest<T> {
+    fn clone(&self) -> Self {
+        Self {
+            format: self.format.clone(),
+            arguments: self.arguments.clone(),
+            sort: self.sort.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            p: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for FetchRequest<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FetchRequest")
+            .field("format", &self.format)
+            .field("arguments", &self.arguments)
+            .field("sort", &self.sort)
+            .field("limit", &self.limit)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// A value that can be substituted into the format string of
+/// [`FetchRequest::filter`].
+///
+/// This is implemented for every [`Attribute`].
+pub trait PredicateArgument {
+    #[doc(hidden)]
+    fn __to_argument(&self) -> Id<AnyObject>;
+}
+
+impl<A: Attribute> PredicateArgument for A {
+    fn __to_argument(&self) -> Id<AnyObject> {
+        self.to_object()
+            .expect("predicate arguments must not be nil; use `== nil` instead")
+    }
+}
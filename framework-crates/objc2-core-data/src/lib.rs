@@ -15,6 +15,62 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSAttributeDescription",
+    feature = "NSEntityDescription",
+    feature = "NSFetchRequest",
+    feature = "NSManagedObject",
+    feature = "NSManagedObjectContext",
+    feature = "NSPropertyDescription"
+))]
+mod fetch;
 mod generated;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSAttributeDescription",
+    feature = "NSEntityDescription",
+    feature = "NSFetchRequest",
+    feature = "NSManagedObject",
+    feature = "NSManagedObjectContext",
+    feature = "NSPropertyDescription"
+))]
+mod managed_object;
+
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSAttributeDescription",
+    feature = "NSEntityDescription",
+    feature = "NSFetchRequest",
+    feature = "NSManagedObject",
+    feature = "NSManagedObjectContext",
+    feature = "NSPropertyDescription"
+))]
+pub use self::fetch::{FetchError, FetchRequest, PredicateArgument};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSAttributeDescription",
+    feature = "NSEntityDescription",
+    feature = "NSFetchRequest",
+    feature = "NSManagedObject",
+    feature = "NSManagedObjectContext",
+    feature = "NSPropertyDescription"
+))]
+pub use self::managed_object::{Attribute, AttributeError, ManagedObject};
+
+// Used by `managed_object!`.
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSAttributeDescription",
+    feature = "NSEntityDescription",
+    feature = "NSFetchRequest",
+    feature = "NSManagedObject",
+    feature = "NSManagedObjectContext",
+    feature = "NSPropertyDescription"
+))]
+#[doc(hidden)]
+pub mod __macro_helpers {
+    pub use objc2::rc::Id;
+}
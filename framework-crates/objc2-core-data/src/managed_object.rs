@@ -0,0 +1,420 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol};
+use objc2::ClassType;
+use objc2_foundation::{NSData, NSNumber, NSString};
+
+use crate::*;
+
+/// A Rust type that can be stored in an attribute of a managed object.
+///
+/// This is implemented for the Rust equivalents of the scalar, string and
+/// binary attribute types, and for [`Option`] of those, which maps `nil` to
+/// `None`.
+pub trait Attribute: Sized {
+    /// The attribute types in the managed object model that this type can
+    /// be stored in.
+    const TYPES: &'static [NSAttributeType];
+
+    /// Convert the value to the object stored with key-value coding.
+    fn to_object(&self) -> Option<Id<AnyObject>>;
+
+    /// Convert an object retrieved with key-value coding to the value.
+    ///
+    /// Returns `None` if the object is `nil` or of an unexpected class.
+    fn from_object(object: Option<&AnyObject>) -> Option<Self>;
+}
+
+/// Cast the object to the given class, if it is an instance of it.
+fn downcast<T: ClassType>(object: Option<&AnyObject>) -> Option<&T> {
+    let object: *const AnyObject = object?;
+    // SAFETY: Attribute values retrieved with key-value coding are
+    // `NSObject`s.
+    let checked = unsafe { &*object.cast::<NSObject>() };
+    if checked.is_kind_of::<T>() {
+        // SAFETY: Just checked that the object is an instance of `T`.
+        Some(unsafe { &*object.cast::<T>() })
+    } else {
+        None
+    }
+}
+
+macro_rules! number_attribute {
+    ($($t:ty => $new:ident, $as:ident, [$($ty:ident),*];)*) => {$(
+        impl Attribute for $t {
+            const TYPES: &'static [NSAttributeType] = &[$(NSAttributeType::$ty),*];
+
+            fn to_object(&self) -> Option<Id<AnyObject>> {
+                Some(Id::into_super(Id::into_super(Id::into_super(NSNumber::$new(*self)))))
+            }
+
+            fn from_object(object: Option<&AnyObject>) -> Option<Self> {
+                downcast::<NSNumber>(object).map(|number| number.$as())
+            }
+        }
+    )*};
+}
+
+number_attribute! {
+    i16 => new_i16, as_i16, [NSInteger16AttributeType];
+    i32 => new_i32, as_i32, [NSInteger32AttributeType, NSInteger16AttributeType];
+    i64 => new_i64, as_i64, [
+        NSInteger64AttributeType,
+        NSInteger32AttributeType,
+        NSInteger16AttributeType
+    ];
+    f32 => new_f32, as_f32, [NSFloatAttributeType];
+    f64 => new_f64, as_f64, [NSDoubleAttributeType, NSFloatAttributeType];
+    bool => new_bool, as_bool, [NSBooleanAttributeType];
+}
+
+impl Attribute for String {
+    const TYPES: &'static [NSAttributeType] = &[NSAttributeType::NSStringAttributeType];
+
+    fn to_object(&self) -> Option<Id<AnyObject>> {
+        Some(Id::into_super(Id::into_super(NSString::from_str(self))))
+    }
+
+    fn from_object(object: Option<&AnyObject>) -> Option<Self> {
+        downcast::<NSString>(object).map(|string| string.to_string())
+    }
+}
+
+impl Attribute for Vec<u8> {
+    const TYPES: &'static [NSAttributeType] = &[NSAttributeType::NSBinaryDataAttributeType];
+
+    fn to_object(&self) -> Option<Id<AnyObject>> {
+        Some(Id::into_super(Id::into_super(NSData::with_bytes(self))))
+    }
+
+    fn from_object(object: Option<&AnyObject>) -> Option<Self> {
+        downcast::<NSData>(object).map(|data| data.bytes().to_vec())
+    }
+}
+
+impl<T: Attribute> Attribute for Option<T> {
+    const TYPES: &'static [NSAttributeType] = T::TYPES;
+
+    fn to_object(&self) -> Option<Id<AnyObject>> {
+        self.as_ref().and_then(T::to_object)
+    }
+
+    fn from_object(object: Option<&AnyObject>) -> Option<Self> {
+        match object {
+            None => Some(None),
+            Some(object) => T::from_object(Some(object)).map(Some),
+        }
+    }
+}
+
+/// An error from accessing an attribute of a managed object.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AttributeError {
+    /// The object is not an instance of the expected entity.
+    WrongEntity {
+        /// The name of the expected entity.
+        expected: &'static str,
+        /// The name of the object's entity.
+        actual: Option<String>,
+    },
+    /// The entity has no attribute with the given name.
+    Missing {
+        /// The name of the attribute.
+        key: String,
+    },
+    /// The attribute has a type that the Rust type cannot be stored in.
+    TypeMismatch {
+        /// The name of the attribute.
+        key: String,
+        /// The type of the attribute in the managed object model.
+        actual: NSAttributeType,
+    },
+    /// The attribute is `nil`, but the Rust type is not an `Option`.
+    Nil {
+        /// The name of the attribute.
+        key: String,
+    },
+    /// The value of the attribute is of an unexpected class.
+    InvalidValue {
+        /// The name of the attribute.
+        key: String,
+    },
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongEntity { expected, actual } => write!(
+                f,
+                "expected an instance of entity {expected:?}, found {actual:?}"
+            ),
+            Self::Missing { key } => write!(f, "the entity has no attribute {key:?}"),
+            Self::TypeMismatch { key, actual } => write!(
+                f,
+                "attribute {key:?} has type {actual:?}, which does not match the Rust type"
+            ),
+            Self::Nil { key } => write!(f, "attribute {key:?} is nil"),
+            Self::InvalidValue { key } => {
+                write!(f, "attribute {key:?} has a value of an unexpected class")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AttributeError {}
+
+impl NSManagedObject {
+    /// Check that the object's entity has an attribute with the given name,
+    /// of a type that `T` can be stored in.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AttributeError::Missing`] or
+    /// [`AttributeError::TypeMismatch`] if not.
+    #[doc(alias = "attributesByName")]
+    pub fn check_attribute<T: Attribute>(&self, key: &str) -> Result<(), AttributeError> {
+        // SAFETY: Getting the entity and its attributes is safe.
+        let attributes = unsafe { self.entity().attributesByName() };
+        let attribute = match attributes.get(&NSString::from_str(key)) {
+            Some(attribute) => attribute,
+            None => {
+                return Err(AttributeError::Missing { key: key.into() });
+            }
+        };
+        // SAFETY: Getting the attribute type is safe.
+        let actual = unsafe { attribute.attributeType() };
+        if T::TYPES.contains(&actual) {
+            Ok(())
+        } else {
+            Err(AttributeError::TypeMismatch {
+                key: key.into(),
+                actual,
+            })
+        }
+    }
+
+    /// Get the value of an attribute, with type checking against the
+    /// managed object model.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity has no such attribute, if the
+    /// attribute's type does not match `T`, or if the value is `nil` and `T`
+    /// is not an `Option`.
+    #[doc(alias = "valueForKey:")]
+    pub fn get<T: Attribute>(&self, key: &str) -> Result<T, AttributeError> {
+        self.check_attribute::<T>(key)?;
+        // SAFETY: The key is an attribute of the entity.
+        let object = unsafe { self.valueForKey(&NSString::from_str(key)) };
+        match T::from_object(object.as_deref()) {
+            Some(value) => Ok(value),
+            None if object.is_none() => Err(AttributeError::Nil { key: key.into() }),
+            None => Err(AttributeError::InvalidValue { key: key.into() }),
+        }
+    }
+
+    /// Set the value of an attribute, with type checking against the
+    /// managed object model.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity has no such attribute, or if the
+    /// attribute's type does not match `T`. The object is not modified in
+    /// that case.
+    #[doc(alias = "setValue:forKey:")]
+    pub fn set<T: Attribute>(&self, key: &str, value: &T) -> Result<(), AttributeError> {
+        self.check_attribute::<T>(key)?;
+        let object = value.to_object();
+        // SAFETY: The key is an attribute of the entity, and the value has
+        // the class that the attribute type expects.
+        unsafe { self.setValue_forKey(object.as_deref(), &NSString::from_str(key)) };
+        Ok(())
+    }
+}
+
+/// A Rust type that wraps instances of an entity in a managed object
+/// model.
+///
+/// This is usually implemented with the [`managed_object!`] macro.
+///
+/// [`managed_object!`]: crate::managed_object
+pub trait ManagedObject: Sized {
+    /// The name of the entity in the managed object model.
+    const ENTITY_NAME: &'static str;
+
+    /// The names of the attributes that the type accesses, along with the
+    /// types they can be stored in.
+    const ATTRIBUTES: &'static [(&'static str, &'static [NSAttributeType])];
+
+    /// Wrap an object, without checking its entity.
+    #[doc(hidden)]
+    fn __from_object_unchecked(object: Id<NSManagedObject>) -> Self;
+
+    /// The underlying managed object.
+    fn as_object(&self) -> &NSManagedObject;
+
+    /// Wrap a managed object, after checking that it is an instance of
+    /// [`ENTITY_NAME`](Self::ENTITY_NAME) with the expected attributes.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity or any of its attributes do not match.
+    fn from_object(object: Id<NSManagedObject>) -> Result<Self, AttributeError> {
+        // SAFETY: Getting the entity and its attributes is safe.
+        let (name, attributes) = unsafe {
+            let entity = object.entity();
+            (entity.name(), entity.attributesByName())
+        };
+        let name = name.map(|name| name.to_string());
+        if name.as_deref() != Some(Self::ENTITY_NAME) {
+            return Err(AttributeError::WrongEntity {
+                expected: Self::ENTITY_NAME,
+                actual: name,
+            });
+        }
+        for (key, types) in Self::ATTRIBUTES {
+            let attribute = match attributes.get(&NSString::from_str(key)) {
+                Some(attribute) => attribute,
+                None => return Err(AttributeError::Missing { key: (*key).into() }),
+            };
+            // SAFETY: Getting the attribute type is safe.
+            let actual = unsafe { attribute.attributeType() };
+            if !types.contains(&actual) {
+                return Err(AttributeError::TypeMismatch {
+                    key: (*key).into(),
+                    actual,
+                });
+            }
+        }
+        Ok(Self::__from_object_unchecked(object))
+    }
+
+    /// Insert a new instance of the entity into the context.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity in the context's model does not match
+    /// the type. The inserted object is deleted again in that case.
+    #[doc(alias = "insertNewObjectForEntityForName:inManagedObjectContext:")]
+    fn insert(context: &NSManagedObjectContext) -> Result<Self, AttributeError> {
+        // SAFETY: Inserting an object is safe; Core Data raises an
+        // exception if the entity does not exist.
+        let object = unsafe {
+            NSEntityDescription::insertNewObjectForEntityForName_inManagedObjectContext(
+                &NSString::from_str(Self::ENTITY_NAME),
+                context,
+            )
+        };
+        Self::from_object(object.clone()).map_err(|err| {
+            // SAFETY: The object was just inserted into the context.
+            unsafe { context.deleteObject(&object) };
+            err
+        })
+    }
+}
+
+/// Declare a Rust type that wraps instances of an entity in a managed
+/// object model, with typed accessors for its attributes.
+///
+/// The type implements [`ManagedObject`], whose
+/// [`from_object`](ManagedObject::from_object) checks the attribute types
+/// against the model once, so that mismatches are reported up front instead
+/// of when an attribute is first accessed.
+///
+/// Each attribute is given as `name: Type => setter`, where `Type`
+/// implements [`Attribute`]. The getters return [`Result`], since values
+/// that are not optional in Rust may still be `nil` until set.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_core_data::{managed_object, ManagedObject, NSManagedObjectContext};
+///
+/// managed_object! {
+///     /// A person in the address book.
+///     pub struct Person("Person") {
+///         name: String => set_name,
+///         age: i64 => set_age,
+///         nickname: Option<String> => set_nickname,
+///     }
+/// }
+///
+/// # fn example(context: &NSManagedObjectContext) -> Result<(), Box<dyn std::error::Error>> {
+/// let person = Person::insert(context)?;
+/// person.set_name(&"Ferris".to_string())?;
+/// person.set_age(&7)?;
+/// assert_eq!(person.name()?, "Ferris");
+/// # Ok(())
+/// # }
+/// ```
+// For auto_doc_cfg
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSAttributeDescription",
+    feature = "NSEntityDescription",
+    feature = "NSFetchRequest",
+    feature = "NSManagedObject",
+    feature = "NSManagedObjectContext",
+    feature = "NSPropertyDescription"
+))]
+#[macro_export]
+macro_rules! managed_object {
+    (
+        $(#[$m:meta])*
+        $v:vis struct $name:ident($entity:literal) {
+            $(
+                $(#[$field_m:meta])*
+                $field:ident: $ty:ty => $setter:ident
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$m])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        $v struct $name($crate::__macro_helpers::Id<$crate::NSManagedObject>);
+
+        impl $crate::ManagedObject for $name {
+            const ENTITY_NAME: &'static str = $entity;
+
+            const ATTRIBUTES: &'static [(&'static str, &'static [$crate::NSAttributeType])] = &[
+                $((stringify!($field), <$ty as $crate::Attribute>::TYPES),)*
+            ];
+
+            fn __from_object_unchecked(
+                object: $crate::__macro_helpers::Id<$crate::NSManagedObject>,
+            ) -> Self {
+                Self(object)
+            }
+
+            fn as_object(&self) -> &$crate::NSManagedObject {
+                &self.0
+            }
+        }
+
+        impl $name {
+            $(
+                $(#[$field_m])*
+                #[allow(dead_code)]
+                $v fn $field(&self) -> ::core::result::Result<$ty, $crate::AttributeError> {
+                    self.0.get::<$ty>(stringify!($field))
+                }
+
+                #[doc = concat!("Set the value of [`", stringify!($field), "`](Self::", stringify!($field), ").")]
+                #[allow(dead_code)]
+                $v fn $setter(&self, value: &$ty) -> ::core::result::Result<(), $crate::AttributeError> {
+                    self.0.set::<$ty>(stringify!($field), value)
+                }
+            )*
+        }
+    };
+}
@@ -16,5 +16,24 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(all(
+    feature = "std",
+    feature = "futures-core",
+    feature = "CLError",
+    feature = "CLLocation",
+    feature = "CLLocationManager",
+    feature = "CLLocationManagerDelegate"
+))]
+mod manager;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "std",
+    feature = "futures-core",
+    feature = "CLError",
+    feature = "CLLocation",
+    feature = "CLLocationManager",
+    feature = "CLLocationManagerDelegate"
+))]
+pub use self::manager::{Location, LocationManager, LocationUpdates};
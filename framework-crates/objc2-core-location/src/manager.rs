@@ -0,0 +1,297 @@
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use std::time::SystemTime;
+
+use futures_core::Stream;
+use objc2::rc::{Allocated, Id};
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{declare_class, msg_send, msg_send_id, mutability, ClassType, DeclaredClass};
+use objc2_foundation::{
+    event_channel, BufferPolicy, EventSender, EventStream, MainThreadMarker, NSArray, NSError,
+};
+
+use crate::*;
+
+/// A location reported by a [`LocationManager`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    /// The latitude in degrees, positive north of the equator.
+    pub latitude: f64,
+    /// The longitude in degrees, positive east of the prime meridian.
+    pub longitude: f64,
+    /// The radius of uncertainty of the coordinate in meters, or negative if
+    /// the coordinate is invalid.
+    pub horizontal_accuracy: f64,
+    /// The altitude above mean sea level in meters.
+    pub altitude: f64,
+    /// The uncertainty of the altitude in meters, or negative if the
+    /// altitude is invalid.
+    pub vertical_accuracy: f64,
+    /// The speed in meters per second, or negative if invalid.
+    pub speed: f64,
+    /// The direction of travel in degrees relative to due north, or
+    /// negative if invalid.
+    pub course: f64,
+    /// The time at which the location was determined.
+    pub timestamp: SystemTime,
+}
+
+impl Location {
+    /// Convert the location into a plain Rust struct.
+    pub fn from_location(location: &CLLocation) -> Self {
+        // SAFETY: The getters are safe to call.
+        unsafe {
+            let coordinate = location.coordinate();
+            let since_epoch = location.timestamp().timeIntervalSince1970();
+            let timestamp = if since_epoch >= 0.0 {
+                SystemTime::UNIX_EPOCH + Duration::from_secs_f64(since_epoch)
+            } else {
+                SystemTime::UNIX_EPOCH - Duration::from_secs_f64(-since_epoch)
+            };
+            Self {
+                latitude: coordinate.latitude,
+                longitude: coordinate.longitude,
+                horizontal_accuracy: location.horizontalAccuracy(),
+                altitude: location.altitude(),
+                vertical_accuracy: location.verticalAccuracy(),
+                speed: location.speed(),
+                course: location.course(),
+                timestamp,
+            }
+        }
+    }
+}
+
+struct Ivars {
+    sender: RefCell<Option<EventSender<Location>>>,
+    authorization_wakers: RefCell<Vec<Waker>>,
+}
+
+declare_class!(
+    struct Delegate;
+
+    unsafe impl ClassType for Delegate {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustCLLocationManagerDelegate";
+    }
+
+    impl DeclaredClass for Delegate {
+        type Ivars = Ivars;
+    }
+
+    unsafe impl NSObjectProtocol for Delegate {}
+
+    unsafe impl CLLocationManagerDelegate for Delegate {
+        #[method(locationManager:didUpdateLocations:)]
+        fn did_update_locations(&self, _manager: &CLLocationManager, locations: &NSArray<CLLocation>) {
+            if let Some(sender) = &*self.ivars().sender.borrow() {
+                for location in locations.iter() {
+                    sender.send(Location::from_location(location));
+                }
+            }
+        }
+
+        #[method(locationManager:didFailWithError:)]
+        fn did_fail_with_error(&self, _manager: &CLLocationManager, error: &NSError) {
+            // Temporary failures (such as `kCLErrorLocationUnknown`) are
+            // ignored, since the manager keeps trying to get a location. If
+            // access is denied, the stream is ended instead.
+            if error.code() == CLError::kCLErrorDenied.0 {
+                drop(self.ivars().sender.take());
+            }
+        }
+
+        #[method(locationManagerDidChangeAuthorization:)]
+        fn did_change_authorization(&self, _manager: &CLLocationManager) {
+            self.wake_authorization();
+        }
+
+        // Called instead of the above before macOS 11 and iOS 14.
+        #[method(locationManager:didChangeAuthorizationStatus:)]
+        fn did_change_authorization_status(
+            &self,
+            _manager: &CLLocationManager,
+            _status: CLAuthorizationStatus,
+        ) {
+            self.wake_authorization();
+        }
+    }
+);
+
+impl Delegate {
+    fn new(mtm: MainThreadMarker) -> Id<Self> {
+        let this: Allocated<Self> = mtm.alloc::<Self>().set_ivars(Ivars {
+            sender: RefCell::new(None),
+            authorization_wakers: RefCell::new(Vec::new()),
+        });
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        unsafe { msg_send_id![super(this), init] }
+    }
+
+    fn wake_authorization(&self) {
+        let wakers = self.ivars().authorization_wakers.take();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// A location manager with a delegate implemented in Rust.
+///
+/// This wraps [`CLLocationManager`], and delivers location updates and
+/// authorization changes asynchronously. Delegate methods are called on the
+/// main thread, so the manager must be created there.
+///
+/// The underlying manager can be configured (for example with
+/// `setDesiredAccuracy:` and `setDistanceFilter:`) through
+/// [`manager`](LocationManager::manager).
+pub struct LocationManager {
+    manager: Id<CLLocationManager>,
+    delegate: Id<Delegate>,
+}
+
+impl LocationManager {
+    /// Create a new location manager.
+    pub fn new(mtm: MainThreadMarker) -> Self {
+        let delegate = Delegate::new(mtm);
+        // SAFETY: Creating a location manager is safe.
+        let manager = unsafe { CLLocationManager::new() };
+        // SAFETY: The delegate implements the protocol, and is kept alive
+        // for as long as the manager, since the manager only holds a weak
+        // reference to it.
+        unsafe { manager.setDelegate(Some(ProtocolObject::from_ref(&*delegate))) };
+        Self { manager, delegate }
+    }
+
+    /// The underlying location manager.
+    pub fn manager(&self) -> &CLLocationManager {
+        &self.manager
+    }
+
+    /// The app's current authorization to use location services.
+    ///
+    /// This requires macOS 11, iOS 14 or later.
+    #[doc(alias = "authorizationStatus")]
+    pub fn authorization_status(&self) -> CLAuthorizationStatus {
+        // SAFETY: The instance method is available since macOS 11 and
+        // iOS 14, and is safe to call.
+        unsafe { msg_send![&self.manager, authorizationStatus] }
+    }
+
+    /// Request permission to use location services while the app is in use,
+    /// and wait for the user's decision.
+    ///
+    /// Resolves immediately with the current status if the user has already
+    /// decided.
+    #[doc(alias = "requestWhenInUseAuthorization")]
+    pub fn request_when_in_use_authorization(
+        &self,
+    ) -> impl Future<Output = CLAuthorizationStatus> + '_ {
+        // SAFETY: Requesting authorization is safe.
+        unsafe { self.manager.requestWhenInUseAuthorization() };
+        AuthorizationChange(self)
+    }
+
+    /// Request permission to use location services at any time, and wait for
+    /// the user's decision.
+    ///
+    /// Resolves immediately with the current status if the user has already
+    /// decided.
+    #[doc(alias = "requestAlwaysAuthorization")]
+    pub fn request_always_authorization(&self) -> impl Future<Output = CLAuthorizationStatus> + '_ {
+        // SAFETY: Requesting authorization is safe.
+        unsafe { self.manager.requestAlwaysAuthorization() };
+        AuthorizationChange(self)
+    }
+
+    /// Start updating the location, and receive the updates as an
+    /// asynchronous [`Stream`].
+    ///
+    /// Locations are buffered according to the given policy until the
+    /// stream is polled; use [`BufferPolicy::DropOldest(1)`] to only receive
+    /// the latest location. Updates are stopped when the stream is dropped.
+    ///
+    /// The stream borrows the manager mutably, so only one stream of updates
+    /// can be active at a time. The stream ends if access to location
+    /// services is denied.
+    ///
+    /// [`BufferPolicy::DropOldest(1)`]: BufferPolicy::DropOldest
+    #[doc(alias = "startUpdatingLocation")]
+    pub fn updates(&mut self, policy: BufferPolicy) -> LocationUpdates<'_> {
+        let (sender, stream) = event_channel(policy);
+        *self.delegate.ivars().sender.borrow_mut() = Some(sender);
+        // SAFETY: Starting updates is safe.
+        unsafe { self.manager.startUpdatingLocation() };
+        LocationUpdates {
+            manager: self,
+            stream,
+        }
+    }
+}
+
+impl fmt::Debug for LocationManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocationManager")
+            .field("manager", &self.manager)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Resolves once the user has decided on the authorization status.
+struct AuthorizationChange<'a>(&'a LocationManager);
+
+impl Future for AuthorizationChange<'_> {
+    type Output = CLAuthorizationStatus;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<CLAuthorizationStatus> {
+        let status = self.0.authorization_status();
+        if status == CLAuthorizationStatus::kCLAuthorizationStatusNotDetermined {
+            let mut wakers = self.0.delegate.ivars().authorization_wakers.borrow_mut();
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        } else {
+            Poll::Ready(status)
+        }
+    }
+}
+
+/// A stream of location updates.
+///
+/// Created with [`LocationManager::updates`].
+#[must_use = "streams do nothing unless polled"]
+pub struct LocationUpdates<'a> {
+    manager: &'a LocationManager,
+    stream: EventStream<Location>,
+}
+
+impl Stream for LocationUpdates<'_> {
+    type Item = Location;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Location>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+impl Drop for LocationUpdates<'_> {
+    fn drop(&mut self) {
+        // SAFETY: Stopping updates is safe.
+        unsafe { self.manager.manager.stopUpdatingLocation() };
+        drop(self.manager.delegate.ivars().sender.take());
+    }
+}
+
+impl fmt::Debug for LocationUpdates<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocationUpdates")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
@@ -363,6 +363,20 @@ impl<T: Message> NSArray<T> {
     {
         IterRetained(super::iter::IterRetained::new(self))
     }
+
+    /// Iterate over a retained snapshot of the array's elements.
+    ///
+    /// Unlike [`iter`][Self::iter], this allows the array to be mutated while
+    /// iterating (e.g. from Objective-C code), since the elements are copied
+    /// out of the array before iteration starts.
+    #[cfg(feature = "NSRange")]
+    #[doc(alias = "getObjects:range:")]
+    pub fn iter_copied(&self) -> alloc::vec::IntoIter<Id<T>>
+    where
+        T: IsIdCloneable,
+    {
+        self.to_vec_retained().into_iter()
+    }
 }
 
 #[cfg(feature = "NSEnumerator")]
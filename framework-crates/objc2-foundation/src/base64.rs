@@ -0,0 +1,291 @@
+//! Streaming Base64 encoding and decoding with Foundation.
+//!
+//! [`NSData`]'s Base64 methods work on the whole data at once, which means
+//! that both the input and the output must be kept in memory. The
+//! [`Encoder`] and [`Decoder`] here instead process the data in fixed-size
+//! chunks, while producing the same output as Foundation does for the
+//! whole data.
+//!
+//!
+//! # Example
+//!
+//! Encode a file to Base64 without reading it into memory.
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use std::io;
+//!
+//! use objc2_foundation::base64::Encoder;
+//! use objc2_foundation::NSDataBase64EncodingOptions;
+//!
+//! let mut input = File::open("attachment.pdf")?;
+//! let output = File::create("attachment.pdf.b64")?;
+//! let mut encoder = Encoder::new(output, NSDataBase64EncodingOptions::empty());
+//! io::copy(&mut input, &mut encoder)?;
+//! encoder.finish()?;
+//! # Ok::<(), io::Error>(())
+//! ```
+use alloc::vec::Vec;
+use std::io;
+
+use objc2::ClassType;
+
+use crate::Foundation::{NSData, NSDataBase64DecodingOptions, NSDataBase64EncodingOptions};
+
+/// The number of input bytes encoded at a time.
+///
+/// This is a multiple of 3, so that no padding is needed inside the
+/// output, and of 48 and 57, the number of bytes per line with the
+/// 64 and 76 character line length options.
+const ENCODE_CHUNK: usize = 3 * 16 * 19 * 64;
+
+/// The number of input bytes read at a time when decoding.
+const DECODE_CHUNK: usize = 4 * 16 * 1024;
+
+/// Encodes the bytes written to it as Base64, and writes the result to the
+/// inner writer.
+///
+/// The last bytes can only be encoded once all input is known, so
+/// [`finish`](Self::finish) must be called after writing all the data.
+#[doc(alias = "base64EncodedDataWithOptions:")]
+#[derive(Debug)]
+pub struct Encoder<W: io::Write> {
+    inner: W,
+    options: NSDataBase64EncodingOptions,
+    pending: Vec<u8>,
+    /// Encoded output that has not yet been written to the inner writer.
+    output: Vec<u8>,
+    wrote_line: bool,
+}
+
+impl<W: io::Write> Encoder<W> {
+    /// Create an encoder that writes to the given writer.
+    ///
+    /// The options control line breaks in the output, like with
+    /// `-[NSData base64EncodedDataWithOptions:]`.
+    pub fn new(inner: W, options: NSDataBase64EncodingOptions) -> Self {
+        Self {
+            inner,
+            options,
+            pending: Vec::with_capacity(ENCODE_CHUNK),
+            output: Vec::new(),
+            wrote_line: false,
+        }
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    fn line_ending(&self) -> Option<&'static [u8]> {
+        let line_length = NSDataBase64EncodingOptions::NSDataBase64Encoding64CharacterLineLength
+            | NSDataBase64EncodingOptions::NSDataBase64Encoding76CharacterLineLength;
+        if (self.options & line_length).is_empty() {
+            return None;
+        }
+        let cr = self
+            .options
+            .contains(NSDataBase64EncodingOptions::NSDataBase64EncodingEndLineWithCarriageReturn);
+        let lf = self
+            .options
+            .contains(NSDataBase64EncodingOptions::NSDataBase64EncodingEndLineWithLineFeed);
+        Some(match (cr, lf) {
+            (true, false) => b"\r",
+            (false, true) => b"\n",
+            _ => b"\r\n",
+        })
+    }
+
+    /// Move the first `len` pending bytes to the output buffer.
+    fn encode(&mut self, len: usize) {
+        if len == 0 {
+            return;
+        }
+        // Foundation does not end the output with a line ending, so add
+        // one between the chunks instead.
+        if self.wrote_line {
+            if let Some(line_ending) = self.line_ending() {
+                self.output.extend_from_slice(line_ending);
+            }
+        }
+        let chunk = NSData::with_bytes(&self.pending[..len]);
+        // SAFETY: The options are valid.
+        let encoded = unsafe { chunk.base64EncodedDataWithOptions(self.options) };
+        self.output.extend_from_slice(encoded.bytes());
+        self.pending.drain(..len);
+        self.wrote_line = true;
+    }
+
+    /// Write the output buffer to the inner writer.
+    ///
+    /// On errors, the part that was not written is kept, so that it can be
+    /// retried later.
+    fn write_output(&mut self) -> io::Result<()> {
+        while !self.output.is_empty() {
+            match self.inner.write(&self.output) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the encoded data",
+                    ))
+                }
+                Ok(n) => {
+                    self.output.drain(..n);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode the remaining bytes, adding padding if necessary, and return
+    /// the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_output()?;
+        self.encode(self.pending.len());
+        self.write_output()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: io::Write> io::Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Only accept more input once the previous chunk has been written,
+        // so that an error here does not lose any bytes.
+        self.write_output()?;
+        let len = usize::min(buf.len(), ENCODE_CHUNK - self.pending.len());
+        self.pending.extend_from_slice(&buf[..len]);
+        if self.pending.len() == ENCODE_CHUNK {
+            self.encode(ENCODE_CHUNK);
+            // The bytes have been accepted, so an error writing them out is
+            // instead returned by the next call, which retries the write.
+            let _ = self.write_output();
+        }
+        Ok(len)
+    }
+
+    /// Writes the encoded chunks to the inner writer, and flushes it.
+    ///
+    /// Up to one chunk of input may still be buffered afterwards, since
+    /// encoding it before all input is known could add padding in the
+    /// middle of the output.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_output()?;
+        self.inner.flush()
+    }
+}
+
+/// Decodes Base64 read from the inner reader.
+///
+/// Line breaks and other whitespace in the input are always skipped, so
+/// that the output of [`Encoder`] with any options can be decoded.
+#[doc(alias = "initWithBase64EncodedData:options:")]
+#[derive(Debug)]
+pub struct Decoder<R: io::Read> {
+    inner: R,
+    options: NSDataBase64DecodingOptions,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: io::Read> Decoder<R> {
+    /// Create a decoder that reads from the given reader.
+    ///
+    /// With `NSDataBase64DecodingIgnoreUnknownCharacters`, characters
+    /// outside the Base64 alphabet are skipped instead of causing an error.
+    pub fn new(inner: R, options: NSDataBase64DecodingOptions) -> Self {
+        Self {
+            inner,
+            options,
+            input: Vec::new(),
+            output: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consume the decoder, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read and decode the next chunk into the output buffer.
+    fn fill(&mut self) -> io::Result<()> {
+        let ignore_unknown = self
+            .options
+            .contains(NSDataBase64DecodingOptions::NSDataBase64DecodingIgnoreUnknownCharacters);
+        let mut buf = [0; 4096];
+        while !self.eof && self.input.len() < DECODE_CHUNK {
+            let n = match self.inner.read(&mut buf) {
+                Ok(0) => {
+                    self.eof = true;
+                    break;
+                }
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            for &byte in &buf[..n] {
+                match byte {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'+' | b'/' | b'=' => {
+                        self.input.push(byte);
+                    }
+                    b' ' | b'\t' | b'\r' | b'\n' => {}
+                    _ if ignore_unknown => {}
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "invalid character in Base64 input",
+                        ))
+                    }
+                }
+            }
+        }
+
+        // Decode whole groups of four characters, and the rest at the end.
+        let len = if self.eof {
+            self.input.len()
+        } else {
+            self.input.len() - self.input.len() % 4
+        };
+        self.output.clear();
+        self.pos = 0;
+        if len == 0 {
+            return Ok(());
+        }
+        let chunk = NSData::with_bytes(&self.input[..len]);
+        // SAFETY: The options are valid.
+        let decoded = unsafe {
+            NSData::initWithBase64EncodedData_options(NSData::alloc(), &chunk, self.options)
+        }
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid Base64 input"))?;
+        self.output.extend_from_slice(decoded.bytes());
+        self.input.drain(..len);
+        Ok(())
+    }
+}
+
+impl<R: io::Read> io::Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.output.len() {
+            if self.eof && self.input.is_empty() {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let len = usize::min(buf.len(), self.output.len() - self.pos);
+        buf[..len].copy_from_slice(&self.output[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
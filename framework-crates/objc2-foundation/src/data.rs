@@ -1,7 +1,10 @@
+//! Utilities for the `NSData` and `NSMutableData` classes.
 #[cfg(feature = "block2")]
 use alloc::vec::Vec;
 use core::ffi::c_void;
 use core::fmt;
+#[cfg(all(feature = "NSURL", feature = "NSError"))]
+use core::ops::Deref;
 use core::ops::Index;
 use core::ops::IndexMut;
 #[cfg(feature = "NSRange")]
@@ -9,13 +12,15 @@ use core::ops::Range;
 use core::panic::{RefUnwindSafe, UnwindSafe};
 use core::ptr::NonNull;
 use core::slice::{self, SliceIndex};
+#[cfg(feature = "std")]
+use std::io;
 
 use objc2::rc::Id;
 #[cfg(feature = "block2")]
 use objc2::rc::IdFromIterator;
 use objc2::{extern_methods, ClassType};
 
-use crate::Foundation::{NSData, NSMutableData};
+use crate::Foundation::{NSCopying, NSData, NSMutableData};
 
 // SAFETY: `NSData` is immutable and `NSMutableData` can only be mutated from
 // `&mut` methods.
@@ -98,6 +103,264 @@ impl NSData {
     }
 }
 
+#[cfg(all(feature = "NSURL", feature = "NSError"))]
+impl NSData {
+    /// Read the contents of a file, memory-mapping it if possible.
+    ///
+    /// This uses `dataWithContentsOfURL:options:error:`, with
+    /// `NSDataReadingMappedIfSafe` added to the given options. Foundation
+    /// then maps the file into memory instead of reading it, unless it
+    /// determines that this is not safe for the file (e.g. because it is on
+    /// a network volume), so large files can be read without copying them.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the returned data
+    /// is alive, since the changes may then be visible through the
+    /// (supposedly immutable) slice, or accessing the slice may crash the
+    /// process.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_foundation::{NSData, NSDataReadingOptions, NSString, NSURL};
+    ///
+    /// let url = unsafe { NSURL::fileURLWithPath(&NSString::from_str("/path/to/file")) };
+    /// let data = unsafe { NSData::map_file(&url, NSDataReadingOptions::empty()) }.unwrap();
+    /// let bytes: &[u8] = data.as_slice();
+    /// ```
+    #[doc(alias = "dataWithContentsOfURL:options:error:")]
+    #[doc(alias = "NSDataReadingMappedIfSafe")]
+    pub unsafe fn map_file(
+        url: &crate::Foundation::NSURL,
+        options: crate::Foundation::NSDataReadingOptions,
+    ) -> Result<MappedData, Id<crate::Foundation::NSError>> {
+        let options = options | crate::Foundation::NSDataReadingOptions::NSDataReadingMappedIfSafe;
+        // SAFETY: The URL is valid, and the options are valid options. The
+        // caller upholds that the file is not modified while mapped.
+        let data = unsafe { Self::dataWithContentsOfURL_options_error(url, options) }?;
+        Ok(MappedData { data })
+    }
+}
+
+/// The contents of a file, that may be memory-mapped.
+///
+/// This is returned from [`NSData::map_file`], and keeps the underlying
+/// `NSData` (and thereby the mapping) alive for as long as the bytes are
+/// borrowed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg(all(feature = "NSURL", feature = "NSError"))]
+pub struct MappedData {
+    data: Id<NSData>,
+}
+
+#[cfg(all(feature = "NSURL", feature = "NSError"))]
+impl MappedData {
+    /// The contents of the file.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        self.data.bytes()
+    }
+
+    /// Get a reference to the underlying data object.
+    #[inline]
+    pub fn as_data(&self) -> &NSData {
+        &self.data
+    }
+
+    /// Convert into the underlying data object.
+    ///
+    /// The mapping stays alive as long as the data object does.
+    #[inline]
+    pub fn into_data(self) -> Id<NSData> {
+        self.data
+    }
+}
+
+#[cfg(all(feature = "NSURL", feature = "NSError"))]
+impl Deref for MappedData {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+#[cfg(all(feature = "NSURL", feature = "NSError"))]
+impl AsRef<[u8]> for MappedData {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+#[cfg(all(feature = "block2", feature = "NSRange"))]
+impl NSData {
+    /// Call the closure with each contiguous region of the data, in order.
+    ///
+    /// Data created by some system APIs (such as from `dispatch_data_t`) is
+    /// not stored contiguously, and [`bytes`](Self::bytes) has to copy it
+    /// into a single buffer first. This instead visits the regions as they
+    /// are stored, which is useful for processing large data incrementally.
+    ///
+    ///
+    /// # Example
+    ///
+    /// Hash the data without flattening it.
+    ///
+    /// ```
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    ///
+    /// use objc2_foundation::NSData;
+    ///
+    /// let data = NSData::with_bytes(b"hello");
+    /// let mut hasher = DefaultHasher::new();
+    /// data.for_each_region(|bytes| hasher.write(bytes));
+    /// let _hash = hasher.finish();
+    /// ```
+    #[doc(alias = "enumerateByteRangesUsingBlock:")]
+    pub fn for_each_region(&self, f: impl FnMut(&[u8])) {
+        let f = core::cell::RefCell::new(f);
+        let block = block2::StackBlock::new(
+            |bytes: NonNull<c_void>,
+             range: crate::Foundation::NSRange,
+             _stop: NonNull<objc2::runtime::Bool>| {
+                // SAFETY: The pointer is valid for reads of the range's
+                // length for the duration of the call, and the data is not
+                // mutated while we hold `&self`.
+                let bytes =
+                    unsafe { slice::from_raw_parts(bytes.as_ptr().cast::<u8>(), range.length) };
+                (f.borrow_mut())(bytes);
+            },
+        );
+        // SAFETY: The block has the correct signature, and is called
+        // synchronously.
+        unsafe { self.enumerateByteRangesUsingBlock(&block) };
+    }
+}
+
+/// An error from compressing or decompressing [`NSData`].
+///
+/// This is returned from [`NSData::compressed`] and related methods.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg(all(feature = "NSError", feature = "NSString"))]
+#[non_exhaustive]
+pub enum CompressionError {
+    /// Compression is not available on this OS version; it requires macOS
+    /// 10.15, iOS 13 or equivalent.
+    Unavailable,
+    /// The data could not be compressed or decompressed, for example
+    /// because it was not compressed with the given algorithm.
+    Failed(Id<crate::Foundation::NSError>),
+}
+
+#[cfg(all(feature = "NSError", feature = "NSString"))]
+impl fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable => write!(f, "data compression is not available"),
+            Self::Failed(error) => write!(f, "data compression failed: {error}"),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "NSError", feature = "NSString"))]
+impl std::error::Error for CompressionError {}
+
+#[cfg(all(feature = "NSError", feature = "NSString"))]
+impl NSData {
+    /// Whether the system supports compressing and decompressing data.
+    ///
+    /// This is the case on macOS 10.15, iOS 13 and equivalent or newer.
+    #[doc(alias = "compressedDataUsingAlgorithm:error:")]
+    pub fn compression_available() -> bool {
+        Self::class().responds_to(objc2::sel!(compressedDataUsingAlgorithm:error:))
+    }
+
+    /// Compress the data with the given algorithm, using the system's
+    /// implementation.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{NSData, NSDataCompressionAlgorithm};
+    ///
+    /// let data = NSData::with_bytes(&[1; 1024]);
+    /// if NSData::compression_available() {
+    ///     let compressed = data.compressed(NSDataCompressionAlgorithm::Zlib).unwrap();
+    ///     assert!(compressed.len() < data.len());
+    ///     let decompressed = compressed.decompressed(NSDataCompressionAlgorithm::Zlib).unwrap();
+    ///     assert_eq!(decompressed.bytes(), data.bytes());
+    /// }
+    /// ```
+    #[doc(alias = "compressedDataUsingAlgorithm:error:")]
+    pub fn compressed(
+        &self,
+        algorithm: crate::Foundation::NSDataCompressionAlgorithm,
+    ) -> Result<Id<NSData>, CompressionError> {
+        if !Self::compression_available() {
+            return Err(CompressionError::Unavailable);
+        }
+        // SAFETY: Just checked that the method is available.
+        unsafe { self.compressedDataUsingAlgorithm_error(algorithm) }
+            .map_err(CompressionError::Failed)
+    }
+
+    /// Decompress data that was compressed with the given algorithm.
+    #[doc(alias = "decompressedDataUsingAlgorithm:error:")]
+    pub fn decompressed(
+        &self,
+        algorithm: crate::Foundation::NSDataCompressionAlgorithm,
+    ) -> Result<Id<NSData>, CompressionError> {
+        if !Self::compression_available() {
+            return Err(CompressionError::Unavailable);
+        }
+        // SAFETY: Just checked that the method is available.
+        unsafe { self.decompressedDataUsingAlgorithm_error(algorithm) }
+            .map_err(CompressionError::Failed)
+    }
+}
+
+#[cfg(all(feature = "NSError", feature = "NSString"))]
+impl NSMutableData {
+    /// Compress the data in place with the given algorithm.
+    ///
+    /// The data is left unchanged if compression fails.
+    #[doc(alias = "compressUsingAlgorithm:error:")]
+    pub fn compress(
+        &mut self,
+        algorithm: crate::Foundation::NSDataCompressionAlgorithm,
+    ) -> Result<(), CompressionError> {
+        if !NSData::compression_available() {
+            return Err(CompressionError::Unavailable);
+        }
+        // SAFETY: Just checked that the method is available, and we hold
+        // `&mut self`, so the bytes are not borrowed.
+        unsafe { self.compressUsingAlgorithm_error(algorithm) }.map_err(CompressionError::Failed)
+    }
+
+    /// Decompress the data in place, assuming it was compressed with the
+    /// given algorithm.
+    ///
+    /// The data is left unchanged if decompression fails.
+    #[doc(alias = "decompressUsingAlgorithm:error:")]
+    pub fn decompress(
+        &mut self,
+        algorithm: crate::Foundation::NSDataCompressionAlgorithm,
+    ) -> Result<(), CompressionError> {
+        if !NSData::compression_available() {
+            return Err(CompressionError::Unavailable);
+        }
+        // SAFETY: Same as above.
+        unsafe { self.decompressUsingAlgorithm_error(algorithm) }.map_err(CompressionError::Failed)
+    }
+}
+
 impl NSMutableData {
     #[doc(alias = "mutableBytes")]
     pub fn bytes_mut(&mut self) -> &mut [u8] {
@@ -245,22 +508,292 @@ impl<'a> Extend<&'a u8> for NSMutableData {
     }
 }
 
-impl std::io::Write for NSMutableData {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+/// Appends to the end of the data.
+///
+/// See [`Cursor`] for writing at a specific position.
+#[cfg(feature = "std")]
+impl io::Write for NSMutableData {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.extend_from_slice(buf);
         Ok(buf.len())
     }
 
-    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
         self.extend_from_slice(buf);
         Ok(())
     }
 
-    fn flush(&mut self) -> std::io::Result<()> {
+    fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
 }
 
+/// A cursor for writing into, and seeking within, an [`NSMutableData`].
+///
+/// The [`io::Write`] implementation on `NSMutableData` itself always appends
+/// to the end of the data. This instead keeps track of a position, such that
+/// it can also implement [`io::Seek`], which some encoders (e.g. for image
+/// formats that write a header after the body) require. Writing past the
+/// end of the data extends it, and seeking past the end and then writing
+/// fills the gap with zeroes, like with `std::io::Cursor<Vec<u8>>`.
+///
+/// The inner value can be either an `Id<NSMutableData>`, or a
+/// `&mut NSMutableData`.
+///
+///
+/// # Example
+///
+/// ```
+/// use std::io::{Seek, SeekFrom, Write};
+///
+/// use objc2_foundation::data::Cursor;
+/// use objc2_foundation::NSMutableData;
+///
+/// let mut cursor = Cursor::new(NSMutableData::with_bytes(b"hello world"));
+/// cursor.seek(SeekFrom::Start(6)).unwrap();
+/// cursor.write_all(b"there").unwrap();
+/// cursor.seek(SeekFrom::End(0)).unwrap();
+/// cursor.write_all(b"!").unwrap();
+///
+/// let data = cursor.into_inner();
+/// assert_eq!(data.bytes(), b"hello there!");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub struct Cursor<T> {
+    inner: T,
+    pos: u64,
+}
+
+#[cfg(feature = "std")]
+impl<T> Cursor<T> {
+    /// Create a new cursor, positioned at the start of the data.
+    pub fn new(inner: T) -> Self {
+        Self { inner, pos: 0 }
+    }
+
+    /// Consume the cursor, returning the underlying data.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Get a reference to the underlying data.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying data.
+    ///
+    /// Changing the data through this does not change the position of the
+    /// cursor.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// The current position of the cursor.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Set the position of the cursor.
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: AsMut<NSMutableData>> io::Write for Cursor<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let pos = usize::try_from(self.pos).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cursor position exceeds maximum possible data length",
+            )
+        })?;
+        let data = self.inner.as_mut();
+
+        if data.len() < pos {
+            // Zero-fills the gap.
+            data.setLength(pos);
+        }
+
+        // Overwrite the existing bytes, and append the rest.
+        let overlap = usize::min(data.len() - pos, buf.len());
+        let (overwrite, append) = buf.split_at(overlap);
+        data.bytes_mut()[pos..pos + overlap].copy_from_slice(overwrite);
+        if !append.is_empty() {
+            data.extend_from_slice(append);
+        }
+
+        self.pos += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: AsRef<NSMutableData>> io::Seek for Cursor<T> {
+    fn seek(&mut self, style: io::SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match style {
+            io::SeekFrom::Start(pos) => {
+                self.pos = pos;
+                return Ok(pos);
+            }
+            io::SeekFrom::End(offset) => (self.inner.as_ref().len() as u64, offset),
+            io::SeekFrom::Current(offset) => (self.pos, offset),
+        };
+        let pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.unsigned_abs())
+        };
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+/// Types that contain contiguous bytes, and can be converted to [`NSData`].
+///
+/// This is implemented for the common byte containers, so that functions
+/// that need an `NSData` can be generic over where the bytes come from,
+/// instead of requiring the conversion at every call-site.
+///
+/// The bytes are copied, except when converting from `NSData` itself (or
+/// [`MappedData`]), in which case the object is just retained. Use
+/// [`NSData::from_vec`] to convert a `Vec<u8>` without copying.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::rc::Id;
+/// use objc2_foundation::{NSData, ToNSData};
+///
+/// fn takes_data(bytes: impl ToNSData) -> usize {
+///     let data: Id<NSData> = bytes.to_ns_data();
+///     data.len()
+/// }
+///
+/// assert_eq!(takes_data(b"abc"), 3);
+/// assert_eq!(takes_data(&b"abc"[..]), 3);
+/// assert_eq!(takes_data(vec![1u8, 2]), 2);
+/// assert_eq!(takes_data(NSData::with_bytes(&[1])), 1);
+/// ```
+pub trait ToNSData {
+    /// Create an [`NSData`] containing the bytes.
+    fn to_ns_data(&self) -> Id<NSData>;
+}
+
+impl ToNSData for [u8] {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        NSData::with_bytes(self)
+    }
+}
+
+impl<const N: usize> ToNSData for [u8; N] {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        NSData::with_bytes(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ToNSData for alloc::vec::Vec<u8> {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        NSData::with_bytes(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ToNSData for alloc::boxed::Box<[u8]> {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        NSData::with_bytes(self)
+    }
+}
+
+impl ToNSData for NSData {
+    /// Retains the data if it is immutable, and copies it otherwise.
+    #[inline]
+    #[doc(alias = "copy")]
+    fn to_ns_data(&self) -> Id<NSData> {
+        // `self` may be an `NSMutableData` that we only have an immutable
+        // reference to, so we can't just retain it; `copy` only retains
+        // immutable instances.
+        self.copy()
+    }
+}
+
+impl ToNSData for NSMutableData {
+    #[inline]
+    #[doc(alias = "dataWithData:")]
+    fn to_ns_data(&self) -> Id<NSData> {
+        NSData::dataWithData(self)
+    }
+}
+
+#[cfg(all(feature = "NSURL", feature = "NSError"))]
+impl ToNSData for MappedData {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        self.data.clone()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ToNSData for bytes::Bytes {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        NSData::with_bytes(self)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ToNSData for bytes::BytesMut {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        NSData::with_bytes(self)
+    }
+}
+
+impl<T: ?Sized + ToNSData> ToNSData for &T {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        (**self).to_ns_data()
+    }
+}
+
+impl<T: ?Sized + ToNSData> ToNSData for &mut T {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        (**self).to_ns_data()
+    }
+}
+
+impl<T: ?Sized + ToNSData> ToNSData for Id<T> {
+    #[inline]
+    fn to_ns_data(&self) -> Id<NSData> {
+        (**self).to_ns_data()
+    }
+}
+
 #[cfg(feature = "block2")]
 impl IdFromIterator<u8> for NSData {
     fn id_from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Id<Self> {
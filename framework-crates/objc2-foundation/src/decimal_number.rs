@@ -0,0 +1,259 @@
+//! Arithmetic on `NSDecimalNumber`.
+//!
+//! The operators and methods here never raise Objective-C exceptions. When
+//! a calculation overflows, underflows or divides by zero, the result is
+//! Foundation's "not a number" value instead, which can be checked with
+//! [`NSDecimalNumber::is_nan`].
+#[cfg(feature = "rust_decimal")]
+use alloc::string::ToString;
+use core::fmt;
+use core::ops::{Add, Div, Mul, Sub};
+
+use objc2::rc::Id;
+use objc2::runtime::ProtocolObject;
+
+use crate::Foundation::{
+    NSDecimalNumber, NSDecimalNumberBehaviors, NSDecimalNumberHandler, NSNumber, NSRoundingMode,
+    NSString,
+};
+
+/// How [`NSDecimalNumber`] calculations round their results.
+///
+/// This wraps an `NSDecimalNumberHandler` that never raises exceptions.
+#[doc(alias = "NSDecimalNumberHandler")]
+#[doc(alias = "NSDecimalNumberBehaviors")]
+#[derive(Clone)]
+pub struct DecimalBehavior {
+    rounding: NSRoundingMode,
+    scale: Option<i16>,
+    handler: Id<NSDecimalNumberHandler>,
+}
+
+impl DecimalBehavior {
+    /// Round with the given mode to `scale` digits after the decimal
+    /// separator.
+    ///
+    /// A negative scale rounds to powers of ten, and `None` keeps as many
+    /// digits as an `NSDecimal` can hold.
+    #[doc(
+        alias = "decimalNumberHandlerWithRoundingMode:scale:raiseOnExactness:raiseOnOverflow:raiseOnUnderflow:raiseOnDivideByZero:"
+    )]
+    pub fn new(rounding: NSRoundingMode, scale: Option<i16>) -> Self {
+        // `NSDecimalNoScale`.
+        let raw_scale = scale.unwrap_or(i16::MAX);
+        // SAFETY: Creating a handler is safe.
+        let handler = unsafe {
+            NSDecimalNumberHandler::decimalNumberHandlerWithRoundingMode_scale_raiseOnExactness_raiseOnOverflow_raiseOnUnderflow_raiseOnDivideByZero(
+                rounding, raw_scale, false, false, false, false,
+            )
+        };
+        Self {
+            rounding,
+            scale,
+            handler,
+        }
+    }
+
+    /// The rounding mode.
+    pub fn rounding(&self) -> NSRoundingMode {
+        self.rounding
+    }
+
+    /// The number of digits after the decimal separator, if limited.
+    pub fn scale(&self) -> Option<i16> {
+        self.scale
+    }
+
+    fn as_protocol(&self) -> &ProtocolObject<dyn NSDecimalNumberBehaviors> {
+        ProtocolObject::from_ref(&*self.handler)
+    }
+}
+
+impl Default for DecimalBehavior {
+    /// Round to the nearest value, keeping as many digits as possible.
+    fn default() -> Self {
+        Self::new(NSRoundingMode::NSRoundPlain, None)
+    }
+}
+
+impl fmt::Debug for DecimalBehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecimalBehavior")
+            .field("rounding", &self.rounding)
+            .field("scale", &self.scale)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NSDecimalNumber {
+    /// Parse a decimal number, such as `"-12.50"` or `"1.5e3"`.
+    ///
+    /// The decimal separator is always `.`, independent of the current
+    /// locale. Returns `None` if the string is not a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::NSDecimalNumber;
+    ///
+    /// let price = NSDecimalNumber::from_str("19.99").unwrap();
+    /// assert_eq!(price.to_string(), "19.99");
+    /// assert!(NSDecimalNumber::from_str("abc").is_none());
+    /// ```
+    #[doc(alias = "decimalNumberWithString:")]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(string: &str) -> Option<Id<Self>> {
+        // SAFETY: Parsing a string is safe.
+        let number = unsafe { Self::decimalNumberWithString(&NSString::from_str(string)) };
+        if number.is_nan() {
+            None
+        } else {
+            Some(number)
+        }
+    }
+
+    /// Create a decimal number with the value
+    /// `mantissa * 10^exponent`, negated if `negative` is set.
+    #[doc(alias = "decimalNumberWithMantissa:exponent:isNegative:")]
+    pub fn from_parts(mantissa: u64, exponent: i16, negative: bool) -> Id<Self> {
+        // SAFETY: Creating a number is safe.
+        unsafe { Self::decimalNumberWithMantissa_exponent_isNegative(mantissa, exponent, negative) }
+    }
+
+    /// Whether this is Foundation's "not a number" value, which results
+    /// from invalid calculations.
+    #[doc(alias = "notANumber")]
+    pub fn is_nan(&self) -> bool {
+        // SAFETY: Getting the shared "not a number" value is safe.
+        let nan = unsafe { Self::notANumber() };
+        *self == *nan
+    }
+
+    /// Add two numbers, rounding the result with the given behavior.
+    #[doc(alias = "decimalNumberByAdding:withBehavior:")]
+    pub fn add_with(&self, other: &Self, behavior: &DecimalBehavior) -> Id<Self> {
+        // SAFETY: The behavior does not raise exceptions.
+        unsafe { self.decimalNumberByAdding_withBehavior(other, Some(behavior.as_protocol())) }
+    }
+
+    /// Subtract `other` from `self`, rounding the result with the given
+    /// behavior.
+    #[doc(alias = "decimalNumberBySubtracting:withBehavior:")]
+    pub fn sub_with(&self, other: &Self, behavior: &DecimalBehavior) -> Id<Self> {
+        // SAFETY: The behavior does not raise exceptions.
+        unsafe { self.decimalNumberBySubtracting_withBehavior(other, Some(behavior.as_protocol())) }
+    }
+
+    /// Multiply two numbers, rounding the result with the given behavior.
+    #[doc(alias = "decimalNumberByMultiplyingBy:withBehavior:")]
+    pub fn mul_with(&self, other: &Self, behavior: &DecimalBehavior) -> Id<Self> {
+        // SAFETY: The behavior does not raise exceptions.
+        unsafe {
+            self.decimalNumberByMultiplyingBy_withBehavior(other, Some(behavior.as_protocol()))
+        }
+    }
+
+    /// Divide `self` by `other`, rounding the result with the given
+    /// behavior.
+    ///
+    /// Dividing by zero results in "not a number".
+    #[doc(alias = "decimalNumberByDividingBy:withBehavior:")]
+    pub fn div_with(&self, other: &Self, behavior: &DecimalBehavior) -> Id<Self> {
+        // SAFETY: The behavior does not raise exceptions.
+        unsafe { self.decimalNumberByDividingBy_withBehavior(other, Some(behavior.as_protocol())) }
+    }
+
+    /// Raise the number to the given power, rounding the result with the
+    /// given behavior.
+    #[doc(alias = "decimalNumberByRaisingToPower:withBehavior:")]
+    pub fn pow_with(&self, power: usize, behavior: &DecimalBehavior) -> Id<Self> {
+        // SAFETY: The behavior does not raise exceptions.
+        unsafe {
+            self.decimalNumberByRaisingToPower_withBehavior(power, Some(behavior.as_protocol()))
+        }
+    }
+
+    /// Round the number with the given behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{DecimalBehavior, NSDecimalNumber, NSRoundingMode};
+    ///
+    /// let cents = DecimalBehavior::new(NSRoundingMode::NSRoundBankers, Some(2));
+    /// let amount = NSDecimalNumber::from_str("2.345").unwrap();
+    /// assert_eq!(amount.round_with(&cents).to_string(), "2.34");
+    /// ```
+    #[doc(alias = "decimalNumberByRoundingAccordingToBehavior:")]
+    pub fn round_with(&self, behavior: &DecimalBehavior) -> Id<Self> {
+        // SAFETY: The behavior does not raise exceptions.
+        unsafe { self.decimalNumberByRoundingAccordingToBehavior(Some(behavior.as_protocol())) }
+    }
+
+    /// Divide `self` by `other`, returning `None` if the result is "not a
+    /// number", such as when dividing by zero.
+    pub fn checked_div(&self, other: &Self) -> Option<Id<Self>> {
+        let result = self.div_with(other, &DecimalBehavior::default());
+        if result.is_nan() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+}
+
+macro_rules! decimal_op {
+    ($($trait:ident, $method:ident => $with:ident;)*) => {$(
+        /// Uses [`DecimalBehavior::default`] for rounding.
+        impl $trait<&NSDecimalNumber> for &NSDecimalNumber {
+            type Output = Id<NSDecimalNumber>;
+
+            #[inline]
+            fn $method(self, other: &NSDecimalNumber) -> Id<NSDecimalNumber> {
+                self.$with(other, &DecimalBehavior::default())
+            }
+        }
+    )*};
+}
+
+decimal_op! {
+    Add, add => add_with;
+    Sub, sub => sub_with;
+    Mul, mul => mul_with;
+    Div, div => div_with;
+}
+
+/// Compares the numeric values, as defined by "compare:".
+#[cfg(feature = "NSObjCRuntime")]
+impl PartialOrd for NSDecimalNumber {
+    #[doc(alias = "compare:")]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        Some(NSNumber::compare(self, other).into())
+    }
+}
+
+impl fmt::Display for NSDecimalNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&NSNumber::stringValue(self), f)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl NSDecimalNumber {
+    /// Convert a [`rust_decimal::Decimal`] to a decimal number, exactly.
+    pub fn from_decimal(decimal: rust_decimal::Decimal) -> Id<Self> {
+        // `Decimal` has at most 28 significant digits, which always fit.
+        Self::from_str(&decimal.to_string()).expect("failed converting Decimal")
+    }
+
+    /// Convert the decimal number to a [`rust_decimal::Decimal`].
+    ///
+    /// Returns `None` if the number is "not a number", or if it cannot be
+    /// represented exactly.
+    pub fn to_decimal(&self) -> Option<rust_decimal::Decimal> {
+        rust_decimal::Decimal::from_str_exact(&self.to_string()).ok()
+    }
+}
@@ -26,6 +26,15 @@ unsafe impl<T: Message> iter::FastEnumerationHelper for NSEnumerator<T> {
 }
 
 /// A consuming iterator over the items of a `NSEnumerator`.
+///
+/// This is created by calling `into_iter` on an `Id<NSEnumerator<T>>`, which
+/// allows using enumerators returned from various APIs directly in iterator
+/// pipelines.
+///
+/// The number of remaining objects is not known up front, so the size hint
+/// is inexact. If an exact count is needed, and retrieving all the objects
+/// at once is cheap (as it is for enumerators over a collection), use
+/// `allObjects` instead.
 #[derive(Debug)]
 pub struct IntoIter<T: Message>(iter::IntoIter<NSEnumerator<T>>);
 
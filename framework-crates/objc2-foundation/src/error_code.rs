@@ -0,0 +1,296 @@
+//! Typed error codes for well-known error domains.
+//!
+//! Error codes in Objective-C are plain integers, whose meaning depend on
+//! the domain of the error. This module contains enums for the codes in
+//! the most common domains, along with the [`ErrorCode`] trait that ties an
+//! enum to its domain, such that you can use [`NSError::code_as`] to match
+//! on specific error conditions.
+use objc2::ffi::NSInteger;
+use objc2::ns_enum;
+
+#[cfg(feature = "NSString")]
+use crate::Foundation::{NSError, NSErrorDomain};
+
+/// A type representing the error codes in a specific error domain.
+///
+/// This is implemented for [`NSCocoaErrorCode`] and [`NSURLErrorCode`], and
+/// you can implement it for your own error code types (for example those
+/// declared with [`objc2::ns_enum!`]) to use them with
+/// [`NSError::code_as`].
+#[cfg(feature = "NSString")]
+pub trait ErrorCode: Sized {
+    /// The error domain that the codes belong to.
+    fn domain() -> &'static NSErrorDomain;
+
+    /// Convert from the raw error code.
+    fn from_code(code: NSInteger) -> Self;
+}
+
+#[cfg(feature = "NSString")]
+impl NSError {
+    /// The error code as a typed enum, if the error is in the domain of
+    /// that enum.
+    ///
+    ///
+    /// # Example
+    ///
+    /// Check whether an error was caused by a missing file.
+    ///
+    /// ```
+    /// use objc2_foundation::{NSCocoaErrorCode, NSCocoaErrorDomain, NSError};
+    ///
+    /// let error = NSError::new(260, unsafe { NSCocoaErrorDomain });
+    /// match error.code_as::<NSCocoaErrorCode>() {
+    ///     Some(NSCocoaErrorCode::FileReadNoSuchFile) => println!("file not found"),
+    ///     Some(code) => println!("other Cocoa error: {code:?}"),
+    ///     None => println!("error in another domain: {error}"),
+    /// }
+    /// ```
+    pub fn code_as<T: ErrorCode>(&self) -> Option<T> {
+        if *self.domain() == *T::domain() {
+            Some(T::from_code(self.code()))
+        } else {
+            None
+        }
+    }
+}
+
+ns_enum!(
+    /// Error codes in the `NSCocoaErrorDomain` error domain.
+    ///
+    /// See [Apple's documentation](https://developer.apple.com/documentation/foundation/1448136-nserror_codes?language=objc).
+    pub enum NSCocoaErrorCode: NSInteger {
+        #[doc(alias = "NSFileNoSuchFileError")]
+        FileNoSuchFile = 4,
+        #[doc(alias = "NSFileLockingError")]
+        FileLocking = 255,
+        #[doc(alias = "NSFileReadUnknownError")]
+        FileReadUnknown = 256,
+        #[doc(alias = "NSFileReadNoPermissionError")]
+        FileReadNoPermission = 257,
+        #[doc(alias = "NSFileReadInvalidFileNameError")]
+        FileReadInvalidFileName = 258,
+        #[doc(alias = "NSFileReadCorruptFileError")]
+        FileReadCorruptFile = 259,
+        #[doc(alias = "NSFileReadNoSuchFileError")]
+        FileReadNoSuchFile = 260,
+        #[doc(alias = "NSFileReadInapplicableStringEncodingError")]
+        FileReadInapplicableStringEncoding = 261,
+        #[doc(alias = "NSFileReadUnsupportedSchemeError")]
+        FileReadUnsupportedScheme = 262,
+        #[doc(alias = "NSFileReadTooLargeError")]
+        FileReadTooLarge = 263,
+        #[doc(alias = "NSFileReadUnknownStringEncodingError")]
+        FileReadUnknownStringEncoding = 264,
+        #[doc(alias = "NSFileWriteUnknownError")]
+        FileWriteUnknown = 512,
+        #[doc(alias = "NSFileWriteNoPermissionError")]
+        FileWriteNoPermission = 513,
+        #[doc(alias = "NSFileWriteInvalidFileNameError")]
+        FileWriteInvalidFileName = 514,
+        #[doc(alias = "NSFileWriteFileExistsError")]
+        FileWriteFileExists = 516,
+        #[doc(alias = "NSFileWriteInapplicableStringEncodingError")]
+        FileWriteInapplicableStringEncoding = 517,
+        #[doc(alias = "NSFileWriteUnsupportedSchemeError")]
+        FileWriteUnsupportedScheme = 518,
+        #[doc(alias = "NSFileWriteOutOfSpaceError")]
+        FileWriteOutOfSpace = 640,
+        #[doc(alias = "NSFileWriteVolumeReadOnlyError")]
+        FileWriteVolumeReadOnly = 642,
+        #[doc(alias = "NSFileManagerUnmountUnknownError")]
+        FileManagerUnmountUnknown = 768,
+        #[doc(alias = "NSFileManagerUnmountBusyError")]
+        FileManagerUnmountBusy = 769,
+        #[doc(alias = "NSKeyValueValidationError")]
+        KeyValueValidation = 1024,
+        #[doc(alias = "NSFormattingError")]
+        Formatting = 2048,
+        #[doc(alias = "NSUserCancelledError")]
+        UserCancelled = 3072,
+        #[doc(alias = "NSFeatureUnsupportedError")]
+        FeatureUnsupported = 3328,
+        #[doc(alias = "NSExecutableNotLoadableError")]
+        ExecutableNotLoadable = 3584,
+        #[doc(alias = "NSExecutableArchitectureMismatchError")]
+        ExecutableArchitectureMismatch = 3585,
+        #[doc(alias = "NSExecutableRuntimeMismatchError")]
+        ExecutableRuntimeMismatch = 3586,
+        #[doc(alias = "NSExecutableLoadError")]
+        ExecutableLoad = 3587,
+        #[doc(alias = "NSExecutableLinkError")]
+        ExecutableLink = 3588,
+        #[doc(alias = "NSPropertyListReadCorruptError")]
+        PropertyListReadCorrupt = 3840,
+        #[doc(alias = "NSPropertyListReadUnknownVersionError")]
+        PropertyListReadUnknownVersion = 3841,
+        #[doc(alias = "NSPropertyListReadStreamError")]
+        PropertyListReadStream = 3842,
+        #[doc(alias = "NSPropertyListWriteStreamError")]
+        PropertyListWriteStream = 3851,
+        #[doc(alias = "NSPropertyListWriteInvalidError")]
+        PropertyListWriteInvalid = 3852,
+        #[doc(alias = "NSXPCConnectionInterrupted")]
+        XPCConnectionInterrupted = 4097,
+        #[doc(alias = "NSXPCConnectionInvalid")]
+        XPCConnectionInvalid = 4099,
+        #[doc(alias = "NSXPCConnectionReplyInvalid")]
+        XPCConnectionReplyInvalid = 4101,
+        #[doc(alias = "NSUbiquitousFileUnavailableError")]
+        UbiquitousFileUnavailable = 4353,
+        #[doc(alias = "NSUbiquitousFileNotUploadedDueToQuotaError")]
+        UbiquitousFileNotUploadedDueToQuota = 4354,
+        #[doc(alias = "NSUbiquitousFileUbiquityServerNotAvailable")]
+        UbiquitousFileUbiquityServerNotAvailable = 4355,
+        #[doc(alias = "NSUserActivityHandoffFailedError")]
+        UserActivityHandoffFailed = 4608,
+        #[doc(alias = "NSUserActivityConnectionUnavailableError")]
+        UserActivityConnectionUnavailable = 4609,
+        #[doc(alias = "NSUserActivityRemoteApplicationTimedOutError")]
+        UserActivityRemoteApplicationTimedOut = 4610,
+        #[doc(alias = "NSUserActivityHandoffUserInfoTooLargeError")]
+        UserActivityHandoffUserInfoTooLarge = 4611,
+        #[doc(alias = "NSCoderReadCorruptError")]
+        CoderReadCorrupt = 4864,
+        #[doc(alias = "NSCoderValueNotFoundError")]
+        CoderValueNotFound = 4865,
+        #[doc(alias = "NSCoderInvalidValueError")]
+        CoderInvalidValue = 4866,
+        /// An error code not known to these bindings.
+        Other(_),
+    }
+);
+
+#[cfg(feature = "NSString")]
+impl ErrorCode for NSCocoaErrorCode {
+    fn domain() -> &'static NSErrorDomain {
+        // SAFETY: The static is immutable, and always initialized.
+        unsafe { crate::NSCocoaErrorDomain }
+    }
+
+    fn from_code(code: NSInteger) -> Self {
+        Self::from_raw(code)
+    }
+}
+
+#[cfg(feature = "NSURLError")]
+ns_enum!(
+    /// Error codes in the `NSURLErrorDomain` error domain.
+    ///
+    /// See [Apple's documentation](https://developer.apple.com/documentation/foundation/1508628-url_loading_system_error_codes?language=objc).
+    pub enum NSURLErrorCode: NSInteger {
+        #[doc(alias = "NSURLErrorUnknown")]
+        Unknown = -1,
+        #[doc(alias = "NSURLErrorCancelled")]
+        Cancelled = -999,
+        #[doc(alias = "NSURLErrorBadURL")]
+        BadURL = -1000,
+        #[doc(alias = "NSURLErrorTimedOut")]
+        TimedOut = -1001,
+        #[doc(alias = "NSURLErrorUnsupportedURL")]
+        UnsupportedURL = -1002,
+        #[doc(alias = "NSURLErrorCannotFindHost")]
+        CannotFindHost = -1003,
+        #[doc(alias = "NSURLErrorCannotConnectToHost")]
+        CannotConnectToHost = -1004,
+        #[doc(alias = "NSURLErrorNetworkConnectionLost")]
+        NetworkConnectionLost = -1005,
+        #[doc(alias = "NSURLErrorDNSLookupFailed")]
+        DNSLookupFailed = -1006,
+        #[doc(alias = "NSURLErrorHTTPTooManyRedirects")]
+        HTTPTooManyRedirects = -1007,
+        #[doc(alias = "NSURLErrorResourceUnavailable")]
+        ResourceUnavailable = -1008,
+        #[doc(alias = "NSURLErrorNotConnectedToInternet")]
+        NotConnectedToInternet = -1009,
+        #[doc(alias = "NSURLErrorRedirectToNonExistentLocation")]
+        RedirectToNonExistentLocation = -1010,
+        #[doc(alias = "NSURLErrorBadServerResponse")]
+        BadServerResponse = -1011,
+        #[doc(alias = "NSURLErrorUserCancelledAuthentication")]
+        UserCancelledAuthentication = -1012,
+        #[doc(alias = "NSURLErrorUserAuthenticationRequired")]
+        UserAuthenticationRequired = -1013,
+        #[doc(alias = "NSURLErrorZeroByteResource")]
+        ZeroByteResource = -1014,
+        #[doc(alias = "NSURLErrorCannotDecodeRawData")]
+        CannotDecodeRawData = -1015,
+        #[doc(alias = "NSURLErrorCannotDecodeContentData")]
+        CannotDecodeContentData = -1016,
+        #[doc(alias = "NSURLErrorCannotParseResponse")]
+        CannotParseResponse = -1017,
+        #[doc(alias = "NSURLErrorInternationalRoamingOff")]
+        InternationalRoamingOff = -1018,
+        #[doc(alias = "NSURLErrorCallIsActive")]
+        CallIsActive = -1019,
+        #[doc(alias = "NSURLErrorDataNotAllowed")]
+        DataNotAllowed = -1020,
+        #[doc(alias = "NSURLErrorRequestBodyStreamExhausted")]
+        RequestBodyStreamExhausted = -1021,
+        #[doc(alias = "NSURLErrorAppTransportSecurityRequiresSecureConnection")]
+        AppTransportSecurityRequiresSecureConnection = -1022,
+        #[doc(alias = "NSURLErrorFileDoesNotExist")]
+        FileDoesNotExist = -1100,
+        #[doc(alias = "NSURLErrorFileIsDirectory")]
+        FileIsDirectory = -1101,
+        #[doc(alias = "NSURLErrorNoPermissionsToReadFile")]
+        NoPermissionsToReadFile = -1102,
+        #[doc(alias = "NSURLErrorDataLengthExceedsMaximum")]
+        DataLengthExceedsMaximum = -1103,
+        #[doc(alias = "NSURLErrorFileOutsideSafeArea")]
+        FileOutsideSafeArea = -1104,
+        #[doc(alias = "NSURLErrorSecureConnectionFailed")]
+        SecureConnectionFailed = -1200,
+        #[doc(alias = "NSURLErrorServerCertificateHasBadDate")]
+        ServerCertificateHasBadDate = -1201,
+        #[doc(alias = "NSURLErrorServerCertificateUntrusted")]
+        ServerCertificateUntrusted = -1202,
+        #[doc(alias = "NSURLErrorServerCertificateHasUnknownRoot")]
+        ServerCertificateHasUnknownRoot = -1203,
+        #[doc(alias = "NSURLErrorServerCertificateNotYetValid")]
+        ServerCertificateNotYetValid = -1204,
+        #[doc(alias = "NSURLErrorClientCertificateRejected")]
+        ClientCertificateRejected = -1205,
+        #[doc(alias = "NSURLErrorClientCertificateRequired")]
+        ClientCertificateRequired = -1206,
+        #[doc(alias = "NSURLErrorCannotLoadFromNetwork")]
+        CannotLoadFromNetwork = -2000,
+        #[doc(alias = "NSURLErrorCannotCreateFile")]
+        CannotCreateFile = -3000,
+        #[doc(alias = "NSURLErrorCannotOpenFile")]
+        CannotOpenFile = -3001,
+        #[doc(alias = "NSURLErrorCannotCloseFile")]
+        CannotCloseFile = -3002,
+        #[doc(alias = "NSURLErrorCannotWriteToFile")]
+        CannotWriteToFile = -3003,
+        #[doc(alias = "NSURLErrorCannotRemoveFile")]
+        CannotRemoveFile = -3004,
+        #[doc(alias = "NSURLErrorCannotMoveFile")]
+        CannotMoveFile = -3005,
+        #[doc(alias = "NSURLErrorDownloadDecodingFailedMidStream")]
+        DownloadDecodingFailedMidStream = -3006,
+        #[doc(alias = "NSURLErrorDownloadDecodingFailedToComplete")]
+        DownloadDecodingFailedToComplete = -3007,
+        #[doc(alias = "NSURLErrorBackgroundSessionRequiresSharedContainer")]
+        BackgroundSessionRequiresSharedContainer = -995,
+        #[doc(alias = "NSURLErrorBackgroundSessionInUseByAnotherProcess")]
+        BackgroundSessionInUseByAnotherProcess = -996,
+        #[doc(alias = "NSURLErrorBackgroundSessionWasDisconnected")]
+        BackgroundSessionWasDisconnected = -997,
+        /// An error code not known to these bindings.
+        Other(_),
+    }
+);
+
+#[cfg(feature = "NSURLError")]
+#[cfg(feature = "NSString")]
+impl ErrorCode for NSURLErrorCode {
+    fn domain() -> &'static NSErrorDomain {
+        // SAFETY: The static is immutable, and always initialized.
+        unsafe { crate::NSURLErrorDomain }
+    }
+
+    fn from_code(code: NSInteger) -> Self {
+        Self::from_raw(code)
+    }
+}
@@ -0,0 +1,516 @@
+//! Notifications and key-value observing as asynchronous streams.
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::Mutex;
+
+use futures_core::Stream;
+
+/// How a stream buffers events that have not yet been consumed.
+///
+/// Notifications and key-value changes are delivered whenever they happen,
+/// regardless of whether the stream is currently being polled, so they have
+/// to be buffered in the meantime.
+///
+/// The default is [`BufferPolicy::Unbounded`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BufferPolicy {
+    /// Buffer every event.
+    ///
+    /// The buffer grows without bound if the stream is not consumed.
+    Unbounded,
+    /// Buffer at most the given number of events, discarding the oldest
+    /// buffered event when full.
+    ///
+    /// `DropOldest(1)` thus only ever yields the latest event. A capacity of
+    /// zero is treated as one.
+    DropOldest(usize),
+    /// Buffer at most the given number of events, discarding new events when
+    /// full.
+    ///
+    /// A capacity of zero is treated as one.
+    DropNewest(usize),
+}
+
+// Deriving requires `#[default]`, which is not available in our MSRV.
+#[allow(clippy::derivable_impls)]
+impl Default for BufferPolicy {
+    fn default() -> Self {
+        Self::Unbounded
+    }
+}
+
+struct Buffer<T> {
+    items: VecDeque<T>,
+    waker: Option<Waker>,
+    policy: BufferPolicy,
+    /// The number of live [`EventSender`]s, if created with
+    /// [`event_channel`].
+    senders: usize,
+    closed: bool,
+}
+
+/// The buffer shared between the observer and the stream.
+pub(crate) struct Shared<T>(Mutex<Buffer<T>>);
+
+impl<T> Shared<T> {
+    pub(crate) fn new(policy: BufferPolicy) -> Arc<Self> {
+        Arc::new(Self(Mutex::new(Buffer {
+            items: VecDeque::new(),
+            waker: None,
+            policy,
+            senders: 0,
+            closed: false,
+        })))
+    }
+
+    /// Buffer the item according to the policy, and wake the stream.
+    pub(crate) fn push(&self, item: T) {
+        let mut buffer = self.0.lock().unwrap();
+        match buffer.policy {
+            BufferPolicy::Unbounded => buffer.items.push_back(item),
+            BufferPolicy::DropOldest(capacity) => {
+                while buffer.items.len() >= capacity.max(1) {
+                    let _ = buffer.items.pop_front();
+                }
+                buffer.items.push_back(item);
+            }
+            BufferPolicy::DropNewest(capacity) => {
+                if buffer.items.len() < capacity.max(1) {
+                    buffer.items.push_back(item);
+                }
+            }
+        }
+        let waker = buffer.waker.take();
+        // Wake without holding the lock.
+        drop(buffer);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut buffer = self.0.lock().unwrap();
+        match buffer.items.pop_front() {
+            Some(item) => Poll::Ready(Some(item)),
+            None if buffer.closed => Poll::Ready(None),
+            None => {
+                buffer.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().items.len()
+    }
+}
+
+/// Create a stream of events, and a sender for sending events to it.
+///
+/// This is useful for implementing streams on top of delegates, observers
+/// and callbacks, where the sender is moved into the callback. Events are
+/// buffered according to the given policy until the stream is polled.
+///
+/// The stream ends once all senders have been dropped and the buffered
+/// events have been consumed.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_foundation::{event_channel, BufferPolicy};
+///
+/// let (sender, stream) = event_channel::<i32>(BufferPolicy::DropOldest(1));
+/// sender.send(1);
+/// sender.send(2);
+/// // Only the latest event is kept.
+/// assert_eq!(stream.buffered(), 1);
+/// ```
+pub fn event_channel<T>(policy: BufferPolicy) -> (EventSender<T>, EventStream<T>) {
+    let shared = Shared::new(policy);
+    shared.0.lock().unwrap().senders = 1;
+    (EventSender(shared.clone()), EventStream(shared))
+}
+
+/// The sending half of an [`EventStream`].
+///
+/// Created with [`event_channel`].
+pub struct EventSender<T>(Arc<Shared<T>>);
+
+impl<T> EventSender<T> {
+    /// Send an event to the stream.
+    ///
+    /// The event is buffered according to the stream's [`BufferPolicy`],
+    /// and the task polling the stream, if any, is woken.
+    pub fn send(&self, event: T) {
+        self.0.push(event);
+    }
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        self.0 .0.lock().unwrap().senders += 1;
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Drop for EventSender<T> {
+    fn drop(&mut self) {
+        let mut buffer = self.0 .0.lock().unwrap();
+        buffer.senders -= 1;
+        if buffer.senders == 0 {
+            buffer.closed = true;
+            let waker = buffer.waker.take();
+            drop(buffer);
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for EventSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSender").finish_non_exhaustive()
+    }
+}
+
+/// A stream of events sent with an [`EventSender`].
+///
+/// Created with [`event_channel`].
+#[must_use = "streams do nothing unless polled"]
+pub struct EventStream<T>(Arc<Shared<T>>);
+
+impl<T> EventStream<T> {
+    /// The number of events that are currently buffered.
+    pub fn buffered(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<T> Stream for EventStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.0.poll_next(cx)
+    }
+}
+
+impl<T> fmt::Debug for EventStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStream")
+            .field("buffered", &self.buffered())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+mod notification {
+    use core::ptr::NonNull;
+
+    use block2::{Block, RcBlock};
+    use objc2::rc::Id;
+    use objc2::runtime::AnyObject;
+    use objc2::ClassType;
+
+    use super::*;
+    use crate::Foundation::{NSNotification, NSNotificationCenter, NSNotificationName};
+
+    /// A stream of notifications posted to a notification center.
+    ///
+    /// Created with [`NSNotificationCenter::notification_stream`]. The
+    /// observer is removed from the notification center when the stream is
+    /// dropped.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct NotificationStream<T> {
+        center: Id<NSNotificationCenter>,
+        token: Id<AnyObject>,
+        shared: Arc<Shared<T>>,
+    }
+
+    impl NSNotificationCenter {
+        /// Observe notifications as an asynchronous [`Stream`].
+        ///
+        /// The `name` and `object` parameters filter the notifications like
+        /// in `addObserverForName:object:queue:usingBlock:`. Each
+        /// notification is converted to an item with `f`, and the items are
+        /// buffered according to the given policy until the stream is
+        /// polled.
+        ///
+        /// Notifications are delivered on whichever thread posts them, so
+        /// both the closure and the items must be safe to send to other
+        /// threads; `NSNotification` itself is not, so extract what you need
+        /// from it in the closure.
+        #[doc(alias = "addObserverForName:object:queue:usingBlock:")]
+        pub fn notification_stream<T, F>(
+            &self,
+            name: Option<&NSNotificationName>,
+            object: Option<&AnyObject>,
+            policy: BufferPolicy,
+            f: F,
+        ) -> NotificationStream<T>
+        where
+            T: Send + 'static,
+            F: Fn(&NSNotification) -> T + Send + Sync + 'static,
+        {
+            // SAFETY: `f` is `Send + Sync`, and the items are `Send`, so the
+            // block may be called on any thread.
+            unsafe { self.notification_stream_unchecked(name, object, policy, f) }
+        }
+
+        /// Like [`notification_stream`], but without requiring the closure
+        /// and items to be thread-safe.
+        ///
+        /// [`notification_stream`]: Self::notification_stream
+        ///
+        ///
+        /// # Safety
+        ///
+        /// The notifications must only be posted on the thread that the
+        /// stream is used on, unless `f` and `T` are thread-safe.
+        pub(crate) unsafe fn notification_stream_unchecked<T, F>(
+            &self,
+            name: Option<&NSNotificationName>,
+            object: Option<&AnyObject>,
+            policy: BufferPolicy,
+            f: F,
+        ) -> NotificationStream<T>
+        where
+            T: 'static,
+            F: Fn(&NSNotification) -> T + 'static,
+        {
+            let shared = Shared::new(policy);
+            let block = RcBlock::new({
+                let shared = shared.clone();
+                move |notification: NonNull<NSNotification>| {
+                    // SAFETY: The notification is valid for the duration of
+                    // the call.
+                    let notification = unsafe { notification.as_ref() };
+                    shared.push(f(notification));
+                }
+            });
+            let block: &Block<dyn Fn(NonNull<NSNotification>) + 'static> = &block;
+            // SAFETY: The block has the correct signature, and the
+            // notification center retains (copies) it. Passing no queue
+            // runs the block synchronously on the posting thread, which is
+            // upheld by the caller to be fine.
+            let token = unsafe {
+                self.addObserverForName_object_queue_usingBlock(name, object, None, block)
+            };
+            NotificationStream {
+                center: self.retain(),
+                // SAFETY: The observer token is an object.
+                token: unsafe { Id::cast(token) },
+                shared,
+            }
+        }
+    }
+
+    impl<T> Stream for NotificationStream<T> {
+        type Item = T;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.shared.poll_next(cx)
+        }
+    }
+
+    impl<T> Drop for NotificationStream<T> {
+        fn drop(&mut self) {
+            // SAFETY: The token was returned by
+            // `addObserverForName:object:queue:usingBlock:` on this center.
+            unsafe { self.center.removeObserver(&self.token) };
+        }
+    }
+
+    impl<T> fmt::Debug for NotificationStream<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NotificationStream")
+                .field("buffered", &self.shared.len())
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSObject",
+    feature = "NSString"
+))]
+mod key_value {
+    use core::ffi::c_void;
+    use core::ptr;
+
+    use objc2::mutability::IsRetainable;
+    use objc2::rc::Id;
+    use objc2::runtime::{AnyObject, NSObject};
+    use objc2::{
+        declare_class, msg_send, msg_send_id, mutability, ClassType, DeclaredClass, Message,
+    };
+
+    use super::*;
+    use crate::Foundation::{
+        NSDictionary, NSKeyValueChangeKey, NSKeyValueObservingOptions, NSString,
+    };
+    use crate::NSCopying;
+
+    /// The change dictionary passed to `observeValueForKeyPath:...`.
+    pub type KeyValueChange = NSDictionary<NSKeyValueChangeKey, AnyObject>;
+
+    declare_class!(
+        struct Observer;
+
+        unsafe impl ClassType for Observer {
+            type Super = NSObject;
+            type Mutability = mutability::InteriorMutable;
+            const NAME: &'static str = "__RustKeyValueStreamObserver";
+        }
+
+        impl DeclaredClass for Observer {
+            type Ivars = Arc<Shared<Id<KeyValueChange>>>;
+        }
+
+        unsafe impl Observer {
+            #[method(observeValueForKeyPath:ofObject:change:context:)]
+            fn observe_value(
+                &self,
+                _key_path: Option<&NSString>,
+                _object: Option<&AnyObject>,
+                change: Option<&KeyValueChange>,
+                _context: *mut c_void,
+            ) {
+                if let Some(change) = change {
+                    let change: *const KeyValueChange = change;
+                    // SAFETY: Change dictionaries are never mutated after
+                    // being delivered, so retaining it is safe. Upheld by
+                    // the caller of `KeyValueStream::new` that this happens
+                    // on the thread that owns the stream.
+                    let change = unsafe { Id::retain(change as *mut KeyValueChange) };
+                    self.ivars().push(change.unwrap());
+                }
+            }
+        }
+    );
+
+    /// A stream of key-value observing changes to a property.
+    ///
+    /// Created with [`KeyValueStream::new`]. The observer is removed when
+    /// the stream is dropped.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct KeyValueStream {
+        object: Id<AnyObject>,
+        key_path: Id<NSString>,
+        observer: Id<Observer>,
+    }
+
+    impl KeyValueStream {
+        /// Observe changes to the property at `key_path` on `object` as an
+        /// asynchronous [`Stream`].
+        ///
+        /// Each item is the change dictionary, whose contents are
+        /// determined by `options` as in
+        /// `addObserver:forKeyPath:options:context:`. Changes are buffered
+        /// according to the given policy until the stream is polled.
+        ///
+        /// The stream keeps the observed object alive, since it must stay
+        /// alive for as long as it is being observed.
+        ///
+        ///
+        /// # Safety
+        ///
+        /// The property at `key_path` must be key-value observing compliant,
+        /// and changes to it must not happen concurrently with dropping the
+        /// stream.
+        ///
+        /// Changes are delivered on the thread that makes them, so they must
+        /// only happen on the thread that the stream was created on.
+        #[doc(alias = "addObserver:forKeyPath:options:context:")]
+        pub unsafe fn new<T: Message + IsRetainable>(
+            object: &T,
+            key_path: &NSString,
+            options: NSKeyValueObservingOptions,
+            policy: BufferPolicy,
+        ) -> Self {
+            let observer = Observer::alloc().set_ivars(Shared::new(policy));
+            // SAFETY: `NSObject`'s `init` method is safe to call.
+            let observer: Id<Observer> = unsafe { msg_send_id![super(observer), init] };
+            // SAFETY: The observer implements
+            // `observeValueForKeyPath:ofObject:change:context:`, and is
+            // removed again when the stream is dropped. Upheld by the caller
+            // that the key path is observable.
+            let _: () = unsafe {
+                msg_send![
+                    object,
+                    addObserver: &*observer,
+                    forKeyPath: key_path,
+                    options: options,
+                    context: ptr::null_mut::<c_void>(),
+                ]
+            };
+            let object: *const T = object;
+            // SAFETY: The object is retainable, and any object can be viewed
+            // as `AnyObject`.
+            let object = unsafe { Id::retain(object.cast::<AnyObject>() as *mut AnyObject) };
+            Self {
+                object: object.unwrap(),
+                key_path: key_path.copy(),
+                observer,
+            }
+        }
+    }
+
+    impl Stream for KeyValueStream {
+        type Item = Id<KeyValueChange>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.observer.ivars().poll_next(cx)
+        }
+    }
+
+    impl Drop for KeyValueStream {
+        fn drop(&mut self) {
+            // SAFETY: The observer was added for this key path and context
+            // in `new`.
+            let _: () = unsafe {
+                msg_send![
+                    &self.object,
+                    removeObserver: &*self.observer,
+                    forKeyPath: &*self.key_path,
+                    context: ptr::null_mut::<c_void>(),
+                ]
+            };
+        }
+    }
+
+    impl fmt::Debug for KeyValueStream {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("KeyValueStream")
+                .field("key_path", &self.key_path)
+                .field("buffered", &self.observer.ivars().len())
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSObject",
+    feature = "NSString"
+))]
+pub use self::key_value::{KeyValueChange, KeyValueStream};
+#[cfg(all(
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+pub use self::notification::NotificationStream;
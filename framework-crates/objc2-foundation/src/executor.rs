@@ -0,0 +1,404 @@
+//! A futures executor driven by the main run loop.
+//!
+//! GUI applications spend most of their time in the main run loop (for
+//! example inside `NSApplication::run`), so instead of running a separate
+//! async runtime on a background thread, futures can be driven directly by
+//! that run loop. Each spawned task is polled from the main thread whenever
+//! it is woken, by scheduling a perform request on the main run loop (see
+//! `-[NSObject performSelectorOnMainThread:withObject:waitUntilDone:]`).
+//!
+//! Since tasks are always polled on the main thread, futures spawned with
+//! [`spawn_local`] need not be [`Send`], and can hold on to main-thread-only
+//! objects. Futures that are `Send` can be spawned from any thread with
+//! [`RunLoopSpawner`].
+//!
+//! Note that tasks only make progress while the main run loop is running.
+//! If the main thread does not otherwise run it, use [`block_on`].
+//!
+//!
+//! # Panics
+//!
+//! Panicking inside a spawned task is not supported, and will abort the
+//! process, since the panic would otherwise have to unwind through the run
+//! loop.
+//!
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use objc2_foundation::executor::{block_on, sleep, spawn_local};
+//! use objc2_foundation::MainThreadMarker;
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//!
+//! spawn_local(mtm, async move {
+//!     sleep(mtm, Duration::from_millis(100)).await;
+//!     println!("woke up after 100ms");
+//! });
+//!
+//! // Run the main run loop until the timer has fired at least once.
+//! block_on(mtm, sleep(mtm, Duration::from_millis(200)));
+//! ```
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::task::Wake;
+use std::thread::{self, Thread};
+
+use block2::{Block, RcBlock};
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{declare_class, msg_send, msg_send_id, mutability, sel, ClassType, DeclaredClass};
+
+use crate::Foundation::{NSDate, NSDefaultRunLoopMode, NSRunLoop, NSTimer};
+use crate::{BoxFuture, MainThreadMarker, Spawner};
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()> + 'static>>;
+
+std::thread_local! {
+    /// The futures of the tasks that are currently pending.
+    ///
+    /// Only ever accessed on the main thread. Keyed by the address of the
+    /// task object, which is kept alive by the entry itself.
+    static TASKS: RefCell<HashMap<*const Task, (Id<Task>, LocalFuture)>> =
+        RefCell::new(HashMap::new());
+}
+
+struct TaskIvars {
+    /// Whether the task is already scheduled to be polled.
+    scheduled: AtomicBool,
+    /// A future spawned from another thread, that has not yet been moved to
+    /// the main thread.
+    incoming: Mutex<Option<BoxFuture>>,
+}
+
+declare_class!(
+    /// The receiver of the perform requests that poll a task.
+    struct Task;
+
+    unsafe impl ClassType for Task {
+        type Super = NSObject;
+        type Mutability = mutability::InteriorMutable;
+        const NAME: &'static str = "__RustRunLoopExecutorTask";
+    }
+
+    impl DeclaredClass for Task {
+        type Ivars = TaskIvars;
+    }
+
+    unsafe impl Task {
+        #[method(pollTask)]
+        fn __poll_task(&self) {
+            // SAFETY: This is only invoked through
+            // `performSelectorOnMainThread:withObject:waitUntilDone:`.
+            let mtm = unsafe { MainThreadMarker::new_unchecked() };
+            self.poll_task(mtm);
+        }
+    }
+);
+
+// SAFETY: The ivars are `Send` and `Sync`, and the future of a task is only
+// ever stored in it while it is `Send`.
+unsafe impl Send for Task {}
+// SAFETY: Same as above.
+unsafe impl Sync for Task {}
+
+impl Task {
+    fn new(incoming: Option<BoxFuture>) -> Id<Self> {
+        let this = Self::alloc().set_ivars(TaskIvars {
+            scheduled: AtomicBool::new(false),
+            incoming: Mutex::new(incoming),
+        });
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        unsafe { msg_send_id![super(this), init] }
+    }
+
+    /// Schedule the task to be polled on the main thread, unless it already
+    /// is.
+    fn schedule(&self) {
+        if !self.ivars().scheduled.swap(true, Ordering::AcqRel) {
+            // SAFETY: The selector is implemented by the class, and takes no
+            // arguments. The receiver is retained until the request has been
+            // performed.
+            let _: () = unsafe {
+                msg_send![
+                    self,
+                    performSelectorOnMainThread: sel!(pollTask),
+                    withObject: ptr::null_mut::<AnyObject>(),
+                    waitUntilDone: false,
+                ]
+            };
+        }
+    }
+
+    fn poll_task(&self, _mtm: MainThreadMarker) {
+        // Reset before polling, so that wakeups that happen while polling
+        // schedule the task again.
+        self.ivars().scheduled.store(false, Ordering::Release);
+
+        let key: *const Self = self;
+        let entry = TASKS.with(|tasks| tasks.borrow_mut().remove(&key));
+        let (this, mut future) = match entry {
+            Some(entry) => entry,
+            None => match self.ivars().incoming.lock().unwrap().take() {
+                Some(future) => (self.retain(), future as LocalFuture),
+                // The task has already completed, or is only used for
+                // waking up the run loop.
+                None => return,
+            },
+        };
+
+        // The future is polled without `TASKS` being borrowed, so that it can
+        // spawn further tasks.
+        let waker = Waker::from(Arc::new(TaskWaker(this.clone())));
+        let mut cx = Context::from_waker(&waker);
+        if future.as_mut().poll(&mut cx).is_pending() {
+            TASKS.with(|tasks| tasks.borrow_mut().insert(key, (this, future)));
+        }
+    }
+}
+
+struct TaskWaker(Id<Task>);
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.schedule();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.schedule();
+    }
+}
+
+/// Spawn a future onto the main run loop.
+///
+/// The future is first polled the next time the main run loop runs, and is
+/// then polled on the main thread each time it is woken, until it completes.
+///
+/// See the [module-level documentation](self) for details.
+pub fn spawn_local<F>(mtm: MainThreadMarker, future: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    let _ = mtm;
+    let task = Task::new(None);
+    let key: *const Task = &*task;
+    let future: LocalFuture = Box::pin(future);
+    TASKS.with(|tasks| tasks.borrow_mut().insert(key, (task.clone(), future)));
+    task.schedule();
+}
+
+/// Run the main run loop until the given future has completed.
+///
+/// The future itself is polled directly on the main thread, while tasks
+/// spawned with [`spawn_local`] or [`RunLoopSpawner`] (and any other run
+/// loop sources) are processed in between.
+///
+/// This may be called recursively, such as from inside a spawned task,
+/// though the outer future cannot make progress until the inner one has
+/// completed.
+pub fn block_on<F: Future>(mtm: MainThreadMarker, future: F) -> F::Output {
+    struct BlockOnWaker {
+        woken: AtomicBool,
+        task: Id<Task>,
+        thread: Thread,
+    }
+
+    impl Wake for BlockOnWaker {
+        fn wake(self: Arc<Self>) {
+            self.wake_by_ref();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.woken.store(true, Ordering::Release);
+            // Schedule a no-op on the main run loop, to make it return from
+            // `runMode:beforeDate:`.
+            self.task.schedule();
+            // And in case the run loop wasn't running, wake up the thread.
+            self.thread.unpark();
+        }
+    }
+
+    let _ = mtm;
+    let mut future = Box::pin(future);
+    let waker = Arc::new(BlockOnWaker {
+        woken: AtomicBool::new(false),
+        task: Task::new(None),
+        thread: thread::current(),
+    });
+    let waker_ref = Waker::from(waker.clone());
+    let mut cx = Context::from_waker(&waker_ref);
+
+    // SAFETY: Getting the main run loop and the distant future is safe.
+    let run_loop = unsafe { NSRunLoop::mainRunLoop() };
+    let date = unsafe { NSDate::distantFuture() };
+
+    loop {
+        waker.woken.store(false, Ordering::Release);
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        while !waker.woken.load(Ordering::Acquire) {
+            // SAFETY: Running the main run loop on the main thread is safe,
+            // and the mode is a valid run loop mode.
+            let ran = unsafe { run_loop.runMode_beforeDate(NSDefaultRunLoopMode, &date) };
+            if !ran {
+                // The run loop returns immediately if it has no sources or
+                // timers, so wait for the waker instead of spinning.
+                thread::park();
+            }
+        }
+    }
+}
+
+/// A [`Spawner`] that runs futures on the main run loop.
+///
+/// This can be used from any thread, and thus requires the futures to be
+/// [`Send`]; use [`spawn_local`] on the main thread to spawn futures that
+/// are not.
+///
+///
+/// # Example
+///
+/// Use the main run loop for spawning all futures.
+///
+/// ```
+/// use objc2_foundation::executor::RunLoopSpawner;
+/// use objc2_foundation::set_global_spawner;
+///
+/// set_global_spawner(RunLoopSpawner).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RunLoopSpawner;
+
+impl Spawner for RunLoopSpawner {
+    fn spawn_boxed(&self, future: BoxFuture) {
+        // The future is moved into the main thread's `TASKS` on the first
+        // poll.
+        Task::new(Some(future)).schedule();
+    }
+}
+
+struct SleepState {
+    fired: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+/// A future that completes after a given duration.
+///
+/// Created with [`sleep`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    duration: Duration,
+    state: Rc<SleepState>,
+    timer: Option<Id<NSTimer>>,
+}
+
+/// Wait for the given duration, using a timer on the main run loop.
+///
+/// The timer is scheduled when the returned future is first polled, and is
+/// invalidated if the future is dropped before it fires.
+///
+/// Like other run loop timers, this may fire later than requested if the
+/// run loop is busy, or is running in a mode other than the default mode.
+#[doc(alias = "scheduledTimerWithTimeInterval:repeats:block:")]
+pub fn sleep(mtm: MainThreadMarker, duration: Duration) -> Sleep {
+    let _ = mtm;
+    Sleep {
+        duration,
+        state: Rc::new(SleepState {
+            fired: Cell::new(false),
+            waker: RefCell::new(None),
+        }),
+        timer: None,
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if this.state.fired.get() {
+            return Poll::Ready(());
+        }
+        *this.state.waker.borrow_mut() = Some(cx.waker().clone());
+
+        if this.timer.is_none() {
+            let state = this.state.clone();
+            let block = RcBlock::new(move |_timer: NonNull<NSTimer>| {
+                state.fired.set(true);
+                if let Some(waker) = state.waker.borrow_mut().take() {
+                    waker.wake();
+                }
+            });
+            let block: &Block<dyn Fn(NonNull<NSTimer>) + 'static> = &block;
+            // SAFETY: The block has the correct signature, and the timer
+            // retains (copies) it. `Sleep` is not `Send`, so this is on the
+            // main thread, and the timer is scheduled on the main run loop.
+            let timer = unsafe {
+                NSTimer::scheduledTimerWithTimeInterval_repeats_block(
+                    this.duration.as_secs_f64(),
+                    false,
+                    block,
+                )
+            };
+            this.timer = Some(timer);
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(timer) = &self.timer {
+            if !self.state.fired.get() {
+                // SAFETY: Invalidating the timer on the thread it was
+                // scheduled on is safe.
+                unsafe { timer.invalidate() };
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep")
+            .field("duration", &self.duration)
+            .field("fired", &self.state.fired.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MainThreadMarker {
+    /// Spawn a future onto the main run loop.
+    ///
+    /// See [`executor::spawn_local`](crate::executor::spawn_local).
+    #[inline]
+    pub fn spawn_local<F>(self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        spawn_local(self, future);
+    }
+
+    /// Run the main run loop until the given future has completed.
+    ///
+    /// See [`executor::block_on`](crate::executor::block_on).
+    #[inline]
+    pub fn block_on<F: Future>(self, future: F) -> F::Output {
+        block_on(self, future)
+    }
+}
@@ -0,0 +1,380 @@
+//! Coordinated file access, and file presenters as streams.
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+use block2::StackBlock;
+use objc2::rc::Id;
+
+use crate::Foundation::{
+    NSError, NSFileCoordinator, NSFileCoordinatorReadingOptions, NSFileCoordinatorWritingOptions,
+    NSURL,
+};
+
+/// Run a coordination method, which calls its accessor synchronously at
+/// most once.
+fn coordinate<R>(
+    f: impl FnOnce(&mut Option<Id<NSError>>, &dyn Fn(&NSURL, Option<&NSURL>)),
+    accessor: impl FnOnce(&NSURL, Option<&NSURL>) -> R,
+) -> Result<R, Id<NSError>> {
+    let accessor = Cell::new(Some(accessor));
+    let result = Cell::new(None);
+    let mut error = None;
+    f(&mut error, &|url, other| {
+        if let Some(accessor) = accessor.take() {
+            result.set(Some(accessor(url, other)));
+        }
+    });
+    match (result.into_inner(), error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => Err(error),
+        (None, None) => unreachable!("coordination failed without an error"),
+    }
+}
+
+impl NSFileCoordinator {
+    /// Read the item at the URL, after other readers and writers that
+    /// require exclusive access are done with it.
+    ///
+    /// The closure receives the URL to read from, which may differ from the
+    /// given URL if the item was moved. The closure is called synchronously
+    /// on the current thread, and other processes are blocked from writing
+    /// to the item until it returns.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if coordination failed, for example if a file
+    /// presenter failed to save its changes. The closure is not called in
+    /// that case.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_foundation::{NSFileCoordinator, NSFileCoordinatorReadingOptions, NSURL};
+    /// use std::path::Path;
+    ///
+    /// let url = NSURL::from_path(Path::new("/tmp/notes.txt"), false).unwrap();
+    /// let coordinator = NSFileCoordinator::new();
+    /// let contents = coordinator
+    ///     .coordinate_read(&url, NSFileCoordinatorReadingOptions::empty(), |url| {
+    ///         std::fs::read_to_string(url.to_file_path().unwrap())
+    ///     })
+    ///     .expect("coordination failed")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[doc(alias = "coordinateReadingItemAtURL:options:error:byAccessor:")]
+    pub fn coordinate_read<R>(
+        &self,
+        url: &NSURL,
+        options: NSFileCoordinatorReadingOptions,
+        reader: impl FnOnce(&NSURL) -> R,
+    ) -> Result<R, Id<NSError>> {
+        coordinate(
+            |error, accessor| {
+                let block = StackBlock::new(|url: NonNull<NSURL>| {
+                    // SAFETY: The URL is valid for the duration of the call.
+                    accessor(unsafe { url.as_ref() }, None)
+                });
+                // SAFETY: The block has the correct signature, and is called
+                // synchronously.
+                unsafe {
+                    self.coordinateReadingItemAtURL_options_error_byAccessor(
+                        url,
+                        options,
+                        Some(error),
+                        &block,
+                    )
+                };
+            },
+            |url, _| reader(url),
+        )
+    }
+
+    /// Write to the item at the URL, after other readers and writers are
+    /// done with it.
+    ///
+    /// The closure receives the URL to write to, which may differ from the
+    /// given URL if the item was moved. The closure is called synchronously
+    /// on the current thread, and other processes are blocked from reading
+    /// or writing the item until it returns.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if coordination failed. The closure is not called in
+    /// that case.
+    #[doc(alias = "coordinateWritingItemAtURL:options:error:byAccessor:")]
+    pub fn coordinate_write<R>(
+        &self,
+        url: &NSURL,
+        options: NSFileCoordinatorWritingOptions,
+        writer: impl FnOnce(&NSURL) -> R,
+    ) -> Result<R, Id<NSError>> {
+        coordinate(
+            |error, accessor| {
+                let block = StackBlock::new(|url: NonNull<NSURL>| {
+                    // SAFETY: The URL is valid for the duration of the call.
+                    accessor(unsafe { url.as_ref() }, None)
+                });
+                // SAFETY: The block has the correct signature, and is called
+                // synchronously.
+                unsafe {
+                    self.coordinateWritingItemAtURL_options_error_byAccessor(
+                        url,
+                        options,
+                        Some(error),
+                        &block,
+                    )
+                };
+            },
+            |url, _| writer(url),
+        )
+    }
+
+    /// Read from one item and write to another in a single coordinated
+    /// operation, for example when copying a file.
+    ///
+    /// The closure receives the URLs to read from and write to, in that
+    /// order.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if coordination failed. The closure is not called in
+    /// that case.
+    #[doc(alias = "coordinateReadingItemAtURL:options:writingItemAtURL:options:error:byAccessor:")]
+    pub fn coordinate_read_write<R>(
+        &self,
+        read_url: &NSURL,
+        read_options: NSFileCoordinatorReadingOptions,
+        write_url: &NSURL,
+        write_options: NSFileCoordinatorWritingOptions,
+        accessor: impl FnOnce(&NSURL, &NSURL) -> R,
+    ) -> Result<R, Id<NSError>> {
+        coordinate(
+            |error, inner| {
+                let block = StackBlock::new(|read: NonNull<NSURL>, write: NonNull<NSURL>| {
+                    // SAFETY: The URLs are valid for the duration of the call.
+                    unsafe { inner(read.as_ref(), Some(write.as_ref())) }
+                });
+                // SAFETY: The block has the correct signature, and is called
+                // synchronously.
+                unsafe {
+                    self.coordinateReadingItemAtURL_options_writingItemAtURL_options_error_byAccessor(
+                        read_url,
+                        read_options,
+                        write_url,
+                        write_options,
+                        Some(error),
+                        &block,
+                    )
+                };
+            },
+            |read, write| accessor(read, write.expect("write URL")),
+        )
+    }
+}
+
+#[cfg(all(
+    feature = "futures-core",
+    feature = "NSFilePresenter",
+    feature = "NSOperation"
+))]
+mod presenter {
+    use core::fmt;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use std::sync::Mutex;
+
+    use block2::Block;
+    use futures_core::Stream;
+    use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+    use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass, Message};
+
+    use super::*;
+    use crate::Foundation::{NSFilePresenter, NSOperationQueue};
+    use crate::{event_channel, BufferPolicy, EventSender, EventStream};
+
+    /// A change to an item observed by a [`FilePresenter`].
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[non_exhaustive]
+    pub enum PresenterEvent {
+        /// The contents or attributes of the item changed.
+        #[doc(alias = "presentedItemDidChange")]
+        Changed,
+        /// The item was moved or renamed to the given URL.
+        ///
+        /// The presenter follows the item, so later events refer to the new
+        /// location.
+        #[doc(alias = "presentedItemDidMoveToURL:")]
+        Moved(Id<NSURL>),
+        /// The item is about to be deleted.
+        #[doc(alias = "accommodatePresentedItemDeletionWithCompletionHandler:")]
+        WillBeDeleted,
+        /// An item inside the presented directory changed, or was added.
+        #[doc(alias = "presentedSubitemDidChangeAtURL:")]
+        SubitemChanged(Id<NSURL>),
+    }
+
+    struct Ivars {
+        url: Mutex<Id<NSURL>>,
+        queue: Id<NSOperationQueue>,
+        sender: EventSender<PresenterEvent>,
+    }
+
+    declare_class!(
+        struct Presenter;
+
+        // SAFETY:
+        // - The superclass NSObject does not have any subclassing
+        //   requirements.
+        // - The presenter is called on its operation queue, which is fine
+        //   since the state is thread-safe.
+        // - `Presenter` does not implement `Drop`.
+        unsafe impl ClassType for Presenter {
+            type Super = NSObject;
+            type Mutability = mutability::InteriorMutable;
+            const NAME: &'static str = "__RustNSFilePresenter";
+        }
+
+        impl DeclaredClass for Presenter {
+            type Ivars = Ivars;
+        }
+
+        unsafe impl NSObjectProtocol for Presenter {}
+
+        unsafe impl NSFilePresenter for Presenter {
+            #[method_id(presentedItemURL)]
+            fn url(&self) -> Option<Id<NSURL>> {
+                Some(self.ivars().url.lock().unwrap().clone())
+            }
+
+            #[method_id(presentedItemOperationQueue)]
+            fn queue(&self) -> Id<NSOperationQueue> {
+                self.ivars().queue.clone()
+            }
+
+            #[method(presentedItemDidChange)]
+            fn did_change(&self) {
+                self.ivars().sender.send(PresenterEvent::Changed);
+            }
+
+            #[method(presentedItemDidMoveToURL:)]
+            fn did_move(&self, url: &NSURL) {
+                *self.ivars().url.lock().unwrap() = url.retain();
+                self.ivars().sender.send(PresenterEvent::Moved(url.retain()));
+            }
+
+            #[method(accommodatePresentedItemDeletionWithCompletionHandler:)]
+            fn will_delete(&self, completion_handler: &Block<dyn Fn(*mut NSError)>) {
+                self.ivars().sender.send(PresenterEvent::WillBeDeleted);
+                completion_handler.call((core::ptr::null_mut(),));
+            }
+
+            #[method(presentedSubitemDidChangeAtURL:)]
+            fn subitem_did_change(&self, url: &NSURL) {
+                self.ivars()
+                    .sender
+                    .send(PresenterEvent::SubitemChanged(url.retain()));
+            }
+        }
+    );
+
+    /// Observe changes made to a file or directory by other processes, as
+    /// an asynchronous [`Stream`] of [`PresenterEvent`]s.
+    ///
+    /// Only changes made through file coordination are observed, such as
+    /// those made by document providers, iCloud Drive and other apps using
+    /// [`NSFileCoordinator`]. To avoid observing its own changes, the app
+    /// should coordinate its access with [`FilePresenter::coordinator`].
+    ///
+    /// The presenter is unregistered when this is dropped.
+    #[doc(alias = "NSFilePresenter")]
+    #[doc(alias = "addFilePresenter:")]
+    #[doc(alias = "removeFilePresenter:")]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct FilePresenter {
+        presenter: Id<Presenter>,
+        stream: EventStream<PresenterEvent>,
+    }
+
+    impl FilePresenter {
+        /// Start observing the item at the URL.
+        pub fn new(url: &NSURL, policy: BufferPolicy) -> Self {
+            let (sender, stream) = event_channel(policy);
+            // SAFETY: Creating an operation queue is safe. The presenter is
+            // called serially, so that events are delivered in order.
+            let queue = unsafe {
+                let queue = NSOperationQueue::new();
+                queue.setMaxConcurrentOperationCount(1);
+                queue
+            };
+            let presenter = Presenter::alloc().set_ivars(Ivars {
+                url: Mutex::new(url.retain()),
+                queue,
+                sender,
+            });
+            // SAFETY: `NSObject`'s `init` method is safe to call.
+            let presenter: Id<Presenter> = unsafe { msg_send_id![super(presenter), init] };
+            // SAFETY: The presenter implements the protocol, and is removed
+            // again when dropped.
+            unsafe { NSFileCoordinator::addFilePresenter(ProtocolObject::from_ref(&*presenter)) };
+            Self { presenter, stream }
+        }
+
+        /// The current URL of the item, which changes if it is moved.
+        pub fn url(&self) -> Id<NSURL> {
+            self.presenter.ivars().url.lock().unwrap().clone()
+        }
+
+        /// A file coordinator that does not notify this presenter of the
+        /// changes made through it.
+        #[doc(alias = "initWithFilePresenter:")]
+        pub fn coordinator(&self) -> Id<NSFileCoordinator> {
+            // SAFETY: The presenter is registered.
+            unsafe {
+                NSFileCoordinator::initWithFilePresenter(
+                    NSFileCoordinator::alloc(),
+                    Some(ProtocolObject::from_ref(&*self.presenter)),
+                )
+            }
+        }
+    }
+
+    impl Stream for FilePresenter {
+        type Item = PresenterEvent;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<PresenterEvent>> {
+            Pin::new(&mut self.stream).poll_next(cx)
+        }
+    }
+
+    impl Drop for FilePresenter {
+        fn drop(&mut self) {
+            // SAFETY: The presenter was added in `new`.
+            unsafe {
+                NSFileCoordinator::removeFilePresenter(ProtocolObject::from_ref(&*self.presenter))
+            };
+        }
+    }
+
+    impl fmt::Debug for FilePresenter {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("FilePresenter")
+                .field("url", &self.url())
+                .field("stream", &self.stream)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "futures-core",
+    feature = "NSFilePresenter",
+    feature = "NSOperation"
+))]
+pub use self::presenter::{FilePresenter, PresenterEvent};
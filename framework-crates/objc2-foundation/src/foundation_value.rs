@@ -0,0 +1,285 @@
+//! Dynamically typed Foundation values.
+//!
+//! Property lists, JSON from `NSJSONSerialization`, `userInfo` dictionaries
+//! and similar all contain heterogeneous object graphs, where the static type
+//! is just `AnyObject`. [`FoundationValue`] provides a single entry point for
+//! converting such graphs to (and from) plain Rust values.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ptr::{self, NonNull};
+
+use objc2::encode::Encoding;
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol};
+use objc2::{msg_send_id, sel, ClassType};
+
+use super::util;
+use crate::Foundation::{NSArray, NSData, NSDate, NSDictionary, NSNull, NSNumber, NSString};
+
+/// A dynamically typed Foundation value.
+///
+/// This is the Rust equivalent of the object graphs produced by property
+/// lists, `NSJSONSerialization` and the like, and can be created from any
+/// object with [`FoundationValue::from_object`], and converted back with
+/// [`FoundationValue::to_object`].
+///
+/// Objects whose class is not one of the well-known Foundation classes below
+/// are stored as-is in [`FoundationValue::Other`].
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_foundation::{FoundationValue, NSArray, NSNumber};
+///
+/// let array = NSArray::from_vec(vec![NSNumber::new_i32(42), NSNumber::new_f64(1.5)]);
+///
+/// let value = FoundationValue::from_object(&array);
+/// assert_eq!(
+///     value,
+///     FoundationValue::Array(vec![
+///         FoundationValue::Integer(42),
+///         FoundationValue::Float(1.5),
+///     ]),
+/// );
+///
+/// let value = FoundationValue::Dictionary(vec![(
+///     FoundationValue::String("key".into()),
+///     FoundationValue::Null,
+/// )]);
+/// let obj = value.to_object();
+/// assert_eq!(FoundationValue::from_object(&obj), value);
+/// ```
+#[derive(Clone, Debug)]
+pub enum FoundationValue {
+    /// An `NSString`.
+    String(String),
+    /// An `NSNumber` created from a boolean.
+    ///
+    /// Note that this is only detected for the `kCFBooleanTrue` and
+    /// `kCFBooleanFalse` singletons (which is what `+[NSNumber
+    /// numberWithBool:]` returns); other numbers created from a `BOOL` or a
+    /// `char` will be converted to [`FoundationValue::Integer`].
+    Bool(bool),
+    /// An `NSNumber` containing an integer.
+    Integer(i64),
+    /// An `NSNumber` containing a floating point value, or an unsigned
+    /// integer that is too large to fit in an [`i64`].
+    Float(f64),
+    /// An `NSData`.
+    Data(Vec<u8>),
+    /// An `NSDate`, as the number of seconds since 1970-01-01 00:00:00 UTC.
+    Date(f64),
+    /// An `NSArray`.
+    Array(Vec<FoundationValue>),
+    /// An `NSDictionary`, as a list of key-value pairs.
+    ///
+    /// The order of the pairs is unspecified, so beware when comparing two
+    /// dictionaries with more than one entry.
+    Dictionary(Vec<(FoundationValue, FoundationValue)>),
+    /// `NSNull`.
+    Null,
+    /// Any other object.
+    ///
+    /// This is typed as [`AnyObject`] instead of `NSObject`, since it may be
+    /// an object with a different root class, such as `NSProxy`.
+    Other(Id<AnyObject>),
+}
+
+/// Cast the object to the given class.
+///
+/// # Safety
+///
+/// The object must be an instance of `T`.
+unsafe fn cast_unchecked<T: ClassType>(obj: &AnyObject) -> &T {
+    let obj: *const AnyObject = obj;
+    // SAFETY: Upheld by the caller.
+    unsafe { &*obj.cast::<T>() }
+}
+
+fn number_to_value(number: &NSNumber) -> FoundationValue {
+    match number.encoding() {
+        Encoding::Char => {
+            if ptr::eq(number, &*NSNumber::new_bool(true)) {
+                FoundationValue::Bool(true)
+            } else if ptr::eq(number, &*NSNumber::new_bool(false)) {
+                FoundationValue::Bool(false)
+            } else {
+                FoundationValue::Integer(number.as_i64())
+            }
+        }
+        Encoding::Float | Encoding::Double => FoundationValue::Float(number.as_f64()),
+        Encoding::ULong | Encoding::ULongLong => {
+            let value = number.as_u64();
+            match i64::try_from(value) {
+                Ok(value) => FoundationValue::Integer(value),
+                Err(_) => FoundationValue::Float(value as f64),
+            }
+        }
+        _ => FoundationValue::Integer(number.as_i64()),
+    }
+}
+
+impl FoundationValue {
+    /// Convert an object to a Rust value, recursively converting the
+    /// elements of arrays and dictionaries.
+    ///
+    /// The object is checked against, in order: `NSString`, `NSNumber`,
+    /// `NSData`, `NSDate`, `NSArray`, `NSDictionary` and `NSNull`. Mutable
+    /// subclasses of these are converted as well, by taking a snapshot of
+    /// their current contents.
+    pub fn from_object(obj: &AnyObject) -> Self {
+        if !obj.class().responds_to(sel!(isKindOfClass:)) {
+            return Self::other(obj);
+        }
+        // SAFETY: We only use `isKindOfClass:` on the object, which it
+        // responds to.
+        let checked = unsafe { &*(obj as *const AnyObject).cast::<NSObject>() };
+        if checked.is_kind_of::<NSString>() {
+            // SAFETY: Just checked that the object is an `NSString`.
+            let string: &NSString = unsafe { cast_unchecked(obj) };
+            Self::String(string.to_string())
+        } else if checked.is_kind_of::<NSNumber>() {
+            // SAFETY: Just checked that the object is an `NSNumber`.
+            number_to_value(unsafe { cast_unchecked(obj) })
+        } else if checked.is_kind_of::<NSData>() {
+            // SAFETY: Just checked that the object is an `NSData`.
+            let data: &NSData = unsafe { cast_unchecked(obj) };
+            Self::Data(data.bytes().to_vec())
+        } else if checked.is_kind_of::<NSDate>() {
+            // SAFETY: Just checked that the object is an `NSDate`.
+            let date: &NSDate = unsafe { cast_unchecked(obj) };
+            // SAFETY: `timeIntervalSince1970` is safe to call on any date.
+            Self::Date(unsafe { date.timeIntervalSince1970() })
+        } else if checked.is_kind_of::<NSArray>() {
+            // SAFETY: Just checked that the object is an `NSArray`, and any
+            // object in it can be viewed as `AnyObject`.
+            let array: &NSArray<AnyObject> = unsafe { cast_unchecked(obj) };
+            Self::Array(array.iter().map(Self::from_object).collect())
+        } else if checked.is_kind_of::<NSDictionary>() {
+            // SAFETY: Same as above.
+            let dictionary: &NSDictionary<AnyObject, AnyObject> = unsafe { cast_unchecked(obj) };
+            let (keys, objects) = dictionary.to_vecs();
+            Self::Dictionary(
+                keys.into_iter()
+                    .zip(objects)
+                    .map(|(key, obj)| (Self::from_object(key), Self::from_object(obj)))
+                    .collect(),
+            )
+        } else if checked.is_kind_of::<NSNull>() {
+            Self::Null
+        } else {
+            Self::other(obj)
+        }
+    }
+
+    fn other(obj: &AnyObject) -> Self {
+        let obj: *const AnyObject = obj;
+        // SAFETY: The pointer came from a reference, and `AnyObject` is
+        // `IsIdCloneable`, so it can always be retained.
+        Self::Other(unsafe { Id::retain(obj as *mut AnyObject).unwrap_unchecked() })
+    }
+
+    /// Convert the value to an object, recursively converting the elements
+    /// of arrays and dictionaries.
+    ///
+    /// The returned objects are immutable.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a dictionary key is [`FoundationValue::Other`] and the
+    /// object does not implement `NSCopying`, as required by `NSDictionary`.
+    pub fn to_object(&self) -> Id<AnyObject> {
+        match self {
+            Self::String(string) => upcast(NSString::from_str(string)),
+            Self::Bool(value) => upcast(NSNumber::new_bool(*value)),
+            Self::Integer(value) => upcast(NSNumber::new_i64(*value)),
+            Self::Float(value) => upcast(NSNumber::new_f64(*value)),
+            Self::Data(bytes) => upcast(NSData::with_bytes(bytes)),
+            // SAFETY: Creating a date from a time interval is always safe.
+            Self::Date(secs) => upcast(unsafe { NSDate::dateWithTimeIntervalSince1970(*secs) }),
+            Self::Array(values) => upcast(NSArray::from_vec(
+                values.iter().map(Self::to_object).collect(),
+            )),
+            Self::Dictionary(pairs) => {
+                let mut keys: Vec<Id<AnyObject>> = Vec::with_capacity(pairs.len());
+                let mut objects: Vec<Id<AnyObject>> = Vec::with_capacity(pairs.len());
+                for (key, obj) in pairs {
+                    let key = key.to_object();
+                    assert!(
+                        key.class().responds_to(sel!(copyWithZone:)),
+                        "dictionary key must conform to NSCopying",
+                    );
+                    keys.push(key);
+                    objects.push(obj.to_object());
+                }
+                let keys: *mut NonNull<AnyObject> = util::id_ptr_cast(keys.as_mut_ptr());
+                let objects: *mut NonNull<AnyObject> = util::id_ptr_cast(objects.as_mut_ptr());
+                let count = pairs.len();
+                // SAFETY: The keys and objects are valid for `count`
+                // elements, and the keys conform to `NSCopying`. The
+                // dictionary retains (or copies) its contents, so the
+                // vectors can be dropped afterwards.
+                unsafe {
+                    msg_send_id![
+                        NSDictionary::<AnyObject, AnyObject>::class(),
+                        dictionaryWithObjects: objects,
+                        forKeys: keys,
+                        count: count,
+                    ]
+                }
+            }
+            Self::Null => NSNull::null_object(),
+            Self::Other(obj) => obj.clone(),
+        }
+    }
+
+    /// Look up a value in a dictionary by a string key.
+    ///
+    /// Returns [`None`] if `self` is not [`FoundationValue::Dictionary`], or
+    /// if the key is not present.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Dictionary(pairs) => pairs.iter().find_map(|(k, v)| match k {
+                Self::String(k) if k == key => Some(v),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn upcast<T: ClassType>(obj: Id<T>) -> Id<AnyObject> {
+    // SAFETY: All objects can be viewed as `AnyObject`.
+    unsafe { Id::cast(obj) }
+}
+
+/// Structural equality.
+///
+/// [`FoundationValue::Other`] is compared by pointer equality, and floating
+/// point values use the usual [`f64`] semantics.
+impl PartialEq for FoundationValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a == b,
+            (Self::Data(a), Self::Data(b)) => a == b,
+            (Self::Date(a), Self::Date(b)) => a == b,
+            (Self::Array(a), Self::Array(b)) => a == b,
+            (Self::Dictionary(a), Self::Dictionary(b)) => a == b,
+            (Self::Null, Self::Null) => true,
+            (Self::Other(a), Self::Other(b)) => ptr::eq::<AnyObject>(&**a, &**b),
+            _ => false,
+        }
+    }
+}
+
+impl From<&AnyObject> for FoundationValue {
+    #[inline]
+    fn from(obj: &AnyObject) -> Self {
+        Self::from_object(obj)
+    }
+}
@@ -0,0 +1,220 @@
+//! Utilities for the `NSIndexSet` and `NSMutableIndexSet` classes.
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+#[cfg(feature = "NSRange")]
+use core::ops::Range;
+
+use objc2::rc::{Id, IdFromIterator};
+
+use crate::Foundation::{NSIndexSet, NSMutableIndexSet};
+
+impl NSIndexSet {
+    /// Creates an [`NSIndexSet`] containing the given indexes.
+    ///
+    /// Duplicate indexes are only stored once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::NSIndexSet;
+    ///
+    /// let set = NSIndexSet::from_indexes(&[5, 1, 3, 1]);
+    /// assert_eq!(set.to_vec(), [1, 3, 5]);
+    /// ```
+    pub fn from_indexes(indexes: &[usize]) -> Id<Self> {
+        Id::into_super(NSMutableIndexSet::from_indexes(indexes))
+    }
+
+    /// Creates an [`NSIndexSet`] containing the indexes in the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::NSIndexSet;
+    ///
+    /// let set = NSIndexSet::from_range(2..5);
+    /// assert_eq!(set.to_vec(), [2, 3, 4]);
+    /// ```
+    #[doc(alias = "indexSetWithIndexesInRange:")]
+    #[cfg(feature = "NSRange")]
+    pub fn from_range(range: Range<usize>) -> Id<Self> {
+        unsafe { Self::indexSetWithIndexesInRange(range.into()) }
+    }
+
+    /// Returns the number of indexes in the set.
+    #[doc(alias = "count")]
+    pub fn len(&self) -> usize {
+        unsafe { self.count() }
+    }
+
+    /// Returns `true` if the set contains no indexes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the set contains the index.
+    #[doc(alias = "containsIndex:")]
+    pub fn contains(&self, index: usize) -> bool {
+        unsafe { self.containsIndex(index) }
+    }
+
+    /// An iterator visiting all indexes in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::NSIndexSet;
+    ///
+    /// let set = NSIndexSet::from_indexes(&[4, 2]);
+    /// let doubled: Vec<usize> = set.iter().map(|index| index * 2).collect();
+    /// assert_eq!(doubled, [4, 8]);
+    /// ```
+    #[doc(alias = "firstIndex")]
+    #[doc(alias = "indexGreaterThanIndex:")]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            set: self,
+            last: None,
+            remaining: self.len(),
+        }
+    }
+
+    /// Returns a [`Vec`] containing the indexes in ascending order.
+    pub fn to_vec(&self) -> Vec<usize> {
+        self.iter().collect()
+    }
+}
+
+impl NSMutableIndexSet {
+    /// Creates an [`NSMutableIndexSet`] containing the given indexes.
+    ///
+    /// See [`NSIndexSet::from_indexes`] for details.
+    pub fn from_indexes(indexes: &[usize]) -> Id<Self> {
+        let mut set = unsafe { Self::new() };
+        set.extend(indexes.iter().copied());
+        set
+    }
+
+    /// Add an index to the set. Returns whether the index was newly
+    /// inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::NSMutableIndexSet;
+    ///
+    /// let mut set = NSMutableIndexSet::from_indexes(&[]);
+    /// assert_eq!(set.insert(3), true);
+    /// assert_eq!(set.insert(3), false);
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    #[doc(alias = "addIndex:")]
+    pub fn insert(&mut self, index: usize) -> bool {
+        let contains_index = self.contains(index);
+        unsafe { self.addIndex(index) };
+        !contains_index
+    }
+
+    /// Add the indexes in the range to the set.
+    #[doc(alias = "addIndexesInRange:")]
+    #[cfg(feature = "NSRange")]
+    pub fn insert_range(&mut self, range: Range<usize>) {
+        unsafe { self.addIndexesInRange(range.into()) };
+    }
+
+    /// Remove an index from the set. Returns whether the index was present
+    /// in the set.
+    #[doc(alias = "removeIndex:")]
+    pub fn remove(&mut self, index: usize) -> bool {
+        let contains_index = self.contains(index);
+        unsafe { self.removeIndex(index) };
+        contains_index
+    }
+}
+
+/// An iterator over the indexes of a `NSIndexSet`.
+///
+/// Created with [`NSIndexSet::iter`].
+#[derive(Clone)]
+pub struct Iter<'a> {
+    set: &'a NSIndexSet,
+    last: Option<usize>,
+    remaining: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // The number of remaining indexes is tracked instead of checking for
+        // `NSNotFound`, so that the set must contain the returned index.
+        let index = match self.last {
+            None => unsafe { self.set.firstIndex() },
+            Some(last) => unsafe { self.set.indexGreaterThanIndex(last) },
+        };
+        self.last = Some(index);
+        self.remaining -= 1;
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+impl fmt::Debug for Iter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter")
+            .field("last", &self.last)
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> IntoIterator for &'a NSIndexSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a NSMutableIndexSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl Extend<usize> for NSMutableIndexSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |index| {
+            self.insert(index);
+        });
+    }
+}
+
+impl IdFromIterator<usize> for NSIndexSet {
+    fn id_from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Id<Self> {
+        Id::into_super(NSMutableIndexSet::id_from_iter(iter))
+    }
+}
+
+impl IdFromIterator<usize> for NSMutableIndexSet {
+    fn id_from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Id<Self> {
+        let mut set = unsafe { Self::new() };
+        set.extend(iter);
+        set
+    }
+}
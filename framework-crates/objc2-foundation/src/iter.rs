@@ -1,6 +1,5 @@
 #![allow(dead_code)]
 use core::ptr::{self, NonNull};
-#[cfg(debug_assertions)]
 use std::os::raw::c_ulong;
 
 use objc2::mutability::IsIdCloneable;
@@ -43,13 +42,16 @@ struct FastEnumeratorHelper {
     // ensure, so don't think we should consider that one.
     current_item: usize,
     items_count: usize,
-    /// Track mutation mistakes when debug assertions are enabled (they should
-    /// be made impossible by Rust at compile-time, so hence we don't need to
-    /// track them in release mode).
+    /// Track mutations of the collection while it is being enumerated.
+    ///
+    /// Rust's borrow rules make most such mutations impossible, but they can
+    /// still happen through Objective-C code (for example in a callback), or
+    /// through interior mutability. Objective-C's `for-in` loops throw an
+    /// exception in that case, we instead panic with a clear message, both
+    /// with and without debug assertions.
     ///
     /// This is set to `None` initially, but later loaded to `Some(_)` after
     /// the first enumeration.
-    #[cfg(debug_assertions)]
     mutations_state: Option<c_ulong>,
 }
 
@@ -76,7 +78,6 @@ impl FastEnumeratorHelper {
             buf: [ptr::null_mut(); BUF_SIZE],
             current_item: 0,
             items_count: 0,
-            #[cfg(debug_assertions)]
             mutations_state: None,
         }
     }
@@ -156,46 +157,43 @@ impl FastEnumeratorHelper {
             }
         }
 
-        #[cfg(debug_assertions)]
-        {
-            // If the mutation ptr is not set, we do nothing.
-            if let Some(ptr) = NonNull::new(self.state.mutationsPtr) {
-                // SAFETY:
-                // - The pointer is not NULL.
-                //
-                // - The enumerator is expected to give back a dereferenceable
-                //   pointer, that is alive for as long as the collection is
-                //   alive.
-                //
-                //   Note that iterating past the first returned `None` is not
-                //   tested by most Objective-C implementations, so it may
-                //   deallocate the mutations ptr in that case?
-                //
-                // - The enumeration should not be modifiable across threads,
-                //   so neither will this pointer be accessed from different
-                //   threads.
-                //
-                //   Note that this assumption is relatively likely to be
-                //   violated, but if that is the case, the program already
-                //   has UB, so then it is better that we detect it.
-                //
-                // - The value is an integer, so is always initialized.
-                //
-                //
-                // We do an unaligned read here since we have no guarantees
-                // about this pointer, and efficiency doesn't really matter.
-                let new_state = unsafe { ptr.as_ptr().read_unaligned() };
-                match self.mutations_state {
-                    // On the first iteration, initialize the mutation state
-                    None => {
-                        self.mutations_state = Some(new_state);
-                    }
-                    // On subsequent iterations, verify that the state hasn't
-                    // changed.
-                    Some(current_state) => {
-                        if current_state != new_state {
-                            panic!("mutation detected during enumeration. This is undefined behaviour, and must be avoided");
-                        }
+        // If the mutation ptr is not set, we do nothing.
+        if let Some(ptr) = NonNull::new(self.state.mutationsPtr) {
+            // SAFETY:
+            // - The pointer is not NULL.
+            //
+            // - The enumerator is expected to give back a dereferenceable
+            //   pointer, that is alive for as long as the collection is
+            //   alive.
+            //
+            //   Note that iterating past the first returned `None` is not
+            //   tested by most Objective-C implementations, so it may
+            //   deallocate the mutations ptr in that case?
+            //
+            // - The enumeration should not be modifiable across threads,
+            //   so neither will this pointer be accessed from different
+            //   threads.
+            //
+            //   Note that this assumption is relatively likely to be
+            //   violated, but if that is the case, the program already
+            //   has UB, so then it is better that we detect it.
+            //
+            // - The value is an integer, so is always initialized.
+            //
+            //
+            // We do an unaligned read here since we have no guarantees
+            // about this pointer, and efficiency doesn't really matter.
+            let new_state = unsafe { ptr.as_ptr().read_unaligned() };
+            match self.mutations_state {
+                // On the first iteration, initialize the mutation state
+                None => {
+                    self.mutations_state = Some(new_state);
+                }
+                // On subsequent iterations, verify that the state hasn't
+                // changed.
+                Some(current_state) => {
+                    if current_state != new_state {
+                        mutation_detected(collection, current_state, new_state);
                     }
                 }
             }
@@ -223,6 +221,24 @@ impl FastEnumeratorHelper {
     }
 }
 
+/// Like `objc_enumerationMutation`, but with a more helpful message.
+#[cold]
+#[track_caller]
+fn mutation_detected(
+    collection: &ProtocolObject<dyn NSFastEnumeration>,
+    old: c_ulong,
+    new: c_ulong,
+) -> ! {
+    // SAFETY: `ProtocolObject` is a wrapper around `AnyObject`.
+    let obj: &AnyObject = unsafe { &*(collection as *const ProtocolObject<_>).cast() };
+    panic!(
+        "mutation detected during enumeration of {}: the collection was mutated \
+        while being iterated over (the mutation counter changed from {old} to {new}). \
+        Use `iter_copied` to iterate over a snapshot of the collection instead",
+        obj.class(),
+    )
+}
+
 // Unfortunately, `NSFastEnumeration` doesn't provide a way for enumerated
 // objects to clean up their work after having being enumerated over.
 //
@@ -834,8 +850,8 @@ mod tests {
     fn test_enumerator_helper() {
         // We should attempt to reduce these if possible
         assert_eq!(size_of::<NSFastEnumerationState>(), 64);
-        assert_eq!(size_of::<FastEnumeratorHelper>(), 208);
-        assert_eq!(size_of::<Iter<'_, NSArray<NSNumber>>>(), 216);
+        assert_eq!(size_of::<FastEnumeratorHelper>(), 224);
+        assert_eq!(size_of::<Iter<'_, NSArray<NSNumber>>>(), 232);
     }
 
     #[test]
@@ -25,6 +25,8 @@
 //! | `NSError*` | `Arc<dyn Error + Send + Sync>` |
 //! | `NSException*` | `Arc<dyn Error + Send + Sync>` |
 //! | `NSRange` | `ops::Range<usize>` |
+//! | `NSIndexSet*` | `Arc<BTreeSet<usize>>` |
+//! | `NSMutableIndexSet*` | `BTreeSet<usize>` |
 //! | `NSComparisonResult` | `cmp::Ordering` |
 //! | `NSArray<T>*` | `Arc<[T]>` |
 //! | `NSMutableArray<T>*` | `Vec<T>` |
@@ -84,6 +86,8 @@ mod iter;
 pub mod array;
 #[cfg(feature = "NSAttributedString")]
 mod attributed_string;
+#[cfg(all(feature = "std", feature = "NSData"))]
+pub mod base64;
 #[cfg(feature = "NSBundle")]
 mod bundle;
 #[cfg(feature = "NSObjCRuntime")]
@@ -91,40 +95,117 @@ mod comparison_result;
 #[cfg(feature = "NSObject")]
 mod copying;
 #[cfg(feature = "NSData")]
-mod data;
+pub mod data;
 #[cfg(feature = "NSDecimal")]
 mod decimal;
+#[cfg(all(
+    feature = "NSDecimal",
+    feature = "NSDecimalNumber",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+mod decimal_number;
 #[cfg(feature = "NSDictionary")]
 pub mod dictionary;
 #[cfg(feature = "NSEnumerator")]
 pub mod enumerator;
 #[cfg(feature = "NSError")]
 mod error;
+#[cfg(feature = "NSError")]
+mod error_code;
+#[cfg(feature = "futures-core")]
+mod event_stream;
 #[cfg(feature = "NSException")]
 mod exception;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSDate",
+    feature = "NSRunLoop",
+    feature = "NSString",
+    feature = "NSTimer"
+))]
+pub mod executor;
 #[cfg(feature = "NSEnumerator")]
 mod fast_enumeration_state;
+#[cfg(all(
+    feature = "block2",
+    feature = "NSError",
+    feature = "NSFileCoordinator",
+    feature = "NSURL"
+))]
+mod file_coordinator;
+#[cfg(all(
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSDate",
+    feature = "NSDictionary",
+    feature = "NSEnumerator",
+    feature = "NSNull",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+mod foundation_value;
 mod generated;
 mod generics;
 #[cfg(feature = "NSGeometry")]
 mod geometry;
+#[cfg(feature = "NSIndexSet")]
+pub mod index_set;
 mod macros;
+#[cfg(all(
+    feature = "futures-core",
+    feature = "block2",
+    feature = "NSArray",
+    feature = "NSDictionary",
+    feature = "NSMetadata",
+    feature = "NSMetadataAttributes",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSPredicate",
+    feature = "NSSortDescriptor",
+    feature = "NSString",
+    feature = "NSURL",
+    feature = "NSValue"
+))]
+mod metadata_query;
 mod ns_consumed;
+#[cfg(all(feature = "NSNotification", feature = "NSString", feature = "NSOperation"))]
+#[cfg(feature = "block2")]
+mod notification_center;
+#[cfg(feature = "NSNull")]
+mod null;
 #[cfg(feature = "NSValue")]
 mod number;
+#[cfg(feature = "std")]
+mod oneshot;
+#[cfg(feature = "NSOrderedSet")]
+pub mod ordered_set;
 #[cfg(feature = "NSProcessInfo")]
 mod process_info;
 #[cfg(feature = "NSRange")]
 mod range;
 #[cfg(feature = "NSSet")]
 pub mod set;
+#[cfg(feature = "alloc")]
+mod spawn;
+#[cfg(all(feature = "NSStream", feature = "std"))]
+mod stream;
 #[cfg(feature = "NSString")]
 mod string;
+#[cfg(feature = "NSString")]
+mod string_encoding;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
 mod thread;
+#[cfg(all(feature = "NSTimer", feature = "NSDate", feature = "block2"))]
+mod timer;
 #[cfg(feature = "NSObject")]
 mod to_owned;
+#[cfg(all(feature = "std", feature = "NSURL"))]
+mod url;
 mod util;
 #[cfg(feature = "NSUUID")]
 mod uuid;
@@ -135,26 +216,129 @@ mod value;
 pub use self::comparison_result::NSComparisonResult;
 #[cfg(feature = "NSObject")]
 pub use self::copying::{NSCopying, NSMutableCopying};
+#[cfg(feature = "NSData")]
+pub use self::data::ToNSData;
 #[cfg(feature = "NSDecimal")]
 pub use self::decimal::NSDecimal;
+#[cfg(all(
+    feature = "NSDecimal",
+    feature = "NSDecimalNumber",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+pub use self::decimal_number::DecimalBehavior;
+#[cfg(feature = "NSError")]
+#[cfg(feature = "NSString")]
+pub use self::error_code::ErrorCode;
+#[cfg(feature = "NSError")]
+pub use self::error_code::NSCocoaErrorCode;
+#[cfg(feature = "NSError")]
+#[cfg(feature = "NSURLError")]
+pub use self::error_code::NSURLErrorCode;
+#[cfg(feature = "futures-core")]
+pub use self::event_stream::{event_channel, BufferPolicy, EventSender, EventStream};
+#[cfg(all(
+    feature = "futures-core",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSObject",
+    feature = "NSString"
+))]
+pub use self::event_stream::{KeyValueChange, KeyValueStream};
+#[cfg(all(
+    feature = "futures-core",
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+pub use self::event_stream::NotificationStream;
 #[cfg(feature = "NSEnumerator")]
 pub use self::fast_enumeration_state::NSFastEnumerationState;
+#[cfg(all(
+    feature = "futures-core",
+    feature = "block2",
+    feature = "NSError",
+    feature = "NSFileCoordinator",
+    feature = "NSFilePresenter",
+    feature = "NSOperation",
+    feature = "NSURL"
+))]
+pub use self::file_coordinator::{FilePresenter, PresenterEvent};
+#[cfg(all(
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSDate",
+    feature = "NSDictionary",
+    feature = "NSEnumerator",
+    feature = "NSNull",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+pub use self::foundation_value::FoundationValue;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generics::*;
 #[cfg(feature = "NSGeometry")]
 pub use self::geometry::{CGFloat, CGPoint, CGRect, CGSize, NSPoint, NSRect, NSRectEdge, NSSize};
+#[cfg(all(
+    feature = "futures-core",
+    feature = "block2",
+    feature = "NSArray",
+    feature = "NSDictionary",
+    feature = "NSMetadata",
+    feature = "NSMetadataAttributes",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSPredicate",
+    feature = "NSSortDescriptor",
+    feature = "NSString",
+    feature = "NSURL",
+    feature = "NSValue"
+))]
+pub use self::metadata_query::{
+    FromMetadataItem, MetadataEvent, MetadataFile, MetadataQuery, MetadataSearch, SearchScope,
+};
 #[cfg(feature = "NSMapTable")]
 pub use self::ns_consumed::NSFreeMapTable;
+#[cfg(feature = "std")]
+pub use self::oneshot::{oneshot, Oneshot, OneshotSender};
 #[cfg(feature = "NSRange")]
 pub use self::range::NSRange;
+#[cfg(feature = "async-std")]
+pub use self::spawn::AsyncStdSpawner;
+#[cfg(feature = "tokio")]
+pub use self::spawn::TokioSpawner;
+#[cfg(feature = "alloc")]
+pub use self::spawn::{global_spawner, set_global_spawner, spawn, BoxFuture, Spawner};
+#[cfg(feature = "NSString")]
+pub use self::string_encoding::StringEncoding;
 pub use self::thread::MainThreadMarker;
 #[cfg(feature = "NSThread")]
 pub use self::thread::{is_main_thread, is_multi_threaded};
 #[cfg(feature = "NSThread")]
 #[cfg(feature = "dispatch")]
-pub use self::thread::{run_on_main, MainThreadBound};
+pub use self::thread::{run_on_main, MainQueueMarker, MainThreadBound, MainThreadCheck};
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "std")]
+pub use self::thread::{
+    on_main_or_dispatch, on_main_or_dispatch_timeout, MainThreadDeadlock,
+    MAIN_THREAD_DEADLOCK_TIMEOUT,
+};
+#[cfg(all(feature = "std", feature = "NSURL"))]
+pub use self::url::ScopedAccess;
+#[cfg(all(
+    feature = "std",
+    feature = "NSURL",
+    target_os = "macos",
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSString"
+))]
+pub use self::url::ResolvedBookmark;
 
 // Available under Foundation, so makes sense here as well:
 // https://developer.apple.com/documentation/foundation/numbers_data_and_basic_values?language=objc
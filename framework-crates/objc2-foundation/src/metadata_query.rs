@@ -0,0 +1,467 @@
+//! Spotlight searches with `NSMetadataQuery`, as asynchronous streams.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::path::PathBuf;
+
+use futures_core::Stream;
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol};
+use objc2::{ClassType, Message};
+
+use crate::Foundation::{
+    NSArray, NSMetadataItem, NSMetadataItemContentTypeKey, NSMetadataItemFSNameKey,
+    NSMetadataItemFSSizeKey, NSMetadataItemPathKey, NSMetadataQuery,
+    NSMetadataQueryDidFinishGatheringNotification, NSMetadataQueryDidUpdateNotification,
+    NSMetadataQueryLocalComputerScope, NSMetadataQueryNetworkScope,
+    NSMetadataQueryUbiquitousDataScope, NSMetadataQueryUbiquitousDocumentsScope,
+    NSMetadataQueryUpdateAddedItemsKey, NSMetadataQueryUpdateChangedItemsKey,
+    NSMetadataQueryUpdateRemovedItemsKey, NSMetadataQueryUserHomeScope, NSNotification,
+    NSNotificationCenter, NSNumber, NSPredicate, NSSortDescriptor, NSString, NSURL,
+};
+use crate::{BufferPolicy, NotificationStream};
+
+/// Cast the object to the given class, if it is an instance of it.
+fn downcast<T: ClassType>(object: &AnyObject) -> Option<&T> {
+    let object: *const AnyObject = object;
+    // SAFETY: Query results and attribute values are `NSObject`s.
+    let checked = unsafe { &*object.cast::<NSObject>() };
+    if checked.is_kind_of::<T>() {
+        // SAFETY: Just checked that the object is an instance of `T`.
+        Some(unsafe { &*object.cast::<T>() })
+    } else {
+        None
+    }
+}
+
+impl NSMetadataItem {
+    /// The value of a string attribute, such as `NSMetadataItemFSNameKey`.
+    #[doc(alias = "valueForAttribute:")]
+    pub fn string_attribute(&self, attribute: &NSString) -> Option<String> {
+        // SAFETY: Getting an attribute is safe.
+        let value = unsafe { self.valueForAttribute(attribute) }?;
+        downcast::<NSString>(&value).map(|string| string.to_string())
+    }
+
+    /// The value of a numeric attribute, such as `NSMetadataItemFSSizeKey`.
+    #[doc(alias = "valueForAttribute:")]
+    pub fn number_attribute(&self, attribute: &NSString) -> Option<Id<NSNumber>> {
+        // SAFETY: Getting an attribute is safe.
+        let value = unsafe { self.valueForAttribute(attribute) }?;
+        downcast::<NSNumber>(&value).map(|number| number.retain())
+    }
+}
+
+/// A Rust type that can be created from the results of a
+/// [`MetadataQuery`].
+pub trait FromMetadataItem: Sized {
+    /// Read the attributes of the item.
+    ///
+    /// Returns `None` to skip the item, for example if an attribute is
+    /// missing.
+    fn from_item(item: &NSMetadataItem) -> Option<Self>;
+}
+
+impl FromMetadataItem for Id<NSMetadataItem> {
+    fn from_item(item: &NSMetadataItem) -> Option<Self> {
+        Some(item.retain())
+    }
+}
+
+/// The common attributes of a file found by a [`MetadataQuery`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct MetadataFile {
+    /// The path of the file.
+    #[doc(alias = "NSMetadataItemPathKey")]
+    pub path: PathBuf,
+    /// The file name, as stored on disk.
+    #[doc(alias = "NSMetadataItemFSNameKey")]
+    pub name: String,
+    /// The uniform type identifier of the file's content, such as
+    /// `public.plain-text`.
+    #[doc(alias = "NSMetadataItemContentTypeKey")]
+    pub content_type: Option<String>,
+    /// The size of the file in bytes.
+    #[doc(alias = "NSMetadataItemFSSizeKey")]
+    pub size: Option<u64>,
+}
+
+impl FromMetadataItem for MetadataFile {
+    fn from_item(item: &NSMetadataItem) -> Option<Self> {
+        // SAFETY: The attribute keys are valid strings.
+        unsafe {
+            Some(Self {
+                path: item.string_attribute(NSMetadataItemPathKey)?.into(),
+                name: item.string_attribute(NSMetadataItemFSNameKey)?,
+                content_type: item.string_attribute(NSMetadataItemContentTypeKey),
+                size: item
+                    .number_attribute(NSMetadataItemFSSizeKey)
+                    .map(|size| size.as_u64()),
+            })
+        }
+    }
+}
+
+/// Where a [`MetadataQuery`] searches.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SearchScope {
+    /// The user's home directory.
+    #[doc(alias = "NSMetadataQueryUserHomeScope")]
+    UserHome,
+    /// All local volumes.
+    #[doc(alias = "NSMetadataQueryLocalComputerScope")]
+    LocalComputer,
+    /// All mounted network volumes.
+    #[doc(alias = "NSMetadataQueryNetworkScope")]
+    Network,
+    /// The `Documents` directories of the app's iCloud containers.
+    #[doc(alias = "NSMetadataQueryUbiquitousDocumentsScope")]
+    UbiquitousDocuments,
+    /// The app's iCloud containers, outside of their `Documents`
+    /// directories.
+    #[doc(alias = "NSMetadataQueryUbiquitousDataScope")]
+    UbiquitousData,
+    /// The given directory and its subdirectories.
+    Directory(PathBuf),
+}
+
+impl SearchScope {
+    fn to_object(&self) -> Option<Id<AnyObject>> {
+        // SAFETY: The scope constants are valid strings.
+        let scope: &NSString = unsafe {
+            match self {
+                Self::UserHome => NSMetadataQueryUserHomeScope,
+                Self::LocalComputer => NSMetadataQueryLocalComputerScope,
+                Self::Network => NSMetadataQueryNetworkScope,
+                Self::UbiquitousDocuments => NSMetadataQueryUbiquitousDocumentsScope,
+                Self::UbiquitousData => NSMetadataQueryUbiquitousDataScope,
+                Self::Directory(path) => {
+                    let url = directory_url(path)?;
+                    return Some(Id::into_super(Id::into_super(url)));
+                }
+            }
+        };
+        Some(Id::into_super(Id::into_super(scope.retain())))
+    }
+}
+
+#[cfg(unix)]
+fn directory_url(path: &std::path::Path) -> Option<Id<NSURL>> {
+    NSURL::from_path(path, true)
+}
+
+#[cfg(not(unix))]
+fn directory_url(path: &std::path::Path) -> Option<Id<NSURL>> {
+    let path = NSString::from_str(path.to_str()?);
+    // SAFETY: Creating a file URL is safe.
+    Some(unsafe { NSURL::fileURLWithPath_isDirectory(&path, true) })
+}
+
+/// A builder for Spotlight searches.
+///
+/// Filters are predicate format strings over metadata attributes, such as
+/// `kMDItemFSName` or `kMDItemContentTypeTree`, and are combined with
+/// `AND`.
+///
+///
+/// # Example
+///
+/// Find the PDF files in the user's home directory, sorted by name.
+///
+/// ```no_run
+/// use objc2_foundation::{MetadataFile, MetadataQuery, SearchScope};
+///
+/// let search = MetadataQuery::new()
+///     .scope(SearchScope::UserHome)
+///     .content_type("com.adobe.pdf")
+///     .sort("kMDItemFSName", true)
+///     .start::<MetadataFile>()
+///     .expect("failed starting query");
+///
+/// // Poll `search` on the main thread, for example in a task spawned with
+/// // `executor::spawn_local`.
+/// # drop(search);
+/// ```
+#[doc(alias = "NSMetadataQuery")]
+#[derive(Clone, Debug, Default)]
+pub struct MetadataQuery {
+    format: String,
+    arguments: Vec<Id<NSString>>,
+    scopes: Vec<SearchScope>,
+    sort: Vec<Id<NSSortDescriptor>>,
+}
+
+impl MetadataQuery {
+    /// A query matching every item.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match items for which the predicate format string is true.
+    ///
+    /// Each `%@` in the format is replaced by the next argument.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The format must be a valid predicate format string, with exactly one
+    /// format specifier per argument.
+    ///
+    /// The predicate is parsed in [`to_query`](Self::to_query) (and
+    /// therefore in [`start`](Self::start)), and `NSPredicate` raises an
+    /// Objective-C exception there if the format is invalid, which is
+    /// undefined behaviour to unwind into Rust.
+    #[doc(alias = "setPredicate:")]
+    pub unsafe fn filter(mut self, format: &str, arguments: &[&str]) -> Self {
+        if self.format.is_empty() {
+            self.format.push('(');
+        } else {
+            self.format.push_str(" AND (");
+        }
+        self.format.push_str(format);
+        self.format.push(')');
+        self.arguments.extend(
+            arguments
+                .iter()
+                .map(|argument| NSString::from_str(argument)),
+        );
+        self
+    }
+
+    /// Only match items whose file name matches the pattern, ignoring case
+    /// and diacritics.
+    ///
+    /// The pattern may contain the wildcards `*` and `?`.
+    pub fn name_like(self, pattern: &str) -> Self {
+        // SAFETY: The format is valid, and has a single argument.
+        unsafe { self.filter("kMDItemFSName LIKE[cd] %@", &[pattern]) }
+    }
+
+    /// Only match items whose content type conforms to the given uniform
+    /// type identifier, such as `public.image`.
+    pub fn content_type(self, identifier: &str) -> Self {
+        // SAFETY: The format is valid, and has a single argument.
+        unsafe { self.filter("kMDItemContentTypeTree == %@", &[identifier]) }
+    }
+
+    /// Search the given scope, in addition to the previously added ones.
+    ///
+    /// Without any scopes, Spotlight searches all local volumes.
+    #[doc(alias = "setSearchScopes:")]
+    pub fn scope(mut self, scope: SearchScope) -> Self {
+        self.scopes.push(scope);
+        self
+    }
+
+    /// Sort the results by the attribute, after any previously added sort
+    /// attributes.
+    #[doc(alias = "setSortDescriptors:")]
+    pub fn sort(mut self, attribute: &str, ascending: bool) -> Self {
+        // SAFETY: Creating a sort descriptor is safe.
+        let descriptor = unsafe {
+            NSSortDescriptor::sortDescriptorWithKey_ascending(
+                Some(&NSString::from_str(attribute)),
+                ascending,
+            )
+        };
+        self.sort.push(descriptor);
+        self
+    }
+
+    /// Create the underlying query, without starting it.
+    pub fn to_query(&self) -> Id<NSMetadataQuery> {
+        let format = if self.format.is_empty() {
+            "TRUEPREDICATE"
+        } else {
+            &self.format
+        };
+        let scopes: Vec<Id<AnyObject>> = self
+            .scopes
+            .iter()
+            .filter_map(|scope| scope.to_object())
+            .collect();
+        let arguments: Vec<Id<AnyObject>> = self
+            .arguments
+            .iter()
+            .map(|argument| Id::into_super(Id::into_super(argument.clone())))
+            .collect();
+        // SAFETY: The format strings are either our own, or were checked by
+        // the caller of `filter`, so creating the predicate won't throw.
+        unsafe {
+            let query = NSMetadataQuery::new();
+            let predicate = NSPredicate::predicateWithFormat_argumentArray(
+                &NSString::from_str(format),
+                Some(&NSArray::from_vec(arguments)),
+            );
+            query.setPredicate(Some(&predicate));
+            if !scopes.is_empty() {
+                query.setSearchScopes(&NSArray::from_vec(scopes));
+            }
+            if !self.sort.is_empty() {
+                query.setSortDescriptors(&NSArray::from_id_slice(&self.sort));
+            }
+            query
+        }
+    }
+
+    /// Start the search, and stream its results converted to `T`.
+    ///
+    /// The query gathers its initial results and observes later changes on
+    /// the run loop of the current thread, which is usually the main
+    /// thread; the stream only receives events while that run loop is
+    /// running. The query is stopped when the stream is dropped.
+    ///
+    /// Returns `None` if the query could not be started.
+    #[doc(alias = "startQuery")]
+    pub fn start<T: FromMetadataItem>(&self) -> Option<MetadataSearch<T>> {
+        let query = self.to_query();
+        let object: &AnyObject = &query;
+        // SAFETY: The query posts its notifications on the run loop of the
+        // current thread, and the search is not `Send`, so it is used on that
+        // thread too.
+        let notifications = unsafe {
+            NSNotificationCenter::defaultCenter().notification_stream_unchecked(
+                None,
+                Some(object),
+                BufferPolicy::Unbounded,
+                |notification| notification.retain(),
+            )
+        };
+        // SAFETY: The query is configured, and not yet started.
+        if unsafe { query.startQuery() } {
+            Some(MetadataSearch {
+                query,
+                notifications,
+                p: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A change to the results of a [`MetadataSearch`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MetadataEvent<T> {
+    /// The initial results, once they have been gathered.
+    #[doc(alias = "NSMetadataQueryDidFinishGatheringNotification")]
+    Gathered(Vec<T>),
+    /// The results changed after they were gathered.
+    #[doc(alias = "NSMetadataQueryDidUpdateNotification")]
+    Updated {
+        /// Items that started matching the query.
+        added: Vec<T>,
+        /// Items that still match the query, but whose attributes changed.
+        changed: Vec<T>,
+        /// Items that no longer match the query.
+        removed: Vec<T>,
+    },
+}
+
+/// A running Spotlight search.
+///
+/// Created with [`MetadataQuery::start`].
+#[must_use = "streams do nothing unless polled"]
+pub struct MetadataSearch<T> {
+    query: Id<NSMetadataQuery>,
+    notifications: NotificationStream<Id<NSNotification>>,
+    p: PhantomData<fn() -> T>,
+}
+
+impl<T: FromMetadataItem> MetadataSearch<T> {
+    /// The underlying query.
+    pub fn query(&self) -> &NSMetadataQuery {
+        &self.query
+    }
+
+    /// The current results of the query.
+    #[doc(alias = "resultAtIndex:")]
+    pub fn results(&self) -> Vec<T> {
+        // SAFETY: Updates are disabled while the results are read, so that
+        // they are not changed concurrently.
+        unsafe {
+            self.query.disableUpdates();
+            let results = (0..self.query.resultCount())
+                .filter_map(|index| {
+                    let item = self.query.resultAtIndex(index);
+                    downcast::<NSMetadataItem>(&item).and_then(T::from_item)
+                })
+                .collect();
+            self.query.enableUpdates();
+            results
+        }
+    }
+
+    fn event(&self, notification: &NSNotification) -> Option<MetadataEvent<T>> {
+        // SAFETY: The notification names and keys are valid strings.
+        unsafe {
+            let name = notification.name();
+            if &*name == NSMetadataQueryDidFinishGatheringNotification {
+                Some(MetadataEvent::Gathered(self.results()))
+            } else if &*name == NSMetadataQueryDidUpdateNotification {
+                let user_info = notification.userInfo();
+                let items = |key: &NSString| -> Vec<T> {
+                    let key: &AnyObject = key;
+                    let items = user_info.as_ref().and_then(|user_info| user_info.get(key));
+                    let items = match items.and_then(downcast::<NSArray>) {
+                        Some(items) => items,
+                        None => return Vec::new(),
+                    };
+                    (0..items.len())
+                        .filter_map(|index| items.get(index))
+                        .filter_map(downcast::<NSMetadataItem>)
+                        .filter_map(T::from_item)
+                        .collect()
+                };
+                Some(MetadataEvent::Updated {
+                    added: items(NSMetadataQueryUpdateAddedItemsKey),
+                    changed: items(NSMetadataQueryUpdateChangedItemsKey),
+                    removed: items(NSMetadataQueryUpdateRemovedItemsKey),
+                })
+            } else {
+                // Progress notifications are not forwarded.
+                None
+            }
+        }
+    }
+}
+
+impl<T: FromMetadataItem> Stream for MetadataSearch<T> {
+    type Item = MetadataEvent<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.notifications).poll_next(cx) {
+                Poll::Ready(Some(notification)) => {
+                    if let Some(event) = self.event(&notification) {
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> Drop for MetadataSearch<T> {
+    fn drop(&mut self) {
+        // SAFETY: Stopping a started query is safe.
+        unsafe { self.query.stopQuery() };
+    }
+}
+
+impl<T> fmt::Debug for MetadataSearch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetadataSearch")
+            .field("query", &self.query)
+            .field("notifications", &self.notifications)
+            .finish_non_exhaustive()
+    }
+}
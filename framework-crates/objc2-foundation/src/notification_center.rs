@@ -0,0 +1,51 @@
+use core::ptr::NonNull;
+
+use block2::{Block, RcBlock};
+use objc2::mutability::IsRetainable;
+use objc2::rc::{Id, WeakCallback};
+use objc2::runtime::{AnyObject, ProtocolObject};
+use objc2::Message;
+
+use crate::Foundation::{
+    NSNotification, NSNotificationCenter, NSNotificationName, NSObjectProtocol, NSOperationQueue,
+};
+
+impl NSNotificationCenter {
+    /// Add an observer block that is only invoked while `observer` is alive.
+    ///
+    /// The closure is given a strong reference to `observer`, which is
+    /// otherwise only referenced weakly, so that registering the block does
+    /// not keep the observer alive (see [`WeakCallback`]). Once the observer
+    /// has been deallocated, notifications are ignored.
+    ///
+    /// The returned object must still be passed to `removeObserver:` to
+    /// unregister the block when you are done with it.
+    ///
+    /// If `queue` is `None`, the block is invoked synchronously on whichever
+    /// thread posts the notification, so both the observer and the closure
+    /// must be safe to use from other threads.
+    #[doc(alias = "addObserverForName:object:queue:usingBlock:")]
+    pub fn add_weak_observer<T, F>(
+        &self,
+        name: Option<&NSNotificationName>,
+        object: Option<&AnyObject>,
+        queue: Option<&NSOperationQueue>,
+        observer: &T,
+        f: F,
+    ) -> Id<ProtocolObject<dyn NSObjectProtocol>>
+    where
+        T: Message + IsRetainable + Send + Sync + 'static,
+        F: Fn(&T, &NSNotification) + Send + Sync + 'static,
+    {
+        let callback = WeakCallback::new(observer, f);
+        let block = RcBlock::new(move |notification: NonNull<NSNotification>| {
+            // SAFETY: The notification is valid for the duration of the call.
+            let notification = unsafe { notification.as_ref() };
+            let _ = callback.call_with(notification);
+        });
+        let block: &Block<dyn Fn(NonNull<NSNotification>) + 'static> = &block;
+        // SAFETY: The block has the correct signature, and the notification
+        // center retains (copies) it.
+        unsafe { self.addObserverForName_object_queue_usingBlock(name, object, queue, block) }
+    }
+}
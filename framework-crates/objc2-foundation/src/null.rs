@@ -0,0 +1,151 @@
+//! Bridging `NSNull` in collections to `Option`.
+//!
+//! Collections in Objective-C cannot contain `nil`, so `NSNull` is used as a
+//! placeholder instead; notably, `NSJSONSerialization` returns `NSNull` for
+//! JSON `null`. This means that a collection that is nominally typed as
+//! e.g. `NSArray<NSString>` may in reality contain `NSNull` objects too, so
+//! the helpers in this module check each element, and either map `NSNull` to
+//! [`None`] or skip it, depending on which conversion is used.
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "NSArray")]
+use objc2::mutability::IsRetainable;
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+use objc2::{msg_send_id, ClassType, Message};
+
+#[cfg(feature = "NSDictionary")]
+use crate::Foundation::NSDictionary;
+use crate::Foundation::NSNull;
+#[cfg(feature = "NSArray")]
+use crate::Foundation::{NSArray, NSMutableArray};
+
+impl NSNull {
+    /// Whether the given object is `NSNull`.
+    ///
+    /// This works on any object, also those whose static type claims that
+    /// they can't be `NSNull`, such as elements of an `NSArray<NSString>`
+    /// that came from JSON.
+    pub fn is_null<T: Message>(obj: &T) -> bool {
+        let obj: *const T = obj;
+        // SAFETY: All `Message` types are objects, and can be viewed as
+        // `AnyObject`.
+        let obj: &AnyObject = unsafe { &*obj.cast() };
+        obj.class() == Self::class()
+    }
+
+    /// Get the `NSNull` singleton as an [`AnyObject`], for storing in
+    /// heterogeneous collections.
+    #[doc(alias = "null")]
+    pub fn null_object() -> Id<AnyObject> {
+        // SAFETY: `+[NSNull null]` returns the (non-null) singleton.
+        unsafe { msg_send_id![Self::class(), null] }
+    }
+}
+
+/// Map `NSNull` to [`None`].
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+fn non_null<T: Message>(obj: &T) -> Option<&T> {
+    if NSNull::is_null(obj) {
+        None
+    } else {
+        Some(obj)
+    }
+}
+
+/// Convert an optional object to an [`AnyObject`], using `NSNull` for
+/// [`None`].
+#[cfg(feature = "NSArray")]
+fn retain_or_null<T: Message + IsRetainable>(obj: Option<&T>) -> Id<AnyObject> {
+    match obj {
+        Some(obj) => {
+            let obj: *const T = obj;
+            // SAFETY: The pointer came from a reference, and the
+            // `IsRetainable` bound ensures that we're allowed to retain it.
+            // All `Message` types can be viewed as `AnyObject`.
+            unsafe { Id::retain(obj as *mut AnyObject).unwrap_unchecked() }
+        }
+        None => NSNull::null_object(),
+    }
+}
+
+#[cfg(feature = "NSArray")]
+impl<T: Message> NSArray<T> {
+    /// Convert the array to a `Vec`, mapping `NSNull` elements to [`None`].
+    ///
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "NSString", doc = "```")]
+    #[cfg_attr(not(feature = "NSString"), doc = "```ignore")]
+    /// use objc2_foundation::{ns_string, NSArray};
+    ///
+    /// let array = NSArray::from_nullable_slice(&[Some(ns_string!("a")), None]);
+    /// assert_eq!(array.len(), 2);
+    ///
+    /// let vec = array.to_vec_nullable();
+    /// assert!(vec[0].is_some());
+    /// assert!(vec[1].is_none());
+    /// assert_eq!(array.to_vec_non_null().len(), 1);
+    /// ```
+    #[cfg(feature = "NSRange")]
+    pub fn to_vec_nullable(&self) -> Vec<Option<&T>> {
+        self.to_vec().into_iter().map(non_null).collect()
+    }
+
+    /// Convert the array to a `Vec`, skipping `NSNull` elements.
+    #[cfg(feature = "NSRange")]
+    pub fn to_vec_non_null(&self) -> Vec<&T> {
+        self.to_vec()
+            .into_iter()
+            .filter(|obj| !NSNull::is_null(*obj))
+            .collect()
+    }
+
+    /// Create an array from a slice of optional objects, using `NSNull` to
+    /// represent [`None`].
+    ///
+    /// The returned array is typed as containing [`AnyObject`], since it
+    /// may contain `NSNull`.
+    pub fn from_nullable_slice(slice: &[Option<&T>]) -> Id<NSArray<AnyObject>>
+    where
+        T: IsRetainable,
+    {
+        NSArray::from_vec(slice.iter().map(|obj| retain_or_null(*obj)).collect())
+    }
+}
+
+#[cfg(feature = "NSArray")]
+impl<T: Message> NSMutableArray<T> {
+    /// Create a mutable array from a slice of optional objects, using
+    /// `NSNull` to represent [`None`].
+    pub fn from_nullable_slice(slice: &[Option<&T>]) -> Id<NSMutableArray<AnyObject>>
+    where
+        T: IsRetainable,
+    {
+        NSMutableArray::from_vec(slice.iter().map(|obj| retain_or_null(*obj)).collect())
+    }
+}
+
+#[cfg(feature = "NSDictionary")]
+impl<K: Message, V: Message> NSDictionary<K, V> {
+    /// Get the value for the given key, treating a value of `NSNull` the
+    /// same as if the key was not present in the dictionary.
+    #[doc(alias = "objectForKey:")]
+    pub fn get_non_null(&self, key: &K) -> Option<&V>
+    where
+        K: Eq + core::hash::Hash,
+    {
+        self.get(key).filter(|obj| !NSNull::is_null(*obj))
+    }
+
+    /// Convert the dictionary to a `Vec` of keys and a `Vec` of values,
+    /// mapping `NSNull` values to [`None`].
+    #[doc(alias = "getObjects:andKeys:")]
+    pub fn to_vecs_nullable(&self) -> (Vec<&K>, Vec<Option<&V>>) {
+        let (keys, objs) = self.to_vecs();
+        let objs = objs.into_iter().map(non_null).collect();
+        (keys, objs)
+    }
+}
@@ -0,0 +1,117 @@
+//! A future that resolves with a single value, e.g. from a completion
+//! handler.
+use alloc::sync::Arc;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::Mutex;
+
+struct State<T> {
+    value: Option<T>,
+    sent: bool,
+    waker: Option<Waker>,
+}
+
+/// Create a future that resolves with the first value sent to it, and a
+/// sender for sending that value.
+///
+/// This is useful for implementing futures on top of completion handlers,
+/// where the sender is moved into the handler. The sender is thread-safe
+/// (if the value is [`Send`]), so the handler may be called on any thread.
+///
+/// If the sender is never used, the future never resolves.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_foundation::oneshot;
+///
+/// let (sender, receiver) = oneshot::<i32>();
+/// sender.send(1);
+/// // Only the first value is used.
+/// sender.send(2);
+/// assert_eq!(receiver.try_take(), Some(1));
+/// ```
+pub fn oneshot<T>() -> (OneshotSender<T>, Oneshot<T>) {
+    let state = Arc::new(Mutex::new(State {
+        value: None,
+        sent: false,
+        waker: None,
+    }));
+    (OneshotSender(state.clone()), Oneshot(state))
+}
+
+/// The sending half of a [`Oneshot`].
+///
+/// Created with [`oneshot`].
+pub struct OneshotSender<T>(Arc<Mutex<State<T>>>);
+
+impl<T> OneshotSender<T> {
+    /// Send the value to the future, and wake the task polling it, if any.
+    ///
+    /// Values sent after the first are ignored.
+    pub fn send(&self, value: T) {
+        let mut state = self.0.lock().unwrap();
+        if state.sent {
+            return;
+        }
+        state.value = Some(value);
+        state.sent = true;
+        let waker = state.waker.take();
+        // Wake without holding the lock.
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Clone for OneshotSender<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for OneshotSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OneshotSender").finish_non_exhaustive()
+    }
+}
+
+/// A future that resolves with the value sent with a [`OneshotSender`].
+///
+/// Created with [`oneshot`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Oneshot<T>(Arc<Mutex<State<T>>>);
+
+impl<T> Oneshot<T> {
+    /// Take the value if it has been sent, without waiting for it.
+    pub fn try_take(&self) -> Option<T> {
+        self.0.lock().unwrap().value.take()
+    }
+}
+
+impl<T> Future for Oneshot<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.0.lock().unwrap();
+        match state.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Oneshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Oneshot")
+            .field("sent", &self.0.lock().unwrap().sent)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,524 @@
+//! Utilities for the `NSOrderedSet` and `NSMutableOrderedSet` classes.
+use alloc::vec::Vec;
+#[cfg(feature = "NSEnumerator")]
+use core::fmt;
+use core::hash::Hash;
+use core::ops::Index;
+
+use objc2::mutability::{HasStableHash, IsIdCloneable, IsRetainable};
+use objc2::rc::{Id, IdFromIterator};
+use objc2::{extern_methods, ClassType, Message};
+
+#[cfg(feature = "NSEnumerator")]
+use super::iter;
+use super::util;
+#[cfg(feature = "NSIndexSet")]
+use crate::Foundation::NSIndexSet;
+use crate::Foundation::{NSMutableOrderedSet, NSOrderedSet};
+
+impl<T: Message> NSOrderedSet<T> {
+    /// Returns the number of elements in the ordered set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{NSOrderedSet, NSString};
+    ///
+    /// let strs = ["one", "two", "one"].map(NSString::from_str);
+    /// let set = NSOrderedSet::from_id_slice(&strs);
+    /// assert_eq!(set.len(), 2);
+    /// ```
+    #[doc(alias = "count")]
+    pub fn len(&self) -> usize {
+        self.count()
+    }
+
+    /// Returns `true` if the ordered set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Message + Eq + Hash> NSOrderedSet<T> {
+    /// Creates an [`NSOrderedSet`] from a vector.
+    ///
+    /// The elements keep the order of their first occurrence in the vector,
+    /// and later duplicates are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSOrderedSet, NSString};
+    ///
+    /// let strs = ["b", "a", "b"].map(NSString::from_str).to_vec();
+    /// let set = NSOrderedSet::from_vec(strs);
+    /// assert_eq!(set.index_of(ns_string!("a")), Some(1));
+    /// ```
+    pub fn from_vec(mut vec: Vec<Id<T>>) -> Id<Self>
+    where
+        T: HasStableHash,
+    {
+        let len = vec.len();
+        let ptr = util::id_ptr_cast(vec.as_mut_ptr());
+        // SAFETY: Same as `NSArray::from_vec`.
+        unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
+    }
+
+    /// Creates an [`NSOrderedSet`] from a slice of `Id`s.
+    ///
+    /// See [`from_vec`](Self::from_vec) for how duplicates are handled.
+    pub fn from_id_slice(slice: &[Id<T>]) -> Id<Self>
+    where
+        T: HasStableHash + IsIdCloneable,
+    {
+        let len = slice.len();
+        let ptr = util::id_ptr_cast_const(slice.as_ptr());
+        // SAFETY: Same as `NSArray::from_id_slice`
+        unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
+    }
+
+    pub fn from_slice(slice: &[&T]) -> Id<Self>
+    where
+        T: HasStableHash + IsRetainable,
+    {
+        let len = slice.len();
+        let ptr = util::ref_ptr_cast_const(slice.as_ptr());
+        // SAFETY: Same as `NSArray::from_slice`.
+        unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
+    }
+
+    /// Returns a [`Vec`] containing the ordered set's elements, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{NSOrderedSet, NSString};
+    ///
+    /// let strs = ["one", "two", "three"].map(NSString::from_str);
+    /// let set = NSOrderedSet::from_id_slice(&strs);
+    /// let vec = set.to_vec();
+    /// assert_eq!(vec, [&*strs[0], &*strs[1], &*strs[2]]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<&T> {
+        (0..self.len())
+            .filter_map(|index| self.get(index))
+            .collect()
+    }
+
+    pub fn to_vec_retained(&self) -> Vec<Id<T>>
+    where
+        T: IsIdCloneable,
+    {
+        (0..self.len())
+            .filter_map(|index| self.get_retained(index))
+            .collect()
+    }
+
+    /// Returns an [`NSArray`] containing the ordered set's elements, in
+    /// order.
+    ///
+    /// [`NSArray`]: crate::Foundation::NSArray
+    #[doc(alias = "array")]
+    #[cfg(feature = "NSArray")]
+    pub fn to_array(&self) -> Id<crate::Foundation::NSArray<T>>
+    where
+        T: IsIdCloneable,
+    {
+        // SAFETY: The `T: IsIdCloneable` bound ensures that it is safe to
+        // create what is effectively a copy of the collection from a `&self`
+        // reference.
+        unsafe { self.array() }
+    }
+
+    /// Returns `true` if the ordered set contains a value.
+    #[doc(alias = "containsObject:")]
+    pub fn contains(&self, value: &T) -> bool {
+        unsafe { self.containsObject(value) }
+    }
+
+    /// Returns the position of the value in the ordered set, if it is
+    /// contained in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSOrderedSet, NSString};
+    ///
+    /// let strs = ["one", "two"].map(NSString::from_str);
+    /// let set = NSOrderedSet::from_id_slice(&strs);
+    /// assert_eq!(set.index_of(ns_string!("two")), Some(1));
+    /// assert_eq!(set.index_of(ns_string!("three")), None);
+    /// ```
+    #[doc(alias = "indexOfObject:")]
+    pub fn index_of(&self, value: &T) -> Option<usize> {
+        let index = unsafe { self.indexOfObject(value) };
+        // Missing values return `NSNotFound`, which is out of bounds.
+        if index < self.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Message> NSOrderedSet<T> {
+    /// Returns the elements at the given indexes, in ascending order of
+    /// the indexes.
+    ///
+    /// Returns `None` if any of the indexes are out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSIndexSet, NSOrderedSet, NSString};
+    ///
+    /// let strs = ["a", "b", "c"].map(NSString::from_str);
+    /// let set = NSOrderedSet::from_id_slice(&strs);
+    /// let indexes = NSIndexSet::from_indexes(&[0, 2]);
+    /// assert_eq!(
+    ///     set.get_indexes(&indexes),
+    ///     Some(vec![ns_string!("a"), ns_string!("c")]),
+    /// );
+    /// assert_eq!(set.get_indexes(&NSIndexSet::from_indexes(&[3])), None);
+    /// ```
+    #[doc(alias = "objectsAtIndexes:")]
+    #[cfg(feature = "NSIndexSet")]
+    pub fn get_indexes(&self, indexes: &NSIndexSet) -> Option<Vec<&T>> {
+        indexes.iter().map(|index| self.get(index)).collect()
+    }
+}
+
+extern_methods!(
+    unsafe impl<T: Message> NSOrderedSet<T> {
+        #[method(objectAtIndex:)]
+        unsafe fn get_unchecked(&self, index: usize) -> &T;
+
+        #[doc(alias = "objectAtIndex:")]
+        pub fn get(&self, index: usize) -> Option<&T> {
+            if index < self.len() {
+                // SAFETY: The index is checked to be in bounds.
+                Some(unsafe { self.get_unchecked(index) })
+            } else {
+                None
+            }
+        }
+
+        #[doc(alias = "objectAtIndex:")]
+        pub fn get_retained(&self, index: usize) -> Option<Id<T>>
+        where
+            T: IsIdCloneable,
+        {
+            // SAFETY: The object is stored in the ordered set
+            self.get(index)
+                .map(|obj| unsafe { util::collection_retain_id(obj) })
+        }
+
+        #[doc(alias = "firstObject")]
+        #[method(firstObject)]
+        pub fn first(&self) -> Option<&T>;
+
+        #[doc(alias = "lastObject")]
+        #[method(lastObject)]
+        pub fn last(&self) -> Option<&T>;
+    }
+);
+
+impl<T: Message + Eq + Hash> NSMutableOrderedSet<T> {
+    /// Creates an [`NSMutableOrderedSet`] from a vector.
+    ///
+    /// See [`NSOrderedSet::from_vec`] for how duplicates are handled.
+    pub fn from_vec(mut vec: Vec<Id<T>>) -> Id<Self>
+    where
+        T: HasStableHash,
+    {
+        let len = vec.len();
+        let ptr = util::id_ptr_cast(vec.as_mut_ptr());
+        // SAFETY: Same as `NSArray::from_vec`.
+        unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
+    }
+
+    pub fn from_id_slice(slice: &[Id<T>]) -> Id<Self>
+    where
+        T: HasStableHash + IsIdCloneable,
+    {
+        let len = slice.len();
+        let ptr = util::id_ptr_cast_const(slice.as_ptr());
+        // SAFETY: Same as `NSArray::from_id_slice`
+        unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
+    }
+
+    pub fn from_slice(slice: &[&T]) -> Id<Self>
+    where
+        T: HasStableHash + IsRetainable,
+    {
+        let len = slice.len();
+        let ptr = util::ref_ptr_cast_const(slice.as_ptr());
+        // SAFETY: Same as `NSArray::from_slice`.
+        unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
+    }
+
+    /// Returns a [`Vec`] containing the ordered set's elements, in order,
+    /// consuming the set.
+    pub fn into_vec(set: Id<Self>) -> Vec<Id<T>> {
+        set.to_vec()
+            .into_iter()
+            .map(|obj| unsafe { util::mutable_collection_retain_removed_id(obj) })
+            .collect()
+    }
+
+    /// Add a value to the end of the ordered set. Returns whether the value
+    /// was newly inserted; values that are already contained keep their
+    /// position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSMutableOrderedSet, NSString};
+    ///
+    /// let mut set = NSMutableOrderedSet::new();
+    ///
+    /// assert_eq!(set.push(NSString::from_str("one")), true);
+    /// assert_eq!(set.push(NSString::from_str("two")), true);
+    /// assert_eq!(set.push(NSString::from_str("one")), false);
+    /// assert_eq!(set.index_of(ns_string!("two")), Some(1));
+    /// ```
+    #[doc(alias = "addObject:")]
+    pub fn push(&mut self, value: Id<T>) -> bool
+    where
+        T: HasStableHash,
+    {
+        let contains_value = self.contains(&value);
+        // SAFETY: We've consumed ownership of the object.
+        unsafe { self.addObject(&value) };
+        !contains_value
+    }
+
+    /// Insert a value at the given position. Returns whether the value was
+    /// newly inserted; values that are already contained are not moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[doc(alias = "insertObject:atIndex:")]
+    pub fn insert(&mut self, index: usize, value: Id<T>) -> bool
+    where
+        T: HasStableHash,
+    {
+        let len = self.len();
+        if index > len {
+            panic!("insertion index (is {index}) should be <= len (is {len})");
+        }
+        let contains_value = self.contains(&value);
+        // SAFETY: We've consumed ownership of the object, and the index is
+        // checked to be in bounds.
+        unsafe { self.insertObject_atIndex(&value, index) };
+        !contains_value
+    }
+
+    /// Removes a value from the ordered set. Returns whether the value was
+    /// present in the set.
+    #[doc(alias = "removeObject:")]
+    pub fn remove(&mut self, value: &T) -> bool
+    where
+        T: HasStableHash,
+    {
+        let contains_value = self.contains(value);
+        unsafe { self.removeObject(value) };
+        contains_value
+    }
+
+    /// Removes and returns the value at the given position.
+    #[doc(alias = "removeObjectAtIndex:")]
+    pub fn remove_at(&mut self, index: usize) -> Option<Id<T>> {
+        let obj = self.get(index)?;
+        // SAFETY: We remove the object from the ordered set below.
+        let obj = unsafe { util::mutable_collection_retain_removed_id(obj) };
+        // SAFETY: The index is checked to be in bounds.
+        unsafe { self.removeObjectAtIndex(index) };
+        Some(obj)
+    }
+}
+
+impl<T: Message> NSOrderedSet<T> {
+    /// An iterator visiting all elements in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{NSOrderedSet, NSString};
+    ///
+    /// let strs = ["one", "two", "three"].map(NSString::from_str);
+    /// let set = NSOrderedSet::from_id_slice(&strs);
+    /// for s in &set {
+    ///     println!("{s}");
+    /// }
+    /// ```
+    #[doc(alias = "objectEnumerator")]
+    #[cfg(feature = "NSEnumerator")]
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(super::iter::Iter::new(self))
+    }
+
+    #[doc(alias = "objectEnumerator")]
+    #[cfg(feature = "NSEnumerator")]
+    #[inline]
+    pub fn iter_retained(&self) -> IterRetained<'_, T>
+    where
+        T: IsIdCloneable,
+    {
+        IterRetained(super::iter::IterRetained::new(self))
+    }
+}
+
+#[cfg(feature = "NSEnumerator")]
+unsafe impl<T: Message> iter::FastEnumerationHelper for NSOrderedSet<T> {
+    type Item = T;
+
+    #[inline]
+    fn maybe_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+#[cfg(feature = "NSEnumerator")]
+unsafe impl<T: Message> iter::FastEnumerationHelper for NSMutableOrderedSet<T> {
+    type Item = T;
+
+    #[inline]
+    fn maybe_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+/// An iterator over the items of a `NSOrderedSet`.
+#[derive(Debug)]
+#[cfg(feature = "NSEnumerator")]
+pub struct Iter<'a, T: Message>(iter::Iter<'a, NSOrderedSet<T>>);
+
+#[cfg(feature = "NSEnumerator")]
+__impl_iter! {
+    impl<'a, T: Message> Iterator<Item = &'a T> for Iter<'a, T> { ... }
+}
+
+/// An iterator that retains the items of a `NSOrderedSet`.
+#[derive(Debug)]
+#[cfg(feature = "NSEnumerator")]
+pub struct IterRetained<'a, T: Message>(iter::IterRetained<'a, NSOrderedSet<T>>);
+
+#[cfg(feature = "NSEnumerator")]
+__impl_iter! {
+    impl<'a, T: Message + IsIdCloneable> Iterator<Item = Id<T>> for IterRetained<'a, T> { ... }
+}
+
+/// A consuming iterator over the items of a `NSOrderedSet`.
+#[derive(Debug)]
+#[cfg(feature = "NSEnumerator")]
+pub struct IntoIter<T: Message>(iter::IntoIter<NSOrderedSet<T>>);
+
+#[cfg(feature = "NSEnumerator")]
+__impl_iter! {
+    impl<'a, T: Message> Iterator<Item = Id<T>> for IntoIter<T> { ... }
+}
+
+#[cfg(feature = "NSEnumerator")]
+__impl_into_iter! {
+    impl<T: Message> IntoIterator for &NSOrderedSet<T> {
+        type IntoIter = Iter<'_, T>;
+    }
+
+    impl<T: Message> IntoIterator for &NSMutableOrderedSet<T> {
+        type IntoIter = Iter<'_, T>;
+    }
+
+    impl<T: Message + IsIdCloneable> IntoIterator for Id<NSOrderedSet<T>> {
+        type IntoIter = IntoIter<T>;
+    }
+
+    impl<T: Message> IntoIterator for Id<NSMutableOrderedSet<T>> {
+        type IntoIter = IntoIter<T>;
+    }
+}
+
+impl<T: Message> Index<usize> for NSOrderedSet<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T: Message> Index<usize> for NSMutableOrderedSet<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+#[cfg(feature = "NSEnumerator")]
+impl<T: fmt::Debug + Message> fmt::Debug for NSOrderedSet<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self).finish()
+    }
+}
+
+#[cfg(feature = "NSEnumerator")]
+impl<T: fmt::Debug + Message> fmt::Debug for NSMutableOrderedSet<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Message + Eq + Hash + HasStableHash> Extend<Id<T>> for NSMutableOrderedSet<T> {
+    fn extend<I: IntoIterator<Item = Id<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |item| {
+            self.push(item);
+        });
+    }
+}
+
+impl<'a, T: Message + Eq + Hash + HasStableHash + IsRetainable> Extend<&'a T>
+    for NSMutableOrderedSet<T>
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        // SAFETY: Because of the `T: IsRetainable` bound, it is safe for the
+        // ordered set to retain the object here.
+        iter.into_iter()
+            .for_each(move |item| unsafe { self.addObject(item) });
+    }
+}
+
+impl<'a, T: Message + Eq + Hash + HasStableHash + IsRetainable + 'a> IdFromIterator<&'a T>
+    for NSOrderedSet<T>
+{
+    fn id_from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Id<Self> {
+        let vec = Vec::from_iter(iter);
+        Self::from_slice(&vec)
+    }
+}
+
+impl<T: Message + Eq + Hash + HasStableHash> IdFromIterator<Id<T>> for NSOrderedSet<T> {
+    fn id_from_iter<I: IntoIterator<Item = Id<T>>>(iter: I) -> Id<Self> {
+        let vec = Vec::from_iter(iter);
+        Self::from_vec(vec)
+    }
+}
+
+impl<'a, T: Message + Eq + Hash + HasStableHash + IsRetainable + 'a> IdFromIterator<&'a T>
+    for NSMutableOrderedSet<T>
+{
+    fn id_from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Id<Self> {
+        let vec = Vec::from_iter(iter);
+        Self::from_slice(&vec)
+    }
+}
+
+impl<T: Message + Eq + Hash + HasStableHash> IdFromIterator<Id<T>> for NSMutableOrderedSet<T> {
+    fn id_from_iter<I: IntoIterator<Item = Id<T>>>(iter: I) -> Id<Self> {
+        let vec = Vec::from_iter(iter);
+        Self::from_vec(vec)
+    }
+}
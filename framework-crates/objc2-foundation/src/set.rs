@@ -11,7 +11,7 @@ use objc2::{extern_methods, ClassType, Message};
 #[cfg(feature = "NSEnumerator")]
 use super::iter;
 use super::util;
-use crate::Foundation::{NSMutableSet, NSSet};
+use crate::Foundation::{NSCountedSet, NSMutableSet, NSSet};
 
 impl<T: Message> NSSet<T> {
     /// Returns the number of elements in the set.
@@ -435,6 +435,61 @@ impl<T: Message + Eq + Hash> NSMutableSet<T> {
     }
 }
 
+impl<T: Message + Eq + Hash> NSCountedSet<T> {
+    /// Creates an [`NSCountedSet`] from a vector, counting how many times
+    /// each distinct value occurs in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSCountedSet, NSString};
+    ///
+    /// let strs = ["one", "two", "one"].map(NSString::from_str).to_vec();
+    /// let set = NSCountedSet::from_vec(strs);
+    /// assert_eq!(set.len(), 2);
+    /// assert_eq!(set.count_of(ns_string!("one")), 2);
+    /// ```
+    pub fn from_vec(vec: Vec<Id<T>>) -> Id<Self>
+    where
+        T: HasStableHash,
+    {
+        let mut set = Self::new();
+        set.extend(vec);
+        set
+    }
+
+    /// Returns how many times the value has been added to the set, minus
+    /// the number of times it has been removed.
+    ///
+    /// Returns `0` if the set does not contain the value.
+    #[doc(alias = "countForObject:")]
+    pub fn count_of(&self, value: &T) -> usize {
+        unsafe { self.countForObject(value) }
+    }
+
+    /// An iterator visiting all distinct elements in arbitrary order,
+    /// together with their counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{NSCountedSet, NSString};
+    ///
+    /// let strs = ["one", "two", "one"].map(NSString::from_str).to_vec();
+    /// let set = NSCountedSet::from_vec(strs);
+    /// let total: usize = set.iter_counts().map(|(_, count)| count).sum();
+    /// assert_eq!(total, 3);
+    /// ```
+    #[doc(alias = "objectEnumerator")]
+    #[cfg(feature = "NSEnumerator")]
+    pub fn iter_counts(&self) -> CountedIter<'_, T> {
+        CountedIter {
+            set: self,
+            iter: self.iter(),
+        }
+    }
+}
+
 // Iteration is not supposed to touch the elements, not even do comparisons.
 //
 // TODO: Verify that this is actually the case.
@@ -520,6 +575,32 @@ __impl_iter! {
     impl<'a, T: Message> Iterator<Item = Id<T>> for IntoIter<T> { ... }
 }
 
+/// An iterator over the items of a `NSCountedSet` and their counts.
+///
+/// Created with [`NSCountedSet::iter_counts`].
+#[derive(Debug)]
+#[cfg(feature = "NSEnumerator")]
+pub struct CountedIter<'a, T: Message> {
+    set: &'a NSCountedSet<T>,
+    iter: Iter<'a, T>,
+}
+
+#[cfg(feature = "NSEnumerator")]
+impl<'a, T: Message + Eq + Hash> Iterator for CountedIter<'a, T> {
+    type Item = (&'a T, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let obj = self.iter.next()?;
+        Some((obj, self.set.count_of(obj)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 #[cfg(feature = "NSEnumerator")]
 __impl_into_iter! {
     impl<T: Message> IntoIterator for &NSSet<T> {
@@ -556,7 +637,7 @@ impl<T: fmt::Debug + Message> fmt::Debug for NSMutableSet<T> {
 }
 
 #[cfg(feature = "NSEnumerator")]
-impl<T: fmt::Debug + Message> fmt::Debug for crate::Foundation::NSCountedSet<T> {
+impl<T: fmt::Debug + Message> fmt::Debug for NSCountedSet<T> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(&**self, f)
@@ -610,3 +691,10 @@ impl<T: Message + Eq + Hash + HasStableHash> IdFromIterator<Id<T>> for NSMutable
         Self::from_vec(vec)
     }
 }
+
+impl<T: Message + Eq + Hash + HasStableHash> IdFromIterator<Id<T>> for NSCountedSet<T> {
+    fn id_from_iter<I: IntoIterator<Item = Id<T>>>(iter: I) -> Id<Self> {
+        let vec = Vec::from_iter(iter);
+        Self::from_vec(vec)
+    }
+}
@@ -0,0 +1,200 @@
+//! Runtime-agnostic spawning of futures.
+//!
+//! Work that completes asynchronously (such as waiting for a completion
+//! handler) sometimes has to be driven by a task that runs independently of
+//! the caller. This crate does not depend on any specific async runtime;
+//! instead, such work is handed to a [`Spawner`], which can either be
+//! configured globally with [`set_global_spawner`], or passed explicitly.
+use alloc::boxed::Box;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// A boxed future that can be spawned with a [`Spawner`].
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// A handle to an executor that futures can be spawned onto.
+///
+/// Implementations are provided for [Tokio] (with the `"tokio"` feature),
+/// [async-std] (with the `"async-std"` feature) and the main run loop (see
+/// [`executor::RunLoopSpawner`]), and it is easy to implement for other
+/// executors.
+///
+/// [`executor::RunLoopSpawner`]: crate::executor::RunLoopSpawner
+///
+/// [Tokio]: https://docs.rs/tokio
+/// [async-std]: https://docs.rs/async-std
+///
+///
+/// # Example
+///
+/// Implement the trait for a custom executor.
+///
+/// ```
+/// use objc2_foundation::{BoxFuture, Spawner};
+///
+/// struct Detached;
+///
+/// impl Spawner for Detached {
+///     fn spawn_boxed(&self, future: BoxFuture) {
+///         std::thread::spawn(move || {
+///             // Drive `future` to completion with your executor of choice.
+///             # drop(future);
+///         });
+///     }
+/// }
+///
+/// Detached.spawn(async { /* ... */ });
+/// ```
+pub trait Spawner: Send + Sync {
+    /// Spawn the boxed future, running it to completion in the background.
+    ///
+    /// The future should be run even if nobody is waiting for it, and a
+    /// panic in the future should not bring down the executor.
+    fn spawn_boxed(&self, future: BoxFuture);
+
+    /// Spawn the future, running it to completion in the background.
+    fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+        Self: Sized,
+    {
+        self.spawn_boxed(Box::pin(future));
+    }
+}
+
+impl<S: ?Sized + Spawner> Spawner for &S {
+    #[inline]
+    fn spawn_boxed(&self, future: BoxFuture) {
+        (**self).spawn_boxed(future);
+    }
+}
+
+impl<S: ?Sized + Spawner> Spawner for Box<S> {
+    #[inline]
+    fn spawn_boxed(&self, future: BoxFuture) {
+        (**self).spawn_boxed(future);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: ?Sized + Spawner> Spawner for std::sync::Arc<S> {
+    #[inline]
+    fn spawn_boxed(&self, future: BoxFuture) {
+        (**self).spawn_boxed(future);
+    }
+}
+
+/// The global spawner.
+///
+/// This is a leaked `Box<Box<dyn Spawner>>`, since `Box<dyn Spawner>` is a
+/// fat pointer that cannot be stored atomically.
+static GLOBAL_SPAWNER: AtomicPtr<Box<dyn Spawner>> = AtomicPtr::new(ptr::null_mut());
+
+/// Set the spawner used by [`spawn`].
+///
+/// This can only be done once, usually at the start of `main`.
+///
+///
+/// # Errors
+///
+/// If a global spawner has already been set, the given spawner is returned
+/// back in the `Err` variant.
+pub fn set_global_spawner<S: Spawner + 'static>(spawner: S) -> Result<(), S> {
+    let boxed: Box<Box<dyn Spawner>> = Box::new(Box::new(spawner));
+    let new = Box::into_raw(boxed);
+    let res =
+        GLOBAL_SPAWNER.compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire);
+    if res.is_ok() {
+        Ok(())
+    } else {
+        // SAFETY: The pointer was created above, and was not stored.
+        let boxed = unsafe { Box::from_raw(new) };
+        // SAFETY: The inner box was created from an `S` above.
+        let spawner = unsafe { Box::from_raw(Box::into_raw(*boxed).cast::<S>()) };
+        Err(*spawner)
+    }
+}
+
+/// Get the spawner set with [`set_global_spawner`], if any.
+pub fn global_spawner() -> Option<&'static dyn Spawner> {
+    let ptr = GLOBAL_SPAWNER.load(Ordering::Acquire);
+    // SAFETY: The pointer is either NULL, or a leaked box that is never
+    // freed, and hence valid for `'static`.
+    unsafe { ptr.as_ref() }.map(|spawner| &**spawner)
+}
+
+/// Spawn a future onto the global spawner.
+///
+///
+/// # Panics
+///
+/// Panics if no global spawner has been set with [`set_global_spawner`].
+#[track_caller]
+pub fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    match global_spawner() {
+        Some(spawner) => spawner.spawn_boxed(Box::pin(future)),
+        None => panic!("no global spawner set; call `set_global_spawner` first"),
+    }
+}
+
+/// A [`Spawner`] that spawns futures onto a [Tokio] runtime.
+///
+/// [Tokio]: https://docs.rs/tokio
+#[cfg(feature = "tokio")]
+#[derive(Clone, Debug)]
+pub struct TokioSpawner(tokio::runtime::Handle);
+
+#[cfg(feature = "tokio")]
+impl TokioSpawner {
+    /// Spawn futures onto the runtime with the given handle.
+    pub fn new(handle: tokio::runtime::Handle) -> Self {
+        Self(handle)
+    }
+
+    /// Spawn futures onto the runtime that is currently running.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a Tokio runtime, see
+    /// [`Handle::current`](tokio::runtime::Handle::current).
+    #[track_caller]
+    pub fn current() -> Self {
+        Self(tokio::runtime::Handle::current())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Spawner for TokioSpawner {
+    fn spawn_boxed(&self, future: BoxFuture) {
+        // Dropping the `JoinHandle` detaches the task.
+        drop(self.0.spawn(future));
+    }
+}
+
+/// A [`Spawner`] that spawns futures onto the global [async-std] executor.
+///
+/// [async-std]: https://docs.rs/async-std
+#[cfg(feature = "async-std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStdSpawner;
+
+#[cfg(feature = "async-std")]
+impl Spawner for AsyncStdSpawner {
+    fn spawn_boxed(&self, future: BoxFuture) {
+        // Dropping the `JoinHandle` detaches the task.
+        drop(async_std::task::spawn(future));
+    }
+}
+
+impl fmt::Debug for dyn Spawner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Spawner").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,92 @@
+use core::ptr::NonNull;
+use std::io;
+
+use crate::Foundation::{NSInputStream, NSOutputStream, NSStream};
+
+/// Reads with `read:maxLength:`.
+///
+/// The stream must have been opened with `open` beforehand. This reads at
+/// most `buf.len()` bytes at a time, so large streams (e.g. of a file or an
+/// HTTP body) can be processed in chunks without holding all of their
+/// contents in memory.
+impl io::Read for &NSInputStream {
+    #[doc(alias = "read:maxLength:")]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let ptr = NonNull::new(buf.as_mut_ptr()).unwrap();
+        // SAFETY: The pointer is valid for writes of `buf.len()` bytes.
+        let res = unsafe { self.read_maxLength(ptr, buf.len()) };
+        if res < 0 {
+            Err(stream_error(self))
+        } else {
+            // A return value of zero means that the end was reached.
+            Ok(res as usize)
+        }
+    }
+}
+
+/// Reads with `read:maxLength:`, see the implementation on
+/// `&NSInputStream`.
+impl io::Read for NSInputStream {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+/// Writes with `write:maxLength:`.
+///
+/// The stream must have been opened with `open` beforehand. Since the
+/// stream may accept fewer bytes than given (e.g. if it has a fixed
+/// capacity), you will usually want to use [`io::Write::write_all`].
+impl io::Write for &NSOutputStream {
+    #[doc(alias = "write:maxLength:")]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let ptr = NonNull::new(buf.as_ptr() as *mut u8).unwrap();
+        // SAFETY: The pointer is valid for reads of `buf.len()` bytes.
+        let res = unsafe { self.write_maxLength(ptr, buf.len()) };
+        if res < 0 {
+            Err(stream_error(self))
+        } else {
+            // A return value of zero means that the capacity was reached,
+            // which `write_all` turns into `ErrorKind::WriteZero`.
+            Ok(res as usize)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // `NSOutputStream` has no buffering of its own.
+        Ok(())
+    }
+}
+
+/// Writes with `write:maxLength:`, see the implementation on
+/// `&NSOutputStream`.
+impl io::Write for NSOutputStream {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self).flush()
+    }
+}
+
+#[cfg_attr(
+    not(all(feature = "NSError", feature = "NSString")),
+    allow(unused_variables)
+)]
+fn stream_error(stream: &NSStream) -> io::Error {
+    #[cfg(all(feature = "NSError", feature = "NSString"))]
+    if let Some(error) = unsafe { stream.streamError() } {
+        return io::Error::new(io::ErrorKind::Other, error.to_string());
+    }
+    io::Error::new(io::ErrorKind::Other, "NSStream operation failed")
+}
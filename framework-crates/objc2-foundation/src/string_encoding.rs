@@ -0,0 +1,259 @@
+//! Conversions between `NSString` and bytes in other encodings than UTF-8.
+#[cfg(feature = "NSData")]
+use alloc::vec::Vec;
+use core::fmt;
+use std::os::raw::c_void;
+
+use objc2::rc::Id;
+use objc2::{msg_send, msg_send_id, ClassType};
+
+use crate::Foundation::NSString;
+
+/// The type of `NSStringEncoding` in messages.
+///
+/// The generated methods assume `usize`, whereas GNUStep uses `i32`, so we
+/// send the messages ourselves (like `NSString::from_str` does).
+#[cfg(not(feature = "gnustep-1-7"))]
+type RawEncoding = usize;
+#[cfg(feature = "gnustep-1-7")]
+type RawEncoding = i32;
+
+/// A character encoding that strings can be converted to and from.
+///
+/// This wraps an `NSStringEncoding` value. The common encodings are
+/// available as associated constants, and others can be created with
+/// [`StringEncoding::from_raw`].
+///
+/// The `UTF16` and `UTF32` encodings write a byte order mark in the host's
+/// byte order when encoding, and use the byte order mark (or big-endian, if
+/// there is none) when decoding. The encodings with an explicit byte order
+/// neither write nor interpret byte order marks; see
+/// [`StringEncoding::from_bom`] for detecting them.
+#[doc(alias = "NSStringEncoding")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StringEncoding(usize);
+
+impl StringEncoding {
+    #[doc(alias = "NSASCIIStringEncoding")]
+    pub const ASCII: Self = Self(1);
+    #[doc(alias = "NSNEXTSTEPStringEncoding")]
+    pub const NEXTSTEP: Self = Self(2);
+    #[doc(alias = "NSJapaneseEUCStringEncoding")]
+    pub const JAPANESE_EUC: Self = Self(3);
+    #[doc(alias = "NSUTF8StringEncoding")]
+    pub const UTF8: Self = Self(4);
+    #[doc(alias = "NSISOLatin1StringEncoding")]
+    pub const ISO_LATIN_1: Self = Self(5);
+    #[doc(alias = "NSSymbolStringEncoding")]
+    pub const SYMBOL: Self = Self(6);
+    #[doc(alias = "NSNonLossyASCIIStringEncoding")]
+    pub const NON_LOSSY_ASCII: Self = Self(7);
+    #[doc(alias = "NSShiftJISStringEncoding")]
+    pub const SHIFT_JIS: Self = Self(8);
+    #[doc(alias = "NSISOLatin2StringEncoding")]
+    pub const ISO_LATIN_2: Self = Self(9);
+    #[doc(alias = "NSUnicodeStringEncoding")]
+    #[doc(alias = "NSUTF16StringEncoding")]
+    pub const UTF16: Self = Self(10);
+    #[doc(alias = "NSWindowsCP1251StringEncoding")]
+    pub const WINDOWS_CP1251: Self = Self(11);
+    #[doc(alias = "NSWindowsCP1252StringEncoding")]
+    pub const WINDOWS_CP1252: Self = Self(12);
+    #[doc(alias = "NSWindowsCP1253StringEncoding")]
+    pub const WINDOWS_CP1253: Self = Self(13);
+    #[doc(alias = "NSWindowsCP1254StringEncoding")]
+    pub const WINDOWS_CP1254: Self = Self(14);
+    #[doc(alias = "NSWindowsCP1250StringEncoding")]
+    pub const WINDOWS_CP1250: Self = Self(15);
+    #[doc(alias = "NSISO2022JPStringEncoding")]
+    pub const ISO_2022_JP: Self = Self(21);
+    #[doc(alias = "NSMacOSRomanStringEncoding")]
+    pub const MAC_OS_ROMAN: Self = Self(30);
+    #[doc(alias = "NSUTF16BigEndianStringEncoding")]
+    pub const UTF16_BE: Self = Self(0x90000100);
+    #[doc(alias = "NSUTF16LittleEndianStringEncoding")]
+    pub const UTF16_LE: Self = Self(0x94000100);
+    #[doc(alias = "NSUTF32StringEncoding")]
+    pub const UTF32: Self = Self(0x8c000100);
+    #[doc(alias = "NSUTF32BigEndianStringEncoding")]
+    pub const UTF32_BE: Self = Self(0x98000100);
+    #[doc(alias = "NSUTF32LittleEndianStringEncoding")]
+    pub const UTF32_LE: Self = Self(0x9c000100);
+
+    /// Create an encoding from an `NSStringEncoding` value.
+    #[inline]
+    pub const fn from_raw(raw: usize) -> Self {
+        Self(raw)
+    }
+
+    /// The `NSStringEncoding` value.
+    #[inline]
+    pub const fn raw(self) -> usize {
+        self.0
+    }
+
+    #[inline]
+    fn as_msg(self) -> RawEncoding {
+        // Values above `i32::MAX` keep their bit pattern on GNUStep.
+        self.0 as RawEncoding
+    }
+
+    /// Detect the Unicode encoding from the byte order mark at the start of
+    /// the bytes.
+    ///
+    /// Returns the encoding with an explicit byte order, and the length of
+    /// the byte order mark, which should be skipped when decoding with the
+    /// returned encoding.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{NSString, StringEncoding};
+    ///
+    /// let bytes = [0xFF, 0xFE, b'h', 0, b'i', 0];
+    /// let (encoding, bom_len) = StringEncoding::from_bom(&bytes).unwrap();
+    /// assert_eq!(encoding, StringEncoding::UTF16_LE);
+    /// let string = NSString::from_bytes(&bytes[bom_len..], encoding).unwrap();
+    /// assert_eq!(string.to_string(), "hi");
+    /// ```
+    pub fn from_bom(bytes: &[u8]) -> Option<(Self, usize)> {
+        // UTF-32 LE must be checked before UTF-16 LE, since it starts with
+        // the same bytes.
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => Some((Self::UTF8, 3)),
+            [0x00, 0x00, 0xFE, 0xFF, ..] => Some((Self::UTF32_BE, 4)),
+            [0xFF, 0xFE, 0x00, 0x00, ..] => Some((Self::UTF32_LE, 4)),
+            [0xFE, 0xFF, ..] => Some((Self::UTF16_BE, 2)),
+            [0xFF, 0xFE, ..] => Some((Self::UTF16_LE, 2)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for StringEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match *self {
+            Self::ASCII => "ASCII",
+            Self::NEXTSTEP => "NEXTSTEP",
+            Self::JAPANESE_EUC => "JAPANESE_EUC",
+            Self::UTF8 => "UTF8",
+            Self::ISO_LATIN_1 => "ISO_LATIN_1",
+            Self::SYMBOL => "SYMBOL",
+            Self::NON_LOSSY_ASCII => "NON_LOSSY_ASCII",
+            Self::SHIFT_JIS => "SHIFT_JIS",
+            Self::ISO_LATIN_2 => "ISO_LATIN_2",
+            Self::UTF16 => "UTF16",
+            Self::WINDOWS_CP1251 => "WINDOWS_CP1251",
+            Self::WINDOWS_CP1252 => "WINDOWS_CP1252",
+            Self::WINDOWS_CP1253 => "WINDOWS_CP1253",
+            Self::WINDOWS_CP1254 => "WINDOWS_CP1254",
+            Self::WINDOWS_CP1250 => "WINDOWS_CP1250",
+            Self::ISO_2022_JP => "ISO_2022_JP",
+            Self::MAC_OS_ROMAN => "MAC_OS_ROMAN",
+            Self::UTF16_BE => "UTF16_BE",
+            Self::UTF16_LE => "UTF16_LE",
+            Self::UTF32 => "UTF32",
+            Self::UTF32_BE => "UTF32_BE",
+            Self::UTF32_LE => "UTF32_LE",
+            _ => return f.debug_tuple("StringEncoding").field(&self.0).finish(),
+        };
+        write!(f, "StringEncoding::{name}")
+    }
+}
+
+impl NSString {
+    /// Decode a string from bytes in the given encoding.
+    ///
+    /// Returns `None` if the bytes are not valid in the encoding, or if the
+    /// encoding is not supported.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{NSString, StringEncoding};
+    ///
+    /// let string = NSString::from_bytes(b"caf\xE9", StringEncoding::ISO_LATIN_1).unwrap();
+    /// assert_eq!(string.to_string(), "café");
+    /// assert!(NSString::from_bytes(b"caf\xE9", StringEncoding::UTF8).is_none());
+    /// ```
+    #[doc(alias = "initWithBytes:length:encoding:")]
+    pub fn from_bytes(bytes: &[u8], encoding: StringEncoding) -> Option<Id<Self>> {
+        let ptr: *const c_void = bytes.as_ptr().cast();
+        // SAFETY: The pointer is valid for `bytes.len()` bytes, and the
+        // method returns `nil` on invalid input.
+        unsafe {
+            msg_send_id![
+                Self::alloc(),
+                initWithBytes: ptr,
+                length: bytes.len(),
+                encoding: encoding.as_msg(),
+            ]
+        }
+    }
+
+    /// Whether the string can be encoded in the given encoding without
+    /// losing information.
+    #[doc(alias = "canBeConvertedToEncoding:")]
+    pub fn can_encode(&self, encoding: StringEncoding) -> bool {
+        // SAFETY: Any encoding value is accepted.
+        unsafe { msg_send![self, canBeConvertedToEncoding: encoding.as_msg()] }
+    }
+
+    /// Encode the string in the given encoding.
+    ///
+    /// Returns `None` if the string contains characters that the encoding
+    /// cannot represent, see [`encode_lossy`](Self::encode_lossy).
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{NSString, StringEncoding};
+    ///
+    /// let string = NSString::from_str("hi");
+    /// let bytes = string.encode(StringEncoding::UTF16_BE).unwrap();
+    /// assert_eq!(bytes, [0, b'h', 0, b'i']);
+    /// ```
+    #[doc(alias = "dataUsingEncoding:")]
+    #[cfg(feature = "NSData")]
+    pub fn encode(&self, encoding: StringEncoding) -> Option<Vec<u8>> {
+        self.encode_inner(encoding, false)
+    }
+
+    /// Encode the string in the given encoding, replacing or dropping the
+    /// characters that the encoding cannot represent.
+    ///
+    /// Returns `None` if the encoding is not supported.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{NSString, StringEncoding};
+    ///
+    /// let string = NSString::from_str("café");
+    /// assert!(string.encode(StringEncoding::ASCII).is_none());
+    /// let bytes = string.encode_lossy(StringEncoding::ASCII).unwrap();
+    /// assert!(bytes.starts_with(b"caf"));
+    /// ```
+    #[doc(alias = "dataUsingEncoding:allowLossyConversion:")]
+    #[cfg(feature = "NSData")]
+    pub fn encode_lossy(&self, encoding: StringEncoding) -> Option<Vec<u8>> {
+        self.encode_inner(encoding, true)
+    }
+
+    #[cfg(feature = "NSData")]
+    fn encode_inner(&self, encoding: StringEncoding, lossy: bool) -> Option<Vec<u8>> {
+        // SAFETY: The method returns `nil` if the conversion failed.
+        let data: Option<Id<crate::Foundation::NSData>> = unsafe {
+            msg_send_id![
+                self,
+                dataUsingEncoding: encoding.as_msg(),
+                allowLossyConversion: lossy,
+            ]
+        };
+        data.map(|data| data.bytes().to_vec())
+    }
+}
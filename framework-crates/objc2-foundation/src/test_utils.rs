@@ -0,0 +1,198 @@
+//! Utilities for testing code that must run on the main thread.
+//!
+//! Testing code that uses AppKit, UIKit or other main-thread-only APIs is
+//! difficult, since Rust's default test harness runs each test on a separate
+//! worker thread, and keeps the main thread busy while doing so.
+//!
+//! To work around this, mark such tests with the [`main_thread_test`]
+//! attribute, and run them from a test target that uses `harness = false`
+//! and calls [`main_thread_test_harness`] in its `main` function. The
+//! harness runs your tests on a background thread, while keeping the main
+//! thread free to execute the test bodies.
+//!
+//! Each test body is run inside an autorelease pool, and if the
+//! `run_loop_ms` argument is given, the current run loop is then run for
+//! that many milliseconds, to give timers, notifications and other
+//! asynchronous work a chance to complete.
+//!
+//! When used with the default test harness, the annotated functions are
+//! registered as normal tests, but will panic unless they happen to be run
+//! on the main thread.
+//!
+//!
+//! # Example
+//!
+//! In `Cargo.toml`:
+//!
+//! ```toml
+//! [[test]]
+//! name = "main_thread"
+//! harness = false
+//! ```
+//!
+//! And in `tests/main_thread.rs`:
+//!
+//! ```no_run
+//! use objc2_foundation::test_utils::{main_thread_test, main_thread_test_harness};
+//! use objc2_foundation::MainThreadMarker;
+//!
+//! #[main_thread_test]
+//! fn runs_on_main() {
+//!     assert!(objc2_foundation::is_main_thread());
+//! }
+//!
+//! #[main_thread_test(run_loop_ms = 100)]
+//! fn with_marker(mtm: MainThreadMarker) {
+//!     // Use `mtm` to create main-thread-only objects here.
+//!     let _ = mtm;
+//! }
+//!
+//! fn main() {
+//!     main_thread_test_harness(|| {
+//!         runs_on_main();
+//!         with_marker();
+//!     })
+//! }
+//! ```
+
+use std::boxed::Box;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use objc2::rc::autoreleasepool;
+
+use crate::{MainThreadMarker, NSDate, NSRunLoop};
+
+#[doc(inline)]
+pub use objc2_proc_macros::main_thread_test;
+
+enum Message {
+    Run(Box<dyn FnOnce() + Send>),
+    Exit(i32),
+}
+
+/// The channel to the main thread, set by `main_thread_test_harness`.
+///
+/// This is leaked, since the harness never returns.
+static MAIN_THREAD: AtomicPtr<Mutex<mpsc::Sender<Message>>> = AtomicPtr::new(ptr::null_mut());
+
+/// Run the given tests on a background thread, while executing the bodies of
+/// [`main_thread_test`] functions on the main thread.
+///
+/// The process exits once `tests` returns, with a non-zero exit code if it
+/// panicked.
+///
+/// See [the module docs][self] for details.
+///
+///
+/// # Panics
+///
+/// Panics if not called on the main thread, or if called more than once.
+pub fn main_thread_test_harness<F>(tests: F) -> !
+where
+    F: FnOnce() + Send + 'static,
+{
+    let _mtm = MainThreadMarker::new().expect("must run the test harness on the main thread");
+
+    let (sender, receiver) = mpsc::channel();
+    let exit_sender = sender.clone();
+    let sender = Box::into_raw(Box::new(Mutex::new(sender)));
+    if MAIN_THREAD
+        .compare_exchange(ptr::null_mut(), sender, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        panic!("the test harness was already started");
+    }
+
+    thread::Builder::new()
+        .name("test harness".into())
+        .spawn(move || {
+            let code = match panic::catch_unwind(AssertUnwindSafe(tests)) {
+                Ok(()) => 0,
+                // Same exit code as the default test harness
+                Err(_) => 101,
+            };
+            let _ = exit_sender.send(Message::Exit(code));
+        })
+        .expect("failed spawning test harness thread");
+
+    loop {
+        match receiver.recv() {
+            Ok(Message::Run(job)) => job(),
+            Ok(Message::Exit(code)) => process::exit(code),
+            // Unreachable, since we hold a sender in `MAIN_THREAD`.
+            Err(_) => process::exit(101),
+        }
+    }
+}
+
+fn run_test<F>(mtm: MainThreadMarker, run_loop: Option<Duration>, f: F)
+where
+    F: FnOnce(MainThreadMarker),
+{
+    autoreleasepool(|_| f(mtm));
+
+    if let Some(duration) = run_loop {
+        autoreleasepool(|_| {
+            // SAFETY: Creating a date and running the current run loop until
+            // then is safe.
+            let date = unsafe { NSDate::dateWithTimeIntervalSinceNow(duration.as_secs_f64()) };
+            unsafe { NSRunLoop::currentRunLoop().runUntilDate(&date) };
+        });
+    }
+}
+
+/// Used by the `#[main_thread_test]` attribute.
+///
+///
+/// # Panics
+///
+/// Panics if the test panics, or if not on the main thread and the harness
+/// is not running.
+#[doc(hidden)]
+#[track_caller]
+pub fn __main_thread_test<F>(run_loop: Option<Duration>, f: F)
+where
+    F: FnOnce(MainThreadMarker) + Send + 'static,
+{
+    if let Some(mtm) = MainThreadMarker::new() {
+        run_test(mtm, run_loop, f);
+        return;
+    }
+
+    let sender = MAIN_THREAD.load(Ordering::Acquire);
+    // SAFETY: The pointer is either NULL, or a leaked `Box`.
+    let sender = match unsafe { sender.as_ref() } {
+        Some(sender) => sender,
+        None => panic!(
+            "`#[main_thread_test]` requires the main thread, which the default \
+             test harness does not make available; use `harness = false` and \
+             `main_thread_test_harness` instead"
+        ),
+    };
+
+    let (result_sender, result_receiver) = mpsc::channel();
+    let job = move || {
+        // SAFETY: Jobs are only run by the harness on the main thread.
+        let mtm = unsafe { MainThreadMarker::new_unchecked() };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run_test(mtm, run_loop, f)));
+        let _ = result_sender.send(result);
+    };
+    sender
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .send(Message::Run(Box::new(job)))
+        .expect("test harness exited");
+
+    match result_receiver.recv() {
+        Ok(Ok(())) => {}
+        // Propagate the panic to the test thread, for `#[should_panic]`.
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Err(_) => panic!("test harness exited"),
+    }
+}
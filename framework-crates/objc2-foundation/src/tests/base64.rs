@@ -0,0 +1,112 @@
+#![cfg(feature = "std")]
+#![cfg(feature = "NSData")]
+use alloc::vec::Vec;
+use std::io::{Read, Write};
+
+use crate::base64::{Decoder, Encoder};
+use crate::Foundation::{NSData, NSDataBase64DecodingOptions, NSDataBase64EncodingOptions};
+
+fn encode(input: &[u8], options: NSDataBase64EncodingOptions) -> Vec<u8> {
+    let mut encoder = Encoder::new(Vec::new(), options);
+    // Write in odd-sized pieces, to exercise the buffering.
+    for piece in input.chunks(1000) {
+        encoder.write_all(piece).unwrap();
+    }
+    encoder.finish().unwrap()
+}
+
+fn decode(input: &[u8]) -> Vec<u8> {
+    let mut decoder = Decoder::new(input, NSDataBase64DecodingOptions::empty());
+    let mut output = Vec::new();
+    decoder.read_to_end(&mut output).unwrap();
+    output
+}
+
+#[test]
+fn test_matches_foundation() {
+    let input: Vec<u8> = (0..200_000u32).map(|i| (i * 7 + i / 13) as u8).collect();
+    let data = NSData::with_bytes(&input);
+    for options in [
+        NSDataBase64EncodingOptions::empty(),
+        NSDataBase64EncodingOptions::NSDataBase64Encoding64CharacterLineLength,
+        NSDataBase64EncodingOptions::NSDataBase64Encoding76CharacterLineLength
+            | NSDataBase64EncodingOptions::NSDataBase64EncodingEndLineWithLineFeed,
+    ] {
+        let expected = unsafe { data.base64EncodedDataWithOptions(options) };
+        let encoded = encode(&input, options);
+        assert_eq!(encoded, expected.bytes());
+        assert_eq!(decode(&encoded), input);
+    }
+}
+
+/// A writer that fails the first few times it is written to.
+struct FlakyWriter {
+    failures: usize,
+    output: Vec<u8>,
+}
+
+impl Write for FlakyWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.failures > 0 {
+            self.failures -= 1;
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, "flaky"));
+        }
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_write_errors_do_not_lose_data() {
+    let input: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+    let writer = FlakyWriter {
+        failures: 2,
+        output: Vec::new(),
+    };
+    let mut encoder = Encoder::new(writer, NSDataBase64EncodingOptions::empty());
+    let mut remaining = &input[..];
+    let mut errors = 0;
+    while !remaining.is_empty() {
+        match encoder.write(remaining) {
+            Ok(n) => remaining = &remaining[n..],
+            Err(_) => errors += 1,
+        }
+    }
+    assert_eq!(errors, 1);
+    let writer = encoder.finish().unwrap();
+
+    let data = NSData::with_bytes(&input);
+    let expected =
+        unsafe { data.base64EncodedDataWithOptions(NSDataBase64EncodingOptions::empty()) };
+    assert_eq!(writer.output, expected.bytes());
+}
+
+#[test]
+fn test_small() {
+    for input in [&b""[..], b"a", b"ab", b"abc", b"abcd"] {
+        let encoded = encode(input, NSDataBase64EncodingOptions::empty());
+        assert_eq!(decode(&encoded), input);
+    }
+    assert_eq!(
+        encode(b"hello", NSDataBase64EncodingOptions::empty()),
+        b"aGVsbG8="
+    );
+}
+
+#[test]
+fn test_invalid() {
+    let mut decoder = Decoder::new(&b"aGVs*bG8="[..], NSDataBase64DecodingOptions::empty());
+    assert!(decoder.read_to_end(&mut Vec::new()).is_err());
+
+    let mut decoder = Decoder::new(
+        &b"aGVs*bG8="[..],
+        NSDataBase64DecodingOptions::NSDataBase64DecodingIgnoreUnknownCharacters,
+    );
+    let mut output = Vec::new();
+    decoder.read_to_end(&mut output).unwrap();
+    assert_eq!(output, b"hello");
+}
@@ -1,6 +1,9 @@
 #![cfg(feature = "NSString")]
+use alloc::string::ToString;
+
 use objc2::{rc::Id, runtime::ProtocolObject};
-use objc2_foundation::{NSCopying, NSMutableCopying, NSString};
+
+use crate::Foundation::{NSCopying, NSMutableCopying, NSMutableString, NSString};
 
 #[test]
 fn copy() {
@@ -15,3 +18,32 @@ fn copy_mutable() {
     let protocol_object: &ProtocolObject<dyn NSMutableCopying> = ProtocolObject::from_ref(&*obj);
     let _: Id<ProtocolObject<dyn NSMutableCopying>> = protocol_object.mutableCopy();
 }
+
+#[test]
+fn copy_typed() {
+    let obj = NSString::from_str("abc");
+
+    // Copies come back as the immutable or mutable counterpart.
+    let copied: Id<NSString> = obj.copy();
+    let mut mutable: Id<NSMutableString> = obj.mutableCopy();
+    *mutable += &NSString::from_str("def");
+    let copied_mutable: Id<NSString> = mutable.copy();
+    let mutable_again: Id<NSMutableString> = mutable.mutableCopy();
+
+    assert_eq!(copied.to_string(), "abc");
+    assert_eq!(copied_mutable.to_string(), "abcdef");
+    assert_eq!(mutable_again.to_string(), "abcdef");
+}
+
+#[test]
+#[cfg(feature = "NSData")]
+fn copy_typed_data() {
+    use crate::Foundation::{NSData, NSMutableData};
+
+    let data = NSData::with_bytes(&[1, 2]);
+    let mut mutable: Id<NSMutableData> = data.mutableCopy();
+    mutable.push(3);
+    let copied: Id<NSData> = mutable.copy();
+    assert_eq!(copied.bytes(), [1, 2, 3]);
+    assert_eq!(data.bytes(), [1, 2]);
+}
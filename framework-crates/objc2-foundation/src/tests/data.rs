@@ -41,3 +41,95 @@ fn test_collect() {
     let data: objc2::rc::Id<NSData> = bytes.into_iter().collect();
     assert_eq!(format!("{data:?}"), "[3, 7, 16, 52, 112, 19]");
 }
+
+#[cfg(all(feature = "NSURL", feature = "NSError", feature = "NSString"))]
+#[cfg(feature = "std")]
+#[test]
+fn test_map_file() {
+    use crate::Foundation::{NSDataReadingOptions, NSString, NSURL};
+
+    let path = std::env::temp_dir().join("objc2-foundation-test-map-file");
+    let bytes: alloc::vec::Vec<u8> = (0..=255).cycle().take(100_000).collect();
+    std::fs::write(&path, &bytes).unwrap();
+
+    let url = unsafe { NSURL::fileURLWithPath(&NSString::from_str(path.to_str().unwrap())) };
+    let data = unsafe { NSData::map_file(&url, NSDataReadingOptions(0)) }.unwrap();
+    assert_eq!(data.as_slice(), &*bytes);
+    assert_eq!(data.into_data().len(), bytes.len());
+
+    std::fs::remove_file(&path).unwrap();
+
+    let res = unsafe { NSData::map_file(&url, NSDataReadingOptions(0)) };
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_to_ns_data() {
+    use crate::Foundation::{NSMutableData, ToNSData};
+
+    assert_eq!(b"abc".to_ns_data().bytes(), b"abc");
+    assert_eq!([1u8, 2][..].to_ns_data().bytes(), [1, 2]);
+    assert_eq!(vec![1u8, 2, 3].to_ns_data().bytes(), [1, 2, 3]);
+
+    // Immutable data is just retained.
+    let data = NSData::with_bytes(&[4, 5]);
+    let converted = data.to_ns_data();
+    assert_eq!(converted, data);
+    assert!(core::ptr::eq(&*converted, &*data));
+
+    // Mutable data is copied, so that later modifications are not visible.
+    let mut data = NSMutableData::with_bytes(&[6]);
+    let converted = data.to_ns_data();
+    data.push(7);
+    assert_eq!(converted.bytes(), [6]);
+    assert_eq!(data.bytes(), [6, 7]);
+}
+
+#[test]
+#[cfg(all(feature = "NSError", feature = "NSString"))]
+fn test_compression() {
+    use crate::data::CompressionError;
+    use crate::Foundation::{NSDataCompressionAlgorithm, NSMutableData};
+
+    let data = NSData::with_bytes(&[42; 4096]);
+    if !NSData::compression_available() {
+        assert_eq!(
+            data.compressed(NSDataCompressionAlgorithm::LZFSE),
+            Err(CompressionError::Unavailable)
+        );
+        return;
+    }
+
+    for algorithm in [
+        NSDataCompressionAlgorithm::LZFSE,
+        NSDataCompressionAlgorithm::LZ4,
+        NSDataCompressionAlgorithm::LZMA,
+        NSDataCompressionAlgorithm::Zlib,
+    ] {
+        let compressed = data.compressed(algorithm).unwrap();
+        assert!(compressed.len() < data.len());
+        let decompressed = compressed.decompressed(algorithm).unwrap();
+        assert_eq!(decompressed.bytes(), data.bytes());
+    }
+
+    let invalid = NSData::with_bytes(&[1, 2, 3]);
+    assert!(matches!(
+        invalid.decompressed(NSDataCompressionAlgorithm::Zlib),
+        Err(CompressionError::Failed(_))
+    ));
+
+    let mut data = NSMutableData::with_bytes(&[42; 4096]);
+    data.compress(NSDataCompressionAlgorithm::Zlib).unwrap();
+    assert!(data.len() < 4096);
+    data.decompress(NSDataCompressionAlgorithm::Zlib).unwrap();
+    assert_eq!(data.bytes(), [42; 4096]);
+}
+
+#[test]
+#[cfg(all(feature = "block2", feature = "NSRange"))]
+fn test_for_each_region() {
+    let data = NSData::with_bytes(b"hello world");
+    let mut collected = vec![];
+    data.for_each_region(|bytes| collected.extend_from_slice(bytes));
+    assert_eq!(collected, b"hello world");
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "NSDecimal")]
+#![cfg(feature = "NSDecimalNumber")]
+#![cfg(feature = "NSString")]
+#![cfg(feature = "NSValue")]
+use alloc::string::ToString;
+
+use crate::Foundation::{DecimalBehavior, NSDecimalNumber, NSRoundingMode};
+
+#[test]
+fn test_parse() {
+    let number = NSDecimalNumber::from_str("-12.50").unwrap();
+    assert_eq!(number.to_string(), "-12.5");
+    assert_eq!(*number, *NSDecimalNumber::from_parts(125, -1, true));
+    assert!(NSDecimalNumber::from_str("not a number").is_none());
+}
+
+#[test]
+fn test_arithmetic() {
+    let a = NSDecimalNumber::from_str("0.1").unwrap();
+    let b = NSDecimalNumber::from_str("0.2").unwrap();
+    assert_eq!((&*a + &*b).to_string(), "0.3");
+    assert_eq!((&*b - &*a).to_string(), "0.1");
+    assert_eq!((&*a * &*b).to_string(), "0.02");
+    assert_eq!((&*b / &*a).to_string(), "2");
+}
+
+#[test]
+fn test_divide_by_zero() {
+    let one = NSDecimalNumber::from_parts(1, 0, false);
+    let zero = NSDecimalNumber::from_parts(0, 0, false);
+    assert!((&*one / &*zero).is_nan());
+    assert_eq!(one.checked_div(&zero), None);
+}
+
+#[test]
+fn test_rounding() {
+    let number = NSDecimalNumber::from_str("2.345").unwrap();
+    let bankers = DecimalBehavior::new(NSRoundingMode::NSRoundBankers, Some(2));
+    assert_eq!(number.round_with(&bankers).to_string(), "2.34");
+    let up = DecimalBehavior::new(NSRoundingMode::NSRoundUp, Some(1));
+    assert_eq!(number.round_with(&up).to_string(), "2.4");
+    let hundreds = DecimalBehavior::new(NSRoundingMode::NSRoundPlain, Some(-2));
+    let number = NSDecimalNumber::from_str("1250").unwrap();
+    assert_eq!(number.round_with(&hundreds).to_string(), "1300");
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "NSEnumerator")]
+#![cfg(feature = "NSArray")]
+#![cfg(feature = "NSValue")]
+use alloc::vec::Vec;
+
+use crate::Foundation::{NSArray, NSNumber};
+
+fn sample_array() -> objc2::rc::Id<NSArray<NSNumber>> {
+    NSArray::from_vec((0..4).map(NSNumber::new_i32).collect())
+}
+
+#[test]
+fn test_into_iter() {
+    let array = sample_array();
+    let enumerator = unsafe { array.objectEnumerator() };
+    let mut iter = enumerator.into_iter();
+    assert_eq!(iter.size_hint(), (0, None));
+
+    let values: Vec<i32> = iter.by_ref().map(|n| n.as_i32()).collect();
+    assert_eq!(values, [0, 1, 2, 3]);
+    assert!(iter.next().is_none());
+}
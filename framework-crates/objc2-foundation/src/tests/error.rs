@@ -2,7 +2,7 @@
 #![cfg(feature = "NSString")]
 use alloc::format;
 
-use crate::Foundation::{ns_string, NSCocoaErrorDomain, NSError};
+use crate::Foundation::{ns_string, NSCocoaErrorCode, NSCocoaErrorDomain, NSError};
 
 #[test]
 fn basic() {
@@ -27,3 +27,38 @@ fn custom_domain() {
     };
     assert_eq!(format!("{error}"), expected);
 }
+
+#[test]
+fn code_as() {
+    let error = NSError::new(260, unsafe { NSCocoaErrorDomain });
+    assert_eq!(
+        error.code_as::<NSCocoaErrorCode>(),
+        Some(NSCocoaErrorCode::FileReadNoSuchFile)
+    );
+
+    let error = NSError::new(-12345, unsafe { NSCocoaErrorDomain });
+    assert_eq!(
+        error.code_as::<NSCocoaErrorCode>(),
+        Some(NSCocoaErrorCode::Other(-12345))
+    );
+
+    let error = NSError::new(260, ns_string!("MyDomain"));
+    assert_eq!(error.code_as::<NSCocoaErrorCode>(), None);
+}
+
+#[test]
+#[cfg(feature = "NSURLError")]
+fn url_code_as() {
+    use crate::Foundation::{NSURLErrorCode, NSURLErrorDomain};
+
+    let error = NSError::new(-1001, unsafe { NSURLErrorDomain });
+    assert_eq!(
+        error.code_as::<NSURLErrorCode>(),
+        Some(NSURLErrorCode::TimedOut)
+    );
+    assert_eq!(
+        NSError::new(-1, unsafe { NSURLErrorDomain }).code_as::<NSURLErrorCode>(),
+        Some(NSURLErrorCode::Unknown)
+    );
+    assert_eq!(error.code_as::<NSCocoaErrorCode>(), None);
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "futures-core")]
+use core::pin::Pin;
+use core::ptr;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use futures_core::Stream;
+
+use crate::{event_channel, BufferPolicy};
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) }
+}
+
+fn buffered<S: Stream + Unpin>(stream: &mut S) -> usize {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut count = 0;
+    while let Poll::Ready(item) = Pin::new(&mut *stream).poll_next(&mut cx) {
+        assert!(item.is_some());
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn channel() {
+    let (sender, mut stream) = event_channel(BufferPolicy::DropNewest(2));
+    let sender2 = sender.clone();
+    sender.send(1);
+    sender2.send(2);
+    sender.send(3);
+    assert_eq!(stream.buffered(), 2);
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(
+        Pin::new(&mut stream).poll_next(&mut cx),
+        Poll::Ready(Some(1))
+    );
+
+    // The stream ends once all senders are dropped and it is drained.
+    drop(sender);
+    drop(sender2);
+    assert_eq!(
+        Pin::new(&mut stream).poll_next(&mut cx),
+        Poll::Ready(Some(2))
+    );
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+#[cfg(all(
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+fn notification_stream() {
+    use crate::Foundation::{NSNotificationCenter, NSString};
+
+    let center = unsafe { NSNotificationCenter::defaultCenter() };
+    let name = NSString::from_str("TestNotificationStream");
+    let post = || unsafe { center.postNotificationName_object(&name, None) };
+
+    let mut unbounded =
+        center.notification_stream(Some(&name), None, BufferPolicy::Unbounded, |_| ());
+    let mut latest =
+        center.notification_stream(Some(&name), None, BufferPolicy::DropOldest(1), |_| ());
+    let mut first =
+        center.notification_stream(Some(&name), None, BufferPolicy::DropNewest(2), |_| ());
+    assert_eq!(buffered(&mut unbounded), 0);
+
+    for _ in 0..3 {
+        post();
+    }
+    assert_eq!(buffered(&mut unbounded), 3);
+    assert_eq!(buffered(&mut latest), 1);
+    assert_eq!(buffered(&mut first), 2);
+
+    // Dropping the stream removes the observer.
+    drop(latest);
+    drop(first);
+    post();
+    assert_eq!(buffered(&mut unbounded), 1);
+}
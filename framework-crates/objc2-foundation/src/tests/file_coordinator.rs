@@ -0,0 +1,35 @@
+#![cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSError",
+    feature = "NSFileCoordinator",
+    feature = "NSURL"
+))]
+#![cfg(unix)]
+#![cfg(not(feature = "gnustep-1-7"))]
+use std::fs;
+
+use crate::Foundation::{
+    NSFileCoordinator, NSFileCoordinatorReadingOptions, NSFileCoordinatorWritingOptions, NSURL,
+};
+
+#[test]
+fn read_write() {
+    let path = std::env::temp_dir().join("objc2-file-coordinator-test.txt");
+    let url = NSURL::from_path(&path, false).unwrap();
+    let coordinator = NSFileCoordinator::new();
+
+    coordinator
+        .coordinate_write(&url, NSFileCoordinatorWritingOptions::empty(), |url| {
+            fs::write(url.to_file_path().unwrap(), "hello").unwrap();
+        })
+        .unwrap();
+    let contents = coordinator
+        .coordinate_read(&url, NSFileCoordinatorReadingOptions::empty(), |url| {
+            fs::read_to_string(url.to_file_path().unwrap()).unwrap()
+        })
+        .unwrap();
+    assert_eq!(contents, "hello");
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "NSArray")]
+#![cfg(feature = "NSData")]
+#![cfg(feature = "NSDate")]
+#![cfg(feature = "NSDictionary")]
+#![cfg(feature = "NSEnumerator")]
+#![cfg(feature = "NSNull")]
+#![cfg(feature = "NSString")]
+#![cfg(feature = "NSValue")]
+use alloc::vec;
+
+use crate::Foundation::{ns_string, FoundationValue, NSArray, NSData, NSNumber, NSObject};
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+use objc2::ClassType;
+
+fn round_trip(value: FoundationValue) {
+    let obj = value.to_object();
+    assert_eq!(FoundationValue::from_object(&obj), value);
+}
+
+#[test]
+fn from_object() {
+    assert_eq!(
+        FoundationValue::from_object(ns_string!("abc")),
+        FoundationValue::String("abc".into()),
+    );
+    assert_eq!(
+        FoundationValue::from_object(&NSNumber::new_i8(-3)),
+        FoundationValue::Integer(-3),
+    );
+    assert_eq!(
+        FoundationValue::from_object(&NSNumber::new_u64(u64::MAX)),
+        FoundationValue::Float(u64::MAX as f64),
+    );
+    assert_eq!(
+        FoundationValue::from_object(&NSData::with_bytes(&[1, 2, 3])),
+        FoundationValue::Data(vec![1, 2, 3]),
+    );
+    assert_eq!(
+        FoundationValue::from_object(&NSArray::from_vec(vec![NSNumber::new_f32(0.5)])),
+        FoundationValue::Array(vec![FoundationValue::Float(0.5)]),
+    );
+}
+
+#[test]
+fn other() {
+    let obj = NSObject::new();
+    let value = FoundationValue::from_object(&obj);
+    match &value {
+        FoundationValue::Other(other) => assert_eq!(other.class(), NSObject::class()),
+        _ => panic!("expected Other, got {value:?}"),
+    }
+    // The object is passed through as-is.
+    let converted: Id<AnyObject> = value.to_object();
+    let obj: &AnyObject = &obj;
+    assert!(core::ptr::eq(&*converted, obj));
+}
+
+#[test]
+fn round_trips() {
+    round_trip(FoundationValue::String("hello".into()));
+    round_trip(FoundationValue::Bool(true));
+    round_trip(FoundationValue::Bool(false));
+    round_trip(FoundationValue::Integer(i64::MIN));
+    round_trip(FoundationValue::Float(1.25));
+    round_trip(FoundationValue::Data(vec![0xde, 0xad]));
+    round_trip(FoundationValue::Date(1_000_000.5));
+    round_trip(FoundationValue::Null);
+    round_trip(FoundationValue::Array(vec![
+        FoundationValue::Integer(1),
+        FoundationValue::Array(vec![FoundationValue::Null]),
+    ]));
+    round_trip(FoundationValue::Dictionary(vec![(
+        FoundationValue::String("key".into()),
+        FoundationValue::Array(vec![FoundationValue::String("value".into())]),
+    )]));
+}
+
+#[test]
+fn get() {
+    let value = FoundationValue::Dictionary(vec![
+        (
+            FoundationValue::String("a".into()),
+            FoundationValue::Integer(1),
+        ),
+        (FoundationValue::Integer(2), FoundationValue::Integer(2)),
+    ]);
+    assert_eq!(value.get("a"), Some(&FoundationValue::Integer(1)));
+    assert_eq!(value.get("b"), None);
+    assert_eq!(FoundationValue::Null.get("a"), None);
+}
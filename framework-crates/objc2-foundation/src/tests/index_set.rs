@@ -0,0 +1,55 @@
+#![cfg(feature = "NSIndexSet")]
+use alloc::vec::Vec;
+
+use objc2::rc::Id;
+
+use crate::Foundation::{NSIndexSet, NSMutableIndexSet};
+
+#[test]
+fn test_from_indexes() {
+    let set = NSIndexSet::from_indexes(&[]);
+    assert!(set.is_empty());
+    assert_eq!(set.iter().next(), None);
+
+    let set = NSIndexSet::from_indexes(&[7, 0, 3, 7]);
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(3));
+    assert!(!set.contains(4));
+    assert_eq!(set.to_vec(), [0, 3, 7]);
+    assert_eq!(set.iter().len(), 3);
+}
+
+#[test]
+#[cfg(feature = "NSRange")]
+fn test_ranges() {
+    let set = NSIndexSet::from_range(3..6);
+    assert_eq!(set.to_vec(), [3, 4, 5]);
+    assert!(NSIndexSet::from_range(3..3).is_empty());
+
+    let mut set = NSMutableIndexSet::from_indexes(&[0]);
+    set.insert_range(10..12);
+    assert_eq!(set.to_vec(), [0, 10, 11]);
+}
+
+#[test]
+fn test_mutable() {
+    let mut set = NSMutableIndexSet::from_indexes(&[1]);
+    assert!(set.insert(5));
+    assert!(!set.insert(1));
+    assert!(set.remove(1));
+    assert!(!set.remove(1));
+    assert_eq!(set.to_vec(), [5]);
+
+    set.extend([2, 9]);
+    let vec: Vec<usize> = set.into_iter().collect();
+    assert_eq!(vec, [2, 5, 9]);
+}
+
+#[test]
+fn test_from_iter() {
+    let set: Id<NSIndexSet> = [4, 2, 4].into_iter().collect();
+    assert_eq!(set.to_vec(), [2, 4]);
+
+    let set: Id<NSMutableIndexSet> = (0..3).collect();
+    assert_eq!(set.to_vec(), [0, 1, 2]);
+}
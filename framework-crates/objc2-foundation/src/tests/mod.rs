@@ -1,22 +1,37 @@
 mod array;
 mod attributed_string;
 mod auto_traits;
+mod base64;
 mod bundle;
+mod copying;
 mod data;
+mod decimal_number;
 mod dictionary;
+mod enumerator;
 mod error;
+mod event_stream;
 mod exception;
+mod file_coordinator;
+mod foundation_value;
+mod index_set;
 mod lock;
 mod mutable_array;
 mod mutable_data;
 mod mutable_dictionary;
 mod mutable_set;
 mod mutable_string;
+mod notification_center;
+mod null;
 mod number;
+mod oneshot;
+mod ordered_set;
 mod process_info;
 mod proxy;
 mod set;
+mod spawn;
+mod stream;
 mod string;
 mod thread;
+mod url;
 mod uuid;
 mod value;
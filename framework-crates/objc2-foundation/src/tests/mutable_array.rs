@@ -60,11 +60,7 @@ fn test_allowed_mutation_while_iterating() {
 #[test]
 #[should_panic = "mutation detected during enumeration"]
 #[cfg_attr(
-    not(debug_assertions),
-    ignore = "enumeration mutation only detected with debug assertions on"
-)]
-#[cfg_attr(
-    all(debug_assertions, feature = "gnustep-1-7"),
+    feature = "gnustep-1-7",
     ignore = "thread safety issues regarding initialization"
 )]
 fn test_iter_mutation_detection() {
@@ -76,6 +72,20 @@ fn test_iter_mutation_detection() {
     }
 }
 
+#[test]
+#[cfg(feature = "NSRange")]
+fn test_iter_copied_allows_mutation() {
+    let array = NSMutableArray::from_id_slice(&[NSObject::new(), NSObject::new()]);
+
+    let mut count = 0;
+    for item in array.iter_copied() {
+        let _: () = unsafe { msg_send![&array, removeObject: &*item] };
+        count += 1;
+    }
+    assert_eq!(count, 2);
+    assert_eq!(array.len(), 0);
+}
+
 #[test]
 #[cfg_attr(
     feature = "gnustep-1-7",
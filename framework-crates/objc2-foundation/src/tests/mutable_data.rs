@@ -120,3 +120,35 @@ fn test_as_ref_borrow() {
     let obj: &mut NSData = obj;
     let _: &[u8] = obj.as_ref();
 }
+
+#[test]
+#[cfg(feature = "std")]
+fn test_cursor() {
+    use std::io::{Seek, SeekFrom, Write};
+
+    use crate::data::Cursor;
+
+    let mut cursor = Cursor::new(NSMutableData::with_bytes(&[]));
+    cursor.write_all(&[1, 2, 3]).unwrap();
+    assert_eq!(cursor.position(), 3);
+
+    // Overwrite in the middle, and extend past the end.
+    assert_eq!(cursor.seek(SeekFrom::Current(-1)).unwrap(), 2);
+    cursor.write_all(&[4, 5]).unwrap();
+    assert_eq!(cursor.get_ref().bytes(), [1, 2, 4, 5]);
+
+    // Seeking past the end zero-fills when writing.
+    assert_eq!(cursor.seek(SeekFrom::End(2)).unwrap(), 6);
+    assert_eq!(cursor.get_ref().len(), 4);
+    cursor.write_all(&[6]).unwrap();
+    assert_eq!(cursor.get_ref().bytes(), [1, 2, 4, 5, 0, 0, 6]);
+
+    assert!(cursor.seek(SeekFrom::Current(-8)).is_err());
+    assert_eq!(cursor.position(), 7);
+
+    // Also works with `&mut NSMutableData`.
+    let mut data = cursor.into_inner();
+    let mut cursor = Cursor::new(&mut *data);
+    cursor.write_all(&[7]).unwrap();
+    assert_eq!(data.bytes(), [7, 2, 4, 5, 0, 0, 6]);
+}
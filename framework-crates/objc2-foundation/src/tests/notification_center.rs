@@ -0,0 +1,42 @@
+#![cfg(feature = "NSNotification")]
+#![cfg(feature = "NSString")]
+#![cfg(feature = "NSOperation")]
+#![cfg(feature = "NSData")]
+#![cfg(feature = "block2")]
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+
+use crate::Foundation::{NSData, NSNotificationCenter, NSString};
+
+#[test]
+fn test_weak_observer() {
+    let center = unsafe { NSNotificationCenter::defaultCenter() };
+    let name = NSString::from_str("TestWeakObserverNotification");
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    // Non-empty, so that it isn't a shared singleton.
+    let observer = NSData::with_bytes(&[1, 2, 3]);
+    let token = center.add_weak_observer(Some(&name), None, None, &*observer, {
+        let calls = calls.clone();
+        move |observer, _notification| {
+            assert_eq!(observer.bytes(), [1, 2, 3]);
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    unsafe { center.postNotificationName_object(&name, None) };
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    drop(observer);
+    if cfg!(not(feature = "gnustep-1-7")) {
+        // GNUStep may still load the weak reference, see `WeakId`'s tests.
+        unsafe { center.postNotificationName_object(&name, None) };
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    let token: Id<AnyObject> = unsafe { Id::cast(token) };
+    unsafe { center.removeObserver(&token) };
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "NSNull")]
+#![cfg(feature = "NSString")]
+use crate::Foundation::{ns_string, NSNull, NSString};
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+
+#[test]
+fn is_null() {
+    let null = NSNull::null_object();
+    assert!(NSNull::is_null(&*null));
+    assert!(!NSNull::is_null(ns_string!("null")));
+}
+
+#[test]
+#[cfg(feature = "NSArray")]
+#[cfg(feature = "NSRange")]
+fn array_round_trip() {
+    use crate::Foundation::{NSArray, NSMutableArray};
+
+    let a = ns_string!("a");
+    let b = ns_string!("b");
+    let array = NSArray::from_nullable_slice(&[Some(a), None, Some(b), None]);
+    assert_eq!(array.len(), 4);
+
+    let vec = array.to_vec_nullable();
+    assert_eq!(vec.len(), 4);
+    assert!(vec[1].is_none());
+    assert!(vec[3].is_none());
+    let a_obj: &AnyObject = vec[0].unwrap();
+    assert!(core::ptr::eq(
+        a_obj as *const AnyObject as *const NSString,
+        a
+    ));
+
+    assert_eq!(array.to_vec_non_null().len(), 2);
+
+    let array = NSMutableArray::<NSString>::from_nullable_slice(&[None]);
+    assert_eq!(array.len(), 1);
+    assert!(array.to_vec_nullable()[0].is_none());
+    assert!(array.to_vec_non_null().is_empty());
+}
+
+#[test]
+#[cfg(feature = "NSDictionary")]
+#[cfg(feature = "NSObject")]
+fn dictionary() {
+    use alloc::vec;
+
+    use crate::Foundation::NSDictionary;
+
+    let null = NSNull::null_object();
+    // SAFETY: Upcasting to `AnyObject` is always valid.
+    let value: Id<AnyObject> = unsafe { Id::cast(NSString::from_str("value")) };
+    let dict = NSDictionary::from_vec(
+        &[ns_string!("null"), ns_string!("value")],
+        vec![null, value],
+    );
+
+    assert!(dict.get(ns_string!("null")).is_some());
+    assert!(dict.get_non_null(ns_string!("null")).is_none());
+    assert!(dict.get_non_null(ns_string!("value")).is_some());
+    assert!(dict.get_non_null(ns_string!("missing")).is_none());
+
+    let (keys, values) = dict.to_vecs_nullable();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(values.iter().filter(|value| value.is_none()).count(), 1);
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "std")]
+use alloc::sync::Arc;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll, Waker};
+use std::task::Wake;
+use std::thread;
+
+use crate::oneshot;
+
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn send_from_other_thread() {
+    let (sender, mut receiver) = oneshot::<i32>();
+    let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(Pin::new(&mut receiver).poll(&mut cx), Poll::Pending);
+
+    let sender2 = sender.clone();
+    thread::spawn(move || sender2.send(1)).join().unwrap();
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+
+    // Later values are ignored, and don't wake the task again.
+    sender.send(2);
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+
+    assert_eq!(Pin::new(&mut receiver).poll(&mut cx), Poll::Ready(1));
+    assert_eq!(receiver.try_take(), None);
+}
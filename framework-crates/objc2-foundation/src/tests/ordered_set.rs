@@ -0,0 +1,87 @@
+#![cfg(feature = "NSOrderedSet")]
+#![cfg(feature = "NSString")]
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::Foundation::{ns_string, NSMutableOrderedSet, NSOrderedSet, NSString};
+
+#[test]
+fn test_from_vec() {
+    let set = NSOrderedSet::<NSString>::from_vec(Vec::new());
+    assert!(set.is_empty());
+
+    let strs = ["b", "a", "b", "c"].map(NSString::from_str);
+    let set = NSOrderedSet::from_vec(strs.to_vec());
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.to_vec(), [&*strs[0], &*strs[1], &*strs[3]]);
+    assert_eq!(set.first(), Some(ns_string!("b")));
+    assert_eq!(set.last(), Some(ns_string!("c")));
+}
+
+#[test]
+fn test_index_of() {
+    let set = NSOrderedSet::from_id_slice(&["one", "two"].map(NSString::from_str));
+    assert!(set.contains(ns_string!("two")));
+    assert_eq!(set.index_of(ns_string!("one")), Some(0));
+    assert_eq!(set.index_of(ns_string!("two")), Some(1));
+    assert_eq!(set.index_of(ns_string!("three")), None);
+    assert_eq!(set.get(1), Some(ns_string!("two")));
+    assert_eq!(set.get(2), None);
+    assert_eq!(&set[0], ns_string!("one"));
+}
+
+#[test]
+fn test_mutable() {
+    let mut set = NSMutableOrderedSet::new();
+    assert!(set.push(NSString::from_str("one")));
+    assert!(set.push(NSString::from_str("three")));
+    assert!(!set.push(NSString::from_str("one")));
+    assert!(set.insert(1, NSString::from_str("two")));
+    assert_eq!(
+        set.to_vec(),
+        [ns_string!("one"), ns_string!("two"), ns_string!("three")]
+    );
+
+    assert!(set.remove(ns_string!("two")));
+    assert!(!set.remove(ns_string!("two")));
+    assert_eq!(&*set.remove_at(0).unwrap(), ns_string!("one"));
+    assert_eq!(set.remove_at(1), None);
+
+    let vec = NSMutableOrderedSet::into_vec(set);
+    assert_eq!(vec.len(), 1);
+}
+
+#[test]
+#[should_panic = "insertion index (is 2) should be <= len (is 0)"]
+fn test_insert_out_of_bounds() {
+    let mut set = NSMutableOrderedSet::<NSString>::new();
+    set.insert(2, NSString::from_str("one"));
+}
+
+#[test]
+#[cfg(feature = "NSEnumerator")]
+fn test_iter() {
+    let strs = ["one", "two", "three"].map(NSString::from_str);
+    let set = NSOrderedSet::from_id_slice(&strs);
+    assert!(set.iter().eq(strs.iter().map(|s| &**s)));
+    assert_eq!(format!("{set:?}"), "{\"one\", \"two\", \"three\"}");
+}
+
+#[test]
+#[cfg(feature = "NSIndexSet")]
+fn test_get_indexes() {
+    use alloc::vec;
+
+    use crate::Foundation::NSIndexSet;
+
+    let set = NSOrderedSet::from_id_slice(&["one", "two", "three"].map(NSString::from_str));
+    assert_eq!(
+        set.get_indexes(&NSIndexSet::from_indexes(&[2, 0])),
+        Some(vec![ns_string!("one"), ns_string!("three")])
+    );
+    assert_eq!(
+        set.get_indexes(&NSIndexSet::from_indexes(&[])),
+        Some(vec![])
+    );
+    assert_eq!(set.get_indexes(&NSIndexSet::from_indexes(&[1, 3])), None);
+}
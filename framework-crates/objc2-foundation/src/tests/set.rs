@@ -1,6 +1,7 @@
 #![cfg(feature = "NSSet")]
 #![cfg(feature = "NSString")]
 #![cfg(feature = "NSValue")]
+use alloc::string::ToString;
 use alloc::vec::Vec;
 use alloc::{format, vec};
 
@@ -198,6 +199,33 @@ fn test_debug() {
     ));
 }
 
+#[test]
+fn test_counted() {
+    let strs = ["one", "two", "one"].map(NSString::from_str);
+    let mut set = Foundation::NSCountedSet::from_vec(strs.to_vec());
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.count_of(ns_string!("one")), 2);
+    assert_eq!(set.count_of(ns_string!("two")), 1);
+    assert_eq!(set.count_of(ns_string!("three")), 0);
+
+    assert!(set.remove(ns_string!("one")));
+    assert_eq!(set.count_of(ns_string!("one")), 1);
+    assert!(set.contains(ns_string!("one")));
+}
+
+#[test]
+#[cfg(feature = "NSEnumerator")]
+fn test_counted_iter() {
+    let strs = ["one", "two", "one"].map(NSString::from_str);
+    let set = Foundation::NSCountedSet::from_vec(strs.to_vec());
+    let mut counts: Vec<_> = set
+        .iter_counts()
+        .map(|(s, count)| (s.to_string(), count))
+        .collect();
+    counts.sort();
+    assert_eq!(counts, [("one".into(), 2), ("two".into(), 1)]);
+}
+
 /// This currently works, but we should figure out a way to disallow it!
 #[test]
 #[cfg(all(feature = "NSArray", feature = "NSCalendar"))]
@@ -0,0 +1,81 @@
+#![cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::sync::{Arc, Mutex};
+
+use crate::{global_spawner, set_global_spawner, spawn, BoxFuture, Spawner};
+
+/// A spawner that just stores the futures, to be run later.
+#[derive(Default)]
+struct Queue(Mutex<Vec<BoxFuture>>);
+
+impl Spawner for Queue {
+    fn spawn_boxed(&self, future: BoxFuture) {
+        self.0.lock().unwrap().push(future);
+    }
+}
+
+impl Queue {
+    fn run(&self) {
+        fn noop_raw_waker() -> RawWaker {
+            fn clone(_: *const ()) -> RawWaker {
+                noop_raw_waker()
+            }
+            fn noop(_: *const ()) {}
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(ptr::null(), &VTABLE)
+        }
+        let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+        let mut cx = Context::from_waker(&waker);
+
+        let futures = core::mem::take(&mut *self.0.lock().unwrap());
+        for mut future in futures {
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(()));
+        }
+    }
+}
+
+#[test]
+fn spawn_through_references() {
+    let queue = Arc::new(Queue::default());
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let c = counter.clone();
+    queue.spawn(async move {
+        c.fetch_add(1, Ordering::Relaxed);
+    });
+    let c = counter.clone();
+    Spawner::spawn(&&*queue, async move {
+        c.fetch_add(1, Ordering::Relaxed);
+    });
+    let dyn_spawner: &dyn Spawner = &*queue;
+    let c = counter.clone();
+    dyn_spawner.spawn_boxed(Box::pin(async move {
+        c.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    assert_eq!(counter.load(Ordering::Relaxed), 0);
+    queue.run();
+    assert_eq!(counter.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn global() {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let queue: &'static Queue = Box::leak(Box::default());
+
+    // Only set in this test.
+    assert!(global_spawner().is_none());
+    assert!(set_global_spawner(queue).is_ok());
+    assert!(global_spawner().is_some());
+    assert!(set_global_spawner(Queue::default()).is_err());
+
+    spawn(async {
+        COUNTER.fetch_add(1, Ordering::Relaxed);
+    });
+    queue.run();
+    assert_eq!(COUNTER.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "NSStream")]
+#![cfg(feature = "NSData")]
+#![cfg(feature = "NSString")]
+#![cfg(feature = "std")]
+use std::io::{Read, Write};
+
+use objc2::rc::Id;
+
+use crate::Foundation::{
+    NSData, NSInputStream, NSOutputStream, NSStreamDataWrittenToMemoryStreamKey,
+};
+
+#[test]
+fn test_write_to_memory() {
+    let stream = unsafe { NSOutputStream::outputStreamToMemory() };
+    unsafe { stream.open() };
+
+    (&*stream).write_all(b"hello ").unwrap();
+    write!(&*stream, "{}", "world").unwrap();
+
+    let data = unsafe { stream.propertyForKey(NSStreamDataWrittenToMemoryStreamKey) };
+    let data: Id<NSData> = unsafe { Id::cast(data.unwrap()) };
+    assert_eq!(data.bytes(), b"hello world");
+
+    unsafe { stream.close() };
+}
+
+#[test]
+fn test_read_from_data() {
+    let data = NSData::with_bytes(b"hello world");
+    let stream = unsafe { NSInputStream::inputStreamWithData(&data) }.unwrap();
+    unsafe { stream.open() };
+
+    let mut buf = [0; 6];
+    (&*stream).read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello ");
+    let mut rest = std::vec::Vec::new();
+    (&*stream).read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"world");
+
+    unsafe { stream.close() };
+}
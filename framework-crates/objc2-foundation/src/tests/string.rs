@@ -4,7 +4,7 @@ use alloc::string::ToString;
 
 use objc2::rc::autoreleasepool;
 
-use crate::Foundation::{ns_string, NSString};
+use crate::Foundation::{ns_string, NSString, StringEncoding};
 
 #[test]
 fn test_equality() {
@@ -256,3 +256,70 @@ fn test_macro_in_unsafe() {
     };
     assert_eq!(s.to_string(), "abc");
 }
+
+#[test]
+fn test_decode_encodings() {
+    let s = NSString::from_bytes(b"caf\xE9", StringEncoding::ISO_LATIN_1).unwrap();
+    assert_eq!(s.to_string(), "café");
+    let s = NSString::from_bytes(&[0, b'h', 0, b'i'], StringEncoding::UTF16_BE).unwrap();
+    assert_eq!(s.to_string(), "hi");
+    let s = NSString::from_bytes(&[b'h', 0, b'i', 0], StringEncoding::UTF16_LE).unwrap();
+    assert_eq!(s.to_string(), "hi");
+
+    // The byte order mark is used by the generic UTF-16 encoding.
+    let s = NSString::from_bytes(&[0xFF, 0xFE, b'h', 0, b'i', 0], StringEncoding::UTF16).unwrap();
+    assert_eq!(s.to_string(), "hi");
+
+    assert!(NSString::from_bytes(b"caf\xE9", StringEncoding::UTF8).is_none());
+}
+
+#[test]
+fn test_from_bom() {
+    assert_eq!(
+        StringEncoding::from_bom(b"\xEF\xBB\xBFabc"),
+        Some((StringEncoding::UTF8, 3))
+    );
+    assert_eq!(
+        StringEncoding::from_bom(&[0xFF, 0xFE, 0, 0]),
+        Some((StringEncoding::UTF32_LE, 4))
+    );
+    assert_eq!(
+        StringEncoding::from_bom(&[0xFF, 0xFE, b'a', 0]),
+        Some((StringEncoding::UTF16_LE, 2))
+    );
+    assert_eq!(StringEncoding::from_bom(b"abc"), None);
+    assert_eq!(
+        format!("{:?}", StringEncoding::UTF16_BE),
+        "StringEncoding::UTF16_BE"
+    );
+}
+
+#[test]
+#[cfg(feature = "NSData")]
+fn test_encode_encodings() {
+    let s = NSString::from_str("café");
+    assert!(s.can_encode(StringEncoding::ISO_LATIN_1));
+    assert_eq!(s.encode(StringEncoding::ISO_LATIN_1).unwrap(), b"caf\xE9");
+    assert_eq!(
+        s.encode(StringEncoding::UTF16_LE).unwrap(),
+        [b'c', 0, b'a', 0, b'f', 0, 0xE9, 0]
+    );
+
+    assert!(!s.can_encode(StringEncoding::ASCII));
+    assert_eq!(s.encode(StringEncoding::ASCII), None);
+    let lossy = s.encode_lossy(StringEncoding::ASCII).unwrap();
+    assert!(lossy.starts_with(b"caf"));
+
+    for encoding in [
+        StringEncoding::UTF8,
+        StringEncoding::UTF16,
+        StringEncoding::UTF16_BE,
+        StringEncoding::UTF32_LE,
+        StringEncoding::SHIFT_JIS,
+    ] {
+        let s = NSString::from_str("日本語");
+        let bytes = s.encode(encoding).unwrap();
+        let decoded = NSString::from_bytes(&bytes, encoding).unwrap();
+        assert_eq!(decoded.to_string(), "日本語", "{encoding:?}");
+    }
+}
@@ -123,3 +123,54 @@ fn test_main_thread_bound_into_inner() {
     drop(foo);
     assert!(is_dropped.get());
 }
+
+#[test]
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "std")]
+fn test_on_main_or_dispatch_detects_blocked_main_thread() {
+    use crate::Foundation::{on_main_or_dispatch_timeout, MainThreadBound};
+    use std::time::Duration;
+
+    // SAFETY: For testing only, `()` is not main-thread-only
+    let mtm = unsafe { MainThreadMarker::new_unchecked() };
+    let bound = MainThreadBound::new((), mtm);
+
+    let res = on_main_or_dispatch_timeout(&bound, Duration::from_millis(50), |_, _| 42);
+    if is_main_thread() {
+        assert_eq!(res, Ok(42));
+    } else {
+        // The test harness' main thread is blocked waiting for the tests to
+        // finish, and is not running the main queue.
+        assert!(res.is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+fn test_new_including_main_queue() {
+    use crate::Foundation::MainThreadCheck;
+
+    let res = MainThreadMarker::new_including_main_queue();
+    if is_main_thread() {
+        assert!(matches!(res, Some(MainThreadCheck::MainThread(_))));
+    } else {
+        assert_eq!(res, None);
+    }
+
+    let res = std::thread::spawn(|| MainThreadMarker::new_including_main_queue().is_some())
+        .join()
+        .unwrap();
+    assert!(!res);
+
+    // A queue with the same label as the main queue is not the main queue.
+    let queue = dispatch::Queue::create("com.apple.main-thread", dispatch::QueueAttribute::Serial);
+    let is_main_queue = queue.exec_sync(|| {
+        matches!(
+            MainThreadMarker::new_including_main_queue(),
+            Some(MainThreadCheck::MainQueue(_))
+        )
+    });
+    assert!(!is_main_queue);
+}
@@ -0,0 +1,41 @@
+#![cfg(all(feature = "std", feature = "NSURL", feature = "NSString"))]
+#![cfg(unix)]
+use std::path::Path;
+
+use crate::Foundation::{NSString, NSURL};
+
+#[test]
+fn path_roundtrip() {
+    let path = Path::new("/tmp/some file/ä.txt");
+    let url = NSURL::from_path(path, false).unwrap();
+    assert!(unsafe { url.isFileURL() });
+    assert_eq!(url.to_file_path().as_deref(), Some(path));
+}
+
+#[test]
+fn path_with_nul() {
+    assert!(NSURL::from_path(Path::new("/tmp/a\0b"), false).is_none());
+}
+
+#[test]
+fn non_file_url_has_no_path() {
+    let url = unsafe { NSURL::URLWithString(&NSString::from_str("https://example.com")) }.unwrap();
+    assert_eq!(url.to_file_path(), None);
+}
+
+#[test]
+#[cfg(not(feature = "gnustep-1-7"))]
+fn access_non_scoped() {
+    let url = NSURL::from_path(Path::new("/tmp"), true).unwrap();
+    let access = url.access_security_scoped_resource();
+    assert!(!access.is_security_scoped());
+    assert_eq!(access.url(), &*url);
+}
+
+#[test]
+#[cfg(not(feature = "gnustep-1-7"))]
+fn with_access() {
+    let url = NSURL::from_path(Path::new("/tmp"), true).unwrap();
+    let path = url.with_security_scoped_access(|url| url.to_file_path());
+    assert_eq!(path.as_deref(), Some(Path::new("/tmp")));
+}
@@ -24,6 +24,16 @@ fn test_bytes() {
     assert_eq!(uuid.as_bytes(), [10; 16]);
 }
 
+#[test]
+#[cfg(feature = "uuid")]
+fn test_uuid_crate() {
+    let uuid = ::uuid::Uuid::from_bytes([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
+    let obj = NSUUID::from_uuid(uuid);
+    assert_eq!(obj.as_bytes(), *uuid.as_bytes());
+    assert_eq!(obj.as_uuid(), uuid);
+    assert_eq!(::uuid::Uuid::from(&*obj), uuid);
+}
+
 #[test]
 #[cfg(feature = "NSString")]
 fn display_debug() {
@@ -44,6 +44,59 @@ pub fn is_main_thread() -> bool {
     NSThread::isMainThread_class()
 }
 
+/// Whether the current thread is executing a block submitted to the main
+/// dispatch queue (or to a queue that targets it).
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+fn is_main_queue() -> bool {
+    use core::ffi::c_void;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    #[repr(C)]
+    struct DispatchQueue {
+        _priv: [u8; 0],
+    }
+
+    extern "C" {
+        /// `dispatch_get_main_queue()` is a macro for the address of this.
+        static _dispatch_main_q: DispatchQueue;
+        fn dispatch_queue_set_specific(
+            queue: *const DispatchQueue,
+            key: *const c_void,
+            context: *mut c_void,
+            destructor: Option<unsafe extern "C" fn(*mut c_void)>,
+        );
+        fn dispatch_get_specific(key: *const c_void) -> *mut c_void;
+    }
+
+    // We identify the main queue by a queue-specific value that only we set,
+    // and only on the main queue. Unlike e.g. the queue's label, this cannot
+    // be faked by other queues.
+    static KEY: u8 = 0;
+    static IS_KEY_SET: AtomicBool = AtomicBool::new(false);
+
+    let key: *const c_void = (&KEY as *const u8).cast();
+    if !IS_KEY_SET.load(Ordering::Acquire) {
+        // SAFETY: The main queue is valid for the lifetime of the program,
+        // and the key is the address of a static that is never used for
+        // anything else. The context is not a pointer to any data, so it
+        // doesn't need a destructor. Setting it more than once is harmless.
+        unsafe {
+            dispatch_queue_set_specific(
+                core::ptr::addr_of!(_dispatch_main_q),
+                key,
+                key as *mut c_void,
+                None,
+            )
+        };
+        IS_KEY_SET.store(true, Ordering::Release);
+    }
+
+    // SAFETY: The key is valid. This looks up the value on the current queue,
+    // and on the queues it targets.
+    !unsafe { dispatch_get_specific(key) }.is_null()
+}
+
 #[allow(unused)]
 #[cfg(feature = "NSThread")]
 fn make_multithreaded() {
@@ -92,6 +145,192 @@ where
     }
 }
 
+/// How long [`on_main_or_dispatch`] waits for the main thread to pick up the
+/// closure, before assuming that it is deadlocked.
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "std")]
+pub const MAIN_THREAD_DEADLOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// The main thread did not start running a closure submitted by
+/// [`on_main_or_dispatch`], most likely because it is blocked waiting for
+/// the current thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "std")]
+pub struct MainThreadDeadlock {
+    _priv: (),
+}
+
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "std")]
+impl fmt::Display for MainThreadDeadlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the main thread is blocked waiting for the current thread")
+    }
+}
+
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "std")]
+impl std::error::Error for MainThreadDeadlock {}
+
+/// Access the given main-thread-only receiver on the main thread, without
+/// hanging if the main thread is blocked on the current thread.
+///
+/// If the current thread is the main thread, this runs the closure
+/// immediately. Otherwise, the closure is submitted to the main queue, and
+/// the current thread waits for it to complete, like [`run_on_main`].
+///
+/// Unlike [`run_on_main`] (and `dispatch_sync`), this detects the common
+/// deadlocks where the main queue cannot run the closure until the current
+/// thread returns:
+/// - If the current thread is itself executing the main queue (which can
+///   happen off the main thread after `dispatch_main`), this returns
+///   [`MainThreadDeadlock`] immediately.
+/// - If the main thread is (directly or indirectly) waiting on the current
+///   thread, e.g. because it has synchronously dispatched work that ended up
+///   calling into UI code, this is detected by the main thread not starting
+///   the closure within [`MAIN_THREAD_DEADLOCK_TIMEOUT`]. The closure is then
+///   cancelled, and [`MainThreadDeadlock`] is returned instead. Use
+///   [`on_main_or_dispatch_timeout`] to configure the timeout.
+///
+/// Once the closure has started running, this waits for it to complete. If
+/// the closure panics, the panic is resumed on the current thread.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_foundation::{on_main_or_dispatch, MainThreadBound};
+/// # let receiver: MainThreadBound<()> = unimplemented!();
+///
+/// let res = on_main_or_dispatch(&receiver, |receiver, _mtm| {
+///     // Do something with the receiver on the main thread
+/// });
+/// if res.is_err() {
+///     eprintln!("main thread is blocked, skipping UI update");
+/// }
+/// ```
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "std")]
+#[inline]
+pub fn on_main_or_dispatch<T, F, R>(
+    receiver: &MainThreadBound<T>,
+    f: F,
+) -> Result<R, MainThreadDeadlock>
+where
+    F: Send + FnOnce(&T, MainThreadMarker) -> R,
+    R: Send,
+{
+    on_main_or_dispatch_timeout(receiver, MAIN_THREAD_DEADLOCK_TIMEOUT, f)
+}
+
+/// Same as [`on_main_or_dispatch`], but with a custom timeout for the main
+/// thread to start running the closure.
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[cfg(feature = "std")]
+pub fn on_main_or_dispatch_timeout<T, F, R>(
+    receiver: &MainThreadBound<T>,
+    timeout: std::time::Duration,
+    f: F,
+) -> Result<R, MainThreadDeadlock>
+where
+    F: Send + FnOnce(&T, MainThreadMarker) -> R,
+    R: Send,
+{
+    use alloc::boxed::Box;
+    use core::any::Any;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::{Arc, Condvar, Mutex};
+
+    if let Some(mtm) = MainThreadMarker::new() {
+        return Ok(f(receiver.get(mtm), mtm));
+    }
+
+    // The main queue is serial, so it won't run the job until we return.
+    if is_main_queue() {
+        return Err(MainThreadDeadlock { _priv: () });
+    }
+
+    enum State {
+        Pending(Box<dyn FnOnce() + Send>),
+        Running,
+        Done,
+        Panicked(Box<dyn Any + Send>),
+        Cancelled,
+    }
+
+    let mut result = None;
+    let job: Box<dyn FnOnce() + Send + '_> = Box::new(|| {
+        // SAFETY: The job is only run on the main queue.
+        let mtm = unsafe { MainThreadMarker::new_unchecked() };
+        result = Some(f(receiver.get(mtm), mtm));
+    });
+    // SAFETY: We erase the lifetime so that the job can be submitted to the
+    // main queue. This is sound because we don't return from this function
+    // until the job has either been run to completion (or panicked), or
+    // taken back out of the shared state (and dropped) without running.
+    let job: Box<dyn FnOnce() + Send + 'static> = unsafe { mem::transmute(job) };
+
+    let shared = Arc::new((Mutex::new(State::Pending(job)), Condvar::new()));
+
+    dispatch::Queue::main().exec_async({
+        let shared = Arc::clone(&shared);
+        move || {
+            let (state, cond) = &*shared;
+            let job = {
+                let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+                match mem::replace(&mut *state, State::Running) {
+                    State::Pending(job) => job,
+                    other => {
+                        // Cancelled by the waiting thread.
+                        *state = other;
+                        return;
+                    }
+                }
+            };
+            // Catch panics, both so that the waiting thread is woken up, and
+            // so that they don't unwind into libdispatch.
+            let new_state = match panic::catch_unwind(AssertUnwindSafe(job)) {
+                Ok(()) => State::Done,
+                Err(payload) => State::Panicked(payload),
+            };
+            *state.lock().unwrap_or_else(|err| err.into_inner()) = new_state;
+            cond.notify_all();
+        }
+    });
+
+    let (state, cond) = &*shared;
+    let state = state.lock().unwrap_or_else(|err| err.into_inner());
+    let (mut state, _) = cond
+        .wait_timeout_while(state, timeout, |state| matches!(state, State::Pending(_)))
+        .unwrap_or_else(|err| err.into_inner());
+
+    if let State::Pending(_) = &*state {
+        // Timed out before the main thread picked up the job; take it back
+        // out, and drop it here while the borrowed data is still alive.
+        drop(mem::replace(&mut *state, State::Cancelled));
+        return Err(MainThreadDeadlock { _priv: () });
+    }
+
+    // The job has started running, wait for it to finish.
+    let mut state = cond
+        .wait_while(state, |state| matches!(state, State::Running))
+        .unwrap_or_else(|err| err.into_inner());
+    if let State::Panicked(payload) = mem::replace(&mut *state, State::Done) {
+        drop(state);
+        panic::resume_unwind(payload);
+    }
+    drop(state);
+
+    Ok(result.expect("main thread job completed without result"))
+}
+
 /// A marker type taken by functions that can only be executed on the main
 /// thread.
 ///
@@ -189,6 +428,35 @@ impl MainThreadMarker {
         }
     }
 
+    /// Check whether the current thread is the main thread, or is executing
+    /// the main dispatch queue.
+    ///
+    /// Blocks submitted to the main queue are normally executed on the main
+    /// thread. If the process has called `dispatch_main` however, the main
+    /// thread exits, and the main queue is instead serviced by a thread from
+    /// the dispatch thread pool; the main queue then takes over the role of
+    /// the main thread, in that work submitted to it is still serialized.
+    ///
+    /// This returns which check succeeded, or [`None`] if the current thread
+    /// is neither the main thread, nor executing the main queue. Only the
+    /// former gives a [`MainThreadMarker`]; executing the main queue gives a
+    /// [`MainQueueMarker`] instead, since that is not enough to safely use
+    /// main-thread-only APIs.
+    ///
+    /// The main queue is identified with a queue-specific value (see
+    /// `dispatch_queue_set_specific`), so blocks on queues that target the
+    /// main queue are also considered to be executing it.
+    #[cfg(feature = "dispatch")]
+    #[cfg(feature = "NSThread")]
+    #[doc(alias = "dispatch_assert_queue")]
+    pub fn new_including_main_queue() -> Option<MainThreadCheck> {
+        if let Some(mtm) = Self::new() {
+            Some(MainThreadCheck::MainThread(mtm))
+        } else {
+            MainQueueMarker::new().map(MainThreadCheck::MainQueue)
+        }
+    }
+
     /// Construct a new [`MainThreadMarker`] without first checking whether
     /// the current thread is the main one.
     ///
@@ -262,6 +530,86 @@ impl MainThreadMarker {
     }
 }
 
+/// Which check succeeded in [`MainThreadMarker::new_including_main_queue`].
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MainThreadCheck {
+    /// The current thread is the main thread.
+    MainThread(MainThreadMarker),
+    /// The current thread is not the main thread, but is executing a block
+    /// submitted to the main dispatch queue.
+    MainQueue(MainQueueMarker),
+}
+
+/// A marker type for code that is executing a block submitted to the main
+/// dispatch queue.
+///
+/// Like [`MainThreadMarker`], this is neither [`Send`] nor [`Sync`]. Unlike
+/// it, it does not guarantee that the current thread is the main thread, so
+/// it cannot be used to access main-thread-only APIs directly; see
+/// [`MainThreadMarker::new_including_main_queue`] for when that is the case.
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MainQueueMarker {
+    _priv: PhantomData<*mut ()>,
+}
+
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+impl MainQueueMarker {
+    /// Construct a new [`MainQueueMarker`].
+    ///
+    /// Returns [`None`] if the current thread is not executing the main
+    /// queue. Note that the main thread only executes the main queue while
+    /// running a block submitted to it.
+    #[inline]
+    pub fn new() -> Option<Self> {
+        if is_main_queue() {
+            // SAFETY: We just checked that we are executing the main queue.
+            Some(unsafe { Self::new_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Construct a new [`MainQueueMarker`] without first checking whether
+    /// the current thread is executing the main queue.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The current thread must be executing a block submitted to the main
+    /// queue.
+    #[inline]
+    pub unsafe fn new_unchecked() -> Self {
+        Self { _priv: PhantomData }
+    }
+
+    /// Use the main queue in place of the main thread.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// Any use of the returned marker must be safe to do from the main queue
+    /// outside the main thread. This is not the case for e.g. AppKit, which
+    /// checks the thread itself.
+    #[inline]
+    pub unsafe fn assume_main_thread(self) -> MainThreadMarker {
+        // SAFETY: Upheld by caller.
+        unsafe { MainThreadMarker::new_unchecked() }
+    }
+}
+
+#[cfg(feature = "dispatch")]
+#[cfg(feature = "NSThread")]
+impl fmt::Debug for MainQueueMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MainQueueMarker").finish()
+    }
+}
+
 /// Get a [`MainThreadMarker`] from a main-thread-only object.
 ///
 /// This function exists purely in the type-system, and will always
@@ -0,0 +1,43 @@
+use core::ptr::NonNull;
+
+use block2::{Block, RcBlock};
+use objc2::mutability::IsRetainable;
+use objc2::rc::{Id, WeakCallback};
+use objc2::Message;
+
+use crate::Foundation::{NSTimeInterval, NSTimer};
+
+impl NSTimer {
+    /// Schedule a timer on the current run loop, whose closure is only
+    /// invoked while `target` is alive.
+    ///
+    /// The closure is given a strong reference to `target`, which is
+    /// otherwise only referenced weakly, so that the timer does not keep the
+    /// target alive (see [`WeakCallback`]). If the timer fires after the
+    /// target has been deallocated, the timer is invalidated.
+    #[doc(alias = "scheduledTimerWithTimeInterval:repeats:block:")]
+    pub fn scheduled_weak<T, F>(
+        interval: NSTimeInterval,
+        repeats: bool,
+        target: &T,
+        f: F,
+    ) -> Id<NSTimer>
+    where
+        T: Message + IsRetainable + 'static,
+        F: Fn(&T, &NSTimer) + 'static,
+    {
+        let callback = WeakCallback::new(target, f);
+        let block = RcBlock::new(move |timer: NonNull<NSTimer>| {
+            // SAFETY: The timer is valid for the duration of the call.
+            let timer = unsafe { timer.as_ref() };
+            if callback.call_with(timer).is_none() {
+                // The target is gone, so there is no reason to keep firing.
+                unsafe { timer.invalidate() };
+            }
+        });
+        let block: &Block<dyn Fn(NonNull<NSTimer>) + 'static> = &block;
+        // SAFETY: The block has the correct signature, and the timer retains
+        // (copies) it.
+        unsafe { Self::scheduledTimerWithTimeInterval_repeats_block(interval, repeats, block) }
+    }
+}
@@ -0,0 +1,308 @@
+use core::fmt;
+#[cfg(unix)]
+use std::ffi::{CStr, CString, OsStr};
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+
+use objc2::rc::Id;
+use objc2::ClassType;
+
+use crate::Foundation::NSURL;
+#[cfg(all(
+    target_os = "macos",
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSString"
+))]
+use crate::Foundation::{
+    NSData, NSError, NSURLBookmarkCreationOptions, NSURLBookmarkResolutionOptions,
+};
+
+impl NSURL {
+    /// Create a file URL from a path.
+    ///
+    /// Relative paths are resolved against the current directory.
+    ///
+    /// Returns `None` if the path contains a NUL byte.
+    #[cfg(unix)]
+    #[doc(alias = "fileURLWithFileSystemRepresentation:isDirectory:relativeToURL:")]
+    pub fn from_path(path: &Path, is_directory: bool) -> Option<Id<Self>> {
+        let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+        // SAFETY: The path is a valid NUL-terminated string, that is only
+        // read during the call.
+        let url = unsafe {
+            Self::fileURLWithFileSystemRepresentation_isDirectory_relativeToURL(
+                core::ptr::NonNull::new_unchecked(path.as_ptr() as *mut _),
+                is_directory,
+                None,
+            )
+        };
+        Some(url)
+    }
+
+    /// The path of a file URL.
+    ///
+    /// Returns `None` if this is not a file URL.
+    #[cfg(unix)]
+    #[doc(alias = "fileSystemRepresentation")]
+    pub fn to_file_path(&self) -> Option<PathBuf> {
+        // SAFETY: Checking the scheme is safe.
+        if !unsafe { self.isFileURL() } {
+            return None;
+        }
+        // SAFETY: File URLs always have a file system representation, which
+        // is a NUL-terminated string that lives as long as the URL (it is
+        // autoreleased, and we copy it before returning).
+        let path = unsafe { CStr::from_ptr(self.fileSystemRepresentation().as_ptr()) };
+        Some(PathBuf::from(OsStr::from_bytes(path.to_bytes())))
+    }
+
+    /// Make the resource that the URL points to available to a sandboxed
+    /// application, until the returned guard is dropped.
+    ///
+    /// URLs resolved from a [security-scoped bookmark] must be accessed
+    /// through this, and every call must be balanced with a call to
+    /// `stopAccessingSecurityScopedResource`, otherwise kernel resources
+    /// are leaked until the application is restarted. The guard takes care
+    /// of the balancing.
+    ///
+    /// It is fine to call this on URLs that are not security-scoped, or
+    /// outside of a sandbox; the guard then does nothing.
+    ///
+    /// [security-scoped bookmark]: NSURL::security_scoped_bookmark
+    #[doc(alias = "startAccessingSecurityScopedResource")]
+    #[doc(alias = "stopAccessingSecurityScopedResource")]
+    pub fn access_security_scoped_resource(&self) -> ScopedAccess {
+        // SAFETY: Starting access is safe, and is balanced in `Drop`.
+        let accessing = unsafe { self.startAccessingSecurityScopedResource() };
+        ScopedAccess {
+            url: self.retain(),
+            accessing,
+        }
+    }
+
+    /// Create a bookmark that allows a sandboxed application to access the
+    /// resource again after it is relaunched.
+    ///
+    /// The URL must be accessible to the application, for example because
+    /// the user chose it in an open panel. Store the returned data, and
+    /// resolve it with [`NSURL::from_security_scoped_bookmark`].
+    ///
+    /// If `read_only` is set, the resolved URL will only grant read access.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bookmark could not be created, for example
+    /// if the application lacks the
+    /// `com.apple.security.files.bookmarks.app-scope` entitlement.
+    #[cfg(all(
+        target_os = "macos",
+        feature = "NSArray",
+        feature = "NSData",
+        feature = "NSError",
+        feature = "NSString"
+    ))]
+    #[doc(alias = "bookmarkDataWithOptions:includingResourceValuesForKeys:relativeToURL:error:")]
+    #[doc(alias = "NSURLBookmarkCreationWithSecurityScope")]
+    pub fn security_scoped_bookmark(&self, read_only: bool) -> Result<Id<NSData>, Id<NSError>> {
+        let mut options = NSURLBookmarkCreationOptions::NSURLBookmarkCreationWithSecurityScope;
+        if read_only {
+            options |=
+                NSURLBookmarkCreationOptions::NSURLBookmarkCreationSecurityScopeAllowOnlyReadAccess;
+        }
+        // SAFETY: The options are valid.
+        unsafe {
+            self.bookmarkDataWithOptions_includingResourceValuesForKeys_relativeToURL_error(
+                options, None, None,
+            )
+        }
+    }
+
+    /// Resolve a bookmark created with
+    /// [`NSURL::security_scoped_bookmark`].
+    ///
+    /// The resolved URL must be accessed with
+    /// [`ResolvedBookmark::access`] (or
+    /// [`NSURL::access_security_scoped_resource`]). If the bookmark is
+    /// [stale](ResolvedBookmark::is_stale), it should be recreated and
+    /// stored again.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bookmark could not be resolved, for example
+    /// because the file was deleted.
+    #[cfg(all(
+        target_os = "macos",
+        feature = "NSArray",
+        feature = "NSData",
+        feature = "NSError",
+        feature = "NSString"
+    ))]
+    #[doc(alias = "URLByResolvingBookmarkData:options:relativeToURL:bookmarkDataIsStale:error:")]
+    #[doc(alias = "NSURLBookmarkResolutionWithSecurityScope")]
+    pub fn from_security_scoped_bookmark(
+        bookmark: &NSData,
+    ) -> Result<ResolvedBookmark, Id<NSError>> {
+        let mut is_stale = objc2::runtime::Bool::NO;
+        // SAFETY: The options are valid, and the stale flag is a valid
+        // pointer for the duration of the call.
+        let url = unsafe {
+            Self::URLByResolvingBookmarkData_options_relativeToURL_bookmarkDataIsStale_error(
+                bookmark,
+                NSURLBookmarkResolutionOptions::NSURLBookmarkResolutionWithSecurityScope
+                    | NSURLBookmarkResolutionOptions::NSURLBookmarkResolutionWithoutUI,
+                None,
+                &mut is_stale,
+            )
+        }?;
+        Ok(ResolvedBookmark {
+            url,
+            is_stale: is_stale.as_bool(),
+        })
+    }
+
+    /// Run the closure with access to the security-scoped resource that the
+    /// URL points to.
+    ///
+    /// This is a shorthand for holding the guard returned by
+    /// [`NSURL::access_security_scoped_resource`] for the duration of the
+    /// closure.
+    pub fn with_security_scoped_access<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+        let access = self.access_security_scoped_resource();
+        f(access.url())
+    }
+}
+
+/// A URL resolved from a security-scoped bookmark.
+///
+/// Returned from [`NSURL::from_security_scoped_bookmark`].
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_foundation::{NSData, NSURL};
+///
+/// # fn load_bookmark() -> objc2::rc::Id<NSData> { unimplemented!() }
+/// # fn store_bookmark(_: &NSData) {}
+/// let bookmark = load_bookmark();
+/// let resolved = NSURL::from_security_scoped_bookmark(&bookmark).unwrap();
+/// if resolved.is_stale() {
+///     store_bookmark(&resolved.refresh(false).unwrap());
+/// }
+/// let access = resolved.access();
+/// // Read the file at `access.url()`; access ends when `access` is dropped.
+/// ```
+#[cfg(all(
+    target_os = "macos",
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSString"
+))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolvedBookmark {
+    url: Id<NSURL>,
+    is_stale: bool,
+}
+
+#[cfg(all(
+    target_os = "macos",
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSString"
+))]
+impl ResolvedBookmark {
+    /// The resolved URL.
+    pub fn url(&self) -> &NSURL {
+        &self.url
+    }
+
+    /// Convert into the resolved URL.
+    pub fn into_url(self) -> Id<NSURL> {
+        self.url
+    }
+
+    /// Whether the bookmark is stale, for example because the file was
+    /// moved or renamed.
+    ///
+    /// Stale bookmarks still resolve, but should be [refreshed] and the new
+    /// bookmark stored in place of the old one.
+    ///
+    /// [refreshed]: Self::refresh
+    #[doc(alias = "bookmarkDataIsStale")]
+    pub fn is_stale(&self) -> bool {
+        self.is_stale
+    }
+
+    /// Start accessing the resolved URL.
+    ///
+    /// See [`NSURL::access_security_scoped_resource`].
+    pub fn access(&self) -> ScopedAccess {
+        self.url.access_security_scoped_resource()
+    }
+
+    /// Create a new bookmark for the resolved URL.
+    ///
+    /// Creating the bookmark requires access to the resource, which is
+    /// acquired for the duration of the call.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// See [`NSURL::security_scoped_bookmark`].
+    pub fn refresh(&self, read_only: bool) -> Result<Id<NSData>, Id<NSError>> {
+        self.url
+            .with_security_scoped_access(|url| url.security_scoped_bookmark(read_only))
+    }
+}
+
+/// Access to a security-scoped resource, that ends when dropped.
+///
+/// Created with [`NSURL::access_security_scoped_resource`].
+#[must_use = "access ends when the guard is dropped"]
+pub struct ScopedAccess {
+    url: Id<NSURL>,
+    accessing: bool,
+}
+
+impl ScopedAccess {
+    /// The URL that is being accessed.
+    pub fn url(&self) -> &NSURL {
+        &self.url
+    }
+
+    /// Whether access was granted because the URL is security-scoped.
+    ///
+    /// This is `false` if the URL was accessible anyhow, or if access was
+    /// denied; in the latter case, operations on the resource will fail.
+    #[doc(alias = "startAccessingSecurityScopedResource")]
+    pub fn is_security_scoped(&self) -> bool {
+        self.accessing
+    }
+}
+
+impl Drop for ScopedAccess {
+    fn drop(&mut self) {
+        if self.accessing {
+            // SAFETY: Access was successfully started in
+            // `access_security_scoped_resource`, and is only stopped once.
+            unsafe { self.url.stopAccessingSecurityScopedResource() };
+        }
+    }
+}
+
+impl fmt::Debug for ScopedAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedAccess")
+            .field("url", &self.url)
+            .field("accessing", &self.accessing)
+            .finish()
+    }
+}
@@ -45,20 +45,9 @@ impl NSUUID {
 
     /// Create a new `NSUUID` from the given bytes.
     ///
-    ///
-    /// # Example
-    ///
-    /// Create a new `NSUUID` from the `uuid` crate.
-    ///
-    /// ```ignore
-    /// use uuid::Uuid;
-    /// use objc2_foundation::NSUUID;
-    ///
-    /// let uuid: Uuid;
-    /// # uuid = todo!();
-    /// let obj = NSUUID::from_bytes(uuid.into_bytes());
-    /// assert_eq!(obj.as_bytes(), uuid.into_bytes());
-    /// ```
+    /// See also [`NSUUID::from_uuid`] for creating one from the `uuid`
+    /// crate, which requires the `"uuid"` feature.
+    #[doc(alias = "initWithUUIDBytes:")]
     pub fn from_bytes(bytes: [u8; 16]) -> Id<Self> {
         let bytes = UuidBytes(bytes);
         Self::initWithUUIDBytes(Self::alloc(), &bytes)
@@ -69,6 +58,11 @@ impl NSUUID {
         Self::initWithUUIDString(Self::alloc(), string)
     }
 
+    /// The bytes of the UUID.
+    ///
+    /// This reads the bytes directly, without formatting the UUID as a
+    /// string.
+    #[doc(alias = "getUUIDBytes:")]
     pub fn as_bytes(&self) -> [u8; 16] {
         let mut bytes = UuidBytes([0; 16]);
         self.getUUIDBytes(&mut bytes);
@@ -76,6 +70,41 @@ impl NSUUID {
     }
 }
 
+#[cfg(feature = "uuid")]
+impl NSUUID {
+    /// Create a new `NSUUID` from a [`uuid::Uuid`].
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::NSUUID;
+    /// use uuid::Uuid;
+    ///
+    /// let uuid = Uuid::from_bytes([10; 16]);
+    /// let obj = NSUUID::from_uuid(uuid);
+    /// assert_eq!(obj.as_uuid(), uuid);
+    /// ```
+    #[doc(alias = "initWithUUIDBytes:")]
+    pub fn from_uuid(uuid: uuid::Uuid) -> Id<Self> {
+        Self::from_bytes(uuid.into_bytes())
+    }
+
+    /// Convert the `NSUUID` to a [`uuid::Uuid`].
+    #[doc(alias = "getUUIDBytes:")]
+    pub fn as_uuid(&self) -> uuid::Uuid {
+        uuid::Uuid::from_bytes(self.as_bytes())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<&NSUUID> for uuid::Uuid {
+    #[inline]
+    fn from(obj: &NSUUID) -> Self {
+        obj.as_uuid()
+    }
+}
+
 #[cfg(feature = "NSString")]
 impl fmt::Display for NSUUID {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
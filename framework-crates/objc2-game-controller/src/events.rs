@@ -0,0 +1,148 @@
+use core::fmt;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::task::{Context, Poll};
+
+use block2::{Block, RcBlock};
+use futures_core::Stream;
+use objc2::rc::Id;
+use objc2::runtime::AnyObject;
+use objc2::ClassType;
+use objc2_foundation::{
+    event_channel, BufferPolicy, EventSender, EventStream, NSNotification, NSNotificationCenter,
+    NSString,
+};
+
+use crate::*;
+
+/// A controller being connected or disconnected.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ControllerEvent {
+    /// The controller was connected, and is ready to use.
+    #[doc(alias = "GCControllerDidConnectNotification")]
+    Connected(Id<GCController>),
+    /// The controller was disconnected.
+    #[doc(alias = "GCControllerDidDisconnectNotification")]
+    Disconnected(Id<GCController>),
+}
+
+impl ControllerEvent {
+    /// The controller that the event is about.
+    pub fn controller(&self) -> &GCController {
+        match self {
+            Self::Connected(controller) | Self::Disconnected(controller) => controller,
+        }
+    }
+}
+
+/// A stream of controllers being connected and disconnected.
+///
+/// Created with [`ControllerEvents::new`]. The observers are removed from
+/// the notification center when the stream is dropped.
+#[must_use = "streams do nothing unless polled"]
+pub struct ControllerEvents {
+    center: Id<NSNotificationCenter>,
+    tokens: [Id<AnyObject>; 2],
+    stream: EventStream<ControllerEvent>,
+}
+
+impl ControllerEvents {
+    /// Observe controllers being connected and disconnected.
+    ///
+    /// The stream starts with a [`ControllerEvent::Connected`] event for
+    /// each controller that is already connected, so that no controller is
+    /// missed between querying the connected controllers and observing new
+    /// ones. Events are buffered according to the given policy until the
+    /// stream is polled.
+    pub fn new(policy: BufferPolicy) -> Self {
+        let (sender, stream) = event_channel(policy);
+        // SAFETY: Getting the default center and the connected controllers
+        // is safe.
+        let (center, controllers) = unsafe {
+            (
+                NSNotificationCenter::defaultCenter(),
+                GCController::controllers(),
+            )
+        };
+        for controller in controllers.iter() {
+            sender.send(ControllerEvent::Connected(controller.retain()));
+        }
+        // SAFETY: The notification names are valid strings.
+        let tokens = unsafe {
+            [
+                observe(
+                    &center,
+                    GCControllerDidConnectNotification,
+                    &sender,
+                    ControllerEvent::Connected,
+                ),
+                observe(
+                    &center,
+                    GCControllerDidDisconnectNotification,
+                    &sender,
+                    ControllerEvent::Disconnected,
+                ),
+            ]
+        };
+        Self {
+            center,
+            tokens,
+            stream,
+        }
+    }
+}
+
+fn observe(
+    center: &NSNotificationCenter,
+    name: &NSString,
+    sender: &EventSender<ControllerEvent>,
+    event: fn(Id<GCController>) -> ControllerEvent,
+) -> Id<AnyObject> {
+    let block = RcBlock::new({
+        let sender = sender.clone();
+        move |notification: NonNull<NSNotification>| {
+            // SAFETY: The notification is valid for the duration of the call,
+            // and getting its object is safe.
+            let object = unsafe { notification.as_ref().object() };
+            if let Some(object) = object {
+                // SAFETY: The object of the connection notifications is the
+                // controller.
+                sender.send(event(unsafe { Id::cast::<GCController>(object) }));
+            }
+        }
+    });
+    let block: &Block<dyn Fn(NonNull<NSNotification>) + 'static> = &block;
+    // SAFETY: The block has the correct signature, and the center retains
+    // (copies) it. The notifications are posted on the main thread, where
+    // the block is then run.
+    let token =
+        unsafe { center.addObserverForName_object_queue_usingBlock(Some(name), None, None, block) };
+    // SAFETY: The observer token is an object.
+    unsafe { Id::cast(token) }
+}
+
+impl Stream for ControllerEvents {
+    type Item = ControllerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ControllerEvent>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+impl Drop for ControllerEvents {
+    fn drop(&mut self) {
+        for token in &self.tokens {
+            // SAFETY: The tokens were returned by
+            // `addObserverForName:object:queue:usingBlock:` on this center.
+            unsafe { self.center.removeObserver(token) };
+        }
+    }
+}
+
+impl fmt::Debug for ControllerEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ControllerEvents")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
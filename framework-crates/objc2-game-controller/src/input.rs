@@ -0,0 +1,122 @@
+use crate::*;
+
+/// The state of a button.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ButtonState {
+    /// How far the button is pressed, from `0.0` to `1.0`.
+    ///
+    /// Buttons that are not pressure-sensitive are either `0.0` or `1.0`.
+    pub value: f32,
+    /// Whether the button is considered pressed.
+    pub pressed: bool,
+}
+
+impl ButtonState {
+    fn read(button: &GCControllerButtonInput) -> Self {
+        // SAFETY: The getters are safe to call.
+        unsafe {
+            Self {
+                value: button.value(),
+                pressed: button.isPressed(),
+            }
+        }
+    }
+}
+
+/// The state of a directional pad or thumbstick.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AxisState {
+    /// The horizontal position, from `-1.0` (left) to `1.0` (right).
+    pub x: f32,
+    /// The vertical position, from `-1.0` (down) to `1.0` (up).
+    pub y: f32,
+}
+
+impl AxisState {
+    fn read(pad: &GCControllerDirectionPad) -> Self {
+        // SAFETY: The getters are safe to call.
+        unsafe {
+            Self {
+                x: pad.xAxis().value(),
+                y: pad.yAxis().value(),
+            }
+        }
+    }
+}
+
+/// The state of every element of an extended gamepad at a point in time.
+///
+/// Created with [`GCExtendedGamepad::state`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExtendedGamepadState {
+    /// The directional pad.
+    pub dpad: AxisState,
+    /// The left thumbstick.
+    pub left_thumbstick: AxisState,
+    /// The right thumbstick.
+    pub right_thumbstick: AxisState,
+    /// The bottom face button (A on Xbox controllers, cross on PlayStation
+    /// controllers).
+    pub button_a: ButtonState,
+    /// The right face button.
+    pub button_b: ButtonState,
+    /// The left face button.
+    pub button_x: ButtonState,
+    /// The top face button.
+    pub button_y: ButtonState,
+    /// The left shoulder button.
+    pub left_shoulder: ButtonState,
+    /// The right shoulder button.
+    pub right_shoulder: ButtonState,
+    /// The left trigger.
+    pub left_trigger: ButtonState,
+    /// The right trigger.
+    pub right_trigger: ButtonState,
+    /// The menu button.
+    pub button_menu: ButtonState,
+    /// The options button, if the controller has one.
+    pub button_options: Option<ButtonState>,
+    /// The home button, if the controller has one and it is available to
+    /// the app.
+    pub button_home: Option<ButtonState>,
+    /// Pressing the left thumbstick, if the controller supports it.
+    pub left_thumbstick_button: Option<ButtonState>,
+    /// Pressing the right thumbstick, if the controller supports it.
+    pub right_thumbstick_button: Option<ButtonState>,
+}
+
+impl GCExtendedGamepad {
+    /// Read the current state of every element of the gamepad.
+    ///
+    /// This is useful for polling the input once per frame, instead of
+    /// reacting to value changed handlers.
+    pub fn state(&self) -> ExtendedGamepadState {
+        // SAFETY: The getters are safe to call.
+        unsafe {
+            ExtendedGamepadState {
+                dpad: AxisState::read(&self.dpad()),
+                left_thumbstick: AxisState::read(&self.leftThumbstick()),
+                right_thumbstick: AxisState::read(&self.rightThumbstick()),
+                button_a: ButtonState::read(&self.buttonA()),
+                button_b: ButtonState::read(&self.buttonB()),
+                button_x: ButtonState::read(&self.buttonX()),
+                button_y: ButtonState::read(&self.buttonY()),
+                left_shoulder: ButtonState::read(&self.leftShoulder()),
+                right_shoulder: ButtonState::read(&self.rightShoulder()),
+                left_trigger: ButtonState::read(&self.leftTrigger()),
+                right_trigger: ButtonState::read(&self.rightTrigger()),
+                button_menu: ButtonState::read(&self.buttonMenu()),
+                button_options: self.buttonOptions().as_deref().map(ButtonState::read),
+                button_home: self.buttonHome().as_deref().map(ButtonState::read),
+                left_thumbstick_button: self
+                    .leftThumbstickButton()
+                    .as_deref()
+                    .map(ButtonState::read),
+                right_thumbstick_button: self
+                    .rightThumbstickButton()
+                    .as_deref()
+                    .map(ButtonState::read),
+            }
+        }
+    }
+}
@@ -15,17 +15,47 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "futures-core",
+    feature = "block2",
+    feature = "GCController"
+))]
+mod events;
 #[cfg(feature = "GCExtendedGamepadSnapshot")]
 mod extended_gamepad_snapshot;
 mod generated;
+#[cfg(all(
+    feature = "GCControllerAxisInput",
+    feature = "GCControllerButtonInput",
+    feature = "GCControllerDirectionPad",
+    feature = "GCControllerElement",
+    feature = "GCExtendedGamepad",
+    feature = "GCPhysicalInputProfile"
+))]
+mod input;
 #[cfg(feature = "GCInputNames")]
 mod input_names;
 
+#[cfg(all(
+    feature = "futures-core",
+    feature = "block2",
+    feature = "GCController"
+))]
+pub use self::events::{ControllerEvent, ControllerEvents};
 #[cfg(feature = "GCExtendedGamepadSnapshot")]
 #[allow(deprecated)]
 pub use self::extended_gamepad_snapshot::GCExtendedGamepadSnapshotData;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "GCControllerAxisInput",
+    feature = "GCControllerButtonInput",
+    feature = "GCControllerDirectionPad",
+    feature = "GCControllerElement",
+    feature = "GCExtendedGamepad",
+    feature = "GCPhysicalInputProfile"
+))]
+pub use self::input::{AxisState, ButtonState, ExtendedGamepadState};
 #[cfg(feature = "GCInputNames")]
 pub use self::input_names::*;
 
@@ -0,0 +1,129 @@
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use block2::{Block, RcBlock};
+use objc2::rc::Id;
+use objc2::runtime::Bool;
+use objc2::ClassType;
+use objc2_foundation::{oneshot, NSError, NSString, Oneshot};
+
+use crate::*;
+
+impl LAContext {
+    /// Evaluate the policy, asynchronously returning whether the user
+    /// successfully authenticated.
+    ///
+    /// `reason` is shown to the user in the authentication dialog, and
+    /// should explain why authentication is requested.
+    ///
+    /// Dropping the returned future before it has completed invalidates the
+    /// context, which cancels the evaluation and dismisses any
+    /// authentication dialog.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by the framework if the user failed to
+    /// authenticate, or cancelled authentication. See `LAError` for the
+    /// possible error codes.
+    #[doc(alias = "evaluatePolicy:localizedReason:reply:")]
+    pub fn evaluate_policy(&self, policy: LAPolicy, reason: &str) -> EvaluatePolicy {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new(move |success: Bool, error: *mut NSError| {
+            let result = if success.as_bool() {
+                Ok(())
+            } else {
+                // SAFETY: The error is either NULL or a valid error.
+                match unsafe { Id::retain(error) } {
+                    Some(error) => Err(error),
+                    // The error should always be set on failure, but be
+                    // defensive in case it is not.
+                    None => Err(NSError::new(
+                        LAError::AuthenticationFailed.0,
+                        // SAFETY: The error domain is a valid string.
+                        unsafe { LAErrorDomain },
+                    )),
+                }
+            };
+            sender.send(result);
+        });
+        let block: &Block<dyn Fn(Bool, *mut NSError) + 'static> = &block;
+        // SAFETY: The block has the correct signature, and is copied by the
+        // context. The reply is called on a private queue, which is fine
+        // since the sender is thread-safe.
+        unsafe {
+            self.evaluatePolicy_localizedReason_reply(policy, &NSString::from_str(reason), block)
+        };
+        EvaluatePolicy {
+            context: self.retain(),
+            receiver,
+            completed: false,
+        }
+    }
+
+    /// The type of biometric authentication supported by the device, if
+    /// any.
+    ///
+    /// [`biometryType`](LAContext::biometryType) is only set after the
+    /// policy has been checked for the context, so this checks whether
+    /// [`LAPolicy::DeviceOwnerAuthenticationWithBiometrics`] can be
+    /// evaluated first.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if biometric authentication is not available, for
+    /// example because the user has not enrolled any biometrics.
+    #[doc(alias = "biometryType")]
+    #[doc(alias = "canEvaluatePolicy:error:")]
+    pub fn available_biometry(&self) -> Result<LABiometryType, Id<NSError>> {
+        // SAFETY: Checking the policy and getting the biometry type is safe.
+        unsafe {
+            self.canEvaluatePolicy_error(LAPolicy::DeviceOwnerAuthenticationWithBiometrics)?;
+            Ok(self.biometryType())
+        }
+    }
+}
+
+/// A future that resolves when a policy has been evaluated.
+///
+/// Created with [`LAContext::evaluate_policy`].
+#[must_use = "futures do nothing unless polled"]
+pub struct EvaluatePolicy {
+    context: Id<LAContext>,
+    receiver: Oneshot<Result<(), Id<NSError>>>,
+    completed: bool,
+}
+
+impl Future for EvaluatePolicy {
+    type Output = Result<(), Id<NSError>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = Pin::new(&mut self.receiver).poll(cx);
+        if result.is_ready() {
+            self.completed = true;
+        }
+        result
+    }
+}
+
+impl Drop for EvaluatePolicy {
+    fn drop(&mut self) {
+        // The evaluation is still ongoing if the future has not completed,
+        // and the reply has not yet been received.
+        if !self.completed && self.receiver.try_take().is_none() {
+            // SAFETY: Invalidating the context is safe.
+            unsafe { self.context.invalidate() };
+        }
+    }
+}
+
+impl fmt::Debug for EvaluatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvaluatePolicy")
+            .field("context", &self.context)
+            .finish_non_exhaustive()
+    }
+}
@@ -15,6 +15,21 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "LAContext",
+    feature = "LAError"
+))]
+mod context;
 mod generated;
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "LAContext",
+    feature = "LAError"
+))]
+pub use self::context::EvaluatePolicy;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
@@ -0,0 +1,175 @@
+use core::ffi::c_void;
+use core::mem;
+use core::ops::Range;
+use core::ptr::NonNull;
+use core::slice;
+
+use objc2::rc::Id;
+use objc2::runtime::ProtocolObject;
+use objc2::Message;
+use objc2_foundation::NSRange;
+
+use crate::*;
+
+#[cfg(feature = "MTLDevice")]
+fn storage_mode_from_options(options: MTLResourceOptions) -> MTLStorageMode {
+    MTLStorageMode((options.0 & MTLResourceStorageModeMask) >> MTLResourceStorageModeShift)
+}
+
+fn is_cpu_accessible(mode: MTLStorageMode) -> bool {
+    mode == MTLStorageMode::Shared || mode == MTLStorageMode::Managed
+}
+
+/// Helpers for accessing the contents of a [`MTLBuffer`] from Rust.
+pub trait MTLBufferExt: MTLBuffer + Message {
+    /// Whether the contents of the buffer can be accessed by the CPU.
+    ///
+    /// This is the case for buffers using the shared and managed storage
+    /// modes.
+    fn is_cpu_accessible(&self) -> bool;
+
+    /// The contents of the buffer as a slice of `T`.
+    ///
+    /// Returns `None` if the buffer is not accessible by the CPU, or if the
+    /// contents are not suitably aligned for `T`. If the length of the
+    /// buffer is not a multiple of the size of `T`, the remaining bytes are
+    /// not included in the slice.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// - Any bit pattern in the buffer must be a valid `T`.
+    /// - The contents must not be modified while the slice is alive, neither
+    ///   by the GPU nor through other references.
+    #[doc(alias = "contents")]
+    unsafe fn contents_slice<T: Copy>(&self) -> Option<&[T]>;
+
+    /// The contents of the buffer as a mutable slice of `T`.
+    ///
+    /// Returns `None` under the same conditions as
+    /// [`contents_slice`](MTLBufferExt::contents_slice).
+    ///
+    /// For buffers using the managed storage mode, you must call
+    /// [`did_modify`](MTLBufferExt::did_modify) afterwards for the GPU to
+    /// see the changes.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// - Any bit pattern in the buffer must be a valid `T`.
+    /// - The contents must not be accessed while the slice is alive, neither
+    ///   by the GPU nor through other references.
+    #[doc(alias = "contents")]
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn contents_slice_mut<T: Copy>(&self) -> Option<&mut [T]>;
+
+    /// Notify Metal that the given byte range of the contents were modified
+    /// by the CPU.
+    ///
+    /// This is required for buffers using the managed storage mode, and does
+    /// nothing for other storage modes.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the buffer.
+    #[doc(alias = "didModifyRange:")]
+    fn did_modify(&self, range: Range<usize>);
+}
+
+impl<P: MTLBuffer + Message> MTLBufferExt for P {
+    fn is_cpu_accessible(&self) -> bool {
+        // SAFETY: Querying the storage mode is safe.
+        is_cpu_accessible(unsafe { self.storageMode() })
+    }
+
+    unsafe fn contents_slice<T: Copy>(&self) -> Option<&[T]> {
+        let (ptr, len) = contents_ptr_len::<T, _>(self)?;
+        // SAFETY: The pointer is valid and aligned for `len` elements, and
+        // the caller upholds the rest.
+        Some(unsafe { slice::from_raw_parts(ptr.as_ptr(), len) })
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn contents_slice_mut<T: Copy>(&self) -> Option<&mut [T]> {
+        let (ptr, len) = contents_ptr_len::<T, _>(self)?;
+        // SAFETY: The pointer is valid and aligned for `len` elements, and
+        // the caller upholds the rest.
+        Some(unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), len) })
+    }
+
+    fn did_modify(&self, range: Range<usize>) {
+        // SAFETY: Querying the length is safe.
+        let length = unsafe { self.length() };
+        assert!(
+            range.start <= range.end && range.end <= length,
+            "range {range:?} out of bounds of buffer with length {length}",
+        );
+        // SAFETY: Querying the storage mode is safe.
+        if unsafe { self.storageMode() } == MTLStorageMode::Managed {
+            // SAFETY: The range is in bounds, and the buffer is managed.
+            unsafe { self.didModifyRange(NSRange::from(range)) };
+        }
+    }
+}
+
+fn contents_ptr_len<T, P: MTLBuffer + Message + ?Sized>(buffer: &P) -> Option<(NonNull<T>, usize)> {
+    // SAFETY: Querying the storage mode is safe.
+    if !is_cpu_accessible(unsafe { buffer.storageMode() }) {
+        return None;
+    }
+    // SAFETY: The buffer is accessible by the CPU, so the contents pointer
+    // is valid for `length` bytes.
+    let ptr: NonNull<c_void> = unsafe { buffer.contents() };
+    let ptr = ptr.cast::<T>();
+    if ptr.as_ptr().align_offset(mem::align_of::<T>()) != 0 {
+        return None;
+    }
+    // SAFETY: Querying the length is safe.
+    let length = unsafe { buffer.length() };
+    let len = match mem::size_of::<T>() {
+        0 => 0,
+        size => length / size,
+    };
+    Some((ptr, len))
+}
+
+/// Helpers for creating a [`MTLBuffer`] from Rust data.
+#[cfg(feature = "MTLDevice")]
+pub trait MTLDeviceBufferExt: MTLDevice + Message {
+    /// Create a buffer containing a copy of the given slice.
+    ///
+    /// Returns `None` if the slice is empty, if `options` specifies a
+    /// storage mode that is not accessible by the CPU (such as private or
+    /// memoryless), or if the device failed to allocate the buffer.
+    ///
+    /// Buffers using the managed storage mode are synchronized with the GPU
+    /// on creation, so [`did_modify`](MTLBufferExt::did_modify) need not be
+    /// called afterwards.
+    #[doc(alias = "newBufferWithBytes:length:options:")]
+    fn new_buffer_with_slice<T: Copy>(
+        &self,
+        data: &[T],
+        options: MTLResourceOptions,
+    ) -> Option<Id<ProtocolObject<dyn MTLBuffer>>>;
+}
+
+#[cfg(feature = "MTLDevice")]
+impl<P: MTLDevice + Message> MTLDeviceBufferExt for P {
+    fn new_buffer_with_slice<T: Copy>(
+        &self,
+        data: &[T],
+        options: MTLResourceOptions,
+    ) -> Option<Id<ProtocolObject<dyn MTLBuffer>>> {
+        let length = mem::size_of_val(data);
+        if length == 0 || !is_cpu_accessible(storage_mode_from_options(options)) {
+            return None;
+        }
+        let ptr: *const T = data.as_ptr();
+        // SAFETY: Slice pointers are always non-null.
+        let ptr = unsafe { NonNull::new_unchecked(ptr as *mut c_void) };
+        // SAFETY: The pointer is valid for `length` bytes, which are copied
+        // into the new buffer. `T: Copy`, so copying the bytes is fine.
+        unsafe { self.newBufferWithBytes_length_options(ptr, length, options) }
+    }
+}
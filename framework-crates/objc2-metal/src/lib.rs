@@ -39,6 +39,8 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(feature = "MTLBuffer", feature = "MTLResource"))]
+mod buffer;
 #[cfg(feature = "MTLCaptureManager")]
 mod capture;
 #[cfg(feature = "MTLDevice")]
@@ -52,6 +54,10 @@ mod private;
 mod resource;
 mod slice;
 
+#[cfg(all(feature = "MTLBuffer", feature = "MTLResource"))]
+pub use self::buffer::MTLBufferExt;
+#[cfg(all(feature = "MTLBuffer", feature = "MTLDevice", feature = "MTLResource"))]
+pub use self::buffer::MTLDeviceBufferExt;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
 #[cfg(feature = "MTLAccelerationStructureTypes")]
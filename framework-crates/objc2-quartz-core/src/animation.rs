@@ -0,0 +1,508 @@
+//! Typed builders for property animations.
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+use core::future::Future;
+use core::marker::PhantomData;
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+use core::pin::Pin;
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+use core::task::{Context, Poll};
+
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+use block2::{Block, RcBlock};
+
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{msg_send_id, ClassType};
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+use objc2_foundation::{oneshot, Oneshot};
+use objc2_foundation::{CGFloat, CGPoint, CGRect, CGSize, NSArray, NSNumber, NSString, NSValue};
+
+use crate::*;
+
+/// A value that an animatable layer property can have.
+///
+/// This is implemented for floating point numbers (which are converted to
+/// `NSNumber`) and for geometry types (which are converted to `NSValue`).
+/// Colors are not supported yet, since `CGColor` has no bindings.
+pub trait AnimatableValue: Copy {
+    /// Convert the value to the object that Core Animation expects.
+    fn to_object(&self) -> Id<NSObject>;
+}
+
+impl AnimatableValue for f64 {
+    fn to_object(&self) -> Id<NSObject> {
+        Id::into_super(Id::into_super(NSNumber::new_f64(*self)))
+    }
+}
+
+impl AnimatableValue for f32 {
+    fn to_object(&self) -> Id<NSObject> {
+        Id::into_super(Id::into_super(NSNumber::new_f32(*self)))
+    }
+}
+
+macro_rules! geometry_value {
+    ($($t:ty),*) => {$(
+        impl AnimatableValue for $t {
+            fn to_object(&self) -> Id<NSObject> {
+                Id::into_super(NSValue::new(*self))
+            }
+        }
+    )*};
+}
+
+geometry_value!(CGPoint, CGSize, CGRect);
+
+/// A key path to an animatable layer property of type `T`.
+///
+/// Constants are provided for the common properties of [`CALayer`].
+pub struct KeyPath<T> {
+    path: &'static str,
+    p: PhantomData<fn() -> T>,
+}
+
+impl<T> KeyPath<T> {
+    /// Create a key path to a property of type `T`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The property at the key path must be animatable, and have type `T`.
+    pub const unsafe fn new_unchecked(path: &'static str) -> Self {
+        Self {
+            path,
+            p: PhantomData,
+        }
+    }
+
+    /// The key path as a string.
+    pub const fn as_str(&self) -> &'static str {
+        self.path
+    }
+}
+
+macro_rules! key_paths {
+    ($ty:ty { $($(#[$m:meta])* $name:ident = $path:literal;)* }) => {
+        impl KeyPath<$ty> {
+            $(
+                $(#[$m])*
+                #[doc = concat!("The `", $path, "` key path.")]
+                pub const $name: Self = Self { path: $path, p: PhantomData };
+            )*
+        }
+    };
+}
+
+key_paths!(CGFloat {
+    OPACITY = "opacity";
+    CORNER_RADIUS = "cornerRadius";
+    BORDER_WIDTH = "borderWidth";
+    SHADOW_RADIUS = "shadowRadius";
+    SHADOW_OPACITY = "shadowOpacity";
+    Z_POSITION = "zPosition";
+    ROTATION = "transform.rotation.z";
+    SCALE = "transform.scale";
+    SCALE_X = "transform.scale.x";
+    SCALE_Y = "transform.scale.y";
+    TRANSLATION_X = "transform.translation.x";
+    TRANSLATION_Y = "transform.translation.y";
+});
+
+key_paths!(CGPoint {
+    POSITION = "position";
+    ANCHOR_POINT = "anchorPoint";
+});
+
+key_paths!(CGSize {
+    SIZE = "bounds.size";
+    SHADOW_OFFSET = "shadowOffset";
+});
+
+key_paths!(CGRect {
+    BOUNDS = "bounds";
+    CONTENTS_RECT = "contentsRect";
+});
+
+impl<T> Clone for KeyPath<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for KeyPath<T> {}
+
+impl<T> fmt::Debug for KeyPath<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("KeyPath").field(&self.path).finish()
+    }
+}
+
+/// The pacing of an animation.
+///
+/// The default is [`TimingFunction::Default`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[doc(alias = "CAMediaTimingFunction")]
+pub enum TimingFunction {
+    /// The system default timing function.
+    Default,
+    /// Linear pacing.
+    Linear,
+    /// Begin slowly, and then speed up.
+    EaseIn,
+    /// Begin quickly, and then slow down.
+    EaseOut,
+    /// Begin slowly, speed up, and then slow down again.
+    EaseInEaseOut,
+    /// A cubic Bézier curve with the given control points.
+    CubicBezier([f32; 4]),
+}
+
+// Deriving requires `#[default]`, which is not available in our MSRV.
+#[allow(clippy::derivable_impls)]
+impl Default for TimingFunction {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl TimingFunction {
+    /// Create the corresponding `CAMediaTimingFunction`.
+    pub fn to_media_timing_function(self) -> Id<CAMediaTimingFunction> {
+        // SAFETY: The names are valid timing function names.
+        let name = unsafe {
+            match self {
+                Self::Default => kCAMediaTimingFunctionDefault,
+                Self::Linear => kCAMediaTimingFunctionLinear,
+                Self::EaseIn => kCAMediaTimingFunctionEaseIn,
+                Self::EaseOut => kCAMediaTimingFunctionEaseOut,
+                Self::EaseInEaseOut => kCAMediaTimingFunctionEaseInEaseOut,
+                Self::CubicBezier([c1x, c1y, c2x, c2y]) => {
+                    return msg_send_id![
+                        CAMediaTimingFunction::class(),
+                        functionWithControlPoints: c1x,
+                        : c1y,
+                        : c2x,
+                        : c2y,
+                    ];
+                }
+            }
+        };
+        // SAFETY: The name is one of the predefined names.
+        unsafe { CAMediaTimingFunction::functionWithName(name) }
+    }
+}
+
+/// Timing properties shared by the animation builders.
+#[derive(Clone, Copy, Debug)]
+struct Timing {
+    duration: f64,
+    timing_function: Option<TimingFunction>,
+    repeat_count: f32,
+    autoreverses: bool,
+}
+
+impl Timing {
+    const fn new() -> Self {
+        Self {
+            duration: 0.25,
+            timing_function: None,
+            repeat_count: 0.0,
+            autoreverses: false,
+        }
+    }
+
+    fn apply(&self, animation: &CAPropertyAnimation) {
+        // SAFETY: The values are valid for these properties.
+        unsafe {
+            animation.setDuration(self.duration);
+            animation.setRepeatCount(self.repeat_count);
+            animation.setAutoreverses(self.autoreverses);
+        }
+        if let Some(timing_function) = self.timing_function {
+            let timing_function = timing_function.to_media_timing_function();
+            // SAFETY: The timing function is valid.
+            unsafe { animation.setTimingFunction(Some(&timing_function)) };
+        }
+    }
+}
+
+macro_rules! timing_setters {
+    () => {
+        /// Set the duration of the animation in seconds.
+        ///
+        /// Defaults to 0.25 seconds.
+        #[doc(alias = "setDuration:")]
+        pub fn duration(mut self, seconds: f64) -> Self {
+            self.timing.duration = seconds;
+            self
+        }
+
+        /// Set the pacing of the animation.
+        #[doc(alias = "setTimingFunction:")]
+        pub fn timing_function(mut self, timing_function: TimingFunction) -> Self {
+            self.timing.timing_function = Some(timing_function);
+            self
+        }
+
+        /// Set how many times the animation repeats.
+        ///
+        /// Use [`f32::INFINITY`] to repeat forever.
+        #[doc(alias = "setRepeatCount:")]
+        pub fn repeat_count(mut self, count: f32) -> Self {
+            self.timing.repeat_count = count;
+            self
+        }
+
+        /// Set whether the animation plays backwards after playing forwards.
+        #[doc(alias = "setAutoreverses:")]
+        pub fn autoreverses(mut self, autoreverses: bool) -> Self {
+            self.timing.autoreverses = autoreverses;
+            self
+        }
+    };
+}
+
+/// A builder for a [`CABasicAnimation`] of a property of type `T`.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// use objc2_quartz_core::{BasicAnimation, KeyPath, TimingFunction};
+///
+/// BasicAnimation::new(KeyPath::OPACITY)
+///     .from(0.0)
+///     .to(1.0)
+///     .duration(0.5)
+///     .timing_function(TimingFunction::EaseOut)
+///     .add_to(&layer, Some("fade-in"));
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[doc(alias = "CABasicAnimation")]
+pub struct BasicAnimation<T> {
+    key_path: KeyPath<T>,
+    from: Option<T>,
+    to: Option<T>,
+    by: Option<T>,
+    timing: Timing,
+}
+
+impl<T: AnimatableValue> BasicAnimation<T> {
+    /// Start building an animation of the property at the given key path.
+    ///
+    /// If no values are set, the property is animated from its current
+    /// presentation value to its current model value.
+    pub fn new(key_path: KeyPath<T>) -> Self {
+        Self {
+            key_path,
+            from: None,
+            to: None,
+            by: None,
+            timing: Timing::new(),
+        }
+    }
+
+    /// Set the value that the animation starts at.
+    #[doc(alias = "setFromValue:")]
+    pub fn from(mut self, value: T) -> Self {
+        self.from = Some(value);
+        self
+    }
+
+    /// Set the value that the animation ends at.
+    #[doc(alias = "setToValue:")]
+    pub fn to(mut self, value: T) -> Self {
+        self.to = Some(value);
+        self
+    }
+
+    /// Set the value that the animation changes the property by.
+    #[doc(alias = "setByValue:")]
+    pub fn by(mut self, value: T) -> Self {
+        self.by = Some(value);
+        self
+    }
+
+    timing_setters!();
+
+    /// Create the animation.
+    pub fn build(&self) -> Id<CABasicAnimation> {
+        let key_path = NSString::from_str(self.key_path.as_str());
+        // SAFETY: The key path refers to an animatable property.
+        let animation = unsafe { CABasicAnimation::animationWithKeyPath(Some(&key_path)) };
+        let from = self.from.map(|v| v.to_object());
+        let to = self.to.map(|v| v.to_object());
+        let by = self.by.map(|v| v.to_object());
+        // SAFETY: The values have the type of the property, as ensured by
+        // `KeyPath`.
+        unsafe {
+            animation.setFromValue(from.as_deref().map(as_any));
+            animation.setToValue(to.as_deref().map(as_any));
+            animation.setByValue(by.as_deref().map(as_any));
+        }
+        self.timing.apply(&animation);
+        animation
+    }
+
+    /// Create the animation, and add it to the layer.
+    ///
+    /// Adding an animation with the same key as an existing animation
+    /// replaces the existing animation.
+    #[doc(alias = "addAnimation:forKey:")]
+    pub fn add_to(&self, layer: &CALayer, key: Option<&str>) -> Id<CABasicAnimation> {
+        let animation = self.build();
+        add_animation(layer, &animation, key);
+        animation
+    }
+
+    /// Create the animation, add it to the layer, and return a future that
+    /// completes when the animation has finished (or has been removed).
+    #[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+    #[doc(alias = "setCompletionBlock:")]
+    pub fn run(&self, layer: &CALayer, key: Option<&str>) -> AnimationCompletion {
+        add_animation_with_completion(layer, &self.build(), key)
+    }
+}
+
+/// A builder for a [`CAKeyframeAnimation`] of a property of type `T`.
+#[derive(Clone, Debug)]
+#[doc(alias = "CAKeyframeAnimation")]
+pub struct KeyframeAnimation<T> {
+    key_path: KeyPath<T>,
+    values: Vec<T>,
+    key_times: Option<Vec<f64>>,
+    timing: Timing,
+}
+
+impl<T: AnimatableValue> KeyframeAnimation<T> {
+    /// Start building an animation through the given values of the
+    /// property at the given key path.
+    ///
+    /// The values are evenly spaced over the duration of the animation,
+    /// unless [`key_times`](Self::key_times) is set.
+    pub fn new(key_path: KeyPath<T>, values: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            key_path,
+            values: values.into_iter().collect(),
+            key_times: None,
+            timing: Timing::new(),
+        }
+    }
+
+    /// Set the time at which each value is reached, as a fraction of the
+    /// duration between `0.0` and `1.0`.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of key times differs from the number of values.
+    #[doc(alias = "setKeyTimes:")]
+    #[track_caller]
+    pub fn key_times(mut self, key_times: impl IntoIterator<Item = f64>) -> Self {
+        let key_times: Vec<f64> = key_times.into_iter().collect();
+        assert_eq!(
+            key_times.len(),
+            self.values.len(),
+            "must have a key time for each value"
+        );
+        self.key_times = Some(key_times);
+        self
+    }
+
+    timing_setters!();
+
+    /// Create the animation.
+    pub fn build(&self) -> Id<CAKeyframeAnimation> {
+        let key_path = NSString::from_str(self.key_path.as_str());
+        // SAFETY: The key path refers to an animatable property.
+        let animation = unsafe { CAKeyframeAnimation::animationWithKeyPath(Some(&key_path)) };
+        let values: Vec<Id<AnyObject>> = self
+            .values
+            .iter()
+            .map(|v| Id::into_super(v.to_object()))
+            .collect();
+        let values = NSArray::from_vec(values);
+        // SAFETY: The values have the type of the property, as ensured by
+        // `KeyPath`.
+        unsafe { animation.setValues(Some(&values)) };
+        if let Some(key_times) = &self.key_times {
+            let key_times: Vec<Id<NSNumber>> =
+                key_times.iter().map(|t| NSNumber::new_f64(*t)).collect();
+            let key_times = NSArray::from_vec(key_times);
+            // SAFETY: There is a key time for each value.
+            unsafe { animation.setKeyTimes(Some(&key_times)) };
+        }
+        self.timing.apply(&animation);
+        animation
+    }
+
+    /// Create the animation, and add it to the layer.
+    ///
+    /// Adding an animation with the same key as an existing animation
+    /// replaces the existing animation.
+    #[doc(alias = "addAnimation:forKey:")]
+    pub fn add_to(&self, layer: &CALayer, key: Option<&str>) -> Id<CAKeyframeAnimation> {
+        let animation = self.build();
+        add_animation(layer, &animation, key);
+        animation
+    }
+
+    /// Create the animation, add it to the layer, and return a future that
+    /// completes when the animation has finished (or has been removed).
+    #[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+    #[doc(alias = "setCompletionBlock:")]
+    pub fn run(&self, layer: &CALayer, key: Option<&str>) -> AnimationCompletion {
+        add_animation_with_completion(layer, &self.build(), key)
+    }
+}
+
+fn as_any(obj: &NSObject) -> &AnyObject {
+    obj
+}
+
+fn add_animation(layer: &CALayer, animation: &CAAnimation, key: Option<&str>) {
+    let key = key.map(NSString::from_str);
+    // SAFETY: The animation is valid, and is copied by the layer.
+    unsafe { layer.addAnimation_forKey(animation, key.as_deref()) };
+}
+
+/// A future that completes when an animation has finished.
+///
+/// Created with [`BasicAnimation::run`] or [`KeyframeAnimation::run`].
+/// Dropping the future does not stop the animation.
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct AnimationCompletion(Oneshot<()>);
+
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+impl Future for AnimationCompletion {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "block2", feature = "CATransaction"))]
+fn add_animation_with_completion(
+    layer: &CALayer,
+    animation: &CAAnimation,
+    key: Option<&str>,
+) -> AnimationCompletion {
+    let (sender, receiver) = oneshot();
+    let block = RcBlock::new(move || sender.send(()));
+    let block: &Block<dyn Fn() + 'static> = &block;
+    // SAFETY: The block is copied by the transaction, and is called once
+    // all animations added in the transaction have completed.
+    unsafe {
+        CATransaction::begin();
+        CATransaction::setCompletionBlock(Some(block));
+    }
+    add_animation(layer, animation, key);
+    // SAFETY: The transaction was begun above.
+    unsafe { CATransaction::commit() };
+    AnimationCompletion(receiver)
+}
@@ -0,0 +1,183 @@
+//! Frame callbacks synchronized with the display.
+use alloc::boxed::Box;
+use core::fmt;
+
+use objc2::rc::Id;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol, Sel};
+use objc2::{declare_class, msg_send_id, mutability, sel, ClassType, DeclaredClass};
+use objc2_foundation::{MainThreadMarker, NSRunLoop, NSRunLoopCommonModes};
+
+use crate::*;
+
+/// The timing of a frame, passed to the callback of a [`DisplayLink`].
+///
+/// The timestamps are in the same time base as `CACurrentMediaTime`, in
+/// seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameTime {
+    /// When the last frame was displayed.
+    #[doc(alias = "timestamp")]
+    pub timestamp: f64,
+    /// When the frame being prepared will be displayed.
+    ///
+    /// Rendering should be finished before this time, and animations
+    /// should be advanced to it.
+    #[doc(alias = "targetTimestamp")]
+    pub target_timestamp: f64,
+    /// The time between frames at the maximum refresh rate of the display.
+    #[doc(alias = "duration")]
+    pub duration: f64,
+}
+
+declare_class!(
+    struct Target;
+
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - The display link is scheduled on the main run loop, so the callback
+    //   is called on the main thread.
+    // - `Target` does not implement `Drop`.
+    unsafe impl ClassType for Target {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustDisplayLinkTarget";
+    }
+
+    impl DeclaredClass for Target {
+        type Ivars = Box<dyn Fn(FrameTime)>;
+    }
+
+    unsafe impl NSObjectProtocol for Target {}
+
+    unsafe impl Target {
+        #[method(step:)]
+        fn step(&self, link: &CADisplayLink) {
+            // SAFETY: The getters are safe to call from the callback.
+            let time = unsafe {
+                FrameTime {
+                    timestamp: link.timestamp(),
+                    target_timestamp: link.targetTimestamp(),
+                    duration: link.duration(),
+                }
+            };
+            (self.ivars())(time);
+        }
+    }
+);
+
+/// A timer that calls a closure once per frame, synchronized with the
+/// refresh rate of the display.
+///
+/// The closure is called on the main thread, as long as the main run loop
+/// is running. The display link is invalidated when this is dropped.
+///
+/// On macOS 14 and above, display links are created for a specific view,
+/// window or screen with [`DisplayLink::with_factory`]; on other platforms,
+/// use [`DisplayLink::new`].
+#[doc(alias = "CADisplayLink")]
+pub struct DisplayLink {
+    link: Id<CADisplayLink>,
+}
+
+impl DisplayLink {
+    /// Create a display link for the main display, and start calling the
+    /// closure every frame.
+    #[cfg(not(target_os = "macos"))]
+    #[doc(alias = "displayLinkWithTarget:selector:")]
+    pub fn new(mtm: MainThreadMarker, f: impl Fn(FrameTime) + 'static) -> Self {
+        // SAFETY: The target responds to the selector, which takes the
+        // display link as its only argument.
+        Self::with_factory(mtm, f, |target, selector| unsafe {
+            CADisplayLink::displayLinkWithTarget_selector(target, selector)
+        })
+    }
+
+    /// Create a display link using the given function, and start calling
+    /// the closure every frame.
+    ///
+    /// The function receives a target and selector, which it must use to
+    /// create the display link. On macOS, this is usually done with
+    /// `displayLinkWithTarget:selector:` on an `NSView`, `NSWindow` or
+    /// `NSScreen`, so that the link follows the refresh rate of the display
+    /// that the content is on.
+    pub fn with_factory(
+        mtm: MainThreadMarker,
+        f: impl Fn(FrameTime) + 'static,
+        create: impl FnOnce(&AnyObject, Sel) -> Id<CADisplayLink>,
+    ) -> Self {
+        let target = mtm
+            .alloc::<Target>()
+            .set_ivars(Box::new(f) as Box<dyn Fn(FrameTime)>);
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        let target: Id<Target> = unsafe { msg_send_id![super(target), init] };
+        let target_object: &AnyObject = &target;
+        // The display link retains the target until it is invalidated.
+        let link = create(target_object, sel!(step:));
+        // SAFETY: Scheduling on the main run loop is safe, since we are on
+        // the main thread. Using the common modes keeps the link running
+        // during event tracking, such as while resizing a window.
+        unsafe {
+            link.addToRunLoop_forMode(&NSRunLoop::mainRunLoop(), NSRunLoopCommonModes);
+        }
+        Self { link }
+    }
+
+    /// Stop calling the closure, until [resumed](Self::resume).
+    #[doc(alias = "setPaused:")]
+    pub fn pause(&self) {
+        // SAFETY: Pausing is safe.
+        unsafe { self.link.setPaused(true) };
+    }
+
+    /// Resume calling the closure every frame.
+    #[doc(alias = "setPaused:")]
+    pub fn resume(&self) {
+        // SAFETY: Resuming is safe.
+        unsafe { self.link.setPaused(false) };
+    }
+
+    /// Whether the display link is paused.
+    #[doc(alias = "isPaused")]
+    pub fn is_paused(&self) -> bool {
+        // SAFETY: Getting the paused state is safe.
+        unsafe { self.link.isPaused() }
+    }
+
+    /// Request a range of frame rates, in frames per second.
+    ///
+    /// The system chooses the actual rate based on the display and power
+    /// conditions, preferring `preferred` if possible. A `preferred` of `0`
+    /// lets the system choose within the range.
+    #[cfg(feature = "CAFrameRateRange")]
+    #[doc(alias = "setPreferredFrameRateRange:")]
+    pub fn set_frame_rate_range(&self, minimum: f32, maximum: f32, preferred: f32) {
+        let range = CAFrameRateRange {
+            minimum,
+            maximum,
+            preferred,
+        };
+        // SAFETY: Setting the frame rate range is safe.
+        unsafe { self.link.setPreferredFrameRateRange(range) };
+    }
+
+    /// The underlying display link.
+    pub fn link(&self) -> &CADisplayLink {
+        &self.link
+    }
+}
+
+impl Drop for DisplayLink {
+    fn drop(&mut self) {
+        // SAFETY: Invalidating removes the link from the run loop, and
+        // releases the target.
+        unsafe { self.link.invalidate() };
+    }
+}
+
+impl fmt::Debug for DisplayLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DisplayLink")
+            .field("link", &self.link)
+            .finish()
+    }
+}
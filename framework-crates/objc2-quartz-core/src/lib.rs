@@ -19,7 +19,40 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "alloc",
+    feature = "CAAnimation",
+    feature = "CALayer",
+    feature = "CAMediaTiming",
+    feature = "CAMediaTimingFunction"
+))]
+mod animation;
+#[cfg(all(feature = "alloc", feature = "CADisplayLink"))]
+mod display_link;
 mod generated;
+
+#[cfg(all(
+    feature = "alloc",
+    feature = "CAAnimation",
+    feature = "CALayer",
+    feature = "CAMediaTiming",
+    feature = "CAMediaTimingFunction"
+))]
+pub use self::animation::{
+    AnimatableValue, BasicAnimation, KeyPath, KeyframeAnimation, TimingFunction,
+};
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "CAAnimation",
+    feature = "CALayer",
+    feature = "CAMediaTiming",
+    feature = "CAMediaTimingFunction",
+    feature = "CATransaction"
+))]
+pub use self::animation::AnimationCompletion;
+#[cfg(all(feature = "alloc", feature = "CADisplayLink"))]
+pub use self::display_link::{DisplayLink, FrameTime};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
 
@@ -16,5 +16,37 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(all(
+    feature = "std",
+    feature = "SKPayment",
+    feature = "SKPaymentQueue",
+    feature = "SKPaymentTransaction",
+    feature = "SKProduct",
+    feature = "SKProductsRequest",
+    feature = "SKRequest"
+))]
+mod payment;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "std",
+    feature = "SKPayment",
+    feature = "SKPaymentQueue",
+    feature = "SKPaymentTransaction",
+    feature = "SKProduct",
+    feature = "SKProductsRequest",
+    feature = "SKRequest"
+))]
+pub use self::payment::{Products, ProductsRequest, TransactionEvent};
+#[cfg(all(
+    feature = "std",
+    feature = "futures-core",
+    feature = "SKPayment",
+    feature = "SKPaymentQueue",
+    feature = "SKPaymentTransaction",
+    feature = "SKProduct",
+    feature = "SKProductsRequest",
+    feature = "SKRequest"
+))]
+pub use self::payment::TransactionStream;
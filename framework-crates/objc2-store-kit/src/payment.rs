@@ -0,0 +1,372 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use objc2::rc::Id;
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass, Message};
+use objc2_foundation::{oneshot, NSError, NSInteger, NSSet, NSString, Oneshot, OneshotSender};
+
+use crate::*;
+
+/// The products returned by a [`ProductsRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Products {
+    /// The products that are available for sale, in no particular order.
+    pub products: Vec<Id<SKProduct>>,
+    /// The requested identifiers that do not correspond to a product that
+    /// can be sold, for example because it is not configured in App Store
+    /// Connect.
+    #[doc(alias = "invalidProductIdentifiers")]
+    pub invalid_identifiers: Vec<String>,
+}
+
+type ProductsResult = Result<Products, Id<NSError>>;
+
+declare_class!(
+    struct ProductsDelegate;
+
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - The delegate may be called on any thread, which is fine since the
+    //   sender is thread-safe.
+    // - `ProductsDelegate` does not implement `Drop`.
+    unsafe impl ClassType for ProductsDelegate {
+        type Super = NSObject;
+        type Mutability = mutability::InteriorMutable;
+        const NAME: &'static str = "__RustSKProductsRequestDelegate";
+    }
+
+    impl DeclaredClass for ProductsDelegate {
+        // Only the first of the response and failure is used.
+        type Ivars = OneshotSender<ProductsResult>;
+    }
+
+    unsafe impl NSObjectProtocol for ProductsDelegate {}
+
+    unsafe impl SKRequestDelegate for ProductsDelegate {
+        #[method(request:didFailWithError:)]
+        fn did_fail(&self, _request: &SKRequest, error: &NSError) {
+            self.ivars().send(Err(error.retain()));
+        }
+    }
+
+    unsafe impl SKProductsRequestDelegate for ProductsDelegate {
+        #[method(productsRequest:didReceiveResponse:)]
+        fn did_receive_response(&self, _request: &SKProductsRequest, response: &SKProductsResponse) {
+            // SAFETY: The getters are safe to call.
+            let (products, invalid) =
+                unsafe { (response.products(), response.invalidProductIdentifiers()) };
+            let products = Products {
+                products: (0..products.len())
+                    .filter_map(|index| products.get_retained(index))
+                    .collect(),
+                invalid_identifiers: (0..invalid.len())
+                    .filter_map(|index| invalid.get(index))
+                    .map(|identifier| identifier.to_string())
+                    .collect(),
+            };
+            self.ivars().send(Ok(products));
+        }
+    }
+);
+
+impl ProductsDelegate {
+    fn new(sender: OneshotSender<ProductsResult>) -> Id<Self> {
+        let this = Self::alloc().set_ivars(sender);
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// A future that resolves to the products with the given identifiers.
+///
+/// Created with [`ProductsRequest::new`]. The request is cancelled if this
+/// is dropped before it has finished.
+#[doc(alias = "SKProductsRequest")]
+#[must_use = "futures do nothing unless polled"]
+pub struct ProductsRequest {
+    request: Id<SKProductsRequest>,
+    // The request only holds a weak reference to its delegate.
+    _delegate: Id<ProductsDelegate>,
+    receiver: Oneshot<ProductsResult>,
+    finished: bool,
+}
+
+impl ProductsRequest {
+    /// Start fetching the products with the given identifiers from the App
+    /// Store.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_store_kit::{ProductsRequest, SKPaymentQueue};
+    ///
+    /// # async fn example() -> Result<(), objc2::rc::Id<objc2_foundation::NSError>> {
+    /// let products = ProductsRequest::new(&["com.example.coins"]).await?;
+    /// for product in &products.products {
+    ///     unsafe { SKPaymentQueue::defaultQueue() }.purchase(product, 1);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "initWithProductIdentifiers:")]
+    #[doc(alias = "start")]
+    pub fn new(identifiers: &[&str]) -> Self {
+        let identifiers: Vec<Id<NSString>> = identifiers
+            .iter()
+            .map(|identifier| NSString::from_str(identifier))
+            .collect();
+        let (sender, receiver) = oneshot();
+        let delegate = ProductsDelegate::new(sender);
+        // SAFETY: The delegate implements the protocol, and is kept alive
+        // by the returned future.
+        let request = unsafe {
+            let request = SKProductsRequest::initWithProductIdentifiers(
+                SKProductsRequest::alloc(),
+                &NSSet::from_id_slice(&identifiers),
+            );
+            request.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
+            request.start();
+            request
+        };
+        Self {
+            request,
+            _delegate: delegate,
+            receiver,
+            finished: false,
+        }
+    }
+}
+
+impl Future for ProductsRequest {
+    type Output = Result<Products, Id<NSError>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = Pin::new(&mut self.receiver).poll(cx);
+        if result.is_ready() {
+            self.finished = true;
+        }
+        result
+    }
+}
+
+impl Drop for ProductsRequest {
+    fn drop(&mut self) {
+        if !self.finished {
+            // SAFETY: Cancelling a request is safe, also if it has already
+            // finished.
+            unsafe { self.request.cancel() };
+        }
+    }
+}
+
+impl fmt::Debug for ProductsRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProductsRequest")
+            .field("request", &self.request)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SKPaymentQueue {
+    /// Request payment for the given quantity of the product.
+    ///
+    /// The outcome is delivered to the queue's transaction observers, for
+    /// example through `SKPaymentQueue::transactions`.
+    #[doc(alias = "addPayment:")]
+    pub fn purchase(&self, product: &SKProduct, quantity: usize) {
+        // SAFETY: Creating and adding a payment is safe. The queue copies
+        // the payment, so it may be released afterwards.
+        unsafe {
+            let payment = SKMutablePayment::paymentWithProduct(product);
+            payment.setQuantity(quantity as NSInteger);
+            self.addPayment(&payment);
+        }
+    }
+
+    /// Restore the transactions of the user's earlier non-consumable and
+    /// subscription purchases.
+    ///
+    /// The restored transactions are delivered to the queue's transaction
+    /// observers, followed by [`TransactionEvent::RestoreFinished`] or
+    /// [`TransactionEvent::RestoreFailed`].
+    #[doc(alias = "restoreCompletedTransactions")]
+    pub fn restore(&self) {
+        // SAFETY: Restoring transactions is safe.
+        unsafe { self.restoreCompletedTransactions() };
+    }
+
+    /// Remove a transaction from the queue, once its content has been
+    /// delivered to the user.
+    ///
+    /// Transactions that are not finished are delivered again the next time
+    /// the app starts observing the queue.
+    #[doc(alias = "finishTransaction:")]
+    pub fn finish(&self, transaction: &SKPaymentTransaction) {
+        // SAFETY: Finishing a transaction is safe.
+        unsafe { self.finishTransaction(transaction) };
+    }
+}
+
+/// An event delivered to the observer of an [`SKPaymentQueue`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TransactionEvent {
+    /// The state of a transaction changed.
+    ///
+    /// Use `transactionState` to check whether it was purchased, restored,
+    /// failed or deferred, and [`SKPaymentQueue::finish`] it once handled.
+    #[doc(alias = "paymentQueue:updatedTransactions:")]
+    Updated(Id<SKPaymentTransaction>),
+    /// A transaction was removed from the queue.
+    #[doc(alias = "paymentQueue:removedTransactions:")]
+    Removed(Id<SKPaymentTransaction>),
+    /// All transactions requested with [`SKPaymentQueue::restore`] have been
+    /// delivered.
+    #[doc(alias = "paymentQueueRestoreCompletedTransactionsFinished:")]
+    RestoreFinished,
+    /// Restoring transactions failed.
+    #[doc(alias = "paymentQueue:restoreCompletedTransactionsFailedWithError:")]
+    RestoreFailed(Id<NSError>),
+}
+
+#[cfg(feature = "futures-core")]
+mod observer {
+    use futures_core::Stream;
+    use objc2_foundation::{event_channel, BufferPolicy, EventSender, EventStream, NSArray};
+
+    use super::*;
+
+    declare_class!(
+        struct Observer;
+
+        // SAFETY:
+        // - The superclass NSObject does not have any subclassing
+        //   requirements.
+        // - The observer may be called on any thread, which is fine since
+        //   the sender is thread-safe.
+        // - `Observer` does not implement `Drop`.
+        unsafe impl ClassType for Observer {
+            type Super = NSObject;
+            type Mutability = mutability::InteriorMutable;
+            const NAME: &'static str = "__RustSKPaymentTransactionObserver";
+        }
+
+        impl DeclaredClass for Observer {
+            type Ivars = EventSender<TransactionEvent>;
+        }
+
+        unsafe impl NSObjectProtocol for Observer {}
+
+        unsafe impl SKPaymentTransactionObserver for Observer {
+            #[method(paymentQueue:updatedTransactions:)]
+            fn updated(&self, _queue: &SKPaymentQueue, transactions: &NSArray<SKPaymentTransaction>) {
+                for index in 0..transactions.len() {
+                    if let Some(transaction) = transactions.get_retained(index) {
+                        self.ivars().send(TransactionEvent::Updated(transaction));
+                    }
+                }
+            }
+
+            #[method(paymentQueue:removedTransactions:)]
+            fn removed(&self, _queue: &SKPaymentQueue, transactions: &NSArray<SKPaymentTransaction>) {
+                for index in 0..transactions.len() {
+                    if let Some(transaction) = transactions.get_retained(index) {
+                        self.ivars().send(TransactionEvent::Removed(transaction));
+                    }
+                }
+            }
+
+            #[method(paymentQueueRestoreCompletedTransactionsFinished:)]
+            fn restore_finished(&self, _queue: &SKPaymentQueue) {
+                self.ivars().send(TransactionEvent::RestoreFinished);
+            }
+
+            #[method(paymentQueue:restoreCompletedTransactionsFailedWithError:)]
+            fn restore_failed(&self, _queue: &SKPaymentQueue, error: &NSError) {
+                self.ivars().send(TransactionEvent::RestoreFailed(error.retain()));
+            }
+        }
+    );
+
+    impl Observer {
+        fn new(sender: EventSender<TransactionEvent>) -> Id<Self> {
+            let this = Self::alloc().set_ivars(sender);
+            // SAFETY: `NSObject`'s `init` method is safe to call.
+            unsafe { msg_send_id![super(this), init] }
+        }
+    }
+
+    /// A stream of the events delivered to a payment queue's transaction
+    /// observer.
+    ///
+    /// Created with [`SKPaymentQueue::transactions`]. The observer is
+    /// removed from the queue when the stream is dropped.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct TransactionStream {
+        queue: Id<SKPaymentQueue>,
+        observer: Id<Observer>,
+        stream: EventStream<TransactionEvent>,
+    }
+
+    impl SKPaymentQueue {
+        /// Receive the events of the queue's transactions as an
+        /// asynchronous [`Stream`].
+        ///
+        /// This should be called when the app launches, so that
+        /// transactions that were not finished in an earlier run are
+        /// delivered, as well as purchases started outside the app.
+        #[doc(alias = "addTransactionObserver:")]
+        pub fn transactions(&self, policy: BufferPolicy) -> TransactionStream {
+            let (sender, stream) = event_channel(policy);
+            let observer = Observer::new(sender);
+            // SAFETY: The observer implements the protocol, and is kept
+            // alive by the stream.
+            unsafe { self.addTransactionObserver(ProtocolObject::from_ref(&*observer)) };
+            TransactionStream {
+                queue: self.retain(),
+                observer,
+                stream,
+            }
+        }
+    }
+
+    impl Stream for TransactionStream {
+        type Item = TransactionEvent;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<TransactionEvent>> {
+            Pin::new(&mut self.stream).poll_next(cx)
+        }
+    }
+
+    impl Drop for TransactionStream {
+        fn drop(&mut self) {
+            // SAFETY: The observer was added in `transactions`.
+            unsafe {
+                self.queue
+                    .removeTransactionObserver(ProtocolObject::from_ref(&*self.observer))
+            };
+        }
+    }
+
+    impl fmt::Debug for TransactionStream {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("TransactionStream")
+                .field("stream", &self.stream)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(feature = "futures-core")]
+pub use self::observer::TransactionStream;
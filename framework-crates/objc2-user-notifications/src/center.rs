@@ -0,0 +1,481 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+
+use block2::{Block, RcBlock};
+use objc2::rc::Id;
+use objc2::runtime::Bool;
+use objc2_foundation::{oneshot, NSDateComponents, NSError, NSNumber, NSString};
+
+use crate::*;
+
+/// Retain the error passed to a completion handler.
+///
+/// # Safety
+///
+/// The pointer must be NULL or a valid error.
+unsafe fn error_result(error: *mut NSError) -> Result<(), Id<NSError>> {
+    // SAFETY: Upheld by the caller.
+    match unsafe { Id::retain(error) } {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// When a notification is delivered.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum NotificationTrigger {
+    /// Deliver the notification after the given amount of time.
+    ///
+    /// Repeating notifications must use an interval of at least one minute.
+    TimeInterval {
+        /// The time until the notification is delivered.
+        interval: Duration,
+        /// Whether to deliver the notification again after each interval.
+        repeats: bool,
+    },
+    /// Deliver the notification when the current date matches the given
+    /// date components.
+    Calendar {
+        /// The date components to match.
+        components: DateComponents,
+        /// Whether to deliver the notification every time the components
+        /// match.
+        repeats: bool,
+    },
+}
+
+/// Date components used by [`NotificationTrigger::Calendar`].
+///
+/// Components that are `None` are not matched, so setting only `hour` and
+/// `minute` matches that time on every day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DateComponents {
+    /// The year.
+    pub year: Option<isize>,
+    /// The month, starting at 1.
+    pub month: Option<isize>,
+    /// The day of the month, starting at 1.
+    pub day: Option<isize>,
+    /// The day of the week, where 1 is Sunday.
+    pub weekday: Option<isize>,
+    /// The hour, from 0 to 23.
+    pub hour: Option<isize>,
+    /// The minute, from 0 to 59.
+    pub minute: Option<isize>,
+    /// The second, from 0 to 59.
+    pub second: Option<isize>,
+}
+
+impl DateComponents {
+    fn to_ns(self) -> Id<NSDateComponents> {
+        // SAFETY: Creating and configuring date components is safe.
+        unsafe {
+            let components = NSDateComponents::new();
+            if let Some(year) = self.year {
+                components.setYear(year);
+            }
+            if let Some(month) = self.month {
+                components.setMonth(month);
+            }
+            if let Some(day) = self.day {
+                components.setDay(day);
+            }
+            if let Some(weekday) = self.weekday {
+                components.setWeekday(weekday);
+            }
+            if let Some(hour) = self.hour {
+                components.setHour(hour);
+            }
+            if let Some(minute) = self.minute {
+                components.setMinute(minute);
+            }
+            if let Some(second) = self.second {
+                components.setSecond(second);
+            }
+            components
+        }
+    }
+}
+
+/// A local notification, to be scheduled with
+/// [`UNUserNotificationCenter::schedule`].
+///
+/// The notification is converted into a `UNNotificationRequest` with
+/// `UNMutableNotificationContent` when scheduled.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LocalNotification {
+    /// The identifier of the notification.
+    ///
+    /// Scheduling a notification with the same identifier as a pending
+    /// notification replaces it.
+    pub identifier: String,
+    /// The title of the notification.
+    pub title: String,
+    /// The subtitle of the notification.
+    pub subtitle: Option<String>,
+    /// The body of the notification.
+    pub body: String,
+    /// The number to show on the app's icon, or `None` to leave it
+    /// unchanged.
+    pub badge: Option<isize>,
+    /// Whether to play the default notification sound.
+    pub sound: bool,
+    /// The identifier used to group related notifications.
+    pub thread_identifier: Option<String>,
+    /// The identifier of the registered `UNNotificationCategory`, which
+    /// determines the available actions.
+    pub category_identifier: Option<String>,
+    /// When to deliver the notification, or `None` to deliver it
+    /// immediately.
+    pub trigger: Option<NotificationTrigger>,
+}
+
+impl LocalNotification {
+    /// Convert the notification into a notification request.
+    #[doc(alias = "requestWithIdentifier:content:trigger:")]
+    pub fn to_request(&self) -> Id<UNNotificationRequest> {
+        // SAFETY: Creating and configuring the content is safe.
+        let content = unsafe {
+            let content = UNMutableNotificationContent::new();
+            content.setTitle(&NSString::from_str(&self.title));
+            if let Some(subtitle) = &self.subtitle {
+                content.setSubtitle(&NSString::from_str(subtitle));
+            }
+            content.setBody(&NSString::from_str(&self.body));
+            if let Some(badge) = self.badge {
+                content.setBadge(Some(&NSNumber::new_isize(badge)));
+            }
+            if self.sound {
+                content.setSound(Some(&UNNotificationSound::defaultSound()));
+            }
+            if let Some(thread_identifier) = &self.thread_identifier {
+                content.setThreadIdentifier(&NSString::from_str(thread_identifier));
+            }
+            if let Some(category_identifier) = &self.category_identifier {
+                content.setCategoryIdentifier(&NSString::from_str(category_identifier));
+            }
+            content
+        };
+
+        // SAFETY: Creating triggers is safe.
+        let trigger: Option<Id<UNNotificationTrigger>> =
+            self.trigger.as_ref().map(|trigger| unsafe {
+                match trigger {
+                    NotificationTrigger::TimeInterval { interval, repeats } => Id::into_super(
+                        UNTimeIntervalNotificationTrigger::triggerWithTimeInterval_repeats(
+                            interval.as_secs_f64(),
+                            *repeats,
+                        ),
+                    ),
+                    NotificationTrigger::Calendar {
+                        components,
+                        repeats,
+                    } => Id::into_super(
+                        UNCalendarNotificationTrigger::triggerWithDateMatchingComponents_repeats(
+                            &components.to_ns(),
+                            *repeats,
+                        ),
+                    ),
+                }
+            });
+
+        // SAFETY: Creating a request is safe.
+        unsafe {
+            UNNotificationRequest::requestWithIdentifier_content_trigger(
+                &NSString::from_str(&self.identifier),
+                &content,
+                trigger.as_deref(),
+            )
+        }
+    }
+}
+
+impl UNUserNotificationCenter {
+    /// Request authorization to deliver notifications.
+    ///
+    /// The user is only prompted the first time this is requested; later
+    /// requests resolve with the user's earlier choice.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by the framework if authorization could
+    /// not be requested, for example if notifications are not allowed for
+    /// the app.
+    #[doc(alias = "requestAuthorizationWithOptions:completionHandler:")]
+    pub fn request_authorization(
+        &self,
+        options: UNAuthorizationOptions,
+    ) -> impl Future<Output = Result<bool, Id<NSError>>> {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new(move |granted: Bool, error: *mut NSError| {
+            // SAFETY: The error is either NULL or a valid error.
+            let result = unsafe { error_result(error) };
+            sender.send(result.map(|()| granted.as_bool()));
+        });
+        let block: &Block<dyn Fn(Bool, *mut NSError) + 'static> = &block;
+        // SAFETY: The block has the correct signature, and is copied by the
+        // center. It may be called on any thread, which is fine since the
+        // sender is thread-safe.
+        unsafe { self.requestAuthorizationWithOptions_completionHandler(options, block) };
+        receiver
+    }
+
+    /// Schedule the local notification for delivery.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by the framework if the notification could
+    /// not be scheduled, for example if the app is not authorized to deliver
+    /// notifications.
+    #[doc(alias = "addNotificationRequest:withCompletionHandler:")]
+    pub fn schedule(
+        &self,
+        notification: &LocalNotification,
+    ) -> impl Future<Output = Result<(), Id<NSError>>> {
+        let (sender, receiver) = oneshot();
+        let block = RcBlock::new({
+            // SAFETY: The error is either NULL or a valid error.
+            move |error: *mut NSError| sender.send(unsafe { error_result(error) })
+        });
+        let block: &Block<dyn Fn(*mut NSError) + 'static> = &block;
+        // SAFETY: The block has the correct signature, and is copied by the
+        // center.
+        unsafe {
+            self.addNotificationRequest_withCompletionHandler(
+                &notification.to_request(),
+                Some(block),
+            )
+        };
+        receiver
+    }
+
+    /// Remove pending notifications with the given identifiers.
+    #[doc(alias = "removePendingNotificationRequestsWithIdentifiers:")]
+    pub fn remove_pending(&self, identifiers: &[&str]) {
+        let identifiers: Vec<_> = identifiers
+            .iter()
+            .map(|identifier| NSString::from_str(identifier))
+            .collect();
+        let identifiers = objc2_foundation::NSArray::from_vec(identifiers);
+        // SAFETY: Removing requests is safe.
+        unsafe { self.removePendingNotificationRequestsWithIdentifiers(&identifiers) };
+    }
+}
+
+#[cfg(feature = "futures-core")]
+mod response {
+    use core::fmt;
+    use core::ptr;
+
+    use futures_core::Stream;
+    use objc2::rc::Allocated;
+    use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+    use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
+    use objc2_foundation::{event_channel, BufferPolicy, EventSender, EventStream};
+
+    use super::*;
+
+    /// The action the user took in response to a notification.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum NotificationAction {
+        /// The user opened the app from the notification.
+        #[doc(alias = "UNNotificationDefaultActionIdentifier")]
+        Default,
+        /// The user dismissed the notification.
+        ///
+        /// This is only delivered for categories created with the
+        /// `CustomDismissAction` option.
+        #[doc(alias = "UNNotificationDismissActionIdentifier")]
+        Dismiss,
+        /// The user chose the custom action with the given identifier.
+        Custom(String),
+    }
+
+    /// The user's response to a delivered notification.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[non_exhaustive]
+    pub struct NotificationResponse {
+        /// The identifier of the notification that was responded to.
+        pub identifier: String,
+        /// The category identifier of the notification.
+        pub category_identifier: String,
+        /// The action that the user took.
+        pub action: NotificationAction,
+        /// The text entered by the user, for text input actions.
+        #[doc(alias = "UNTextInputNotificationResponse")]
+        pub user_text: Option<String>,
+    }
+
+    impl NotificationResponse {
+        /// Convert the response.
+        pub fn from_response(response: &UNNotificationResponse) -> Self {
+            // SAFETY: The getters are safe to call.
+            unsafe {
+                let request = response.notification().request();
+                let action = response.actionIdentifier();
+                let action = if &*action == UNNotificationDefaultActionIdentifier {
+                    NotificationAction::Default
+                } else if &*action == UNNotificationDismissActionIdentifier {
+                    NotificationAction::Dismiss
+                } else {
+                    NotificationAction::Custom(action.to_string())
+                };
+                let user_text = if response.is_kind_of::<UNTextInputNotificationResponse>() {
+                    let response: *const UNNotificationResponse = response;
+                    let response = &*response.cast::<UNTextInputNotificationResponse>();
+                    Some(response.userText().to_string())
+                } else {
+                    None
+                };
+                Self {
+                    identifier: request.identifier().to_string(),
+                    category_identifier: request.content().categoryIdentifier().to_string(),
+                    action,
+                    user_text,
+                }
+            }
+        }
+    }
+
+    struct Ivars {
+        sender: EventSender<NotificationResponse>,
+        presentation: UNNotificationPresentationOptions,
+    }
+
+    declare_class!(
+        struct Delegate;
+
+        unsafe impl ClassType for Delegate {
+            type Super = NSObject;
+            type Mutability = mutability::InteriorMutable;
+            const NAME: &'static str = "__RustUNUserNotificationCenterDelegate";
+        }
+
+        impl DeclaredClass for Delegate {
+            type Ivars = Ivars;
+        }
+
+        unsafe impl NSObjectProtocol for Delegate {}
+
+        unsafe impl UNUserNotificationCenterDelegate for Delegate {
+            #[method(userNotificationCenter:didReceiveNotificationResponse:withCompletionHandler:)]
+            fn did_receive_response(
+                &self,
+                _center: &UNUserNotificationCenter,
+                response: &UNNotificationResponse,
+                completion_handler: &Block<dyn Fn()>,
+            ) {
+                self.ivars()
+                    .sender
+                    .send(NotificationResponse::from_response(response));
+                completion_handler.call(());
+            }
+
+            #[method(userNotificationCenter:willPresentNotification:withCompletionHandler:)]
+            fn will_present(
+                &self,
+                _center: &UNUserNotificationCenter,
+                _notification: &UNNotification,
+                completion_handler: &Block<dyn Fn(UNNotificationPresentationOptions)>,
+            ) {
+                completion_handler.call((self.ivars().presentation,));
+            }
+        }
+    );
+
+    impl Delegate {
+        fn new(ivars: Ivars) -> Id<Self> {
+            let this: Allocated<Self> = Self::alloc().set_ivars(ivars);
+            // SAFETY: `NSObject`'s `init` method is safe to call.
+            unsafe { msg_send_id![super(this), init] }
+        }
+    }
+
+    /// A stream of the user's responses to notifications.
+    ///
+    /// Created with [`UNUserNotificationCenter::responses`]. The delegate of
+    /// the notification center is reset when the stream is dropped.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ResponseStream {
+        center: Id<UNUserNotificationCenter>,
+        delegate: Id<Delegate>,
+        stream: EventStream<NotificationResponse>,
+    }
+
+    impl UNUserNotificationCenter {
+        /// Receive the user's responses to notifications as an asynchronous
+        /// [`Stream`].
+        ///
+        /// This installs a delegate on the notification center, replacing
+        /// any existing delegate. Notifications delivered while the app is
+        /// in the foreground are presented with the given options.
+        ///
+        /// The delegate should be installed before the app finishes
+        /// launching, since a response that launched the app is otherwise
+        /// not delivered.
+        #[doc(alias = "setDelegate:")]
+        pub fn responses(
+            &self,
+            presentation: UNNotificationPresentationOptions,
+            policy: BufferPolicy,
+        ) -> ResponseStream {
+            let (sender, stream) = event_channel(policy);
+            let delegate = Delegate::new(Ivars {
+                sender,
+                presentation,
+            });
+            // SAFETY: The delegate implements the protocol, and is kept
+            // alive by the stream, since the center only holds a weak
+            // reference to it.
+            unsafe { self.setDelegate(Some(ProtocolObject::from_ref(&*delegate))) };
+            ResponseStream {
+                center: self.retain(),
+                delegate,
+                stream,
+            }
+        }
+    }
+
+    impl Stream for ResponseStream {
+        type Item = NotificationResponse;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<NotificationResponse>> {
+            Pin::new(&mut self.stream).poll_next(cx)
+        }
+    }
+
+    impl Drop for ResponseStream {
+        fn drop(&mut self) {
+            // SAFETY: Getting the delegate is safe.
+            let current = unsafe { self.center.delegate() };
+            let ours: *const ProtocolObject<dyn UNUserNotificationCenterDelegate> =
+                ProtocolObject::from_ref(&*self.delegate);
+            // Only reset the delegate if it has not been replaced since.
+            if current.map_or(false, |current| ptr::eq(&*current, ours)) {
+                // SAFETY: Resetting the delegate is safe.
+                unsafe { self.center.setDelegate(None) };
+            }
+        }
+    }
+
+    impl fmt::Debug for ResponseStream {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("ResponseStream")
+                .field("stream", &self.stream)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(feature = "futures-core")]
+pub use self::response::{NotificationAction, NotificationResponse, ResponseStream};
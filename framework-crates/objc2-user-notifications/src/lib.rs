@@ -15,6 +15,44 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "UNNotification",
+    feature = "UNNotificationContent",
+    feature = "UNNotificationRequest",
+    feature = "UNNotificationResponse",
+    feature = "UNNotificationSound",
+    feature = "UNNotificationTrigger",
+    feature = "UNUserNotificationCenter"
+))]
+mod center;
 mod generated;
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "UNNotification",
+    feature = "UNNotificationContent",
+    feature = "UNNotificationRequest",
+    feature = "UNNotificationResponse",
+    feature = "UNNotificationSound",
+    feature = "UNNotificationTrigger",
+    feature = "UNUserNotificationCenter"
+))]
+pub use self::center::{DateComponents, LocalNotification, NotificationTrigger};
+#[cfg(all(
+    feature = "std",
+    feature = "futures-core",
+    feature = "block2",
+    feature = "UNNotification",
+    feature = "UNNotificationContent",
+    feature = "UNNotificationRequest",
+    feature = "UNNotificationResponse",
+    feature = "UNNotificationSound",
+    feature = "UNNotificationTrigger",
+    feature = "UNUserNotificationCenter"
+))]
+pub use self::center::{NotificationAction, NotificationResponse, ResponseStream};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
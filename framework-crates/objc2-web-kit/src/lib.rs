@@ -25,6 +25,14 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(all(
+    feature = "alloc",
+    feature = "WKScriptMessage",
+    feature = "WKScriptMessageHandler",
+    feature = "WKUserContentController"
+))]
+mod script_message;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
 
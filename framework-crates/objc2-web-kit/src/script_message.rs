@@ -0,0 +1,177 @@
+//! Bridging between JavaScript and Rust.
+use alloc::boxed::Box;
+
+use objc2::rc::Id;
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
+use objc2_foundation::{MainThreadMarker, NSString};
+
+use crate::*;
+
+type HandlerFn = Box<dyn Fn(&WKScriptMessage)>;
+
+declare_class!(
+    struct ScriptMessageHandler;
+
+    unsafe impl ClassType for ScriptMessageHandler {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "__RustWKScriptMessageHandler";
+    }
+
+    impl DeclaredClass for ScriptMessageHandler {
+        type Ivars = HandlerFn;
+    }
+
+    unsafe impl NSObjectProtocol for ScriptMessageHandler {}
+
+    unsafe impl WKScriptMessageHandler for ScriptMessageHandler {
+        #[method(userContentController:didReceiveScriptMessage:)]
+        fn did_receive_script_message(
+            &self,
+            _controller: &WKUserContentController,
+            message: &WKScriptMessage,
+        ) {
+            (self.ivars())(message);
+        }
+    }
+);
+
+impl ScriptMessageHandler {
+    fn new(mtm: MainThreadMarker, f: HandlerFn) -> Id<Self> {
+        let this = mtm.alloc::<Self>().set_ivars(f);
+        // SAFETY: `NSObject`'s `init` method is safe to call.
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+impl WKUserContentController {
+    /// Install a closure that is called whenever JavaScript posts a message
+    /// to the handler with the given name.
+    ///
+    /// Messages are posted from JavaScript with
+    /// `window.webkit.messageHandlers.<name>.postMessage(<body>)`.
+    ///
+    /// The closure is kept alive by the content controller until the handler
+    /// is removed with `removeScriptMessageHandlerForName:`. Note that this
+    /// means that the closure must not hold a strong reference to the
+    /// content controller or its web view, as that would create a reference
+    /// cycle.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// WebKit raises an exception if a handler with the given name is
+    /// already installed.
+    #[doc(alias = "addScriptMessageHandler:name:")]
+    pub fn add_script_message_handler<F>(&self, name: &str, mtm: MainThreadMarker, f: F)
+    where
+        F: Fn(&WKScriptMessage) + 'static,
+    {
+        let handler = ScriptMessageHandler::new(mtm, Box::new(f));
+        let handler = ProtocolObject::from_ref(&*handler);
+        // SAFETY: The handler implements the protocol, and is retained by
+        // the content controller.
+        unsafe { self.addScriptMessageHandler_name(handler, &NSString::from_str(name)) };
+    }
+
+    /// Create a user script from the given source, and add it to the
+    /// content controller.
+    ///
+    /// The script is injected into each page loaded by web views using this
+    /// content controller, at the given injection time. If
+    /// `main_frame_only` is `true`, it is not injected into subframes.
+    #[cfg(feature = "WKUserScript")]
+    #[doc(alias = "addUserScript:")]
+    pub fn add_user_script(
+        &self,
+        source: &str,
+        injection_time: WKUserScriptInjectionTime,
+        main_frame_only: bool,
+        mtm: MainThreadMarker,
+    ) -> Id<WKUserScript> {
+        // SAFETY: Creating a user script from a string is safe.
+        let script = unsafe {
+            WKUserScript::initWithSource_injectionTime_forMainFrameOnly(
+                mtm.alloc(),
+                &NSString::from_str(source),
+                injection_time,
+                main_frame_only,
+            )
+        };
+        // SAFETY: Adding a user script is safe.
+        unsafe { self.addUserScript(&script) };
+        script
+    }
+}
+
+#[cfg(feature = "foundation-value")]
+mod value {
+    use core::cell::Cell;
+
+    use block2::{Block, RcBlock};
+    use objc2::runtime::AnyObject;
+    use objc2_foundation::{FoundationValue, NSError};
+
+    use super::*;
+
+    impl WKScriptMessage {
+        /// The body of the message, converted to a [`FoundationValue`].
+        ///
+        /// JavaScript values are converted to Foundation types by WebKit
+        /// before being delivered, so this is usually a string, number,
+        /// array, dictionary, date or null.
+        #[doc(alias = "body")]
+        pub fn body_value(&self) -> FoundationValue {
+            // SAFETY: Getting the body is safe.
+            let body = unsafe { self.body() };
+            FoundationValue::from_object(&body)
+        }
+    }
+
+    #[cfg(feature = "WKWebView")]
+    impl WKWebView {
+        /// Evaluate the given JavaScript, and call the closure with the
+        /// result converted to a [`FoundationValue`].
+        ///
+        /// Results that cannot be represented in Objective-C (such as
+        /// `undefined`) are converted to [`FoundationValue::Null`]. If
+        /// evaluating the script throws an exception, or the result is of an
+        /// unsupported type, the closure is instead called with the error.
+        ///
+        /// The closure is called on the main thread once the script has
+        /// finished evaluating.
+        #[doc(alias = "evaluateJavaScript:completionHandler:")]
+        pub fn evaluate_javascript_value<F>(&self, script: &str, completion: F)
+        where
+            F: FnOnce(Result<FoundationValue, Id<NSError>>) + 'static,
+        {
+            let completion = Cell::new(Some(completion));
+            let block = RcBlock::new(move |result: *mut AnyObject, error: *mut NSError| {
+                // The completion handler is only called once, but the block
+                // has to be `Fn`.
+                let completion = match completion.take() {
+                    Some(completion) => completion,
+                    None => return,
+                };
+                // SAFETY: The error is either NULL or a valid error object.
+                let error = unsafe { Id::retain(error) };
+                let result = match error {
+                    Some(error) => Err(error),
+                    // SAFETY: The result is either NULL or a valid object.
+                    None => Ok(match unsafe { result.as_ref() } {
+                        Some(result) => FoundationValue::from_object(result),
+                        None => FoundationValue::Null,
+                    }),
+                };
+                completion(result);
+            });
+            let block: &Block<dyn Fn(*mut AnyObject, *mut NSError) + 'static> = &block;
+            // SAFETY: The block has the correct signature, and is copied by
+            // the web view.
+            unsafe {
+                self.evaluateJavaScript_completionHandler(&NSString::from_str(script), Some(block))
+            };
+        }
+    }
+}